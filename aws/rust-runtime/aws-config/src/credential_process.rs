@@ -126,14 +126,20 @@ impl CredentialProcessProvider {
                 secret_access_key,
                 session_token,
                 expiration,
-                ..
-            }) => Ok(Credentials::new(
-                access_key_id,
-                secret_access_key,
-                Some(session_token.to_string()),
-                expiration.into(),
-                "CredentialProcess",
-            )),
+                account_id,
+            }) => {
+                let mut credentials = Credentials::new(
+                    access_key_id,
+                    secret_access_key,
+                    Some(session_token.to_string()),
+                    expiration.into(),
+                    "CredentialProcess",
+                );
+                if let Some(account_id) = account_id {
+                    credentials = credentials.with_account_id(account_id);
+                }
+                Ok(credentials)
+            }
             Err(invalid) => Err(CredentialsError::provider_error(format!(
                 "Error retrieving credentials from external process, could not parse response: {}",
                 invalid
@@ -155,6 +161,7 @@ pub(crate) fn parse_credential_process_json_credentials(
     let mut secret_access_key = None;
     let mut session_token = None;
     let mut expiration = None;
+    let mut account_id = None;
     json_parse_loop(credentials_response.as_bytes(), |key, value| {
         match (key, value) {
             /*
@@ -162,7 +169,8 @@ pub(crate) fn parse_credential_process_json_credentials(
              "AccessKeyId": "ASIARTESTID",
              "SecretAccessKey": "TESTSECRETKEY",
              "SessionToken": "TESTSESSIONTOKEN",
-             "Expiration": "2022-05-02T18:36:00+00:00"
+             "Expiration": "2022-05-02T18:36:00+00:00",
+             "AccountId": "123456789012"
             */
             (key, Token::ValueNumber { value, .. }) if key.eq_ignore_ascii_case("Version") => {
                 version = Some(i32::try_from(*value).map_err(|err| {
@@ -186,6 +194,9 @@ pub(crate) fn parse_credential_process_json_credentials(
             (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Expiration") => {
                 expiration = Some(value.to_unescaped()?)
             }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("AccountId") => {
+                account_id = Some(value.to_unescaped()?)
+            }
 
             _ => {}
         };
@@ -225,6 +236,7 @@ pub(crate) fn parse_credential_process_json_credentials(
         secret_access_key,
         session_token,
         expiration,
+        account_id,
     })
 }
 
@@ -244,6 +256,7 @@ mod test {
         ));
         let creds = provider.provide_credentials().await.expect("valid creds");
         assert_eq!(creds.access_key_id(), "ASIARTESTID");
+        assert_eq!(creds.account_id(), None);
         assert_eq!(creds.secret_access_key(), "TESTSECRETKEY");
         assert_eq!(creds.session_token(), Some("TESTSESSIONTOKEN"));
         assert_eq!(
@@ -258,6 +271,15 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn credential_process_with_account_id() {
+        let provider = CredentialProcessProvider::new(String::from(
+            r#"echo '{ "Version": 1, "AccessKeyId": "ASIARTESTID", "SecretAccessKey": "TESTSECRETKEY", "SessionToken": "TESTSESSIONTOKEN", "Expiration": "2022-05-02T18:36:00+00:00", "AccountId": "123456789012" }'"#,
+        ));
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(creds.account_id(), Some("123456789012"));
+    }
+
     #[tokio::test]
     async fn credentials_process_timeouts() {
         let provider = CredentialProcessProvider::new(String::from("sleep 1000"));
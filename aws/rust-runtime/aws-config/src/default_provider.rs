@@ -42,8 +42,23 @@ pub mod timeout_config;
 /// if you need to set custom configuration options like [`region`](credentials::Builder::region) or [`profile_name`](credentials::Builder::profile_name).
 pub mod credentials;
 
+/// Default bearer token provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options like [`profile_name`](token::Builder::profile_name).
+pub mod token;
+
 /// Default FIPS provider chain
 pub mod use_fips;
 
 /// Default dual-stack provider chain
 pub mod use_dual_stack;
+
+/// Default "disable request compression" provider chain
+pub mod disable_request_compression;
+
+/// Default provider chain for the minimum size of request bodies to compress
+pub mod request_min_compression_size_bytes;
+
+/// Default account ID endpoint mode provider chain
+pub mod account_id_endpoint_mode;
@@ -99,6 +99,7 @@ pub struct Builder {
     ecs_builder: crate::ecs::Builder,
     region_override: Option<Box<dyn ProvideRegion>>,
     region_chain: crate::default_provider::region::Builder,
+    race: bool,
     conf: Option<ProviderConfig>,
 }
 
@@ -160,6 +161,18 @@ impl Builder {
         self
     }
 
+    /// Start all credential providers at the same time instead of one after another
+    ///
+    /// Providers keep their priority, so the credentials that are returned don't change. This
+    /// avoids waiting on providers that probe the network, like IMDS, when an earlier provider
+    /// doesn't have credentials. This is disabled by default.
+    ///
+    /// See [`race`](crate::meta::credentials::CredentialsProviderChain::race)
+    pub fn race(mut self, race: bool) -> Self {
+        self.race = race;
+        self
+    }
+
     /// Override the configuration used for this provider
     pub fn configure(mut self, config: ProviderConfig) -> Self {
         self.region_chain = self.region_chain.configure(&config);
@@ -190,7 +203,9 @@ impl Builder {
             .or_else("Profile", profile_provider)
             .or_else("WebIdentityToken", web_identity_token_provider)
             .or_else("EcsContainer", ecs_provider)
-            .or_else("Ec2InstanceMetadata", imds_provider);
+            .or_else("Ec2InstanceMetadata", imds_provider)
+            .race(self.race)
+            .time_source(conf.time_source());
 
         DefaultCredentialsChain { provider_chain }
     }
@@ -306,6 +321,11 @@ mod test {
     #[cfg(feature = "sso")]
     make_test!(sso_no_token_file);
 
+    #[cfg(feature = "sso")]
+    make_test!(sso_session);
+
+    make_test!(sso_session_missing);
+
     #[cfg(feature = "credentials-sso")]
     make_test!(e2e_fips_and_dual_stack_sso);
 
@@ -329,6 +349,45 @@ mod test {
         assert_eq!(creds.access_key_id(), "correct_key_secondary");
     }
 
+    #[tokio::test]
+    async fn race_is_forwarded_to_the_provider_chain() {
+        for race in [false, true] {
+            let env =
+                TestEnvironment::from_dir("./test-data/default-provider-chain/prefer_environment")
+                    .await
+                    .unwrap();
+            let provider = DefaultCredentialsChain::builder()
+                .race(race)
+                .configure(env.provider_config().clone())
+                .build()
+                .await;
+            assert!(format!("{:?}", provider).contains(&format!("race: {race}")));
+            let creds = provider
+                .provide_credentials()
+                .await
+                .expect("creds should load");
+            assert_eq!(creds.access_key_id(), "correct_key");
+        }
+    }
+
+    #[tokio::test]
+    async fn race_keeps_provider_priority() {
+        TestEnvironment::from_dir("./test-data/default-provider-chain/imds_default_chain_success")
+            .await
+            .unwrap()
+            .with_provider_config(|config| {
+                config.with_time_source(StaticTimeSource::new(UNIX_EPOCH))
+            })
+            .execute(|conf| async move {
+                DefaultCredentialsChain::builder()
+                    .race(true)
+                    .configure(conf)
+                    .build()
+                    .await
+            })
+            .await
+    }
+
     #[tokio::test]
     #[cfg(feature = "client-hyper")]
     async fn no_providers_configured_err() {
@@ -0,0 +1,158 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::provider_config::ProviderConfig;
+use crate::standard_property::StandardProperty;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+mod env {
+    pub(super) const REQUEST_MIN_COMPRESSION_SIZE_BYTES: &str =
+        "AWS_REQUEST_MIN_COMPRESSION_SIZE_BYTES";
+}
+
+mod profile_key {
+    pub(super) const REQUEST_MIN_COMPRESSION_SIZE_BYTES: &str =
+        "request_min_compression_size_bytes";
+}
+
+/// The largest valid minimum compression size
+const MAX_REQUEST_MIN_COMPRESSION_SIZE_BYTES: u32 = 10_485_760;
+
+/// Load the minimum size in bytes of request bodies to compress
+///
+/// This checks the following sources:
+/// 1. The environment variable `AWS_REQUEST_MIN_COMPRESSION_SIZE_BYTES=<bytes>`
+/// 2. The profile key `request_min_compression_size_bytes=<bytes>`
+///
+/// Valid values are between 0 and 10485760 inclusive. If invalid values are found, the provider
+/// will return None and an error will be logged.
+pub async fn request_min_compression_size_bytes_provider(
+    provider_config: &ProviderConfig,
+) -> Option<u32> {
+    StandardProperty::new()
+        .env(env::REQUEST_MIN_COMPRESSION_SIZE_BYTES)
+        .profile(profile_key::REQUEST_MIN_COMPRESSION_SIZE_BYTES)
+        .validate(provider_config, parse_min_compression_size)
+        .await
+        .map_err(|err| {
+            tracing::warn!(
+                err = %DisplayErrorContext(&err),
+                "invalid value for the request minimum compression size setting"
+            )
+        })
+        .unwrap_or(None)
+}
+
+#[derive(Debug)]
+enum InvalidMinCompressionSize {
+    NotAnInteger(ParseIntError),
+    OutOfRange(u32),
+}
+
+impl fmt::Display for InvalidMinCompressionSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnInteger(_) => write!(f, "the value was not a non-negative integer"),
+            Self::OutOfRange(bytes) => write!(
+                f,
+                "{bytes} is larger than the maximum of {MAX_REQUEST_MIN_COMPRESSION_SIZE_BYTES} bytes"
+            ),
+        }
+    }
+}
+
+impl Error for InvalidMinCompressionSize {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NotAnInteger(source) => Some(source),
+            Self::OutOfRange(_) => None,
+        }
+    }
+}
+
+fn parse_min_compression_size(value: &str) -> Result<u32, InvalidMinCompressionSize> {
+    match value.parse::<u32>() {
+        Ok(bytes) if bytes > MAX_REQUEST_MIN_COMPRESSION_SIZE_BYTES => {
+            Err(InvalidMinCompressionSize::OutOfRange(bytes))
+        }
+        Ok(bytes) => Ok(bytes),
+        Err(source) => Err(InvalidMinCompressionSize::NotAnInteger(source)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::default_provider::request_min_compression_size_bytes::request_min_compression_size_bytes_provider;
+    use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
+    use crate::provider_config::ProviderConfig;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use tracing_test::traced_test;
+
+    fn env(value: &'static str) -> ProviderConfig {
+        ProviderConfig::empty().with_env(Env::from_slice(&[(
+            "AWS_REQUEST_MIN_COMPRESSION_SIZE_BYTES",
+            value,
+        )]))
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn log_error_on_invalid_value() {
+        assert_eq!(
+            request_min_compression_size_bytes_provider(&env("-1")).await,
+            None
+        );
+        assert!(logs_contain(
+            "invalid value for the request minimum compression size setting"
+        ));
+        assert!(logs_contain("AWS_REQUEST_MIN_COMPRESSION_SIZE_BYTES"));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn log_error_on_out_of_range_value() {
+        assert_eq!(
+            request_min_compression_size_bytes_provider(&env("10485761")).await,
+            None
+        );
+        assert!(logs_contain("larger than the maximum of 10485760 bytes"));
+    }
+
+    #[tokio::test]
+    async fn bounds_are_valid() {
+        assert_eq!(
+            request_min_compression_size_bytes_provider(&env("0")).await,
+            Some(0)
+        );
+        assert_eq!(
+            request_min_compression_size_bytes_provider(&env("10485760")).await,
+            Some(10485760)
+        );
+    }
+
+    #[tokio::test]
+    async fn environment_priority() {
+        let conf = env("100")
+            .with_profile_config(
+                Some(
+                    ProfileFiles::builder()
+                        .with_file(ProfileFileKind::Config, "conf")
+                        .build(),
+                ),
+                None,
+            )
+            .with_fs(Fs::from_slice(&[(
+                "conf",
+                "[default]\nrequest_min_compression_size_bytes = 200",
+            )]));
+        assert_eq!(
+            request_min_compression_size_bytes_provider(&conf).await,
+            Some(100)
+        );
+    }
+}
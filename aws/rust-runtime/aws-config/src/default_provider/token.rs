@@ -0,0 +1,137 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_credential_types::provider::future;
+use aws_credential_types::provider::token::{self, ProvideToken};
+use tracing::Instrument;
+
+use crate::environment::token::EnvironmentVariableTokenProvider;
+use crate::meta::token::TokenProviderChain;
+use crate::provider_config::ProviderConfig;
+
+/// Default bearer token provider chain
+pub async fn default_provider() -> impl ProvideToken {
+    DefaultTokenChain::builder().build().await
+}
+
+/// Default AWS bearer token provider chain
+///
+/// Resolution order:
+/// 1. Environment variables: [`EnvironmentVariableTokenProvider`](crate::environment::token::EnvironmentVariableTokenProvider)
+/// 2. The SSO token cached for the `sso_session` of the active profile:
+///    [`ProfileFileTokenProvider`](crate::profile::token::ProfileFileTokenProvider)
+///
+/// # Examples
+/// Create a default chain that uses a different profile:
+/// ```no_run
+/// # async fn example() {
+/// use aws_config::default_provider::token::DefaultTokenChain;
+/// let token_provider = DefaultTokenChain::builder()
+///     .profile_name("otherprofile")
+///     .build()
+///     .await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DefaultTokenChain {
+    provider_chain: TokenProviderChain,
+}
+
+impl DefaultTokenChain {
+    /// Builder for `DefaultTokenChain`
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn token(&self) -> token::Result {
+        self.provider_chain
+            .provide_token()
+            .instrument(tracing::debug_span!("provide_token", provider = %"default_chain"))
+            .await
+    }
+}
+
+impl ProvideToken for DefaultTokenChain {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideToken::new(self.token())
+    }
+}
+
+/// Builder for [`DefaultTokenChain`](DefaultTokenChain)
+#[derive(Debug, Default)]
+pub struct Builder {
+    profile_file_builder: crate::profile::token::Builder,
+    conf: Option<ProviderConfig>,
+}
+
+impl Builder {
+    /// Override the profile name used by this provider
+    ///
+    /// When unset, the value of the `AWS_PROFILE` environment variable will be used.
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file_builder = self.profile_file_builder.profile_name(name);
+        self
+    }
+
+    /// Override the configuration used for this provider
+    pub fn configure(mut self, config: ProviderConfig) -> Self {
+        self.conf = Some(config);
+        self
+    }
+
+    /// Creates a `DefaultTokenChain`
+    pub async fn build(self) -> DefaultTokenChain {
+        let conf = self.conf.unwrap_or_default();
+
+        let env_provider = EnvironmentVariableTokenProvider::new_with_env(conf.env());
+        let profile_provider = self.profile_file_builder.configure(&conf).build();
+
+        let provider_chain = TokenProviderChain::first_try("Environment", env_provider)
+            .or_else("Profile", profile_provider);
+
+        DefaultTokenChain { provider_chain }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DefaultTokenChain;
+    use crate::provider_config::ProviderConfig;
+    use aws_credential_types::provider::error::TokenError;
+    use aws_credential_types::provider::token::ProvideToken;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    #[tokio::test]
+    async fn environment_takes_precedence() {
+        let conf = ProviderConfig::no_configuration()
+            .with_env(Env::from_slice(&[
+                ("HOME", "/home/user"),
+                ("AWS_BEARER_TOKEN", "env-token"),
+            ]))
+            .with_fs(Fs::from_slice(&[(
+                "/home/user/.aws/config",
+                "[default]\nsso_session = test\n",
+            )]));
+        let chain = DefaultTokenChain::builder().configure(conf).build().await;
+        let token = chain.provide_token().await.expect("success");
+        assert_eq!("env-token", token.token());
+    }
+
+    #[tokio::test]
+    async fn no_token_configured() {
+        let chain = DefaultTokenChain::builder()
+            .configure(ProviderConfig::no_configuration())
+            .build()
+            .await;
+        let err = chain.provide_token().await.expect_err("no token");
+        assert!(
+            matches!(err, TokenError::TokenNotLoaded(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+}
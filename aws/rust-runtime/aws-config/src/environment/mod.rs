@@ -19,6 +19,10 @@ pub use credentials::EnvironmentVariableCredentialsProvider;
 pub mod region;
 pub use region::EnvironmentVariableRegionProvider;
 
+/// Load bearer tokens from the environment
+pub mod token;
+pub use token::EnvironmentVariableTokenProvider;
+
 #[derive(Debug)]
 pub(crate) struct InvalidBooleanValue {
     value: String,
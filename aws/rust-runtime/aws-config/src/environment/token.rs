@@ -0,0 +1,107 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_credential_types::provider::error::TokenError;
+use aws_credential_types::provider::future;
+use aws_credential_types::provider::token::{self, ProvideToken};
+use aws_credential_types::Token;
+use aws_types::os_shim_internal::Env;
+
+const AWS_BEARER_TOKEN: &str = "AWS_BEARER_TOKEN";
+
+/// Load a bearer token from an environment variable
+///
+/// `EnvironmentVariableTokenProvider` uses the `AWS_BEARER_TOKEN` environment variable.
+#[derive(Debug, Clone)]
+pub struct EnvironmentVariableTokenProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableTokenProvider {
+    /// Create a `EnvironmentVariableTokenProvider`
+    pub fn new() -> Self {
+        Self::new_with_env(Env::real())
+    }
+
+    #[doc(hidden)]
+    /// Create a new `EnvironmentVariableTokenProvider` with `Env` overridden
+    ///
+    /// This function is intended for tests that mock out the process environment.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    fn token(&self) -> token::Result {
+        match self.env.get(AWS_BEARER_TOKEN) {
+            Ok(value) if !value.trim().is_empty() => Ok(Token::new(value.trim(), None)),
+            Ok(_) => Err(TokenError::not_loaded(format!(
+                "environment variable set but blank ({})",
+                AWS_BEARER_TOKEN
+            ))),
+            Err(err) => Err(TokenError::not_loaded(format!(
+                "could not read {}: {}",
+                AWS_BEARER_TOKEN, err
+            ))),
+        }
+    }
+}
+
+impl Default for EnvironmentVariableTokenProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProvideToken for EnvironmentVariableTokenProvider {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideToken::ready(self.token())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnvironmentVariableTokenProvider;
+    use aws_credential_types::provider::error::TokenError;
+    use aws_credential_types::provider::token::ProvideToken;
+    use aws_types::os_shim_internal::Env;
+    use futures_util::FutureExt;
+
+    fn make_provider(vars: &[(&str, &str)]) -> EnvironmentVariableTokenProvider {
+        EnvironmentVariableTokenProvider::new_with_env(Env::from_slice(vars))
+    }
+
+    #[test]
+    fn loads_token() {
+        let provider = make_provider(&[("AWS_BEARER_TOKEN", "some-token")]);
+        let token = provider
+            .provide_token()
+            .now_or_never()
+            .unwrap()
+            .expect("valid token");
+        assert_eq!("some-token", token.token());
+        assert_eq!(None, token.expiration());
+    }
+
+    #[test]
+    fn missing_or_blank_token_is_not_loaded() {
+        for provider in [
+            make_provider(&[]),
+            make_provider(&[("AWS_BEARER_TOKEN", " ")]),
+        ] {
+            let err = provider
+                .provide_token()
+                .now_or_never()
+                .unwrap()
+                .expect_err("no token");
+            assert!(
+                matches!(err, TokenError::TokenNotLoaded(_)),
+                "unexpected error: {err:?}"
+            );
+        }
+    }
+}
@@ -170,13 +170,20 @@ fn parse_response(
             secret_access_key,
             session_token,
             expiration,
-        }) => Ok(Credentials::new(
-            access_key_id,
-            secret_access_key,
-            Some(session_token.to_string()),
-            Some(expiration),
-            provider_name,
-        )),
+            account_id,
+        }) => {
+            let mut credentials = Credentials::new(
+                access_key_id,
+                secret_access_key,
+                Some(session_token.to_string()),
+                Some(expiration),
+                provider_name,
+            );
+            if let Some(account_id) = account_id {
+                credentials = credentials.with_account_id(account_id);
+            }
+            Ok(credentials)
+        }
         JsonCredentials::Error { code, message } => Err(OrchestratorError::operation(
             CredentialsError::provider_error(format!(
                 "failed to load credentials [{}]: {}",
@@ -0,0 +1,150 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+pub use aws_smithy_runtime::client::identity::persistent;
+pub use aws_smithy_runtime::client::identity::IdentityCache;
+pub use aws_smithy_runtime::client::identity::LazyCacheBuilder;
+pub use aws_smithy_runtime::client::identity::PersistentCacheBuilder;
+
+use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
+use aws_credential_types::Credentials;
+use aws_smithy_json::deserialize::Token;
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::identity::Identity;
+use persistent::IdentityCodec;
+use std::time::SystemTime;
+
+const PROVIDER_NAME: &str = "PersistentIdentityCache";
+
+/// [`IdentityCodec`] for AWS [`Credentials`], allowing refreshed credentials to be persisted.
+///
+/// Credentials are serialized as JSON, in the format used by the `credential_process` setting.
+///
+/// # Examples
+///
+/// Reusing the credentials resolved by the previous invocations of a command line tool:
+/// ```no_run
+/// use aws_config::identity::persistent::InMemoryIdentityStore;
+/// use aws_config::identity::{CredentialsCodec, IdentityCache};
+///
+/// # async fn example() {
+/// # let store = InMemoryIdentityStore::new();
+/// let sdk_config = aws_config::from_env()
+///     .identity_cache(
+///         IdentityCache::persistent()
+///             .key("my-tool/default")
+///             // e.g. `EncryptedFileIdentityStore`, with the `encrypted-identity-cache` feature
+///             .store(store)
+///             .codec(CredentialsCodec::new())
+///             .build(),
+///     )
+///     .load()
+///     .await;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct CredentialsCodec;
+
+impl CredentialsCodec {
+    /// Creates a new credentials codec.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl IdentityCodec for CredentialsCodec {
+    fn encode(&self, identity: &Identity) -> Option<Vec<u8>> {
+        let credentials = identity.data::<Credentials>()?;
+        let mut out = String::new();
+        let mut writer = JsonObjectWriter::new(&mut out);
+        writer
+            .key("Version")
+            .number(aws_smithy_types::Number::PosInt(1));
+        writer
+            .key("AccessKeyId")
+            .string(credentials.access_key_id());
+        writer
+            .key("SecretAccessKey")
+            .string(credentials.secret_access_key());
+        if let Some(session_token) = credentials.session_token() {
+            writer.key("SessionToken").string(session_token);
+        }
+        writer.finish();
+        Some(out.into_bytes())
+    }
+
+    fn decode(&self, data: &[u8], expiration: SystemTime) -> Result<Identity, BoxError> {
+        let mut access_key_id = None;
+        let mut secret_access_key = None;
+        let mut session_token = None;
+        json_parse_loop(data, |key, value| {
+            match (key, value) {
+                (key, Token::ValueString { value, .. })
+                    if key.eq_ignore_ascii_case("AccessKeyId") =>
+                {
+                    access_key_id = Some(value.to_unescaped()?.into_owned());
+                }
+                (key, Token::ValueString { value, .. })
+                    if key.eq_ignore_ascii_case("SecretAccessKey") =>
+                {
+                    secret_access_key = Some(value.to_unescaped()?.into_owned());
+                }
+                (key, Token::ValueString { value, .. })
+                    if key.eq_ignore_ascii_case("SessionToken") =>
+                {
+                    session_token = Some(value.to_unescaped()?.into_owned());
+                }
+                _ => {}
+            }
+            Ok(())
+        })?;
+        let credentials = Credentials::new(
+            access_key_id.ok_or(InvalidJsonCredentials::MissingField("AccessKeyId"))?,
+            secret_access_key.ok_or(InvalidJsonCredentials::MissingField("SecretAccessKey"))?,
+            session_token,
+            Some(expiration),
+            PROVIDER_NAME,
+        );
+        Ok(credentials.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn credentials_round_trip() {
+        let expiration = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let credentials = Credentials::new("akid", "secret", Some("token".into()), None, "test");
+        let codec = CredentialsCodec::new();
+
+        let data = codec.encode(&credentials.into()).unwrap();
+        let identity = codec.decode(&data, expiration).unwrap();
+        assert_eq!(Some(expiration), identity.expiration());
+        let decoded = identity.data::<Credentials>().unwrap();
+        assert_eq!("akid", decoded.access_key_id());
+        assert_eq!("secret", decoded.secret_access_key());
+        assert_eq!(Some("token"), decoded.session_token());
+        assert_eq!(Some(expiration), decoded.expiry());
+    }
+
+    #[test]
+    fn other_identities_are_not_encoded() {
+        let identity = Identity::new("not credentials", None);
+        assert_eq!(None, CredentialsCodec::new().encode(&identity));
+    }
+
+    #[test]
+    fn missing_fields_are_rejected() {
+        let err = CredentialsCodec::new()
+            .decode(br#"{"AccessKeyId":"akid"}"#, SystemTime::UNIX_EPOCH)
+            .unwrap_err();
+        assert!(err.to_string().contains("SecretAccessKey"), "{err}");
+    }
+}
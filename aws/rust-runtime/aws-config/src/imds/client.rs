@@ -8,11 +8,13 @@
 //! Client for direct access to IMDSv2.
 
 use crate::imds::client::error::{BuildError, ImdsError, InnerImdsError, InvalidEndpointMode};
+use crate::imds::client::metadata::MetadataCache;
 use crate::imds::client::token::TokenRuntimePlugin;
 use crate::provider_config::ProviderConfig;
 use crate::PKG_VERSION;
 use aws_http::user_agent::{ApiMetadata, AwsUserAgent};
 use aws_runtime::user_agent::UserAgentInterceptor;
+use aws_smithy_async::rt::sleep::SharedAsyncSleep;
 use aws_smithy_runtime::client::orchestrator::operation::Operation;
 use aws_smithy_runtime::client::retries::strategy::StandardRetryStrategy;
 use aws_smithy_runtime_api::client::auth::AuthSchemeOptionResolverParams;
@@ -45,6 +47,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 pub mod error;
+pub mod metadata;
 mod token;
 
 // 6 hours
@@ -52,6 +55,7 @@ const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(21_600);
 const DEFAULT_ATTEMPTS: u32 = 4;
 const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
 const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(1);
+const DEFAULT_METADATA_CACHE_TTL: Duration = Duration::from_secs(300);
 
 fn user_agent() -> AwsUserAgent {
     AwsUserAgent::new_from_environment(Env::real(), ApiMetadata::new("imds", PKG_VERSION))
@@ -126,9 +130,20 @@ fn user_agent() -> AwsUserAgent {
 ///
 /// 7. The default value of `http://169.254.169.254` will be used.
 ///
+/// # Typed metadata
+/// Besides [`get`](Client::get), the client has typed accessors for the documented metadata
+/// categories, like [`instance_identity_document`](Client::instance_identity_document),
+/// [`instance_tags`](Client::instance_tags), and [`network_interfaces`](Client::network_interfaces).
+/// Responses that rarely change are cached for the [metadata cache TTL](Builder::metadata_cache_ttl).
+/// Spot instance interruption notices and rebalance recommendations are never cached, and can be
+/// waited for with [`wait_for_interruption_notice`](Client::wait_for_interruption_notice).
+///
+/// See [`metadata`] for the response types.
 #[derive(Clone, Debug)]
 pub struct Client {
     operation: Operation<String, SensitiveString, InnerImdsError>,
+    metadata_cache: MetadataCache,
+    sleep_impl: Option<SharedAsyncSleep>,
 }
 
 impl Client {
@@ -321,6 +336,7 @@ pub struct Builder {
     endpoint: Option<EndpointSource>,
     mode_override: Option<EndpointMode>,
     token_ttl: Option<Duration>,
+    metadata_cache_ttl: Option<Duration>,
     connect_timeout: Option<Duration>,
     read_timeout: Option<Duration>,
     config: Option<ProviderConfig>,
@@ -382,6 +398,17 @@ impl Builder {
         self
     }
 
+    /// Override the time-to-live for cached metadata
+    ///
+    /// The typed metadata accessors of the [`Client`], like
+    /// [`instance_identity_document`](Client::instance_identity_document), cache responses for
+    /// this long. By default, responses are cached for 5 minutes. Set this to zero to disable
+    /// caching. [`Client::get`] never caches responses.
+    pub fn metadata_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.metadata_cache_ttl = Some(ttl);
+        self
+    }
+
     /// Override the connect timeout for IMDS
     ///
     /// This value defaults to 1 second
@@ -455,7 +482,15 @@ impl Builder {
                 }
             })
             .build();
-        Client { operation }
+        Client {
+            operation,
+            metadata_cache: MetadataCache::new(
+                self.metadata_cache_ttl
+                    .unwrap_or(DEFAULT_METADATA_CACHE_TTL),
+                config.time_source(),
+            ),
+            sleep_impl: config.sleep_impl(),
+        }
     }
 }
 
@@ -0,0 +1,1162 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Typed instance metadata
+//!
+//! Response types for the typed accessors of the [IMDS `Client`](super::Client). See
+//! [Instance metadata categories](https://docs.aws.amazon.com/AWSEC2/latest/UserGuide/instancedata-data-categories.html)
+//! for a description of each category.
+
+use super::error::ImdsError;
+use super::{Client, SensitiveString};
+use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_json::deserialize::token::skip_value;
+use aws_smithy_json::deserialize::{json_token_iter, EscapeError, Token};
+use aws_smithy_runtime::expiring_cache::ExpiringCache;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::{DateTime, Number};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+mod paths {
+    pub(super) const IDENTITY_DOCUMENT: &str = "/latest/dynamic/instance-identity/document";
+    pub(super) const AMI_ID: &str = "/latest/meta-data/ami-id";
+    pub(super) const INSTANCE_ID: &str = "/latest/meta-data/instance-id";
+    pub(super) const INSTANCE_TYPE: &str = "/latest/meta-data/instance-type";
+    pub(super) const HOSTNAME: &str = "/latest/meta-data/hostname";
+    pub(super) const LOCAL_IPV4: &str = "/latest/meta-data/local-ipv4";
+    pub(super) const PUBLIC_IPV4: &str = "/latest/meta-data/public-ipv4";
+    pub(super) const MAC: &str = "/latest/meta-data/mac";
+    pub(super) const IAM_INFO: &str = "/latest/meta-data/iam/info";
+    pub(super) const PLACEMENT: &str = "/latest/meta-data/placement";
+    pub(super) const TAGS: &str = "/latest/meta-data/tags/instance";
+    pub(super) const MACS: &str = "/latest/meta-data/network/interfaces/macs";
+    pub(super) const USER_DATA: &str = "/latest/user-data";
+    pub(super) const SPOT_INSTANCE_ACTION: &str = "/latest/meta-data/spot/instance-action";
+    pub(super) const REBALANCE_RECOMMENDATION: &str =
+        "/latest/meta-data/events/recommendations/rebalance";
+}
+
+/// Cache of metadata responses, keyed by path
+#[derive(Clone, Debug)]
+pub(super) struct MetadataCache {
+    ttl: Duration,
+    time_source: SharedTimeSource,
+    entries: Arc<Mutex<HashMap<String, ExpiringCache<SensitiveString, ImdsError>>>>,
+}
+
+impl MetadataCache {
+    pub(super) fn new(ttl: Duration, time_source: SharedTimeSource) -> Self {
+        Self {
+            ttl,
+            time_source,
+            entries: Default::default(),
+        }
+    }
+
+    async fn get_or_load(
+        &self,
+        client: &Client,
+        path: String,
+    ) -> Result<SensitiveString, ImdsError> {
+        if self.ttl.is_zero() {
+            return client.get(path).await;
+        }
+        let entry = self
+            .entries
+            .lock()
+            .unwrap()
+            .entry(path.clone())
+            .or_insert_with(|| ExpiringCache::new(Duration::ZERO))
+            .clone();
+        let now = self.time_source.now();
+        match entry.yield_or_clear_if_expired(now).await {
+            Some(response) => {
+                tracing::trace!(path = %path, "loaded metadata from cache");
+                Ok(response)
+            }
+            None => {
+                entry
+                    .get_or_load(|| async {
+                        let response = client.get(path).await?;
+                        Ok((response, self.time_source.now() + self.ttl))
+                    })
+                    .await
+            }
+        }
+    }
+}
+
+/// The instance identity document
+///
+/// The document describes the instance, and is loaded from
+/// `/latest/dynamic/instance-identity/document`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstanceIdentityDocument {
+    account_id: String,
+    architecture: String,
+    availability_zone: String,
+    image_id: String,
+    instance_id: String,
+    instance_type: String,
+    kernel_id: Option<String>,
+    ramdisk_id: Option<String>,
+    pending_time: Option<SystemTime>,
+    private_ip: Option<String>,
+    region: String,
+    version: Option<String>,
+    billing_products: Vec<String>,
+    marketplace_product_codes: Vec<String>,
+}
+
+impl InstanceIdentityDocument {
+    fn parse(document: &str) -> Result<Self, InvalidMetadata> {
+        let mut object = JsonObject::parse(document)?;
+        Ok(Self {
+            account_id: object.required("accountId")?,
+            architecture: object.required("architecture")?,
+            availability_zone: object.required("availabilityZone")?,
+            image_id: object.required("imageId")?,
+            instance_id: object.required("instanceId")?,
+            instance_type: object.required("instanceType")?,
+            kernel_id: object.string("kernelId"),
+            ramdisk_id: object.string("ramdiskId"),
+            pending_time: object.time("pendingTime")?,
+            private_ip: object.string("privateIp"),
+            region: object.required("region")?,
+            version: object.string("version"),
+            billing_products: object.strings("billingProducts"),
+            marketplace_product_codes: object.strings("marketplaceProductCodes"),
+        })
+    }
+
+    /// Returns the ID of the account that owns the instance
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// Returns the architecture of the AMI used to launch the instance, for example `x86_64`
+    pub fn architecture(&self) -> &str {
+        &self.architecture
+    }
+
+    /// Returns the Availability Zone in which the instance is running
+    pub fn availability_zone(&self) -> &str {
+        &self.availability_zone
+    }
+
+    /// Returns the ID of the AMI used to launch the instance
+    pub fn image_id(&self) -> &str {
+        &self.image_id
+    }
+
+    /// Returns the ID of the instance
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Returns the instance type of the instance
+    pub fn instance_type(&self) -> &str {
+        &self.instance_type
+    }
+
+    /// Returns the ID of the kernel associated with the instance, if applicable
+    pub fn kernel_id(&self) -> Option<&str> {
+        self.kernel_id.as_deref()
+    }
+
+    /// Returns the ID of the RAM disk associated with the instance, if applicable
+    pub fn ramdisk_id(&self) -> Option<&str> {
+        self.ramdisk_id.as_deref()
+    }
+
+    /// Returns the date and time that the instance was launched
+    pub fn pending_time(&self) -> Option<SystemTime> {
+        self.pending_time
+    }
+
+    /// Returns the private IPv4 address of the instance
+    pub fn private_ip(&self) -> Option<&str> {
+        self.private_ip.as_deref()
+    }
+
+    /// Returns the Region in which the instance is running
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Returns the version of the instance identity document format
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns the billing products of the instance
+    pub fn billing_products(&self) -> &[String] {
+        &self.billing_products
+    }
+
+    /// Returns the AWS Marketplace product codes of the AMI used to launch the instance
+    pub fn marketplace_product_codes(&self) -> &[String] {
+        &self.marketplace_product_codes
+    }
+}
+
+/// Information about the IAM role associated with the instance profile of the instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IamInfo {
+    instance_profile_arn: String,
+    instance_profile_id: String,
+    last_updated: Option<SystemTime>,
+}
+
+impl IamInfo {
+    fn parse(document: &str) -> Result<Self, InvalidMetadata> {
+        let mut object = JsonObject::parse(document)?;
+        match object.string("Code").as_deref() {
+            None | Some("Success") => {}
+            Some(code) => {
+                return Err(InvalidMetadata(
+                    format!("IAM info returned an error code: {}", code).into(),
+                ))
+            }
+        }
+        Ok(Self {
+            instance_profile_arn: object.required("InstanceProfileArn")?,
+            instance_profile_id: object.required("InstanceProfileId")?,
+            last_updated: object.time("LastUpdated")?,
+        })
+    }
+
+    /// Returns the ARN of the instance profile
+    pub fn instance_profile_arn(&self) -> &str {
+        &self.instance_profile_arn
+    }
+
+    /// Returns the ID of the instance profile
+    pub fn instance_profile_id(&self) -> &str {
+        &self.instance_profile_id
+    }
+
+    /// Returns when the IAM information was last updated
+    pub fn last_updated(&self) -> Option<SystemTime> {
+        self.last_updated
+    }
+}
+
+/// Where the instance was placed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    availability_zone: String,
+    availability_zone_id: Option<String>,
+    region: String,
+    group_name: Option<String>,
+    partition_number: Option<u32>,
+    host_id: Option<String>,
+}
+
+impl Placement {
+    /// Returns the Availability Zone in which the instance is running
+    pub fn availability_zone(&self) -> &str {
+        &self.availability_zone
+    }
+
+    /// Returns the ID of the Availability Zone, which is consistent across accounts
+    pub fn availability_zone_id(&self) -> Option<&str> {
+        self.availability_zone_id.as_deref()
+    }
+
+    /// Returns the Region in which the instance is running
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Returns the name of the placement group the instance was launched in, if any
+    pub fn group_name(&self) -> Option<&str> {
+        self.group_name.as_deref()
+    }
+
+    /// Returns the partition of a partition placement group that the instance is in, if any
+    pub fn partition_number(&self) -> Option<u32> {
+        self.partition_number
+    }
+
+    /// Returns the ID of the Dedicated Host that the instance is running on, if any
+    pub fn host_id(&self) -> Option<&str> {
+        self.host_id.as_deref()
+    }
+}
+
+/// A network interface attached to the instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkInterface {
+    mac: String,
+    device_number: Option<u32>,
+    interface_id: Option<String>,
+    subnet_id: Option<String>,
+    vpc_id: Option<String>,
+    local_ipv4s: Vec<String>,
+    public_ipv4s: Vec<String>,
+    ipv6s: Vec<String>,
+    security_group_ids: Vec<String>,
+}
+
+impl NetworkInterface {
+    /// Returns the MAC address of the network interface
+    pub fn mac(&self) -> &str {
+        &self.mac
+    }
+
+    /// Returns the device number of the network interface. The primary interface has device number `0`.
+    pub fn device_number(&self) -> Option<u32> {
+        self.device_number
+    }
+
+    /// Returns the ID of the network interface
+    pub fn interface_id(&self) -> Option<&str> {
+        self.interface_id.as_deref()
+    }
+
+    /// Returns the ID of the subnet in which the interface resides
+    pub fn subnet_id(&self) -> Option<&str> {
+        self.subnet_id.as_deref()
+    }
+
+    /// Returns the ID of the VPC in which the interface resides
+    pub fn vpc_id(&self) -> Option<&str> {
+        self.vpc_id.as_deref()
+    }
+
+    /// Returns the private IPv4 addresses associated with the interface
+    pub fn local_ipv4s(&self) -> &[String] {
+        &self.local_ipv4s
+    }
+
+    /// Returns the public IPv4 addresses associated with the interface
+    pub fn public_ipv4s(&self) -> &[String] {
+        &self.public_ipv4s
+    }
+
+    /// Returns the IPv6 addresses associated with the interface
+    pub fn ipv6s(&self) -> &[String] {
+        &self.ipv6s
+    }
+
+    /// Returns the IDs of the security groups the interface belongs to
+    pub fn security_group_ids(&self) -> &[String] {
+        &self.security_group_ids
+    }
+}
+
+/// A notice that a Spot Instance will be stopped, hibernated, or terminated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpotInstanceAction {
+    action: String,
+    time: SystemTime,
+}
+
+impl SpotInstanceAction {
+    fn parse(document: &str) -> Result<Self, InvalidMetadata> {
+        let mut object = JsonObject::parse(document)?;
+        Ok(Self {
+            action: object.required("action")?,
+            time: object
+                .time("time")?
+                .ok_or(InvalidMetadata("missing field `time`".into()))?,
+        })
+    }
+
+    /// Returns the action that will be taken: `stop`, `hibernate`, or `terminate`
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    /// Returns when the action will be taken
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+}
+
+/// A notice that the instance is at an elevated risk of Spot interruption
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebalanceRecommendation {
+    notice_time: SystemTime,
+}
+
+impl RebalanceRecommendation {
+    fn parse(document: &str) -> Result<Self, InvalidMetadata> {
+        let mut object = JsonObject::parse(document)?;
+        Ok(Self {
+            notice_time: object
+                .time("noticeTime")?
+                .ok_or(InvalidMetadata("missing field `noticeTime`".into()))?,
+        })
+    }
+
+    /// Returns when the rebalance recommendation was emitted
+    pub fn notice_time(&self) -> SystemTime {
+        self.notice_time
+    }
+}
+
+/// A notice returned by [`Client::wait_for_interruption_notice`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InterruptionNotice {
+    /// The Spot Instance will be stopped, hibernated, or terminated
+    SpotInstanceAction(SpotInstanceAction),
+    /// The instance is at an elevated risk of Spot interruption
+    RebalanceRecommendation(RebalanceRecommendation),
+}
+
+impl Client {
+    /// Load the [instance identity document](InstanceIdentityDocument)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aws_config::imds::client::Client;
+    /// # async fn docs() {
+    /// let client = Client::builder().build();
+    /// let document = client
+    ///     .instance_identity_document()
+    ///     .await
+    ///     .expect("failure communicating with IMDS");
+    /// println!("running {} in {}", document.instance_id(), document.region());
+    /// # }
+    /// ```
+    pub async fn instance_identity_document(&self) -> Result<InstanceIdentityDocument, ImdsError> {
+        let document = self.get_cached(paths::IDENTITY_DOCUMENT).await?;
+        InstanceIdentityDocument::parse(document.as_ref()).map_err(ImdsError::unexpected)
+    }
+
+    /// Load the ID of the AMI used to launch the instance
+    pub async fn ami_id(&self) -> Result<String, ImdsError> {
+        self.get_text(paths::AMI_ID).await
+    }
+
+    /// Load the ID of the instance
+    pub async fn instance_id(&self) -> Result<String, ImdsError> {
+        self.get_text(paths::INSTANCE_ID).await
+    }
+
+    /// Load the instance type of the instance
+    pub async fn instance_type(&self) -> Result<String, ImdsError> {
+        self.get_text(paths::INSTANCE_TYPE).await
+    }
+
+    /// Load the private IPv4 DNS hostname of the instance
+    pub async fn hostname(&self) -> Result<String, ImdsError> {
+        self.get_text(paths::HOSTNAME).await
+    }
+
+    /// Load the private IPv4 address of the instance
+    pub async fn local_ipv4(&self) -> Result<String, ImdsError> {
+        self.get_text(paths::LOCAL_IPV4).await
+    }
+
+    /// Load the public IPv4 address of the instance, or `None` if it doesn't have one
+    pub async fn public_ipv4(&self) -> Result<Option<String>, ImdsError> {
+        Ok(self
+            .get_optional(paths::PUBLIC_IPV4, true)
+            .await?
+            .map(String::from))
+    }
+
+    /// Load the MAC address of the primary network interface of the instance
+    pub async fn mac(&self) -> Result<String, ImdsError> {
+        self.get_text(paths::MAC).await
+    }
+
+    /// Load information about the instance profile, or `None` if the instance doesn't have one
+    pub async fn iam_info(&self) -> Result<Option<IamInfo>, ImdsError> {
+        match self.get_optional(paths::IAM_INFO, true).await? {
+            Some(info) => Ok(Some(
+                IamInfo::parse(info.as_ref()).map_err(ImdsError::unexpected)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Load the [placement](Placement) of the instance
+    pub async fn placement(&self) -> Result<Placement, ImdsError> {
+        let field = |name: &'static str| format!("{}/{}", paths::PLACEMENT, name);
+        let optional = |name: &'static str| async move {
+            Ok::<_, ImdsError>(
+                self.get_optional(field(name), true)
+                    .await?
+                    .map(String::from),
+            )
+        };
+        Ok(Placement {
+            availability_zone: self.get_text(field("availability-zone")).await?,
+            availability_zone_id: optional("availability-zone-id").await?,
+            region: self.get_text(field("region")).await?,
+            group_name: optional("group-name").await?,
+            partition_number: parse_number(optional("partition-number").await?)
+                .map_err(ImdsError::unexpected)?,
+            host_id: optional("host-id").await?,
+        })
+    }
+
+    /// Load the tags of the instance
+    ///
+    /// Tags are only available when access to tags in instance metadata is enabled for the
+    /// instance. Otherwise, IMDS returns a `404` [error response](ImdsError::ErrorResponse).
+    pub async fn instance_tags(&self) -> Result<HashMap<String, String>, ImdsError> {
+        let keys = self.get_cached(paths::TAGS).await?;
+        let mut tags = HashMap::new();
+        for key in lines(keys.as_ref()) {
+            let value = self.get_text(format!("{}/{}", paths::TAGS, key)).await?;
+            tags.insert(key.to_string(), value);
+        }
+        Ok(tags)
+    }
+
+    /// Load the [network interfaces](NetworkInterface) attached to the instance
+    pub async fn network_interfaces(&self) -> Result<Vec<NetworkInterface>, ImdsError> {
+        let macs = self.get_cached(format!("{}/", paths::MACS)).await?;
+        let mut interfaces = vec![];
+        for mac in lines(macs.as_ref()) {
+            let mac = mac.trim_end_matches('/');
+            let field = |name: &'static str| format!("{}/{}/{}", paths::MACS, mac, name);
+            let optional = |name: &'static str| async move {
+                Ok::<_, ImdsError>(
+                    self.get_optional(field(name), true)
+                        .await?
+                        .map(String::from),
+                )
+            };
+            let list = |name: &'static str| async move {
+                Ok::<_, ImdsError>(
+                    optional(name)
+                        .await?
+                        .map(|list| lines(&list).map(String::from).collect())
+                        .unwrap_or_default(),
+                )
+            };
+            interfaces.push(NetworkInterface {
+                mac: mac.to_string(),
+                device_number: parse_number(optional("device-number").await?)
+                    .map_err(ImdsError::unexpected)?,
+                interface_id: optional("interface-id").await?,
+                subnet_id: optional("subnet-id").await?,
+                vpc_id: optional("vpc-id").await?,
+                local_ipv4s: list("local-ipv4s").await?,
+                public_ipv4s: list("public-ipv4s").await?,
+                ipv6s: list("ipv6s").await?,
+                security_group_ids: list("security-group-ids").await?,
+            });
+        }
+        Ok(interfaces)
+    }
+
+    /// Load the user data of the instance, or `None` if it doesn't have any
+    pub async fn user_data(&self) -> Result<Option<SensitiveString>, ImdsError> {
+        self.get_optional(paths::USER_DATA, true).await
+    }
+
+    /// Check for a pending [Spot Instance action](SpotInstanceAction)
+    ///
+    /// Returns `None` when no action is scheduled. The response is never cached.
+    pub async fn spot_instance_action(&self) -> Result<Option<SpotInstanceAction>, ImdsError> {
+        match self
+            .get_optional(paths::SPOT_INSTANCE_ACTION, false)
+            .await?
+        {
+            Some(action) => Ok(Some(
+                SpotInstanceAction::parse(action.as_ref()).map_err(ImdsError::unexpected)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Check for a [rebalance recommendation](RebalanceRecommendation)
+    ///
+    /// Returns `None` when no recommendation was emitted. The response is never cached.
+    pub async fn rebalance_recommendation(
+        &self,
+    ) -> Result<Option<RebalanceRecommendation>, ImdsError> {
+        match self
+            .get_optional(paths::REBALANCE_RECOMMENDATION, false)
+            .await?
+        {
+            Some(recommendation) => Ok(Some(
+                RebalanceRecommendation::parse(recommendation.as_ref())
+                    .map_err(ImdsError::unexpected)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Wait until a Spot Instance action is scheduled or a rebalance recommendation is emitted
+    ///
+    /// Both notices are checked every `poll_interval`, and the first notice found is returned. A
+    /// Spot Instance action takes precedence over a rebalance recommendation. AWS recommends
+    /// checking for notices every 5 seconds.
+    ///
+    /// This requires a sleep implementation to be configured on the client, and fails with an
+    /// [unexpected error](ImdsError::Unexpected) otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aws_config::imds::client::metadata::InterruptionNotice;
+    /// use aws_config::imds::client::Client;
+    /// use std::time::Duration;
+    /// # async fn docs() {
+    /// let client = Client::builder().build();
+    /// match client.wait_for_interruption_notice(Duration::from_secs(5)).await {
+    ///     Ok(InterruptionNotice::SpotInstanceAction(action)) => {
+    ///         println!("the instance will {} soon", action.action())
+    ///     }
+    ///     Ok(notice) => println!("received {:?}", notice),
+    ///     Err(err) => println!("failed to check for notices: {}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn wait_for_interruption_notice(
+        &self,
+        poll_interval: Duration,
+    ) -> Result<InterruptionNotice, ImdsError> {
+        let sleep_impl = self.sleep_impl.clone().ok_or_else(|| {
+            ImdsError::unexpected("a sleep implementation is required to poll for notices")
+        })?;
+        loop {
+            if let Some(action) = self.spot_instance_action().await? {
+                return Ok(InterruptionNotice::SpotInstanceAction(action));
+            }
+            if let Some(recommendation) = self.rebalance_recommendation().await? {
+                return Ok(InterruptionNotice::RebalanceRecommendation(recommendation));
+            }
+            tracing::trace!(poll_interval = ?poll_interval, "no interruption notice yet");
+            sleep_impl.sleep(poll_interval).await;
+        }
+    }
+
+    async fn get_cached(&self, path: impl Into<String>) -> Result<SensitiveString, ImdsError> {
+        self.metadata_cache.get_or_load(self, path.into()).await
+    }
+
+    async fn get_text(&self, path: impl Into<String>) -> Result<String, ImdsError> {
+        Ok(self.get_cached(path).await?.into())
+    }
+
+    /// Load metadata that may not exist, mapping a `404` response to `None`
+    async fn get_optional(
+        &self,
+        path: impl Into<String>,
+        cached: bool,
+    ) -> Result<Option<SensitiveString>, ImdsError> {
+        let response = match cached {
+            true => self.get_cached(path).await,
+            false => self.get(path).await,
+        };
+        match response {
+            Ok(response) => Ok(Some(response)),
+            Err(ImdsError::ErrorResponse(context))
+                if context.response().status().as_u16() == 404 =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Splits a metadata listing into its non-empty lines
+fn lines(listing: &str) -> impl Iterator<Item = &str> {
+    listing
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+fn parse_number(value: Option<String>) -> Result<Option<u32>, InvalidMetadata> {
+    value
+        .map(|value| {
+            value.trim().parse().map_err(|_| {
+                InvalidMetadata(format!("expected a number, found `{}`", value).into())
+            })
+        })
+        .transpose()
+}
+
+/// A metadata response could not be parsed
+#[derive(Debug)]
+struct InvalidMetadata(Cow<'static, str>);
+
+impl From<EscapeError> for InvalidMetadata {
+    fn from(err: EscapeError) -> Self {
+        InvalidMetadata(format!("invalid JSON in response: {}", err).into())
+    }
+}
+
+impl From<aws_smithy_json::deserialize::error::DeserializeError> for InvalidMetadata {
+    fn from(err: aws_smithy_json::deserialize::error::DeserializeError) -> Self {
+        InvalidMetadata(format!("invalid JSON in response: {}", err).into())
+    }
+}
+
+impl fmt::Display for InvalidMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid instance metadata: {}", self.0)
+    }
+}
+
+impl Error for InvalidMetadata {}
+
+#[derive(Debug)]
+enum JsonValue {
+    String(String),
+    Strings(Vec<String>),
+    Number(Number),
+}
+
+/// The fields of a JSON object whose values are strings, numbers, or arrays of strings
+///
+/// Fields with other values, like nested objects, are ignored.
+struct JsonObject(HashMap<String, JsonValue>);
+
+impl JsonObject {
+    fn parse(document: &str) -> Result<Self, InvalidMetadata> {
+        let mut tokens = json_token_iter(document.as_bytes()).peekable();
+        if !matches!(tokens.next().transpose()?, Some(Token::StartObject { .. })) {
+            return Err(InvalidMetadata(
+                "expected a JSON document starting with `{`".into(),
+            ));
+        }
+        let mut fields = HashMap::new();
+        loop {
+            let key = match tokens.next().transpose()? {
+                Some(Token::EndObject { .. }) => break,
+                Some(Token::ObjectKey { key, .. }) => key.to_unescaped()?.into_owned(),
+                other => {
+                    return Err(InvalidMetadata(
+                        format!("expected object key, found: {:?}", other).into(),
+                    ))
+                }
+            };
+            if matches!(tokens.peek(), Some(Ok(Token::StartObject { .. }))) {
+                skip_value(&mut tokens)?;
+                continue;
+            }
+            let value = match tokens.next().transpose()? {
+                Some(Token::ValueString { value, .. }) => {
+                    JsonValue::String(value.to_unescaped()?.into_owned())
+                }
+                Some(Token::ValueNumber { value, .. }) => JsonValue::Number(value),
+                Some(Token::StartArray { .. }) => {
+                    let mut values = vec![];
+                    loop {
+                        match tokens.next().transpose()? {
+                            Some(Token::EndArray { .. }) => break,
+                            Some(Token::ValueString { value, .. }) => {
+                                values.push(value.to_unescaped()?.into_owned())
+                            }
+                            other => {
+                                return Err(InvalidMetadata(
+                                    format!(
+                                        "expected an array of strings for `{}`, found: {:?}",
+                                        key, other
+                                    )
+                                    .into(),
+                                ))
+                            }
+                        }
+                    }
+                    JsonValue::Strings(values)
+                }
+                // `null` and booleans
+                _ => continue,
+            };
+            fields.insert(key, value);
+        }
+        if tokens.next().is_some() {
+            return Err(InvalidMetadata(
+                "found more JSON tokens after completing parsing".into(),
+            ));
+        }
+        Ok(Self(fields))
+    }
+
+    fn string(&mut self, key: &str) -> Option<String> {
+        match self.0.remove(key)? {
+            JsonValue::String(value) => Some(value),
+            JsonValue::Number(Number::PosInt(value)) => Some(value.to_string()),
+            JsonValue::Number(Number::NegInt(value)) => Some(value.to_string()),
+            JsonValue::Number(Number::Float(value)) => Some(value.to_string()),
+            JsonValue::Strings(_) => None,
+        }
+    }
+
+    fn required(&mut self, key: &'static str) -> Result<String, InvalidMetadata> {
+        self.string(key)
+            .ok_or_else(|| InvalidMetadata(format!("missing field `{}`", key).into()))
+    }
+
+    fn strings(&mut self, key: &str) -> Vec<String> {
+        match self.0.remove(key) {
+            Some(JsonValue::Strings(values)) => values,
+            _ => vec![],
+        }
+    }
+
+    fn time(&mut self, key: &'static str) -> Result<Option<SystemTime>, InvalidMetadata> {
+        self.string(key)
+            .map(|value| {
+                DateTime::from_str(&value, Format::DateTime)
+                    .ok()
+                    .and_then(|time| SystemTime::try_from(time).ok())
+                    .ok_or_else(|| {
+                        InvalidMetadata(format!("invalid timestamp in `{}`: {}", key, value).into())
+                    })
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{InstanceIdentityDocument, InterruptionNotice, JsonObject};
+    use crate::imds::client::test::{imds_request, imds_response, token_request, token_response};
+    use crate::imds::client::Client;
+    use crate::provider_config::ProviderConfig;
+    use aws_smithy_async::test_util::instant_time_and_sleep;
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+    use aws_smithy_types::body::SdkBody;
+    use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const TOKEN: &str = "AQAEAFTNrA4eEGx0AQgJ1arIq_Cc-t4tWt3fB0Hd8RKhXlKc5ccvhg==";
+    const BASE: &str = "http://169.254.169.254";
+
+    const IDENTITY_DOCUMENT: &str = r#"{
+        "accountId" : "123456789012",
+        "architecture" : "x86_64",
+        "availabilityZone" : "us-west-2b",
+        "billingProducts" : null,
+        "devpayProductCodes" : null,
+        "marketplaceProductCodes" : [ "1abc2defghijklm3nopqrs4tu" ],
+        "imageId" : "ami-5fb8c835",
+        "instanceId" : "i-1234567890abcdef0",
+        "instanceType" : "t2.micro",
+        "kernelId" : null,
+        "pendingTime" : "2016-11-19T16:32:11Z",
+        "privateIp" : "10.158.112.84",
+        "ramdiskId" : null,
+        "region" : "us-west-2",
+        "version" : "2017-09-30"
+    }"#;
+
+    fn token() -> ReplayEvent {
+        ReplayEvent::new(token_request(BASE, 21600), token_response(21600, TOKEN))
+    }
+
+    fn get(path: &'static str, body: &'static str) -> ReplayEvent {
+        ReplayEvent::new(imds_request(path, TOKEN), imds_response(body))
+    }
+
+    fn not_found(path: &'static str) -> ReplayEvent {
+        ReplayEvent::new(
+            imds_request(path, TOKEN),
+            HttpResponse::try_from(
+                http::Response::builder()
+                    .status(404)
+                    .body(SdkBody::empty())
+                    .unwrap(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn parse_identity_document() {
+        let document = InstanceIdentityDocument::parse(IDENTITY_DOCUMENT).expect("valid");
+        assert_eq!("123456789012", document.account_id());
+        assert_eq!("i-1234567890abcdef0", document.instance_id());
+        assert_eq!("us-west-2", document.region());
+        assert_eq!(None, document.kernel_id());
+        assert_eq!(Some("10.158.112.84"), document.private_ip());
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(1479573131)),
+            document.pending_time()
+        );
+        assert!(document.billing_products().is_empty());
+        assert_eq!(
+            &["1abc2defghijklm3nopqrs4tu".to_string()],
+            document.marketplace_product_codes()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_documents() {
+        for (document, message) in [
+            ("not json", "invalid JSON"),
+            ("[]", "expected a JSON document starting with `{`"),
+            (
+                r#"{"accountId": "123456789012"}"#,
+                "missing field `architecture`",
+            ),
+            (
+                r#"{"billingProducts": [1]}"#,
+                "expected an array of strings",
+            ),
+        ] {
+            let err = InstanceIdentityDocument::parse(document).expect_err("invalid");
+            assert!(
+                err.to_string().contains(message),
+                "`{}` did not contain `{}`",
+                err,
+                message
+            );
+        }
+        // nested objects are skipped
+        let mut object = JsonObject::parse(r#"{"nested": {"a": [1, 2]}, "b": 2}"#).unwrap();
+        assert_eq!(None, object.string("nested"));
+        assert_eq!(Some("2".to_string()), object.string("b"));
+    }
+
+    #[tokio::test]
+    async fn metadata_is_cached_for_ttl() {
+        let http_client = StaticReplayClient::new(vec![
+            token(),
+            get(
+                "http://169.254.169.254/latest/dynamic/instance-identity/document",
+                IDENTITY_DOCUMENT,
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/instance-id",
+                "i-1234567890abcdef0",
+            ),
+            get(
+                "http://169.254.169.254/latest/dynamic/instance-identity/document",
+                IDENTITY_DOCUMENT,
+            ),
+        ]);
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let client = Client::builder()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_http_client(http_client.clone())
+                    .with_time_source(time_source.clone())
+                    .with_sleep_impl(sleep),
+            )
+            .metadata_cache_ttl(Duration::from_secs(60))
+            .build();
+
+        let first = client.instance_identity_document().await.expect("success");
+        let cached = client.instance_identity_document().await.expect("success");
+        assert_eq!(first, cached);
+        assert_eq!(
+            "i-1234567890abcdef0",
+            client.instance_id().await.expect("success")
+        );
+        time_source.advance(Duration::from_secs(60));
+        let reloaded = client.instance_identity_document().await.expect("success");
+        assert_eq!(first, reloaded);
+        http_client.assert_requests_match(&[]);
+    }
+
+    #[tokio::test]
+    async fn caching_can_be_disabled() {
+        let http_client = StaticReplayClient::new(vec![
+            token(),
+            get(
+                "http://169.254.169.254/latest/meta-data/mac",
+                "0e:49:61:0f:c3:11",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/mac",
+                "0e:49:61:0f:c3:11",
+            ),
+        ]);
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let client = Client::builder()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_http_client(http_client.clone())
+                    .with_time_source(time_source)
+                    .with_sleep_impl(sleep),
+            )
+            .metadata_cache_ttl(Duration::ZERO)
+            .build();
+        for _ in 0..2 {
+            assert_eq!("0e:49:61:0f:c3:11", client.mac().await.expect("success"));
+        }
+        http_client.assert_requests_match(&[]);
+    }
+
+    #[tokio::test]
+    async fn instance_tags() {
+        let http_client = StaticReplayClient::new(vec![
+            token(),
+            get(
+                "http://169.254.169.254/latest/meta-data/tags/instance",
+                "Name\nteam",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/tags/instance/Name",
+                "web-1",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/tags/instance/team",
+                "platform",
+            ),
+        ]);
+        let client = crate::imds::client::test::make_imds_client(&http_client);
+        let tags = client.instance_tags().await.expect("success");
+        assert_eq!(
+            HashMap::from([
+                ("Name".to_string(), "web-1".to_string()),
+                ("team".to_string(), "platform".to_string()),
+            ]),
+            tags
+        );
+        http_client.assert_requests_match(&[]);
+    }
+
+    #[tokio::test]
+    async fn network_interfaces_and_placement() {
+        let http_client = StaticReplayClient::new(vec![
+            token(),
+            get(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/",
+                "0e:49:61:0f:c3:11/\n",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/device-number",
+                "0",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/interface-id",
+                "eni-0f95d3625f5c521cc",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/subnet-id",
+                "subnet-0ac62554",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/vpc-id",
+                "vpc-d295a6a7",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/local-ipv4s",
+                "10.0.0.12\n10.0.0.13",
+            ),
+            not_found(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/public-ipv4s",
+            ),
+            not_found(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/ipv6s",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/network/interfaces/macs/0e:49:61:0f:c3:11/security-group-ids",
+                "sg-0a1b2c3d",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/placement/availability-zone",
+                "us-west-2b",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/placement/availability-zone-id",
+                "usw2-az2",
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/placement/region",
+                "us-west-2",
+            ),
+            not_found("http://169.254.169.254/latest/meta-data/placement/group-name"),
+            not_found("http://169.254.169.254/latest/meta-data/placement/partition-number"),
+            not_found("http://169.254.169.254/latest/meta-data/placement/host-id"),
+        ]);
+        let client = crate::imds::client::test::make_imds_client(&http_client);
+
+        let interfaces = client.network_interfaces().await.expect("success");
+        assert_eq!(1, interfaces.len());
+        let interface = &interfaces[0];
+        assert_eq!("0e:49:61:0f:c3:11", interface.mac());
+        assert_eq!(Some(0), interface.device_number());
+        assert_eq!(Some("eni-0f95d3625f5c521cc"), interface.interface_id());
+        assert_eq!(
+            &["10.0.0.12".to_string(), "10.0.0.13".to_string()],
+            interface.local_ipv4s()
+        );
+        assert!(interface.public_ipv4s().is_empty());
+        assert!(interface.ipv6s().is_empty());
+        assert_eq!(&["sg-0a1b2c3d".to_string()], interface.security_group_ids());
+
+        let placement = client.placement().await.expect("success");
+        assert_eq!("us-west-2b", placement.availability_zone());
+        assert_eq!(Some("usw2-az2"), placement.availability_zone_id());
+        assert_eq!("us-west-2", placement.region());
+        assert_eq!(None, placement.group_name());
+        assert_eq!(None, placement.partition_number());
+        http_client.assert_requests_match(&[]);
+    }
+
+    #[tokio::test]
+    async fn wait_for_interruption_notice() {
+        let http_client = StaticReplayClient::new(vec![
+            token(),
+            not_found("http://169.254.169.254/latest/meta-data/spot/instance-action"),
+            not_found("http://169.254.169.254/latest/meta-data/events/recommendations/rebalance"),
+            not_found("http://169.254.169.254/latest/meta-data/spot/instance-action"),
+            get(
+                "http://169.254.169.254/latest/meta-data/events/recommendations/rebalance",
+                r#"{"noticeTime": "2020-10-27T08:22:00Z"}"#,
+            ),
+            get(
+                "http://169.254.169.254/latest/meta-data/spot/instance-action",
+                r#"{"action": "terminate", "time": "2020-10-27T08:24:00Z"}"#,
+            ),
+        ]);
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let client = Client::builder()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_http_client(http_client.clone())
+                    .with_time_source(time_source)
+                    .with_sleep_impl(sleep.clone()),
+            )
+            .build();
+
+        let notice = client
+            .wait_for_interruption_notice(Duration::from_secs(5))
+            .await
+            .expect("success");
+        match notice {
+            InterruptionNotice::RebalanceRecommendation(recommendation) => assert_eq!(
+                UNIX_EPOCH + Duration::from_secs(1603786920),
+                recommendation.notice_time()
+            ),
+            other => panic!("unexpected notice: {:?}", other),
+        }
+        let polls = sleep
+            .logs()
+            .into_iter()
+            .filter(|d| *d == Duration::from_secs(5));
+        assert_eq!(1, polls.count());
+
+        // notices are never cached, and spot instance actions take precedence
+        let notice = client
+            .wait_for_interruption_notice(Duration::from_secs(5))
+            .await
+            .expect("success");
+        match notice {
+            InterruptionNotice::SpotInstanceAction(action) => {
+                assert_eq!("terminate", action.action());
+                assert_eq!(UNIX_EPOCH + Duration::from_secs(1603787040), action.time());
+            }
+            other => panic!("unexpected notice: {:?}", other),
+        }
+        http_client.assert_requests_match(&[]);
+    }
+}
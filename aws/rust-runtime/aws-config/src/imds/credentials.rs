@@ -235,16 +235,19 @@ impl ImdsCredentialsProvider {
                 secret_access_key,
                 session_token,
                 expiration,
-                ..
+                account_id,
             })) => {
                 let expiration = self.maybe_extend_expiration(expiration);
-                let creds = Credentials::new(
+                let mut creds = Credentials::new(
                     access_key_id,
                     secret_access_key,
                     Some(session_token.to_string()),
                     expiration.into(),
                     "IMDSv2",
                 );
+                if let Some(account_id) = account_id {
+                    creds = creds.with_account_id(account_id);
+                }
                 *self.last_retrieved_credentials.write().unwrap() = Some(creds.clone());
                 Ok(creds)
             }
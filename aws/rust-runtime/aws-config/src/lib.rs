@@ -103,10 +103,7 @@ pub use aws_types::{
 pub use loader::ConfigLoader;
 
 /// Types for configuring identity caching.
-pub mod identity {
-    pub use aws_smithy_runtime::client::identity::IdentityCache;
-    pub use aws_smithy_runtime::client::identity::LazyCacheBuilder;
-}
+pub mod identity;
 
 #[allow(dead_code)]
 const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -127,7 +124,9 @@ pub mod meta;
 pub mod profile;
 pub mod provider_config;
 pub mod retry;
+pub mod saml;
 mod sensitive_command;
+mod service_config;
 #[cfg(feature = "sso")]
 pub mod sso;
 pub(crate) mod standard_property;
@@ -156,12 +155,19 @@ pub async fn load_from_env() -> aws_types::SdkConfig {
 }
 
 mod loader {
+    use crate::default_provider::account_id_endpoint_mode::account_id_endpoint_mode_provider;
+    use crate::default_provider::disable_request_compression::disable_request_compression_provider;
+    use crate::default_provider::request_min_compression_size_bytes::request_min_compression_size_bytes_provider;
     use crate::default_provider::use_dual_stack::use_dual_stack_provider;
     use crate::default_provider::use_fips::use_fips_provider;
-    use crate::default_provider::{app_name, credentials, region, retry_config, timeout_config};
+    use crate::default_provider::{
+        app_name, credentials, region, retry_config, timeout_config, token,
+    };
     use crate::meta::region::ProvideRegion;
     use crate::profile::profile_file::ProfileFiles;
     use crate::provider_config::ProviderConfig;
+    use crate::service_config::EnvServiceConfig;
+    use aws_credential_types::provider::token::{ProvideToken, SharedTokenProvider};
     use aws_credential_types::provider::{ProvideCredentials, SharedCredentialsProvider};
     use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep, SharedAsyncSleep};
     use aws_smithy_async::time::{SharedTimeSource, TimeSource};
@@ -172,9 +178,11 @@ mod loader {
     use aws_smithy_types::timeout::TimeoutConfig;
     use aws_types::app_name::AppName;
     use aws_types::docs_for;
+    use aws_types::endpoint_config::AccountIdEndpointMode;
     use aws_types::os_shim_internal::{Env, Fs};
     use aws_types::sdk_config::SharedHttpClient;
     use aws_types::SdkConfig;
+    use std::sync::Arc;
 
     #[derive(Default, Debug)]
     enum CredentialsProviderOption {
@@ -198,6 +206,7 @@ mod loader {
         app_name: Option<AppName>,
         identity_cache: Option<SharedIdentityCache>,
         credentials_provider: CredentialsProviderOption,
+        token_provider: Option<SharedTokenProvider>,
         endpoint_url: Option<String>,
         region: Option<Box<dyn ProvideRegion>>,
         retry_config: Option<RetryConfig>,
@@ -209,6 +218,9 @@ mod loader {
         profile_files_override: Option<ProfileFiles>,
         use_fips: Option<bool>,
         use_dual_stack: Option<bool>,
+        account_id_endpoint_mode: Option<AccountIdEndpointMode>,
+        disable_request_compression: Option<bool>,
+        request_min_compression_size_bytes: Option<u32>,
         time_source: Option<SharedTimeSource>,
         env: Option<Env>,
         fs: Option<Fs>,
@@ -431,6 +443,27 @@ mod loader {
             self
         }
 
+        /// Override the bearer token provider used to build [`SdkConfig`](aws_types::SdkConfig).
+        ///
+        /// The token provider is used by services that authenticate requests with HTTP bearer auth.
+        /// When unset, the [default token provider chain](crate::default_provider::token) is used.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// # async fn create_config() {
+        /// use aws_credential_types::Token;
+        /// let config = aws_config::from_env()
+        ///     .token_provider(Token::new("example-token", None))
+        ///     .load()
+        ///     .await;
+        /// # }
+        /// ```
+        pub fn token_provider(mut self, token_provider: impl ProvideToken + 'static) -> Self {
+            self.token_provider = Some(SharedTokenProvider::new(token_provider));
+            self
+        }
+
         /// Override the name of the app used to build [`SdkConfig`](aws_types::SdkConfig).
         ///
         /// This _optional_ name is used to identify the application in the user agent that
@@ -528,6 +561,10 @@ mod loader {
         /// When this method is used, the [`Region`](aws_types::region::Region) is only used for
         /// signing; it is not used to route the request.
         ///
+        /// When this method isn't used, each service resolves its endpoint URL from the
+        /// `AWS_ENDPOINT_URL_<SERVICE>` and `AWS_ENDPOINT_URL` environment variables, then from the
+        /// `[services]` section referenced by the profile and the profile's `endpoint_url` key.
+        ///
         /// # Examples
         ///
         /// Use a static endpoint for all services
@@ -555,6 +592,36 @@ mod loader {
             self
         }
 
+        #[doc = docs_for!(account_id_endpoint_mode)]
+        ///
+        /// When this method isn't used, the value is loaded from the `AWS_ACCOUNT_ID_ENDPOINT_MODE`
+        /// environment variable, or the profile's `account_id_endpoint_mode` key.
+        pub fn account_id_endpoint_mode(mut self, mode: AccountIdEndpointMode) -> Self {
+            self.account_id_endpoint_mode = Some(mode);
+            self
+        }
+
+        #[doc = docs_for!(disable_request_compression)]
+        ///
+        /// When this method isn't used, the value is loaded from the `AWS_DISABLE_REQUEST_COMPRESSION`
+        /// environment variable, or the profile's `disable_request_compression` key.
+        pub fn disable_request_compression(mut self, disable_request_compression: bool) -> Self {
+            self.disable_request_compression = Some(disable_request_compression);
+            self
+        }
+
+        #[doc = docs_for!(request_min_compression_size_bytes)]
+        ///
+        /// When this method isn't used, the value is loaded from the `AWS_REQUEST_MIN_COMPRESSION_SIZE_BYTES`
+        /// environment variable, or the profile's `request_min_compression_size_bytes` key.
+        pub fn request_min_compression_size_bytes(
+            mut self,
+            request_min_compression_size_bytes: u32,
+        ) -> Self {
+            self.request_min_compression_size_bytes = Some(request_min_compression_size_bytes);
+            self
+        }
+
         /// Set configuration for all sub-loaders (credentials, region etc.)
         ///
         /// Update the `ProviderConfig` used for all nested loaders. This can be used to override
@@ -634,6 +701,29 @@ mod loader {
                 use_dual_stack_provider(&conf).await
             };
 
+            let account_id_endpoint_mode =
+                if let Some(account_id_endpoint_mode) = self.account_id_endpoint_mode {
+                    Some(account_id_endpoint_mode)
+                } else {
+                    account_id_endpoint_mode_provider(&conf).await
+                };
+
+            let disable_request_compression =
+                if let Some(disable_request_compression) = self.disable_request_compression {
+                    Some(disable_request_compression)
+                } else {
+                    disable_request_compression_provider(&conf).await
+                };
+
+            let request_min_compression_size_bytes =
+                if let Some(request_min_compression_size_bytes) =
+                    self.request_min_compression_size_bytes
+                {
+                    Some(request_min_compression_size_bytes)
+                } else {
+                    request_min_compression_size_bytes_provider(&conf).await
+                };
+
             let conf = conf
                 .with_use_fips(use_fips)
                 .with_use_dual_stack(use_dual_stack);
@@ -686,6 +776,18 @@ mod loader {
                 CredentialsProviderOption::ExplicitlyUnset => None,
             };
 
+            let token_provider = match self.token_provider {
+                Some(provider) => provider,
+                None => SharedTokenProvider::new(
+                    token::DefaultTokenChain::builder()
+                        .configure(conf.clone())
+                        .build()
+                        .await,
+                ),
+            };
+
+            let service_config = EnvServiceConfig::new(conf.env(), conf.profile().await.cloned());
+
             let mut builder = SdkConfig::builder()
                 .region(region)
                 .retry_config(retry_config)
@@ -696,10 +798,15 @@ mod loader {
             builder.set_app_name(app_name);
             builder.set_identity_cache(self.identity_cache);
             builder.set_credentials_provider(credentials_provider);
+            builder.set_token_provider(Some(token_provider));
             builder.set_sleep_impl(sleep_impl);
             builder.set_endpoint_url(self.endpoint_url);
             builder.set_use_fips(use_fips);
             builder.set_use_dual_stack(use_dual_stack);
+            builder.set_account_id_endpoint_mode(account_id_endpoint_mode);
+            builder.set_disable_request_compression(disable_request_compression);
+            builder.set_request_min_compression_size_bytes(request_min_compression_size_bytes);
+            builder.set_service_config(Some(Arc::new(service_config)));
             builder.build()
         }
     }
@@ -726,7 +833,9 @@ mod loader {
         use aws_smithy_async::rt::sleep::TokioSleep;
         use aws_smithy_runtime::client::http::test_util::{infallible_client_fn, NeverClient};
         use aws_types::app_name::AppName;
+        use aws_types::endpoint_config::AccountIdEndpointMode;
         use aws_types::os_shim_internal::{Env, Fs};
+        use aws_types::service_config::ServiceConfigKey;
         use std::sync::atomic::{AtomicUsize, Ordering};
         use std::sync::Arc;
         use tracing_test::traced_test;
@@ -806,6 +915,83 @@ mod loader {
             assert_eq!(None, conf.use_dual_stack());
         }
 
+        #[tokio::test]
+        async fn load_account_id_endpoint_mode() {
+            let conf = base_conf().load().await;
+            assert_eq!(None, conf.account_id_endpoint_mode());
+
+            let env = Env::from_slice(&[("AWS_ACCOUNT_ID_ENDPOINT_MODE", "required")]);
+            let conf = base_conf().env(env.clone()).load().await;
+            assert_eq!(
+                Some(&AccountIdEndpointMode::Required),
+                conf.account_id_endpoint_mode()
+            );
+
+            let conf = base_conf()
+                .env(env)
+                .account_id_endpoint_mode(AccountIdEndpointMode::Disabled)
+                .load()
+                .await;
+            assert_eq!(
+                Some(&AccountIdEndpointMode::Disabled),
+                conf.account_id_endpoint_mode()
+            );
+        }
+
+        #[tokio::test]
+        async fn load_request_compression_settings() {
+            let env = Env::from_slice(&[
+                ("AWS_DISABLE_REQUEST_COMPRESSION", "true"),
+                ("AWS_REQUEST_MIN_COMPRESSION_SIZE_BYTES", "128"),
+            ]);
+            let conf = base_conf().env(env.clone()).load().await;
+            assert_eq!(Some(true), conf.disable_request_compression());
+            assert_eq!(Some(128), conf.request_min_compression_size_bytes());
+
+            let conf = base_conf()
+                .env(env)
+                .disable_request_compression(false)
+                .request_min_compression_size_bytes(256)
+                .load()
+                .await;
+            assert_eq!(Some(false), conf.disable_request_compression());
+            assert_eq!(Some(256), conf.request_min_compression_size_bytes());
+        }
+
+        #[tokio::test]
+        async fn load_service_config() {
+            let env = Env::from_slice(&[("AWS_ENDPOINT_URL_S3", "http://s3-env")]);
+            let fs = Fs::from_slice(&[(
+                "test_config",
+                "[default]\nservices = local\n[services local]\ndynamodb =\n  endpoint_url = http://ddb-profile",
+            )]);
+            let conf = base_conf()
+                .env(env)
+                .fs(fs)
+                .profile_files(
+                    ProfileFiles::builder()
+                        .with_file(ProfileFileKind::Config, "test_config")
+                        .build(),
+                )
+                .load()
+                .await;
+            let endpoint_url = |service_id| {
+                conf.service_config()
+                    .expect("service config is always loaded")
+                    .load_config(ServiceConfigKey::new(
+                        service_id,
+                        "AWS_ENDPOINT_URL",
+                        "endpoint_url",
+                    ))
+            };
+            assert_eq!(Some("http://s3-env".to_string()), endpoint_url("S3"));
+            assert_eq!(
+                Some("http://ddb-profile".to_string()),
+                endpoint_url("DynamoDB")
+            );
+            assert_eq!(None, endpoint_url("STS"));
+        }
+
         #[tokio::test]
         async fn app_name() {
             let app_name = AppName::new("my-app-name").unwrap();
@@ -7,8 +7,15 @@ use aws_credential_types::{
     provider::{self, error::CredentialsError, future, ProvideCredentials},
     Credentials,
 };
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
 use aws_smithy_types::error::display::DisplayErrorContext;
 use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
 use tracing::Instrument;
 
 /// Credentials provider that checks a series of inner providers
@@ -21,6 +28,16 @@ use tracing::Instrument;
 ///   the next provider will be checked.
 /// * Finally, if a provider returns any other error condition, an error will be returned immediately.
 ///
+/// When no provider loads credentials, the returned `CredentialsNotLoaded` error has a
+/// [`ChainDiagnostics`] source that describes why each provider was skipped.
+///
+/// ## Race mode
+/// Providers that probe the network, like IMDS or ECS, can take several seconds to give up when
+/// they aren't available. With [`race`](CredentialsProviderChain::race) enabled, all providers are
+/// started at the same time. The selection is the same as when evaluating in order: the result of
+/// the first provider that doesn't return `CredentialsNotLoaded` is used, even if a later provider
+/// finished first. Once a result is selected, the remaining providers are cancelled.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -36,6 +53,8 @@ use tracing::Instrument;
 #[derive(Debug)]
 pub struct CredentialsProviderChain {
     providers: Vec<(Cow<'static, str>, Box<dyn ProvideCredentials>)>,
+    race: bool,
+    time_source: SharedTimeSource,
 }
 
 impl CredentialsProviderChain {
@@ -46,6 +65,8 @@ impl CredentialsProviderChain {
     ) -> Self {
         CredentialsProviderChain {
             providers: vec![(name.into(), Box::new(provider))],
+            race: false,
+            time_source: SharedTimeSource::default(),
         }
     }
 
@@ -59,6 +80,37 @@ impl CredentialsProviderChain {
         self
     }
 
+    /// Start all providers at the same time instead of one after another
+    ///
+    /// Providers keep their priority: the result of a provider is only used once every provider
+    /// before it has returned `CredentialsNotLoaded`. This is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn example() {
+    /// use aws_config::meta::credentials::CredentialsProviderChain;
+    /// use aws_config::ecs::EcsCredentialsProvider;
+    /// use aws_config::imds::credentials::ImdsCredentialsProvider;
+    ///
+    /// let provider = CredentialsProviderChain::first_try("EcsContainer", EcsCredentialsProvider::builder().build())
+    ///     .or_else("Ec2InstanceMetadata", ImdsCredentialsProvider::builder().build())
+    ///     .race(true);
+    /// # }
+    /// ```
+    pub fn race(mut self, race: bool) -> Self {
+        self.race = race;
+        self
+    }
+
+    /// Override the time source used to measure how long each provider took
+    ///
+    /// When unset, the system time source is used.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = SharedTimeSource::new(time_source);
+        self
+    }
+
     /// Add a fallback to the default provider chain
     #[cfg(feature = "rustls")]
     pub async fn or_default_provider(self) -> Self {
@@ -77,35 +129,112 @@ impl CredentialsProviderChain {
         )
     }
 
+    fn elapsed_since(&self, start: SystemTime) -> Duration {
+        self.time_source
+            .now()
+            .duration_since(start)
+            .unwrap_or_default()
+    }
+
     async fn credentials(&self) -> provider::Result {
-        for (name, provider) in &self.providers {
+        let mut diagnostics = ChainDiagnostics::new(&self.providers);
+        for (index, (name, provider)) in self.providers.iter().enumerate() {
             let span = tracing::debug_span!("load_credentials", provider = %name);
-            match provider.provide_credentials().instrument(span).await {
-                Ok(credentials) => {
-                    tracing::debug!(provider = %name, "loaded credentials");
-                    return Ok(credentials);
-                }
-                Err(err @ CredentialsError::CredentialsNotLoaded(_)) => {
-                    tracing::debug!(provider = %name, context = %DisplayErrorContext(&err), "provider in chain did not provide credentials");
+            let start = self.time_source.now();
+            let result = provider.provide_credentials().instrument(span).await;
+            diagnostics.record(index, self.elapsed_since(start), &result);
+            if !matches!(result, Err(CredentialsError::CredentialsNotLoaded(_))) {
+                return select(name, result, &diagnostics);
+            }
+        }
+        Err(exhausted(diagnostics))
+    }
+
+    async fn race_credentials(&self) -> provider::Result {
+        let mut diagnostics = ChainDiagnostics::new(&self.providers);
+        let start = self.time_source.now();
+        let mut pending: Vec<_> = self
+            .providers
+            .iter()
+            .map(|(name, provider)| {
+                let span = tracing::debug_span!("load_credentials", provider = %name);
+                Some(provider.provide_credentials().instrument(span))
+            })
+            .collect();
+        let mut results: Vec<Option<provider::Result>> = pending.iter().map(|_| None).collect();
+
+        let selected = std::future::poll_fn(|cx| {
+            for (index, slot) in pending.iter_mut().enumerate() {
+                if let Some(future) = slot {
+                    if let Poll::Ready(result) = Pin::new(future).poll(cx) {
+                        diagnostics.record(index, self.elapsed_since(start), &result);
+                        results[index] = Some(result);
+                        *slot = None;
+                    }
                 }
-                Err(err) => {
-                    tracing::warn!(provider = %name, error = %DisplayErrorContext(&err), "provider failed to provide credentials");
-                    return Err(err);
+            }
+            // Walk the results in priority order. A provider that is still running blocks the
+            // selection of every provider after it.
+            for (index, result) in results.iter().enumerate() {
+                match result {
+                    None => return Poll::Pending,
+                    Some(Err(CredentialsError::CredentialsNotLoaded(_))) => {}
+                    Some(_) => return Poll::Ready(Some(index)),
                 }
             }
+            Poll::Ready(None)
+        })
+        .await;
+
+        for (index, slot) in pending.iter().enumerate() {
+            if slot.is_some() {
+                diagnostics.cancel(index, self.elapsed_since(start));
+            }
+        }
+        drop(pending);
+        match selected {
+            Some(index) => {
+                let result = results[index]
+                    .take()
+                    .expect("selected provider has a result");
+                select(&self.providers[index].0, result, &diagnostics)
+            }
+            None => Err(exhausted(diagnostics)),
         }
-        Err(CredentialsError::not_loaded(
-            "no providers in chain provided credentials",
-        ))
     }
 }
 
+fn select(
+    name: &str,
+    result: provider::Result,
+    diagnostics: &ChainDiagnostics,
+) -> provider::Result {
+    match &result {
+        Ok(_) => {
+            tracing::debug!(provider = %name, diagnostics = %diagnostics, "loaded credentials")
+        }
+        Err(err) => {
+            tracing::warn!(provider = %name, error = %DisplayErrorContext(err), diagnostics = %diagnostics, "provider failed to provide credentials")
+        }
+    }
+    result
+}
+
+fn exhausted(diagnostics: ChainDiagnostics) -> CredentialsError {
+    tracing::debug!(diagnostics = %diagnostics, "no providers in chain provided credentials");
+    CredentialsError::not_loaded(diagnostics)
+}
+
 impl ProvideCredentials for CredentialsProviderChain {
     fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'_>
     where
         Self: 'a,
     {
-        future::ProvideCredentials::new(self.credentials())
+        if self.race {
+            future::ProvideCredentials::new(self.race_credentials())
+        } else {
+            future::ProvideCredentials::new(self.credentials())
+        }
     }
 
     fn fallback_on_interrupt(&self) -> Option<Credentials> {
@@ -119,18 +248,174 @@ impl ProvideCredentials for CredentialsProviderChain {
     }
 }
 
+/// Report of how each provider in a [`CredentialsProviderChain`] was evaluated
+///
+/// When no provider in the chain loads credentials, this is the source of the returned
+/// `CredentialsNotLoaded` error:
+///
+/// ```no_run
+/// use aws_config::meta::credentials::ChainDiagnostics;
+/// use aws_credential_types::provider::error::CredentialsError;
+/// use std::error::Error;
+///
+/// fn print_diagnostics(err: &CredentialsError) {
+///     if let Some(diagnostics) = err.source().and_then(|source| source.downcast_ref::<ChainDiagnostics>()) {
+///         for provider in diagnostics.providers() {
+///             println!("{}: {:?} after {:?}", provider.name(), provider.outcome(), provider.elapsed());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChainDiagnostics {
+    providers: Vec<ProviderDiagnostics>,
+}
+
+impl ChainDiagnostics {
+    fn new(providers: &[(Cow<'static, str>, Box<dyn ProvideCredentials>)]) -> Self {
+        Self {
+            providers: providers
+                .iter()
+                .map(|(name, _)| ProviderDiagnostics {
+                    name: name.clone(),
+                    elapsed: None,
+                    outcome: ProviderOutcome::NotStarted,
+                })
+                .collect(),
+        }
+    }
+
+    fn record(&mut self, index: usize, elapsed: Duration, result: &provider::Result) {
+        let provider = &mut self.providers[index];
+        provider.elapsed = Some(elapsed);
+        provider.outcome = match result {
+            Ok(_) => ProviderOutcome::Loaded,
+            Err(err @ CredentialsError::CredentialsNotLoaded(_)) => ProviderOutcome::NotLoaded {
+                reason: DisplayErrorContext(err).to_string(),
+            },
+            Err(err) => ProviderOutcome::Failed {
+                reason: DisplayErrorContext(err).to_string(),
+            },
+        };
+    }
+
+    fn cancel(&mut self, index: usize, elapsed: Duration) {
+        let provider = &mut self.providers[index];
+        provider.elapsed = Some(elapsed);
+        provider.outcome = ProviderOutcome::Cancelled;
+    }
+
+    /// Returns the diagnostics of each provider, in the order that they were added to the chain
+    pub fn providers(&self) -> &[ProviderDiagnostics] {
+        &self.providers
+    }
+}
+
+impl fmt::Display for ChainDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no providers in chain provided credentials")?;
+        for (index, provider) in self.providers.iter().enumerate() {
+            let separator = if index == 0 { " (" } else { "; " };
+            write!(f, "{}{}", separator, provider)?;
+        }
+        if !self.providers.is_empty() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ChainDiagnostics {}
+
+/// How a single provider in a [`CredentialsProviderChain`] was evaluated
+#[derive(Clone, Debug)]
+pub struct ProviderDiagnostics {
+    name: Cow<'static, str>,
+    elapsed: Option<Duration>,
+    outcome: ProviderOutcome,
+}
+
+impl ProviderDiagnostics {
+    /// Returns the name that the provider was added to the chain with
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns how long the provider ran for, or `None` if it was never started
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+    /// Returns the outcome of the provider
+    pub fn outcome(&self) -> &ProviderOutcome {
+        &self.outcome
+    }
+}
+
+impl fmt::Display for ProviderDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.name)?;
+        match &self.outcome {
+            ProviderOutcome::Loaded => write!(f, "loaded credentials")?,
+            ProviderOutcome::NotLoaded { reason } => write!(f, "not loaded: {}", reason)?,
+            ProviderOutcome::Failed { reason } => write!(f, "failed: {}", reason)?,
+            ProviderOutcome::Cancelled => write!(f, "cancelled")?,
+            ProviderOutcome::NotStarted => return write!(f, "not started"),
+        }
+        match self.elapsed {
+            Some(elapsed) => write!(f, " after {:?}", elapsed),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The outcome of a single provider in a [`CredentialsProviderChain`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProviderOutcome {
+    /// The provider loaded credentials
+    Loaded,
+    /// The provider was skipped because it returned `CredentialsNotLoaded`
+    #[non_exhaustive]
+    NotLoaded {
+        /// Why the provider did not load credentials
+        reason: String,
+    },
+    /// The provider returned an error other than `CredentialsNotLoaded`
+    #[non_exhaustive]
+    Failed {
+        /// The error that the provider returned
+        reason: String,
+    },
+    /// The provider was still running when the result of a higher priority provider was selected
+    Cancelled,
+    /// The chain finished before the provider was started
+    NotStarted,
+}
+
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
 
     use aws_credential_types::{
         credential_fn::provide_credentials_fn,
-        provider::{error::CredentialsError, future, ProvideCredentials},
+        provider::{
+            error::CredentialsError, future, ProvideCredentials, SharedCredentialsProvider,
+        },
         Credentials,
     };
     use aws_smithy_async::future::timeout::Timeout;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_runtime::client::identity::IdentityCache;
+    use aws_smithy_runtime_api::client::identity::{ResolveCachedIdentity, SharedIdentityResolver};
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::config_bag::ConfigBag;
 
-    use crate::meta::credentials::CredentialsProviderChain;
+    use crate::meta::credentials::{ChainDiagnostics, CredentialsProviderChain, ProviderOutcome};
 
     #[derive(Debug)]
     struct FallbackCredentials(Credentials);
@@ -151,6 +436,33 @@ mod tests {
         }
     }
 
+    /// A provider that advances `time` by `delay` and then returns `result`
+    fn slow_provider(
+        time: &ManualTimeSource,
+        delay: Duration,
+        result: fn() -> aws_credential_types::provider::Result,
+    ) -> impl ProvideCredentials {
+        let time = time.clone();
+        provide_credentials_fn(move || {
+            time.advance(delay);
+            async move { result() }
+        })
+    }
+
+    fn creds(access_key_id: &str) -> Credentials {
+        Credentials::new(access_key_id, "secret", None, None, "test")
+    }
+
+    fn diagnostics(err: &CredentialsError) -> &ChainDiagnostics {
+        assert!(
+            matches!(err, CredentialsError::CredentialsNotLoaded(_)),
+            "unexpected error: {err:?}"
+        );
+        err.source()
+            .and_then(|source| source.downcast_ref::<ChainDiagnostics>())
+            .expect("chain diagnostics")
+    }
+
     #[tokio::test]
     async fn fallback_credentials_should_be_returned_from_provider2_on_timeout_while_provider2_was_providing_credentials(
     ) {
@@ -216,4 +528,211 @@ mod tests {
             },
         };
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn race_starts_providers_concurrently() {
+        let chain = CredentialsProviderChain::first_try(
+            "provider1",
+            provide_credentials_fn(|| async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Err(CredentialsError::not_loaded("not configured"))
+            }),
+        )
+        .or_else(
+            "provider2",
+            provide_credentials_fn(|| async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(creds("provider2"))
+            }),
+        )
+        .race(true);
+
+        let start = tokio::time::Instant::now();
+        let credentials = chain.provide_credentials().await.expect("success");
+        assert_eq!("provider2", credentials.access_key_id());
+        assert_eq!(Duration::from_secs(1), start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn race_preserves_priority() {
+        let chain = CredentialsProviderChain::first_try(
+            "provider1",
+            provide_credentials_fn(|| async {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                Ok(creds("provider1"))
+            }),
+        )
+        .or_else(
+            "provider2",
+            provide_credentials_fn(|| async { Ok(creds("provider2")) }),
+        )
+        .race(true);
+        let credentials = chain.provide_credentials().await.expect("success");
+        assert_eq!("provider1", credentials.access_key_id());
+
+        let chain = CredentialsProviderChain::first_try(
+            "provider1",
+            provide_credentials_fn(|| async {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                Err(CredentialsError::invalid_configuration("bad config"))
+            }),
+        )
+        .or_else(
+            "provider2",
+            provide_credentials_fn(|| async { Ok(creds("provider2")) }),
+        )
+        .race(true);
+        let err = chain
+            .provide_credentials()
+            .await
+            .expect_err("provider1 failed");
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn race_cancels_lower_priority_providers() {
+        let chain = CredentialsProviderChain::first_try(
+            "provider1",
+            provide_credentials_fn(|| async { Ok(creds("provider1")) }),
+        )
+        .or_else(
+            "provider2",
+            provide_credentials_fn(|| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(creds("provider2"))
+            }),
+        )
+        .race(true);
+
+        let start = tokio::time::Instant::now();
+        let credentials = chain.provide_credentials().await.expect("success");
+        assert_eq!("provider1", credentials.access_key_id());
+        assert_eq!(Duration::ZERO, start.elapsed());
+    }
+
+    #[tokio::test]
+    async fn diagnostics_report_why_providers_were_skipped() {
+        for race in [false, true] {
+            let time = ManualTimeSource::new(UNIX_EPOCH);
+            let chain = CredentialsProviderChain::first_try(
+                "Environment",
+                slow_provider(&time, Duration::ZERO, || {
+                    Err(CredentialsError::not_loaded("environment variable not set"))
+                }),
+            )
+            .or_else(
+                "Ec2InstanceMetadata",
+                slow_provider(&time, Duration::from_secs(1), || {
+                    Err(CredentialsError::not_loaded("IMDS is disabled"))
+                }),
+            )
+            .time_source(time.clone())
+            .race(race);
+
+            let err = chain
+                .provide_credentials()
+                .await
+                .expect_err("no credentials");
+            let diagnostics = diagnostics(&err);
+            let providers = diagnostics.providers();
+            assert_eq!(2, providers.len());
+            assert_eq!("Environment", providers[0].name());
+            assert_eq!("Ec2InstanceMetadata", providers[1].name());
+            for provider in providers {
+                assert!(
+                    matches!(provider.outcome(), ProviderOutcome::NotLoaded { .. }),
+                    "unexpected outcome: {provider:?}"
+                );
+            }
+            assert!(
+                matches!(providers[1].outcome(), ProviderOutcome::NotLoaded { reason } if reason.contains("IMDS is disabled")),
+                "unexpected outcome: {:?}",
+                providers[1]
+            );
+            if !race {
+                // Providers that are evaluated in order are each timed separately
+                assert_eq!(Some(Duration::ZERO), providers[0].elapsed());
+                assert_eq!(Some(Duration::from_secs(1)), providers[1].elapsed());
+            }
+
+            let message = diagnostics.to_string();
+            assert!(
+                message.starts_with("no providers in chain provided credentials"),
+                "{message}"
+            );
+            assert!(
+                message.contains("environment variable not set"),
+                "{message}"
+            );
+            assert!(message.contains("IMDS is disabled"), "{message}");
+        }
+    }
+
+    #[tokio::test]
+    async fn lazy_cache_reloads_through_race_chain() {
+        let time = ManualTimeSource::new(UNIX_EPOCH);
+        let loads = Arc::new(AtomicUsize::new(0));
+        let chain = CredentialsProviderChain::first_try(
+            "Environment",
+            provide_credentials_fn(|| async {
+                Err(CredentialsError::not_loaded("environment variable not set"))
+            }),
+        )
+        .or_else("Ec2InstanceMetadata", {
+            let (time, loads) = (time.clone(), loads.clone());
+            provide_credentials_fn(move || {
+                let expiry = time.advance(Duration::from_secs(1)) + Duration::from_secs(1000);
+                let load = loads.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match load {
+                        0 | 1 => Ok(Credentials::new(
+                            "akid",
+                            "secret",
+                            None,
+                            Some(expiry),
+                            "test",
+                        )),
+                        _ => Err(CredentialsError::not_loaded("IMDS is disabled")),
+                    }
+                }
+            })
+        })
+        .time_source(time.clone())
+        .race(true);
+
+        let cache = IdentityCache::lazy()
+            .time_source(time.clone())
+            .sleep_impl(TokioSleep::new())
+            .build();
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time.clone()))
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap();
+        let config_bag = ConfigBag::base();
+        let resolver = SharedIdentityResolver::new(SharedCredentialsProvider::new(chain));
+        let resolve = || cache.resolve_cached_identity(resolver.clone(), &components, &config_bag);
+
+        resolve().await.expect("loaded");
+        resolve().await.expect("cached");
+        assert_eq!(1, loads.load(Ordering::SeqCst));
+
+        // Once the credentials expire, the chain is raced again
+        time.advance(Duration::from_secs(1000));
+        resolve().await.expect("reloaded");
+        assert_eq!(2, loads.load(Ordering::SeqCst));
+
+        // The diagnostics are still available after passing through the cache
+        time.advance(Duration::from_secs(1000));
+        let err = resolve().await.expect_err("no credentials");
+        let err = err
+            .downcast_ref::<CredentialsError>()
+            .expect("credentials error");
+        let providers = diagnostics(err).providers();
+        assert_eq!(Some(Duration::from_secs(1)), providers[1].elapsed());
+        assert_eq!(3, loads.load(Ordering::SeqCst));
+    }
 }
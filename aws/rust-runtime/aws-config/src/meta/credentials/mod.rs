@@ -6,4 +6,4 @@
 //! Credential providers that augment an existing credentials providers to add functionality
 
 mod chain;
-pub use chain::CredentialsProviderChain;
+pub use chain::{ChainDiagnostics, CredentialsProviderChain, ProviderDiagnostics, ProviderOutcome};
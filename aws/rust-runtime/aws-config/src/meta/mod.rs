@@ -7,3 +7,4 @@
 
 pub mod credentials;
 pub mod region;
+pub mod token;
@@ -0,0 +1,171 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Token providers that augment existing token providers to add functionality
+
+use aws_credential_types::provider::error::TokenError;
+use aws_credential_types::provider::future;
+use aws_credential_types::provider::token::{self, ProvideToken};
+use aws_smithy_types::error::display::DisplayErrorContext;
+use std::borrow::Cow;
+use tracing::Instrument;
+
+/// Token provider that checks a series of inner providers
+///
+/// Each provider will be evaluated in order:
+/// * If a provider returns a valid [`Token`](aws_credential_types::Token) it will be returned immediately.
+///   No other token providers will be used.
+/// * Otherwise, if a provider returns
+///   [`TokenError::TokenNotLoaded`](aws_credential_types::provider::error::TokenError::TokenNotLoaded),
+///   the next provider will be checked.
+/// * Finally, if a provider returns any other error condition, an error will be returned immediately.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn example() {
+/// use aws_config::meta::token::TokenProviderChain;
+/// use aws_config::environment::token::EnvironmentVariableTokenProvider;
+/// use aws_config::profile::token::ProfileFileTokenProvider;
+///
+/// let provider = TokenProviderChain::first_try("Environment", EnvironmentVariableTokenProvider::new())
+///     .or_else("Profile", ProfileFileTokenProvider::builder().build());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TokenProviderChain {
+    providers: Vec<(Cow<'static, str>, Box<dyn ProvideToken>)>,
+}
+
+impl TokenProviderChain {
+    /// Create a `TokenProviderChain` that begins by evaluating this provider
+    pub fn first_try(
+        name: impl Into<Cow<'static, str>>,
+        provider: impl ProvideToken + 'static,
+    ) -> Self {
+        TokenProviderChain {
+            providers: vec![(name.into(), Box::new(provider))],
+        }
+    }
+
+    /// Add a fallback provider to the token provider chain
+    pub fn or_else(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        provider: impl ProvideToken + 'static,
+    ) -> Self {
+        self.providers.push((name.into(), Box::new(provider)));
+        self
+    }
+
+    /// Add a fallback to the default provider chain
+    pub async fn or_default_provider(self) -> Self {
+        self.or_else(
+            "DefaultProviderChain",
+            crate::default_provider::token::default_provider().await,
+        )
+    }
+
+    /// Creates a token provider chain that starts with the default provider
+    pub async fn default_provider() -> Self {
+        Self::first_try(
+            "DefaultProviderChain",
+            crate::default_provider::token::default_provider().await,
+        )
+    }
+
+    async fn token(&self) -> token::Result {
+        for (name, provider) in &self.providers {
+            let span = tracing::debug_span!("load_token", provider = %name);
+            match provider.provide_token().instrument(span).await {
+                Ok(token) => {
+                    tracing::debug!(provider = %name, "loaded token");
+                    return Ok(token);
+                }
+                Err(err @ TokenError::TokenNotLoaded(_)) => {
+                    tracing::debug!(provider = %name, context = %DisplayErrorContext(&err), "provider in chain did not provide a token");
+                }
+                Err(err) => {
+                    tracing::warn!(provider = %name, error = %DisplayErrorContext(&err), "provider failed to provide a token");
+                    return Err(err);
+                }
+            }
+        }
+        Err(TokenError::not_loaded(
+            "no providers in chain provided a token",
+        ))
+    }
+}
+
+impl ProvideToken for TokenProviderChain {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideToken::new(self.token())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::meta::token::TokenProviderChain;
+    use aws_credential_types::provider::error::TokenError;
+    use aws_credential_types::provider::future;
+    use aws_credential_types::provider::token::ProvideToken;
+    use aws_credential_types::Token;
+
+    #[derive(Debug)]
+    struct FailingProvider(fn() -> TokenError);
+
+    impl ProvideToken for FailingProvider {
+        fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+        where
+            Self: 'a,
+        {
+            future::ProvideToken::ready(Err((self.0)()))
+        }
+    }
+
+    #[tokio::test]
+    async fn skips_providers_that_did_not_load_a_token() {
+        let chain = TokenProviderChain::first_try(
+            "provider1",
+            FailingProvider(|| TokenError::not_loaded("nothing here")),
+        )
+        .or_else("provider2", Token::new("token2", None));
+
+        let token = chain.provide_token().await.expect("success");
+        assert_eq!("token2", token.token());
+    }
+
+    #[tokio::test]
+    async fn stops_at_provider_errors() {
+        let chain = TokenProviderChain::first_try(
+            "provider1",
+            FailingProvider(|| TokenError::provider_error("broken")),
+        )
+        .or_else("provider2", Token::new("token2", None));
+
+        let err = chain.provide_token().await.expect_err("failure");
+        assert!(
+            matches!(err, TokenError::ProviderError(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn no_token_loaded() {
+        let chain = TokenProviderChain::first_try(
+            "provider1",
+            FailingProvider(|| TokenError::not_loaded("nothing here")),
+        );
+
+        let err = chain.provide_token().await.expect_err("failure");
+        assert!(
+            matches!(err, TokenError::TokenNotLoaded(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+}
@@ -124,6 +124,16 @@ impl ProvideCredentials for ProfileFileCredentialsProvider {
 ///
 /// An external process can be used to provide credentials.
 ///
+/// ### Credentials from a federated SAML assertion
+/// ```ini
+/// [default]
+/// role_arn = arn:aws:iam::123456789:role/RoleA
+/// saml_assertion_process = /opt/bin/saml-login --username helen
+/// ```
+///
+/// The assertion can also be read from a file with `saml_assertion_file`. When the assertion
+/// grants a single role, `role_arn` may be omitted. See [`saml`](crate::saml) for more details.
+///
 /// ### Loading Credentials from SSO
 /// ```ini
 /// [default]
@@ -134,6 +144,21 @@ impl ProvideCredentials for ProfileFileCredentialsProvider {
 /// region = us-west-2
 /// ```
 ///
+/// The IAM Identity Center configuration can also live in an `[sso-session]` section that the
+/// profile references. The cached SSO token is then looked up by session name, and refreshed
+/// when it's about to expire:
+/// ```ini
+/// [default]
+/// sso_session = my-sso
+/// sso_account_id = 123456789011
+/// sso_role_name = readOnly
+/// region = us-west-2
+///
+/// [sso-session my-sso]
+/// sso_start_url = https://example.com/start
+/// sso_region = us-east-2
+/// ```
+///
 /// SSO can also be used as a source profile for assume role chains.
 ///
 #[doc = include_str!("location_of_profile_files.md")]
@@ -250,6 +275,22 @@ pub enum ProfileFileError {
         /// Error message
         message: Cow<'static, str>,
     },
+    /// The profile referred to an `[sso-session]` section that was not defined
+    #[non_exhaustive]
+    MissingSsoSession {
+        /// The name of the profile
+        profile: String,
+        /// The name of the missing SSO session
+        sso_session: String,
+    },
+    /// The `[sso-session]` section referenced by a profile was invalid
+    #[non_exhaustive]
+    InvalidSsoSession {
+        /// The name of the SSO session
+        sso_session: String,
+        /// Error message
+        message: Cow<'static, str>,
+    },
     /// The profile referred to `credential_source` that was not defined
     #[non_exhaustive]
     UnknownProvider {
@@ -306,6 +347,18 @@ impl Display for ProfileFileError {
             ProfileFileError::MissingProfile { profile, message } => {
                 write!(f, "profile `{}` was not defined: {}", profile, message)
             }
+            ProfileFileError::MissingSsoSession {
+                profile,
+                sso_session,
+            } => write!(
+                f,
+                "sso-session `{}` referenced by profile `{}` was not defined",
+                sso_session, profile
+            ),
+            ProfileFileError::InvalidSsoSession {
+                sso_session,
+                message,
+            } => write!(f, "invalid sso-session `{}`: {}", sso_session, message),
             ProfileFileError::UnknownProvider { name } => write!(
                 f,
                 "profile referenced `{}` provider but that provider is not supported",
@@ -497,4 +550,8 @@ mod test {
     make_test!(credential_process_failure);
     #[cfg(feature = "credentials-process")]
     make_test!(credential_process_invalid);
+    make_test!(saml_assertion_file);
+    make_test!(saml_multiple_roles);
+    #[cfg(feature = "credentials-process")]
+    make_test!(saml_assertion_process);
 }
@@ -3,11 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use super::repr::{self, BaseProvider};
+use super::repr::{self, BaseProvider, SamlAssertionSource};
 #[cfg(feature = "credentials-process")]
 use crate::credential_process::CredentialProcessProvider;
 use crate::profile::credentials::ProfileFileError;
 use crate::provider_config::ProviderConfig;
+use crate::saml::assertion::FileSamlAssertionProvider;
+use crate::saml::SamlCredentialsProvider;
 use crate::sts;
 use crate::web_identity_token::{StaticConfiguration, WebIdentityTokenCredentialsProvider};
 use aws_credential_types::provider::{
@@ -130,6 +132,7 @@ impl ProviderChain {
                 sso_region,
                 sso_role_name,
                 sso_start_url,
+                sso_session_name,
             } => {
                 #[cfg(feature = "sso")]
                 {
@@ -141,8 +144,7 @@ impl ProviderChain {
                         role_name: sso_role_name.to_string(),
                         start_url: sso_start_url.to_string(),
                         region: Region::new(sso_region.to_string()),
-                        // TODO(https://github.com/awslabs/aws-sdk-rust/issues/703): Implement sso_session_name profile property
-                        session_name: None,
+                        session_name: sso_session_name.map(|name| name.to_string()),
                     };
                     Arc::new(SsoCredentialsProvider::new(provider_config, sso_config))
                 }
@@ -154,6 +156,44 @@ impl ProviderChain {
                     })?
                 }
             }
+            BaseProvider::Saml {
+                role_arn,
+                principal_arn,
+                assertion_source,
+            } => {
+                let mut builder = match assertion_source {
+                    SamlAssertionSource::File(path) => SamlCredentialsProvider::builder(
+                        FileSamlAssertionProvider::new(*path).with_fs(provider_config.fs()),
+                    ),
+                    SamlAssertionSource::Process(_process) => {
+                        #[cfg(feature = "credentials-process")]
+                        {
+                            use crate::saml::assertion::ProcessSamlAssertionProvider;
+                            SamlCredentialsProvider::builder(ProcessSamlAssertionProvider::new(
+                                _process.unredacted(),
+                            ))
+                        }
+                        #[cfg(not(feature = "credentials-process"))]
+                        {
+                            Err(ProfileFileError::FeatureNotEnabled {
+                                feature: "credentials-process".into(),
+                                message: Some(
+                                    "In order to spawn a subprocess, the `credentials-process` feature must be enabled."
+                                        .into(),
+                                ),
+                            })?
+                        }
+                    }
+                }
+                .configure(provider_config);
+                if let Some(role_arn) = role_arn {
+                    builder = builder.role_arn(*role_arn);
+                }
+                if let Some(principal_arn) = principal_arn {
+                    builder = builder.principal_arn(*principal_arn);
+                }
+                Arc::new(builder.build())
+            }
         };
         tracing::info!(base = ?repr.base(), "first credentials will be loaded from {:?}", repr.base());
         let chain = repr
@@ -13,7 +13,7 @@
 //! multiple actions into the same profile).
 
 use crate::profile::credentials::ProfileFileError;
-use crate::profile::{Profile, ProfileSet};
+use crate::profile::{Profile, ProfileSet, SsoSession};
 use crate::sensitive_command::CommandWithSensitiveArgs;
 use aws_credential_types::Credentials;
 
@@ -77,11 +77,16 @@ pub(super) enum BaseProvider<'a> {
     },
 
     /// An SSO Provider
+    ///
+    /// When the profile references an `[sso-session]` with `sso_session`, `sso_region` and
+    /// `sso_start_url` are taken from that session, and the session name is used to load (and
+    /// refresh) the SSO token.
     Sso {
         sso_account_id: &'a str,
         sso_region: &'a str,
         sso_role_name: &'a str,
         sso_start_url: &'a str,
+        sso_session_name: Option<&'a str>,
     },
 
     /// A profile that specifies a `credential_process`
@@ -90,6 +95,29 @@ pub(super) enum BaseProvider<'a> {
     /// credential_process = /opt/bin/awscreds-custom --username helen
     /// ```
     CredentialProcess(CommandWithSensitiveArgs<&'a str>),
+
+    /// A profile that exchanges a SAML assertion for credentials
+    ///
+    /// `role_arn` is optional: when it's missing, the role is selected from the assertion.
+    /// ```ini
+    /// [profile saml]
+    /// role_arn = arn:aws:iam::123456789012:role/Developer
+    /// saml_assertion_file = /path/to/assertion
+    /// ```
+    Saml {
+        role_arn: Option<&'a str>,
+        principal_arn: Option<&'a str>,
+        assertion_source: SamlAssertionSource<'a>,
+    },
+}
+
+/// Where a [`BaseProvider::Saml`] loads its SAML assertion from
+#[derive(Clone, Debug)]
+pub(super) enum SamlAssertionSource<'a> {
+    /// `saml_assertion_file`
+    File(&'a str),
+    /// `saml_assertion_process`
+    Process(CommandWithSensitiveArgs<&'a str>),
 }
 
 /// A profile that specifies a role to assume
@@ -172,7 +200,7 @@ pub(super) fn resolve_chain(
                 chain.push(role_provider);
                 next
             } else {
-                break base_provider(profile).map_err(|err| {
+                break base_provider(profile_set, profile).map_err(|err| {
                     // It's possible for base_provider to return a `ProfileFileError::ProfileDidNotContainCredentials`
                     // if we're still looking at the first provider we want to surface it. However,
                     // if we're looking at any provider after the first we want to instead return a `ProfileFileError::InvalidCredentialSource`
@@ -193,7 +221,7 @@ pub(super) fn resolve_chain(
                 // self referential profile, don't go through the loop because it will error
                 // on the infinite loop check. Instead, reload this profile as a base profile
                 // and exit.
-                break base_provider(profile)?;
+                break base_provider(profile_set, profile)?;
             }
             NextProfile::Named(name) => source_profile_name = name,
         }
@@ -216,16 +244,24 @@ mod sso {
     pub(super) const REGION: &str = "sso_region";
     pub(super) const ROLE_NAME: &str = "sso_role_name";
     pub(super) const START_URL: &str = "sso_start_url";
+    pub(super) const SESSION_NAME: &str = "sso_session";
 }
 
 mod web_identity_token {
     pub(super) const TOKEN_FILE: &str = "web_identity_token_file";
 }
 
+mod saml {
+    pub(super) const ASSERTION_FILE: &str = "saml_assertion_file";
+    pub(super) const ASSERTION_PROCESS: &str = "saml_assertion_process";
+    pub(super) const PRINCIPAL_ARN: &str = "saml_principal_arn";
+}
+
 mod static_credentials {
     pub(super) const AWS_ACCESS_KEY_ID: &str = "aws_access_key_id";
     pub(super) const AWS_SECRET_ACCESS_KEY: &str = "aws_secret_access_key";
     pub(super) const AWS_SESSION_TOKEN: &str = "aws_session_token";
+    pub(super) const AWS_ACCOUNT_ID: &str = "aws_account_id";
 }
 
 mod credential_process {
@@ -234,12 +270,16 @@ mod credential_process {
 
 const PROVIDER_NAME: &str = "ProfileFile";
 
-fn base_provider(profile: &Profile) -> Result<BaseProvider<'_>, ProfileFileError> {
+fn base_provider<'a>(
+    profile_set: &'a ProfileSet,
+    profile: &'a Profile,
+) -> Result<BaseProvider<'a>, ProfileFileError> {
     // the profile must define either a `CredentialsSource` or a concrete set of access keys
     match profile.get(role::CREDENTIAL_SOURCE) {
         Some(source) => Ok(BaseProvider::NamedSource(source)),
         None => web_identity_token_from_profile(profile)
-            .or_else(|| sso_from_profile(profile))
+            .or_else(|| saml_from_profile(profile))
+            .or_else(|| sso_from_profile(profile_set, profile))
             .or_else(|| credential_process_from_profile(profile))
             .unwrap_or_else(|| Ok(BaseProvider::AccessKey(static_creds_from_profile(profile)?))),
     }
@@ -282,6 +322,11 @@ fn role_arn_from_profile(profile: &Profile) -> Option<RoleArn<'_>> {
     if profile.get(web_identity_token::TOKEN_FILE).is_some() {
         return None;
     }
+    // SAML assertions are root providers that may use `role_arn` to select a role
+    if profile.get(saml::ASSERTION_FILE).is_some() || profile.get(saml::ASSERTION_PROCESS).is_some()
+    {
+        return None;
+    }
     let role_arn = profile.get(role::ROLE_ARN)?;
     let session_name = profile.get(role::SESSION_NAME);
     let external_id = profile.get(role::EXTERNAL_ID);
@@ -292,20 +337,34 @@ fn role_arn_from_profile(profile: &Profile) -> Option<RoleArn<'_>> {
     })
 }
 
-fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider<'_>, ProfileFileError>> {
+fn sso_from_profile<'a>(
+    profile_set: &'a ProfileSet,
+    profile: &'a Profile,
+) -> Option<Result<BaseProvider<'a>, ProfileFileError>> {
     /*
-    Sample:
+    Sample (legacy):
     [profile sample-profile]
     sso_account_id = 012345678901
     sso_region = us-east-1
     sso_role_name = SampleRole
     sso_start_url = https://d-abc123.awsapps.com/start-beta
+
+    Sample (with an sso-session):
+    [profile sample-profile]
+    sso_session = sample-session
+    sso_account_id = 012345678901
+    sso_role_name = SampleRole
+
+    [sso-session sample-session]
+    sso_region = us-east-1
+    sso_start_url = https://d-abc123.awsapps.com/start-beta
     */
     let account_id = profile.get(sso::ACCOUNT_ID);
     let region = profile.get(sso::REGION);
     let role_name = profile.get(sso::ROLE_NAME);
     let start_url = profile.get(sso::START_URL);
-    if [account_id, region, role_name, start_url]
+    let session_name = profile.get(sso::SESSION_NAME);
+    if [account_id, region, role_name, start_url, session_name]
         .iter()
         .all(|field| field.is_none())
     {
@@ -313,20 +372,70 @@ fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider<'_>, Profil
     }
     let missing_field = |s| move || ProfileFileError::missing_field(profile, s);
     let parse_profile = || {
+        let (sso_region, sso_start_url) = match session_name {
+            Some(session_name) => {
+                let session = profile_set.sso_session(session_name).ok_or_else(|| {
+                    ProfileFileError::MissingSsoSession {
+                        profile: profile.name().to_string(),
+                        sso_session: session_name.to_string(),
+                    }
+                })?;
+                (
+                    sso_session_field(profile, session, sso::REGION)?,
+                    sso_session_field(profile, session, sso::START_URL)?,
+                )
+            }
+            None => (
+                region.ok_or_else(missing_field(sso::REGION))?,
+                start_url.ok_or_else(missing_field(sso::START_URL))?,
+            ),
+        };
         let sso_account_id = account_id.ok_or_else(missing_field(sso::ACCOUNT_ID))?;
-        let sso_region = region.ok_or_else(missing_field(sso::REGION))?;
         let sso_role_name = role_name.ok_or_else(missing_field(sso::ROLE_NAME))?;
-        let sso_start_url = start_url.ok_or_else(missing_field(sso::START_URL))?;
         Ok(BaseProvider::Sso {
             sso_account_id,
             sso_region,
             sso_role_name,
             sso_start_url,
+            sso_session_name: session_name,
         })
     };
     Some(parse_profile())
 }
 
+/// Load a required field from an `[sso-session]` section
+///
+/// The profile that references the session may repeat the field, but only with the same value.
+fn sso_session_field<'a>(
+    profile: &Profile,
+    session: &'a SsoSession,
+    field: &'static str,
+) -> Result<&'a str, ProfileFileError> {
+    let value = session
+        .get(field)
+        .ok_or_else(|| ProfileFileError::InvalidSsoSession {
+            sso_session: session.name().to_string(),
+            message: format!("`{}` was missing", field).into(),
+        })?;
+    match profile.get(field) {
+        Some(profile_value) if profile_value != value => {
+            Err(ProfileFileError::InvalidCredentialSource {
+                profile: profile.name().to_string(),
+                message: format!(
+                    "`{}` in the profile (`{}`) does not match `{}` in sso-session `{}` (`{}`)",
+                    field,
+                    profile_value,
+                    field,
+                    session.name(),
+                    value
+                )
+                .into(),
+            })
+        }
+        _ => Ok(value),
+    }
+}
+
 fn web_identity_token_from_profile(
     profile: &Profile,
 ) -> Option<Result<BaseProvider<'_>, ProfileFileError>> {
@@ -349,6 +458,50 @@ fn web_identity_token_from_profile(
     }
 }
 
+/// Load a SAML provider from a profile
+///
+/// Example:
+/// ```ini
+/// [profile saml]
+/// role_arn = arn:aws:iam::123456789012:role/Developer # optional
+/// saml_principal_arn = arn:aws:iam::123456789012:saml-provider/CorpIdP # optional
+/// saml_assertion_process = /opt/bin/saml-login --username helen
+/// ```
+fn saml_from_profile(profile: &Profile) -> Option<Result<BaseProvider<'_>, ProfileFileError>> {
+    let principal_arn = profile.get(saml::PRINCIPAL_ARN);
+    let invalid = |message: &'static str| ProfileFileError::InvalidCredentialSource {
+        profile: profile.name().to_string(),
+        message: message.into(),
+    };
+    let assertion_source = match (
+        profile.get(saml::ASSERTION_FILE),
+        profile.get(saml::ASSERTION_PROCESS),
+    ) {
+        (Some(file), None) => SamlAssertionSource::File(file),
+        (None, Some(process)) => {
+            SamlAssertionSource::Process(CommandWithSensitiveArgs::new(process))
+        }
+        (Some(_), Some(_)) => {
+            return Some(Err(invalid(
+                "profile contained both `saml_assertion_file` and `saml_assertion_process`. \
+                Only one or the other can be defined",
+            )))
+        }
+        (None, None) => {
+            return principal_arn.map(|_| {
+                Err(invalid(
+                    "`saml_principal_arn` was specified but no SAML assertion source was defined",
+                ))
+            })
+        }
+    };
+    Some(Ok(BaseProvider::Saml {
+        role_arn: profile.get(role::ROLE_ARN),
+        principal_arn,
+        assertion_source,
+    }))
+}
+
 /// Load static credentials from a profile
 ///
 /// Example:
@@ -356,6 +509,7 @@ fn web_identity_token_from_profile(
 /// [profile B]
 /// aws_access_key_id = abc123
 /// aws_secret_access_key = def456
+/// aws_account_id = 123456789012 # optional
 /// ```
 fn static_creds_from_profile(profile: &Profile) -> Result<Credentials, ProfileFileError> {
     use static_credentials::*;
@@ -378,13 +532,17 @@ fn static_creds_from_profile(profile: &Profile) -> Result<Credentials, ProfileFi
         message: "profile missing aws_secret_access_key".into(),
     })?;
     // There might not be an active session token so we don't error out if it's missing
-    Ok(Credentials::new(
+    let credentials = Credentials::new(
         access_key,
         secret_key,
         session_token.map(|s| s.to_string()),
         None,
         PROVIDER_NAME,
-    ))
+    );
+    Ok(match profile.get(AWS_ACCOUNT_ID) {
+        Some(account_id) => credentials.with_account_id(account_id),
+        None => credentials,
+    })
 }
 
 /// Load credentials from `credential_process`
@@ -408,7 +566,9 @@ fn credential_process_from_profile(
 
 #[cfg(test)]
 mod tests {
-    use crate::profile::credentials::repr::{resolve_chain, BaseProvider, ProfileChain};
+    use crate::profile::credentials::repr::{
+        resolve_chain, BaseProvider, ProfileChain, SamlAssertionSource,
+    };
     use crate::profile::ProfileSet;
     use crate::sensitive_command::CommandWithSensitiveArgs;
     use serde::Deserialize;
@@ -429,7 +589,8 @@ mod tests {
     }
 
     fn check(test_case: TestCase) {
-        let source = ProfileSet::new(test_case.input.profile, test_case.input.selected_profile);
+        let source = ProfileSet::new(test_case.input.profile, test_case.input.selected_profile)
+            .with_sso_sessions(test_case.input.sso_sessions);
         let actual = resolve_chain(&source);
         let expected = test_case.output;
         match (expected, actual) {
@@ -460,6 +621,8 @@ mod tests {
     struct TestInput {
         profile: HashMap<String, HashMap<String, String>>,
         selected_profile: String,
+        #[serde(default)]
+        sso_sessions: HashMap<String, HashMap<String, String>>,
     }
 
     fn to_test_output(profile_chain: ProfileChain<'_>) -> Vec<Provider> {
@@ -470,6 +633,7 @@ mod tests {
                 access_key_id: creds.access_key_id().into(),
                 secret_access_key: creds.secret_access_key().into(),
                 session_token: creds.session_token().map(|tok| tok.to_string()),
+                account_id: creds.account_id().map(|id| id.to_string()),
             }),
             BaseProvider::CredentialProcess(credential_process) => output.push(
                 Provider::CredentialProcess(credential_process.unredacted().into()),
@@ -488,12 +652,32 @@ mod tests {
                 sso_region,
                 sso_role_name,
                 sso_start_url,
+                sso_session_name,
             } => output.push(Provider::Sso {
                 sso_account_id: sso_account_id.into(),
                 sso_region: sso_region.into(),
                 sso_role_name: sso_role_name.into(),
                 sso_start_url: sso_start_url.into(),
+                sso_session_name: sso_session_name.map(ToString::to_string),
             }),
+            BaseProvider::Saml {
+                role_arn,
+                principal_arn,
+                assertion_source,
+            } => {
+                let (assertion_file, assertion_process) = match assertion_source {
+                    SamlAssertionSource::File(file) => (Some(file.to_string()), None),
+                    SamlAssertionSource::Process(process) => {
+                        (None, Some(process.unredacted().to_string()))
+                    }
+                };
+                output.push(Provider::Saml {
+                    role_arn: role_arn.map(ToString::to_string),
+                    principal_arn: principal_arn.map(ToString::to_string),
+                    assertion_file,
+                    assertion_process,
+                })
+            }
         };
         for role in profile_chain.chain {
             output.push(Provider::AssumeRole {
@@ -522,6 +706,7 @@ mod tests {
             access_key_id: String,
             secret_access_key: String,
             session_token: Option<String>,
+            account_id: Option<String>,
         },
         NamedSource(String),
         CredentialProcess(String),
@@ -535,6 +720,13 @@ mod tests {
             sso_region: String,
             sso_role_name: String,
             sso_start_url: String,
+            sso_session_name: Option<String>,
+        },
+        Saml {
+            role_arn: Option<String>,
+            principal_arn: Option<String>,
+            assertion_file: Option<String>,
+            assertion_process: Option<String>,
         },
     }
 
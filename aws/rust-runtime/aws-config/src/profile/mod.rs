@@ -16,14 +16,19 @@ mod parser;
 #[doc(inline)]
 pub use parser::ProfileParseError;
 #[doc(inline)]
-pub use parser::{load, Profile, ProfileFileLoadError, ProfileSet, Property};
+pub use parser::{
+    load, Profile, ProfileFileLoadError, ProfileSet, Property, ServicesSection, SsoSession,
+};
 
 pub mod app_name;
 pub mod credentials;
 pub mod profile_file;
 pub mod region;
+pub mod token;
 
 #[doc(inline)]
 pub use credentials::ProfileFileCredentialsProvider;
 #[doc(inline)]
 pub use region::ProfileFileRegionProvider;
+#[doc(inline)]
+pub use token::ProfileFileTokenProvider;
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::profile::parser::parse::parse_profile_file;
+use crate::profile::parser::parse::{parse_profile_file, WHITESPACE};
 use crate::profile::parser::source::Source;
 use crate::profile::profile_file::ProfileFiles;
 use aws_types::os_shim_internal::{Env, Fs};
@@ -16,6 +16,9 @@ use std::sync::Arc;
 
 pub use self::parse::ProfileParseError;
 
+/// The profile property that selects a `[services]` section
+const SERVICES: &str = "services";
+
 mod normalize;
 mod parse;
 mod source;
@@ -55,6 +58,37 @@ mod source;
 /// [other]
 /// aws_access_key_id = 456
 /// ```
+///
+/// ### SSO sessions
+/// The config file may also contain `[sso-session <name>]` sections. These are not profiles: they
+/// hold IAM Identity Center settings that profiles reference with the `sso_session` property:
+/// ```ini
+/// [profile dev]
+/// sso_session = my-sso
+/// sso_account_id = 123456789011
+/// sso_role_name = readOnly
+///
+/// [sso-session my-sso]
+/// sso_start_url = https://example.com/start
+/// sso_region = us-east-2
+/// ```
+/// `sso-session` sections in the credentials file are ignored.
+///
+/// ### Service-specific configuration
+/// Settings for individual services live in `[services <name>]` sections of the config file. A
+/// profile selects one with the `services` property, and each service's settings are nested
+/// beneath its service ID:
+/// ```ini
+/// [profile dev]
+/// services = local-services
+///
+/// [services local-services]
+/// s3 =
+///   endpoint_url = http://localhost:4566
+/// dynamodb =
+///   endpoint_url = http://localhost:8000
+/// ```
+/// `services` sections in the credentials file are ignored.
 pub async fn load(
     fs: &Fs,
     env: &Env,
@@ -74,6 +108,8 @@ pub async fn load(
 pub struct ProfileSet {
     profiles: HashMap<String, Profile>,
     selected_profile: Cow<'static, str>,
+    sso_sessions: HashMap<String, SsoSession>,
+    services: HashMap<String, ServicesSection>,
 }
 
 impl ProfileSet {
@@ -102,6 +138,27 @@ impl ProfileSet {
         base
     }
 
+    /// Add `[sso-session]` sections to a ProfileSet created with [`ProfileSet::new`]
+    #[cfg(test)]
+    pub(crate) fn with_sso_sessions(
+        mut self,
+        sso_sessions: HashMap<String, HashMap<String, String>>,
+    ) -> Self {
+        for (name, session) in sso_sessions {
+            self.sso_sessions.insert(
+                name.clone(),
+                SsoSession::new(
+                    name,
+                    session
+                        .into_iter()
+                        .map(|(k, v)| (k.clone(), Property::new(k, v)))
+                        .collect(),
+                ),
+            );
+        }
+        self
+    }
+
     /// Retrieves a key-value pair from the currently selected profile
     pub fn get(&self, key: &str) -> Option<&str> {
         self.profiles
@@ -129,6 +186,41 @@ impl ProfileSet {
         self.profiles.keys().map(String::as_ref)
     }
 
+    /// Retrieves a named `[sso-session]` section from the profile set
+    pub fn sso_session(&self, session_name: &str) -> Option<&SsoSession> {
+        self.sso_sessions.get(session_name)
+    }
+
+    /// Returns the names of the `[sso-session]` sections in this profile set
+    pub fn sso_sessions(&self) -> impl Iterator<Item = &str> {
+        self.sso_sessions.keys().map(String::as_ref)
+    }
+
+    /// Retrieves a named `[services]` section from the profile set
+    pub fn services_section(&self, section_name: &str) -> Option<&ServicesSection> {
+        self.services.get(section_name)
+    }
+
+    /// Retrieves a setting for a service from the `[services]` section of the selected profile
+    ///
+    /// `service_key` is the key of the service within the section, e.g. `s3` or
+    /// `elastic_beanstalk`. Returns `None` if the selected profile doesn't have a `services`
+    /// property, or if the section doesn't configure `property` for this service.
+    pub fn get_service_property(&self, service_key: &str, property: &str) -> Option<&str> {
+        let section_name = self.get(SERVICES)?;
+        match self.services_section(section_name) {
+            Some(section) => section.get_sub_property(service_key, property),
+            None => {
+                tracing::warn!(
+                    profile = %self.selected_profile,
+                    "profile referenced services section `{}` but that section was not defined",
+                    section_name
+                );
+                None
+            }
+        }
+    }
+
     fn parse(source: Source) -> Result<Self, ProfileParseError> {
         let mut base = ProfileSet::empty();
         base.selected_profile = source.profile;
@@ -143,6 +235,8 @@ impl ProfileSet {
         Self {
             profiles: Default::default(),
             selected_profile: "default".into(),
+            sso_sessions: Default::default(),
+            services: Default::default(),
         }
     }
 }
@@ -171,6 +265,75 @@ impl Profile {
     pub fn get(&self, name: &str) -> Option<&str> {
         self.properties.get(name).map(|prop| prop.value())
     }
+
+    /// Returns a reference to the sub-property `sub_property_name` nested in the property `name`
+    ///
+    /// ```ini
+    /// [profile example]
+    /// name =
+    ///   sub_property_name = value
+    /// ```
+    pub fn get_sub_property(&self, name: &str, sub_property_name: &str) -> Option<&str> {
+        self.properties
+            .get(name)
+            .and_then(|prop| prop.sub_property(sub_property_name))
+    }
+}
+
+/// An `[sso-session <name>]` section of the config file
+///
+/// SSO sessions hold the IAM Identity Center configuration (`sso_start_url`, `sso_region`, ...)
+/// that profiles refer to with the `sso_session` property.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SsoSession {
+    name: String,
+    properties: HashMap<String, Property>,
+}
+
+impl SsoSession {
+    /// Create a new SSO session
+    pub fn new(name: String, properties: HashMap<String, Property>) -> Self {
+        Self { name, properties }
+    }
+
+    /// The name of this SSO session
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a reference to the property named `name`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(|prop| prop.value())
+    }
+}
+
+/// A `[services <name>]` section of the config file
+///
+/// Each property of the section is keyed by a service and holds that service's settings as
+/// sub-properties.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ServicesSection {
+    name: String,
+    properties: HashMap<String, Property>,
+}
+
+impl ServicesSection {
+    /// Create a new services section
+    pub fn new(name: String, properties: HashMap<String, Property>) -> Self {
+        Self { name, properties }
+    }
+
+    /// The name of this services section
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a reference to the setting `sub_property_name` of the service `service_key`
+    pub fn get_sub_property(&self, service_key: &str, sub_property_name: &str) -> Option<&str> {
+        self.properties
+            .get(service_key)
+            .and_then(|prop| prop.sub_property(sub_property_name))
+    }
 }
 
 /// Key-Value property pair
@@ -195,6 +358,24 @@ impl Property {
     pub fn new(key: String, value: String) -> Self {
         Property { key, value }
     }
+
+    /// Value of the sub-property named `name`
+    ///
+    /// Only properties without a value on their first line have sub-properties:
+    /// ```ini
+    /// s3 =
+    ///   endpoint_url = http://localhost:4566
+    /// ```
+    pub fn sub_property(&self, name: &str) -> Option<&str> {
+        if !self.value.starts_with('\n') {
+            return None;
+        }
+        self.value
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .find(|(key, _)| key.trim_matches(WHITESPACE) == name)
+            .map(|(_, value)| value.trim_matches(WHITESPACE))
+    }
 }
 
 /// Failed to read or parse the profile file(s)
@@ -248,7 +429,7 @@ pub struct CouldNotReadProfileFile {
 mod test {
     use crate::profile::parser::source::{File, Source};
     use crate::profile::profile_file::ProfileFileKind;
-    use crate::profile::ProfileSet;
+    use crate::profile::{ProfileSet, Property};
     use arbitrary::{Arbitrary, Unstructured};
     use serde::Deserialize;
     use std::collections::HashMap;
@@ -296,6 +477,34 @@ mod test {
         assert_eq!(profile_names, vec!["bar", "foo"]);
     }
 
+    #[test]
+    fn sub_properties_are_exposed() {
+        let source = make_source(ParserInput {
+            config_file: Some(
+                "[default]\nservices = local\nnested =\n  a = 1\n  b = 2\nflat = a = 1\n\
+                 [services local]\ns3 =\n  endpoint_url = http://localhost:4566"
+                    .to_string(),
+            ),
+            credentials_file: None,
+        });
+
+        let profile_set = ProfileSet::parse(source).expect("profiles loaded");
+        let profile = profile_set.get_profile("default").expect("default profile");
+        assert_eq!(Some("1"), profile.get_sub_property("nested", "a"));
+        assert_eq!(Some("2"), profile.get_sub_property("nested", "b"));
+        assert_eq!(None, profile.get_sub_property("nested", "c"));
+        // a property with a value on its first line doesn't have sub-properties
+        assert_eq!(None, profile.get_sub_property("flat", "a"));
+        assert_eq!(
+            Some("http://localhost:4566"),
+            profile_set.get_service_property("s3", "endpoint_url")
+        );
+        assert_eq!(
+            None,
+            profile_set.get_service_property("dynamodb", "endpoint_url")
+        );
+    }
+
     /// Run all tests from the fuzzing corpus to validate coverage
     #[test]
     #[ignore]
@@ -366,16 +575,74 @@ mod test {
         }
     }
 
+    // for test comparison purposes, flatten the non-profile sections of a profile set into hashmaps
+    fn flatten_sections<'a>(
+        sections: impl Iterator<Item = (&'a String, &'a HashMap<String, Property>)>,
+    ) -> HashMap<String, HashMap<String, String>> {
+        sections
+            .map(|(name, properties)| {
+                (
+                    name.clone(),
+                    properties
+                        .values()
+                        .map(|prop| (prop.key.clone(), prop.value.clone()))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
     // wrapper to generate nicer errors during test failure
     fn check(test_case: ParserTest) {
         let copy = test_case.clone();
-        let parsed = ProfileSet::parse(make_source(test_case.input));
-        let res = match (parsed.map(flatten), &test_case.output) {
-            (Ok(actual), ParserOutput::Profiles(expected)) if &actual != expected => Err(format!(
-                "mismatch:\nExpected: {:#?}\nActual: {:#?}",
-                expected, actual
-            )),
+        let parsed = ProfileSet::parse(make_source(test_case.input)).map(|profile_set| {
+            let sso_sessions = flatten_sections(
+                profile_set
+                    .sso_sessions
+                    .values()
+                    .map(|session| (&session.name, &session.properties)),
+            );
+            let services = flatten_sections(
+                profile_set
+                    .services
+                    .values()
+                    .map(|services| (&services.name, &services.properties)),
+            );
+            ((sso_sessions, services), flatten(profile_set))
+        });
+        let res = match (parsed, &test_case.output) {
+            (Ok((_, actual)), ParserOutput::Profiles(expected)) if &actual != expected => {
+                Err(format!(
+                    "mismatch:\nExpected: {:#?}\nActual: {:#?}",
+                    expected, actual
+                ))
+            }
             (Ok(_), ParserOutput::Profiles(_)) => Ok(()),
+            (
+                Ok(((actual_sso_sessions, actual_services), actual_profiles)),
+                ParserOutput::Sections {
+                    profiles,
+                    sso_sessions,
+                    services,
+                },
+            ) => {
+                if &actual_profiles != profiles
+                    || &actual_sso_sessions != sso_sessions
+                    || &actual_services != services
+                {
+                    Err(format!(
+                        "mismatch:\nExpected: {:#?}\n{:#?}\n{:#?}\nActual: {:#?}\n{:#?}\n{:#?}",
+                        profiles,
+                        sso_sessions,
+                        services,
+                        actual_profiles,
+                        actual_sso_sessions,
+                        actual_services
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
             (Err(msg), ParserOutput::ErrorContaining(substr)) => {
                 if format!("{}", msg).contains(substr) {
                     Ok(())
@@ -387,9 +654,7 @@ mod test {
                 "expected an error: {} but parse succeeded:\n{:#?}",
                 err, output
             )),
-            (Err(err), ParserOutput::Profiles(_expected)) => {
-                Err(format!("Expected to succeed but got: {}", err))
-            }
+            (Err(err), _) => Err(format!("Expected to succeed but got: {}", err)),
         };
         if let Err(e) = res {
             eprintln!("Test case failed: {:#?}", copy);
@@ -416,6 +681,14 @@ mod test {
     #[serde(rename_all = "camelCase")]
     enum ParserOutput {
         Profiles(HashMap<String, HashMap<String, String>>),
+        #[serde(rename_all = "camelCase")]
+        Sections {
+            profiles: HashMap<String, HashMap<String, String>>,
+            #[serde(default)]
+            sso_sessions: HashMap<String, HashMap<String, String>>,
+            #[serde(default)]
+            services: HashMap<String, HashMap<String, String>>,
+        },
         ErrorContaining(String),
     }
 
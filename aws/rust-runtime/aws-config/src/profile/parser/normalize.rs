@@ -5,12 +5,47 @@
 
 use crate::profile::parser::parse::{RawProfileSet, WHITESPACE};
 use crate::profile::profile_file::ProfileFileKind;
-use crate::profile::{Profile, ProfileSet, Property};
+use crate::profile::{Profile, ProfileSet, Property, ServicesSection, SsoSession};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
 const DEFAULT: &str = "default";
 const PROFILE_PREFIX: &str = "profile";
+const SSO_SESSION_PREFIX: &str = "sso-session";
+const SERVICES_PREFIX: &str = "services";
+
+/// Returns the section name if `input` is a `[<prefix> <name>]` section header
+fn section_name<'a>(input: &'a str, prefix: &str) -> Option<&'a str> {
+    let input = input.trim_matches(WHITESPACE);
+    match input.strip_prefix(prefix) {
+        // `sso-sessionfoo` isn't considered as having the `sso-session` prefix
+        Some(stripped) if stripped.starts_with(WHITESPACE) => Some(stripped.trim()),
+        _ => None,
+    }
+}
+
+/// Validate the name of a `[<prefix> <name>]` section for a given file kind
+///
+/// 1. `name` must ALWAYS be a valid identifier
+/// 2. Sections other than profiles may only be defined in config files
+fn validate_section_name<'a>(
+    name: &'a str,
+    prefix: &str,
+    kind: ProfileFileKind,
+) -> Result<&'a str, String> {
+    if !matches!(kind, ProfileFileKind::Config) {
+        return Err(format!(
+            "{} `{}` ignored because {} sections are only valid in the config file",
+            prefix, name, prefix
+        ));
+    }
+    validate_identifier(name).map_err(|_| {
+        format!(
+            "{} `{}` ignored because `{}` was not a valid identifier",
+            prefix, name, name
+        )
+    })
+}
 
 #[derive(Eq, PartialEq, Hash, Debug)]
 struct ProfileName<'a> {
@@ -67,13 +102,43 @@ impl ProfileName<'_> {
 /// - Profile names are validated (see `validate_profile_name`)
 /// - A profile named `profile default` takes priority over a profile named `default`.
 /// - Profiles with identical names are merged
+/// - `[sso-session <name>]` and `[services <name>]` sections are split out of the profiles,
+///   validated, and merged by name
 pub(super) fn merge_in(
     base: &mut ProfileSet,
     raw_profile_set: RawProfileSet<'_>,
     kind: ProfileFileKind,
 ) {
+    let mut raw_profiles = Vec::new();
+    for (header, raw_section) in raw_profile_set {
+        if let Some(name) = section_name(header, SSO_SESSION_PREFIX) {
+            match validate_section_name(name, SSO_SESSION_PREFIX, kind) {
+                Ok(name) => {
+                    let session = base
+                        .sso_sessions
+                        .entry(name.to_string())
+                        .or_insert_with(|| SsoSession::new(name.to_string(), Default::default()));
+                    merge_properties(&session.name, &mut session.properties, raw_section);
+                }
+                Err(err_str) => tracing::warn!("{}", err_str),
+            }
+        } else if let Some(name) = section_name(header, SERVICES_PREFIX) {
+            match validate_section_name(name, SERVICES_PREFIX, kind) {
+                Ok(name) => {
+                    let services = base.services.entry(name.to_string()).or_insert_with(|| {
+                        ServicesSection::new(name.to_string(), Default::default())
+                    });
+                    merge_properties(&services.name, &mut services.properties, raw_section);
+                }
+                Err(err_str) => tracing::warn!("{}", err_str),
+            }
+        } else {
+            raw_profiles.push((header, raw_section));
+        }
+    }
+
     // parse / validate profile names
-    let validated_profiles = raw_profile_set
+    let validated_profiles = raw_profiles
         .into_iter()
         .map(|(name, profile)| (ProfileName::parse(name).valid_for(kind), profile));
 
@@ -109,20 +174,22 @@ pub(super) fn merge_in(
             .profiles
             .entry(profile_name.name.to_string())
             .or_insert_with(|| Profile::new(profile_name.name.to_string(), Default::default()));
-        merge_into_base(profile, raw_profile)
+        merge_properties(&profile.name, &mut profile.properties, raw_profile)
     }
 }
 
-fn merge_into_base(target: &mut Profile, profile: HashMap<&str, Cow<'_, str>>) {
-    for (k, v) in profile {
+fn merge_properties(
+    section_name: &str,
+    target: &mut HashMap<String, Property>,
+    properties: HashMap<&str, Cow<'_, str>>,
+) {
+    for (k, v) in properties {
         match validate_identifier(k) {
             Ok(k) => {
-                target
-                    .properties
-                    .insert(k.to_owned(), Property::new(k.to_owned(), v.into()));
+                target.insert(k.to_owned(), Property::new(k.to_owned(), v.into()));
             }
             Err(_) => {
-                tracing::warn!(profile = %&target.name, key = ?k, "key ignored because `{}` was not a valid identifier", k);
+                tracing::warn!(profile = %section_name, key = ?k, "key ignored because `{}` was not a valid identifier", k);
             }
         }
     }
@@ -153,7 +220,7 @@ mod tests {
     use crate::profile::parser::parse::RawProfileSet;
     use crate::profile::ProfileSet;
 
-    use super::{merge_in, ProfileName};
+    use super::{merge_in, section_name, ProfileName, SERVICES_PREFIX, SSO_SESSION_PREFIX};
     use crate::profile::parser::normalize::validate_identifier;
     use crate::profile::profile_file::ProfileFileKind;
 
@@ -233,6 +300,107 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn section_name_parsing() {
+        assert_eq!(
+            Some("name"),
+            section_name("sso-session name", SSO_SESSION_PREFIX)
+        );
+        assert_eq!(
+            Some("name"),
+            section_name("  sso-session\tname  ", SSO_SESSION_PREFIX)
+        );
+        assert_eq!(None, section_name("sso-sessionname", SSO_SESSION_PREFIX));
+        assert_eq!(None, section_name("sso-session", SSO_SESSION_PREFIX));
+        assert_eq!(
+            None,
+            section_name("profile sso-session", SSO_SESSION_PREFIX)
+        );
+        assert_eq!(Some("name"), section_name("services name", SERVICES_PREFIX));
+        assert_eq!(None, section_name("servicesname", SERVICES_PREFIX));
+    }
+
+    #[test]
+    fn sso_sessions_are_not_profiles() {
+        let mut raw: RawProfileSet<'_> = HashMap::new();
+        raw.insert("sso-session dev", {
+            let mut out = HashMap::new();
+            out.insert("sso_region", "us-east-1".into());
+            out
+        });
+        raw.insert("profile dev", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, raw, ProfileFileKind::Config);
+        assert_eq!(vec!["dev"], base.profiles().collect::<Vec<_>>());
+        let session = base.sso_session("dev").expect("session was parsed");
+        assert_eq!("dev", session.name());
+        assert_eq!(Some("us-east-1"), session.get("sso_region"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn sso_session_in_credentials_file_generates_warning() {
+        let mut raw: RawProfileSet<'_> = HashMap::new();
+        raw.insert("sso-session dev", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, raw, ProfileFileKind::Credentials);
+        assert!(base.sso_session("dev").is_none());
+        assert!(base.is_empty());
+        assert!(logs_contain(
+            "sso-session `dev` ignored because sso-session sections are only valid in the config file"
+        ));
+    }
+
+    #[test]
+    #[traced_test]
+    fn invalid_sso_session_generates_warning() {
+        let mut raw: RawProfileSet<'_> = HashMap::new();
+        raw.insert("sso-session in!valid", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, raw, ProfileFileKind::Config);
+        assert!(base.sso_sessions().next().is_none());
+        assert!(logs_contain(
+            "sso-session `in!valid` ignored because `in!valid` was not a valid identifier"
+        ));
+    }
+
+    #[test]
+    fn services_sections_are_not_profiles() {
+        let mut raw: RawProfileSet<'_> = HashMap::new();
+        raw.insert("services dev", {
+            let mut out = HashMap::new();
+            out.insert("s3", "\nendpoint_url = http://localhost:4566".into());
+            out
+        });
+        raw.insert("profile dev", {
+            let mut out = HashMap::new();
+            out.insert("services", "dev".into());
+            out
+        });
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, raw, ProfileFileKind::Config);
+        assert_eq!(vec!["dev"], base.profiles().collect::<Vec<_>>());
+        let services = base.services_section("dev").expect("section was parsed");
+        assert_eq!("dev", services.name());
+        assert_eq!(
+            Some("http://localhost:4566"),
+            services.get_sub_property("s3", "endpoint_url")
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn services_section_in_credentials_file_generates_warning() {
+        let mut raw: RawProfileSet<'_> = HashMap::new();
+        raw.insert("services dev", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, raw, ProfileFileKind::Credentials);
+        assert!(base.services_section("dev").is_none());
+        assert!(logs_contain(
+            "services `dev` ignored because services sections are only valid in the config file"
+        ));
+    }
+
     #[test]
     #[traced_test]
     fn invalid_profile_generates_warning() {
@@ -0,0 +1,243 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Load a bearer token from an AWS profile
+
+use crate::profile::profile_file::ProfileFiles;
+use crate::profile::ProfileSet;
+use crate::provider_config::ProviderConfig;
+use aws_credential_types::provider::error::TokenError;
+use aws_credential_types::provider::future;
+use aws_credential_types::provider::token::{self, ProvideToken};
+
+const SSO_SESSION: &str = "sso_session";
+const SSO_REGION: &str = "sso_region";
+const SSO_START_URL: &str = "sso_start_url";
+
+/// AWS Profile based bearer token provider
+///
+/// This token provider loads the `sso_session` referenced by the active profile, and then
+/// loads the SSO token that was cached in `~/.aws/sso/cache` for that session, for example by
+/// `aws sso login`. The cached token will be refreshed when it gets close to expiring.
+///
+/// ```ini
+/// [default]
+/// sso_session = my-session
+///
+/// [sso-session my-session]
+/// sso_region = us-east-1
+/// sso_start_url = https://d-abc123.awsapps.com/start
+/// ```
+///
+/// Loading SSO tokens requires the `sso` feature. Without it, profiles that reference an
+/// `sso_session` fail to provide a token with an invalid configuration error.
+///
+#[doc = include_str!("location_of_profile_files.md")]
+#[derive(Debug)]
+pub struct ProfileFileTokenProvider {
+    provider_config: ProviderConfig,
+    #[cfg(feature = "sso")]
+    sso_token_provider: tokio::sync::OnceCell<crate::sso::SsoTokenProvider>,
+}
+
+impl ProfileFileTokenProvider {
+    /// Builder for this token provider
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn load_token(&self) -> token::Result {
+        let profile_set = self.provider_config.try_profile().await.map_err(|err| {
+            TokenError::invalid_configuration(format!(
+                "ProfileFile token provider could not load the profile: {}",
+                err
+            ))
+        })?;
+        let session = SsoSessionConfig::from_profile_set(profile_set)?;
+        self.load_sso_token(session).await
+    }
+
+    #[cfg(feature = "sso")]
+    async fn load_sso_token(&self, session: SsoSessionConfig<'_>) -> token::Result {
+        use crate::sso::SsoTokenProvider;
+        use aws_types::region::Region;
+
+        let sso_token_provider = self
+            .sso_token_provider
+            .get_or_init(|| async {
+                SsoTokenProvider::builder()
+                    .configure(&self.provider_config.client_config())
+                    .session_name(session.session_name)
+                    .region(Region::new(session.region.to_string()))
+                    .start_url(session.start_url)
+                    .build_with(self.provider_config.env(), self.provider_config.fs())
+            })
+            .await;
+        sso_token_provider.provide_token().await
+    }
+
+    #[cfg(not(feature = "sso"))]
+    async fn load_sso_token(&self, session: SsoSessionConfig<'_>) -> token::Result {
+        Err(TokenError::invalid_configuration(format!(
+            "the profile references sso-session `{}`, but the `sso` feature is not enabled",
+            session.session_name
+        )))
+    }
+}
+
+impl ProvideToken for ProfileFileTokenProvider {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideToken::new(self.load_token())
+    }
+}
+
+/// The `[sso-session]` section referenced by the active profile
+#[derive(Debug)]
+struct SsoSessionConfig<'a> {
+    session_name: &'a str,
+    #[cfg_attr(not(feature = "sso"), allow(dead_code))]
+    region: &'a str,
+    #[cfg_attr(not(feature = "sso"), allow(dead_code))]
+    start_url: &'a str,
+}
+
+impl<'a> SsoSessionConfig<'a> {
+    fn from_profile_set(profile_set: &'a ProfileSet) -> Result<Self, TokenError> {
+        if profile_set.is_empty() {
+            return Err(TokenError::not_loaded("no profiles were defined"));
+        }
+        let profile_name = profile_set.selected_profile();
+        let profile = profile_set.get_profile(profile_name).ok_or_else(|| {
+            TokenError::not_loaded(format!("profile `{}` was not defined", profile_name))
+        })?;
+        let session_name = profile.get(SSO_SESSION).ok_or_else(|| {
+            TokenError::not_loaded(format!(
+                "profile `{}` did not contain `{}`",
+                profile_name, SSO_SESSION
+            ))
+        })?;
+        let session = profile_set.sso_session(session_name).ok_or_else(|| {
+            TokenError::invalid_configuration(format!(
+                "sso-session `{}` referenced by profile `{}` was not defined",
+                session_name, profile_name
+            ))
+        })?;
+        let field = |name: &'static str| {
+            session.get(name).ok_or_else(|| {
+                TokenError::invalid_configuration(format!(
+                    "invalid sso-session `{}`: `{}` was missing",
+                    session_name, name
+                ))
+            })
+        };
+        Ok(Self {
+            session_name,
+            region: field(SSO_REGION)?,
+            start_url: field(SSO_START_URL)?,
+        })
+    }
+}
+
+/// Builder for [`ProfileFileTokenProvider`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    provider_config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+    profile_files: Option<ProfileFiles>,
+}
+
+impl Builder {
+    /// Override the configuration for the [`ProfileFileTokenProvider`]
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [`ProfileFileTokenProvider`]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Set the profile file that should be used by the [`ProfileFileTokenProvider`]
+    pub fn profile_files(mut self, profile_files: ProfileFiles) -> Self {
+        self.profile_files = Some(profile_files);
+        self
+    }
+
+    /// Builds a [`ProfileFileTokenProvider`]
+    pub fn build(self) -> ProfileFileTokenProvider {
+        let provider_config = self
+            .provider_config
+            .unwrap_or_default()
+            .with_profile_config(self.profile_files, self.profile_override);
+        ProfileFileTokenProvider {
+            provider_config,
+            #[cfg(feature = "sso")]
+            sso_token_provider: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ProfileFileTokenProvider;
+    use crate::provider_config::ProviderConfig;
+    use aws_credential_types::provider::error::TokenError;
+    use aws_credential_types::provider::token::ProvideToken;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider(config: &str, cache: &[(&str, &str)]) -> ProfileFileTokenProvider {
+        let mut files = vec![("/home/user/.aws/config", config)];
+        files.extend_from_slice(cache);
+        let provider_config = ProviderConfig::no_configuration()
+            .with_env(Env::from_slice(&[("HOME", "/home/user")]))
+            .with_fs(Fs::from_slice(&files));
+        ProfileFileTokenProvider::builder()
+            .configure(&provider_config)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn profile_without_sso_session_is_not_loaded() {
+        let provider = provider("[default]\nregion = us-east-1\n", &[]);
+        let err = provider.provide_token().await.expect_err("no token");
+        assert!(
+            matches!(err, TokenError::TokenNotLoaded(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_sso_session_is_invalid_configuration() {
+        let provider = provider("[default]\nsso_session = test\n", &[]);
+        let err = provider.provide_token().await.expect_err("no token");
+        assert!(
+            matches!(err, TokenError::InvalidConfiguration(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[cfg(feature = "sso")]
+    #[tokio::test]
+    async fn loads_cached_sso_token() {
+        let provider = provider(
+            "[default]\n\
+             sso_session = test\n\
+             [sso-session test]\n\
+             sso_region = us-west-2\n\
+             sso_start_url = https://d-123.awsapps.com/start\n",
+            &[(
+                "/home/user/.aws/sso/cache/a94a8fe5ccb19ba61c4c0873d391e987982fbbd3.json",
+                r#"{ "accessToken": "some-token", "expiresAt": "2100-01-01T00:00:00Z" }"#,
+            )],
+        );
+        let token = provider.provide_token().await.expect("success");
+        assert_eq!("some-token", token.token());
+    }
+}
@@ -0,0 +1,59 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Load Credentials from federated SAML assertions
+//!
+//! [`SamlCredentialsProvider`] exchanges a SAML assertion issued by an identity provider (IdP) for
+//! AWS credentials with STS `AssumeRoleWithSAML`. The assertion is loaded from a pluggable
+//! [`ProvideSamlAssertion`] source:
+//! - [`FileSamlAssertionProvider`](assertion::FileSamlAssertionProvider) reads it from a file
+//! - [`ProcessSamlAssertionProvider`](assertion::ProcessSamlAssertionProvider) runs an external
+//!   process, for example a script that performs an interactive login with the IdP
+//! - [`provide_saml_assertion_fn`](assertion::provide_saml_assertion_fn) calls an async closure
+//!
+//! ## Role selection
+//! An assertion lists the roles that the user may assume in its
+//! `https://aws.amazon.com/SAML/Attributes/Role` attribute, each paired with the ARN of the SAML
+//! provider that was registered in IAM. When the assertion grants a single role, that role is
+//! assumed. Otherwise, the role to assume must be selected with a role ARN, and optionally a
+//! principal (SAML provider) ARN.
+//!
+//! ## AWS Profile Configuration
+//! _Note: Configuration of the SAML credentials provider via a shared profile is only supported
+//! when using the [`ProfileFileCredentialsProvider`](crate::profile::credentials)._
+//!
+//! ```ini
+//! [profile saml]
+//! # optional when the assertion grants a single role
+//! role_arn = arn:aws:iam::123456789012:role/Developer
+//! # optional, selects between roles that are granted through different SAML providers
+//! saml_principal_arn = arn:aws:iam::123456789012:saml-provider/CorpIdP
+//! # a file that contains the base64-encoded assertion
+//! saml_assertion_file = /path/to/assertion
+//! # or, a process that writes the base64-encoded assertion to stdout
+//! # saml_assertion_process = /opt/bin/saml-login --username helen
+//! ```
+//!
+//! # Examples
+//! ```no_run
+//! # async fn test() {
+//! use aws_config::provider_config::ProviderConfig;
+//! use aws_config::saml::assertion::FileSamlAssertionProvider;
+//! use aws_config::saml::SamlCredentialsProvider;
+//!
+//! let provider = SamlCredentialsProvider::builder(FileSamlAssertionProvider::new("/path/to/assertion"))
+//!     .role_arn("arn:aws:iam::123456789012:role/Developer")
+//!     .configure(&ProviderConfig::with_default_region().await)
+//!     .build();
+//! # }
+//! ```
+
+pub mod assertion;
+
+pub use assertion::{ProvideSamlAssertion, SamlAssertion};
+
+pub mod credentials;
+
+pub use credentials::SamlCredentialsProvider;
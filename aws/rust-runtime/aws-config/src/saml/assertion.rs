@@ -0,0 +1,478 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Sources of SAML assertions for the [`SamlCredentialsProvider`](super::SamlCredentialsProvider)
+
+use aws_credential_types::provider::error::CredentialsError;
+use aws_smithy_xml::decode::{try_data, Document};
+use aws_types::os_shim_internal::Fs;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+/// Result type for SAML assertion sources
+pub type Result = std::result::Result<SamlAssertion, CredentialsError>;
+
+const ROLE_ATTRIBUTE: &str = "https://aws.amazon.com/SAML/Attributes/Role";
+
+/// A base64-encoded SAML assertion, as it is posted by an identity provider to the AWS sign-in page
+///
+/// The assertion is sensitive, so its contents are redacted from the `Debug` implementation.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SamlAssertion(String);
+
+impl SamlAssertion {
+    /// Creates a `SamlAssertion` from a base64-encoded SAML response
+    ///
+    /// Surrounding whitespace, like a trailing newline in a file, is removed.
+    pub fn new(assertion: impl Into<String>) -> Self {
+        let assertion = assertion.into();
+        match assertion.trim() {
+            trimmed if trimmed.len() == assertion.len() => Self(assertion),
+            trimmed => Self(trimmed.to_string()),
+        }
+    }
+
+    /// Returns the base64-encoded assertion
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the roles granted by the `https://aws.amazon.com/SAML/Attributes/Role` attribute
+    pub(crate) fn roles(&self) -> std::result::Result<Vec<SamlRole>, InvalidSamlAssertion> {
+        let decoded = aws_smithy_types::base64::decode(&self.0)
+            .map_err(|_| InvalidSamlAssertion("the assertion was not valid base64".into()))?;
+        let decoded = std::str::from_utf8(&decoded)
+            .map_err(|_| InvalidSamlAssertion("the assertion was not valid UTF-8".into()))?;
+        let mut doc = Document::new(decoded);
+        let mut roles = vec![];
+        while let Some(start_el) = doc.next_start_element() {
+            if start_el.local() != "Attribute" || start_el.attr("Name") != Some(ROLE_ATTRIBUTE) {
+                continue;
+            }
+            let mut attribute = doc.scoped_to(start_el);
+            while let Some(mut value) = attribute.next_tag() {
+                if value.start_el().local() != "AttributeValue" {
+                    continue;
+                }
+                let value = try_data(&mut value).map_err(|err| {
+                    InvalidSamlAssertion(format!("invalid role attribute: {}", err))
+                })?;
+                roles.push(SamlRole::parse(value.trim())?);
+            }
+        }
+        Ok(roles)
+    }
+}
+
+impl Debug for SamlAssertion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("SamlAssertion(** redacted **)")
+    }
+}
+
+/// A role that a SAML assertion grants, along with the SAML provider that must be used to assume it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SamlRole {
+    pub(crate) role_arn: String,
+    pub(crate) principal_arn: String,
+}
+
+impl SamlRole {
+    /// Parses a role attribute value
+    ///
+    /// The value is a comma-separated pair of a role ARN and a SAML provider ARN, in either order:
+    /// `arn:aws:iam::123456789012:role/Developer,arn:aws:iam::123456789012:saml-provider/CorpIdP`
+    fn parse(value: &str) -> std::result::Result<Self, InvalidSamlAssertion> {
+        let invalid = || InvalidSamlAssertion(format!("invalid role attribute `{}`", value));
+        let (first, second) = value.split_once(',').ok_or_else(invalid)?;
+        let (first, second) = (first.trim(), second.trim());
+        let is_provider = |arn: &str| arn.contains(":saml-provider/");
+        let (role_arn, principal_arn) = match (is_provider(first), is_provider(second)) {
+            (false, true) => (first, second),
+            (true, false) => (second, first),
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            role_arn: role_arn.into(),
+            principal_arn: principal_arn.into(),
+        })
+    }
+}
+
+/// A SAML assertion could not be read
+#[derive(Debug)]
+pub(crate) struct InvalidSamlAssertion(String);
+
+impl fmt::Display for InvalidSamlAssertion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid SAML assertion: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidSamlAssertion {}
+
+/// Futures for SAML assertion sources
+pub mod future {
+    use aws_smithy_async::future::now_or_later::NowOrLater;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    type BoxFuture<'a> = Pin<Box<dyn Future<Output = super::Result> + Send + 'a>>;
+
+    /// Future returned by [`ProvideSamlAssertion`](super::ProvideSamlAssertion)
+    ///
+    /// - When wrapping an already loaded assertion, use [`ready`](ProvideSamlAssertion::ready).
+    /// - When wrapping an asynchronously loaded assertion, use [`new`](ProvideSamlAssertion::new).
+    #[derive(Debug)]
+    pub struct ProvideSamlAssertion<'a>(NowOrLater<super::Result, BoxFuture<'a>>);
+
+    impl<'a> ProvideSamlAssertion<'a> {
+        /// A future that wraps the given future
+        pub fn new(future: impl Future<Output = super::Result> + Send + 'a) -> Self {
+            Self(NowOrLater::new(Box::pin(future)))
+        }
+
+        /// A future that resolves to a given assertion
+        pub fn ready(assertion: super::Result) -> Self {
+            Self(NowOrLater::ready(assertion))
+        }
+    }
+
+    impl Future for ProvideSamlAssertion<'_> {
+        type Output = super::Result;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Pin::new(&mut self.0).poll(cx)
+        }
+    }
+}
+
+/// Source of SAML assertions for the [`SamlCredentialsProvider`](super::SamlCredentialsProvider)
+///
+/// A new assertion is requested every time credentials are loaded. Implementations that require
+/// user interaction, like entering a password or approving a push notification, should be combined
+/// with an identity cache so that the user isn't prompted for every request.
+pub trait ProvideSamlAssertion: Send + Sync + Debug {
+    /// Load a SAML assertion
+    fn provide_saml_assertion<'a>(&'a self) -> future::ProvideSamlAssertion<'a>
+    where
+        Self: 'a;
+}
+
+impl ProvideSamlAssertion for SamlAssertion {
+    fn provide_saml_assertion<'a>(&'a self) -> future::ProvideSamlAssertion<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideSamlAssertion::ready(Ok(self.clone()))
+    }
+}
+
+/// Load a base64-encoded SAML assertion from a file
+///
+/// The file is read every time an assertion is requested, so it can be replaced by an external
+/// login process while the provider is in use.
+#[derive(Debug)]
+pub struct FileSamlAssertionProvider {
+    path: PathBuf,
+    fs: Fs,
+}
+
+impl FileSamlAssertionProvider {
+    /// Creates a `FileSamlAssertionProvider` that reads the assertion from `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            fs: Fs::real(),
+        }
+    }
+
+    pub(crate) fn with_fs(mut self, fs: Fs) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    async fn assertion(&self) -> Result {
+        let contents = self.fs.read_to_end(&self.path).await.map_err(|err| {
+            CredentialsError::provider_error(format!(
+                "could not read the SAML assertion from {}: {}",
+                self.path.display(),
+                err
+            ))
+        })?;
+        let contents = String::from_utf8(contents).map_err(|_utf_8_error| {
+            CredentialsError::unhandled("the SAML assertion was not valid UTF-8")
+        })?;
+        Ok(SamlAssertion::new(contents))
+    }
+}
+
+impl ProvideSamlAssertion for FileSamlAssertionProvider {
+    fn provide_saml_assertion<'a>(&'a self) -> future::ProvideSamlAssertion<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideSamlAssertion::new(self.assertion())
+    }
+}
+
+/// Load a base64-encoded SAML assertion from an external process
+///
+/// The process must exit with status 0 and write the assertion to `stdout`. It inherits `stdin`
+/// and `stderr`, so it may interact with the user, for example to prompt them to sign in to the
+/// identity provider. Only `stdout` is captured.
+#[cfg(feature = "credentials-process")]
+#[derive(Debug)]
+pub struct ProcessSamlAssertionProvider {
+    command: crate::sensitive_command::CommandWithSensitiveArgs<String>,
+}
+
+#[cfg(feature = "credentials-process")]
+impl ProcessSamlAssertionProvider {
+    /// Creates a `ProcessSamlAssertionProvider` that runs `command` with the system shell
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: crate::sensitive_command::CommandWithSensitiveArgs::new(command.into()),
+        }
+    }
+
+    async fn assertion(&self) -> Result {
+        use std::process::{Command, Stdio};
+
+        // Security: command arguments must be redacted at debug level
+        tracing::debug!(command = %self.command, "loading SAML assertion from external process");
+
+        let command = if cfg!(windows) {
+            let mut command = Command::new("cmd.exe");
+            command.args(["/C", self.command.unredacted()]);
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.args(["-c", self.command.unredacted()]);
+            command
+        };
+        let map_err = |e| {
+            CredentialsError::provider_error(format!(
+                "Error retrieving SAML assertion from external process: {}",
+                e
+            ))
+        };
+        // `output()` would capture `stderr` too, so spawn the process with only `stdout` piped
+        let output = tokio::process::Command::from(command)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(map_err)?
+            .wait_with_output()
+            .await
+            .map_err(map_err)?;
+
+        if !output.status.success() {
+            return Err(CredentialsError::provider_error(format!(
+                "Error retrieving SAML assertion: external process exited with code {}. See its stderr output for details",
+                output.status
+            )));
+        }
+
+        let output = String::from_utf8(output.stdout).map_err(|e| {
+            CredentialsError::provider_error(format!(
+                "Error retrieving SAML assertion from external process: could not decode output as UTF-8: {}",
+                e
+            ))
+        })?;
+        Ok(SamlAssertion::new(output))
+    }
+}
+
+#[cfg(feature = "credentials-process")]
+impl ProvideSamlAssertion for ProcessSamlAssertionProvider {
+    fn provide_saml_assertion<'a>(&'a self) -> future::ProvideSamlAssertion<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideSamlAssertion::new(self.assertion())
+    }
+}
+
+/// A [`ProvideSamlAssertion`] implemented by a closure.
+///
+/// See [`provide_saml_assertion_fn`] for more details.
+#[derive(Copy, Clone)]
+pub struct ProvideSamlAssertionFn<'c, T> {
+    f: T,
+    phantom: PhantomData<&'c T>,
+}
+
+impl<T> Debug for ProvideSamlAssertionFn<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ProvideSamlAssertionFn")
+    }
+}
+
+impl<'c, T, F> ProvideSamlAssertion for ProvideSamlAssertionFn<'c, T>
+where
+    T: Fn() -> F + Send + Sync + 'c,
+    F: Future<Output = Result> + Send + 'static,
+{
+    fn provide_saml_assertion<'a>(&'a self) -> future::ProvideSamlAssertion<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideSamlAssertion::new((self.f)())
+    }
+}
+
+/// Returns a new SAML assertion source built with the given closure
+///
+/// This allows an application to sign in to its identity provider however it needs to, for example
+/// by prompting the user for their password.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::saml::assertion::provide_saml_assertion_fn;
+/// use aws_config::saml::SamlAssertion;
+///
+/// async fn sign_in_to_identity_provider() -> String {
+///     todo!()
+/// }
+///
+/// provide_saml_assertion_fn(|| async {
+///     let assertion = sign_in_to_identity_provider().await;
+///     Ok(SamlAssertion::new(assertion))
+/// });
+/// ```
+pub fn provide_saml_assertion_fn<'c, T, F>(f: T) -> ProvideSamlAssertionFn<'c, T>
+where
+    T: Fn() -> F + Send + Sync + 'c,
+    F: Future<Output = Result> + Send + 'static,
+{
+    ProvideSamlAssertionFn {
+        f,
+        phantom: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        provide_saml_assertion_fn, FileSamlAssertionProvider, ProvideSamlAssertion, SamlAssertion,
+        SamlRole,
+    };
+    use aws_credential_types::provider::error::CredentialsError;
+    use aws_smithy_types::error::display::DisplayErrorContext;
+    use aws_types::os_shim_internal::Fs;
+
+    const RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<saml2p:Response xmlns:saml2p="urn:oasis:names:tc:SAML:2.0:protocol" Version="2.0">
+  <saml2:Assertion xmlns:saml2="urn:oasis:names:tc:SAML:2.0:assertion" Version="2.0">
+    <saml2:AttributeStatement>
+      <saml2:Attribute Name="https://aws.amazon.com/SAML/Attributes/RoleSessionName">
+        <saml2:AttributeValue>helen@example.com</saml2:AttributeValue>
+      </saml2:Attribute>
+      <saml2:Attribute Name="https://aws.amazon.com/SAML/Attributes/Role">
+        <saml2:AttributeValue>arn:aws:iam::123456789012:role/Developer,arn:aws:iam::123456789012:saml-provider/CorpIdP</saml2:AttributeValue>
+        <saml2:AttributeValue>arn:aws:iam::123456789012:saml-provider/CorpIdP, arn:aws:iam::123456789012:role/ReadOnly</saml2:AttributeValue>
+      </saml2:Attribute>
+    </saml2:AttributeStatement>
+  </saml2:Assertion>
+</saml2p:Response>"#;
+
+    fn role(role: &str) -> SamlRole {
+        SamlRole {
+            role_arn: format!("arn:aws:iam::123456789012:role/{}", role),
+            principal_arn: "arn:aws:iam::123456789012:saml-provider/CorpIdP".into(),
+        }
+    }
+
+    #[test]
+    fn parse_roles() {
+        let assertion = SamlAssertion::new(aws_smithy_types::base64::encode(RESPONSE));
+        assert_eq!(
+            vec![role("Developer"), role("ReadOnly")],
+            assertion.roles().expect("valid assertion")
+        );
+    }
+
+    #[test]
+    fn invalid_assertions() {
+        for (assertion, message) in [
+            ("not base64!", "not valid base64"),
+            (
+                "<Attribute Name=\"https://aws.amazon.com/SAML/Attributes/Role\"><AttributeValue>arn:aws:iam::123456789012:role/Developer</AttributeValue></Attribute>",
+                "invalid role attribute",
+            ),
+        ] {
+            let assertion = match assertion.starts_with('<') {
+                true => SamlAssertion::new(aws_smithy_types::base64::encode(assertion)),
+                false => SamlAssertion::new(assertion),
+            };
+            let err = assertion.roles().expect_err("invalid assertion");
+            assert!(
+                err.to_string().contains(message),
+                "`{}` did not contain `{}`",
+                err,
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn debug_is_redacted() {
+        let assertion = SamlAssertion::new("c2VjcmV0\n");
+        assert_eq!("c2VjcmV0", assertion.as_str());
+        assert_eq!("SamlAssertion(** redacted **)", format!("{:?}", assertion));
+    }
+
+    #[tokio::test]
+    async fn file_provider() {
+        let provider = FileSamlAssertionProvider::new("/assertion")
+            .with_fs(Fs::from_slice(&[("/assertion", "c2VjcmV0\n")]));
+        let assertion = provider.provide_saml_assertion().await.expect("success");
+        assert_eq!("c2VjcmV0", assertion.as_str());
+
+        let provider = FileSamlAssertionProvider::new("/missing").with_fs(Fs::from_slice(&[]));
+        let err = provider
+            .provide_saml_assertion()
+            .await
+            .expect_err("missing");
+        assert!(
+            matches!(err, CredentialsError::ProviderError(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[cfg(feature = "credentials-process")]
+    #[tokio::test]
+    async fn process_provider() {
+        use super::ProcessSamlAssertionProvider;
+
+        let provider = ProcessSamlAssertionProvider::new("echo c2VjcmV0");
+        let assertion = provider.provide_saml_assertion().await.expect("success");
+        assert_eq!("c2VjcmV0", assertion.as_str());
+
+        let provider = ProcessSamlAssertionProvider::new("echo c2VjcmV0; exit 1");
+        let err = provider
+            .provide_saml_assertion()
+            .await
+            .expect_err("failure");
+        let message = format!("{}", DisplayErrorContext(&err));
+        assert!(
+            message.contains("exited with code") && !message.contains("c2VjcmV0"),
+            "unexpected error: {message}"
+        );
+    }
+
+    #[tokio::test]
+    async fn fn_provider() {
+        let provider = provide_saml_assertion_fn(|| async { Ok(SamlAssertion::new("c2VjcmV0")) });
+        let assertion = provider.provide_saml_assertion().await.expect("success");
+        assert_eq!("c2VjcmV0", assertion.as_str());
+    }
+}
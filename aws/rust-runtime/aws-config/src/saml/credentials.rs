@@ -0,0 +1,289 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! SAML Credentials Provider
+//!
+//! This credentials provider exchanges a SAML assertion for credentials with STS `AssumeRoleWithSAML`.
+//! See the [module documentation](crate::saml) for more details.
+
+use super::assertion::{ProvideSamlAssertion, SamlAssertion, SamlRole};
+use crate::provider_config::ProviderConfig;
+use crate::sts;
+use aws_credential_types::provider::{self, error::CredentialsError, future, ProvideCredentials};
+use aws_sdk_sts::Client as StsClient;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::Instrument;
+
+/// SAML Credentials Provider
+///
+/// _Note: This provider is integrated with the profile-file provider. Unless you need to provide
+/// the assertion from your application, it is recommended to configure it in a profile instead._
+///
+/// See the [module documentation](crate::saml) for more details.
+#[derive(Debug)]
+pub struct SamlCredentialsProvider {
+    assertion_provider: Arc<dyn ProvideSamlAssertion>,
+    role_arn: Option<String>,
+    principal_arn: Option<String>,
+    session_length: Option<Duration>,
+    sts_client: StsClient,
+}
+
+impl SamlCredentialsProvider {
+    /// Creates a builder for a provider that loads SAML assertions from `assertion_provider`
+    pub fn builder(assertion_provider: impl ProvideSamlAssertion + 'static) -> Builder {
+        Builder::new(assertion_provider)
+    }
+
+    /// Selects the role to assume from the roles granted by the assertion
+    fn select_role(&self, assertion: &SamlAssertion) -> Result<SamlRole, CredentialsError> {
+        if let (Some(role_arn), Some(principal_arn)) = (&self.role_arn, &self.principal_arn) {
+            return Ok(SamlRole {
+                role_arn: role_arn.clone(),
+                principal_arn: principal_arn.clone(),
+            });
+        }
+        let granted = assertion
+            .roles()
+            .map_err(CredentialsError::provider_error)?;
+        let mut matching: Vec<_> = granted
+            .iter()
+            .filter(|role| self.role_arn.iter().all(|arn| arn == &role.role_arn))
+            .filter(|role| {
+                self.principal_arn
+                    .iter()
+                    .all(|arn| arn == &role.principal_arn)
+            })
+            .collect();
+        let list = || {
+            granted
+                .iter()
+                .map(|role| role.role_arn.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match (matching.pop(), matching.is_empty()) {
+            (Some(role), true) => Ok(role.clone()),
+            (Some(_), false) => Err(CredentialsError::invalid_configuration(format!(
+                "the SAML assertion grants multiple roles. Set `role_arn` to choose one of: {}",
+                list()
+            ))),
+            (None, _) if granted.is_empty() => Err(CredentialsError::invalid_configuration(
+                "the SAML assertion did not grant any roles",
+            )),
+            (None, _) => Err(CredentialsError::invalid_configuration(format!(
+                "the SAML assertion does not grant the configured role. Granted roles: {}",
+                list()
+            ))),
+        }
+    }
+
+    async fn credentials(&self) -> provider::Result {
+        let assertion = self.assertion_provider.provide_saml_assertion().await?;
+        let role = self.select_role(&assertion)?;
+        tracing::debug!(role_arn = %role.role_arn, principal_arn = %role.principal_arn, "assuming role with SAML assertion");
+        let resp = self
+            .sts_client
+            .assume_role_with_saml()
+            .role_arn(role.role_arn)
+            .principal_arn(role.principal_arn)
+            .saml_assertion(assertion.as_str())
+            .set_duration_seconds(self.session_length.map(|dur| dur.as_secs() as i32))
+            .send()
+            .await
+            .map_err(|sdk_error| {
+                tracing::warn!(error = %DisplayErrorContext(&sdk_error), "STS returned an error assuming role with SAML");
+                CredentialsError::provider_error(sdk_error)
+            })?;
+        sts::util::into_credentials(resp.credentials, resp.assumed_role_user, "Saml")
+    }
+}
+
+impl ProvideCredentials for SamlCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(
+            self.credentials()
+                .instrument(tracing::debug_span!("assume_role_with_saml")),
+        )
+    }
+}
+
+/// Builder for [`SamlCredentialsProvider`]
+#[derive(Debug)]
+pub struct Builder {
+    assertion_provider: Arc<dyn ProvideSamlAssertion>,
+    role_arn: Option<String>,
+    principal_arn: Option<String>,
+    session_length: Option<Duration>,
+    config: Option<ProviderConfig>,
+}
+
+impl Builder {
+    /// Creates a new builder that loads SAML assertions from `assertion_provider`
+    pub fn new(assertion_provider: impl ProvideSamlAssertion + 'static) -> Self {
+        Self {
+            assertion_provider: Arc::new(assertion_provider),
+            role_arn: None,
+            principal_arn: None,
+            session_length: None,
+            config: None,
+        }
+    }
+
+    /// Override the configuration used for this provider
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.config = Some(provider_config.clone());
+        self
+    }
+
+    /// Set the ARN of the role to assume
+    ///
+    /// This is required when the assertion grants more than one role.
+    pub fn role_arn(mut self, role_arn: impl Into<String>) -> Self {
+        self.role_arn = Some(role_arn.into());
+        self
+    }
+
+    /// Set the ARN of the SAML provider in IAM that describes the identity provider
+    ///
+    /// When unset, the SAML provider that the assertion pairs with the selected role is used.
+    pub fn principal_arn(mut self, principal_arn: impl Into<String>) -> Self {
+        self.principal_arn = Some(principal_arn.into());
+        self
+    }
+
+    /// Set the expiration time of the role session
+    ///
+    /// When unset, this value defaults to 1 hour. The session never outlasts the
+    /// `SessionNotOnOrAfter` value of the assertion.
+    pub fn session_length(mut self, length: Duration) -> Self {
+        self.session_length = Some(length);
+        self
+    }
+
+    /// Build a [`SamlCredentialsProvider`]
+    pub fn build(self) -> SamlCredentialsProvider {
+        let conf = self.config.unwrap_or_default();
+        SamlCredentialsProvider {
+            assertion_provider: self.assertion_provider,
+            role_arn: self.role_arn,
+            principal_arn: self.principal_arn,
+            session_length: self.session_length,
+            sts_client: StsClient::new(&conf.client_config()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SamlCredentialsProvider;
+    use crate::provider_config::ProviderConfig;
+    use crate::saml::SamlAssertion;
+    use crate::test_case::no_traffic_client;
+    use aws_credential_types::provider::error::CredentialsError;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_types::error::display::DisplayErrorContext;
+    use aws_types::region::Region;
+
+    const DEVELOPER: &str = "arn:aws:iam::123456789012:role/Developer";
+    const READ_ONLY: &str = "arn:aws:iam::123456789012:role/ReadOnly";
+    const CORP_IDP: &str = "arn:aws:iam::123456789012:saml-provider/CorpIdP";
+
+    fn assertion(roles: &[&str]) -> SamlAssertion {
+        let values: String = roles
+            .iter()
+            .map(|role| format!("<AttributeValue>{},{}</AttributeValue>", role, CORP_IDP))
+            .collect();
+        SamlAssertion::new(aws_smithy_types::base64::encode(format!(
+            "<Response><Assertion><Attribute Name=\"https://aws.amazon.com/SAML/Attributes/Role\">{}</Attribute></Assertion></Response>",
+            values
+        )))
+    }
+
+    fn conf() -> ProviderConfig {
+        ProviderConfig::empty()
+            .with_sleep_impl(TokioSleep::new())
+            .with_http_client(no_traffic_client())
+            .with_region(Some(Region::new("us-east-1")))
+    }
+
+    #[test]
+    fn selects_the_only_granted_role() {
+        let provider = SamlCredentialsProvider::builder(assertion(&[]))
+            .configure(&conf())
+            .build();
+        let role = provider
+            .select_role(&assertion(&[DEVELOPER]))
+            .expect("one role");
+        assert_eq!(DEVELOPER, role.role_arn);
+        assert_eq!(CORP_IDP, role.principal_arn);
+    }
+
+    #[test]
+    fn selects_the_configured_role() {
+        let provider = SamlCredentialsProvider::builder(assertion(&[]))
+            .role_arn(READ_ONLY)
+            .configure(&conf())
+            .build();
+        let role = provider
+            .select_role(&assertion(&[DEVELOPER, READ_ONLY]))
+            .expect("configured role");
+        assert_eq!(READ_ONLY, role.role_arn);
+        assert_eq!(CORP_IDP, role.principal_arn);
+    }
+
+    #[test]
+    fn role_selection_errors() {
+        let unselected = SamlCredentialsProvider::builder(assertion(&[]))
+            .configure(&conf())
+            .build();
+        let not_granted = SamlCredentialsProvider::builder(assertion(&[]))
+            .role_arn("arn:aws:iam::123456789012:role/Admin")
+            .configure(&conf())
+            .build();
+        for (provider, roles, message) in [
+            (&unselected, &[][..], "did not grant any roles"),
+            (&unselected, &[DEVELOPER, READ_ONLY][..], "multiple roles"),
+            (
+                &not_granted,
+                &[DEVELOPER][..],
+                "does not grant the configured role",
+            ),
+        ] {
+            let err = provider
+                .select_role(&assertion(roles))
+                .expect_err("invalid selection");
+            let formatted = format!("{}", DisplayErrorContext(&err));
+            assert!(
+                formatted.contains(message),
+                "`{}` did not contain `{}`",
+                formatted,
+                message
+            );
+            assert!(
+                matches!(err, CredentialsError::InvalidConfiguration(_)),
+                "unexpected error: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn explicit_role_and_principal_skip_parsing() {
+        let provider = SamlCredentialsProvider::builder(assertion(&[]))
+            .role_arn(DEVELOPER)
+            .principal_arn(CORP_IDP)
+            .configure(&conf())
+            .build();
+        let role = provider
+            .select_role(&SamlAssertion::new("opaque"))
+            .expect("explicit role");
+        assert_eq!(DEVELOPER, role.role_arn);
+    }
+}
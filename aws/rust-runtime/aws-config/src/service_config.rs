@@ -0,0 +1,192 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Service-specific configuration loaded from the environment and the shared config files
+
+use crate::profile::ProfileSet;
+use aws_types::os_shim_internal::Env;
+use aws_types::service_config::{LoadServiceConfig, ServiceConfigKey};
+
+/// Loads service-specific configuration from the environment and the selected profile
+///
+/// Values are resolved in the following order, using `endpoint_url` for S3 as an example:
+/// 1. The service-specific environment variable: `AWS_ENDPOINT_URL_S3`
+/// 2. The environment variable for all services: `AWS_ENDPOINT_URL`
+/// 3. The `s3` entry of the `[services]` section referenced by the profile
+/// 4. The profile key for all services: `endpoint_url`
+#[derive(Debug)]
+pub(crate) struct EnvServiceConfig {
+    env: Env,
+    profile: Option<ProfileSet>,
+}
+
+impl EnvServiceConfig {
+    pub(crate) fn new(env: Env, profile: Option<ProfileSet>) -> Self {
+        Self { env, profile }
+    }
+}
+
+impl LoadServiceConfig for EnvServiceConfig {
+    fn load_config(&self, key: ServiceConfigKey<'_>) -> Option<String> {
+        let service_env = format!("{}_{}", key.env(), env_suffix(key.service_id()));
+        if let Ok(value) = self.env.get(&service_env) {
+            tracing::debug!(
+                "loaded {} from environment variable `{}`",
+                key.profile(),
+                service_env
+            );
+            return Some(value);
+        }
+        if let Ok(value) = self.env.get(key.env()) {
+            tracing::debug!(
+                "loaded {} from environment variable `{}`",
+                key.profile(),
+                key.env()
+            );
+            return Some(value);
+        }
+        let profile = self.profile.as_ref()?;
+        let service_key = profile_key(key.service_id());
+        if let Some(value) = profile.get_service_property(&service_key, key.profile()) {
+            tracing::debug!(
+                "loaded {} from the `{}` entry of the profile's services section",
+                key.profile(),
+                service_key
+            );
+            return Some(value.to_string());
+        }
+        profile.get(key.profile()).map(|value| {
+            tracing::debug!(
+                "loaded {} from profile `{}`",
+                key.profile(),
+                profile.selected_profile()
+            );
+            value.to_string()
+        })
+    }
+}
+
+/// Transforms a service ID into the suffix of its environment variables, e.g. `Elastic Beanstalk` => `ELASTIC_BEANSTALK`
+fn env_suffix(service_id: &str) -> String {
+    service_id.replace([' ', '-'], "_").to_ascii_uppercase()
+}
+
+/// Transforms a service ID into its key in a `[services]` section, e.g. `Elastic Beanstalk` => `elastic_beanstalk`
+fn profile_key(service_id: &str) -> String {
+    service_id.replace([' ', '-'], "_").to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnvServiceConfig;
+    use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
+    use crate::provider_config::ProviderConfig;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::service_config::{LoadServiceConfig, ServiceConfigKey};
+
+    const CONFIG: &str = r#"
+[default]
+endpoint_url = http://global-profile
+services = local
+
+[services local]
+s3 =
+  endpoint_url = http://s3-profile
+elastic_beanstalk =
+  endpoint_url = http://eb-profile
+"#;
+
+    async fn service_config(
+        env: &[(&'static str, &'static str)],
+        config: &str,
+    ) -> EnvServiceConfig {
+        let provider_config = ProviderConfig::empty()
+            .with_env(Env::from_slice(env))
+            .with_fs(Fs::from_slice(&[("config", config)]))
+            .with_profile_config(
+                Some(
+                    ProfileFiles::builder()
+                        .with_file(ProfileFileKind::Config, "config")
+                        .build(),
+                ),
+                None,
+            );
+        EnvServiceConfig::new(
+            provider_config.env(),
+            provider_config.profile().await.cloned(),
+        )
+    }
+
+    fn endpoint_url(config: &EnvServiceConfig, service_id: &str) -> Option<String> {
+        config.load_config(ServiceConfigKey::new(
+            service_id,
+            "AWS_ENDPOINT_URL",
+            "endpoint_url",
+        ))
+    }
+
+    #[tokio::test]
+    async fn service_specific_env_takes_priority() {
+        let config = service_config(
+            &[
+                ("AWS_ENDPOINT_URL_S3", "http://s3-env"),
+                ("AWS_ENDPOINT_URL", "http://global-env"),
+            ],
+            CONFIG,
+        )
+        .await;
+        assert_eq!(Some("http://s3-env".into()), endpoint_url(&config, "S3"));
+        assert_eq!(
+            Some("http://global-env".into()),
+            endpoint_url(&config, "DynamoDB")
+        );
+    }
+
+    #[tokio::test]
+    async fn global_env_takes_priority_over_profile() {
+        let config = service_config(&[("AWS_ENDPOINT_URL", "http://global-env")], CONFIG).await;
+        assert_eq!(
+            Some("http://global-env".into()),
+            endpoint_url(&config, "S3")
+        );
+    }
+
+    #[tokio::test]
+    async fn services_section_takes_priority_over_profile() {
+        let config = service_config(&[], CONFIG).await;
+        assert_eq!(
+            Some("http://s3-profile".into()),
+            endpoint_url(&config, "S3")
+        );
+        assert_eq!(
+            Some("http://eb-profile".into()),
+            endpoint_url(&config, "Elastic Beanstalk")
+        );
+        assert_eq!(
+            Some("http://global-profile".into()),
+            endpoint_url(&config, "DynamoDB")
+        );
+    }
+
+    #[tokio::test]
+    async fn service_id_is_normalized_for_env() {
+        let config = service_config(
+            &[("AWS_ENDPOINT_URL_ELASTIC_BEANSTALK", "http://eb-env")],
+            "",
+        )
+        .await;
+        assert_eq!(
+            Some("http://eb-env".into()),
+            endpoint_url(&config, "Elastic Beanstalk")
+        );
+        assert_eq!(None, endpoint_url(&config, "S3"));
+    }
+
+    #[tokio::test]
+    async fn missing_services_section() {
+        let config = service_config(&[], "[default]\nservices = missing").await;
+        assert_eq!(None, endpoint_url(&config, "S3"));
+    }
+}
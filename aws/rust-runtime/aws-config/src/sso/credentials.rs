@@ -63,7 +63,7 @@ impl SsoCredentialsProvider {
                     .start_url(&sso_provider_config.start_url)
                     .session_name(session_name)
                     .region(sso_provider_config.region.clone())
-                    .build_sync(),
+                    .build_with(env.clone(), fs.clone()),
             )
         } else {
             None
@@ -288,5 +288,6 @@ async fn load_sso_credentials(
         credentials.session_token,
         Some(expiration),
         "SSO",
-    ))
+    )
+    .with_account_id(&sso_provider_config.account_id))
 }
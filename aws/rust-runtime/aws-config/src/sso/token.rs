@@ -14,6 +14,9 @@ use crate::identity::IdentityCache;
 use crate::sso::cache::{
     load_cached_token, save_cached_token, CachedSsoToken, CachedSsoTokenError,
 };
+use aws_credential_types::provider::error::TokenError;
+use aws_credential_types::provider::future;
+use aws_credential_types::provider::token::ProvideToken;
 use aws_sdk_ssooidc::error::DisplayErrorContext;
 use aws_sdk_ssooidc::operation::create_token::CreateTokenOutput;
 use aws_sdk_ssooidc::Client as SsoOidcClient;
@@ -243,6 +246,23 @@ impl ResolveIdentity for SsoTokenProvider {
     }
 }
 
+impl ProvideToken for SsoTokenProvider {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        let time_source = self.inner.sdk_config.time_source().unwrap_or_default();
+        let token_future = self.resolve_token(time_source);
+        future::ProvideToken::new(async move {
+            let token = token_future.await.map_err(TokenError::provider_error)?;
+            Ok(Token::new(
+                token.access_token.as_str(),
+                Some(token.expires_at),
+            ))
+        })
+    }
+}
+
 /// Builder for [`SsoTokenProvider`].
 #[derive(Debug, Default)]
 pub struct Builder {
@@ -324,11 +344,7 @@ impl Builder {
         self.build_with(Env::real(), Fs::real())
     }
 
-    pub(crate) fn build_sync(self) -> SsoTokenProvider {
-        self.build_with(Env::real(), Fs::real())
-    }
-
-    fn build_with(self, env: Env, fs: Fs) -> SsoTokenProvider {
+    pub(crate) fn build_with(self, env: Env, fs: Fs) -> SsoTokenProvider {
         SsoTokenProvider {
             inner: Arc::new(Inner {
                 env,
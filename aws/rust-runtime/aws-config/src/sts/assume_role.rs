@@ -286,7 +286,11 @@ impl Inner {
                     access_key_id = ?assumed.credentials.as_ref().map(|c| &c.access_key_id),
                     "obtained assumed credentials"
                 );
-                super::util::into_credentials(assumed.credentials, "AssumeRoleProvider")
+                super::util::into_credentials(
+                    assumed.credentials,
+                    assumed.assumed_role_user,
+                    "AssumeRoleProvider",
+                )
             }
             Err(SdkError::ServiceError(ref context))
                 if matches!(
@@ -488,6 +492,7 @@ mod test {
             .provide_credentials()
             .await
             .expect("should return valid credentials");
+        assert_eq!(Some("130633740322"), creds_first.account_id());
 
         // After time has been advanced by 120 seconds, the first credentials _could_ still be valid
         // if `LazyCredentialsCache` were used, but the provider uses `NoCredentialsCache` by default
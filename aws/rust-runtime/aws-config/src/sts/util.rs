@@ -5,14 +5,17 @@
 
 use aws_credential_types::provider::{self, error::CredentialsError};
 use aws_credential_types::Credentials as AwsCredentials;
-use aws_sdk_sts::types::Credentials as StsCredentials;
+use aws_sdk_sts::types::{AssumedRoleUser, Credentials as StsCredentials};
 
 use std::convert::TryFrom;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Convert STS credentials to aws_auth::Credentials
+///
+/// The account ID of the credentials is taken from the ARN of the assumed role user, when present.
 pub(crate) fn into_credentials(
     sts_credentials: Option<StsCredentials>,
+    assumed_role_user: Option<AssumedRoleUser>,
     provider_name: &'static str,
 ) -> provider::Result {
     let sts_credentials = sts_credentials
@@ -22,13 +25,29 @@ pub(crate) fn into_credentials(
             "credential expiration time cannot be represented by a SystemTime",
         )
     })?;
-    Ok(AwsCredentials::new(
+    let credentials = AwsCredentials::new(
         sts_credentials.access_key_id,
         sts_credentials.secret_access_key,
         Some(sts_credentials.session_token),
         Some(expiration),
         provider_name,
-    ))
+    );
+    Ok(
+        match assumed_role_user
+            .as_ref()
+            .and_then(|user| account_id_from_arn(&user.arn))
+        {
+            Some(account_id) => credentials.with_account_id(account_id),
+            None => credentials,
+        },
+    )
+}
+
+/// Extract the account ID from an ARN, e.g. `arn:aws:sts::123456789012:assumed-role/role/session`
+pub(crate) fn account_id_from_arn(arn: &str) -> Option<&str> {
+    arn.split(':')
+        .nth(4)
+        .filter(|account_id| !account_id.is_empty())
 }
 
 /// Create a default STS session name
@@ -40,3 +59,18 @@ pub(crate) fn default_session_name(base: &str, ts: SystemTime) -> String {
     let now = ts.duration_since(UNIX_EPOCH).expect("post epoch");
     format!("{}-{}", base, now.as_millis())
 }
+
+#[cfg(test)]
+mod test {
+    use super::account_id_from_arn;
+
+    #[test]
+    fn account_id_from_assumed_role_arn() {
+        assert_eq!(
+            Some("123456789012"),
+            account_id_from_arn("arn:aws:sts::123456789012:assumed-role/role/session")
+        );
+        assert_eq!(None, account_id_from_arn("arn:aws:s3:::bucket"));
+        assert_eq!(None, account_id_from_arn("not-an-arn"));
+    }
+}
@@ -38,6 +38,9 @@ struct Inner {
     expires_after: Option<SystemTime>,
 
     provider_name: &'static str,
+
+    /// The ID of the AWS account these credentials belong to, if known
+    account_id: Option<String>,
 }
 
 impl Debug for Credentials {
@@ -58,6 +61,9 @@ impl Debug for Credentials {
                 creds.field("expires_after", &expiry);
             }
         }
+        if let Some(account_id) = self.account_id() {
+            creds.field("account_id", &account_id);
+        }
         creds.finish()
     }
 }
@@ -83,6 +89,7 @@ impl Credentials {
             session_token: Zeroizing::new(session_token),
             expires_after,
             provider_name,
+            account_id: None,
         }))
     }
 
@@ -166,6 +173,20 @@ impl Credentials {
     pub fn session_token(&self) -> Option<&str> {
         self.0.session_token.as_deref()
     }
+
+    /// Returns the ID of the AWS account these credentials belong to, if known.
+    pub fn account_id(&self) -> Option<&str> {
+        self.0.account_id.as_deref()
+    }
+
+    /// Sets the ID of the AWS account these credentials belong to.
+    ///
+    /// Credentials providers that learn the account ID, for example from an assumed role ARN,
+    /// use this so that it can be used to route requests to account-based endpoints.
+    pub fn with_account_id(mut self, account_id: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.0).account_id = Some(account_id.into());
+        self
+    }
 }
 
 #[cfg(feature = "test-util")]
@@ -218,5 +239,12 @@ mod test {
             format!("{:?}", creds),
             r#"Credentials { provider_name: "debug tester", access_key_id: "akid", secret_access_key: "** redacted **", expires_after: "2009-02-13T23:31:30Z" }"#
         );
+
+        let creds = creds.with_account_id("123456789012");
+        assert_eq!(
+            format!("{:?}", creds),
+            r#"Credentials { provider_name: "debug tester", access_key_id: "akid", secret_access_key: "** redacted **", expires_after: "2009-02-13T23:31:30Z", account_id: "123456789012" }"#
+        );
+        assert_eq!(Some("123456789012"), creds.account_id());
     }
 }
@@ -7,6 +7,7 @@
 //! * Traits for credentials providers and for credentials caching
 //! * An opaque struct representing credentials
 //! * Concrete implementations of credentials caching
+//! * Traits for bearer token providers
 
 #![allow(clippy::derive_partial_eq_without_eq)]
 #![warn(
@@ -21,4 +22,5 @@ pub mod credential_fn;
 mod credentials_impl;
 pub mod provider;
 
+pub use aws_smithy_runtime_api::client::identity::http::Token;
 pub use credentials_impl::Credentials;
@@ -77,7 +77,9 @@ use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
 use std::sync::Arc;
 
-/// Credentials provider errors
+pub mod token;
+
+/// Credentials and token provider errors
 pub mod error {
     use std::error::Error;
     use std::fmt;
@@ -89,6 +91,12 @@ pub mod error {
         source: Box<dyn Error + Send + Sync + 'static>,
     }
 
+    /// Details for [`TokenError::TokenNotLoaded`]
+    #[derive(Debug)]
+    pub struct TokenNotLoaded {
+        source: Box<dyn Error + Send + Sync + 'static>,
+    }
+
     /// Details for [`CredentialsError::ProviderTimedOut`]
     #[derive(Debug)]
     pub struct ProviderTimedOut {
@@ -238,12 +246,110 @@ pub mod error {
             }
         }
     }
+
+    /// Error returned when a bearer token failed to load.
+    #[derive(Debug)]
+    #[non_exhaustive]
+    pub enum TokenError {
+        /// No token was available for this provider
+        TokenNotLoaded(TokenNotLoaded),
+
+        /// Loading a token from this provider exceeded the maximum allowed duration
+        ProviderTimedOut(ProviderTimedOut),
+
+        /// The provider was given an invalid configuration
+        ///
+        /// For example, a profile that references an `sso-session` that doesn't exist.
+        InvalidConfiguration(InvalidConfiguration),
+
+        /// The provider experienced an error during token resolution
+        ProviderError(ProviderError),
+
+        /// An unexpected error occurred during token resolution
+        Unhandled(Unhandled),
+    }
+
+    impl TokenError {
+        /// The token provider did not provide a token
+        ///
+        /// This error indicates the token provider was not enabled or no configuration was set.
+        /// Token provider chains move on to their next provider when they see this error.
+        pub fn not_loaded(source: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
+            Self::TokenNotLoaded(TokenNotLoaded {
+                source: source.into(),
+            })
+        }
+
+        /// An unexpected error occurred loading a token from this provider
+        pub fn unhandled(source: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
+            Self::Unhandled(Unhandled {
+                source: source.into(),
+            })
+        }
+
+        /// The token provider returned an error
+        pub fn provider_error(source: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
+            Self::ProviderError(ProviderError {
+                source: source.into(),
+            })
+        }
+
+        /// The provided configuration for a provider was invalid
+        pub fn invalid_configuration(
+            source: impl Into<Box<dyn Error + Send + Sync + 'static>>,
+        ) -> Self {
+            Self::InvalidConfiguration(InvalidConfiguration {
+                source: source.into(),
+            })
+        }
+
+        /// The token provider did not provide a token within an allotted duration
+        pub fn provider_timed_out(timeout_duration: Duration) -> Self {
+            Self::ProviderTimedOut(ProviderTimedOut { timeout_duration })
+        }
+    }
+
+    impl fmt::Display for TokenError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                TokenError::TokenNotLoaded(_) => {
+                    write!(f, "the token provider was not enabled")
+                }
+                TokenError::ProviderTimedOut(details) => write!(
+                    f,
+                    "token provider timed out after {} seconds",
+                    details.timeout_duration.as_secs()
+                ),
+                TokenError::InvalidConfiguration(_) => {
+                    write!(f, "the token provider was not properly configured")
+                }
+                TokenError::ProviderError(_) => {
+                    write!(f, "an error occurred while loading a token")
+                }
+                TokenError::Unhandled(_) => {
+                    write!(f, "unexpected token error")
+                }
+            }
+        }
+    }
+
+    impl Error for TokenError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                TokenError::TokenNotLoaded(details) => Some(details.source.as_ref() as _),
+                TokenError::ProviderTimedOut(_) => None,
+                TokenError::InvalidConfiguration(details) => Some(details.source.as_ref() as _),
+                TokenError::ProviderError(details) => Some(details.source.as_ref() as _),
+                TokenError::Unhandled(details) => Some(details.source.as_ref() as _),
+            }
+        }
+    }
 }
 
 /// Result type for credential providers.
 pub type Result = std::result::Result<Credentials, error::CredentialsError>;
 
-/// Convenience `ProvideCredentials` struct that implements the `ProvideCredentials` trait.
+/// Convenience `ProvideCredentials` and `ProvideToken` structs that implement the `Future` trait.
 pub mod future {
     use aws_smithy_async::future::now_or_later::NowOrLater;
     use std::future::Future;
@@ -275,6 +381,32 @@ pub mod future {
             Pin::new(&mut self.0).poll(cx)
         }
     }
+
+    /// Future new-type that `ProvideToken::provide_token` must return.
+    #[derive(Debug)]
+    pub struct ProvideToken<'a>(
+        NowOrLater<super::token::Result, BoxFuture<'a, super::token::Result>>,
+    );
+
+    impl<'a> ProvideToken<'a> {
+        /// Creates a `ProvideToken` struct from a future.
+        pub fn new(future: impl Future<Output = super::token::Result> + Send + 'a) -> Self {
+            ProvideToken(NowOrLater::new(Box::pin(future)))
+        }
+
+        /// Creates a `ProvideToken` struct from a resolved token value.
+        pub fn ready(token: super::token::Result) -> Self {
+            ProvideToken(NowOrLater::ready(token))
+        }
+    }
+
+    impl Future for ProvideToken<'_> {
+        type Output = super::token::Result;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Pin::new(&mut self.0).poll(cx)
+        }
+    }
 }
 
 /// Asynchronous Credentials Provider
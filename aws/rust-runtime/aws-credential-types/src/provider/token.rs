@@ -0,0 +1,149 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! AWS SDK bearer token providers
+//!
+//! Services that use the `@httpBearerAuth` auth scheme authenticate requests with a bearer
+//! [`Token`] rather than with [`Credentials`](crate::Credentials). A [`ProvideToken`]
+//! implementation loads these tokens, much like [`ProvideCredentials`](super::ProvideCredentials)
+//! does for credentials.
+//!
+//! ```rust
+//! use aws_credential_types::provider::{error::TokenError, future, token::{self, ProvideToken}};
+//! use aws_credential_types::Token;
+//!
+//! #[derive(Debug)]
+//! struct EnvTokenProvider;
+//!
+//! impl EnvTokenProvider {
+//!     async fn load_token(&self) -> token::Result {
+//!         std::env::var("MY_TOKEN")
+//!             .map(|token| Token::new(token, None))
+//!             .map_err(TokenError::not_loaded)
+//!     }
+//! }
+//!
+//! impl ProvideToken for EnvTokenProvider {
+//!     fn provide_token<'a>(&'a self) -> future::ProvideToken<'a> where Self: 'a {
+//!         future::ProvideToken::new(self.load_token())
+//!     }
+//! }
+//! ```
+
+use crate::provider::error::TokenError;
+use crate::provider::future;
+use aws_smithy_runtime_api::client::identity::http::Token;
+use aws_smithy_runtime_api::client::identity::{Identity, IdentityFuture, ResolveIdentity};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use std::sync::Arc;
+
+/// Result type for token providers.
+pub type Result = std::result::Result<Token, TokenError>;
+
+/// Asynchronous bearer token provider
+pub trait ProvideToken: Send + Sync + std::fmt::Debug {
+    /// Returns a future that provides a bearer token.
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a;
+}
+
+impl ProvideToken for Token {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideToken::ready(Ok(self.clone()))
+    }
+}
+
+impl ProvideToken for Arc<dyn ProvideToken> {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        self.as_ref().provide_token()
+    }
+}
+
+/// Token Provider wrapper that may be shared
+///
+/// Newtype wrapper around ProvideToken that implements Clone using an internal Arc.
+#[derive(Clone, Debug)]
+pub struct SharedTokenProvider(Arc<dyn ProvideToken>);
+
+impl SharedTokenProvider {
+    /// Create a new SharedTokenProvider from `ProvideToken`
+    ///
+    /// The given provider will be wrapped in an internal `Arc`. If your
+    /// provider is already in an `Arc`, use `SharedTokenProvider::from(provider)` instead.
+    pub fn new(provider: impl ProvideToken + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+}
+
+impl AsRef<dyn ProvideToken> for SharedTokenProvider {
+    fn as_ref(&self) -> &(dyn ProvideToken + 'static) {
+        self.0.as_ref()
+    }
+}
+
+impl From<Arc<dyn ProvideToken>> for SharedTokenProvider {
+    fn from(provider: Arc<dyn ProvideToken>) -> Self {
+        SharedTokenProvider(provider)
+    }
+}
+
+impl ProvideToken for SharedTokenProvider {
+    fn provide_token<'a>(&'a self) -> future::ProvideToken<'a>
+    where
+        Self: 'a,
+    {
+        self.0.provide_token()
+    }
+}
+
+impl Storable for SharedTokenProvider {
+    type Storer = StoreReplace<SharedTokenProvider>;
+}
+
+impl ResolveIdentity for SharedTokenProvider {
+    fn resolve_identity<'a>(
+        &'a self,
+        _runtime_components: &'a RuntimeComponents,
+        _config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        IdentityFuture::new(async move {
+            let token = self.provide_token().await?;
+            let expiration = token.expiration();
+            Ok(Identity::new(token, expiration))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use std::time::{Duration, SystemTime};
+
+    #[tokio::test]
+    async fn shared_token_provider_resolves_token_identity() {
+        let expiration = SystemTime::UNIX_EPOCH + Duration::from_secs(1234567890);
+        let provider = SharedTokenProvider::new(Token::new("some-token", Some(expiration)));
+
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let identity = provider
+            .resolve_identity(&runtime_components, &ConfigBag::base())
+            .await
+            .expect("success");
+        assert_eq!(Some(expiration), identity.expiration());
+        assert_eq!(
+            "some-token",
+            identity.data::<Token>().expect("is a token").token()
+        );
+    }
+}
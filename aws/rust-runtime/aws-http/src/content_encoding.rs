@@ -3,17 +3,26 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
 use bytes::{Bytes, BytesMut};
 use http::{HeaderMap, HeaderValue};
 use http_body::{Body, SizeHint};
 use pin_project_lite::pin_project;
 
+use std::fmt;
 use std::pin::Pin;
+use std::sync::{mpsc, Mutex};
 use std::task::{Context, Poll};
 
 const CRLF: &str = "\r\n";
 const CHUNK_TERMINATOR: &str = "0\r\n";
 const TRAILER_SEPARATOR: &[u8] = b":";
+const CHUNK_SIGNATURE_PREFIX: &str = ";chunk-signature=";
+const TRAILER_SIGNATURE_PREFIX: &str = "x-amz-trailer-signature:";
+// Chunk and trailer signatures are lowercase hex encoded SHA-256 HMACs
+const SIGNATURE_LENGTH: u64 = 64;
+const DEFAULT_SIGNED_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Content encoding header value constants
 pub mod header_value {
@@ -25,21 +34,38 @@ pub mod header_value {
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct AwsChunkedBodyOptions {
-    /// The total size of the stream. Because we only support unsigned encoding
-    /// this implies that there will only be a single chunk containing the
-    /// underlying payload.
-    stream_length: u64,
+    /// The total size of the stream, if known. When chunks are unsigned and the size is known,
+    /// this implies that there will only be a single chunk containing the underlying payload.
+    /// Otherwise, every frame of the underlying payload is written as its own chunk.
+    stream_length: Option<u64>,
     /// The length of each trailer sent within an `AwsChunkedBody`. Necessary in
     /// order to correctly calculate the total size of the body accurately.
     trailer_lengths: Vec<u64>,
+    /// The size of each signed chunk. Only the last chunk containing the
+    /// underlying payload may be smaller.
+    signed_chunk_size: Option<usize>,
 }
 
 impl AwsChunkedBodyOptions {
     /// Create a new [`AwsChunkedBodyOptions`][AwsChunkedBodyOptions]
     pub fn new(stream_length: u64, trailer_lengths: Vec<u64>) -> Self {
         Self {
-            stream_length,
+            stream_length: Some(stream_length),
             trailer_lengths,
+            signed_chunk_size: None,
+        }
+    }
+
+    /// Create a new [`AwsChunkedBodyOptions`][AwsChunkedBodyOptions] for a stream whose size
+    /// isn't known ahead of time, such as a compressed stream.
+    ///
+    /// The encoded length of an `AwsChunkedBody` created with these options is also unknown,
+    /// so requests carrying it can't set a `Content-Length` header.
+    pub fn new_unsized(trailer_lengths: Vec<u64>) -> Self {
+        Self {
+            stream_length: None,
+            trailer_lengths,
+            signed_chunk_size: None,
         }
     }
 
@@ -54,6 +80,114 @@ impl AwsChunkedBodyOptions {
         self.trailer_lengths.push(trailer_len);
         self
     }
+
+    /// Set the size of each signed chunk. Defaults to 64 KiB.
+    ///
+    /// This has no effect on bodies with unsigned chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn with_signed_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(
+            chunk_size > 0,
+            "the signed chunk size must be greater than zero"
+        );
+        self.signed_chunk_size = Some(chunk_size);
+        self
+    }
+
+    fn signed_chunk_size(&self) -> usize {
+        self.signed_chunk_size.unwrap_or(DEFAULT_SIGNED_CHUNK_SIZE)
+    }
+}
+
+/// Signs the chunks and trailers of an [`AwsChunkedBody`].
+///
+/// Implementations are expected to chain each signature from the previous one, so chunks are
+/// signed in the order they are written, followed by the trailers.
+pub trait SignChunk: fmt::Debug {
+    /// Returns the signature of the given chunk. The final chunk of a body is always empty.
+    fn chunk_signature(&mut self, chunk: &[u8]) -> Result<String, BoxError>;
+
+    /// Returns the signature of the given trailers. This is called after the final chunk has been
+    /// signed, and only for bodies that have trailers.
+    fn trailer_signature(&mut self, trailers: &HeaderMap) -> Result<String, BoxError>;
+}
+
+/// A sender that gets placed in the request config to wire up a chunk signer after signing.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct DeferredChunkSignerSender(Mutex<mpsc::Sender<Box<dyn SignChunk + Send + Sync>>>);
+
+impl DeferredChunkSignerSender {
+    /// Creates a new `DeferredChunkSignerSender`
+    fn new(tx: mpsc::Sender<Box<dyn SignChunk + Send + Sync>>) -> Self {
+        Self(Mutex::new(tx))
+    }
+
+    /// Sends a signer on the channel
+    pub fn send(
+        &self,
+        signer: Box<dyn SignChunk + Send + Sync>,
+    ) -> Result<(), mpsc::SendError<Box<dyn SignChunk + Send + Sync>>> {
+        self.0.lock().unwrap().send(signer)
+    }
+}
+
+impl Storable for DeferredChunkSignerSender {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Deferred chunk signer to allow a signer to be wired up later.
+///
+/// The `aws-chunked` body is established before the HTTP request is signed, but every chunk
+/// signature is chained from the request signature. This signer establishes a MPSC channel so
+/// that the sender can be placed in the request's config, and the HTTP signer implementation can
+/// send an actual signing implementation seeded with the request signature.
+///
+/// The first chunk to be signed acquires the signing implementation off of the channel and caches
+/// it for the remainder of the body. If no signer was sent, signing fails.
+#[derive(Debug)]
+pub struct DeferredChunkSigner {
+    rx: Option<Mutex<mpsc::Receiver<Box<dyn SignChunk + Send + Sync>>>>,
+    signer: Option<Box<dyn SignChunk + Send + Sync>>,
+}
+
+impl DeferredChunkSigner {
+    /// Creates a new `DeferredChunkSigner` and the sender used to wire up its signer.
+    pub fn new() -> (Self, DeferredChunkSignerSender) {
+        let (tx, rx) = mpsc::channel();
+        (
+            Self {
+                rx: Some(Mutex::new(rx)),
+                signer: None,
+            },
+            DeferredChunkSignerSender::new(tx),
+        )
+    }
+
+    fn acquire(&mut self) -> Result<&mut (dyn SignChunk + Send + Sync), BoxError> {
+        if self.signer.is_none() {
+            let signer = self
+                .rx
+                .take()
+                .and_then(|rx| rx.lock().unwrap().try_recv().ok())
+                .ok_or("no chunk signer was sent before the aws-chunked body was polled")?;
+            self.signer = Some(signer);
+        }
+        Ok(self.signer.as_mut().expect("set above").as_mut())
+    }
+}
+
+impl SignChunk for DeferredChunkSigner {
+    fn chunk_signature(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+        self.acquire()?.chunk_signature(chunk)
+    }
+
+    fn trailer_signature(&mut self, trailers: &HeaderMap) -> Result<String, BoxError> {
+        self.acquire()?.trailer_signature(trailers)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -65,16 +199,33 @@ enum AwsChunkedBodyState {
     /// all data is written out. Once there is no more data to write, transition into the
     /// `WritingTrailers` state.
     WritingChunk,
+    /// Write out each frame of the inner body's data as its own chunk, since the total size isn't
+    /// known ahead of time. Once there is no more data to write, write out the chunk terminator
+    /// and transition into the `WritingTrailers` state.
+    WritingUnsizedChunks,
     /// Write out all trailers associated with this `AwsChunkedBody` and then transition into the
     /// `Closed` state.
     WritingTrailers,
+    /// Write out signed chunks of the inner body's data, and then the signed final chunk. If there
+    /// are trailers, transition into the `WritingSignedTrailers` state. Otherwise, write out the
+    /// body terminator and transition into the `Closed` state.
+    WritingSignedChunks,
+    /// Write out all trailers associated with this `AwsChunkedBody` followed by their signature,
+    /// and then transition into the `Closed` state.
+    WritingSignedTrailers,
     /// This is the final state. Write out the body terminator and then remain in this state.
     Closed,
 }
 
 pin_project! {
-    /// A request body compatible with `Content-Encoding: aws-chunked`. This implementation is only
-    /// capable of writing a single chunk and does not support signed chunks.
+    /// A request body compatible with `Content-Encoding: aws-chunked`.
+    ///
+    /// When created with [`AwsChunkedBody::new`], the payload is written as a single unsigned chunk,
+    /// or as one unsigned chunk per frame if its size is [unknown](AwsChunkedBodyOptions::new_unsized).
+    /// When created with [`AwsChunkedBody::new_signed`], the payload is split into chunks of
+    /// [`with_signed_chunk_size`](AwsChunkedBodyOptions::with_signed_chunk_size) bytes, and every
+    /// chunk carries a `chunk-signature` extension. Trailers are then followed by an
+    /// `x-amz-trailer-signature` trailer.
     ///
     /// Chunked-Body grammar is defined in [ABNF] as:
     ///
@@ -101,24 +252,57 @@ pin_project! {
         state: AwsChunkedBodyState,
         options: AwsChunkedBodyOptions,
         inner_body_bytes_read_so_far: usize,
+        signer: Option<Box<dyn SignChunk + Send + Sync>>,
+        buffer: BytesMut,
     }
 }
 
 impl<Inner> AwsChunkedBody<Inner> {
     /// Wrap the given body in an outer body compatible with `Content-Encoding: aws-chunked`
     pub fn new(body: Inner, options: AwsChunkedBodyOptions) -> Self {
+        let state = match options.stream_length {
+            Some(_) => AwsChunkedBodyState::WritingChunkSize,
+            None => AwsChunkedBodyState::WritingUnsizedChunks,
+        };
         Self {
             inner: body,
-            state: AwsChunkedBodyState::WritingChunkSize,
+            state,
             options,
             inner_body_bytes_read_so_far: 0,
+            signer: None,
+            buffer: BytesMut::new(),
         }
     }
 
-    fn encoded_length(&self) -> u64 {
+    /// Wrap the given body in an outer body compatible with `Content-Encoding: aws-chunked`,
+    /// signing every chunk and the trailers with the given `signer`.
+    ///
+    /// The request must be signed with a `STREAMING-AWS4-HMAC-SHA256-PAYLOAD` payload hash, or
+    /// with a `STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER` payload hash if there are trailers.
+    pub fn new_signed(
+        body: Inner,
+        options: AwsChunkedBodyOptions,
+        signer: impl SignChunk + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            inner: body,
+            state: AwsChunkedBodyState::WritingSignedChunks,
+            options,
+            inner_body_bytes_read_so_far: 0,
+            signer: Some(Box::new(signer)),
+            buffer: BytesMut::new(),
+        }
+    }
+
+    fn encoded_length(&self) -> Option<u64> {
+        let stream_length = self.options.stream_length?;
+        if self.signer.is_some() {
+            return Some(self.signed_encoded_length(stream_length));
+        }
+
         let mut length = 0;
-        if self.options.stream_length != 0 {
-            length += get_unsigned_chunk_bytes_length(self.options.stream_length);
+        if stream_length != 0 {
+            length += get_unsigned_chunk_bytes_length(stream_length);
         }
 
         // End chunk
@@ -132,10 +316,70 @@ impl<Inner> AwsChunkedBody<Inner> {
         // Encoding terminator
         length += CRLF.len() as u64;
 
+        Some(length)
+    }
+}
+
+impl<Inner> AwsChunkedBody<Inner> {
+    fn signed_encoded_length(&self, stream_length: u64) -> u64 {
+        let chunk_size = self.options.signed_chunk_size() as u64;
+        let full_chunks = stream_length / chunk_size;
+        let remainder = stream_length % chunk_size;
+
+        let mut length = full_chunks * get_signed_chunk_bytes_length(chunk_size);
+        if remainder != 0 {
+            length += get_signed_chunk_bytes_length(remainder);
+        }
+
+        // Final chunk
+        length += get_signed_chunk_bytes_length(0);
+
+        if !self.options.trailer_lengths.is_empty() {
+            // Trailers, followed by their signature
+            length += self.options.total_trailer_length();
+            length += TRAILER_SIGNATURE_PREFIX.len() as u64 + SIGNATURE_LENGTH + CRLF.len() as u64;
+        }
+
+        // Encoding terminator
+        length += CRLF.len() as u64;
+
         length
     }
 }
 
+fn get_signed_chunk_bytes_length(payload_length: u64) -> u64 {
+    // The final chunk size is written as "0", and isn't followed by chunk data
+    let (hex_repr_len, data_len) = match payload_length {
+        0 => (1, 0),
+        len => (int_log16(len), len + CRLF.len() as u64),
+    };
+    hex_repr_len
+        + CHUNK_SIGNATURE_PREFIX.len() as u64
+        + SIGNATURE_LENGTH
+        + CRLF.len() as u64
+        + data_len
+}
+
+/// Signs `chunk` and writes it out into `output` with its `chunk-signature` extension.
+///
+/// An empty `chunk` is the final chunk, which isn't followed by chunk data.
+fn write_signed_chunk(
+    signer: &mut (dyn SignChunk + Send + Sync),
+    chunk: &[u8],
+    output: &mut BytesMut,
+) -> Result<(), BoxError> {
+    let signature = signer.chunk_signature(chunk)?;
+    tracing::trace!(len = chunk.len(), %signature, "writing signed chunk");
+    output.extend_from_slice(
+        format!("{:x}{CHUNK_SIGNATURE_PREFIX}{signature}{CRLF}", chunk.len()).as_bytes(),
+    );
+    if !chunk.is_empty() {
+        output.extend_from_slice(chunk);
+        output.extend_from_slice(CRLF.as_bytes());
+    }
+    Ok(())
+}
+
 fn get_unsigned_chunk_bytes_length(payload_length: u64) -> u64 {
     let hex_repr_len = int_log16(payload_length);
     hex_repr_len + CRLF.len() as u64 + payload_length + CRLF.len() as u64
@@ -213,7 +457,8 @@ where
 
         match *this.state {
             AwsChunkedBodyState::WritingChunkSize => {
-                if this.options.stream_length == 0 {
+                let stream_length = this.options.stream_length.unwrap_or_default();
+                if stream_length == 0 {
                     // If the stream is empty, we skip to writing trailers after writing the CHUNK_TERMINATOR.
                     *this.state = AwsChunkedBodyState::WritingTrailers;
                     tracing::trace!("stream is empty, writing chunk terminator");
@@ -221,7 +466,7 @@ where
                 } else {
                     *this.state = AwsChunkedBodyState::WritingChunk;
                     // A chunk must be prefixed by chunk size in hexadecimal
-                    let chunk_size = format!("{:X?}{CRLF}", this.options.stream_length);
+                    let chunk_size = format!("{:X?}{CRLF}", stream_length);
                     tracing::trace!(%chunk_size, "writing chunk size");
                     let chunk_size = Bytes::from(chunk_size);
                     Poll::Ready(Some(Ok(chunk_size)))
@@ -234,15 +479,11 @@ where
                     Poll::Ready(Some(Ok(data)))
                 }
                 Poll::Ready(None) => {
-                    let actual_stream_length = *this.inner_body_bytes_read_so_far as u64;
-                    let expected_stream_length = this.options.stream_length;
-                    if actual_stream_length != expected_stream_length {
-                        let err = Box::new(AwsChunkedBodyError::StreamLengthMismatch {
-                            actual: actual_stream_length,
-                            expected: expected_stream_length,
-                        });
+                    if let Err(err) =
+                        check_stream_length(this.options, *this.inner_body_bytes_read_so_far)
+                    {
                         return Poll::Ready(Some(Err(err)));
-                    };
+                    }
 
                     tracing::trace!("no more chunk data, writing CRLF and chunk terminator");
                     *this.state = AwsChunkedBodyState::WritingTrailers;
@@ -253,6 +494,28 @@ where
                 Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
                 Poll::Pending => Poll::Pending,
             },
+            AwsChunkedBodyState::WritingUnsizedChunks => loop {
+                match this.inner.as_mut().poll_data(cx) {
+                    // Empty frames would be mistaken for the final chunk
+                    Poll::Ready(Some(Ok(data))) if data.is_empty() => continue,
+                    Poll::Ready(Some(Ok(data))) => {
+                        tracing::trace!(len = data.len(), "writing unsized chunk");
+                        *this.inner_body_bytes_read_so_far += data.len();
+                        let mut chunk = BytesMut::with_capacity(data.len() + 16);
+                        chunk.extend_from_slice(format!("{:X}{CRLF}", data.len()).as_bytes());
+                        chunk.extend_from_slice(&data);
+                        chunk.extend_from_slice(CRLF.as_bytes());
+                        return Poll::Ready(Some(Ok(chunk.freeze())));
+                    }
+                    Poll::Ready(None) => {
+                        tracing::trace!("no more chunk data, writing chunk terminator");
+                        *this.state = AwsChunkedBodyState::WritingTrailers;
+                        return Poll::Ready(Some(Ok(Bytes::from(CHUNK_TERMINATOR))));
+                    }
+                    Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                    Poll::Pending => return Poll::Pending,
+                }
+            },
             AwsChunkedBodyState::WritingTrailers => {
                 return match this.inner.poll_trailers(cx) {
                     Poll::Ready(Ok(trailers)) => {
@@ -280,6 +543,97 @@ where
                     Poll::Ready(Err(e)) => Poll::Ready(Some(Err(e))),
                 };
             }
+            AwsChunkedBodyState::WritingSignedChunks => {
+                let signer = this.signer.as_mut().expect("signed bodies have a signer");
+                let chunk_size = this.options.signed_chunk_size();
+                loop {
+                    if this.buffer.len() >= chunk_size {
+                        let chunk = this.buffer.split_to(chunk_size);
+                        let mut output = BytesMut::new();
+                        return match write_signed_chunk(signer.as_mut(), &chunk, &mut output) {
+                            Ok(()) => Poll::Ready(Some(Ok(output.freeze()))),
+                            Err(e) => Poll::Ready(Some(Err(e))),
+                        };
+                    }
+
+                    match this.inner.as_mut().poll_data(cx) {
+                        Poll::Ready(Some(Ok(data))) => {
+                            *this.inner_body_bytes_read_so_far += data.len();
+                            this.buffer.extend_from_slice(&data);
+                        }
+                        Poll::Ready(None) => {
+                            if let Err(err) = check_stream_length(
+                                this.options,
+                                *this.inner_body_bytes_read_so_far,
+                            ) {
+                                return Poll::Ready(Some(Err(err)));
+                            }
+
+                            tracing::trace!(
+                                "no more chunk data, writing remaining and final chunks"
+                            );
+                            let mut output = BytesMut::new();
+                            let remaining = this.buffer.split();
+                            let result = if remaining.is_empty() {
+                                Ok(())
+                            } else {
+                                write_signed_chunk(signer.as_mut(), &remaining, &mut output)
+                            }
+                            .and_then(|_| write_signed_chunk(signer.as_mut(), &[], &mut output));
+                            if let Err(e) = result {
+                                return Poll::Ready(Some(Err(e)));
+                            }
+
+                            if this.options.trailer_lengths.is_empty() {
+                                *this.state = AwsChunkedBodyState::Closed;
+                                output.extend_from_slice(CRLF.as_bytes());
+                            } else {
+                                *this.state = AwsChunkedBodyState::WritingSignedTrailers;
+                            }
+                            return Poll::Ready(Some(Ok(output.freeze())));
+                        }
+                        Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+            }
+            AwsChunkedBodyState::WritingSignedTrailers => {
+                return match this.inner.poll_trailers(cx) {
+                    Poll::Ready(Ok(trailers)) => {
+                        *this.state = AwsChunkedBodyState::Closed;
+                        let expected_length = total_rendered_length_of_trailers(trailers.as_ref());
+                        let actual_length = this.options.total_trailer_length();
+
+                        if expected_length != actual_length {
+                            let err =
+                                Box::new(AwsChunkedBodyError::ReportedTrailerLengthMismatch {
+                                    actual: actual_length,
+                                    expected: expected_length,
+                                });
+                            return Poll::Ready(Some(Err(err)));
+                        }
+
+                        let trailers = trailers.unwrap_or_default();
+                        let signer = this.signer.as_mut().expect("signed bodies have a signer");
+                        let signature = match signer.trailer_signature(&trailers) {
+                            Ok(signature) => signature,
+                            Err(e) => return Poll::Ready(Some(Err(e))),
+                        };
+
+                        let mut trailers =
+                            trailers_as_aws_chunked_bytes(Some(trailers), actual_length + 1);
+                        trailers.extend_from_slice(
+                            format!("{TRAILER_SIGNATURE_PREFIX}{signature}{CRLF}").as_bytes(),
+                        );
+                        // Insert the final CRLF to close the body
+                        trailers.extend_from_slice(CRLF.as_bytes());
+
+                        Poll::Ready(Some(Ok(trailers.into())))
+                    }
+                    Poll::Pending => Poll::Pending,
+                    Poll::Ready(Err(e)) => Poll::Ready(Some(Err(e))),
+                };
+            }
             AwsChunkedBodyState::Closed => Poll::Ready(None),
         }
     }
@@ -297,7 +651,23 @@ where
     }
 
     fn size_hint(&self) -> SizeHint {
-        SizeHint::with_exact(self.encoded_length())
+        match self.encoded_length() {
+            Some(length) => SizeHint::with_exact(length),
+            None => SizeHint::default(),
+        }
+    }
+}
+
+/// Returns an error if the stream's length is known and doesn't match the number of bytes read.
+fn check_stream_length(options: &AwsChunkedBodyOptions, bytes_read: usize) -> Result<(), BoxError> {
+    match options.stream_length {
+        Some(expected) if expected != bytes_read as u64 => {
+            Err(Box::new(AwsChunkedBodyError::StreamLengthMismatch {
+                actual: bytes_read as u64,
+                expected,
+            }))
+        }
+        _ => Ok(()),
     }
 }
 
@@ -351,9 +721,10 @@ where
 mod tests {
     use super::{
         total_rendered_length_of_trailers, trailers_as_aws_chunked_bytes, AwsChunkedBody,
-        AwsChunkedBodyOptions, CHUNK_TERMINATOR, CRLF,
+        AwsChunkedBodyOptions, DeferredChunkSigner, SignChunk, CHUNK_TERMINATOR, CRLF,
     };
 
+    use aws_smithy_runtime_api::box_error::BoxError;
     use aws_smithy_types::body::SdkBody;
     use bytes::{Buf, Bytes};
     use bytes_utils::SegmentedBuf;
@@ -580,6 +951,200 @@ mod tests {
         );
     }
 
+    // Signs each chunk with a signature derived from its length and position in the body
+    #[derive(Debug, Default)]
+    struct TestChunkSigner {
+        count: usize,
+    }
+
+    impl SignChunk for TestChunkSigner {
+        fn chunk_signature(&mut self, chunk: &[u8]) -> Result<String, BoxError> {
+            self.count += 1;
+            Ok(format!("{:0>32}{:0>32}", self.count, chunk.len()))
+        }
+
+        fn trailer_signature(&mut self, trailers: &HeaderMap) -> Result<String, BoxError> {
+            self.count += 1;
+            Ok(format!("{:0>32}{:0>32}", self.count, trailers.len()))
+        }
+    }
+
+    struct TrailerBody {
+        data: Option<Bytes>,
+        trailers: Option<HeaderMap>,
+    }
+
+    impl Body for TrailerBody {
+        type Data = Bytes;
+        type Error = aws_smithy_types::body::Error;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            Poll::Ready(self.data.take().map(Ok))
+        }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
+            Poll::Ready(Ok(self.trailers.take()))
+        }
+    }
+
+    async fn read_to_string<B>(mut body: AwsChunkedBody<B>) -> String
+    where
+        B: Body<Data = Bytes, Error = aws_smithy_types::body::Error> + Unpin,
+    {
+        let expected_length = body.size_hint().exact().unwrap();
+        let mut output = SegmentedBuf::new();
+        while let Some(buf) = body.data().await {
+            output.push(buf.unwrap());
+        }
+
+        let mut actual_output = String::new();
+        output
+            .reader()
+            .read_to_string(&mut actual_output)
+            .expect("Doesn't cause IO errors");
+        assert_eq!(expected_length, actual_output.len() as u64);
+        actual_output
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed() {
+        let input = SputteringBody {
+            parts: vec![
+                Some(Bytes::from_static(b"chunk 1, ")),
+                None,
+                Some(Bytes::from_static(b"chunk 2, ")),
+                Some(Bytes::from_static(b"chunk 3")),
+            ],
+            cursor: 0,
+            delay_in_millis: 10,
+        };
+        let opts =
+            AwsChunkedBodyOptions::new(input.len() as u64, Vec::new()).with_signed_chunk_size(10);
+        let body = AwsChunkedBody::new_signed(input, opts, TestChunkSigner::default());
+
+        let expected_output = "\
+            a;chunk-signature=0000000000000000000000000000000100000000000000000000000000000010\r\n\
+            chunk 1, c\r\n\
+            a;chunk-signature=0000000000000000000000000000000200000000000000000000000000000010\r\n\
+            hunk 2, ch\r\n\
+            5;chunk-signature=0000000000000000000000000000000300000000000000000000000000000005\r\n\
+            unk 3\r\n\
+            0;chunk-signature=0000000000000000000000000000000400000000000000000000000000000000\r\n\
+            \r\n";
+        assert_eq!(expected_output, read_to_string(body).await);
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_with_trailers() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-amz-checksum-crc32", HeaderValue::from_static("i9aeUg=="));
+        let trailer_len = total_rendered_length_of_trailers(Some(&trailers)) - CRLF.len() as u64;
+        let input = TrailerBody {
+            data: Some(Bytes::from_static(b"Hello world")),
+            trailers: Some(trailers),
+        };
+        let opts = AwsChunkedBodyOptions::new(11, vec![trailer_len]);
+        let body = AwsChunkedBody::new_signed(input, opts, TestChunkSigner::default());
+
+        let expected_output = "\
+            b;chunk-signature=0000000000000000000000000000000100000000000000000000000000000011\r\n\
+            Hello world\r\n\
+            0;chunk-signature=0000000000000000000000000000000200000000000000000000000000000000\r\n\
+            x-amz-checksum-crc32:i9aeUg==\r\n\
+            x-amz-trailer-signature:0000000000000000000000000000000300000000000000000000000000000001\r\n\
+            \r\n";
+        assert_eq!(expected_output, read_to_string(body).await);
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_empty_body() {
+        let opts = AwsChunkedBodyOptions::new(0, Vec::new());
+        let body = AwsChunkedBody::new_signed(SdkBody::empty(), opts, TestChunkSigner::default());
+
+        let expected_output = "\
+            0;chunk-signature=0000000000000000000000000000000100000000000000000000000000000000\r\n\
+            \r\n";
+        assert_eq!(expected_output, read_to_string(body).await);
+    }
+
+    #[tokio::test]
+    async fn test_deferred_chunk_signer() {
+        let (signer, sender) = DeferredChunkSigner::new();
+        sender.send(Box::new(TestChunkSigner::default())).unwrap();
+        let opts = AwsChunkedBodyOptions::new(0, Vec::new());
+        let body = AwsChunkedBody::new_signed(SdkBody::empty(), opts, signer);
+        assert!(read_to_string(body).await.starts_with("0;chunk-signature="));
+
+        let (signer, _sender) = DeferredChunkSigner::new();
+        let opts = AwsChunkedBodyOptions::new(0, Vec::new());
+        let mut body = AwsChunkedBody::new_signed(SdkBody::empty(), opts, signer);
+        let err = body.data().await.unwrap().expect_err("no signer was sent");
+        assert!(err.to_string().contains("no chunk signer was sent"));
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_unsized_body() {
+        struct FramedBody {
+            frames: Vec<Bytes>,
+            trailers: Option<HeaderMap>,
+        }
+
+        impl Body for FramedBody {
+            type Data = Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_data(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+                if self.frames.is_empty() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Ready(Some(Ok(self.frames.remove(0))))
+                }
+            }
+
+            fn poll_trailers(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
+                Poll::Ready(Ok(self.trailers.take()))
+            }
+        }
+
+        let mut trailers = HeaderMap::new();
+        trailers.insert("foo", HeaderValue::from_static("bar"));
+        let input = FramedBody {
+            frames: vec![
+                Bytes::from_static(b"chunk 1, "),
+                Bytes::new(),
+                Bytes::from_static(b"chunk 2"),
+            ],
+            trailers: Some(trailers),
+        };
+        let mut body = AwsChunkedBody::new(input, AwsChunkedBodyOptions::new_unsized(vec![7]));
+        assert_eq!(None, body.size_hint().exact());
+
+        let mut output = SegmentedBuf::new();
+        while let Some(buf) = body.data().await {
+            output.push(buf.unwrap());
+        }
+        let mut actual_output = String::new();
+        output
+            .reader()
+            .read_to_string(&mut actual_output)
+            .expect("Doesn't cause IO errors");
+
+        let expected_output = "9\r\nchunk 1, \r\n7\r\nchunk 2\r\n0\r\nfoo:bar\r\n\r\n";
+        assert_eq!(expected_output, actual_output);
+    }
+
     #[tokio::test]
     async fn test_total_rendered_length_of_trailers() {
         let mut trailers = HeaderMap::new();
@@ -16,6 +16,9 @@
 /// Supporting code for authentication in the AWS SDK.
 pub mod auth;
 
+/// Supporting code for `aws-chunked` content encoding in the AWS SDK.
+pub mod content_encoding;
+
 /// Supporting code for recursion detection in the AWS SDK.
 pub mod recursion_detection;
 
@@ -0,0 +1,593 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! An [`aws-smithy-http-server`](aws_smithy_http_server) plugin that verifies the SigV4 signature
+//! of every request.
+//!
+//! Requests without a valid signature are rejected before they reach the operation handler.
+//! Verified requests carry a [`VerifiedSignature`] in their extensions, so handlers can tell who
+//! signed them.
+//!
+//! Since the hash of the body is part of the signature, the body of every request is read into
+//! memory before it's verified. Requests that are unsigned, or whose signature is malformed or
+//! expired, are rejected before their body is read, and bodies larger than
+//! [`SigV4VerificationPlugin::max_body_size`] are rejected with a `413` response.
+//!
+//! Requests whose body isn't covered by their signature, because they were signed with
+//! `UNSIGNED-PAYLOAD` or a streaming payload hash, are rejected unless
+//! [`VerificationSettings::allow_unsigned_payload`] is set. When it is, the body of those
+//! requests reaches the handler without being verified, including the chunk signatures of
+//! `aws-chunked` bodies.
+//!
+//! # Example: Verifying the requests of a service
+//!
+//! ```rust,ignore
+//! use aws_sigv4::http_request::VerificationSettings;
+//! use aws_sigv4_server::{SigV4VerificationExt, SigV4VerificationPlugin};
+//! use aws_smithy_http_server::plugin::HttpPlugins;
+//!
+//! let mut settings = VerificationSettings::default();
+//! settings.name = Some("pokemon".into());
+//! let verification = SigV4VerificationPlugin::new(|access_key_id: &str| {
+//!     // Look up the credentials of `access_key_id`
+//!     None
+//! })
+//! .settings(settings);
+//!
+//! let http_plugins = HttpPlugins::new().sigv4_verification(verification);
+//! let app = PokemonService::builder_with_plugins(http_plugins, IdentityPlugin)
+//!     /* handlers */
+//!     .build()
+//!     .unwrap();
+//! ```
+
+#![warn(
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    missing_debug_implementations,
+    rust_2018_idioms,
+    unreachable_pub
+)]
+
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{
+    pre_verify, verify, SignableBody, SignableRequest, VerificationError, VerificationSettings,
+    VerifiedSignature,
+};
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_http_server::body::{to_boxed, BoxBody};
+use aws_smithy_http_server::plugin::{HttpMarker, HttpPlugins, Plugin, PluginStack};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use http::{HeaderValue, StatusCode};
+use http_body::Body as HttpBody;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Service, ServiceExt};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type CredentialsLookup = dyn Fn(&str) -> Option<Credentials> + Send + Sync;
+
+/// The default maximum size of request bodies, in bytes.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// A [`Plugin`] which applies [`SigV4VerificationService`] to every operation.
+#[derive(Clone)]
+pub struct SigV4VerificationPlugin {
+    settings: Arc<VerificationSettings>,
+    credentials: Arc<CredentialsLookup>,
+    time_source: SharedTimeSource,
+    max_body_size: usize,
+}
+
+impl fmt::Debug for SigV4VerificationPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigV4VerificationPlugin")
+            .field("settings", &self.settings)
+            .field("credentials", &"** lookup function **")
+            .field("time_source", &self.time_source)
+            .field("max_body_size", &self.max_body_size)
+            .finish()
+    }
+}
+
+impl SigV4VerificationPlugin {
+    /// Creates a new plugin that verifies requests with the default [`VerificationSettings`].
+    ///
+    /// `credentials` is called with the access key ID of every signed request, and must return
+    /// the matching credentials, or `None` if the access key is unknown.
+    pub fn new(credentials: impl Fn(&str) -> Option<Credentials> + Send + Sync + 'static) -> Self {
+        Self {
+            settings: Arc::new(VerificationSettings::default()),
+            credentials: Arc::new(credentials),
+            time_source: SharedTimeSource::default(),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Sets the settings requests are verified with.
+    pub fn settings(mut self, settings: VerificationSettings) -> Self {
+        self.settings = Arc::new(settings);
+        self
+    }
+
+    /// Sets the time source used to check the signing time of requests.
+    pub fn time_source(mut self, time_source: SharedTimeSource) -> Self {
+        self.time_source = time_source;
+        self
+    }
+
+    /// Sets the maximum size of request bodies, in bytes. Defaults to [`DEFAULT_MAX_BODY_SIZE`].
+    ///
+    /// The body of every request is read into memory to verify its signature, so requests with a
+    /// larger body are rejected with a `413` response.
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for SigV4VerificationPlugin {
+    type Output = SigV4VerificationService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        SigV4VerificationService {
+            inner,
+            plugin: self.clone(),
+        }
+    }
+}
+
+impl HttpMarker for SigV4VerificationPlugin {}
+
+/// An extension trait for applying [`SigV4VerificationPlugin`].
+pub trait SigV4VerificationExt<CurrentPlugin> {
+    /// Verifies the SigV4 signature of every request before it reaches its operation.
+    fn sigv4_verification(
+        self,
+        plugin: SigV4VerificationPlugin,
+    ) -> HttpPlugins<PluginStack<SigV4VerificationPlugin, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> SigV4VerificationExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn sigv4_verification(
+        self,
+        plugin: SigV4VerificationPlugin,
+    ) -> HttpPlugins<PluginStack<SigV4VerificationPlugin, CurrentPlugin>> {
+        self.push(plugin)
+    }
+}
+
+/// A [`Service`] which verifies the SigV4 signature of requests before passing them to the inner
+/// service.
+///
+/// The request body is read in full, since its hash is part of the signature, but only after the
+/// parts of the signature that don't depend on the body have been checked. Requests that fail
+/// verification are rejected with a `400` or `403` response, and requests whose body is too large
+/// with a `413` response. The `x-amzn-errortype` header of the response names the failure.
+#[derive(Clone, Debug)]
+pub struct SigV4VerificationService<S> {
+    inner: S,
+    plugin: SigV4VerificationPlugin,
+}
+
+impl<S> SigV4VerificationService<S> {
+    fn signable_request<'a>(
+        parts: &'a http::request::Parts,
+        body: &'a [u8],
+    ) -> Result<SignableRequest<'a>, VerificationError> {
+        // Headers with values that aren't valid UTF-8 can't be verified, and fail verification if
+        // they were signed.
+        let headers = parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+        SignableRequest::new(
+            parts.method.as_str(),
+            parts.uri.to_string(),
+            headers,
+            SignableBody::Bytes(body),
+        )
+        .map_err(|err| VerificationError::InvalidRequest { source: err.into() })
+    }
+
+    /// Checks the signature of a request before its body has been read.
+    fn pre_verify(&self, parts: &http::request::Parts) -> Result<(), VerificationError> {
+        pre_verify(
+            &Self::signable_request(parts, &[])?,
+            &self.plugin.settings,
+            self.plugin.time_source.now(),
+        )
+    }
+
+    fn verify(
+        &self,
+        parts: &http::request::Parts,
+        body: &[u8],
+    ) -> Result<VerifiedSignature, VerificationError> {
+        let request = Self::signable_request(parts, body)?;
+        verify(
+            &request,
+            &self.plugin.settings,
+            self.plugin.time_source.now(),
+            |access_key_id| (self.plugin.credentials)(access_key_id),
+        )
+    }
+}
+
+impl<B, S> Service<http::Request<B>> for SigV4VerificationService<S>
+where
+    B: HttpBody + From<Bytes> + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            if let Err(error) = this.pre_verify(&parts) {
+                tracing::debug!(%error, "rejected request with an invalid signature");
+                return Ok(rejection(&error));
+            }
+            let body = match read_body(body, &parts.headers, this.plugin.max_body_size).await {
+                Ok(body) => body,
+                Err(ReadBodyError::TooLarge(error)) => {
+                    tracing::debug!(%error, "rejected request with a body that is too large");
+                    return Ok(body_too_large(&error));
+                }
+                Err(ReadBodyError::Failed(source)) => {
+                    return Ok(rejection(&VerificationError::InvalidRequest { source }));
+                }
+            };
+            match this.verify(&parts, &body) {
+                Ok(verified) => {
+                    parts.extensions.insert(verified);
+                    this.inner
+                        .oneshot(http::Request::from_parts(parts, B::from(body)))
+                        .await
+                }
+                Err(error) => {
+                    tracing::debug!(%error, "rejected request with an invalid signature");
+                    Ok(rejection(&error))
+                }
+            }
+        })
+    }
+}
+
+/// The error returned when a request body is larger than
+/// [`SigV4VerificationPlugin::max_body_size`].
+#[derive(Debug)]
+pub struct BodyTooLargeError {
+    max_body_size: usize,
+}
+
+impl BodyTooLargeError {
+    /// Returns the maximum size of request bodies, in bytes.
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_size
+    }
+}
+
+impl fmt::Display for BodyTooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the request body is larger than the maximum of {} bytes",
+            self.max_body_size
+        )
+    }
+}
+
+impl std::error::Error for BodyTooLargeError {}
+
+enum ReadBodyError {
+    TooLarge(BodyTooLargeError),
+    Failed(BoxError),
+}
+
+/// Reads `body` into memory, failing as soon as it's larger than `max_body_size`.
+async fn read_body<B>(
+    body: B,
+    headers: &http::HeaderMap,
+    max_body_size: usize,
+) -> Result<Bytes, ReadBodyError>
+where
+    B: HttpBody,
+    B::Error: Into<BoxError>,
+{
+    let too_large = || ReadBodyError::TooLarge(BodyTooLargeError { max_body_size });
+    let content_length = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+    if matches!(content_length, Some(length) if length > max_body_size as u64) {
+        return Err(too_large());
+    }
+
+    let mut body = std::pin::pin!(body);
+    let mut bytes = BytesMut::new();
+    while let Some(data) = body.as_mut().data().await {
+        let data = data.map_err(|err| ReadBodyError::Failed(err.into()))?;
+        if bytes.len() + data.remaining() > max_body_size {
+            return Err(too_large());
+        }
+        bytes.put(data);
+    }
+    Ok(bytes.freeze())
+}
+
+fn body_too_large(error: &BodyTooLargeError) -> http::Response<BoxBody> {
+    http::Response::builder()
+        .status(StatusCode::PAYLOAD_TOO_LARGE)
+        .header(
+            "x-amzn-errortype",
+            HeaderValue::from_static("RequestEntityTooLarge"),
+        )
+        .body(to_boxed(error.to_string()))
+        .expect("valid response")
+}
+
+/// Converts a verification error into a response, using the error codes of AWS services.
+fn rejection(error: &VerificationError) -> http::Response<BoxBody> {
+    let (status, error_type) = match error {
+        VerificationError::MissingSignature => {
+            (StatusCode::FORBIDDEN, "MissingAuthenticationToken")
+        }
+        VerificationError::MalformedSignature { .. }
+        | VerificationError::UnsupportedAlgorithm { .. }
+        | VerificationError::MissingSignedHeader { .. } => {
+            (StatusCode::BAD_REQUEST, "IncompleteSignature")
+        }
+        VerificationError::UnknownAccessKey { .. } => {
+            (StatusCode::FORBIDDEN, "UnrecognizedClientException")
+        }
+        VerificationError::InvalidSecurityToken => (StatusCode::FORBIDDEN, "InvalidClientTokenId"),
+        VerificationError::RequestTimeTooSkewed { .. } => {
+            (StatusCode::FORBIDDEN, "RequestTimeTooSkewed")
+        }
+        VerificationError::Expired { .. } => (StatusCode::FORBIDDEN, "RequestExpired"),
+        VerificationError::PayloadHashMismatch | VerificationError::UnsignedPayload { .. } => {
+            (StatusCode::BAD_REQUEST, "XAmzContentSHA256Mismatch")
+        }
+        VerificationError::CredentialScopeMismatch { .. }
+        | VerificationError::SignatureMismatch => (StatusCode::FORBIDDEN, "SignatureDoesNotMatch"),
+        VerificationError::InvalidRequest { .. } => (StatusCode::BAD_REQUEST, "InvalidRequest"),
+        _ => (StatusCode::FORBIDDEN, "AccessDenied"),
+    };
+    http::Response::builder()
+        .status(status)
+        .header("x-amzn-errortype", HeaderValue::from_static(error_type))
+        .body(to_boxed(error.to_string()))
+        .expect("valid response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SigV4VerificationPlugin, SigV4VerificationService, DEFAULT_MAX_BODY_SIZE};
+    use aws_credential_types::Credentials;
+    use aws_sigv4::http_request::{
+        sign, PayloadChecksumKind, SignableBody, SignableRequest, SigningSettings,
+        VerifiedSignature,
+    };
+    use aws_sigv4::sign::v4;
+    use aws_smithy_async::time::StaticTimeSource;
+    use aws_smithy_http_server::body::{to_boxed, BoxBody};
+    use aws_smithy_http_server::plugin::Plugin;
+    use std::convert::Infallible;
+    use std::time::{Duration, UNIX_EPOCH};
+    use tower::{service_fn, ServiceExt};
+
+    // 20150830T123600Z
+    const SIGNING_TIME: u64 = 1_440_938_160;
+
+    fn service(
+        max_body_size: usize,
+    ) -> SigV4VerificationService<
+        impl tower::Service<
+                http::Request<hyper::Body>,
+                Response = http::Response<BoxBody>,
+                Error = Infallible,
+                Future = impl Send,
+            > + Clone
+            + Send,
+    > {
+        let inner = service_fn(|request: http::Request<hyper::Body>| async move {
+            let verified = request.extensions().get::<VerifiedSignature>().unwrap();
+            let body = format!("{}:", verified.access_key_id());
+            let body = [
+                body.into_bytes(),
+                hyper::body::to_bytes(request.into_body())
+                    .await
+                    .unwrap()
+                    .to_vec(),
+            ]
+            .concat();
+            Ok::<_, Infallible>(http::Response::new(to_boxed(body)))
+        });
+        let plugin = SigV4VerificationPlugin::new(|access_key_id| {
+            (access_key_id == "ANOTREAL").then(Credentials::for_tests)
+        })
+        .time_source(StaticTimeSource::from_secs(SIGNING_TIME).into())
+        .max_body_size(max_body_size);
+        Plugin::<(), (), _>::apply(&plugin, inner)
+    }
+
+    fn signed_request(body: &'static str) -> http::Request<hyper::Body> {
+        signed_request_with(
+            body,
+            SignableBody::Bytes(body.as_bytes()),
+            SigningSettings::default(),
+        )
+    }
+
+    fn signed_request_with(
+        body: &'static str,
+        signed_body: SignableBody<'_>,
+        settings: SigningSettings,
+    ) -> http::Request<hyper::Body> {
+        let identity = Credentials::for_tests().into();
+        let params = v4::SigningParams::builder()
+            .identity(&identity)
+            .region("us-east-1")
+            .name("service")
+            .time(UNIX_EPOCH + Duration::from_secs(SIGNING_TIME))
+            .settings(settings)
+            .build()
+            .unwrap()
+            .into();
+        let mut request = http::Request::builder()
+            .method("POST")
+            .uri("/operation")
+            .header("host", "example.amazonaws.com")
+            .body(body)
+            .unwrap();
+        let signable = SignableRequest::new(
+            "POST",
+            "/operation",
+            request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.to_str().unwrap())),
+            signed_body,
+        )
+        .unwrap();
+        let (instructions, _) = sign(signable, &params).unwrap().into_parts();
+        instructions.apply_to_request(&mut request);
+        request.map(hyper::Body::from)
+    }
+
+    async fn call(
+        request: http::Request<hyper::Body>,
+    ) -> (http::StatusCode, Option<String>, String) {
+        call_with_max_body_size(request, DEFAULT_MAX_BODY_SIZE).await
+    }
+
+    async fn call_with_max_body_size(
+        request: http::Request<hyper::Body>,
+        max_body_size: usize,
+    ) -> (http::StatusCode, Option<String>, String) {
+        let response = service(max_body_size).oneshot(request).await.unwrap();
+        let status = response.status();
+        let error_type = response
+            .headers()
+            .get("x-amzn-errortype")
+            .map(|value| value.to_str().unwrap().to_owned());
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (
+            status,
+            error_type,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn verified_requests_reach_the_handler() {
+        let (status, error_type, body) = call(signed_request("hello")).await;
+        assert_eq!(http::StatusCode::OK, status);
+        assert_eq!(None, error_type);
+        assert_eq!("ANOTREAL:hello", body);
+    }
+
+    #[tokio::test]
+    async fn unsigned_requests_are_rejected() {
+        let request = signed_request("hello").map(|_| hyper::Body::from("hello"));
+        let (mut parts, body) = request.into_parts();
+        parts.headers.remove("authorization");
+        let (status, error_type, _) = call(http::Request::from_parts(parts, body)).await;
+        assert_eq!(http::StatusCode::FORBIDDEN, status);
+        assert_eq!(Some("MissingAuthenticationToken"), error_type.as_deref());
+    }
+
+    #[tokio::test]
+    async fn tampered_requests_are_rejected() {
+        let request = signed_request("hello").map(|_| hyper::Body::from("goodbye"));
+        let (status, error_type, _) = call(request).await;
+        assert_eq!(http::StatusCode::FORBIDDEN, status);
+        assert_eq!(Some("SignatureDoesNotMatch"), error_type.as_deref());
+    }
+
+    #[tokio::test]
+    async fn requests_with_unsigned_bodies_are_rejected() {
+        let settings = || {
+            let mut settings = SigningSettings::default();
+            settings.payload_checksum_kind = PayloadChecksumKind::XAmzSha256;
+            settings
+        };
+        for signed_body in [
+            SignableBody::UnsignedPayload,
+            SignableBody::StreamingSignedPayload,
+        ] {
+            let request = signed_request_with("hello", signed_body, settings());
+            let (status, error_type, _) = call(request).await;
+            assert_eq!(http::StatusCode::BAD_REQUEST, status);
+            assert_eq!(Some("XAmzContentSHA256Mismatch"), error_type.as_deref());
+        }
+    }
+
+    // Returns a body that fails if it's read
+    fn unreadable_body() -> hyper::Body {
+        let (sender, body) = hyper::Body::channel();
+        sender.abort();
+        body
+    }
+
+    #[tokio::test]
+    async fn unsigned_requests_are_rejected_before_reading_the_body() {
+        let request = signed_request("hello").map(|_| unreadable_body());
+        let (mut parts, body) = request.into_parts();
+        parts.headers.remove("authorization");
+        let (status, error_type, _) = call(http::Request::from_parts(parts, body)).await;
+        assert_eq!(http::StatusCode::FORBIDDEN, status);
+        assert_eq!(Some("MissingAuthenticationToken"), error_type.as_deref());
+
+        let request = signed_request("hello").map(|_| unreadable_body());
+        let (mut parts, body) = request.into_parts();
+        parts.headers.insert(
+            "authorization",
+            http::HeaderValue::from_static("AWS4-HMAC-SHA256 Credential=ANOTREAL"),
+        );
+        let (status, error_type, _) = call(http::Request::from_parts(parts, body)).await;
+        assert_eq!(http::StatusCode::BAD_REQUEST, status);
+        assert_eq!(Some("IncompleteSignature"), error_type.as_deref());
+    }
+
+    #[tokio::test]
+    async fn bodies_larger_than_the_maximum_are_rejected() {
+        let (status, error_type, body) = call_with_max_body_size(signed_request("hello"), 4).await;
+        assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, status);
+        assert_eq!(Some("RequestEntityTooLarge"), error_type.as_deref());
+        assert_eq!(
+            "the request body is larger than the maximum of 4 bytes",
+            body
+        );
+
+        // A body with a `content-length` that is too large isn't read at all
+        let request = signed_request("hello").map(|_| unreadable_body());
+        let (mut parts, body) = request.into_parts();
+        parts
+            .headers
+            .insert("content-length", http::HeaderValue::from_static("5"));
+        let request = http::Request::from_parts(parts, body);
+        let (status, error_type, _) = call_with_max_body_size(request, 4).await;
+        assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, status);
+        assert_eq!(Some("RequestEntityTooLarge"), error_type.as_deref());
+
+        let (status, _, body) = call_with_max_body_size(signed_request("hello"), 5).await;
+        assert_eq!(http::StatusCode::OK, status);
+        assert_eq!("ANOTREAL:hello", body);
+    }
+}
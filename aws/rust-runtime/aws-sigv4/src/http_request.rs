@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! Utilities to sign HTTP requests.
+//! Utilities to sign HTTP requests, and to verify the signatures of received HTTP requests.
 //!
 //! # Example: Signing an HTTP request
 //!
@@ -60,6 +60,7 @@ mod settings;
 mod sign;
 mod uri_path_normalization;
 mod url_escape;
+mod verify;
 
 #[cfg(test)]
 pub(crate) mod test;
@@ -76,6 +77,7 @@ pub use settings::{
 };
 pub use sign::{sign, SignableBody, SignableRequest, SigningInstructions};
 use std::time::SystemTime;
+pub use verify::{pre_verify, verify, VerificationError, VerificationSettings, VerifiedSignature};
 
 // Individual Debug impls are responsible for redacting sensitive fields.
 #[derive(Debug)]
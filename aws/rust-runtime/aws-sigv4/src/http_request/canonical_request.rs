@@ -10,6 +10,7 @@ use crate::http_request::settings::UriPathNormalizationMode;
 use crate::http_request::sign::SignableRequest;
 use crate::http_request::uri_path_normalization::normalize_uri_path;
 use crate::http_request::url_escape::percent_encode_path;
+use crate::http_request::verify::VerificationSettings;
 use crate::http_request::PercentEncodingMode;
 use crate::http_request::{PayloadChecksumKind, SignableBody, SignatureLocation, SigningParams};
 use crate::sign::v4::sha256_hex_string;
@@ -46,8 +47,10 @@ pub(crate) mod param {
 
 pub(crate) const HMAC_256: &str = "AWS4-HMAC-SHA256";
 
-const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+pub(crate) const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
 const STREAMING_UNSIGNED_PAYLOAD_TRAILER: &str = "STREAMING-UNSIGNED-PAYLOAD-TRAILER";
+const STREAMING_SIGNED_PAYLOAD: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD";
+const STREAMING_SIGNED_PAYLOAD_TRAILER: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER";
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct HeaderValues<'a> {
@@ -141,18 +144,11 @@ impl<'a> CanonicalRequest<'a> {
         let creds = params
             .credentials()
             .map_err(|_| CanonicalRequestError::unsupported_identity_type())?;
-        // Path encoding: if specified, re-encode % as %25
-        // Set method and path into CanonicalRequest
-        let path = req.uri().path();
-        let path = match params.settings().uri_path_normalization_mode {
-            UriPathNormalizationMode::Enabled => normalize_uri_path(path),
-            UriPathNormalizationMode::Disabled => Cow::Borrowed(path),
-        };
-        let path = match params.settings().percent_encoding_mode {
-            // The string is already URI encoded, we don't need to encode everything again, just `%`
-            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(&path)),
-            PercentEncodingMode::Single => path,
-        };
+        let path = Self::path(
+            req.uri(),
+            &params.settings().uri_path_normalization_mode,
+            &params.settings().percent_encoding_mode,
+        );
         let payload_hash = Self::payload_hash(req.body());
 
         let date_time = format_date_time(*params.time());
@@ -225,6 +221,91 @@ impl<'a> CanonicalRequest<'a> {
         Ok(creq)
     }
 
+    /// Construct the CanonicalRequest that a received request was signed with, in order to verify
+    /// its signature.
+    ///
+    /// Unlike [`CanonicalRequest::from`], nothing is added to the request: only the headers named
+    /// in `signed_headers` are included, all of which must be present in the request, and the
+    /// query string is used as-is, except for the `X-Amz-Signature` parameter.
+    pub(crate) fn for_verification<'b>(
+        req: &'b SignableRequest<'b>,
+        settings: &VerificationSettings,
+        signed_headers: &[&str],
+        payload_hash: Cow<'b, str>,
+        date_time: String,
+    ) -> Result<CanonicalRequest<'b>, CanonicalRequestError> {
+        let path = Self::path(
+            req.uri(),
+            &settings.uri_path_normalization_mode,
+            &settings.percent_encoding_mode,
+        );
+
+        let mut canonical_headers = HeaderMap::with_capacity(signed_headers.len());
+        for (name, value) in req.headers().iter() {
+            let name = name.to_lowercase();
+            if signed_headers.contains(&name.as_str()) {
+                canonical_headers
+                    .append(HeaderName::from_str(&name)?, normalize_header_value(value)?);
+            }
+        }
+        if signed_headers.contains(&HOST.as_str()) && req.uri().authority().is_some() {
+            Self::insert_host_header(&mut canonical_headers, req.uri());
+        }
+
+        let signed_headers = signed_headers
+            .iter()
+            .map(|name| HeaderName::from_str(name).map(CanonicalHeaderName))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(missing) = signed_headers
+            .iter()
+            .find(|name| !canonical_headers.contains_key(&name.0))
+        {
+            return Err(CanonicalRequestError::missing_signed_header(
+                missing.0.as_str(),
+            ));
+        }
+
+        let mut params: Vec<(Cow<'_, str>, Cow<'_, str>)> =
+            form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+                .filter(|(name, _)| name != param::X_AMZ_SIGNATURE)
+                .collect();
+        params.sort();
+
+        Ok(CanonicalRequest {
+            method: req.method(),
+            path,
+            params: Self::query(req.uri(), params),
+            headers: canonical_headers,
+            values: SignatureValues::Headers(HeaderValues {
+                content_sha256: payload_hash,
+                date_time,
+                security_token: None,
+                signed_headers: SignedHeaders::new(signed_headers),
+                #[cfg(feature = "sigv4a")]
+                region_set: None,
+            }),
+        })
+    }
+
+    fn path<'b>(
+        uri: &'b Uri,
+        uri_path_normalization_mode: &UriPathNormalizationMode,
+        percent_encoding_mode: &PercentEncodingMode,
+    ) -> Cow<'b, str> {
+        // Path encoding: if specified, re-encode % as %25
+        // Set method and path into CanonicalRequest
+        let path = uri.path();
+        let path = match uri_path_normalization_mode {
+            UriPathNormalizationMode::Enabled => normalize_uri_path(path),
+            UriPathNormalizationMode::Disabled => Cow::Borrowed(path),
+        };
+        match percent_encoding_mode {
+            // The string is already URI encoded, we don't need to encode everything again, just `%`
+            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(&path)),
+            PercentEncodingMode::Single => path,
+        }
+    }
+
     fn headers(
         req: &SignableRequest<'_>,
         params: &SigningParams<'_>,
@@ -300,7 +381,7 @@ impl<'a> CanonicalRequest<'a> {
         Ok((signed_headers, canonical_headers))
     }
 
-    fn payload_hash<'b>(body: &'b SignableBody<'b>) -> Cow<'b, str> {
+    pub(crate) fn payload_hash<'b>(body: &'b SignableBody<'b>) -> Cow<'b, str> {
         // Payload hash computation
         //
         // Based on the input body, set the payload_hash of the canonical request:
@@ -310,6 +391,8 @@ impl<'a> CanonicalRequest<'a> {
         // - use `UnsignedPayload`
         // - use `UnsignedPayload` for streaming requests
         // - use `StreamingUnsignedPayloadTrailer` for streaming requests with trailers
+        // - use `StreamingSignedPayload` for streaming requests with signed chunks
+        // - use `StreamingSignedPayloadTrailer` for streaming requests with signed chunks and trailers
         match body {
             SignableBody::Bytes(data) => Cow::Owned(sha256_hex_string(data)),
             SignableBody::Precomputed(digest) => Cow::Borrowed(digest.as_str()),
@@ -317,6 +400,10 @@ impl<'a> CanonicalRequest<'a> {
             SignableBody::StreamingUnsignedPayloadTrailer => {
                 Cow::Borrowed(STREAMING_UNSIGNED_PAYLOAD_TRAILER)
             }
+            SignableBody::StreamingSignedPayload => Cow::Borrowed(STREAMING_SIGNED_PAYLOAD),
+            SignableBody::StreamingSignedPayloadTrailer => {
+                Cow::Borrowed(STREAMING_SIGNED_PAYLOAD_TRAILER)
+            }
         }
     }
 
@@ -351,6 +438,10 @@ impl<'a> CanonicalRequest<'a> {
         // Sort by param name, and then by param value
         params.sort();
 
+        Self::query(uri, params)
+    }
+
+    fn query(uri: &Uri, params: Vec<(Cow<'_, str>, Cow<'_, str>)>) -> Option<String> {
         let mut query = QueryWriter::new(uri);
         query.clear_params();
         for (key, value) in params {
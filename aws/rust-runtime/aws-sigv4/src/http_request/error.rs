@@ -65,6 +65,7 @@ enum CanonicalRequestErrorKind {
     InvalidHeaderValue { source: InvalidHeaderValue },
     InvalidUtf8InHeaderValue { source: Utf8Error },
     InvalidUri { source: InvalidUri },
+    MissingSignedHeader { name: String },
     UnsupportedIdentityType,
 }
 
@@ -81,6 +82,9 @@ impl fmt::Display for CanonicalRequestError {
             InvalidHeaderValue { .. } => write!(f, "invalid header value"),
             InvalidUtf8InHeaderValue { .. } => write!(f, "invalid UTF-8 in header value"),
             InvalidUri { .. } => write!(f, "the uri was invalid"),
+            MissingSignedHeader { ref name } => {
+                write!(f, "the signed header `{name}` is missing from the request")
+            }
             UnsupportedIdentityType => {
                 write!(f, "only AWS credentials are supported for signing")
             }
@@ -96,7 +100,7 @@ impl Error for CanonicalRequestError {
             InvalidHeaderValue { source } => Some(source),
             InvalidUtf8InHeaderValue { source } => Some(source),
             InvalidUri { source } => Some(source),
-            UnsupportedIdentityType => None,
+            MissingSignedHeader { .. } | UnsupportedIdentityType => None,
         }
     }
 }
@@ -113,6 +117,20 @@ impl CanonicalRequestError {
             kind: CanonicalRequestErrorKind::UnsupportedIdentityType,
         }
     }
+
+    pub(crate) fn missing_signed_header(name: &str) -> Self {
+        Self {
+            kind: CanonicalRequestErrorKind::MissingSignedHeader { name: name.into() },
+        }
+    }
+
+    /// Returns the name of the missing signed header, if that's what caused this error.
+    pub(crate) fn as_missing_signed_header(&self) -> Option<&str> {
+        match &self.kind {
+            CanonicalRequestErrorKind::MissingSignedHeader { name } => Some(name),
+            _ => None,
+        }
+    }
 }
 
 impl From<InvalidHeaderName> for CanonicalRequestError {
@@ -91,6 +91,15 @@ pub enum SignableBody<'a> {
 
     /// Set when a streaming body has checksum trailers.
     StreamingUnsignedPayloadTrailer,
+
+    /// Set when a streaming body is sent with the `aws-chunked` content encoding, and each chunk
+    /// is signed with [`sign_chunk`](crate::chunked_encoding::sign_chunk).
+    StreamingSignedPayload,
+
+    /// Set when a streaming body is sent with the `aws-chunked` content encoding, each chunk is
+    /// signed with [`sign_chunk`](crate::chunked_encoding::sign_chunk), and the trailers are signed
+    /// with [`sign_trailers`](crate::chunked_encoding::sign_trailers).
+    StreamingSignedPayloadTrailer,
 }
 
 /// Instructions for applying a signature to an HTTP request.
@@ -232,6 +232,7 @@ fn read(path: &str) -> String {
     v.trim().to_string()
 }
 
+#[derive(Clone)]
 pub(crate) struct TestRequest {
     pub(crate) uri: String,
     pub(crate) method: String,
@@ -239,6 +240,7 @@ pub(crate) struct TestRequest {
     pub(crate) body: TestSignedBody,
 }
 
+#[derive(Clone)]
 pub(crate) enum TestSignedBody {
     Signable(SignableBody<'static>),
     Bytes(Vec<u8>),
@@ -0,0 +1,1051 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::date_time::{format_date, parse_date_time};
+use crate::http_request::canonical_request::{
+    header, param, CanonicalRequest, StringToSign, HMAC_256, UNSIGNED_PAYLOAD,
+};
+use crate::http_request::error::CanonicalRequestError;
+use crate::http_request::{
+    PercentEncodingMode, SignableRequest, SignatureLocation, UriPathNormalizationMode,
+};
+use crate::sign::v4;
+use aws_credential_types::Credentials;
+use http::header::{AUTHORIZATION, HOST};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+const CREDENTIAL_TERMINATOR: &str = "aws4_request";
+// Prefix of the payload hashes of `aws-chunked` bodies, like `STREAMING-AWS4-HMAC-SHA256-PAYLOAD`
+const STREAMING_PREFIX: &str = "STREAMING-";
+const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+// SigV4 presigned requests can be valid for at most 7 days
+const MAX_PRESIGNED_EXPIRATION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// HTTP-specific verification settings
+///
+/// The URI settings must match the [`SigningSettings`](crate::http_request::SigningSettings) that
+/// clients of the service sign requests with.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct VerificationSettings {
+    /// Specifies how the request URL was encoded when it was signed.
+    pub percent_encoding_mode: PercentEncodingMode,
+
+    /// Specifies whether the absolute path component of the URI was normalized when it was signed.
+    pub uri_path_normalization_mode: UriPathNormalizationMode,
+
+    /// The maximum difference between the signing time and the time of verification.
+    ///
+    /// For presigned requests, this only applies to signing times in the future.
+    pub max_clock_skew: Duration,
+
+    /// The region that signatures must be scoped to. Any region is accepted when unset.
+    pub region: Option<Cow<'static, str>>,
+
+    /// The service name that signatures must be scoped to. Any name is accepted when unset.
+    pub name: Option<Cow<'static, str>>,
+
+    /// Whether to accept requests whose body isn't covered by their signature.
+    ///
+    /// These are requests with `UNSIGNED-PAYLOAD` or a streaming payload hash, like
+    /// `STREAMING-AWS4-HMAC-SHA256-PAYLOAD`, in their `x-amz-content-sha256` header, and requests
+    /// without that header that were signed with `UNSIGNED-PAYLOAD`. Their body is not verified,
+    /// so this should only be enabled when the body is verified some other way.
+    pub allow_unsigned_payload: bool,
+}
+
+impl Default for VerificationSettings {
+    fn default() -> Self {
+        Self {
+            percent_encoding_mode: PercentEncodingMode::Double,
+            uri_path_normalization_mode: UriPathNormalizationMode::Enabled,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            region: None,
+            name: None,
+            allow_unsigned_payload: false,
+        }
+    }
+}
+
+/// The details of a successfully verified signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VerifiedSignature {
+    access_key_id: String,
+    region: String,
+    name: String,
+    time: SystemTime,
+    location: SignatureLocation,
+    signature: String,
+}
+
+impl VerifiedSignature {
+    /// Returns the access key ID of the credentials the request was signed with
+    pub fn access_key_id(&self) -> &str {
+        &self.access_key_id
+    }
+
+    /// Returns the region the signature is scoped to
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Returns the service name the signature is scoped to
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the time the request was signed at
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Returns where the signature was found in the request
+    pub fn location(&self) -> SignatureLocation {
+        self.location
+    }
+
+    /// Returns the signature as a lowercase hex string
+    ///
+    /// This is the seed signature for signed `aws-chunked` bodies and event streams.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+}
+
+/// Error verifying the signature of a request
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum VerificationError {
+    /// The request has neither an `authorization` header nor an `X-Amz-Signature` query parameter.
+    MissingSignature,
+
+    /// The signature, or one of the values that go along with it, is malformed.
+    MalformedSignature {
+        /// A description of what is malformed
+        message: Cow<'static, str>,
+    },
+
+    /// The request was signed with an algorithm other than `AWS4-HMAC-SHA256`.
+    UnsupportedAlgorithm {
+        /// The algorithm the request was signed with
+        algorithm: String,
+    },
+
+    /// A header that was signed is missing from the request.
+    MissingSignedHeader {
+        /// The name of the missing header
+        name: String,
+    },
+
+    /// The signature is scoped to a region or service other than the expected ones.
+    CredentialScopeMismatch {
+        /// The region the signature is scoped to
+        region: String,
+        /// The service name the signature is scoped to
+        name: String,
+    },
+
+    /// No credentials were found for the access key ID the request was signed with.
+    UnknownAccessKey {
+        /// The access key ID the request was signed with
+        access_key_id: String,
+    },
+
+    /// The security token of the request doesn't match the session token of the credentials.
+    InvalidSecurityToken,
+
+    /// The signing time is too far from the time of verification.
+    RequestTimeTooSkewed {
+        /// The time the request was signed at
+        signing_time: SystemTime,
+        /// The time of verification
+        now: SystemTime,
+    },
+
+    /// The presigned request has expired.
+    Expired {
+        /// The time the presigned request expired at
+        expiration: SystemTime,
+        /// The time of verification
+        now: SystemTime,
+    },
+
+    /// The `x-amz-content-sha256` header doesn't match the request body.
+    PayloadHashMismatch,
+
+    /// The request body isn't covered by the signature, and
+    /// [`VerificationSettings::allow_unsigned_payload`] isn't set.
+    UnsignedPayload {
+        /// The value of the `x-amz-content-sha256` header
+        content_sha256: String,
+    },
+
+    /// The signature doesn't match the signature calculated for the request.
+    SignatureMismatch,
+
+    /// The request couldn't be read, or converted into a canonical request.
+    InvalidRequest {
+        /// The underlying error
+        source: Box<dyn Error + Send + Sync + 'static>,
+    },
+}
+
+impl VerificationError {
+    fn malformed(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::MalformedSignature {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VerificationError::*;
+        match self {
+            MissingSignature => write!(f, "the request is not signed"),
+            MalformedSignature { message } => write!(f, "malformed signature: {message}"),
+            UnsupportedAlgorithm { algorithm } => {
+                write!(f, "unsupported signing algorithm `{algorithm}`")
+            }
+            MissingSignedHeader { name } => {
+                write!(f, "the signed header `{name}` is missing from the request")
+            }
+            CredentialScopeMismatch { region, name } => write!(
+                f,
+                "the signature is scoped to region `{region}` and service `{name}`, which were not expected"
+            ),
+            UnknownAccessKey { access_key_id } => {
+                write!(f, "no credentials were found for access key ID `{access_key_id}`")
+            }
+            InvalidSecurityToken => write!(f, "the security token is invalid"),
+            RequestTimeTooSkewed { signing_time, now } => write!(
+                f,
+                "the signing time ({signing_time:?}) is too far from the current time ({now:?})"
+            ),
+            Expired { expiration, now } => write!(
+                f,
+                "the presigned request expired at {expiration:?}, and the current time is {now:?}"
+            ),
+            PayloadHashMismatch => {
+                write!(f, "the x-amz-content-sha256 header doesn't match the body")
+            }
+            UnsignedPayload { content_sha256 } => write!(
+                f,
+                "the body must be signed, but the x-amz-content-sha256 header is `{content_sha256}`"
+            ),
+            SignatureMismatch => write!(
+                f,
+                "the signature doesn't match the signature calculated for the request"
+            ),
+            InvalidRequest { .. } => write!(f, "failed to read the request"),
+        }
+    }
+}
+
+impl Error for VerificationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            VerificationError::InvalidRequest { source } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<CanonicalRequestError> for VerificationError {
+    fn from(err: CanonicalRequestError) -> Self {
+        match err.as_missing_signed_header() {
+            Some(name) => Self::MissingSignedHeader { name: name.into() },
+            None => Self::InvalidRequest { source: err.into() },
+        }
+    }
+}
+
+/// The signature of a request, along with the values that were signed with it.
+struct ParsedSignature {
+    location: SignatureLocation,
+    credential: String,
+    signed_headers: String,
+    signature: String,
+    date_time: String,
+    expires: Option<String>,
+    security_token: Option<String>,
+}
+
+impl ParsedSignature {
+    fn from_request(request: &SignableRequest<'_>) -> Result<Self, VerificationError> {
+        if let Some(authorization) = find_header(request, AUTHORIZATION.as_str()) {
+            Self::from_headers(request, authorization)
+        } else {
+            let params: Vec<(Cow<'_, str>, Cow<'_, str>)> =
+                form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+                    .collect();
+            if params
+                .iter()
+                .any(|(name, _)| name == param::X_AMZ_SIGNATURE)
+            {
+                Self::from_query_params(&params)
+            } else {
+                Err(VerificationError::MissingSignature)
+            }
+        }
+    }
+
+    fn from_headers(
+        request: &SignableRequest<'_>,
+        authorization: &str,
+    ) -> Result<Self, VerificationError> {
+        // The authorization header is formatted as:
+        // `<algorithm> Credential=<credential>, SignedHeaders=<signed headers>, Signature=<signature>`
+        let (algorithm, components) = authorization
+            .trim()
+            .split_once(' ')
+            .unwrap_or((authorization.trim(), ""));
+        check_algorithm(algorithm)?;
+
+        let (mut credential, mut signed_headers, mut signature) = (None, None, None);
+        for component in components.split(',') {
+            let (name, value) = component.trim().split_once('=').ok_or_else(|| {
+                VerificationError::malformed("the authorization header is malformed")
+            })?;
+            match name {
+                "Credential" => credential = Some(value),
+                "SignedHeaders" => signed_headers = Some(value),
+                "Signature" => signature = Some(value),
+                _ => {}
+            }
+        }
+
+        let missing = |name: &str| {
+            VerificationError::malformed(format!("the authorization header is missing `{name}`"))
+        };
+        let date_time = find_header(request, header::X_AMZ_DATE).ok_or_else(|| {
+            VerificationError::malformed(format!("the `{}` header is missing", header::X_AMZ_DATE))
+        })?;
+        Ok(Self {
+            location: SignatureLocation::Headers,
+            credential: credential.ok_or_else(|| missing("Credential"))?.into(),
+            signed_headers: signed_headers
+                .ok_or_else(|| missing("SignedHeaders"))?
+                .into(),
+            signature: signature.ok_or_else(|| missing("Signature"))?.into(),
+            date_time: date_time.into(),
+            expires: None,
+            security_token: find_header(request, header::X_AMZ_SECURITY_TOKEN).map(Into::into),
+        })
+    }
+
+    fn from_query_params(
+        params: &[(Cow<'_, str>, Cow<'_, str>)],
+    ) -> Result<Self, VerificationError> {
+        let find = |name: &str| {
+            params
+                .iter()
+                .find(|(param, _)| param == name)
+                .map(|(_, value)| value.to_string())
+        };
+        let require = |name: &str| {
+            find(name).ok_or_else(|| {
+                VerificationError::malformed(format!("the `{name}` query parameter is missing"))
+            })
+        };
+
+        check_algorithm(&require(param::X_AMZ_ALGORITHM)?)?;
+        Ok(Self {
+            location: SignatureLocation::QueryParams,
+            credential: require(param::X_AMZ_CREDENTIAL)?,
+            signed_headers: require(param::X_AMZ_SIGNED_HEADERS)?,
+            signature: require(param::X_AMZ_SIGNATURE)?,
+            date_time: require(param::X_AMZ_DATE)?,
+            expires: Some(require(param::X_AMZ_EXPIRES)?),
+            security_token: find(param::X_AMZ_SECURITY_TOKEN),
+        })
+    }
+}
+
+fn check_algorithm(algorithm: &str) -> Result<(), VerificationError> {
+    if algorithm == HMAC_256 {
+        Ok(())
+    } else {
+        Err(VerificationError::UnsupportedAlgorithm {
+            algorithm: algorithm.into(),
+        })
+    }
+}
+
+fn find_header<'a>(request: &'a SignableRequest<'_>, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+/// The parsed `<access key ID>/<date>/<region>/<service>/aws4_request` credential of a signature.
+struct CredentialScope<'a> {
+    access_key_id: &'a str,
+    date: &'a str,
+    region: &'a str,
+    name: &'a str,
+}
+
+impl<'a> CredentialScope<'a> {
+    fn parse(credential: &'a str) -> Result<Self, VerificationError> {
+        let malformed = || VerificationError::malformed("the credential scope is malformed");
+        let mut parts = credential.rsplitn(5, '/');
+        let terminator = parts.next().ok_or_else(malformed)?;
+        let name = parts.next().ok_or_else(malformed)?;
+        let region = parts.next().ok_or_else(malformed)?;
+        let date = parts.next().ok_or_else(malformed)?;
+        let access_key_id = parts.next().ok_or_else(malformed)?;
+        if terminator != CREDENTIAL_TERMINATOR || access_key_id.is_empty() {
+            return Err(malformed());
+        }
+        Ok(Self {
+            access_key_id,
+            date,
+            region,
+            name,
+        })
+    }
+}
+
+fn check_time(
+    signature: &ParsedSignature,
+    signing_time: SystemTime,
+    now: SystemTime,
+    max_clock_skew: Duration,
+) -> Result<(), VerificationError> {
+    let too_skewed = || VerificationError::RequestTimeTooSkewed { signing_time, now };
+    match signing_time.duration_since(now) {
+        // The request was signed in the future
+        Ok(skew) if skew > max_clock_skew => return Err(too_skewed()),
+        Ok(_) => {}
+        Err(_) => {
+            let age = now.duration_since(signing_time).unwrap_or_default();
+            if signature.expires.is_none() && age > max_clock_skew {
+                return Err(too_skewed());
+            }
+        }
+    }
+
+    if let Some(expires) = &signature.expires {
+        let expires_in = expires
+            .parse::<u64>()
+            .ok()
+            .map(Duration::from_secs)
+            .filter(|expires_in| *expires_in <= MAX_PRESIGNED_EXPIRATION)
+            .ok_or_else(|| {
+                VerificationError::malformed(format!(
+                    "`{}` must be a number of seconds no greater than {}",
+                    param::X_AMZ_EXPIRES,
+                    MAX_PRESIGNED_EXPIRATION.as_secs()
+                ))
+            })?;
+        let expiration = signing_time + expires_in;
+        if now > expiration {
+            return Err(VerificationError::Expired { expiration, now });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the payload hashes the request may have been signed with.
+fn payload_hashes<'a>(
+    request: &'a SignableRequest<'a>,
+    settings: &VerificationSettings,
+) -> Result<Vec<Cow<'a, str>>, VerificationError> {
+    // The caller may not have the body, for example when it's streamed to the handler, in which
+    // case it can't be checked against the payload hash of the request.
+    let body_hash = CanonicalRequest::payload_hash(request.body());
+    let body_is_hashed = is_sha256_hex(&body_hash);
+    if let Some(content_sha256) = find_header(request, header::X_AMZ_CONTENT_SHA_256) {
+        let is_unsigned =
+            content_sha256 == UNSIGNED_PAYLOAD || content_sha256.starts_with(STREAMING_PREFIX);
+        if is_unsigned {
+            if body_is_hashed && !settings.allow_unsigned_payload {
+                return Err(VerificationError::UnsignedPayload {
+                    content_sha256: content_sha256.into(),
+                });
+            }
+        } else if body_is_hashed
+            && !constant_time_eq(content_sha256.as_bytes(), body_hash.as_bytes())
+        {
+            return Err(VerificationError::PayloadHashMismatch);
+        }
+        return Ok(vec![Cow::Borrowed(content_sha256)]);
+    }
+
+    let mut hashes = vec![body_hash];
+    if settings.allow_unsigned_payload && hashes[0] != UNSIGNED_PAYLOAD {
+        hashes.push(Cow::Borrowed(UNSIGNED_PAYLOAD));
+    }
+    Ok(hashes)
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Verifies the SigV4 signature of the given `request`.
+///
+/// The signature is read from the `authorization` header or, for presigned requests, from the
+/// `X-Amz-Signature` query parameter. `credentials` is called with the access key ID the request
+/// was signed with, and must return the matching credentials, or `None` if the access key is
+/// unknown. If the credentials have a session token, the request must carry the same security token.
+///
+/// The request body is used to calculate the payload hash unless the request has an
+/// `x-amz-content-sha256` header, in which case the header must match the body when it's
+/// given as [`SignableBody::Bytes`](crate::http_request::SignableBody::Bytes) or
+/// [`SignableBody::Precomputed`](crate::http_request::SignableBody::Precomputed). Requests signed with
+/// `UNSIGNED-PAYLOAD` or a streaming payload hash are rejected for such bodies, unless
+/// [`VerificationSettings::allow_unsigned_payload`] is set.
+///
+/// # Example: Verifying an HTTP request
+///
+/// ```rust
+/// use aws_credential_types::Credentials;
+/// use aws_sigv4::http_request::{verify, SignableBody, SignableRequest, VerificationSettings};
+/// use std::time::SystemTime;
+///
+/// let request = SignableRequest::new(
+///     "GET",
+///     "/",
+///     [
+///         ("host", "example.amazonaws.com"),
+///         ("x-amz-date", "20150830T123600Z"),
+///         ("authorization", "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"),
+///     ]
+///     .into_iter(),
+///     SignableBody::Bytes(&[]),
+/// )
+/// .expect("valid request");
+///
+/// let result = verify(&request, &VerificationSettings::default(), SystemTime::now(), |access_key_id| {
+///     (access_key_id == "AKIDEXAMPLE").then(|| Credentials::new(
+///         "AKIDEXAMPLE",
+///         "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+///         None,
+///         None,
+///         "hardcoded-credentials",
+///     ))
+/// });
+/// // The request was signed too long ago.
+/// assert!(result.is_err());
+/// ```
+pub fn verify(
+    request: &SignableRequest<'_>,
+    settings: &VerificationSettings,
+    now: SystemTime,
+    credentials: impl FnOnce(&str) -> Option<Credentials>,
+) -> Result<VerifiedSignature, VerificationError> {
+    let signature = ParsedSignature::from_request(request)?;
+    let scope = CredentialScope::parse(&signature.credential)?;
+    let signing_time = check_signature(&signature, &scope, settings, now)?;
+    let signed_headers: Vec<&str> = signature.signed_headers.split(';').collect();
+
+    let creds =
+        credentials(scope.access_key_id).ok_or_else(|| VerificationError::UnknownAccessKey {
+            access_key_id: scope.access_key_id.into(),
+        })?;
+    if let Some(session_token) = creds.session_token() {
+        let matches = matches!(
+            signature.security_token.as_deref(),
+            Some(token) if constant_time_eq(token.as_bytes(), session_token.as_bytes())
+        );
+        if !matches {
+            return Err(VerificationError::InvalidSecurityToken);
+        }
+    }
+
+    let signing_key = v4::generate_signing_key(
+        creds.secret_access_key(),
+        signing_time,
+        scope.region,
+        scope.name,
+    );
+    for payload_hash in payload_hashes(request, settings)? {
+        let creq = CanonicalRequest::for_verification(
+            request,
+            settings,
+            &signed_headers,
+            payload_hash,
+            signature.date_time.clone(),
+        )?;
+        let encoded_creq = v4::sha256_hex_string(creq.to_string().as_bytes());
+        let string_to_sign =
+            StringToSign::new_v4(signing_time, scope.region, scope.name, &encoded_creq).to_string();
+        let calculated = v4::calculate_signature(&signing_key, string_to_sign.as_bytes());
+        tracing::trace!(canonical_request = %creq, string_to_sign = %string_to_sign, "calculated verification parameters");
+
+        if constant_time_eq(calculated.as_bytes(), signature.signature.as_bytes()) {
+            return Ok(VerifiedSignature {
+                access_key_id: scope.access_key_id.into(),
+                region: scope.region.into(),
+                name: scope.name.into(),
+                time: signing_time,
+                location: signature.location,
+                signature: calculated,
+            });
+        }
+    }
+    Err(VerificationError::SignatureMismatch)
+}
+
+/// Checks the parts of the SigV4 signature of `request` that don't depend on its body or on the
+/// credentials it was signed with.
+///
+/// This rejects requests that are unsigned, have a malformed signature, are signed for another
+/// region or service, or were signed too long ago. It's cheap enough to run before the body of the
+/// request has been read, and ignores the body of `request`, so the body can be given as
+/// [`SignableBody::Bytes(&[])`](crate::http_request::SignableBody::Bytes). Requests that pass this
+/// check must still be verified with [`verify`].
+pub fn pre_verify(
+    request: &SignableRequest<'_>,
+    settings: &VerificationSettings,
+    now: SystemTime,
+) -> Result<(), VerificationError> {
+    let signature = ParsedSignature::from_request(request)?;
+    let scope = CredentialScope::parse(&signature.credential)?;
+    check_signature(&signature, &scope, settings, now).map(|_| ())
+}
+
+/// Checks the signing time, credential scope and signed headers of a signature, and returns its
+/// signing time.
+fn check_signature(
+    signature: &ParsedSignature,
+    scope: &CredentialScope<'_>,
+    settings: &VerificationSettings,
+    now: SystemTime,
+) -> Result<SystemTime, VerificationError> {
+    let signing_time = parse_date_time(&signature.date_time)
+        .ok_or_else(|| VerificationError::malformed("the signing time is malformed"))?;
+    if format_date(signing_time) != scope.date {
+        return Err(VerificationError::malformed(
+            "the credential scope date doesn't match the signing time",
+        ));
+    }
+    let region_mismatch = matches!(settings.region.as_deref(), Some(r) if r != scope.region);
+    let name_mismatch = matches!(settings.name.as_deref(), Some(n) if n != scope.name);
+    if region_mismatch || name_mismatch {
+        return Err(VerificationError::CredentialScopeMismatch {
+            region: scope.region.into(),
+            name: scope.name.into(),
+        });
+    }
+    check_time(signature, signing_time, now, settings.max_clock_skew)?;
+
+    if !signature
+        .signed_headers
+        .split(';')
+        .any(|name| name == HOST.as_str())
+    {
+        return Err(VerificationError::malformed(
+            "the host header must be signed",
+        ));
+    }
+    Ok(signing_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::date_time::test_parsers::parse_date_time;
+    use crate::http_request::test;
+    use crate::http_request::{
+        pre_verify, sign, verify, PercentEncodingMode, SignableBody, SignableRequest,
+        SignatureLocation, SigningSettings, VerificationError, VerificationSettings,
+    };
+    use crate::sign::v4;
+    use aws_credential_types::Credentials;
+    use std::borrow::Cow;
+    use std::time::{Duration, SystemTime};
+
+    // Test cases whose signed requests can be parsed, and that have no body
+    const TEST_SUITE: &[&str] = &[
+        "double-encode-path",
+        "get-header-key-duplicate",
+        "get-header-value-order",
+        "get-header-value-trim",
+        "get-unreserved",
+        "get-vanilla",
+        "get-vanilla-empty-query-key",
+        "get-vanilla-query",
+        "get-vanilla-query-order-key",
+        "get-vanilla-query-order-key-case",
+        "get-vanilla-query-order-value",
+        "get-vanilla-query-unreserved",
+        "post-header-key-case",
+        "post-header-key-sort",
+        "post-header-value-case",
+        "post-vanilla",
+        "post-vanilla-empty-query-value",
+        "post-vanilla-query",
+    ];
+
+    fn now() -> SystemTime {
+        parse_date_time("20150830T123600Z").unwrap()
+    }
+
+    fn credentials(access_key_id: &str) -> Option<Credentials> {
+        match access_key_id {
+            "AKIDEXAMPLE" => Some(Credentials::new(
+                "AKIDEXAMPLE",
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                None,
+                None,
+                "test",
+            )),
+            "ANOTREAL" => Some(Credentials::for_tests()),
+            _ => None,
+        }
+    }
+
+    fn settings() -> VerificationSettings {
+        VerificationSettings {
+            // The test suite's paths aren't double encoded
+            percent_encoding_mode: PercentEncodingMode::Single,
+            ..Default::default()
+        }
+    }
+
+    fn verify_request(
+        request: &test::TestRequest,
+        settings: &VerificationSettings,
+        now: SystemTime,
+    ) -> Result<crate::http_request::VerifiedSignature, VerificationError> {
+        verify(&SignableRequest::from(request), settings, now, credentials)
+    }
+
+    // Signs the `get-vanilla` request with the given settings, and returns the signed request
+    fn signed_request(
+        identity: &Credentials,
+        settings: SigningSettings,
+        body: &'static [u8],
+    ) -> test::TestRequest {
+        signed_request_with(identity, settings, SignableBody::Bytes(body), body)
+    }
+
+    // Signs the `get-vanilla` request with `signed_body`, and returns the signed request with `body`
+    fn signed_request_with(
+        identity: &Credentials,
+        settings: SigningSettings,
+        signed_body: SignableBody<'static>,
+        body: &'static [u8],
+    ) -> test::TestRequest {
+        let identity = identity.clone().into();
+        let params = v4::SigningParams {
+            identity: &identity,
+            region: "us-east-1",
+            name: "service",
+            time: now(),
+            settings,
+        }
+        .into();
+        let mut original = test::v4::test_request("get-vanilla");
+        original.set_body(signed_body);
+        let signable = SignableRequest::from(&original);
+        let out = sign(signable, &params).unwrap();
+
+        let mut signed = original.as_http_request();
+        out.output.apply_to_request(&mut signed);
+        let mut signed = test::TestRequest::from(signed);
+        signed.set_body(SignableBody::Bytes(body));
+        signed
+    }
+
+    #[test]
+    fn verify_test_suite() {
+        for name in TEST_SUITE {
+            let request = test::v4::test_signed_request(name);
+            let settings = match *name {
+                "double-encode-path" => VerificationSettings::default(),
+                _ => settings(),
+            };
+            let verified = verify_request(&request, &settings, now())
+                .unwrap_or_else(|err| panic!("failed to verify {name}: {err}"));
+            assert_eq!("us-east-1", verified.region());
+            assert_eq!("service", verified.name());
+            assert_eq!(now(), verified.time());
+            assert_eq!(SignatureLocation::Headers, verified.location());
+        }
+    }
+
+    #[test]
+    fn verify_presigned_test_suite() {
+        let request =
+            test::v4::test_signed_request_query_params("get-vanilla-query-order-key-case");
+        let verified = verify_request(&request, &settings(), now()).unwrap();
+        assert_eq!("ANOTREAL", verified.access_key_id());
+        assert_eq!(SignatureLocation::QueryParams, verified.location());
+
+        // The presigned request expires after 35 seconds, and can be used until then
+        let later = now() + Duration::from_secs(35);
+        verify_request(&request, &settings(), later).unwrap();
+        let expired = now() + Duration::from_secs(36);
+        assert!(matches!(
+            verify_request(&request, &settings(), expired),
+            Err(VerificationError::Expired { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_signed_request() {
+        let identity = credentials("AKIDEXAMPLE").unwrap();
+        let request = signed_request(&identity, SigningSettings::default(), b"body");
+        let verified = verify_request(&request, &settings(), now()).unwrap();
+        assert_eq!("AKIDEXAMPLE", verified.access_key_id());
+
+        let mut tampered = request.clone();
+        tampered.set_body(SignableBody::Bytes(b"other body"));
+        assert!(matches!(
+            verify_request(&tampered, &settings(), now()),
+            Err(VerificationError::SignatureMismatch)
+        ));
+
+        let mut tampered = request.clone();
+        tampered.uri.push_str("other");
+        assert!(matches!(
+            verify_request(&tampered, &settings(), now()),
+            Err(VerificationError::SignatureMismatch)
+        ));
+
+        let mut tampered = request;
+        tampered.headers.retain(|(name, _)| name != "x-amz-date");
+        assert!(matches!(
+            verify_request(&tampered, &settings(), now()),
+            Err(VerificationError::MalformedSignature { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_clock_skew() {
+        let request = test::v4::test_signed_request("get-vanilla");
+        let skew = Duration::from_secs(5 * 60);
+        verify_request(&request, &settings(), now() + skew).unwrap();
+        verify_request(&request, &settings(), now() - skew).unwrap();
+        for now in [
+            now() + skew + Duration::from_secs(1),
+            now() - skew - Duration::from_secs(1),
+        ] {
+            assert!(matches!(
+                verify_request(&request, &settings(), now),
+                Err(VerificationError::RequestTimeTooSkewed { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn verify_credentials() {
+        let request = test::v4::test_signed_request("get-vanilla");
+        let err = verify(&SignableRequest::from(&request), &settings(), now(), |_| {
+            None
+        });
+        assert!(matches!(
+            err,
+            Err(VerificationError::UnknownAccessKey { access_key_id }) if access_key_id == "AKIDEXAMPLE"
+        ));
+
+        // When the credentials have a session token, the request must have been signed with it
+        let identity = Credentials::new(
+            "AKIDEXAMPLE",
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            Some("session-token".into()),
+            None,
+            "test",
+        );
+        let err = verify(&SignableRequest::from(&request), &settings(), now(), |_| {
+            Some(identity.clone())
+        });
+        assert!(matches!(err, Err(VerificationError::InvalidSecurityToken)));
+
+        let request = signed_request(&identity, SigningSettings::default(), b"");
+        verify(&SignableRequest::from(&request), &settings(), now(), |_| {
+            Some(identity.clone())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn verify_credential_scope() {
+        let request = test::v4::test_signed_request("get-vanilla");
+        let mut settings = settings();
+        settings.region = Some(Cow::Borrowed("us-east-1"));
+        settings.name = Some(Cow::Borrowed("service"));
+        verify_request(&request, &settings, now()).unwrap();
+
+        settings.name = Some(Cow::Borrowed("other-service"));
+        assert!(matches!(
+            verify_request(&request, &settings, now()),
+            Err(VerificationError::CredentialScopeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_payload_hash() {
+        use crate::http_request::PayloadChecksumKind;
+
+        let identity = credentials("AKIDEXAMPLE").unwrap();
+        let signing_settings = SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        let request = signed_request(&identity, signing_settings, b"body");
+        verify_request(&request, &settings(), now()).unwrap();
+
+        let mut tampered = request;
+        tampered.set_body(SignableBody::Bytes(b"other body"));
+        assert!(matches!(
+            verify_request(&tampered, &settings(), now()),
+            Err(VerificationError::PayloadHashMismatch)
+        ));
+    }
+
+    #[test]
+    fn verify_unsigned_payload() {
+        let identity = credentials("AKIDEXAMPLE").unwrap();
+        let request = signed_request_with(
+            &identity,
+            SigningSettings::default(),
+            SignableBody::UnsignedPayload,
+            b"body",
+        );
+
+        assert!(matches!(
+            verify_request(&request, &settings(), now()),
+            Err(VerificationError::SignatureMismatch)
+        ));
+        let mut settings = settings();
+        settings.allow_unsigned_payload = true;
+        verify_request(&request, &settings, now()).unwrap();
+    }
+
+    #[test]
+    fn verify_unsigned_payload_hash_header() {
+        use crate::http_request::PayloadChecksumKind;
+
+        let identity = credentials("AKIDEXAMPLE").unwrap();
+        let signing_settings = || SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        for (signed_body, content_sha256) in [
+            (SignableBody::UnsignedPayload, "UNSIGNED-PAYLOAD"),
+            (
+                SignableBody::StreamingUnsignedPayloadTrailer,
+                "STREAMING-UNSIGNED-PAYLOAD-TRAILER",
+            ),
+            (
+                SignableBody::StreamingSignedPayload,
+                "STREAMING-AWS4-HMAC-SHA256-PAYLOAD",
+            ),
+        ] {
+            let request = signed_request_with(&identity, signing_settings(), signed_body, b"body");
+            assert!(request
+                .headers
+                .iter()
+                .any(|(name, value)| name == "x-amz-content-sha256" && value == content_sha256));
+
+            // The body isn't covered by the signature, so it's rejected by default
+            assert!(matches!(
+                verify_request(&request, &settings(), now()),
+                Err(VerificationError::UnsignedPayload { content_sha256: value }) if value == content_sha256
+            ));
+            let mut settings = settings();
+            settings.allow_unsigned_payload = true;
+            verify_request(&request, &settings, now()).unwrap();
+        }
+    }
+
+    #[test]
+    fn verify_invalid_payload_hash_header() {
+        let identity = credentials("AKIDEXAMPLE").unwrap();
+        let mut request = signed_request(&identity, SigningSettings::default(), b"body");
+        request
+            .headers
+            .push(("x-amz-content-sha256".into(), "not-a-hash".into()));
+        let mut settings = settings();
+        settings.allow_unsigned_payload = true;
+        assert!(matches!(
+            verify_request(&request, &settings, now()),
+            Err(VerificationError::PayloadHashMismatch)
+        ));
+    }
+
+    #[test]
+    fn verify_malformed_signatures() {
+        let mut request = test::v4::test_request("get-vanilla");
+        assert!(matches!(
+            verify_request(&request, &settings(), now()),
+            Err(VerificationError::MissingSignature)
+        ));
+
+        request
+            .headers
+            .push(("x-amz-date".into(), "20150830T123600Z".into()));
+        type Check = fn(&VerificationError) -> bool;
+        let cases: &[(&str, Check)] = &[
+            ("Basic dXNlcjpwYXNz", |err| {
+                matches!(err, VerificationError::UnsupportedAlgorithm { algorithm } if algorithm == "Basic")
+            }),
+            ("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request", |err| {
+                matches!(err, VerificationError::MalformedSignature { .. })
+            }),
+            ("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service, SignedHeaders=host;x-amz-date, Signature=abc", |err| {
+                matches!(err, VerificationError::MalformedSignature { .. })
+            }),
+            ("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150831/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=abc", |err| {
+                matches!(err, VerificationError::MalformedSignature { .. })
+            }),
+            ("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=x-amz-date, Signature=abc", |err| {
+                matches!(err, VerificationError::MalformedSignature { .. })
+            }),
+            ("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date;x-custom, Signature=abc", |err| {
+                matches!(err, VerificationError::MissingSignedHeader { name } if name == "x-custom")
+            }),
+        ];
+        for (authorization, check) in cases {
+            let mut request = request.clone();
+            request
+                .headers
+                .push(("authorization".into(), authorization.to_string()));
+            let err = verify_request(&request, &settings(), now()).unwrap_err();
+            assert!(
+                check(&err),
+                "unexpected error for `{authorization}`: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn pre_verify_ignores_the_body_and_credentials() {
+        let identity = credentials("AKIDEXAMPLE").unwrap();
+        let mut request = signed_request(&identity, SigningSettings::default(), b"body");
+        request.body = test::TestSignedBody::Bytes(b"another body".to_vec());
+        let signable = SignableRequest::from(&request);
+        pre_verify(&signable, &settings(), now()).unwrap();
+
+        let too_late = now() + Duration::from_secs(3600);
+        assert!(matches!(
+            pre_verify(&signable, &settings(), too_late),
+            Err(VerificationError::RequestTimeTooSkewed { .. })
+        ));
+
+        let unsigned = test::v4::test_request("get-vanilla");
+        assert!(matches!(
+            pre_verify(&SignableRequest::from(&unsigned), &settings(), now()),
+            Err(VerificationError::MissingSignature)
+        ));
+    }
+}
@@ -27,6 +27,9 @@ pub mod event_stream;
 #[cfg(feature = "sign-http")]
 pub mod http_request;
 
+#[cfg(feature = "sign-http")]
+pub mod chunked_encoding;
+
 /// The version of the signing algorithm to use
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[non_exhaustive]
@@ -8,6 +8,9 @@
 //! Parameters require newtypes so they have distinct types when stored in layers in config bag.
 
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
 /// Newtype for `use_fips`
 #[derive(Clone, Debug)]
@@ -29,3 +32,100 @@ pub struct EndpointUrl(pub String);
 impl Storable for EndpointUrl {
     type Storer = StoreReplace<EndpointUrl>;
 }
+
+/// Controls whether the account ID of the resolved credentials is used to route requests
+///
+/// Services that support account-based endpoints use the account ID that the credentials
+/// belong to, when known, to route requests to an endpoint specific to that account.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum AccountIdEndpointMode {
+    /// Use the account ID for endpoint routing when it is available
+    #[default]
+    Preferred,
+    /// Require an account ID for endpoint routing, and fail requests when it isn't available
+    Required,
+    /// Never use the account ID for endpoint routing
+    Disabled,
+}
+
+impl AccountIdEndpointMode {
+    /// Returns the string representation of this mode, as used by endpoint rules and config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Preferred => "preferred",
+            Self::Required => "required",
+            Self::Disabled => "disabled",
+        }
+    }
+}
+
+impl fmt::Display for AccountIdEndpointMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AccountIdEndpointMode {
+    type Err = AccountIdEndpointModeParseError;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        let mode = mode.trim();
+        if mode.eq_ignore_ascii_case("preferred") {
+            Ok(Self::Preferred)
+        } else if mode.eq_ignore_ascii_case("required") {
+            Ok(Self::Required)
+        } else if mode.eq_ignore_ascii_case("disabled") {
+            Ok(Self::Disabled)
+        } else {
+            Err(AccountIdEndpointModeParseError {
+                mode: mode.to_owned(),
+            })
+        }
+    }
+}
+
+impl Storable for AccountIdEndpointMode {
+    type Storer = StoreReplace<AccountIdEndpointMode>;
+}
+
+/// Error returned when an [`AccountIdEndpointMode`] can't be parsed
+#[derive(Debug)]
+pub struct AccountIdEndpointModeParseError {
+    mode: String,
+}
+
+impl fmt::Display for AccountIdEndpointModeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid account ID endpoint mode `{}`. Valid values are `preferred`, `required`, and `disabled`",
+            self.mode
+        )
+    }
+}
+
+impl Error for AccountIdEndpointModeParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::AccountIdEndpointMode;
+
+    #[test]
+    fn parse_account_id_endpoint_mode() {
+        for mode in [
+            AccountIdEndpointMode::Preferred,
+            AccountIdEndpointMode::Required,
+            AccountIdEndpointMode::Disabled,
+        ] {
+            assert_eq!(mode, mode.as_str().parse().unwrap());
+        }
+        assert_eq!(
+            AccountIdEndpointMode::Required,
+            " REQUIRED ".parse().unwrap()
+        );
+        "sometimes"
+            .parse::<AccountIdEndpointMode>()
+            .expect_err("invalid mode");
+    }
+}
@@ -21,6 +21,7 @@ pub mod endpoint_config;
 pub mod os_shim_internal;
 pub mod region;
 pub mod sdk_config;
+pub mod service_config;
 pub use sdk_config::SdkConfig;
 
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
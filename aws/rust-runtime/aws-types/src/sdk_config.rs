@@ -11,8 +11,12 @@
 
 use crate::app_name::AppName;
 use crate::docs_for;
+use crate::endpoint_config::AccountIdEndpointMode;
 use crate::region::Region;
+use crate::service_config::LoadServiceConfig;
+use std::sync::Arc;
 
+pub use aws_credential_types::provider::token::SharedTokenProvider;
 pub use aws_credential_types::provider::SharedCredentialsProvider;
 use aws_smithy_async::rt::sleep::AsyncSleep;
 pub use aws_smithy_async::rt::sleep::SharedAsyncSleep;
@@ -42,6 +46,21 @@ If no dual-stack endpoint is available the request MAY return an error.
 **Note**: Some services do not offer dual-stack as a configurable parameter (e.g. Code Catalyst). For
 these services, this setting has no effect"
         };
+        (disable_request_compression) => {
+"When true, request bodies won't be compressed, even for operations that support request compression."
+        };
+        (request_min_compression_size_bytes) => {
+"The minimum size in bytes that a request body must be to get compressed, for operations that
+support request compression.
+
+Valid values are between 0 and 10485760 inclusive, and the default is 10240."
+        };
+
+        (account_id_endpoint_mode) => {
+"Controls whether the account ID of the resolved credentials is used to route requests to account-based endpoints.
+
+Defaults to `preferred`, which uses the account ID when it is available."
+        };
 
         (time_source) => { "The time source use to use for this client. This only needs to be required for creating deterministic tests or platforms where `SystemTime::now()` is not supported." };
     }
@@ -53,6 +72,7 @@ pub struct SdkConfig {
     app_name: Option<AppName>,
     identity_cache: Option<SharedIdentityCache>,
     credentials_provider: Option<SharedCredentialsProvider>,
+    token_provider: Option<SharedTokenProvider>,
     region: Option<Region>,
     endpoint_url: Option<String>,
     retry_config: Option<RetryConfig>,
@@ -62,6 +82,10 @@ pub struct SdkConfig {
     http_client: Option<SharedHttpClient>,
     use_fips: Option<bool>,
     use_dual_stack: Option<bool>,
+    account_id_endpoint_mode: Option<AccountIdEndpointMode>,
+    disable_request_compression: Option<bool>,
+    request_min_compression_size_bytes: Option<u32>,
+    service_config: Option<Arc<dyn LoadServiceConfig>>,
 }
 
 /// Builder for AWS Shared Configuration
@@ -74,6 +98,7 @@ pub struct Builder {
     app_name: Option<AppName>,
     identity_cache: Option<SharedIdentityCache>,
     credentials_provider: Option<SharedCredentialsProvider>,
+    token_provider: Option<SharedTokenProvider>,
     region: Option<Region>,
     endpoint_url: Option<String>,
     retry_config: Option<RetryConfig>,
@@ -83,6 +108,10 @@ pub struct Builder {
     http_client: Option<SharedHttpClient>,
     use_fips: Option<bool>,
     use_dual_stack: Option<bool>,
+    account_id_endpoint_mode: Option<AccountIdEndpointMode>,
+    disable_request_compression: Option<bool>,
+    request_min_compression_size_bytes: Option<u32>,
+    service_config: Option<Arc<dyn LoadServiceConfig>>,
 }
 
 impl Builder {
@@ -409,6 +438,51 @@ impl Builder {
         self
     }
 
+    /// Set the bearer token provider for the builder
+    ///
+    /// The token provider is used by services that authenticate requests with HTTP bearer auth.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_credential_types::provider::token::SharedTokenProvider;
+    /// use aws_credential_types::Token;
+    /// use aws_types::SdkConfig;
+    ///
+    /// let config = SdkConfig::builder()
+    ///     .token_provider(SharedTokenProvider::new(Token::new("example-token", None)))
+    ///     .build();
+    /// ```
+    pub fn token_provider(mut self, provider: SharedTokenProvider) -> Self {
+        self.set_token_provider(Some(provider));
+        self
+    }
+
+    /// Set the bearer token provider for the builder
+    ///
+    /// The token provider is used by services that authenticate requests with HTTP bearer auth.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_credential_types::provider::token::SharedTokenProvider;
+    /// use aws_credential_types::Token;
+    /// use aws_types::SdkConfig;
+    ///
+    /// fn override_provider() -> bool {
+    ///   // ...
+    ///   # true
+    /// }
+    ///
+    /// let mut builder = SdkConfig::builder();
+    /// if override_provider() {
+    ///     builder.set_token_provider(Some(SharedTokenProvider::new(Token::new("example-token", None))));
+    /// }
+    /// let config = builder.build();
+    /// ```
+    pub fn set_token_provider(&mut self, provider: Option<SharedTokenProvider>) -> &mut Self {
+        self.token_provider = provider;
+        self
+    }
+
     /// Sets the name of the app that is using the client.
     ///
     /// This _optional_ name is used to identify the application in the user agent that
@@ -524,6 +598,63 @@ impl Builder {
         self
     }
 
+    #[doc = docs_for!(account_id_endpoint_mode)]
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_types::endpoint_config::AccountIdEndpointMode;
+    /// use aws_types::SdkConfig;
+    /// let config = SdkConfig::builder()
+    ///     .account_id_endpoint_mode(AccountIdEndpointMode::Disabled)
+    ///     .build();
+    /// ```
+    pub fn account_id_endpoint_mode(mut self, mode: AccountIdEndpointMode) -> Self {
+        self.set_account_id_endpoint_mode(Some(mode));
+        self
+    }
+
+    #[doc = docs_for!(account_id_endpoint_mode)]
+    pub fn set_account_id_endpoint_mode(
+        &mut self,
+        mode: Option<AccountIdEndpointMode>,
+    ) -> &mut Self {
+        self.account_id_endpoint_mode = mode;
+        self
+    }
+
+    #[doc = docs_for!(disable_request_compression)]
+    pub fn disable_request_compression(mut self, disable_request_compression: bool) -> Self {
+        self.set_disable_request_compression(Some(disable_request_compression));
+        self
+    }
+
+    #[doc = docs_for!(disable_request_compression)]
+    pub fn set_disable_request_compression(
+        &mut self,
+        disable_request_compression: Option<bool>,
+    ) -> &mut Self {
+        self.disable_request_compression = disable_request_compression;
+        self
+    }
+
+    #[doc = docs_for!(request_min_compression_size_bytes)]
+    pub fn request_min_compression_size_bytes(
+        mut self,
+        request_min_compression_size_bytes: u32,
+    ) -> Self {
+        self.set_request_min_compression_size_bytes(Some(request_min_compression_size_bytes));
+        self
+    }
+
+    #[doc = docs_for!(request_min_compression_size_bytes)]
+    pub fn set_request_min_compression_size_bytes(
+        &mut self,
+        request_min_compression_size_bytes: Option<u32>,
+    ) -> &mut Self {
+        self.request_min_compression_size_bytes = request_min_compression_size_bytes;
+        self
+    }
+
     #[doc = docs_for!(time_source)]
     pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
         self.set_time_source(Some(SharedTimeSource::new(time_source)));
@@ -536,12 +667,31 @@ impl Builder {
         self
     }
 
+    /// Set the loader for service-specific configuration, e.g. per-service endpoint URLs.
+    ///
+    /// Service clients created from this config use it to look up settings such as
+    /// `AWS_ENDPOINT_URL_<SERVICE>` that can't be represented in a service-agnostic `SdkConfig`.
+    pub fn service_config(mut self, service_config: impl LoadServiceConfig + 'static) -> Self {
+        self.set_service_config(Some(Arc::new(service_config)));
+        self
+    }
+
+    /// Set the loader for service-specific configuration, e.g. per-service endpoint URLs.
+    pub fn set_service_config(
+        &mut self,
+        service_config: Option<Arc<dyn LoadServiceConfig>>,
+    ) -> &mut Self {
+        self.service_config = service_config;
+        self
+    }
+
     /// Build a [`SdkConfig`](SdkConfig) from this builder
     pub fn build(self) -> SdkConfig {
         SdkConfig {
             app_name: self.app_name,
             identity_cache: self.identity_cache,
             credentials_provider: self.credentials_provider,
+            token_provider: self.token_provider,
             region: self.region,
             endpoint_url: self.endpoint_url,
             retry_config: self.retry_config,
@@ -550,7 +700,11 @@ impl Builder {
             http_client: self.http_client,
             use_fips: self.use_fips,
             use_dual_stack: self.use_dual_stack,
+            account_id_endpoint_mode: self.account_id_endpoint_mode,
+            disable_request_compression: self.disable_request_compression,
+            request_min_compression_size_bytes: self.request_min_compression_size_bytes,
             time_source: self.time_source,
+            service_config: self.service_config,
         }
     }
 }
@@ -592,6 +746,11 @@ impl SdkConfig {
         self.credentials_provider.clone()
     }
 
+    /// Configured bearer token provider
+    pub fn token_provider(&self) -> Option<SharedTokenProvider> {
+        self.token_provider.clone()
+    }
+
     /// Configured time source
     pub fn time_source(&self) -> Option<SharedTimeSource> {
         self.time_source.clone()
@@ -617,6 +776,26 @@ impl SdkConfig {
         self.use_dual_stack
     }
 
+    /// Configured account ID endpoint mode
+    pub fn account_id_endpoint_mode(&self) -> Option<&AccountIdEndpointMode> {
+        self.account_id_endpoint_mode.as_ref()
+    }
+
+    /// Whether request compression is disabled
+    pub fn disable_request_compression(&self) -> Option<bool> {
+        self.disable_request_compression
+    }
+
+    /// Configured minimum size in bytes of request bodies to compress
+    pub fn request_min_compression_size_bytes(&self) -> Option<u32> {
+        self.request_min_compression_size_bytes
+    }
+
+    /// Configured loader for service-specific configuration
+    pub fn service_config(&self) -> Option<&dyn LoadServiceConfig> {
+        self.service_config.as_deref()
+    }
+
     /// Config builder
     ///
     /// _Important:_ Using the `aws-config` crate to configure the SDK is preferred to invoking this
@@ -637,6 +816,7 @@ impl SdkConfig {
             app_name: self.app_name,
             identity_cache: self.identity_cache,
             credentials_provider: self.credentials_provider,
+            token_provider: self.token_provider,
             region: self.region,
             endpoint_url: self.endpoint_url,
             retry_config: self.retry_config,
@@ -646,6 +826,10 @@ impl SdkConfig {
             http_client: self.http_client,
             use_fips: self.use_fips,
             use_dual_stack: self.use_dual_stack,
+            account_id_endpoint_mode: self.account_id_endpoint_mode,
+            disable_request_compression: self.disable_request_compression,
+            request_min_compression_size_bytes: self.request_min_compression_size_bytes,
+            service_config: self.service_config,
         }
     }
 }
@@ -0,0 +1,70 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Service-specific configuration
+//!
+//! Some settings, like `endpoint_url`, can be configured separately for each service, e.g. with
+//! the `AWS_ENDPOINT_URL_S3` environment variable or a `[services]` section of the shared config
+//! file. Service clients look these settings up through [`LoadServiceConfig`] when they're
+//! created from an [`SdkConfig`](crate::SdkConfig).
+
+use std::fmt;
+
+/// The key used to look up a service-specific configuration value
+///
+/// # Examples
+/// ```
+/// use aws_types::service_config::ServiceConfigKey;
+///
+/// // Looks up `AWS_ENDPOINT_URL_S3` and the `s3.endpoint_url` sub-property of a `[services]` section
+/// let key = ServiceConfigKey::new("S3", "AWS_ENDPOINT_URL", "endpoint_url");
+/// assert_eq!("S3", key.service_id());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ServiceConfigKey<'a> {
+    service_id: &'a str,
+    env: &'a str,
+    profile: &'a str,
+}
+
+impl<'a> ServiceConfigKey<'a> {
+    /// Creates a new key
+    ///
+    /// - `service_id` is the SDK ID of the service, e.g. `S3` or `Elastic Beanstalk`
+    /// - `env` is the name of the environment variable that configures the setting for all services,
+    ///   e.g. `AWS_ENDPOINT_URL`
+    /// - `profile` is the name of the profile key that configures the setting, e.g. `endpoint_url`
+    pub fn new(service_id: &'a str, env: &'a str, profile: &'a str) -> Self {
+        Self {
+            service_id,
+            env,
+            profile,
+        }
+    }
+
+    /// The SDK ID of the service
+    pub fn service_id(&self) -> &'a str {
+        self.service_id
+    }
+
+    /// The name of the environment variable that configures the setting for all services
+    pub fn env(&self) -> &'a str {
+        self.env
+    }
+
+    /// The name of the profile key that configures the setting
+    pub fn profile(&self) -> &'a str {
+        self.profile
+    }
+}
+
+/// Loads service-specific configuration values
+///
+/// `aws-config` provides an implementation that reads from the environment and the shared config
+/// files.
+pub trait LoadServiceConfig: fmt::Debug + Send + Sync {
+    /// Returns the value configured for `key`, if any
+    fn load_config(&self, key: ServiceConfigKey<'_>) -> Option<String>;
+}
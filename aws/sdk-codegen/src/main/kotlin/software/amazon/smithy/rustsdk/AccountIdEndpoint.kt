@@ -0,0 +1,222 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rustsdk
+
+import software.amazon.smithy.model.node.Node
+import software.amazon.smithy.rulesengine.language.syntax.parameters.Parameter
+import software.amazon.smithy.rulesengine.language.syntax.parameters.ParameterType
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.endpoint.EndpointCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.endpoint.memberName
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.customize.AdHocCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.adhocCustomization
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.extendIf
+
+/** Endpoint ruleset built-ins for account ID based endpoint routing */
+object AccountIdBuiltIns {
+    val ACCOUNT_ID: Parameter = Parameter.builder()
+        .name("AccountId")
+        .type(ParameterType.STRING)
+        .builtIn("AWS::Auth::AccountId")
+        .documentation("The AWS account ID that the request is made with, taken from the resolved credentials.")
+        .build()
+
+    val ACCOUNT_ID_ENDPOINT_MODE: Parameter = Parameter.builder()
+        .name("AccountIdEndpointMode")
+        .type(ParameterType.STRING)
+        .builtIn("AWS::Auth::AccountIdEndpointMode")
+        .documentation("Whether the account ID is used for endpoint routing: `preferred`, `required`, or `disabled`.")
+        .build()
+}
+
+/**
+ * Routes requests to account ID based endpoints for services whose ruleset references the `AWS::Auth::AccountId`
+ * built-in.
+ *
+ * The account ID comes from the credentials that the request is signed with, so unless it's disabled by the account
+ * ID endpoint mode, the credentials are resolved ahead of the endpoint and the account ID is filled into the endpoint
+ * parameters. When the ruleset also references `AWS::Auth::AccountIdEndpointMode`, an
+ * `account_id_endpoint_mode` setting is added to the `Config` and copied from `SdkConfig`.
+ */
+class AccountIdEndpointDecorator : ClientCodegenDecorator {
+    override val name: String = "AccountIdEndpoint"
+    override val order: Byte = 0
+
+    private fun usesAccountId(codegenContext: ClientCodegenContext) =
+        codegenContext.getBuiltIn(AccountIdBuiltIns.ACCOUNT_ID) != null
+
+    private fun usesAccountIdEndpointMode(codegenContext: ClientCodegenContext) =
+        codegenContext.getBuiltIn(AccountIdBuiltIns.ACCOUNT_ID_ENDPOINT_MODE) != null
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> = baseCustomizations.extendIf(usesAccountIdEndpointMode(codegenContext)) {
+        AccountIdEndpointModeConfig(codegenContext)
+    }
+
+    override fun extraSections(codegenContext: ClientCodegenContext): List<AdHocCustomization> =
+        if (usesAccountIdEndpointMode(codegenContext)) {
+            listOf(
+                adhocCustomization<SdkConfigSection.CopySdkConfigToClientConfig> { section ->
+                    rust(
+                        "${section.serviceConfigBuilder}.set_account_id_endpoint_mode(${section.sdkConfig}.account_id_endpoint_mode().cloned());",
+                    )
+                },
+            )
+        } else {
+            emptyList()
+        }
+
+    override fun endpointCustomizations(codegenContext: ClientCodegenContext): List<EndpointCustomization> {
+        val accountId = codegenContext.getBuiltIn(AccountIdBuiltIns.ACCOUNT_ID) ?: return listOf()
+        val mode = codegenContext.getBuiltIn(AccountIdBuiltIns.ACCOUNT_ID_ENDPOINT_MODE)
+        val runtimeConfig = codegenContext.runtimeConfig
+        val codegenScope = arrayOf(
+            *preludeScope,
+            "AccountIdEndpointMode" to AwsRuntimeType.awsTypes(runtimeConfig)
+                .resolve("endpoint_config::AccountIdEndpointMode"),
+            "Credentials" to AwsRuntimeType.awsCredentialTypes(runtimeConfig).resolve("Credentials"),
+        )
+        return listOf(
+            object : EndpointCustomization {
+                override fun loadBuiltInFromServiceConfig(parameter: Parameter, configRef: String): Writable? =
+                    when (parameter.builtIn) {
+                        AccountIdBuiltIns.ACCOUNT_ID_ENDPOINT_MODE.builtIn -> writable {
+                            rustTemplate(
+                                "$configRef.load::<#{AccountIdEndpointMode}>().map(|mode| mode.to_string())",
+                                *codegenScope,
+                            )
+                        }
+
+                        else -> null
+                    }
+
+                override fun setBuiltInOnServiceConfig(name: String, value: Node, configBuilderRef: String): Writable? =
+                    when (name) {
+                        AccountIdBuiltIns.ACCOUNT_ID.builtIn.get() -> writable {
+                            rustTemplate(
+                                """
+                                let $configBuilderRef = $configBuilderRef.credentials_provider(
+                                    #{Credentials}::new("test", "test", #{None}, #{None}, "test")
+                                        .with_account_id(${value.expectStringNode().value.dq()}),
+                                );
+                                """,
+                                *codegenScope,
+                            )
+                        }
+
+                        AccountIdBuiltIns.ACCOUNT_ID_ENDPOINT_MODE.builtIn.get() -> writable {
+                            rustTemplate(
+                                """
+                                let $configBuilderRef = $configBuilderRef.account_id_endpoint_mode(
+                                    ${value.expectStringNode().value.dq()}
+                                        .parse::<#{AccountIdEndpointMode}>()
+                                        .expect("valid account ID endpoint mode"),
+                                );
+                                """,
+                                *codegenScope,
+                            )
+                        }
+
+                        else -> null
+                    }
+
+                override fun finalizeParams(
+                    paramsRef: String,
+                    identityRef: String,
+                    requiresIdentityRef: String,
+                ): Writable = writable {
+                    val accountIdField = accountId.memberName()
+                    val currentMode = when {
+                        mode == null -> "#{None}::<&str>"
+                        mode.isRequired -> "#{Some}($paramsRef.${mode.memberName()}.as_str())"
+                        else -> "$paramsRef.${mode.memberName()}.as_deref()"
+                    }
+                    rustTemplate(
+                        """
+                        if $paramsRef.$accountIdField.is_none() && $currentMode != #{Some}("disabled") {
+                            match $identityRef {
+                                #{Some}(identity) => {
+                                    $paramsRef.$accountIdField = identity
+                                        .data::<#{Credentials}>()
+                                        .and_then(|credentials| credentials.account_id())
+                                        .map(|account_id| account_id.to_string());
+                                }
+                                // The account ID comes from the credentials, so they're resolved ahead of the endpoint
+                                #{None} => $requiresIdentityRef = true,
+                            }
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+            },
+        )
+    }
+}
+
+/**
+ * Add an `account_id_endpoint_mode` setting to the `Config` for a given service
+ */
+class AccountIdEndpointModeConfig(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "AccountIdEndpointMode" to AwsRuntimeType.awsTypes(runtimeConfig)
+            .resolve("endpoint_config::AccountIdEndpointMode"),
+    )
+
+    override fun section(section: ServiceConfig) = writable {
+        when (section) {
+            ServiceConfig.ConfigImpl -> {
+                rustTemplate(
+                    """
+                    /// Returns how the account ID is used for endpoint routing, if it was set
+                    pub fn account_id_endpoint_mode(&self) -> #{Option}<&#{AccountIdEndpointMode}> {
+                        self.config.load::<#{AccountIdEndpointMode}>()
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            ServiceConfig.BuilderImpl -> {
+                rustTemplate(
+                    """
+                    /// Sets how the account ID is used for endpoint routing
+                    ///
+                    /// When unset, the account ID from the resolved credentials is used when it's available.
+                    pub fn account_id_endpoint_mode(mut self, account_id_endpoint_mode: #{AccountIdEndpointMode}) -> Self {
+                        self.set_account_id_endpoint_mode(#{Some}(account_id_endpoint_mode));
+                        self
+                    }
+
+                    /// Sets how the account ID is used for endpoint routing
+                    ///
+                    /// When unset, the account ID from the resolved credentials is used when it's available.
+                    pub fn set_account_id_endpoint_mode(&mut self, account_id_endpoint_mode: #{Option}<#{AccountIdEndpointMode}>) -> &mut Self {
+                        self.config.store_or_unset(account_id_endpoint_mode);
+                        self
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            else -> emptySection
+        }
+    }
+}
@@ -30,6 +30,8 @@ val DECORATORS: List<ClientCodegenDecorator> = listOf(
     // General AWS Decorators
     listOf(
         CredentialsProviderDecorator(),
+        TokenProviderDecorator(),
+        AccountIdEndpointDecorator(),
         RegionDecorator(),
         RequireEndpointRules(),
         UserAgentDecorator(),
@@ -47,6 +49,7 @@ val DECORATORS: List<ClientCodegenDecorator> = listOf(
         AwsEndpointsStdLib(),
         *PromotedBuiltInsDecorators,
         GenericSmithySdkConfigSettings(),
+        RequestCompressionSdkConfigSettings(),
         OperationInputTestDecorator(),
         AwsRequestIdDecorator(),
         DisabledAuthDecorator(),
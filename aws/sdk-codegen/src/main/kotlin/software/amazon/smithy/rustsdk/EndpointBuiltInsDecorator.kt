@@ -5,6 +5,7 @@
 
 package software.amazon.smithy.rustsdk
 
+import software.amazon.smithy.aws.traits.ServiceTrait
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.node.BooleanNode
 import software.amazon.smithy.model.node.Node
@@ -37,10 +38,12 @@ import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.customize.AdHocCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.adhocCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.mapRustType
 import software.amazon.smithy.rust.codegen.core.util.PANIC
 import software.amazon.smithy.rust.codegen.core.util.dq
 import software.amazon.smithy.rust.codegen.core.util.extendIf
+import software.amazon.smithy.rust.codegen.core.util.getTrait
 import software.amazon.smithy.rust.codegen.core.util.orNull
 import software.amazon.smithy.rust.codegen.core.util.toPascalCase
 import java.util.Optional
@@ -103,6 +106,36 @@ fun Model.sdkConfigSetter(
     return SdkConfigCustomization.copyField(fieldName, map)
 }
 
+/**
+ * Copy the endpoint URL from SDK config to service config
+ *
+ * An endpoint URL set explicitly on the SDK config applies to every service. Otherwise, the URL is looked up with
+ * the SDK config's service config loader so that service-specific settings like `AWS_ENDPOINT_URL_<SERVICE>` or
+ * the `[services]` section of the shared config file are respected.
+ */
+fun ClientCodegenContext.endpointUrlSdkConfigSetter(configParameterNameOverride: String?): AdHocCustomization? {
+    val builtIn = model.loadBuiltIn(serviceShape.id, BuiltIns.SDK_ENDPOINT) ?: return null
+    val fieldName = configParameterNameOverride ?: builtIn.name.rustName()
+    val serviceId = serviceShape.getTrait<ServiceTrait>()?.sdkId ?: serviceShape.id.name
+    return adhocCustomization<SdkConfigSection.CopySdkConfigToClientConfig> { section ->
+        rustTemplate(
+            """
+            ${section.serviceConfigBuilder}.set_$fieldName(
+                ${section.sdkConfig}
+                    .$fieldName()
+                    .map(|s| s.to_string())
+                    .or_else(|| {
+                        ${section.sdkConfig}.service_config().and_then(|conf| {
+                            conf.load_config(#{ServiceConfigKey}::new(${serviceId.dq()}, "AWS_ENDPOINT_URL", "endpoint_url"))
+                        })
+                    }),
+            );
+            """,
+            "ServiceConfigKey" to AwsRuntimeType.awsTypes(runtimeConfig).resolve("service_config::ServiceConfigKey"),
+        )
+    }
+}
+
 /**
  * Create a client codegen decorator that creates bindings for a builtIn parameter. Optionally, you can provide
  * [clientParam.Builder] which allows control over the config parameter that will be generated.
@@ -122,7 +155,14 @@ fun decoratorForBuiltIn(
 
         override fun extraSections(codegenContext: ClientCodegenContext): List<AdHocCustomization> {
             return listOfNotNull(
-                codegenContext.model.sdkConfigSetter(codegenContext.serviceShape.id, builtIn, clientParamBuilder?.name),
+                when (builtIn.builtIn) {
+                    BuiltIns.SDK_ENDPOINT.builtIn -> codegenContext.endpointUrlSdkConfigSetter(clientParamBuilder?.name)
+                    else -> codegenContext.model.sdkConfigSetter(
+                        codegenContext.serviceShape.id,
+                        builtIn,
+                        clientParamBuilder?.name,
+                    )
+                },
             )
         }
 
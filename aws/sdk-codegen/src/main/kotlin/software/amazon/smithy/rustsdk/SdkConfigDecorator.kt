@@ -7,6 +7,7 @@ package software.amazon.smithy.rustsdk
 
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.hasRequestCompression
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
@@ -88,6 +89,25 @@ class GenericSmithySdkConfigSettings : ClientCodegenDecorator {
         )
 }
 
+/**
+ * SdkConfig -> <service>::Config for request compression settings, for services with operations that
+ * compress their requests
+ */
+class RequestCompressionSdkConfigSettings : ClientCodegenDecorator {
+    override val name: String = "RequestCompressionSdkConfigSettings"
+    override val order: Byte = 0
+
+    override fun extraSections(codegenContext: ClientCodegenContext): List<AdHocCustomization> =
+        if (codegenContext.hasRequestCompression()) {
+            listOf(
+                SdkConfigCustomization.copyField("disable_request_compression", null),
+                SdkConfigCustomization.copyField("request_min_compression_size_bytes", null),
+            )
+        } else {
+            emptyList()
+        }
+}
+
 /**
  * Adds functionality for constructing `<service>::Config` objects from `aws_types::SdkConfig`s
  *
@@ -0,0 +1,116 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rustsdk
+
+import software.amazon.smithy.model.knowledge.ServiceIndex
+import software.amazon.smithy.model.traits.HttpBearerAuthTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.customize.AdHocCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.adhocCustomization
+import software.amazon.smithy.rust.codegen.core.util.extendIf
+
+/** Returns true if the service supports the `@httpBearerAuth` auth scheme */
+private fun ClientCodegenContext.usesBearerAuth(): Boolean =
+    ServiceIndex.of(model).getAuthSchemes(serviceShape).containsKey(HttpBearerAuthTrait.ID)
+
+/**
+ * Adds a `.token_provider` to the `Config` of services that use the `@httpBearerAuth` auth scheme, and
+ * copies the token provider from `SdkConfig` into it.
+ *
+ * The token provider is registered as the HTTP bearer auth identity resolver, the same one that's set by the
+ * `bearer_token` and `bearer_token_resolver` config methods from `HttpAuthDecorator`, so whichever is set last wins.
+ */
+class TokenProviderDecorator : ClientCodegenDecorator {
+    override val name: String = "TokenProvider"
+    override val order: Byte = 0
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> = baseCustomizations.extendIf(codegenContext.usesBearerAuth()) {
+        TokenProviderConfig(codegenContext)
+    }
+
+    override fun extraSections(codegenContext: ClientCodegenContext): List<AdHocCustomization> =
+        if (codegenContext.usesBearerAuth()) {
+            listOf(
+                adhocCustomization<SdkConfigSection.CopySdkConfigToClientConfig> { section ->
+                    rust("${section.serviceConfigBuilder}.set_token_provider(${section.sdkConfig}.token_provider());")
+                },
+            )
+        } else {
+            emptyList()
+        }
+}
+
+/**
+ * Add a `.token_provider` field and builder to the `Config` for a given service
+ */
+class TokenProviderConfig(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "ProvideToken" to AwsRuntimeType.awsCredentialTypes(runtimeConfig)
+            .resolve("provider::token::ProvideToken"),
+        "SharedTokenProvider" to AwsRuntimeType.awsCredentialTypes(runtimeConfig)
+            .resolve("provider::token::SharedTokenProvider"),
+        "HTTP_BEARER_AUTH_SCHEME_ID" to CargoDependency.smithyRuntimeApi(runtimeConfig)
+            .withFeature("http-auth").toType()
+            .resolve("client::auth::http::HTTP_BEARER_AUTH_SCHEME_ID"),
+    )
+
+    override fun section(section: ServiceConfig) = writable {
+        when (section) {
+            ServiceConfig.ConfigImpl -> {
+                rustTemplate(
+                    """
+                    /// Returns the bearer token provider for this service
+                    pub fn token_provider(&self) -> #{Option}<#{SharedTokenProvider}> {
+                        self.config.load::<#{SharedTokenProvider}>().cloned()
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            ServiceConfig.BuilderImpl -> {
+                rustTemplate(
+                    """
+                    /// Sets the bearer token provider for this service
+                    ///
+                    /// This replaces any bearer token or bearer token provider that was previously set.
+                    pub fn token_provider(mut self, token_provider: impl #{ProvideToken} + 'static) -> Self {
+                        self.set_token_provider(#{Some}(#{SharedTokenProvider}::new(token_provider)));
+                        self
+                    }
+
+                    /// Sets the bearer token provider for this service
+                    ///
+                    /// This replaces any bearer token or bearer token provider that was previously set.
+                    pub fn set_token_provider(&mut self, token_provider: #{Option}<#{SharedTokenProvider}>) -> &mut Self {
+                        if let #{Some}(token_provider) = &token_provider {
+                            self.runtime_components.set_identity_resolver(#{HTTP_BEARER_AUTH_SCHEME_ID}, token_provider.clone());
+                        }
+                        self.config.store_or_unset(token_provider);
+                        self
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            else -> emptySection
+        }
+    }
+}
@@ -0,0 +1,161 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rustsdk
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+/**
+ * Verifies that the `AccountId` endpoint parameter is filled in from the resolved credentials, and that the
+ * `AccountIdEndpointMode` parameter controls whether it's used
+ */
+class AccountIdEndpointTest {
+    // Requests are routed to an account specific host when the account ID is known. When the mode is `required`
+    // and there is no account ID, endpoint resolution fails.
+    private val model = """
+        namespace test
+
+        use aws.api#service
+        use aws.auth#sigv4
+        use aws.protocols#restJson1
+        use smithy.rules#endpointRuleSet
+
+        @service(sdkId: "dontcare")
+        @restJson1
+        @sigv4(name: "dontcare")
+        @auth([sigv4])
+        @endpointRuleSet({
+            "version": "1.0"
+            "parameters": {
+                "Region": { "required": false, "type": "String", "builtIn": "AWS::Region" },
+                "AccountId": { "required": false, "type": "String", "builtIn": "AWS::Auth::AccountId" },
+                "AccountIdEndpointMode": { "required": false, "type": "String", "builtIn": "AWS::Auth::AccountIdEndpointMode" },
+            }
+            "rules": [
+                {
+                    "type": "endpoint"
+                    "conditions": [
+                        {"fn": "isSet", "argv": [{"ref": "AccountId"}]}
+                    ],
+                    "endpoint": { "url": "https://{AccountId}.example.com" }
+                },
+                {
+                    "type": "error"
+                    "conditions": [
+                        {"fn": "isSet", "argv": [{"ref": "AccountIdEndpointMode"}]},
+                        {"fn": "stringEquals", "argv": [{"ref": "AccountIdEndpointMode"}, "required"]}
+                    ],
+                    "error": "AccountIdEndpointMode is required but no AccountId was provided"
+                },
+                {
+                    "type": "endpoint"
+                    "conditions": [],
+                    "endpoint": { "url": "https://example.com" }
+                }
+            ]
+        })
+        service TestService {
+            version: "2023-01-01",
+            operations: [SomeOperation]
+        }
+
+        @http(uri: "/SomeOperation", method: "GET")
+        operation SomeOperation {}
+    """.asSmithyModel()
+
+    @Test
+    fun `account ID endpoint params are derived from credentials`() {
+        awsSdkIntegrationTest(model) { context, rustCrate ->
+            val moduleName = context.moduleUseName()
+            val rc = context.runtimeConfig
+            val codegenScope = arrayOf(
+                "capture_request" to RuntimeType.captureRequest(rc),
+                "Credentials" to AwsRuntimeType.awsCredentialTypesTestUtil(rc).resolve("Credentials"),
+                "Region" to AwsRuntimeType.awsTypes(rc).resolve("region::Region"),
+                "AccountIdEndpointMode" to AwsRuntimeType.awsTypes(rc)
+                    .resolve("endpoint_config::AccountIdEndpointMode"),
+            )
+            fun client(mode: String?, credentials: String): Writable = writable {
+                rustTemplate(
+                    """
+                    let (http_client, rcvr) = #{capture_request}(None);
+                    let conf = $moduleName::Config::builder()
+                        .http_client(http_client)
+                        .region(#{Region}::new("us-east-1"))
+                        .credentials_provider($credentials)
+                        ${mode?.let { ".account_id_endpoint_mode(#{AccountIdEndpointMode}::$it)" } ?: ""}
+                        .build();
+                    let client = $moduleName::Client::from_conf(conf);
+                    """,
+                    *codegenScope,
+                )
+            }
+            val withAccountId = "#{Credentials}::for_tests().with_account_id(\"123456789012\")"
+
+            rustCrate.integrationTest("account_id_endpoint") {
+                tokioTest("account_id_is_filled_from_credentials") {
+                    rustTemplate(
+                        """
+                        #{client:W}
+                        let _ = client.some_operation().send().await;
+                        let req = rcvr.expect_request();
+                        assert_eq!("https://123456789012.example.com/SomeOperation", req.uri());
+                        """,
+                        "client" to client(null, withAccountId),
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("disabled_mode_ignores_the_account_id") {
+                    rustTemplate(
+                        """
+                        #{client:W}
+                        let _ = client.some_operation().send().await;
+                        let req = rcvr.expect_request();
+                        assert_eq!("https://example.com/SomeOperation", req.uri());
+                        """,
+                        "client" to client("Disabled", withAccountId),
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("required_mode_uses_the_account_id") {
+                    rustTemplate(
+                        """
+                        #{client:W}
+                        let _ = client.some_operation().send().await;
+                        let req = rcvr.expect_request();
+                        assert_eq!("https://123456789012.example.com/SomeOperation", req.uri());
+                        """,
+                        "client" to client("Required", withAccountId),
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("required_mode_fails_without_an_account_id") {
+                    rustTemplate(
+                        """
+                        #{client:W}
+                        let err = client.some_operation().send().await.expect_err("no account ID is available");
+                        let message = format!("{}", #{DisplayErrorContext}(&err));
+                        assert!(message.contains("no AccountId was provided"), "{}", message);
+                        rcvr.expect_no_request();
+                        """,
+                        "client" to client("Required", "#{Credentials}::for_tests()"),
+                        "DisplayErrorContext" to RuntimeType.smithyTypes(rc).resolve("error::display::DisplayErrorContext"),
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
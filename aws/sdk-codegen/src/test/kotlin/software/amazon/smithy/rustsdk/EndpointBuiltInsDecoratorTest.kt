@@ -9,6 +9,7 @@ import org.junit.jupiter.api.Test
 import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
 import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
 
@@ -116,4 +117,95 @@ class EndpointBuiltInsDecoratorTest {
             }
         }
     }
+
+    @Test
+    fun serviceSpecificEndpointUrlsOverrideTheGlobalEndpointUrl() {
+        awsSdkIntegrationTest(endpointUrlModel) { codegenContext, rustCrate ->
+            rustCrate.integrationTest("endpoint_url_service_config") {
+                val module = codegenContext.moduleUseName()
+                rustTemplate(
+                    """
+                    use $module::{Client, config::Region};
+                    use #{aws_config}::profile::profile_file::{ProfileFileKind, ProfileFiles};
+
+                    const PROFILE: &str = r##"
+                    [default]
+                    endpoint_url = https://global-profile
+                    services = local
+
+                    [services local]
+                    dontcare =
+                      endpoint_url = https://service-profile
+                    "##;
+
+                    /// Sends a request with a client created from the shared config and returns the URI it was sent to
+                    async fn request_uri(profile: &str, loader_endpoint_url: #{Option}<&str>, client_endpoint_url: #{Option}<&str>) -> #{String} {
+                        let config_file = #{tempfile}::NamedTempFile::new().unwrap();
+                        #{std}::fs::write(config_file.path(), profile).unwrap();
+                        let (http_client, request) = #{capture_request}(#{None});
+                        let mut loader = #{aws_config}::from_env()
+                            .http_client(http_client)
+                            .region(Region::new("us-east-1"))
+                            .no_credentials()
+                            .profile_files(
+                                ProfileFiles::builder()
+                                    .with_file(ProfileFileKind::Config, config_file.path())
+                                    .build(),
+                            );
+                        if let #{Some}(endpoint_url) = loader_endpoint_url {
+                            loader = loader.endpoint_url(endpoint_url);
+                        }
+                        let sdk_config = loader.load().await;
+                        let mut config = $module::config::Builder::from(&sdk_config);
+                        if let #{Some}(endpoint_url) = client_endpoint_url {
+                            config = config.endpoint_url(endpoint_url);
+                        }
+                        let client = Client::from_conf(config.build());
+                        let _ = client.some_operation().send().await;
+                        request.expect_request().uri().to_string()
+                    }
+
+                    // Environment variables are shared by the whole process, so every case runs in a single test
+                    ##[#{tokio}::test]
+                    async fn service_specific_endpoint_urls_override_the_global_endpoint_url() {
+                        #{std}::env::remove_var("AWS_ENDPOINT_URL");
+                        #{std}::env::remove_var("AWS_ENDPOINT_URL_DONTCARE");
+
+                        // the `[services]` section overrides the profile's `endpoint_url`
+                        assert_eq!("https://service-profile/SomeOperation", request_uri(PROFILE, #{None}, #{None}).await);
+                        assert_eq!(
+                            "https://global-profile/SomeOperation",
+                            request_uri("[default]\nendpoint_url = https://global-profile", #{None}, #{None}).await
+                        );
+
+                        // `AWS_ENDPOINT_URL_<SERVICE>` overrides `AWS_ENDPOINT_URL` and the profile
+                        #{std}::env::set_var("AWS_ENDPOINT_URL", "https://global-env");
+                        assert_eq!("https://global-env/SomeOperation", request_uri("", #{None}, #{None}).await);
+                        #{std}::env::set_var("AWS_ENDPOINT_URL_DONTCARE", "https://service-env");
+                        assert_eq!("https://service-env/SomeOperation", request_uri(PROFILE, #{None}, #{None}).await);
+
+                        // an explicitly configured endpoint URL always wins
+                        assert_eq!(
+                            "https://explicit/SomeOperation",
+                            request_uri(PROFILE, #{Some}("https://explicit"), #{None}).await
+                        );
+                        assert_eq!(
+                            "https://client/SomeOperation",
+                            request_uri(PROFILE, #{Some}("https://explicit"), #{Some}("https://client")).await
+                        );
+
+                        #{std}::env::remove_var("AWS_ENDPOINT_URL");
+                        #{std}::env::remove_var("AWS_ENDPOINT_URL_DONTCARE");
+                    }
+                    """,
+                    *preludeScope,
+                    "aws_config" to AwsCargoDependency.awsConfig(codegenContext.runtimeConfig).toDevDependency().toType(),
+                    "capture_request" to RuntimeType.captureRequest(codegenContext.runtimeConfig),
+                    "std" to RuntimeType.std,
+                    "tempfile" to CargoDependency.TempFile.toType(),
+                    "tokio" to CargoDependency.Tokio.toDevDependency().withFeature("rt").withFeature("macros").toType(),
+                )
+            }
+        }
+    }
 }
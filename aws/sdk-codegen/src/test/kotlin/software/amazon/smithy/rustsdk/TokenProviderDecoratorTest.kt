@@ -0,0 +1,127 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rustsdk
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+class TokenProviderDecoratorTest {
+    private val model = """
+        namespace test
+
+        use aws.api#service
+        use aws.protocols#restJson1
+        use smithy.rules#endpointRuleSet
+
+        @service(sdkId: "dontcare")
+        @restJson1
+        @httpBearerAuth
+        @auth([httpBearerAuth])
+        @endpointRuleSet({
+            "version": "1.0"
+            "parameters": {
+                "Region": { "required": false, "type": "String", "builtIn": "AWS::Region" },
+            }
+            "rules": [
+                {
+                    "type": "endpoint"
+                    "conditions": [],
+                    "endpoint": { "url": "https://example.com" }
+                }
+            ]
+        })
+        service TestService {
+            version: "2023-01-01",
+            operations: [SomeOperation]
+        }
+
+        @http(uri: "/SomeOperation", method: "GET")
+        operation SomeOperation {}
+    """.asSmithyModel()
+
+    @Test
+    fun `the last bearer token or token provider that was set is used`() {
+        awsSdkIntegrationTest(model) { context, rustCrate ->
+            val moduleName = context.moduleUseName()
+            val rc = context.runtimeConfig
+            val codegenScope = arrayOf(
+                "capture_request" to RuntimeType.captureRequest(rc),
+                "SdkConfig" to AwsRuntimeType.awsTypes(rc).resolve("sdk_config::SdkConfig"),
+                "SharedTokenProvider" to AwsRuntimeType.awsCredentialTypes(rc)
+                    .resolve("provider::token::SharedTokenProvider"),
+            )
+            rustCrate.integrationTest("token_provider") {
+                rustTemplate(
+                    """
+                    use $moduleName::config::{Builder, Token};
+
+                    async fn authorization(builder: Builder) -> String {
+                        let (http_client, rcvr) = #{capture_request}(None);
+                        let client = $moduleName::Client::from_conf(builder.http_client(http_client).build());
+                        let _ = client.some_operation().send().await;
+                        let req = rcvr.expect_request();
+                        req.headers().get("authorization").expect("request is signed").to_string()
+                    }
+                    """,
+                    *codegenScope,
+                )
+
+                tokioTest("token_provider_is_used_for_bearer_auth") {
+                    rustTemplate(
+                        """
+                        let builder = Builder::new().token_provider(Token::new("provided", None));
+                        assert_eq!("Bearer provided", authorization(builder).await);
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("bearer_token_replaces_token_provider") {
+                    rustTemplate(
+                        """
+                        let builder = Builder::new()
+                            .token_provider(Token::new("provided", None))
+                            .bearer_token(Token::new("explicit", None));
+                        assert_eq!("Bearer explicit", authorization(builder).await);
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("token_provider_replaces_bearer_token") {
+                    rustTemplate(
+                        """
+                        let builder = Builder::new()
+                            .bearer_token(Token::new("explicit", None))
+                            .token_provider(Token::new("provided", None));
+                        assert_eq!("Bearer provided", authorization(builder).await);
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("bearer_token_replaces_sdk_config_token_provider") {
+                    rustTemplate(
+                        """
+                        let sdk_config = #{SdkConfig}::builder()
+                            .token_provider(#{SharedTokenProvider}::new(Token::new("shared", None)))
+                            .build();
+                        let builder = Builder::from(&sdk_config);
+                        assert_eq!("Bearer shared", authorization(builder.clone()).await);
+                        let builder = builder.bearer_token(Token::new("explicit", None));
+                        assert_eq!("Bearer explicit", authorization(builder).await);
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
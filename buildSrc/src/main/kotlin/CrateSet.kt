@@ -14,11 +14,13 @@ object CrateSet {
         "aws-runtime-api",
         "aws-sig-auth",
         "aws-sigv4",
+        "aws-sigv4-server",
         "aws-types",
     )
 
     private val SMITHY_RUNTIME_COMMON = listOf(
         "aws-smithy-async",
+        "aws-smithy-cbor",
         "aws-smithy-checksums",
         "aws-smithy-client",
         "aws-smithy-eventstream",
@@ -35,7 +37,10 @@ object CrateSet {
         "aws-smithy-xml",
     )
 
-    val AWS_SDK_SMITHY_RUNTIME = SMITHY_RUNTIME_COMMON
+    val AWS_SDK_SMITHY_RUNTIME = SMITHY_RUNTIME_COMMON + listOf(
+        // Required by `aws-sigv4-server`
+        "aws-smithy-http-server",
+    )
 
     val SERVER_SMITHY_RUNTIME = SMITHY_RUNTIME_COMMON + listOf(
         "aws-smithy-http-server",
@@ -63,6 +63,7 @@ val allCodegenTests = listOf(
     ClientTest("com.amazonaws.ebs#Ebs", "ebs", dependsOn = listOf("ebs.json")),
     ClientTest("aws.protocoltests.json10#JsonRpc10", "json_rpc10"),
     ClientTest("aws.protocoltests.json#JsonProtocol", "json_rpc11"),
+    ClientTest("smithy.protocoltests.rpcv2Cbor#RpcV2Protocol", "rpcv2Cbor", dependsOn = listOf("rpcv2Cbor.smithy")),
     ClientTest("aws.protocoltests.restjson#RestJson", "rest_json"),
     ClientTest(
         "aws.protocoltests.restjson#RestJsonExtras",
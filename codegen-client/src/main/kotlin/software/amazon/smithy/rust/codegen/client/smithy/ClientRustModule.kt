@@ -80,6 +80,9 @@ object ClientRustModule {
     val Output = RustModule.public("output")
     val Primitives = RustModule.public("primitives")
 
+    /** crate::waiters */
+    val waiters = RustModule.public("waiters")
+
     /** crate::types */
     val types = Types.self
     object Types {
@@ -113,6 +116,7 @@ class ClientModuleDocProvider(
             ClientRustModule.Primitives -> strDoc("Primitives such as `Blob` or `DateTime` used by other types.")
             ClientRustModule.types -> strDoc("Data structures used by operation inputs/outputs.")
             ClientRustModule.Types.Error -> strDoc("Error types that $serviceName can respond with.")
+            ClientRustModule.waiters -> strDoc("Supporting types for waiters.")
             else -> TODO("Document this module: $module")
         }
     }
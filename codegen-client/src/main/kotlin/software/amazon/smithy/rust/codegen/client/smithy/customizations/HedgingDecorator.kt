@@ -0,0 +1,145 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.knowledge.TopDownIndex
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.IdempotentTrait
+import software.amazon.smithy.model.traits.ReadonlyTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.extendIf
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+
+private fun hedging(runtimeConfig: RuntimeConfig) =
+    RuntimeType.smithyRuntime(runtimeConfig).resolve("client::retries::hedging")
+
+/** Returns true if requests to this operation can safely be sent more than once */
+private fun OperationShape.isIdempotent(): Boolean = hasTrait<ReadonlyTrait>() || hasTrait<IdempotentTrait>()
+
+/**
+ * Adds configuration for request hedging, and marks operations modeled with the `@readonly` or `@idempotent`
+ * traits as eligible for it.
+ */
+class HedgingDecorator : ClientCodegenDecorator {
+    override val name: String = "Hedging"
+    override val order: Byte = 0
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> = baseCustomizations.extendIf(codegenContext.hasIdempotentOperations()) {
+        HedgingConfigCustomization(codegenContext)
+    }
+
+    override fun operationCustomizations(
+        codegenContext: ClientCodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>,
+    ): List<OperationCustomization> = baseCustomizations.extendIf(operation.isIdempotent()) {
+        HedgingOperationCustomization(codegenContext.runtimeConfig)
+    }
+
+    override fun extras(codegenContext: ClientCodegenContext, rustCrate: RustCrate) {
+        if (codegenContext.hasIdempotentOperations()) {
+            rustCrate.withModule(ClientRustModule.Config.retry) {
+                rustTemplate(
+                    "pub use #{hedging}::HedgingConfig;",
+                    "hedging" to hedging(codegenContext.runtimeConfig),
+                )
+            }
+        }
+    }
+}
+
+private class HedgingOperationCustomization(private val runtimeConfig: RuntimeConfig) : OperationCustomization() {
+    override fun section(section: OperationSection): Writable = writable {
+        if (section is OperationSection.AdditionalRuntimePluginConfig) {
+            rustTemplate(
+                "${section.newLayerName}.store_put(#{IdempotentOperation});",
+                "IdempotentOperation" to hedging(runtimeConfig).resolve("IdempotentOperation"),
+            )
+        }
+    }
+}
+
+private class HedgingConfigCustomization(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val moduleUseName = codegenContext.moduleUseName()
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "HedgingConfig" to hedging(codegenContext.runtimeConfig).resolve("HedgingConfig"),
+    )
+
+    override fun section(section: ServiceConfig): Writable = writable {
+        when (section) {
+            ServiceConfig.ConfigImpl -> {
+                rustTemplate(
+                    """
+                    /// Returns the request hedging configuration, if it was configured.
+                    pub fn hedging_config(&self) -> #{Option}<&#{HedgingConfig}> {
+                        self.config.load::<#{HedgingConfig}>()
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            ServiceConfig.BuilderImpl -> {
+                rustTemplate(
+                    """
+                    /// Enables request hedging with the given configuration.
+                    ///
+                    /// When enabled, an attempt of an idempotent operation that is slower than recent requests
+                    /// sends a second request in parallel, and uses whichever successful response arrives first.
+                    /// Hedged requests are paid for out of the retry quota.
+                    ///
+                    /// ## Examples
+                    /// ```no_run
+                    /// use $moduleUseName::config::Config;
+                    /// use $moduleUseName::config::retry::HedgingConfig;
+                    ///
+                    /// let hedging_config = HedgingConfig::new().with_percentile(0.99);
+                    /// let config = Config::builder().hedging_config(hedging_config).build();
+                    /// ```
+                    pub fn hedging_config(mut self, hedging_config: #{HedgingConfig}) -> Self {
+                        self.set_hedging_config(#{Some}(hedging_config));
+                        self
+                    }
+
+                    /// Enables request hedging with the given configuration, or disables it when `None`.
+                    ///
+                    /// When enabled, an attempt of an idempotent operation that is slower than recent requests
+                    /// sends a second request in parallel, and uses whichever successful response arrives first.
+                    /// Hedged requests are paid for out of the retry quota.
+                    pub fn set_hedging_config(&mut self, hedging_config: #{Option}<#{HedgingConfig}>) -> &mut Self {
+                        self.config.store_or_unset(hedging_config);
+                        self
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            else -> {}
+        }
+    }
+}
+
+/** Returns true if any operation of the service can be hedged */
+fun ClientCodegenContext.hasIdempotentOperations(): Boolean =
+    TopDownIndex.of(model).getContainedOperations(serviceShape).any { it.isIdempotent() }
@@ -23,6 +23,7 @@ import software.amazon.smithy.rust.codegen.client.smithy.generators.config.Confi
 import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
 import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
@@ -44,6 +45,7 @@ private fun codegenScope(runtimeConfig: RuntimeConfig): Array<Pair<String, Any>>
         "BasicAuthScheme" to authHttp.resolve("BasicAuthScheme"),
         "BearerAuthScheme" to authHttp.resolve("BearerAuthScheme"),
         "DigestAuthScheme" to authHttp.resolve("DigestAuthScheme"),
+        "DigestAuthChallengeRetryClassifier" to authHttp.resolve("DigestAuthChallengeRetryClassifier"),
         "HTTP_API_KEY_AUTH_SCHEME_ID" to authHttpApi.resolve("HTTP_API_KEY_AUTH_SCHEME_ID"),
         "HTTP_BASIC_AUTH_SCHEME_ID" to authHttpApi.resolve("HTTP_BASIC_AUTH_SCHEME_ID"),
         "HTTP_BEARER_AUTH_SCHEME_ID" to authHttpApi.resolve("HTTP_BEARER_AUTH_SCHEME_ID"),
@@ -206,7 +208,18 @@ private class HttpAuthServiceRuntimePluginCustomization(
                     registerNamedAuthScheme("BearerAuthScheme")
                 }
                 if (authSchemes.digest) {
-                    registerNamedAuthScheme("DigestAuthScheme")
+                    // The digest auth scheme shares challenge state with its interceptor, so they
+                    // must be created from the same instance.
+                    rustTemplate("let digest_auth_scheme = #{DigestAuthScheme}::new();", *codegenScope)
+                    section.registerInterceptor(this) {
+                        rust("digest_auth_scheme.challenge_interceptor()")
+                    }
+                    section.registerRetryClassifier(this) {
+                        rustTemplate("#{DigestAuthChallengeRetryClassifier}::new()", *codegenScope)
+                    }
+                    registerAuthScheme {
+                        rust("digest_auth_scheme")
+                    }
                 }
             }
 
@@ -253,8 +266,10 @@ private class HttpAuthConfigCustomization(
                         }
 
                         /// Sets a bearer token provider that will be used for HTTP bearer auth.
+                        ///
+                        /// This replaces any bearer token or bearer token provider that was previously set.
                         pub fn bearer_token_resolver(mut self, bearer_token_resolver: impl #{ResolveIdentity} + 'static) -> Self {
-                            self.runtime_components.push_identity_resolver(
+                            self.runtime_components.set_identity_resolver(
                                 #{HTTP_BEARER_AUTH_SCHEME_ID},
                                 #{SharedIdentityResolver}::new(bearer_token_resolver)
                             );
@@ -0,0 +1,172 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.knowledge.TopDownIndex
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.RequestCompressionTrait
+import software.amazon.smithy.model.traits.RequiresLengthTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.util.extendIf
+import software.amazon.smithy.rust.codegen.core.util.findStreamingMember
+import software.amazon.smithy.rust.codegen.core.util.getTrait
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+
+private fun requestCompression(runtimeConfig: RuntimeConfig) =
+    CargoDependency.smithyRuntime(runtimeConfig).withFeature("request-compression").toType()
+        .resolve("client::http::request_compression")
+
+/** Maps the encodings of the `@requestCompression` trait to the algorithms supported by the runtime */
+private val supportedAlgorithms = mapOf(
+    "gzip" to "Gzip",
+)
+
+/**
+ * Returns the name of the `CompressionAlgorithm` variant to compress this operation's requests with,
+ * or null if its requests shouldn't be compressed.
+ */
+private fun OperationShape.compressionAlgorithm(model: Model): String? {
+    val trait = getTrait<RequestCompressionTrait>() ?: return null
+    // Streaming bodies can't be compressed if the service requires their length up front
+    val streamingMember = inputShape(model).findStreamingMember(model)
+    if (streamingMember != null && model.expectShape(streamingMember.target).hasTrait<RequiresLengthTrait>()) {
+        return null
+    }
+    return trait.encodings.firstNotNullOfOrNull { supportedAlgorithms[it.lowercase()] }
+}
+
+/**
+ * Compresses the request bodies of operations modeled with the `@requestCompression` trait, and adds
+ * configuration to disable compression or change the minimum size of bodies that get compressed.
+ */
+class RequestCompressionDecorator : ClientCodegenDecorator {
+    override val name: String = "RequestCompression"
+    override val order: Byte = 0
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> = baseCustomizations.extendIf(codegenContext.hasRequestCompression()) {
+        RequestCompressionConfigCustomization(codegenContext.runtimeConfig)
+    }
+
+    override fun operationCustomizations(
+        codegenContext: ClientCodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>,
+    ): List<OperationCustomization> {
+        val algorithm = operation.compressionAlgorithm(codegenContext.model) ?: return baseCustomizations
+        return baseCustomizations + RequestCompressionOperationCustomization(codegenContext.runtimeConfig, algorithm)
+    }
+}
+
+private class RequestCompressionOperationCustomization(
+    private val runtimeConfig: RuntimeConfig,
+    private val algorithm: String,
+) : OperationCustomization() {
+    override fun section(section: OperationSection): Writable = writable {
+        if (section is OperationSection.AdditionalInterceptors) {
+            section.registerInterceptor(runtimeConfig, this) {
+                val requestCompression = requestCompression(runtimeConfig)
+                rustTemplate(
+                    "#{RequestCompressionInterceptor}::new(#{CompressionAlgorithm}::$algorithm)",
+                    "CompressionAlgorithm" to requestCompression.resolve("CompressionAlgorithm"),
+                    "RequestCompressionInterceptor" to requestCompression.resolve("RequestCompressionInterceptor"),
+                )
+            }
+        }
+    }
+}
+
+private class RequestCompressionConfigCustomization(runtimeConfig: RuntimeConfig) : ConfigCustomization() {
+    private val requestCompression = requestCompression(runtimeConfig)
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "DisableRequestCompression" to requestCompression.resolve("DisableRequestCompression"),
+        "RequestMinCompressionSizeBytes" to requestCompression.resolve("RequestMinCompressionSizeBytes"),
+    )
+
+    override fun section(section: ServiceConfig): Writable = writable {
+        when (section) {
+            ServiceConfig.ConfigImpl -> {
+                rustTemplate(
+                    """
+                    /// Returns whether request compression is disabled, if it was configured.
+                    pub fn disable_request_compression(&self) -> #{Option}<bool> {
+                        self.config.load::<#{DisableRequestCompression}>().map(|v| v.disabled())
+                    }
+
+                    /// Returns the minimum size in bytes of request bodies to compress, if it was configured.
+                    pub fn request_min_compression_size_bytes(&self) -> #{Option}<u32> {
+                        self.config.load::<#{RequestMinCompressionSizeBytes}>().map(|v| v.bytes())
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            ServiceConfig.BuilderImpl -> {
+                rustTemplate(
+                    """
+                    /// Sets whether request compression is disabled.
+                    ///
+                    /// When true, request bodies won't be compressed, even for operations that support request compression.
+                    pub fn disable_request_compression(mut self, disable_request_compression: bool) -> Self {
+                        self.set_disable_request_compression(#{Some}(disable_request_compression));
+                        self
+                    }
+
+                    /// Sets whether request compression is disabled.
+                    ///
+                    /// When true, request bodies won't be compressed, even for operations that support request compression.
+                    pub fn set_disable_request_compression(&mut self, disable_request_compression: #{Option}<bool>) -> &mut Self {
+                        self.config.store_or_unset(disable_request_compression.map(#{DisableRequestCompression}::new));
+                        self
+                    }
+
+                    /// Sets the minimum size in bytes that a request body must be to get compressed.
+                    ///
+                    /// Valid values are between 0 and 10485760 inclusive, and the default is 10240.
+                    /// Streaming request bodies are always compressed, regardless of this setting.
+                    pub fn request_min_compression_size_bytes(mut self, request_min_compression_size_bytes: u32) -> Self {
+                        self.set_request_min_compression_size_bytes(#{Some}(request_min_compression_size_bytes));
+                        self
+                    }
+
+                    /// Sets the minimum size in bytes that a request body must be to get compressed.
+                    ///
+                    /// Valid values are between 0 and 10485760 inclusive, and the default is 10240.
+                    /// Streaming request bodies are always compressed, regardless of this setting.
+                    pub fn set_request_min_compression_size_bytes(&mut self, request_min_compression_size_bytes: #{Option}<u32>) -> &mut Self {
+                        self.config.store_or_unset(request_min_compression_size_bytes.map(#{RequestMinCompressionSizeBytes}::new));
+                        self
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            else -> {}
+        }
+    }
+}
+
+/** Returns true if any operation of the service compresses its requests */
+fun ClientCodegenContext.hasRequestCompression(): Boolean =
+    TopDownIndex.of(model).getContainedOperations(serviceShape).any { it.compressionAlgorithm(model) != null }
@@ -30,10 +30,12 @@ class ResiliencyConfigCustomization(codegenContext: ClientCodegenContext) : Conf
         "ClientRateLimiterPartition" to retries.resolve("ClientRateLimiterPartition"),
         "debug" to RuntimeType.Tracing.resolve("debug"),
         "IntoShared" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("shared::IntoShared"),
+        "LimitRequests" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("client::request_limiter::LimitRequests"),
         "RetryConfig" to retryConfig.resolve("RetryConfig"),
         "RetryMode" to RuntimeType.smithyTypes(runtimeConfig).resolve("retry::RetryMode"),
         "RetryPartition" to retries.resolve("RetryPartition"),
         "SharedAsyncSleep" to sleepModule.resolve("SharedAsyncSleep"),
+        "SharedRequestLimiter" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("client::request_limiter::SharedRequestLimiter"),
         "SharedRetryStrategy" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("client::retries::SharedRetryStrategy"),
         "SharedTimeSource" to RuntimeType.smithyAsync(runtimeConfig).resolve("time::SharedTimeSource"),
         "Sleep" to sleepModule.resolve("Sleep"),
@@ -63,6 +65,11 @@ class ResiliencyConfigCustomization(codegenContext: ClientCodegenContext) : Conf
                             self.config.load::<#{TimeoutConfig}>()
                         }
 
+                        /// Return a cloned shared request limiter from this config, if any.
+                        pub fn request_limiter(&self) -> #{Option}<#{SharedRequestLimiter}> {
+                            self.runtime_components.request_limiter()
+                        }
+
                         ##[doc(hidden)]
                         /// Returns a reference to the retry partition contained in this config, if any.
                         ///
@@ -246,6 +253,41 @@ class ResiliencyConfigCustomization(codegenContext: ClientCodegenContext) : Conf
                         *codegenScope,
                     )
 
+                    rustTemplate(
+                        """
+                        /// Set the request limiter for the builder
+                        ///
+                        /// A request limiter caps the rate and concurrency of the requests that the client sends, including
+                        /// retries, so that a client can be kept under a service quota.
+                        ///
+                        /// ## Examples
+                        ///
+                        /// ```no_run
+                        /// use $moduleUseName::config::{Config, RequestLimiter};
+                        ///
+                        /// let request_limiter = RequestLimiter::builder()
+                        ///     .requests_per_second(50.0)
+                        ///     .max_concurrent_requests(10)
+                        ///     .build();
+                        /// let config = Config::builder().request_limiter(request_limiter).build();
+                        /// ```
+                        pub fn request_limiter(mut self, request_limiter: impl #{LimitRequests} + 'static) -> Self {
+                            self.set_request_limiter(#{Some}(#{IntoShared}::into_shared(request_limiter)));
+                            self
+                        }
+
+                        /// Set the request limiter for the builder
+                        ///
+                        /// A request limiter caps the rate and concurrency of the requests that the client sends, including
+                        /// retries, so that a client can be kept under a service quota.
+                        pub fn set_request_limiter(&mut self, request_limiter: #{Option}<#{SharedRequestLimiter}>) -> &mut Self {
+                            self.runtime_components.set_request_limiter(request_limiter);
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+
                     Attribute.DocHidden.render(this)
                     rustTemplate(
                         """
@@ -289,6 +331,10 @@ class ResiliencyReExportCustomization(codegenContext: ClientCodegenContext) {
                 "pub use #{sleep}::{AsyncSleep, SharedAsyncSleep, Sleep};",
                 "sleep" to RuntimeType.smithyAsync(runtimeConfig).resolve("rt::sleep"),
             )
+            rustTemplate(
+                "pub use #{request_limiter}::{RequestLimitScope, RequestLimiter, RequestLimiterBuilder};",
+                "request_limiter" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::request_limiter"),
+            )
         }
         rustCrate.withModule(ClientRustModule.Config.retry) {
             rustTemplate(
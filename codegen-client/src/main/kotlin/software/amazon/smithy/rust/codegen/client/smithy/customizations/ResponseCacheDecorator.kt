@@ -0,0 +1,154 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.knowledge.TopDownIndex
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.ReadonlyTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.extendIf
+import software.amazon.smithy.rust.codegen.core.util.hasStreamingMember
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+private fun responseCache(runtimeConfig: RuntimeConfig) =
+    RuntimeType.smithyRuntime(runtimeConfig).resolve("client::response_cache")
+
+/** Returns true if this operation's responses can be cached. Streaming responses are never cached. */
+private fun OperationShape.isCacheable(model: Model): Boolean =
+    hasTrait<ReadonlyTrait>() && !outputShape(model).hasStreamingMember(model)
+
+/**
+ * Adds configuration for caching responses, and marks operations modeled with the `@readonly` trait as
+ * cacheable.
+ */
+class ResponseCacheDecorator : ClientCodegenDecorator {
+    override val name: String = "ResponseCache"
+    override val order: Byte = 0
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> = baseCustomizations.extendIf(codegenContext.hasCacheableOperations()) {
+        ResponseCacheConfigCustomization(codegenContext)
+    }
+
+    override fun operationCustomizations(
+        codegenContext: ClientCodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>,
+    ): List<OperationCustomization> = baseCustomizations.extendIf(operation.isCacheable(codegenContext.model)) {
+        ResponseCacheOperationCustomization(codegenContext.runtimeConfig, operation)
+    }
+
+    override fun extras(codegenContext: ClientCodegenContext, rustCrate: RustCrate) {
+        if (codegenContext.hasCacheableOperations()) {
+            rustCrate.withModule(ClientRustModule.config) {
+                rustTemplate(
+                    "pub use #{response_cache}::{InMemoryResponseStore, ResponseCache, ResponseCacheBuilder, StoreResponses};",
+                    "response_cache" to responseCache(codegenContext.runtimeConfig),
+                )
+            }
+        }
+    }
+}
+
+private class ResponseCacheOperationCustomization(
+    private val runtimeConfig: RuntimeConfig,
+    private val operation: OperationShape,
+) : OperationCustomization() {
+    override fun section(section: OperationSection): Writable = writable {
+        if (section is OperationSection.AdditionalRuntimePluginConfig) {
+            rustTemplate(
+                "${section.newLayerName}.store_put(#{CacheableOperation}::new(${operation.id.toString().dq()}));",
+                "CacheableOperation" to responseCache(runtimeConfig).resolve("CacheableOperation"),
+            )
+        }
+    }
+}
+
+private class ResponseCacheConfigCustomization(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val moduleUseName = codegenContext.moduleUseName()
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "ResponseCache" to responseCache(codegenContext.runtimeConfig).resolve("ResponseCache"),
+    )
+
+    override fun section(section: ServiceConfig): Writable = writable {
+        when (section) {
+            ServiceConfig.ConfigImpl -> {
+                rustTemplate(
+                    """
+                    /// Returns the response cache, if one was configured.
+                    pub fn response_cache(&self) -> #{Option}<&#{ResponseCache}> {
+                        self.config.load::<#{ResponseCache}>()
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            ServiceConfig.BuilderImpl -> {
+                rustTemplate(
+                    """
+                    /// Sets the cache that the responses to read-only operations are cached in.
+                    ///
+                    /// Response caching is disabled by default. When enabled, a fresh cached response is
+                    /// returned without sending the request, and expired responses with an `ETag` are
+                    /// revalidated with the service.
+                    ///
+                    /// ## Examples
+                    /// ```no_run
+                    /// use std::time::Duration;
+                    /// use $moduleUseName::config::{Config, ResponseCache};
+                    ///
+                    /// let response_cache = ResponseCache::builder()
+                    ///     .default_ttl(Duration::from_secs(300))
+                    ///     .build();
+                    /// let config = Config::builder().response_cache(response_cache).build();
+                    /// ```
+                    pub fn response_cache(mut self, response_cache: #{ResponseCache}) -> Self {
+                        self.set_response_cache(#{Some}(response_cache));
+                        self
+                    }
+
+                    /// Sets the cache that the responses to read-only operations are cached in.
+                    ///
+                    /// Response caching is disabled by default. When enabled, a fresh cached response is
+                    /// returned without sending the request, and expired responses with an `ETag` are
+                    /// revalidated with the service.
+                    pub fn set_response_cache(&mut self, response_cache: #{Option}<#{ResponseCache}>) -> &mut Self {
+                        self.config.store_or_unset(response_cache);
+                        self
+                    }
+                    """,
+                    *codegenScope,
+                )
+            }
+
+            else -> {}
+        }
+    }
+}
+
+/** Returns true if any operation of the service has cacheable responses */
+fun ClientCodegenContext.hasCacheableOperations(): Boolean =
+    TopDownIndex.of(model).getContainedOperations(serviceShape).any { it.isCacheable(model) }
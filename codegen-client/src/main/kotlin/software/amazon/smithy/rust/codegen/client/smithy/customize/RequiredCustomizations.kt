@@ -71,12 +71,21 @@ class RequiredCustomizations : ClientCodegenDecorator {
     override fun extras(codegenContext: ClientCodegenContext, rustCrate: RustCrate) {
         val rc = codegenContext.runtimeConfig
 
-        // Add rt-tokio feature for `ByteStream::from_path`
+        // Add rt-tokio feature for `ByteStream::from_path`. It also makes the default HTTP client and sleep
+        // implementation prefer Tokio when `rt-async-io` is enabled by another crate in the build.
         rustCrate.mergeFeature(
             Feature(
                 "rt-tokio",
                 true,
-                listOf("aws-smithy-async/rt-tokio", "aws-smithy-types/rt-tokio"),
+                listOf("aws-smithy-async/rt-tokio", "aws-smithy-runtime/rt-tokio", "aws-smithy-types/rt-tokio"),
+            ),
+        )
+        // Add rt-async-io feature for running the client on `smol`, `async-std`, or other `async-io` based runtimes
+        rustCrate.mergeFeature(
+            Feature(
+                "rt-async-io",
+                false,
+                listOf("aws-smithy-async/rt-async-io", "aws-smithy-runtime/connector-async-io"),
             ),
         )
 
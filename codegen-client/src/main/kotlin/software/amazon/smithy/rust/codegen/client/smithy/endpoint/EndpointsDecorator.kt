@@ -67,6 +67,21 @@ interface EndpointCustomization {
 
     fun setBuiltInOnServiceConfig(name: String, value: Node, configBuilderRef: String): Writable? = null
 
+    /**
+     * Finalize the endpoint parameters right before endpoint resolution
+     *
+     * [paramsRef] is a `&mut Params` and [identityRef] is an `Option<&Identity>`. This enables parameters that are
+     * derived from the identity, like an account ID, to be set. The identity is only resolved ahead of the endpoint
+     * when a customization asks for it by setting the `bool` named by [requiresIdentityRef] to `true` while
+     * [identityRef] is `None`, after which the parameters are finalized again with the identity. If this
+     * customization doesn't need to modify the parameters, return null.
+     */
+    fun finalizeParams(
+        paramsRef: String,
+        identityRef: String,
+        requiresIdentityRef: String,
+    ): Writable? = null
+
     /**
      * Provide a list of additional endpoints standard library functions that rules can use
      */
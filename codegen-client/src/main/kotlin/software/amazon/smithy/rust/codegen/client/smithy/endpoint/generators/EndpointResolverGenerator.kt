@@ -382,8 +382,41 @@ internal class EndpointResolverGenerator(
 
 fun ClientCodegenContext.serviceSpecificEndpointResolver(): RuntimeType {
     val generator = EndpointTypesGenerator.fromContext(this)
+    val finalizeParamsCustomizations = rootDecorator.endpointCustomizations(this).mapNotNull {
+        it.finalizeParams("endpoint_params", "identity", "requires_identity")
+    }
     return RuntimeType.forInlineFun("ResolveEndpoint", ClientRustModule.Config.endpoint) {
-        val ctx = arrayOf(*preludeScope, "Params" to generator.paramsStruct(), *Types(runtimeConfig).toArray(), "Debug" to RuntimeType.Debug)
+        val ctx = arrayOf(
+            *preludeScope,
+            "Params" to generator.paramsStruct(),
+            *Types(runtimeConfig).toArray(),
+            "Debug" to RuntimeType.Debug,
+            "BoxError" to RuntimeType.boxError(runtimeConfig),
+            "Identity" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("client::identity::Identity"),
+        )
+        val finalizeParams = writable {
+            if (finalizeParamsCustomizations.isNotEmpty()) {
+                rustTemplate(
+                    """
+                    fn finalize_params<'a>(&'a self, params: &'a mut #{EndpointResolverParams}) -> #{Result}<(), #{BoxError}> {
+                        let identity = params.get_property::<#{Identity}>().cloned();
+                        let identity = identity.as_ref();
+                        ##[allow(unused_mut)]
+                        let mut requires_identity = false;
+                        let endpoint_params = match params.get_mut::<#{Params}>() {
+                            #{Some}(params) => params,
+                            #{None} => return #{Err}("params of expected type was not present".into()),
+                        };
+                        #{customizations:W}
+                        params.set_requires_identity(requires_identity);
+                        #{Ok}(())
+                    }
+                    """,
+                    *ctx,
+                    "customizations" to finalizeParamsCustomizations.join("\n"),
+                )
+            }
+        }
         rustTemplate(
             """
             /// Endpoint resolver trait specific to ${serviceShape.serviceNameOrDefault("this service")}
@@ -415,10 +448,13 @@ fun ClientCodegenContext.serviceSpecificEndpointResolver(): RuntimeType {
                     };
                     ep
                 }
+
+                #{finalize_params:W}
             }
 
             """,
             *ctx,
+            "finalize_params" to finalizeParams,
         )
     }
 }
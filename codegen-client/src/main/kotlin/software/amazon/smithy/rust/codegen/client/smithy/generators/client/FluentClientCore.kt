@@ -7,18 +7,49 @@ package software.amazon.smithy.rust.codegen.client.smithy.generators.client
 
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.StructureShape
 import software.amazon.smithy.rust.codegen.core.rustlang.RustType
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.asArgument
+import software.amazon.smithy.rust.codegen.core.rustlang.asOptional
 import software.amazon.smithy.rust.codegen.core.rustlang.deprecatedShape
 import software.amazon.smithy.rust.codegen.core.rustlang.docs
 import software.amazon.smithy.rust.codegen.core.rustlang.documentShape
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.stripOuter
 import software.amazon.smithy.rust.codegen.core.rustlang.withBlockTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
+import software.amazon.smithy.rust.codegen.core.smithy.generators.getterName
 import software.amazon.smithy.rust.codegen.core.smithy.generators.setterName
+import software.amazon.smithy.rust.codegen.core.smithy.rustType
 
 class FluentClientCore(private val model: Model) {
+    /**
+     * Generate and write Rust code for the builder methods that set and get each member of an operation's input.
+     * The fluent builder must store the input builder in a field named `inner`.
+     */
+    fun RustWriter.renderInputHelpers(symbolProvider: RustSymbolProvider, input: StructureShape) {
+        input.members().forEach { member ->
+            val memberName = symbolProvider.toMemberName(member)
+            // All fields in the builder are optional
+            val memberSymbol = symbolProvider.toSymbol(member)
+            val outerType = memberSymbol.rustType()
+            when (val coreType = outerType.stripOuter<RustType.Option>()) {
+                is RustType.Vec -> renderVecHelper(member, memberName, coreType)
+                is RustType.HashMap -> renderMapHelper(member, memberName, coreType)
+                else -> renderInputHelper(member, memberName, coreType)
+            }
+            // pure setter
+            val setterName = member.setterName()
+            val optionalInputType = outerType.asOptional()
+            renderInputHelper(member, setterName, optionalInputType)
+
+            val getterName = member.getterName()
+            renderGetterHelper(member, getterName, optionalInputType)
+        }
+    }
+
     /** Generate and write Rust code for a builder method that sets a Vec<T> */
     fun RustWriter.renderVecHelper(member: MemberShape, memberName: String, coreType: RustType.Vec) {
         docs("Appends an item to `${member.memberName}`.")
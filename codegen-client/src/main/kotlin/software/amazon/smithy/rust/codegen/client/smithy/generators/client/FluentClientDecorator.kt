@@ -40,6 +40,10 @@ class FluentClientDecorator : ClientCodegenDecorator {
         ).render(rustCrate)
 
         rustCrate.mergeFeature(Feature("rustls", default = true, listOf("aws-smithy-runtime/tls-rustls")))
+        // Uses a hyper 1.x based HTTP client by default when the `rustls` feature is disabled
+        rustCrate.mergeFeature(
+            Feature("rustls-hyper-1", default = false, listOf("aws-smithy-runtime/tls-rustls-hyper-1-x")),
+        )
     }
 
     override fun libRsCustomizations(
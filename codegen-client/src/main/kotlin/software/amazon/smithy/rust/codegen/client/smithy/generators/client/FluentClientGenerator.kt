@@ -25,7 +25,6 @@ import software.amazon.smithy.rust.codegen.core.rustlang.RustReservedWords
 import software.amazon.smithy.rust.codegen.core.rustlang.RustType
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.asArgumentType
-import software.amazon.smithy.rust.codegen.core.rustlang.asOptional
 import software.amazon.smithy.rust.codegen.core.rustlang.deprecatedShape
 import software.amazon.smithy.rust.codegen.core.rustlang.docLink
 import software.amazon.smithy.rust.codegen.core.rustlang.docs
@@ -47,7 +46,6 @@ import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
 import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
 import software.amazon.smithy.rust.codegen.core.smithy.customize.writeCustomizations
 import software.amazon.smithy.rust.codegen.core.smithy.expectRustMetadata
-import software.amazon.smithy.rust.codegen.core.smithy.generators.getterName
 import software.amazon.smithy.rust.codegen.core.smithy.generators.setterName
 import software.amazon.smithy.rust.codegen.core.smithy.rustType
 import software.amazon.smithy.rust.codegen.core.util.dq
@@ -425,24 +423,7 @@ class FluentClientGenerator(
                     symbolProvider.symbolForOperationError(operation),
                 ),
             )
-            input.members().forEach { member ->
-                val memberName = symbolProvider.toMemberName(member)
-                // All fields in the builder are optional
-                val memberSymbol = symbolProvider.toSymbol(member)
-                val outerType = memberSymbol.rustType()
-                when (val coreType = outerType.stripOuter<RustType.Option>()) {
-                    is RustType.Vec -> with(core) { renderVecHelper(member, memberName, coreType) }
-                    is RustType.HashMap -> with(core) { renderMapHelper(member, memberName, coreType) }
-                    else -> with(core) { renderInputHelper(member, memberName, coreType) }
-                }
-                // pure setter
-                val setterName = member.setterName()
-                val optionalInputType = outerType.asOptional()
-                with(core) { renderInputHelper(member, setterName, optionalInputType) }
-
-                val getterName = member.getterName()
-                with(core) { renderGetterHelper(member, getterName, optionalInputType) }
-            }
+            with(core) { renderInputHelpers(symbolProvider, input) }
         }
     }
 }
@@ -281,13 +281,25 @@ class DefaultProtocolTestGenerator(
         testCase.headers.forEach { (key, value) ->
             writeWithNoFormatting(".header(${key.dq()}, ${value.dq()})")
         }
-        rust(
+        val body = testCase.body.orNull()?.dq()?.replace("#", "##")
+        rustTemplate(
             """
             .status(${testCase.code})
-            .body(#T::from(${testCase.body.orNull()?.dq()?.replace("#", "##") ?: "vec![]"}))
+            .body(#{SdkBody}::from(#{body}))
             .unwrap();
             """,
-            RT.sdkBody(runtimeConfig = rc),
+            "SdkBody" to RT.sdkBody(runtimeConfig = rc),
+            "body" to writable {
+                when {
+                    body == null -> rust("vec![]")
+                    // Binary protocols such as `rpcv2Cbor` encode the response body in the test case as base64.
+                    testCase.bodyMediaType.orNull() == "application/cbor" -> rustTemplate(
+                        "#{base64_decode}($body).expect(\"response body must be valid base64\")",
+                        "base64_decode" to RT.base64Decode(rc),
+                    )
+                    else -> rust(body)
+                }
+            },
         )
         rustTemplate(
             """
@@ -0,0 +1,135 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.generators.waiters
+
+import software.amazon.smithy.model.neighbor.Walker
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.EnumTrait
+import software.amazon.smithy.model.traits.ErrorTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isEventStream
+import software.amazon.smithy.rust.codegen.core.util.isStreaming
+import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+/**
+ * Implements `ToJmespathValue` for the structures, unions and enums reachable from the inputs and outputs
+ * of operations with waiters, so that waiter acceptors can evaluate JMESPath expressions against them.
+ *
+ * Members are keyed by their name in the model, which is how waiter paths refer to them.
+ */
+class JmespathValueGenerator(private val codegenContext: ClientCodegenContext) {
+    private val model = codegenContext.model
+    private val symbolProvider = codegenContext.symbolProvider
+    private val jmespath = waitersRuntime(codegenContext.runtimeConfig).resolve("jmespath")
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "ToJmespathValue" to jmespath.resolve("ToJmespathValue"),
+        "Value" to jmespath.resolve("Value"),
+    )
+
+    fun render(writer: RustWriter, operations: List<OperationShape>) {
+        val walker = Walker(model)
+        operations
+            .flatMap { listOf(it.inputShape(model), it.outputShape(model)) }
+            .flatMap { walker.walkShapes(it) }
+            .toSet()
+            .sortedBy { it.id }
+            .forEach { shape ->
+                when {
+                    shape is StructureShape && !shape.hasTrait<ErrorTrait>() -> renderStructure(writer, shape)
+                    shape is UnionShape && !shape.isEventStream() -> renderUnion(writer, shape)
+                    shape is StringShape && shape.hasTrait<EnumTrait>() -> renderEnum(writer, shape)
+                }
+            }
+    }
+
+    private fun renderImpl(writer: RustWriter, shape: Shape, body: Writable) {
+        writer.rustTemplate(
+            """
+            impl #{ToJmespathValue} for #{Shape} {
+                fn to_jmespath_value(&self) -> #{Value}<'_> {
+                    #{body}
+                }
+            }
+            """,
+            *codegenScope,
+            "Shape" to symbolProvider.toSymbol(shape),
+            "body" to body,
+        )
+    }
+
+    /** Renders an expression that converts `value`, a reference to the given member's value, into a `Value` */
+    private fun memberValue(member: MemberShape, value: String): Writable = writable {
+        if (member.isStreaming(model) || member.isEventStream(model)) {
+            // Streams can't be read without consuming them
+            rustTemplate("#{Value}::Null", *codegenScope)
+        } else {
+            rustTemplate("#{ToJmespathValue}::to_jmespath_value($value)", *codegenScope)
+        }
+    }
+
+    private fun objectValue(fields: List<Pair<String, Writable>>): Writable = writable {
+        if (fields.isEmpty()) {
+            rustTemplate("#{Value}::Object(#{Vec}::new())", *codegenScope)
+        } else {
+            rustTemplate("#{Value}::object([", *codegenScope)
+            fields.forEach { (name, value) -> rustTemplate("(${name.dq()}, #{value}),", "value" to value) }
+            rust("])")
+        }
+    }
+
+    private fun renderStructure(writer: RustWriter, shape: StructureShape) {
+        val fields = shape.members().map { member ->
+            member.memberName to memberValue(member, "&self.${symbolProvider.toMemberName(member)}")
+        }
+        renderImpl(writer, shape, objectValue(fields))
+    }
+
+    private fun renderUnion(writer: RustWriter, shape: UnionShape) {
+        renderImpl(
+            writer,
+            shape,
+            writable {
+                rustTemplate("match self {", *codegenScope)
+                shape.members().forEach { member ->
+                    val variantName = symbolProvider.toMemberName(member)
+                    if (member.isTargetUnit()) {
+                        rustTemplate(
+                            "Self::$variantName => #{value},",
+                            "value" to objectValue(listOf(member.memberName to objectValue(emptyList()))),
+                        )
+                    } else {
+                        rustTemplate(
+                            "Self::$variantName(inner) => #{value},",
+                            "value" to objectValue(listOf(member.memberName to memberValue(member, "inner"))),
+                        )
+                    }
+                }
+                rustTemplate("Self::Unknown => #{Value}::Null,", *codegenScope)
+                rust("}")
+            },
+        )
+    }
+
+    private fun renderEnum(writer: RustWriter, shape: StringShape) {
+        renderImpl(writer, shape, writable { rustTemplate("#{Value}::string(self.as_str())", *codegenScope) })
+    }
+}
@@ -0,0 +1,266 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.generators.waiters
+
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.generators.client.FluentClientCore
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.RustModule
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.docs
+import software.amazon.smithy.rust.codegen.core.rustlang.escape
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+import software.amazon.smithy.rust.codegen.core.util.toPascalCase
+import software.amazon.smithy.rust.codegen.core.util.toSnakeCase
+import software.amazon.smithy.waiters.AcceptorState
+import software.amazon.smithy.waiters.Matcher
+import software.amazon.smithy.waiters.PathComparator
+import software.amazon.smithy.waiters.Waiter
+
+internal fun waitersRuntime(runtimeConfig: RuntimeConfig) =
+    CargoDependency.smithyRuntime(runtimeConfig).withFeature("waiters").toType().resolve("client::waiters")
+
+/**
+ * Generates the `wait_until_*` method on the client for a waiter defined by the `@waitable` trait, along with
+ * the fluent builder it returns. The builder has the same input setters as the operation's fluent builder,
+ * and a `wait` method that polls the operation until one of the waiter's acceptors transitions it into a
+ * success or failure state.
+ */
+class WaiterGenerator(
+    private val codegenContext: ClientCodegenContext,
+    private val operation: OperationShape,
+    private val waiterName: String,
+    private val waiter: Waiter,
+) {
+    private val model = codegenContext.model
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val symbolProvider = codegenContext.symbolProvider
+    private val waiters = waitersRuntime(runtimeConfig)
+    private val apiWaiters = RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("client::waiters")
+
+    private val fnName = "wait_until_${waiterName.toSnakeCase()}"
+    private val builderName = "${waiterName.toPascalCase()}FluentBuilder"
+    private val finalPollName = "WaitUntil${waiterName.toPascalCase()}FinalPoll"
+    private val errorName = "WaitUntil${waiterName.toPascalCase()}Error"
+    private val module = RustModule.public(
+        waiterName.toSnakeCase(),
+        parent = ClientRustModule.waiters,
+        documentationOverride = "Supporting types for the `${waiterName.toSnakeCase()}` waiter.",
+    )
+
+    private val outputType = symbolProvider.toSymbol(operation.outputShape(model))
+    private val errorType = symbolProvider.symbolForOperationError(operation)
+    private val codegenScope = arrayOf(
+        *preludeScope,
+        "AcceptorState" to waiters.resolve("acceptors::AcceptorState"),
+        "Arc" to RuntimeType.Arc,
+        "Builder" to symbolProvider.symbolForBuilder(operation.inputShape(model)),
+        "Duration" to RuntimeType.std.resolve("time::Duration"),
+        "Error" to errorType,
+        "FinalPoll" to apiWaiters.resolve("FinalPoll"),
+        "HttpResponse" to RuntimeType.smithyRuntimeApi(runtimeConfig).resolve("client::orchestrator::HttpResponse"),
+        "Operation" to symbolProvider.toSymbol(operation),
+        "Output" to outputType,
+        "PathComparator" to waiters.resolve("acceptors::PathComparator"),
+        "PathMatcher" to waiters.resolve("acceptors::PathMatcher"),
+        "SdkError" to RuntimeType.sdkError(runtimeConfig),
+        "WaiterError" to apiWaiters.resolve("error::WaiterError"),
+        "WaiterOrchestrator" to waiters.resolve("WaiterOrchestrator"),
+        "match_error_type" to waiters.resolve("acceptors::match_error_type"),
+        "match_success" to waiters.resolve("acceptors::match_success"),
+    )
+
+    fun render(rustCrate: RustCrate) {
+        rustCrate.withModule(module) {
+            renderFluentBuilder()
+        }
+        rustCrate.withModule(RustModule.private("waiters", parent = ClientRustModule.client)) {
+            rustBlock("impl super::Client") {
+                waiterDocs()
+                if (waiter.isDeprecated) {
+                    Attribute.Deprecated.render(this)
+                }
+                rustTemplate(
+                    """
+                    pub fn $fnName(&self) -> #{Builder} {
+                        #{Builder}::new(self.handle.clone())
+                    }
+                    """,
+                    "Builder" to module.toType().resolve(builderName),
+                )
+            }
+        }
+    }
+
+    private fun RustWriter.waiterDocs() {
+        val docs = waiter.documentation.orElse(null)
+        if (docs.isNullOrBlank()) {
+            docs("Wait for `${operation.id.name}` to reach the `$waiterName` state.")
+        } else {
+            docs(escape(docs))
+        }
+    }
+
+    private fun RustWriter.renderFluentBuilder() {
+        rustTemplate(
+            """
+            /// Successful return type for the `${waiterName.toSnakeCase()}` waiter.
+            pub type $finalPollName = #{FinalPoll}<#{Output}, #{SdkError}<#{Error}, #{HttpResponse}>>;
+
+            /// Error type for the `${waiterName.toSnakeCase()}` waiter.
+            pub type $errorName = #{WaiterError}<#{Output}, #{SdkError}<#{Error}, #{HttpResponse}>>;
+
+            /// Fluent builder for the `${waiterName.toSnakeCase()}` waiter.
+            ///
+            /// This builder is used like the fluent builder for the `${operation.id.name}` operation. However,
+            /// instead of `send`, it has a [`wait`](Self::wait) method that polls the operation until the resource
+            /// reaches the desired state, or the given maximum wait time is exceeded.
+            ///
+            /// Construct this builder by calling `$fnName` on the client.
+            ##[derive(Clone, Debug)]
+            pub struct $builderName {
+                handle: #{Arc}<crate::client::Handle>,
+                inner: #{Builder},
+            }
+
+            impl $builderName {
+                /// Creates a new `$builderName`.
+                pub(crate) fn new(handle: #{Arc}<crate::client::Handle>) -> Self {
+                    Self { handle, inner: #{Default}::default() }
+                }
+
+                /// Access the ${operation.id.name} input as a reference.
+                pub fn as_input(&self) -> &#{Builder} {
+                    &self.inner
+                }
+
+                /// Polls the operation until the waiter reaches a success or failure state, waiting at most `max_wait`.
+                ///
+                /// Between polls, the waiter sleeps for a jittered, exponentially increasing delay between
+                /// ${waiter.minDelay} and ${waiter.maxDelay} seconds.
+                pub async fn wait(self, max_wait: #{Duration}) -> #{Result}<$finalPollName, $errorName> {
+                    let input = self.inner.build().map_err(#{WaiterError}::construction_failure)?;
+                    #{matchers}
+                    let runtime_plugins = #{Operation}::operation_runtime_plugins(
+                        self.handle.runtime_plugins.clone(),
+                        &self.handle.conf,
+                        #{None},
+                    );
+                    let operation = {
+                        #{operation_input}
+                        move || {
+                            let input = input.clone();
+                            let runtime_plugins = runtime_plugins.clone();
+                            async move { #{Operation}::orchestrate(&runtime_plugins, input).await }
+                        }
+                    };
+                    let acceptor = |result: #{Result}<&#{Output}, &#{SdkError}<#{Error}, #{HttpResponse}>>| {
+                        #{acceptors}
+                        #{AcceptorState}::NoAcceptorsMatched
+                    };
+                    let mut orchestrator = #{WaiterOrchestrator}::builder()
+                        .min_delay(#{Duration}::from_secs(${waiter.minDelay}))
+                        .max_delay(#{Duration}::from_secs(${waiter.maxDelay}))
+                        .max_wait(max_wait);
+                    orchestrator
+                        .set_time_source(self.handle.conf.time_source())
+                        .set_sleep_impl(self.handle.conf.sleep_impl());
+                    orchestrator
+                        .acceptor(acceptor)
+                        .operation(operation)
+                        .build()
+                        .orchestrate()
+                        .await
+                }
+
+                #{input_helpers}
+            }
+            """,
+            *codegenScope,
+            "matchers" to matchers(),
+            "acceptors" to acceptors(),
+            // The acceptors borrow the input when they evaluate paths against it, so the operation gets a copy
+            "operation_input" to writable {
+                if (usesInput()) {
+                    rust("let input = input.clone();")
+                }
+            },
+            "input_helpers" to writable {
+                with(FluentClientCore(model)) { renderInputHelpers(symbolProvider, operation.inputShape(model)) }
+            },
+        )
+    }
+
+    /** Returns true if any acceptor evaluates a path against the operation's input */
+    private fun usesInput(): Boolean = waiter.acceptors.any { it.matcher is Matcher.InputOutputMember }
+
+    /** Parses the path of each acceptor with a path matcher once, before the first poll */
+    private fun matchers(): Writable = writable {
+        waiter.acceptors.forEachIndexed { index, acceptor ->
+            val pathMatcher = when (val matcher = acceptor.matcher) {
+                is Matcher.OutputMember -> matcher.value
+                is Matcher.InputOutputMember -> matcher.value
+                else -> null
+            } ?: return@forEachIndexed
+            val comparator = when (pathMatcher.comparator) {
+                PathComparator.STRING_EQUALS -> "StringEquals"
+                PathComparator.BOOLEAN_EQUALS -> "BooleanEquals"
+                PathComparator.ALL_STRING_EQUALS -> "AllStringEquals"
+                PathComparator.ANY_STRING_EQUALS -> "AnyStringEquals"
+                else -> throw IllegalStateException("unsupported path comparator in waiter `$waiterName`: ${pathMatcher.comparator}")
+            }
+            rustTemplate(
+                """
+                let matcher_$index = #{PathMatcher}::new(${pathMatcher.path.dq()}, ${pathMatcher.expected.dq()}, #{PathComparator}::$comparator)
+                    .map_err(#{WaiterError}::construction_failure)?;
+                """,
+                *codegenScope,
+            )
+        }
+    }
+
+    /** Checks each acceptor in order, returning the state of the first one that matches */
+    private fun acceptors(): Writable = writable {
+        waiter.acceptors.forEachIndexed { index, acceptor ->
+            val condition = when (val matcher = acceptor.matcher) {
+                is Matcher.OutputMember -> "matcher_$index.matches_output(result)"
+                is Matcher.InputOutputMember -> "matcher_$index.matches_input_output(&input, result)"
+                is Matcher.SuccessMember -> "#{match_success}(result, ${matcher.value})"
+                // Error types may be shape IDs or shape names, but the error code is always the shape name
+                is Matcher.ErrorTypeMember -> "#{match_error_type}(result, ${matcher.value.substringAfter('#').dq()})"
+                else -> throw IllegalStateException("unsupported matcher in waiter `$waiterName`: $matcher")
+            }
+            val state = when (acceptor.state) {
+                AcceptorState.SUCCESS -> "Success"
+                AcceptorState.FAILURE -> "Failure"
+                AcceptorState.RETRY -> "Retry"
+                else -> throw IllegalStateException("unsupported acceptor state in waiter `$waiterName`: ${acceptor.state}")
+            }
+            rustTemplate(
+                """
+                if $condition {
+                    return #{AcceptorState}::$state;
+                }
+                """,
+                *codegenScope,
+            )
+        }
+    }
+}
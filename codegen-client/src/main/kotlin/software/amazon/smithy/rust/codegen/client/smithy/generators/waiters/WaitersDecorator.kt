@@ -0,0 +1,44 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.generators.waiters
+
+import software.amazon.smithy.model.knowledge.TopDownIndex
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.core.rustlang.RustModule
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.waiters.WaitableTrait
+
+/**
+ * Generates `wait_until_*` methods on the client for the waiters of operations with the `@waitable` trait.
+ */
+class WaitersDecorator : ClientCodegenDecorator {
+    override val name: String = "Waiters"
+    override val order: Byte = 0
+
+    override fun extras(codegenContext: ClientCodegenContext, rustCrate: RustCrate) {
+        if (!codegenContext.settings.codegenConfig.includeFluentClient) {
+            return
+        }
+        val waitableOperations = TopDownIndex.of(codegenContext.model)
+            .getContainedOperations(codegenContext.serviceShape)
+            .filter { it.hasTrait<WaitableTrait>() }
+        if (waitableOperations.isEmpty()) {
+            return
+        }
+
+        waitableOperations.forEach { operation ->
+            operation.expectTrait(WaitableTrait::class.java).waiters.toSortedMap().forEach { (waiterName, waiter) ->
+                WaiterGenerator(codegenContext, operation, waiterName, waiter).render(rustCrate)
+            }
+        }
+        rustCrate.withModule(RustModule.private("jmespath", parent = ClientRustModule.waiters)) {
+            JmespathValueGenerator(codegenContext).render(this, waitableOperations)
+        }
+    }
+}
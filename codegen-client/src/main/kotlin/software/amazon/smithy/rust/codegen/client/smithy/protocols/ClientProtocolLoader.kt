@@ -28,6 +28,8 @@ import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolLoader
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolMap
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestJson
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestXml
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.RpcV2Cbor
+import software.amazon.smithy.rust.codegen.core.smithy.traits.RpcV2CborTrait
 import software.amazon.smithy.rust.codegen.core.util.hasTrait
 
 class ClientProtocolLoader(supportedProtocols: ProtocolMap<OperationGenerator, ClientCodegenContext>) :
@@ -41,6 +43,7 @@ class ClientProtocolLoader(supportedProtocols: ProtocolMap<OperationGenerator, C
             Ec2QueryTrait.ID to ClientEc2QueryFactory(),
             RestJson1Trait.ID to ClientRestJsonFactory(),
             RestXmlTrait.ID to ClientRestXmlFactory(),
+            RpcV2CborTrait.ID to ClientRpcV2CborFactory(),
         )
         val Default = ClientProtocolLoader(DefaultProtocols)
     }
@@ -104,6 +107,15 @@ private class ClientEc2QueryFactory : ProtocolGeneratorFactory<OperationGenerato
     override fun support(): ProtocolSupport = CLIENT_PROTOCOL_SUPPORT
 }
 
+private class ClientRpcV2CborFactory : ProtocolGeneratorFactory<OperationGenerator, ClientCodegenContext> {
+    override fun protocol(codegenContext: ClientCodegenContext): Protocol = RpcV2Cbor(codegenContext)
+
+    override fun buildProtocolGenerator(codegenContext: ClientCodegenContext): OperationGenerator =
+        OperationGenerator(codegenContext, protocol(codegenContext))
+
+    override fun support(): ProtocolSupport = CLIENT_PROTOCOL_SUPPORT
+}
+
 class ClientRestXmlFactory(
     private val generator: (CodegenContext) -> Protocol = { RestXml(it) },
 ) : ProtocolGeneratorFactory<OperationGenerator, ClientCodegenContext> {
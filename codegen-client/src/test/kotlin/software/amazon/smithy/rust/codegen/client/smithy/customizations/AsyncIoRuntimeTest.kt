@@ -0,0 +1,88 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.IntegrationTestParams
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+
+class AsyncIoRuntimeTest {
+    private val model = """
+        namespace com.example
+        use aws.protocols#awsJson1_0
+        @awsJson1_0
+        service HelloService {
+            operations: [SayHello],
+            version: "1"
+        }
+        @optionalAuth
+        operation SayHello { output: TestOutput }
+
+        structure TestOutput {
+           message: String,
+        }
+    """.asSmithyModel()
+
+    @Test
+    fun `client sends requests without a tokio runtime`() {
+        clientIntegrationTest(
+            model,
+            // Without `rt-tokio`, the default sleep implementation and HTTP client come from `rt-async-io`
+            IntegrationTestParams(cargoCommand = "cargo test --no-default-features --features rt-async-io,rustls --tests"),
+        ) { codegenContext, rustCrate ->
+            rustCrate.integrationTest("async_io_runtime") {
+                val moduleName = codegenContext.moduleUseName()
+                rustTemplate(
+                    """
+                    use std::io::{BufRead, BufReader, Read, Write};
+
+                    ##[test]
+                    fn client_sends_requests_without_a_tokio_runtime() {
+                        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+                        let addr = listener.local_addr().unwrap();
+                        let server = std::thread::spawn(move || {
+                            let (stream, _) = listener.accept().unwrap();
+                            let mut reader = BufReader::new(stream);
+                            let mut content_length = 0;
+                            let mut line = String::new();
+                            while reader.read_line(&mut line).unwrap() > 2 {
+                                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                                    content_length = value.trim().parse().unwrap();
+                                }
+                                line.clear();
+                            }
+                            let mut body = vec![0; content_length];
+                            reader.read_exact(&mut body).unwrap();
+
+                            let response = r##"{"message":"hello"}"##;
+                            write!(
+                                reader.into_inner(),
+                                "HTTP/1.1 200 OK\r\ncontent-type: application/x-amz-json-1.0\r\ncontent-length: {}\r\n\r\n{}",
+                                response.len(),
+                                response,
+                            )
+                            .unwrap();
+                        });
+
+                        let config = $moduleName::Config::builder()
+                            .endpoint_url(format!("http://{addr}"))
+                            .build();
+                        let client = $moduleName::Client::from_conf(config);
+                        let output = #{smol}::block_on(client.say_hello().send()).expect("success");
+                        assert_eq!(Some("hello"), output.message());
+                        server.join().unwrap();
+                    }
+                    """,
+                    "smol" to CargoDependency.Smol.toType(),
+                )
+            }
+        }
+    }
+}
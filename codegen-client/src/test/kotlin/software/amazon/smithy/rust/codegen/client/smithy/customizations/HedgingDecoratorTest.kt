@@ -0,0 +1,133 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+class HedgingDecoratorTest {
+    private val model = """
+        namespace com.example
+        use aws.protocols#awsJson1_0
+        @awsJson1_0
+        service HelloService {
+            operations: [GetGreeting, PutGreeting, SayHello],
+            version: "1"
+        }
+
+        @readonly
+        @optionalAuth
+        operation GetGreeting { input: TestInput }
+
+        @idempotent
+        @optionalAuth
+        operation PutGreeting { input: TestInput }
+
+        @optionalAuth
+        operation SayHello { input: TestInput }
+
+        structure TestInput {
+           foo: String,
+        }
+    """.asSmithyModel()
+
+    @Test
+    fun `readonly and idempotent operations are marked as hedgeable`() {
+        clientIntegrationTest(model) { clientCodegenContext, rustCrate ->
+            val runtimeConfig = clientCodegenContext.runtimeConfig
+            val codegenScope = arrayOf(
+                *preludeScope,
+                "BeforeTransmitInterceptorContextMut" to RuntimeType.beforeTransmitInterceptorContextMut(runtimeConfig),
+                "BoxError" to RuntimeType.boxError(runtimeConfig),
+                "ConfigBag" to RuntimeType.configBag(runtimeConfig),
+                "Intercept" to RuntimeType.intercept(runtimeConfig),
+                "IdempotentOperation" to RuntimeType.smithyRuntime(runtimeConfig)
+                    .resolve("client::retries::hedging::IdempotentOperation"),
+                "capture_request" to RuntimeType.captureRequest(runtimeConfig),
+                "RuntimeComponents" to RuntimeType.smithyRuntimeApi(runtimeConfig)
+                    .resolve("client::runtime_components::RuntimeComponents"),
+            )
+            rustCrate.testModule {
+                addDependency(CargoDependency.Tokio.toDevDependency().withFeature("test-util"))
+                tokioTest("test_operations_are_marked_as_hedgeable") {
+                    rustTemplate(
+                        """
+                        // Interceptors aren’t supposed to store states, but it is done this way for a testing purpose.
+                        ##[derive(Debug, Default)]
+                        struct CaptureIdempotency(::std::sync::Arc<::std::sync::Mutex<#{Option}<bool>>>);
+
+                        impl #{Intercept} for CaptureIdempotency {
+                            fn name(&self) -> &'static str {
+                                "CaptureIdempotency"
+                            }
+
+                            fn modify_before_signing(
+                                &self,
+                                _context: &mut #{BeforeTransmitInterceptorContextMut}<'_>,
+                                _runtime_components: &#{RuntimeComponents},
+                                cfg: &mut #{ConfigBag},
+                            ) -> #{Result}<(), #{BoxError}> {
+                                let idempotent = cfg.load::<#{IdempotentOperation}>().is_some();
+                                *self.0.lock().unwrap() = #{Some}(idempotent);
+                                #{Ok}(())
+                            }
+                        }
+
+                        let (http_client, _captured_request) = #{capture_request}(#{None});
+                        let hedging_config = crate::config::retry::HedgingConfig::new()
+                            .with_initial_delay(::std::time::Duration::from_secs(10));
+                        let client_config = crate::config::Config::builder()
+                            .endpoint_url("http://localhost:1234/")
+                            .http_client(http_client)
+                            .hedging_config(hedging_config)
+                            .build();
+                        assert_eq!(
+                            #{Some}(::std::time::Duration::from_secs(10)),
+                            client_config.hedging_config().and_then(|config| config.initial_delay()),
+                        );
+                        let client = crate::client::Client::from_conf(client_config);
+
+                        let interceptor = CaptureIdempotency::default();
+                        let _ = client
+                            .get_greeting()
+                            .customize()
+                            .interceptor(CaptureIdempotency(interceptor.0.clone()))
+                            .send()
+                            .await;
+                        assert_eq!(#{Some}(true), *interceptor.0.lock().unwrap());
+
+                        let interceptor = CaptureIdempotency::default();
+                        let _ = client
+                            .put_greeting()
+                            .customize()
+                            .interceptor(CaptureIdempotency(interceptor.0.clone()))
+                            .send()
+                            .await;
+                        assert_eq!(#{Some}(true), *interceptor.0.lock().unwrap());
+
+                        let interceptor = CaptureIdempotency::default();
+                        let _ = client
+                            .say_hello()
+                            .customize()
+                            .interceptor(CaptureIdempotency(interceptor.0.clone()))
+                            .send()
+                            .await;
+                        assert_eq!(#{Some}(false), *interceptor.0.lock().unwrap());
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
@@ -427,6 +427,65 @@ class HttpAuthDecoratorTest {
         }
     }
 
+    @Test
+    fun digestAuth() {
+        clientIntegrationTest(TestModels.digestAuth) { codegenContext, rustCrate ->
+            rustCrate.integrationTest("digest_auth") {
+                val moduleName = codegenContext.moduleUseName()
+                Attribute.TokioTest.render(this)
+                rustTemplate(
+                    """
+                    async fn digest_auth() {
+                        use aws_smithy_runtime_api::client::identity::http::Login;
+
+                        let http_client = #{StaticReplayClient}::new(
+                            vec![
+                                #{ReplayEvent}::new(
+                                    http::Request::builder()
+                                        .uri("http://localhost:1234/SomeOperation")
+                                        .body(#{SdkBody}::empty())
+                                        .unwrap(),
+                                    http::Response::builder()
+                                        .status(401)
+                                        .header("www-authenticate", r##"Digest realm="test", nonce="some-nonce", algorithm=SHA-256, qop="auth""##)
+                                        .body(#{SdkBody}::empty())
+                                        .unwrap(),
+                                ),
+                                #{ReplayEvent}::new(
+                                    http::Request::builder()
+                                        .uri("http://localhost:1234/SomeOperation")
+                                        .body(#{SdkBody}::empty())
+                                        .unwrap(),
+                                    http::Response::builder().status(200).body(#{SdkBody}::empty()).unwrap(),
+                                ),
+                            ],
+                        );
+
+                        let config = $moduleName::Config::builder()
+                            .digest_auth_login(Login::new("some-user", "some-pass", None))
+                            .endpoint_url("http://localhost:1234")
+                            .http_client(http_client.clone())
+                            .build();
+                        let client = $moduleName::Client::from_conf(config);
+                        let _ = client.some_operation()
+                            .send()
+                            .await
+                            .expect("success");
+                        http_client.assert_requests_match(&["authorization"]);
+                        let authorization: Vec<_> = http_client
+                            .actual_requests()
+                            .map(|req| req.headers().get("authorization"))
+                            .collect();
+                        assert_eq!(None, authorization[0]);
+                        assert!(authorization[1].unwrap().starts_with("Digest username=\"some-user\", realm=\"test\""));
+                    }
+                    """,
+                    *codegenScope(codegenContext.runtimeConfig),
+                )
+            }
+        }
+    }
+
     @Test
     fun optionalAuth() {
         clientIntegrationTest(TestModels.optionalAuth) { codegenContext, rustCrate ->
@@ -596,6 +655,32 @@ private object TestModels {
         }
     """.asSmithyModel()
 
+    val digestAuth = """
+        namespace test
+
+        use aws.api#service
+        use aws.protocols#restJson1
+
+        @service(sdkId: "Test Api Key Auth")
+        @restJson1
+        @httpDigestAuth
+        @auth([httpDigestAuth])
+        service TestService {
+            version: "2023-01-01",
+            operations: [SomeOperation]
+        }
+
+        structure SomeOutput {
+            someAttribute: Long,
+            someVal: String
+        }
+
+        @http(uri: "/SomeOperation", method: "GET")
+        operation SomeOperation {
+            output: SomeOutput
+        }
+    """.asSmithyModel()
+
     val bearerAuth = """
         namespace test
 
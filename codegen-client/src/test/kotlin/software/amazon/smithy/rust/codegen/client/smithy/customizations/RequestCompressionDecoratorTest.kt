@@ -0,0 +1,150 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+class RequestCompressionDecoratorTest {
+    private val model = """
+        namespace com.example
+        use aws.protocols#awsJson1_0
+        @awsJson1_0
+        service HelloService {
+            operations: [PutCompressed, PutUncompressed],
+            version: "1"
+        }
+
+        @optionalAuth
+        @requestCompression(encodings: ["gzip"])
+        operation PutCompressed { input: PutInput }
+
+        @optionalAuth
+        operation PutUncompressed { input: PutInput }
+
+        structure PutInput {
+            data: String
+        }
+    """.asSmithyModel()
+
+    @Test
+    fun `operations with the requestCompression trait compress their requests`() {
+        clientIntegrationTest(model) { codegenContext, rustCrate ->
+            val moduleName = codegenContext.moduleUseName()
+            val codegenScope = arrayOf(
+                "capture_request" to RuntimeType.captureRequest(codegenContext.runtimeConfig),
+            )
+            rustCrate.integrationTest("request_compression") {
+                rustTemplate(
+                    """
+                    use $moduleName::config::Builder;
+
+                    const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+                    /// Sends `data` with the given operation and returns the request's `Content-Encoding` and body
+                    async fn send(builder: Builder, compressed: bool, data: String) -> (Option<String>, Vec<u8>) {
+                        let (http_client, rcvr) = #{capture_request}(None);
+                        let config = builder
+                            .endpoint_url("http://localhost:1234")
+                            .http_client(http_client)
+                            .build();
+                        let client = $moduleName::Client::from_conf(config);
+                        if compressed {
+                            let _ = client.put_compressed().data(data).send().await;
+                        } else {
+                            let _ = client.put_uncompressed().data(data).send().await;
+                        }
+                        let request = rcvr.expect_request();
+                        (
+                            request.headers().get("content-encoding").map(|v| v.to_string()),
+                            request.body().bytes().expect("in-memory body").to_vec(),
+                        )
+                    }
+
+                    fn large_data() -> String {
+                        "a".repeat(20_000)
+                    }
+                    """,
+                    *codegenScope,
+                )
+
+                tokioTest("large_bodies_are_compressed") {
+                    rustTemplate(
+                        """
+                        let (encoding, body) = send(Builder::new(), true, large_data()).await;
+                        assert_eq!(Some("gzip"), encoding.as_deref());
+                        assert!(body.starts_with(GZIP_MAGIC));
+                        assert!(body.len() < 20_000);
+                        """,
+                    )
+                }
+
+                tokioTest("small_bodies_are_not_compressed") {
+                    rustTemplate(
+                        """
+                        let (encoding, body) = send(Builder::new(), true, "small".to_string()).await;
+                        assert_eq!(None, encoding);
+                        assert_eq!(br##"{"data":"small"}"##, body.as_slice());
+                        """,
+                    )
+                }
+
+                tokioTest("min_compression_size_is_configurable") {
+                    rustTemplate(
+                        """
+                        let builder = Builder::new().request_min_compression_size_bytes(0);
+                        let (encoding, body) = send(builder, true, "small".to_string()).await;
+                        assert_eq!(Some("gzip"), encoding.as_deref());
+                        assert!(body.starts_with(GZIP_MAGIC));
+                        """,
+                    )
+                }
+
+                tokioTest("compression_can_be_disabled") {
+                    rustTemplate(
+                        """
+                        let builder = Builder::new().disable_request_compression(true);
+                        let (encoding, body) = send(builder, true, large_data()).await;
+                        assert_eq!(None, encoding);
+                        assert!(!body.starts_with(GZIP_MAGIC));
+                        """,
+                    )
+                }
+
+                tokioTest("operations_without_the_trait_are_not_compressed") {
+                    rustTemplate(
+                        """
+                        let (encoding, body) = send(Builder::new(), false, large_data()).await;
+                        assert_eq!(None, encoding);
+                        assert!(!body.starts_with(GZIP_MAGIC));
+                        """,
+                    )
+                }
+
+                tokioTest("config_accessors_return_the_configured_values") {
+                    rustTemplate(
+                        """
+                        let config = Builder::new()
+                            .disable_request_compression(true)
+                            .request_min_compression_size_bytes(128)
+                            .build();
+                        assert_eq!(Some(true), config.disable_request_compression());
+                        assert_eq!(Some(128), config.request_min_compression_size_bytes());
+                        let config = Builder::new().build();
+                        assert_eq!(None, config.disable_request_compression());
+                        assert_eq!(None, config.request_min_compression_size_bytes());
+                        """,
+                    )
+                }
+            }
+        }
+    }
+}
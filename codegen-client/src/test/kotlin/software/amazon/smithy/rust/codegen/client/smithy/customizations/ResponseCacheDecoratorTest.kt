@@ -0,0 +1,150 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+class ResponseCacheDecoratorTest {
+    private val model = """
+        namespace com.example
+        use aws.protocols#awsJson1_0
+        @awsJson1_0
+        service HelloService {
+            operations: [GetThing, PutThing],
+            version: "1"
+        }
+
+        @readonly
+        @optionalAuth
+        operation GetThing { input: ThingInput, output: ThingOutput }
+
+        @optionalAuth
+        operation PutThing { input: ThingInput, output: ThingOutput }
+
+        structure ThingInput {
+            id: String
+        }
+
+        structure ThingOutput {
+            name: String
+        }
+    """.asSmithyModel()
+
+    @Test
+    fun `responses to readonly operations are cached`() {
+        clientIntegrationTest(model) { codegenContext, rustCrate ->
+            val moduleName = codegenContext.moduleUseName()
+            val rc = codegenContext.runtimeConfig
+            val codegenScope = arrayOf(
+                *preludeScope,
+                "Http" to CargoDependency.Http.toType(),
+                "ReplayEvent" to CargoDependency.smithyRuntimeTestUtil(rc).toType()
+                    .resolve("client::http::test_util::ReplayEvent"),
+                "StaticReplayClient" to CargoDependency.smithyRuntimeTestUtil(rc).toType()
+                    .resolve("client::http::test_util::StaticReplayClient"),
+                "SdkBody" to RuntimeType.sdkBody(rc),
+            )
+            rustCrate.integrationTest("response_cache") {
+                rustTemplate(
+                    """
+                    use $moduleName::config::{Builder, ResponseCache};
+
+                    /// Creates a client that replies to every request with a thing named after the request's position
+                    fn client(builder: Builder) -> ($moduleName::Client, #{StaticReplayClient}) {
+                        let http_client = #{StaticReplayClient}::new(
+                            (1..=3)
+                                .map(|i| {
+                                    #{ReplayEvent}::new(
+                                        #{Http}::Request::builder()
+                                            .uri("http://localhost:1234/")
+                                            .body(#{SdkBody}::empty())
+                                            .unwrap(),
+                                        #{Http}::Response::builder()
+                                            .status(200)
+                                            .body(#{SdkBody}::from(format!(r##"{{"name": "thing {i}"}}"##)))
+                                            .unwrap(),
+                                    )
+                                })
+                                .collect(),
+                        );
+                        let config = builder
+                            .endpoint_url("http://localhost:1234")
+                            .http_client(http_client.clone())
+                            .build();
+                        ($moduleName::Client::from_conf(config), http_client)
+                    }
+                    """,
+                    *codegenScope,
+                )
+
+                tokioTest("readonly_operations_are_served_from_the_cache") {
+                    rustTemplate(
+                        """
+                        let (client, http_client) = client(Builder::new().response_cache(ResponseCache::default()));
+                        let first = client.get_thing().id("a").send().await.unwrap();
+                        let second = client.get_thing().id("a").send().await.unwrap();
+                        assert_eq!(#{Some}("thing 1"), first.name());
+                        assert_eq!(#{Some}("thing 1"), second.name());
+                        assert_eq!(1, http_client.actual_requests().count());
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("different_inputs_are_cached_separately") {
+                    rustTemplate(
+                        """
+                        let (client, http_client) = client(Builder::new().response_cache(ResponseCache::default()));
+                        let a = client.get_thing().id("a").send().await.unwrap();
+                        let b = client.get_thing().id("b").send().await.unwrap();
+                        let a_again = client.get_thing().id("a").send().await.unwrap();
+                        assert_eq!(#{Some}("thing 1"), a.name());
+                        assert_eq!(#{Some}("thing 2"), b.name());
+                        assert_eq!(#{Some}("thing 1"), a_again.name());
+                        assert_eq!(2, http_client.actual_requests().count());
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("operations_that_are_not_readonly_are_not_cached") {
+                    rustTemplate(
+                        """
+                        let (client, http_client) = client(Builder::new().response_cache(ResponseCache::default()));
+                        client.put_thing().id("a").send().await.unwrap();
+                        let second = client.put_thing().id("a").send().await.unwrap();
+                        assert_eq!(#{Some}("thing 2"), second.name());
+                        assert_eq!(2, http_client.actual_requests().count());
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("responses_are_not_cached_by_default") {
+                    rustTemplate(
+                        """
+                        let (client, http_client) = client(Builder::new());
+                        assert!(client.config().response_cache().is_none());
+                        client.get_thing().id("a").send().await.unwrap();
+                        let second = client.get_thing().id("a").send().await.unwrap();
+                        assert_eq!(#{Some}("thing 2"), second.name());
+                        assert_eq!(2, http_client.actual_requests().count());
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
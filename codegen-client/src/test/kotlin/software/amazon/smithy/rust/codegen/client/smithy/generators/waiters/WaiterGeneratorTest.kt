@@ -0,0 +1,186 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.generators.waiters
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+internal class WaiterGeneratorTest {
+    private val model = """
+        namespace test
+
+        use aws.protocols#awsJson1_0
+        use smithy.waiters#waitable
+
+        @awsJson1_0
+        service TestService {
+            operations: [GetStatus]
+        }
+
+        @readonly
+        @optionalAuth
+        @waitable(
+            StatusIsDone: {
+                documentation: "Wait until the status is `DONE`",
+                acceptors: [
+                    {
+                        state: "success",
+                        matcher: { output: { path: "Status", expected: "DONE", comparator: "stringEquals" } }
+                    },
+                    {
+                        state: "failure",
+                        matcher: { output: { path: "Status", expected: "FAILED", comparator: "stringEquals" } }
+                    },
+                    {
+                        state: "retry",
+                        matcher: { errorType: "NotReady" }
+                    }
+                ],
+                minDelay: 1,
+                maxDelay: 2
+            }
+        )
+        operation GetStatus {
+            input: GetStatusInput,
+            output: GetStatusOutput,
+            errors: [NotReady]
+        }
+
+        structure GetStatusInput {
+            id: String
+        }
+
+        structure GetStatusOutput {
+            Status: String
+        }
+
+        @error("client")
+        structure NotReady {
+            message: String
+        }
+    """.asSmithyModel()
+
+    @Test
+    fun `generated waiters poll until an acceptor matches`() {
+        clientIntegrationTest(model) { codegenContext, rustCrate ->
+            val rc = codegenContext.runtimeConfig
+            val codegenScope = arrayOf(
+                *preludeScope,
+                "Http" to CargoDependency.Http.toType(),
+                "ReplayEvent" to CargoDependency.smithyRuntimeTestUtil(rc).toType()
+                    .resolve("client::http::test_util::ReplayEvent"),
+                "StaticReplayClient" to CargoDependency.smithyRuntimeTestUtil(rc).toType()
+                    .resolve("client::http::test_util::StaticReplayClient"),
+                "SdkBody" to RuntimeType.sdkBody(rc),
+                "instant_time_and_sleep" to CargoDependency.smithyAsync(rc).toDevDependency().withFeature("test-util")
+                    .toType().resolve("test_util::instant_time_and_sleep"),
+                "InstantSleep" to CargoDependency.smithyAsync(rc).toDevDependency().withFeature("test-util")
+                    .toType().resolve("test_util::InstantSleep"),
+                "WaiterError" to RuntimeType.smithyRuntimeApi(rc).resolve("client::waiters::error::WaiterError"),
+            )
+            rustCrate.testModule {
+                rustTemplate(
+                    """
+                    /// Creates a client whose responses are the given `(status code, body)` pairs
+                    fn client(responses: &[(u16, &'static str)]) -> (crate::Client, #{InstantSleep}) {
+                        let http_client = #{StaticReplayClient}::new(
+                            responses
+                                .iter()
+                                .map(|(status, body)| {
+                                    #{ReplayEvent}::new(
+                                        #{Http}::Request::builder()
+                                            .uri("http://localhost:1234/")
+                                            .body(#{SdkBody}::empty())
+                                            .unwrap(),
+                                        #{Http}::Response::builder()
+                                            .status(*status)
+                                            .body(#{SdkBody}::from(*body))
+                                            .unwrap(),
+                                    )
+                                })
+                                .collect(),
+                        );
+                        let (time_source, sleep) = #{instant_time_and_sleep}(::std::time::UNIX_EPOCH);
+                        let config = crate::Config::builder()
+                            .endpoint_url("http://localhost:1234")
+                            .http_client(http_client)
+                            .time_source(time_source)
+                            .sleep_impl(sleep.clone())
+                            .build();
+                        (crate::Client::from_conf(config), sleep)
+                    }
+                    """,
+                    *codegenScope,
+                )
+
+                tokioTest("waits_until_success") {
+                    rustTemplate(
+                        """
+                        let (client, sleep) = client(&[
+                            (400, r##"{"__type": "NotReady"}"##),
+                            (200, r##"{"Status": "PENDING"}"##),
+                            (200, r##"{"Status": "DONE"}"##),
+                        ]);
+                        let final_poll = client
+                            .wait_until_status_is_done()
+                            .id("some-id")
+                            .wait(::std::time::Duration::from_secs(60))
+                            .await
+                            .expect("the status reaches DONE");
+                        let output = final_poll.as_result().expect("the final poll succeeded");
+                        assert_eq!(#{Some}("DONE"), output.status());
+                        assert_eq!(2, sleep.logs().len());
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("fails_on_failure_state") {
+                    rustTemplate(
+                        """
+                        let (client, _sleep) = client(&[(200, r##"{"Status": "FAILED"}"##)]);
+                        let err = client
+                            .wait_until_status_is_done()
+                            .wait(::std::time::Duration::from_secs(60))
+                            .await
+                            .expect_err("the status reached FAILED");
+                        match err {
+                            #{WaiterError}::FailureState(state) => {
+                                let output = state.final_poll().as_result().expect("the final poll succeeded");
+                                assert_eq!(#{Some}("FAILED"), output.status());
+                            }
+                            other => panic!("unexpected error: {other:?}"),
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("gives_up_after_max_wait") {
+                    rustTemplate(
+                        """
+                        let (client, _sleep) = client(&[(200, r##"{"Status": "PENDING"}"##); 10]);
+                        let err = client
+                            .wait_until_status_is_done()
+                            .wait(::std::time::Duration::from_secs(3))
+                            .await
+                            .expect_err("the status never reaches DONE");
+                        assert!(matches!(err, #{WaiterError}::ExceededMaxWait(_)), "{err:?}");
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
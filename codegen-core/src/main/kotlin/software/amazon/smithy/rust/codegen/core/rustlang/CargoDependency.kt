@@ -99,6 +99,13 @@ class InlineDependency(
                 CargoDependency.Http,
             )
 
+        fun cborErrors(runtimeConfig: RuntimeConfig) =
+            forInlineableRustFile(
+                "cbor_errors",
+                CargoDependency.smithyCbor(runtimeConfig),
+                CargoDependency.Http,
+            )
+
         fun awsQueryCompatibleErrors(runtimeConfig: RuntimeConfig) =
             forInlineableRustFile(
                 "aws_query_compatible_errors",
@@ -284,6 +291,7 @@ data class CargoDependency(
         )
 
         fun smithyAsync(runtimeConfig: RuntimeConfig) = runtimeConfig.smithyRuntimeCrate("smithy-async")
+        fun smithyCbor(runtimeConfig: RuntimeConfig) = runtimeConfig.smithyRuntimeCrate("smithy-cbor")
         fun smithyChecksums(runtimeConfig: RuntimeConfig) = runtimeConfig.smithyRuntimeCrate("smithy-checksums")
 
         fun smithyEventStream(runtimeConfig: RuntimeConfig) = runtimeConfig.smithyRuntimeCrate("smithy-eventstream")
@@ -30,6 +30,13 @@ interface Protocol {
     /** Returns additional HTTP headers that should be included in HTTP requests for the given operation for this protocol. */
     fun additionalRequestHeaders(operationShape: OperationShape): List<Pair<String, String>> = emptyList()
 
+    /**
+     * Returns additional HTTP headers that should be included in HTTP responses for the given operation.
+     * These MUST all be lowercase, or the application will panic, as per
+     * https://docs.rs/http/latest/http/header/struct.HeaderName.html#method.from_static
+     */
+    fun additionalResponseHeaders(operationShape: OperationShape): List<Pair<String, String>> = emptyList()
+
     /**
      * Returns additional HTTP headers that should be included in HTTP responses for the given error shape.
      * These MUST all be lowercase, or the application will panic, as per
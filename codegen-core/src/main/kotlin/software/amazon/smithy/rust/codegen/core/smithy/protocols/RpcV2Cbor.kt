@@ -0,0 +1,128 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols
+
+import software.amazon.smithy.codegen.core.CodegenException
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.pattern.UriPattern
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ServiceShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.ToShapeId
+import software.amazon.smithy.model.traits.HttpTrait
+import software.amazon.smithy.model.traits.TimestampFormatTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.StructuredDataParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.CborSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.StructuredDataSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.traits.SyntheticInputTrait
+import software.amazon.smithy.rust.codegen.core.util.expectTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isStreaming
+
+class RpcV2CborHttpBindingResolver(
+    private val model: Model,
+    private val serviceShape: ServiceShape,
+) : HttpBindingResolver {
+    private fun bindings(shape: ToShapeId): List<HttpBindingDescriptor> {
+        val members = shape.let { model.expectShape(it.toShapeId()) }.members()
+        if (members.any { it.isStreaming(model) }) {
+            throw CodegenException("The `rpcv2Cbor` protocol does not support streaming members or event streams yet: $shape")
+        }
+
+        return members.map { HttpBindingDescriptor(it, HttpLocation.DOCUMENT, "document") }.toList()
+    }
+
+    /**
+     * All operations are `POST` requests to `/service/{ServiceName}/operation/{OperationName}`, where both names
+     * are the shape names without their namespace.
+     */
+    override fun httpTrait(operationShape: OperationShape): HttpTrait = HttpTrait.builder()
+        .code(200)
+        .method("POST")
+        .uri(UriPattern.parse("/service/${serviceShape.id.name}/operation/${operationShape.id.name}"))
+        .build()
+
+    override fun requestBindings(operationShape: OperationShape): List<HttpBindingDescriptor> =
+        bindings(operationShape.inputShape)
+
+    override fun responseBindings(operationShape: OperationShape): List<HttpBindingDescriptor> =
+        bindings(operationShape.outputShape)
+
+    override fun errorResponseBindings(errorShape: ToShapeId): List<HttpBindingDescriptor> =
+        bindings(errorShape)
+
+    /**
+     * Requests for operations without a modeled input have no body, and therefore no `Content-Type` header.
+     */
+    override fun requestContentType(operationShape: OperationShape): String? =
+        if (operationShape.inputShape(model).expectTrait<SyntheticInputTrait>().originalId == null) {
+            null
+        } else {
+            "application/cbor"
+        }
+
+    override fun responseContentType(operationShape: OperationShape): String = "application/cbor"
+}
+
+open class RpcV2Cbor(val codegenContext: CodegenContext) : Protocol {
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val errorScope = arrayOf(
+        "Bytes" to RuntimeType.Bytes,
+        "ErrorMetadataBuilder" to RuntimeType.errorMetadataBuilder(runtimeConfig),
+        "HeaderMap" to RuntimeType.Http.resolve("HeaderMap"),
+        "CborError" to RuntimeType.smithyCbor(runtimeConfig).resolve("DeserializeError"),
+        "cbor_errors" to RuntimeType.cborErrors(runtimeConfig),
+    )
+
+    override val httpBindingResolver: HttpBindingResolver =
+        RpcV2CborHttpBindingResolver(codegenContext.model, codegenContext.serviceShape)
+
+    // Timestamps are always encoded as tagged epoch seconds; `@timestampFormat` is ignored by the protocol.
+    override val defaultTimestampFormat: TimestampFormatTrait.Format = TimestampFormatTrait.Format.EPOCH_SECONDS
+
+    override fun additionalRequestHeaders(operationShape: OperationShape): List<Pair<String, String>> =
+        listOf("smithy-protocol" to "rpc-v2-cbor", "accept" to "application/cbor")
+
+    override fun additionalResponseHeaders(operationShape: OperationShape): List<Pair<String, String>> =
+        listOf("smithy-protocol" to "rpc-v2-cbor")
+
+    override fun additionalErrorResponseHeaders(errorShape: StructureShape): List<Pair<String, String>> =
+        listOf("smithy-protocol" to "rpc-v2-cbor")
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        CborParserGenerator(codegenContext, httpBindingResolver)
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        CborSerializerGenerator(codegenContext, httpBindingResolver)
+
+    override fun parseHttpErrorMetadata(operationShape: OperationShape): RuntimeType =
+        ProtocolFunctions.crossOperationFn("parse_http_error_metadata") { fnName ->
+            rustTemplate(
+                """
+                pub fn $fnName(_response_status: u16, _response_headers: &#{HeaderMap}, response_body: &[u8]) -> Result<#{ErrorMetadataBuilder}, #{CborError}> {
+                    #{cbor_errors}::parse_error_metadata(response_body)
+                }
+                """,
+                *errorScope,
+            )
+        }
+
+    override fun parseEventStreamErrorMetadata(operationShape: OperationShape): RuntimeType =
+        ProtocolFunctions.crossOperationFn("parse_event_stream_error_metadata") { fnName ->
+            rustTemplate(
+                """
+                pub fn $fnName(payload: &#{Bytes}) -> Result<#{ErrorMetadataBuilder}, #{CborError}> {
+                    #{cbor_errors}::parse_error_metadata(payload)
+                }
+                """,
+                *errorScope,
+            )
+        }
+}
@@ -0,0 +1,33 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.traits.XmlFlattenedTrait
+import software.amazon.smithy.model.traits.XmlNameTrait
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.util.getTrait
+
+/**
+ * Parses AWS Query requests, whose parameters are named and flattened like the members of [AwsQuerySerializerGenerator].
+ */
+class AwsQueryInputParserGenerator(
+    codegenContext: CodegenContext,
+    httpBindingResolver: HttpBindingResolver,
+    returnSymbolToParse: (Shape) -> ReturnSymbolToParse = { shape ->
+        ReturnSymbolToParse(codegenContext.symbolProvider.toSymbol(shape), false)
+    },
+    customizations: List<QueryParserCustomization> = listOf(),
+) : QueryParserGenerator(codegenContext, httpBindingResolver, returnSymbolToParse, customizations) {
+    override val protocolName: String get() = "AWS Query"
+
+    override fun MemberShape.queryKeyName(prioritizedFallback: String?): String =
+        getTrait<XmlNameTrait>()?.value ?: memberName
+
+    override fun MemberShape.isFlattened(): Boolean = getTrait<XmlFlattenedTrait>() != null
+}
@@ -0,0 +1,493 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import software.amazon.smithy.codegen.core.Symbol
+import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.BooleanShape
+import software.amazon.smithy.model.shapes.ByteShape
+import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.DocumentShape
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
+import software.amazon.smithy.model.shapes.IntegerShape
+import software.amazon.smithy.model.shapes.LongShape
+import software.amazon.smithy.model.shapes.MapShape
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.ShortShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.TimestampShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.EnumTrait
+import software.amazon.smithy.model.traits.SparseTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.escape
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenTarget
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.canUseDefault
+import software.amazon.smithy.rust.codegen.core.smithy.customize.NamedCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.Section
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.renderUnknownVariant
+import software.amazon.smithy.rust.codegen.core.smithy.generators.setterName
+import software.amazon.smithy.rust.codegen.core.smithy.isOptional
+import software.amazon.smithy.rust.codegen.core.smithy.isRustBoxed
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpLocation
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolFunctions
+import software.amazon.smithy.rust.codegen.core.util.PANIC
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+/**
+ * Class describing a CBOR parser section that can be used in a customization.
+ */
+sealed class CborParserSection(name: String) : Section(name) {
+    data class BeforeBoxingDeserializedMember(val shape: MemberShape) :
+        CborParserSection("BeforeBoxingDeserializedMember")
+}
+
+/**
+ * Customization for the CBOR parser.
+ */
+typealias CborParserCustomization = NamedCustomization<CborParserSection>
+
+/**
+ * Generates CBOR parsers for the Smithy RPC v2 CBOR protocol.
+ *
+ * Every generated function reads from a `&mut aws_smithy_cbor::Decoder` named `decoder`. Aggregate shapes get their
+ * own function, which decodes a non-null value; members are decoded into an `Option` that is `None` when the
+ * encoded value is `null`, mirroring what [JsonParserGenerator] does.
+ */
+class CborParserGenerator(
+    private val codegenContext: CodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    /**
+     * Whether we should parse a value for a shape into its associated unconstrained type.
+     * See [JsonParserGenerator] for details.
+     */
+    private val returnSymbolToParse: (Shape) -> ReturnSymbolToParse = { shape ->
+        ReturnSymbolToParse(codegenContext.symbolProvider.toSymbol(shape), false)
+    },
+    private val customizations: List<CborParserCustomization> = listOf(),
+) : StructuredDataParserGenerator {
+    private val model = codegenContext.model
+    private val symbolProvider = codegenContext.symbolProvider
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenTarget = codegenContext.target
+    private val smithyCbor = RuntimeType.smithyCbor(runtimeConfig)
+    private val protocolFunctions = ProtocolFunctions(codegenContext)
+    private val builderInstantiator = codegenContext.builderInstantiator()
+    private val codegenScope = arrayOf(
+        "Error" to smithyCbor.resolve("DeserializeError"),
+        "Decoder" to smithyCbor.resolve("Decoder"),
+        "HashMap" to RuntimeType.HashMap,
+    )
+
+    /**
+     * Reusable structure parser implementation that can be used to generate parsing code for
+     * operation, error and structure shapes.
+     */
+    private fun structureParser(
+        shape: Shape,
+        builderSymbol: Symbol,
+        includedMembers: List<MemberShape>,
+        fnNameSuffix: String? = null,
+    ): RuntimeType {
+        return protocolFunctions.deserializeFn(shape, fnNameSuffix) { fnName ->
+            val unusedMut = if (includedMembers.isEmpty()) "##[allow(unused_mut)] " else ""
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: &[u8], ${unusedMut}mut builder: #{Builder}) -> Result<#{Builder}, #{Error}>",
+                "Builder" to builderSymbol,
+                *codegenScope,
+            ) {
+                rustTemplate(
+                    """
+                    if value.is_empty() {
+                        return Ok(builder);
+                    }
+                    let decoder = &mut #{Decoder}::new(value);
+                    """,
+                    *codegenScope,
+                )
+                deserializeStructInner(includedMembers)
+                expectEndOfInput()
+                rust("Ok(builder)")
+            }
+        }
+    }
+
+    override fun payloadParser(member: MemberShape): RuntimeType {
+        val shape = model.expectShape(member.target)
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        check(shape is UnionShape || shape is StructureShape || shape is DocumentShape) {
+            "Payload parser should only be used on structure shapes, union shapes, and document shapes."
+        }
+        return protocolFunctions.deserializeFn(shape, fnNameSuffix = "payload") { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(input: &[u8]) -> Result<#{ReturnType}, #{Error}>",
+                *codegenScope,
+                "ReturnType" to returnSymbolToParse.symbol,
+            ) {
+                rustTemplate("let decoder = &mut #{Decoder}::new(input);", *codegenScope)
+                rust("let result =")
+                deserializeMember(member)
+                rustTemplate(".ok_or_else(|| #{Error}::custom(\"expected payload member value\"));", *codegenScope)
+                expectEndOfInput()
+                rust("result")
+            }
+        }
+    }
+
+    override fun operationParser(operationShape: OperationShape): RuntimeType? {
+        // Don't generate an operation CBOR deserializer if there is no CBOR body
+        val httpDocumentMembers = httpBindingResolver.responseMembers(operationShape, HttpLocation.DOCUMENT)
+        if (httpDocumentMembers.isEmpty()) {
+            return null
+        }
+        val outputShape = operationShape.outputShape(model)
+        return structureParser(operationShape, symbolProvider.symbolForBuilder(outputShape), httpDocumentMembers)
+    }
+
+    override fun errorParser(errorShape: StructureShape): RuntimeType? {
+        if (errorShape.members().isEmpty()) {
+            return null
+        }
+        return structureParser(
+            errorShape,
+            symbolProvider.symbolForBuilder(errorShape),
+            errorShape.members().toList(),
+            fnNameSuffix = "cbor_err",
+        )
+    }
+
+    override fun serverInputParser(operationShape: OperationShape): RuntimeType? {
+        val includedMembers = httpBindingResolver.requestMembers(operationShape, HttpLocation.DOCUMENT)
+        if (includedMembers.isEmpty()) {
+            return null
+        }
+        val inputShape = operationShape.inputShape(model)
+        return structureParser(operationShape, symbolProvider.symbolForBuilder(inputShape), includedMembers)
+    }
+
+    private fun RustWriter.expectEndOfInput() {
+        rustBlock("if !decoder.is_empty()") {
+            rustTemplate(
+                "return Err(#{Error}::custom(\"found more CBOR data after completing parsing\"));",
+                *codegenScope,
+            )
+        }
+    }
+
+    /** Decodes a map into the `builder` in scope, setting each member that is present and not `null`. */
+    private fun RustWriter.deserializeStructInner(members: Collection<MemberShape>) {
+        if (members.isEmpty()) {
+            rust(
+                """
+                decoder.map_entries((), |_, decoder| {
+                    decoder.skip()?;
+                    decoder.skip()
+                })?;
+                """,
+            )
+            return
+        }
+        rustBlock("builder = decoder.map_entries(builder, |mut builder, decoder|") {
+            rustBlock("match decoder.str()?.as_ref()") {
+                for (member in members) {
+                    rustBlock("${member.memberName.dq()} =>") {
+                        if (codegenTarget == CodegenTarget.SERVER && !symbolProvider.toSymbol(member).isOptional()) {
+                            rust("if let Some(v) = ")
+                            deserializeMember(member)
+                            rust(
+                                """
+                                {
+                                    builder = builder.${member.setterName()}(v);
+                                }
+                                """,
+                            )
+                        } else {
+                            withBlock("builder = builder.${member.setterName()}(", ");") {
+                                deserializeMember(member)
+                            }
+                        }
+                    }
+                }
+                rust("_ => decoder.skip()?,")
+            }
+            rust("Ok(builder)")
+        }
+        rust(")?;")
+    }
+
+    /** Writes an expression that evaluates to the member's value, or `None` if it was encoded as `null`. */
+    private fun RustWriter.deserializeMember(memberShape: MemberShape) {
+        when (val target = model.expectShape(memberShape.target)) {
+            is StringShape -> deserializeString(target)
+            is BooleanShape -> rust("decoder.nullable(|decoder| decoder.boolean())?")
+            is ByteShape -> rust("decoder.nullable(|decoder| decoder.byte())?")
+            is ShortShape -> rust("decoder.nullable(|decoder| decoder.short())?")
+            is IntegerShape -> rust("decoder.nullable(|decoder| decoder.integer())?")
+            is LongShape -> rust("decoder.nullable(|decoder| decoder.long())?")
+            is FloatShape -> rust("decoder.nullable(|decoder| decoder.float())?")
+            is DoubleShape -> rust("decoder.nullable(|decoder| decoder.double())?")
+            is BlobShape -> rust("decoder.nullable(|decoder| decoder.blob())?")
+            is TimestampShape -> rust("decoder.nullable(|decoder| decoder.timestamp())?")
+            is CollectionShape -> rust("decoder.nullable(#T)?", collectionParser(target))
+            is MapShape -> rust("decoder.nullable(#T)?", mapParser(target))
+            is StructureShape -> rust("decoder.nullable(#T)?", structParser(target))
+            is UnionShape -> rust("decoder.nullable(#T)?", unionParser(target))
+            // A `null` document is `Document::Null`, not an absent value.
+            is DocumentShape -> rust("Some(decoder.document()?)")
+            else -> PANIC("unexpected shape: $target")
+        }
+        val symbol = symbolProvider.toSymbol(memberShape)
+        if (symbol.isRustBoxed()) {
+            for (customization in customizations) {
+                customization.section(CborParserSection.BeforeBoxingDeserializedMember(memberShape))(this)
+            }
+            rust(".map(Box::new)")
+        }
+    }
+
+    /** Writes an expression that converts the `&str` named [strName] into the type that [target] is parsed into. */
+    private fun RustWriter.deserializeStringInner(target: StringShape, strName: String) {
+        if (target.hasTrait<EnumTrait>() && !returnSymbolToParse(target).isUnconstrained) {
+            rust("#T::from($strName)", symbolProvider.toSymbol(target))
+        } else {
+            rust("$strName.to_owned()")
+        }
+    }
+
+    private fun RustWriter.deserializeString(target: StringShape) {
+        withBlock("decoder.nullable(|decoder| decoder.str().map(|s|", "))?") {
+            deserializeStringInner(target, "s.as_ref()")
+        }
+    }
+
+    private fun collectionParser(shape: CollectionShape): RuntimeType {
+        val isSparse = shape.hasTrait<SparseTrait>()
+        val (returnSymbol, returnUnconstrainedType) = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbol,
+                *codegenScope,
+            ) {
+                rustBlock("let items = decoder.array_items(Vec::new(), |mut items, decoder|") {
+                    if (isSparse) {
+                        withBlock("items.push(", ");") {
+                            deserializeMember(shape.member)
+                        }
+                    } else {
+                        withBlock("let value =", ";") {
+                            deserializeMember(shape.member)
+                        }
+                        rust(
+                            """
+                            if let Some(value) = value {
+                                items.push(value);
+                            }
+                            """,
+                        )
+                        codegenTarget.ifServer {
+                            rustTemplate(
+                                """
+                                else {
+                                    return Err(#{Error}::custom("dense list cannot contain null values"));
+                                }
+                                """,
+                                *codegenScope,
+                            )
+                        }
+                    }
+                    rust("Ok(items)")
+                }
+                rust(")?;")
+                if (returnUnconstrainedType) {
+                    rust("Ok(#T(items))", returnSymbol)
+                } else {
+                    rust("Ok(items)")
+                }
+            }
+        }
+    }
+
+    private fun mapParser(shape: MapShape): RuntimeType {
+        val keyTarget = model.expectShape(shape.key.target) as StringShape
+        val isSparse = shape.hasTrait<SparseTrait>()
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbolToParse.symbol,
+                *codegenScope,
+            ) {
+                rustBlockTemplate("let map = decoder.map_entries(#{HashMap}::new(), |mut map, decoder|", *codegenScope) {
+                    withBlock("let key =", ";") {
+                        deserializeStringInner(keyTarget, "decoder.str()?.as_ref()")
+                    }
+                    withBlock("let value =", ";") {
+                        deserializeMember(shape.value)
+                    }
+                    if (isSparse) {
+                        rust("map.insert(key, value);")
+                    } else {
+                        codegenTarget.ifServer {
+                            rustTemplate(
+                                """
+                                match value {
+                                    Some(value) => { map.insert(key, value); }
+                                    None => return Err(#{Error}::custom("dense map cannot contain null values"))
+                                }
+                                """,
+                                *codegenScope,
+                            )
+                        }
+                        codegenTarget.ifClient {
+                            rust(
+                                """
+                                if let Some(value) = value {
+                                    map.insert(key, value);
+                                }
+                                """,
+                            )
+                        }
+                    }
+                    rust("Ok(map)")
+                }
+                rust(")?;")
+                if (returnSymbolToParse.isUnconstrained) {
+                    rust("Ok(#T(map))", returnSymbolToParse.symbol)
+                } else {
+                    rust("Ok(map)")
+                }
+            }
+        }
+    }
+
+    private fun structParser(shape: StructureShape): RuntimeType {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbolToParse.symbol,
+                *codegenScope,
+            ) {
+                Attribute.AllowUnusedMut.render(this)
+                rustTemplate(
+                    "let mut builder = #{Builder}::default();",
+                    *codegenScope,
+                    "Builder" to symbolProvider.symbolForBuilder(shape),
+                )
+                deserializeStructInner(shape.members())
+                val builder = builderInstantiator.finalizeBuilder(
+                    "builder", shape,
+                ) {
+                    rustTemplate(
+                        """|err|#{Error}::custom_source("Response was invalid", err)""", *codegenScope,
+                    )
+                }
+                rust("Ok(#T)", builder)
+            }
+        }
+    }
+
+    private fun unionParser(shape: UnionShape): RuntimeType {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(decoder: &mut #{Decoder}<'_>) -> Result<#{Shape}, #{Error}>",
+                *codegenScope,
+                "Shape" to returnSymbolToParse.symbol,
+            ) {
+                val checkValueSet = !shape.members().all { it.isTargetUnit() } && !codegenTarget.renderUnknownVariant()
+                rustBlock("let variant = decoder.map_entries(None, |variant, decoder|") {
+                    rustTemplate(
+                        """
+                        let key = decoder.str()?;
+                        if key == "__type" {
+                            decoder.skip()?;
+                            return Ok(variant);
+                        }
+                        if variant.is_some() {
+                            return Err(#{Error}::custom("encountered mixed variants in union"));
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                    withBlock("Ok(match key.as_ref() {", "})") {
+                        for (member in shape.members()) {
+                            val variantName = symbolProvider.toMemberName(member)
+                            rustBlock("${member.memberName.dq()} =>") {
+                                if (member.isTargetUnit()) {
+                                    rustTemplate(
+                                        """
+                                        decoder.skip()?;
+                                        Some(#{Union}::$variantName)
+                                        """,
+                                        "Union" to returnSymbolToParse.symbol,
+                                    )
+                                } else {
+                                    withBlock("Some(#T::$variantName(", "))", returnSymbolToParse.symbol) {
+                                        deserializeMember(member)
+                                        unwrapOrDefaultOrError(member, checkValueSet)
+                                    }
+                                }
+                            }
+                        }
+                        when (codegenTarget.renderUnknownVariant()) {
+                            // In client mode, resolve an unknown union variant to the unknown variant.
+                            true -> rustTemplate(
+                                """
+                                _ => {
+                                    decoder.skip()?;
+                                    Some(#{Union}::${UnionGenerator.UnknownVariantName})
+                                }
+                                """,
+                                "Union" to returnSymbolToParse.symbol,
+                            )
+                            // In server mode, use strict parsing.
+                            false -> rustTemplate(
+                                """variant => return Err(#{Error}::custom(format!("unexpected union variant: {}", variant)))""",
+                                *codegenScope,
+                            )
+                        }
+                    }
+                }
+                rust(")?;")
+                rustTemplate(
+                    """variant.ok_or_else(|| #{Error}::custom("union must have exactly one variant"))""",
+                    *codegenScope,
+                )
+            }
+        }
+    }
+
+    private fun RustWriter.unwrapOrDefaultOrError(member: MemberShape, checkValueSet: Boolean) {
+        if (symbolProvider.toSymbol(member).canUseDefault() && !checkValueSet) {
+            rust(".unwrap_or_default()")
+        } else {
+            rustTemplate(
+                ".ok_or_else(|| #{Error}::custom(\"value for '${escape(member.memberName)}' cannot be null\"))?",
+                *codegenScope,
+            )
+        }
+    }
+}
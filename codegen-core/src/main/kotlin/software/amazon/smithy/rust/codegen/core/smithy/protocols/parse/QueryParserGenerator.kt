@@ -0,0 +1,362 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.BooleanShape
+import software.amazon.smithy.model.shapes.ByteShape
+import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
+import software.amazon.smithy.model.shapes.IntegerShape
+import software.amazon.smithy.model.shapes.LongShape
+import software.amazon.smithy.model.shapes.MapShape
+import software.amazon.smithy.model.shapes.MemberShape
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.Shape
+import software.amazon.smithy.model.shapes.ShortShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.model.shapes.TimestampShape
+import software.amazon.smithy.model.shapes.UnionShape
+import software.amazon.smithy.model.traits.EnumTrait
+import software.amazon.smithy.model.traits.SparseTrait
+import software.amazon.smithy.model.traits.TimestampFormatTrait
+import software.amazon.smithy.model.traits.XmlNameTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.conditionalBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenTarget
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.customize.NamedCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.customize.Section
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.renderUnknownVariant
+import software.amazon.smithy.rust.codegen.core.smithy.generators.setterName
+import software.amazon.smithy.rust.codegen.core.smithy.isOptional
+import software.amazon.smithy.rust.codegen.core.smithy.isRustBoxed
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpLocation
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolFunctions
+import software.amazon.smithy.rust.codegen.core.util.PANIC
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.getTrait
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
+
+/**
+ * Class describing a Query parser section that can be used in a customization.
+ */
+sealed class QueryParserSection(name: String) : Section(name) {
+    data class BeforeBoxingDeserializedMember(val shape: MemberShape) :
+        QueryParserSection("BeforeBoxingDeserializedMember")
+}
+
+/**
+ * Customization for the Query parser.
+ */
+typealias QueryParserCustomization = NamedCustomization<QueryParserSection>
+
+/**
+ * Generates parsers for the form-urlencoded request bodies of the `awsQuery` and `ec2Query` protocols, which are
+ * only needed by servers.
+ *
+ * The body is parsed into an `aws_smithy_query::deserialize::QueryDocument`, and every generated function reads from
+ * a `QueryValue` of that document. Aggregate shapes get their own function; since whether a list or map is flattened
+ * depends on the member targeting it, collection and map functions take a `flat` argument.
+ */
+abstract class QueryParserGenerator(
+    private val codegenContext: CodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    /**
+     * Whether we should parse a value for a shape into its associated unconstrained type.
+     * See [JsonParserGenerator] for details.
+     */
+    private val returnSymbolToParse: (Shape) -> ReturnSymbolToParse = { shape ->
+        ReturnSymbolToParse(codegenContext.symbolProvider.toSymbol(shape), false)
+    },
+    private val customizations: List<QueryParserCustomization> = listOf(),
+) : StructuredDataParserGenerator {
+    private val model = codegenContext.model
+    private val symbolProvider = codegenContext.symbolProvider
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val codegenTarget = codegenContext.target
+    private val smithyQuery = RuntimeType.smithyQuery(runtimeConfig)
+    private val protocolFunctions = ProtocolFunctions(codegenContext)
+    private val builderInstantiator = codegenContext.builderInstantiator()
+    private val codegenScope = arrayOf(
+        "Error" to smithyQuery.resolve("deserialize::DeserializeError"),
+        "QueryDocument" to smithyQuery.resolve("deserialize::QueryDocument"),
+        "QueryValue" to smithyQuery.resolve("deserialize::QueryValue"),
+        "HashMap" to RuntimeType.HashMap,
+    )
+
+    abstract val protocolName: String
+    abstract fun MemberShape.queryKeyName(prioritizedFallback: String? = null): String
+    abstract fun MemberShape.isFlattened(): Boolean
+
+    override fun payloadParser(member: MemberShape): RuntimeType {
+        TODO("$protocolName doesn't support payload parsing")
+    }
+
+    override fun operationParser(operationShape: OperationShape): RuntimeType? {
+        TODO("$protocolName responses are XML documents and are parsed with the XML parser")
+    }
+
+    override fun errorParser(errorShape: StructureShape): RuntimeType? {
+        TODO("$protocolName errors are XML documents and are parsed with the XML parser")
+    }
+
+    override fun serverInputParser(operationShape: OperationShape): RuntimeType? {
+        val includedMembers = httpBindingResolver.requestMembers(operationShape, HttpLocation.DOCUMENT)
+        if (includedMembers.isEmpty()) {
+            return null
+        }
+        val inputShape = operationShape.inputShape(model)
+        return protocolFunctions.deserializeFn(operationShape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: &[u8], mut builder: #{Builder}) -> Result<#{Builder}, #{Error}>",
+                "Builder" to symbolProvider.symbolForBuilder(inputShape),
+                *codegenScope,
+            ) {
+                rustTemplate(
+                    """
+                    let document = #{QueryDocument}::parse(value)?;
+                    let root = document.root();
+                    """,
+                    *codegenScope,
+                )
+                deserializeStructInner(includedMembers, "root")
+                rust("Ok(builder)")
+            }
+        }
+    }
+
+    /** Sets each member of the `builder` in scope that is present below the `QueryValue` named [valueName]. */
+    private fun RustWriter.deserializeStructInner(members: Collection<MemberShape>, valueName: String) {
+        if (members.isEmpty()) {
+            rust("let _ = $valueName;")
+            return
+        }
+        for (member in members) {
+            rustBlock("if let Some(value) = $valueName.member(${member.queryKeyName().dq()})") {
+                // Server builders take non-optional members by value.
+                val wrapInSome = codegenTarget == CodegenTarget.CLIENT || symbolProvider.toSymbol(member).isOptional()
+                withBlock("builder = builder.${member.setterName()}(", ");") {
+                    conditionalBlock("Some(", ")", conditional = wrapInSome) {
+                        deserializeMember(member, "value")
+                    }
+                }
+            }
+        }
+    }
+
+    /** Writes an expression that evaluates to the member's value, read from the `QueryValue` named [valueName]. */
+    private fun RustWriter.deserializeMember(memberShape: MemberShape, valueName: String) {
+        val symbol = symbolProvider.toSymbol(memberShape)
+        if (symbol.isRustBoxed()) {
+            rust("Box::new(")
+        }
+        when (val target = model.expectShape(memberShape.target)) {
+            is StringShape -> deserializeStringInner(target, "$valueName.string()?")
+            is BooleanShape -> rust("$valueName.boolean()?")
+            is ByteShape -> rust("$valueName.primitive::<i8>()?")
+            is ShortShape -> rust("$valueName.primitive::<i16>()?")
+            is IntegerShape -> rust("$valueName.primitive::<i32>()?")
+            is LongShape -> rust("$valueName.primitive::<i64>()?")
+            is FloatShape -> rust("$valueName.primitive::<f32>()?")
+            is DoubleShape -> rust("$valueName.primitive::<f64>()?")
+            is BlobShape -> rust("$valueName.blob()?")
+            is TimestampShape -> {
+                val timestampFormat = httpBindingResolver.timestampFormat(
+                    memberShape,
+                    HttpLocation.DOCUMENT,
+                    TimestampFormatTrait.Format.DATE_TIME,
+                    model,
+                )
+                val timestampFormatType = RuntimeType.parseTimestampFormat(codegenTarget, runtimeConfig, timestampFormat)
+                rust("$valueName.date_time(#T)?", timestampFormatType)
+            }
+            is CollectionShape -> rust("#T($valueName, ${memberShape.isFlattened()})?", collectionParser(target))
+            is MapShape -> rust("#T($valueName, ${memberShape.isFlattened()})?", mapParser(target))
+            is StructureShape -> rust("#T($valueName)?", structParser(target))
+            is UnionShape -> rust("#T($valueName)?", unionParser(target))
+            else -> PANIC("unexpected shape: $target")
+        }
+        if (symbol.isRustBoxed()) {
+            for (customization in customizations) {
+                customization.section(QueryParserSection.BeforeBoxingDeserializedMember(memberShape))(this)
+            }
+            rust(")")
+        }
+    }
+
+    /** Writes an expression that converts the `&str` [strExpression] into the type that [target] is parsed into. */
+    private fun RustWriter.deserializeStringInner(target: StringShape, strExpression: String) {
+        if (target.hasTrait<EnumTrait>() && !returnSymbolToParse(target).isUnconstrained) {
+            rust("#T::from($strExpression)", symbolProvider.toSymbol(target))
+        } else {
+            rust("$strExpression.to_owned()")
+        }
+    }
+
+    private fun collectionParser(shape: CollectionShape): RuntimeType {
+        val isSparse = shape.hasTrait<SparseTrait>()
+        val memberName = when (val name = shape.member.getTrait<XmlNameTrait>()?.value) {
+            null -> "None"
+            else -> "Some(${name.dq()})"
+        }
+        val (returnSymbol, returnUnconstrainedType) = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>, flat: bool) -> Result<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbol,
+                *codegenScope,
+            ) {
+                rust("let mut items = Vec::new();")
+                rustBlock("for value in value.list(flat, $memberName)?") {
+                    // Query requests can't express `null`, so the items of sparse lists are always present.
+                    withBlock("items.push(", ");") {
+                        if (isSparse) {
+                            withBlock("Some(", ")") { deserializeMember(shape.member, "value") }
+                        } else {
+                            deserializeMember(shape.member, "value")
+                        }
+                    }
+                }
+                if (returnUnconstrainedType) {
+                    rust("Ok(#T(items))", returnSymbol)
+                } else {
+                    rust("Ok(items)")
+                }
+            }
+        }
+    }
+
+    private fun mapParser(shape: MapShape): RuntimeType {
+        val keyTarget = model.expectShape(shape.key.target) as StringShape
+        val isSparse = shape.hasTrait<SparseTrait>()
+        val keyName = shape.key.queryKeyName("key").dq()
+        val valueName = shape.value.queryKeyName("value").dq()
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>, flat: bool) -> Result<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbolToParse.symbol,
+                *codegenScope,
+            ) {
+                rustTemplate("let mut map = #{HashMap}::new();", *codegenScope)
+                rustBlock("for (key, value) in value.map(flat, $keyName, $valueName)?") {
+                    withBlock("let key =", ";") {
+                        deserializeStringInner(keyTarget, "key")
+                    }
+                    withBlock("let value =", ";") {
+                        if (isSparse) {
+                            withBlock("Some(", ")") { deserializeMember(shape.value, "value") }
+                        } else {
+                            deserializeMember(shape.value, "value")
+                        }
+                    }
+                    rust("map.insert(key, value);")
+                }
+                if (returnSymbolToParse.isUnconstrained) {
+                    rust("Ok(#T(map))", returnSymbolToParse.symbol)
+                } else {
+                    rust("Ok(map)")
+                }
+            }
+        }
+    }
+
+    private fun structParser(shape: StructureShape): RuntimeType {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>) -> Result<#{ReturnType}, #{Error}>",
+                "ReturnType" to returnSymbolToParse.symbol,
+                *codegenScope,
+            ) {
+                Attribute.AllowUnusedMut.render(this)
+                rustTemplate(
+                    "let mut builder = #{Builder}::default();",
+                    *codegenScope,
+                    "Builder" to symbolProvider.symbolForBuilder(shape),
+                )
+                deserializeStructInner(shape.members(), "value")
+                val builder = builderInstantiator.finalizeBuilder(
+                    "builder", shape,
+                ) {
+                    rustTemplate("""|err| #{Error}::custom(err.to_string())""", *codegenScope)
+                }
+                rust("Ok(#T)", builder)
+            }
+        }
+    }
+
+    private fun unionParser(shape: UnionShape): RuntimeType {
+        val returnSymbolToParse = returnSymbolToParse(shape)
+        return protocolFunctions.deserializeFn(shape) { fnName ->
+            rustBlockTemplate(
+                "pub(crate) fn $fnName(value: #{QueryValue}<'_>) -> Result<#{Shape}, #{Error}>",
+                *codegenScope,
+                "Shape" to returnSymbolToParse.symbol,
+            ) {
+                rust("let mut variants = value.members();")
+                withBlock("let variant = match variants.next() {", "};") {
+                    for (member in shape.members()) {
+                        val variantName = symbolProvider.toMemberName(member)
+                        if (member.isTargetUnit()) {
+                            rust(
+                                "Some((${member.queryKeyName().dq()}, _)) => #T::$variantName,",
+                                returnSymbolToParse.symbol,
+                            )
+                        } else {
+                            withBlock(
+                                "Some((${member.queryKeyName().dq()}, value)) => #T::$variantName(",
+                                "),",
+                                returnSymbolToParse.symbol,
+                            ) {
+                                deserializeMember(member, "value")
+                            }
+                        }
+                    }
+                    when (codegenTarget.renderUnknownVariant()) {
+                        // In client mode, resolve an unknown union variant to the unknown variant.
+                        true -> rust(
+                            "Some(_) => #T::${UnionGenerator.UnknownVariantName},",
+                            returnSymbolToParse.symbol,
+                        )
+                        // In server mode, use strict parsing.
+                        false -> rustTemplate(
+                            """Some((variant, _)) => return Err(#{Error}::custom(format!("unexpected union variant: {}", variant))),""",
+                            *codegenScope,
+                        )
+                    }
+                    rustTemplate(
+                        """None => return Err(#{Error}::custom("union must have exactly one variant")),""",
+                        *codegenScope,
+                    )
+                }
+                rustTemplate(
+                    """
+                    if variants.next().is_some() {
+                        return Err(#{Error}::custom("encountered mixed variants in union"));
+                    }
+                    Ok(variant)
+                    """,
+                    *codegenScope,
+                )
+            }
+        }
+    }
+}
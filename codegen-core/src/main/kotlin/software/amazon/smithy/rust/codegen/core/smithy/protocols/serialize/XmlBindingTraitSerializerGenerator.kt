@@ -54,9 +54,36 @@ import software.amazon.smithy.rust.codegen.core.util.isTargetUnit
 import software.amazon.smithy.rust.codegen.core.util.letIf
 import software.amazon.smithy.rust.codegen.core.util.outputShape
 
+// The string argument is the name of the XML `ScopeWriter` to write the members to
+typealias ResponseInnerWriteable = RustWriter.(String) -> Unit
+
+data class ResponseWrapperContext(
+    /** The operation whose output is being serialized, or the error shape being serialized */
+    val shape: Shape,
+    /** The name of the `XmlWriter` to start the outermost element with */
+    val xmlWriter: String,
+    /** The `write_ns` call to apply to the outermost element, if the shape has a namespace */
+    val xmlNamespace: String,
+)
+
+/**
+ * Writes the elements that wrap the members of a response body. The members are written by calling the
+ * [ResponseInnerWriteable] with the name of the innermost `ScopeWriter`.
+ */
+typealias ResponseWrapperWriteable = RustWriter.(ResponseWrapperContext, ResponseInnerWriteable) -> Unit
+
+/**
+ * Serializes XML bodies as described by the [XML binding traits](https://smithy.io/2.0/spec/protocol-traits.html#xml-bindings).
+ *
+ * By default, the members of operation outputs and errors are written to a single root element. Protocols which
+ * wrap them in additional elements, such as `awsQuery` and `ec2Query`, can provide [writeOperationOutputWrapper] and
+ * [writeErrorWrapper] instead; a response body is then written even if there are no members.
+ */
 class XmlBindingTraitSerializerGenerator(
     codegenContext: CodegenContext,
     private val httpBindingResolver: HttpBindingResolver,
+    private val writeOperationOutputWrapper: ResponseWrapperWriteable? = null,
+    private val writeErrorWrapper: ResponseWrapperWriteable? = null,
 ) : StructuredDataSerializerGenerator {
     private val symbolProvider = codegenContext.symbolProvider
     private val runtimeConfig = codegenContext.runtimeConfig
@@ -201,6 +228,15 @@ class XmlBindingTraitSerializerGenerator(
     override fun operationOutputSerializer(operationShape: OperationShape): RuntimeType? {
         val outputShape = operationShape.outputShape(model)
         val xmlMembers = operationShape.responseBodyMembers()
+        if (writeOperationOutputWrapper != null) {
+            return wrappedResponseSerializer(
+                operationShape,
+                outputShape,
+                xmlMembers,
+                "output",
+                writeOperationOutputWrapper,
+            )
+        }
         if (xmlMembers.isEmpty()) {
             return null
         }
@@ -238,6 +274,15 @@ class XmlBindingTraitSerializerGenerator(
         val xmlMembers = httpBindingResolver.errorResponseBindings(shape)
             .filter { it.location == HttpLocation.DOCUMENT }
             .map { it.member }
+        if (writeErrorWrapper != null) {
+            return wrappedResponseSerializer(
+                errorShape,
+                errorShape,
+                XmlMemberIndex.fromMembers(xmlMembers),
+                "error",
+                writeErrorWrapper,
+            )
+        }
         return protocolFunctions.serializeFn(errorShape, fnNameSuffix = "error") { fnName ->
             rustBlockTemplate(
                 "pub fn $fnName(error: &#{target}) -> Result<String, #{Error}>",
@@ -267,6 +312,49 @@ class XmlBindingTraitSerializerGenerator(
         }
     }
 
+    /**
+     * Renders a serializer for [structureShape] whose [members] are written within the elements of [wrapper].
+     * [shape] is the operation or error the response body is for.
+     */
+    private fun wrappedResponseSerializer(
+        shape: Shape,
+        structureShape: StructureShape,
+        members: XmlMemberIndex,
+        input: String,
+        wrapper: ResponseWrapperWriteable,
+    ): RuntimeType {
+        val fnNameSuffix = if (shape is OperationShape) "output" else "error"
+        return protocolFunctions.serializeFn(shape, fnNameSuffix = fnNameSuffix) { fnName ->
+            rustBlockTemplate(
+                "pub fn $fnName($input: &#{target}) -> Result<String, #{Error}>",
+                *codegenScope, "target" to symbolProvider.toSymbol(structureShape),
+            ) {
+                if (members.dataMembers.isEmpty()) {
+                    rust("let _ = $input;")
+                }
+                rust("let mut out = String::new();")
+                // Create a scope for writer. This ensures that:
+                // - The writer is dropped before returning the string
+                // - All closing tags get written
+                rustBlock("") {
+                    rustTemplate("let mut writer = #{XmlWriter}::new(&mut out);", *codegenScope)
+                    val context = ResponseWrapperContext(
+                        shape,
+                        "writer",
+                        structureShape.xmlNamespace(root = true).apply(),
+                    )
+                    wrapper(context) { scopeWriter ->
+                        // Wrapped responses are only used by the query protocols, which don't support `@xmlAttribute`.
+                        members.dataMembers.forEach { member ->
+                            serializeMember(member, Ctx.Scope(scopeWriter, "&$input").scopedTo(member), null)
+                        }
+                    }
+                }
+                rustTemplate("Ok(out)", *codegenScope)
+            }
+        }
+    }
+
     private fun XmlNamespaceTrait?.apply(): String {
         this ?: return ""
         val prefix = prefix.map { prefix -> "Some(${prefix.dq()})" }.orElse("None")
@@ -0,0 +1,24 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.traits
+
+import software.amazon.smithy.model.node.ObjectNode
+import software.amazon.smithy.model.shapes.ShapeId
+import software.amazon.smithy.model.traits.AnnotationTrait
+
+/**
+ * Protocol trait for [Smithy RPC v2 CBOR](https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html).
+ *
+ * The trait's definition is bundled with codegen-core (see `META-INF/smithy/rpcv2Cbor.smithy`) since the Smithy
+ * version we depend on does not ship it.
+ */
+class RpcV2CborTrait(node: ObjectNode = ObjectNode.objectNode()) : AnnotationTrait(ID, node) {
+    companion object {
+        val ID: ShapeId = ShapeId.from("smithy.protocols#rpcv2Cbor")
+    }
+
+    class Provider : AnnotationTrait.Provider<RpcV2CborTrait>(ID, ::RpcV2CborTrait)
+}
@@ -0,0 +1,82 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols
+
+import io.kotest.assertions.throwables.shouldThrow
+import io.kotest.matchers.shouldBe
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.codegen.core.CodegenException
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.ServiceShape
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.OperationNormalizer
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.util.lookup
+
+class RpcV2CborTest {
+    private val model = OperationNormalizer.transform(
+        """
+        namespace test
+        use smithy.protocols#rpcv2Cbor
+
+        @rpcv2Cbor
+        service TestService {
+            operations: [WithInput, WithoutInput, Streaming]
+        }
+
+        structure WithInputInput {
+            greeting: String,
+            count: Integer,
+        }
+
+        operation WithInput {
+            input: WithInputInput,
+        }
+
+        operation WithoutInput {}
+
+        @streaming
+        blob StreamingBlob
+
+        structure StreamingInput {
+            payload: StreamingBlob,
+        }
+
+        operation Streaming {
+            input: StreamingInput,
+        }
+        """.asSmithyModel(),
+    )
+    private val resolver = RpcV2CborHttpBindingResolver(model, model.lookup<ServiceShape>("test#TestService"))
+
+    @Test
+    fun `all operations are POST requests to the service and operation path`() {
+        val httpTrait = resolver.httpTrait(model.lookup<OperationShape>("test#WithInput"))
+        httpTrait.method shouldBe "POST"
+        httpTrait.uri.toString() shouldBe "/service/TestService/operation/WithInput"
+        httpTrait.code shouldBe 200
+    }
+
+    @Test
+    fun `all members are bound to the document`() {
+        val bindings = resolver.requestBindings(model.lookup<OperationShape>("test#WithInput"))
+        bindings.map { it.memberName } shouldBe listOf("greeting", "count")
+        bindings.map { it.location }.toSet() shouldBe setOf(HttpLocation.DOCUMENT)
+    }
+
+    @Test
+    fun `operations without a modeled input have no content type`() {
+        resolver.requestContentType(model.lookup<OperationShape>("test#WithInput")) shouldBe "application/cbor"
+        resolver.requestContentType(model.lookup<OperationShape>("test#WithoutInput")) shouldBe null
+        resolver.responseContentType(model.lookup<OperationShape>("test#WithoutInput")) shouldBe "application/cbor"
+    }
+
+    @Test
+    fun `streaming members are rejected`() {
+        shouldThrow<CodegenException> {
+            resolver.requestBindings(model.lookup<OperationShape>("test#Streaming"))
+        }
+    }
+}
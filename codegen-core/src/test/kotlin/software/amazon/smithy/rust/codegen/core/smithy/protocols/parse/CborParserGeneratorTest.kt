@@ -0,0 +1,202 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.rust.codegen.core.smithy.generators.EnumGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.TestEnumType
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.RpcV2CborHttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.OperationNormalizer
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.RecursiveShapeBoxer
+import software.amazon.smithy.rust.codegen.core.testutil.TestWorkspace
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.compileAndTest
+import software.amazon.smithy.rust.codegen.core.testutil.renderWithModelBuilder
+import software.amazon.smithy.rust.codegen.core.testutil.testCodegenContext
+import software.amazon.smithy.rust.codegen.core.testutil.testSymbolProvider
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
+import software.amazon.smithy.rust.codegen.core.util.lookup
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+class CborParserGeneratorTest {
+    private val baseModel = """
+        namespace test
+        use smithy.protocols#rpcv2Cbor
+
+        @rpcv2Cbor
+        service TestService {
+            operations: [Op]
+        }
+
+        union Choice {
+            blob: Blob,
+            boolean: Boolean,
+            date: Timestamp,
+            document: Document,
+            enum: FooEnum,
+            int: Integer,
+            list: SomeList,
+            long: Long,
+            map: MyMap,
+            number: Double,
+            s: String,
+            top: Top,
+            unit: Unit,
+        }
+
+        @enum([{name: "FOO", value: "FOO"}])
+        string FooEnum
+
+        map MyMap {
+            key: String,
+            value: Choice,
+        }
+
+        list SomeList {
+            member: Choice
+        }
+
+        structure Top {
+            @required
+            choice: Choice,
+            field: String,
+            extra: Integer,
+            recursive: TopList,
+        }
+
+        list TopList {
+            member: Top
+        }
+
+        structure OpOutput {
+            top: Top
+        }
+
+        @error("client")
+        structure Error {
+            message: String,
+            reason: String
+        }
+
+        operation Op {
+            output: OpOutput,
+            errors: [Error]
+        }
+    """.asSmithyModel()
+
+    @Test
+    fun `generates valid deserializers`() {
+        val model = RecursiveShapeBoxer().transform(OperationNormalizer.transform(baseModel))
+        val codegenContext = testCodegenContext(model)
+        val symbolProvider = codegenContext.symbolProvider
+
+        val parserGenerator = CborParserGenerator(
+            codegenContext,
+            RpcV2CborHttpBindingResolver(model, codegenContext.serviceShape),
+        )
+        val operationGenerator = parserGenerator.operationParser(model.lookup("test#Op"))
+        val errorParser = parserGenerator.errorParser(model.lookup("test#Error"))
+
+        val project = TestWorkspace.testProject(testSymbolProvider(model))
+        project.lib {
+            unitTest(
+                "cbor_parser",
+                """
+                use test_model::Choice;
+
+                // {"top": {"extra": 45, "field": "something", "choice": {"int": 5}, "recursive": [{"choice": {"s": "nested"}}]}}
+                let cbor = b"\xa1\x63top\xa4\x65extra\x18\x2d\x65field\x69something\x66choice\xa1\x63int\x05\x69recursive\x81\xa1\x66choice\xa1\x61s\x66nested";
+
+                let output = ${format(operationGenerator!!)}(cbor, test_output::OpOutput::builder()).unwrap().build();
+                let top = output.top.expect("top");
+                assert_eq!(Some(45), top.extra);
+                assert_eq!(Some("something".to_string()), top.field);
+                assert_eq!(Choice::Int(5), top.choice);
+                let nested = top.recursive.expect("recursive");
+                assert_eq!(Choice::S("nested".to_string()), nested[0].choice);
+                """,
+            )
+            unitTest(
+                "empty_body",
+                """
+                let output = ${format(operationGenerator)}(b"", test_output::OpOutput::builder()).unwrap().build();
+                assert_eq!(output.top, None);
+                """,
+            )
+            unitTest(
+                "null_members_are_unset",
+                """
+                // {"top": {"choice": {"int": 5}, "field": null}}
+                let cbor = b"\xa1\x63top\xa2\x66choice\xa1\x63int\x05\x65field\xf6";
+                let output = ${format(operationGenerator)}(cbor, test_output::OpOutput::builder()).unwrap().build();
+                assert_eq!(None, output.top.unwrap().field);
+                """,
+            )
+            unitTest(
+                "indefinite_length_containers",
+                """
+                // {_ "top": {_ "choice": {_ "int": 5}, "field": (_ "some", "thing")}}
+                let cbor = b"\xbf\x63top\xbf\x66choice\xbf\x63int\x05\xff\x65field\x7f\x64some\x65thing\xff\xff\xff";
+                let output = ${format(operationGenerator)}(cbor, test_output::OpOutput::builder()).unwrap().build();
+                assert_eq!(Some("something".to_string()), output.top.unwrap().field);
+                """,
+            )
+            unitTest(
+                "unknown_variant",
+                """
+                // {"top": {"choice": {"somenewvariant": "data"}}}
+                let cbor = b"\xa1\x63top\xa1\x66choice\xa1\x6esomenewvariant\x64data";
+                let output = ${format(operationGenerator)}(cbor, test_output::OpOutput::builder()).unwrap().build();
+                assert!(output.top.unwrap().choice.is_unknown());
+                """,
+            )
+            unitTest(
+                "unknown_members_are_skipped",
+                """
+                // {"other": [1, {"a": null}], "top": {"choice": {"int": 5}}}
+                let cbor = b"\xa2\x65other\x82\x01\xa1\x61a\xf6\x63top\xa1\x66choice\xa1\x63int\x05";
+                let output = ${format(operationGenerator)}(cbor, test_output::OpOutput::builder()).unwrap().build();
+                assert_eq!(test_model::Choice::Int(5), output.top.unwrap().choice);
+                """,
+            )
+            unitTest(
+                "trailing_data_is_rejected",
+                """
+                let cbor = b"\xa0\x00";
+                ${format(operationGenerator)}(cbor, test_output::OpOutput::builder()).expect_err("trailing data");
+                """,
+            )
+            unitTest(
+                "error_with_message",
+                """
+                // {"message": "hello"}
+                let cbor = b"\xa1\x67message\x65hello";
+                let error_output = ${format(errorParser!!)}(cbor, test_error::Error::builder()).unwrap().build();
+                assert_eq!(error_output.message.expect("message should be set"), "hello");
+                """,
+            )
+        }
+        model.lookup<StructureShape>("test#Top").also { top ->
+            top.renderWithModelBuilder(model, symbolProvider, project)
+            project.moduleFor(top) {
+                UnionGenerator(model, symbolProvider, this, model.lookup("test#Choice")).render()
+                val enum = model.lookup<StringShape>("test#FooEnum")
+                EnumGenerator(model, symbolProvider, enum, TestEnumType).render(this)
+            }
+        }
+        model.lookup<OperationShape>("test#Op").outputShape(model).also { output ->
+            output.renderWithModelBuilder(model, symbolProvider, project)
+        }
+        model.lookup<StructureShape>("test#Error").also { error ->
+            error.renderWithModelBuilder(model, symbolProvider, project)
+        }
+        project.compileAndTest()
+    }
+}
@@ -0,0 +1,170 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.parse
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.generators.EnumGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.TestEnumType
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.AwsQueryBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.OperationNormalizer
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.RecursiveShapeBoxer
+import software.amazon.smithy.rust.codegen.core.testutil.TestWorkspace
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.compileAndTest
+import software.amazon.smithy.rust.codegen.core.testutil.renderWithModelBuilder
+import software.amazon.smithy.rust.codegen.core.testutil.testCodegenContext
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.lookup
+
+class QueryParserGeneratorTest {
+    private val baseModel = """
+        namespace test
+        use aws.protocols#awsQuery
+
+        union Choice {
+            s: String,
+            int: Integer,
+        }
+
+        @enum([{name: "FOO", value: "FOO"}])
+        string FooEnum
+
+        list StringList {
+            member: String
+        }
+
+        list NamedList {
+            @xmlName("item")
+            member: String
+        }
+
+        map StringMap {
+            key: String,
+            value: Integer,
+        }
+
+        structure Nested {
+            field: String,
+            count: Integer,
+        }
+
+        structure OpInput {
+            @xmlName("renamed")
+            boolean: Boolean,
+            list: StringList,
+            @xmlFlattened
+            flatList: StringList,
+            named: NamedList,
+            map: StringMap,
+            nested: Nested,
+            date: Timestamp,
+            fooEnum: FooEnum,
+            choice: Choice,
+        }
+
+        @http(uri: "/", method: "POST")
+        operation Op {
+            input: OpInput,
+        }
+    """.asSmithyModel()
+
+    private val model = RecursiveShapeBoxer().transform(OperationNormalizer.transform(baseModel))
+
+    /** Renders the input parser from [parserGenerator] and a test that parses [body] with it */
+    private fun testInputParser(
+        codegenContext: CodegenContext,
+        model: Model,
+        parserGenerator: QueryParserGenerator,
+        body: String,
+        booleanKey: String,
+    ) {
+        val symbolProvider = codegenContext.symbolProvider
+        val inputParser = parserGenerator.serverInputParser(model.lookup("test#Op"))!!
+        val project = TestWorkspace.testProject(symbolProvider)
+        project.lib {
+            unitTest(
+                "parses_input",
+                """
+                use test_model::{Choice, FooEnum, Nested};
+
+                let body = b"$body";
+                let input = ${format(inputParser)}(body, crate::test_input::OpInput::builder())
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                assert_eq!(Some(true), input.boolean);
+                assert_eq!(Some(vec!["a".to_string(), "b".to_string()]), input.list);
+                assert_eq!(Some(vec!["c".to_string()]), input.flat_list);
+                assert_eq!(Some(vec!["d".to_string()]), input.named);
+                assert_eq!(Some(&1), input.map.as_ref().unwrap().get("k"));
+                assert_eq!(
+                    Some(Nested::builder().field("hi").count(2).build()),
+                    input.nested
+                );
+                assert_eq!(Some(::aws_smithy_types::DateTime::from_secs(1)), input.date);
+                assert_eq!(Some(FooEnum::from("FOO")), input.foo_enum);
+                assert_eq!(Some(Choice::Int(5)), input.choice);
+
+                let err = ${format(inputParser)}(b"Action=Op&Version=test&$booleanKey=maybe", crate::test_input::OpInput::builder())
+                    .expect_err("not a boolean");
+                assert!(format!("{err}").contains("`$booleanKey`"), "{err}");
+                """,
+            )
+        }
+        model.lookup<StructureShape>("test#Nested").also { nested ->
+            nested.renderWithModelBuilder(model, symbolProvider, project)
+            project.moduleFor(nested) {
+                UnionGenerator(model, symbolProvider, this, model.lookup("test#Choice")).render()
+                val enum = model.lookup<StringShape>("test#FooEnum")
+                EnumGenerator(model, symbolProvider, enum, TestEnumType).render(this)
+            }
+        }
+        model.lookup<OperationShape>("test#Op").inputShape(model).also { input ->
+            input.renderWithModelBuilder(model, symbolProvider, project)
+        }
+        project.compileAndTest()
+    }
+
+    @Test
+    fun `generates valid AWS Query input parsers`() {
+        val codegenContext = testCodegenContext(model)
+        testInputParser(
+            codegenContext,
+            model,
+            AwsQueryInputParserGenerator(codegenContext, AwsQueryBindingResolver(model)),
+            "Action=Op&Version=test&renamed=true" +
+                "&list.member.1=a&list.member.2=b&flatList.1=c&named.item.1=d" +
+                "&map.entry.1.key=k&map.entry.1.value=1" +
+                "&nested.field=hi&nested.count=2" +
+                "&date=1970-01-01T00%3A00%3A01Z&fooEnum=FOO&choice.int=5",
+            "renamed",
+        )
+    }
+
+    @Test
+    fun `generates valid EC2 Query input parsers`() {
+        val codegenContext = testCodegenContext(model)
+        testInputParser(
+            codegenContext,
+            model,
+            Ec2QueryInputParserGenerator(codegenContext, AwsQueryBindingResolver(model)),
+            // EC2 Query capitalizes parameter names and always flattens lists and maps
+            "Action=Op&Version=test&Renamed=true" +
+                "&List.1=a&List.2=b&FlatList.1=c&Named.1=d" +
+                "&Map.1.Key=k&Map.1.Value=1" +
+                "&Nested.Field=hi&Nested.Count=2" +
+                "&Date=1970-01-01T00%3A00%3A01Z&FooEnum=FOO&Choice.Int=5",
+            "Renamed",
+        )
+    }
+}
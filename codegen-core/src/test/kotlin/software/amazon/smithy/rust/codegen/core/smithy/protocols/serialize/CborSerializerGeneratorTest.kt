@@ -0,0 +1,167 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize
+
+import io.kotest.matchers.shouldBe
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.shapes.StringShape
+import software.amazon.smithy.model.shapes.StructureShape
+import software.amazon.smithy.rust.codegen.core.smithy.generators.EnumGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.generators.TestEnumType
+import software.amazon.smithy.rust.codegen.core.smithy.generators.UnionGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.RpcV2CborHttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.OperationNormalizer
+import software.amazon.smithy.rust.codegen.core.smithy.transformers.RecursiveShapeBoxer
+import software.amazon.smithy.rust.codegen.core.testutil.TestWorkspace
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.compileAndTest
+import software.amazon.smithy.rust.codegen.core.testutil.renderWithModelBuilder
+import software.amazon.smithy.rust.codegen.core.testutil.testCodegenContext
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
+import software.amazon.smithy.rust.codegen.core.util.inputShape
+import software.amazon.smithy.rust.codegen.core.util.lookup
+
+class CborSerializerGeneratorTest {
+    private val baseModel = """
+        namespace test
+        use smithy.protocols#rpcv2Cbor
+
+        @rpcv2Cbor
+        service TestService {
+            operations: [Op, NoInput]
+        }
+
+        union Choice {
+            blob: Blob,
+            boolean: Boolean,
+            date: Timestamp,
+            document: Document,
+            enum: FooEnum,
+            int: Integer,
+            list: SomeList,
+            long: Long,
+            map: MyMap,
+            number: Double,
+            s: String,
+            top: Top,
+            unit: Unit,
+        }
+
+        @enum([{name: "FOO", value: "FOO"}])
+        string FooEnum
+
+        map MyMap {
+            key: String,
+            value: Choice,
+        }
+
+        list SomeList {
+            member: Choice
+        }
+
+        structure Top {
+            choice: Choice,
+            field: String,
+            extra: Integer,
+            recursive: TopList,
+        }
+
+        list TopList {
+            member: Top
+        }
+
+        structure OpInput {
+            top: Top,
+        }
+
+        operation Op {
+            input: OpInput,
+        }
+
+        operation NoInput {}
+    """.asSmithyModel()
+
+    @Test
+    fun `generates valid serializers`() {
+        val model = RecursiveShapeBoxer().transform(OperationNormalizer.transform(baseModel))
+        val codegenContext = testCodegenContext(model)
+        val symbolProvider = codegenContext.symbolProvider
+        val serializerGenerator = CborSerializerGenerator(
+            codegenContext,
+            RpcV2CborHttpBindingResolver(model, codegenContext.serviceShape),
+        )
+        val operationGenerator = serializerGenerator.operationInputSerializer(model.lookup("test#Op"))
+        val documentGenerator = serializerGenerator.documentSerializer()
+
+        // Operations without a modeled input have no body.
+        serializerGenerator.operationInputSerializer(model.lookup("test#NoInput")) shouldBe null
+
+        val project = TestWorkspace.testProject(symbolProvider)
+        project.lib {
+            unitTest(
+                "cbor_serializers",
+                """
+                use test_model::{Top, Choice};
+
+                // Generate the document serializer even though it's not tested directly
+                // ${format(documentGenerator)}
+
+                let input = crate::test_input::OpInput::builder().top(
+                    Top::builder()
+                        .field("hello!")
+                        .extra(45)
+                        .recursive(Top::builder().extra(55).build())
+                        .build()
+                ).build().unwrap();
+                let serialized = ${format(operationGenerator!!)}(&input).unwrap();
+                // {_ "top": {_ "field": "hello!", "extra": 45, "recursive": [{_ "extra": 55}]}}
+                assert_eq!(
+                    &b"\xbf\x63top\xbf\x65field\x66hello!\x65extra\x18\x2d\x69recursive\x81\xbf\x65extra\x18\x37\xff\xff\xff"[..],
+                    serialized.bytes().unwrap(),
+                );
+
+                let input = crate::test_input::OpInput::builder().top(
+                    Top::builder()
+                        .choice(Choice::Int(5))
+                        .build()
+                ).build().unwrap();
+                let serialized = ${format(operationGenerator)}(&input).unwrap();
+                // {_ "top": {_ "choice": {"int": 5}}}
+                assert_eq!(
+                    &b"\xbf\x63top\xbf\x66choice\xa1\x63int\x05\xff\xff"[..],
+                    serialized.bytes().unwrap(),
+                );
+
+                let input = crate::test_input::OpInput::builder().build().unwrap();
+                let serialized = ${format(operationGenerator)}(&input).unwrap();
+                // Inputs without any members set are still serialized as a map
+                assert_eq!(&b"\xbf\xff"[..], serialized.bytes().unwrap());
+
+                let input = crate::test_input::OpInput::builder().top(
+                    Top::builder()
+                        .choice(Choice::Unknown)
+                        .build()
+                ).build().unwrap();
+                ${format(operationGenerator)}(&input).expect_err("cannot serialize unknown variant");
+                """,
+            )
+        }
+        model.lookup<StructureShape>("test#Top").also { top ->
+            top.renderWithModelBuilder(model, symbolProvider, project)
+            project.moduleFor(top) {
+                UnionGenerator(model, symbolProvider, this, model.lookup("test#Choice")).render()
+                val enum = model.lookup<StringShape>("test#FooEnum")
+                EnumGenerator(model, symbolProvider, enum, TestEnumType).render(this)
+            }
+        }
+
+        model.lookup<OperationShape>("test#Op").inputShape(model).also { input ->
+            input.renderWithModelBuilder(model, symbolProvider, project)
+        }
+        project.compileAndTest()
+    }
+}
@@ -76,6 +76,16 @@ val allCodegenTests = "../codegen-core/common-test-models".let { commonModels ->
         ),
         CodegenTest("aws.protocoltests.json10#JsonRpc10", "json_rpc10"),
         CodegenTest("aws.protocoltests.json#JsonProtocol", "json_rpc11"),
+        CodegenTest(
+            "smithy.protocoltests.rpcv2Cbor#RpcV2Protocol",
+            "rpcv2Cbor",
+            imports = listOf("$commonModels/rpcv2Cbor.smithy"),
+        ),
+        CodegenTest(
+            "aws.protocoltests.serverquery#AwsQueryServer",
+            "aws_query_server",
+            imports = listOf("$commonModels/aws-query.smithy"),
+        ),
         CodegenTest(
             "aws.protocoltests.misc#MiscService",
             "misc",
@@ -120,6 +120,7 @@ class PythonApplicationGenerator(
                 middlewares: Vec<#{SmithyPython}::PyMiddlewareHandler>,
                 context: Option<#{pyo3}::PyObject>,
                 workers: #{parking_lot}::Mutex<Vec<#{pyo3}::PyObject>>,
+                draining_workers: #{parking_lot}::Mutex<Vec<#{pyo3}::PyObject>>,
             }
             """,
             *codegenScope,
@@ -136,6 +137,7 @@ class PythonApplicationGenerator(
                         middlewares: self.middlewares.clone(),
                         context: self.context.clone(),
                         workers: #{parking_lot}::Mutex::new(vec![]),
+                        draining_workers: #{parking_lot}::Mutex::new(vec![]),
                     }
                 }
             }
@@ -154,6 +156,7 @@ class PythonApplicationGenerator(
                         middlewares: vec![],
                         context: None,
                         workers: #{parking_lot}::Mutex::new(vec![]),
+                        draining_workers: #{parking_lot}::Mutex::new(vec![]),
                     }
                 }
             }
@@ -175,6 +178,9 @@ class PythonApplicationGenerator(
                 fn workers(&self) -> &#{parking_lot}::Mutex<Vec<#{pyo3}::PyObject>> {
                     &self.workers
                 }
+                fn draining_workers(&self) -> &#{parking_lot}::Mutex<Vec<#{pyo3}::PyObject>> {
+                    &self.draining_workers
+                }
                 fn context(&self) -> &Option<#{pyo3}::PyObject> {
                     &self.context
                 }
@@ -296,8 +302,10 @@ class PythonApplicationGenerator(
                 /// :param backlog ${PythonType.Optional(PythonType.Int).renderAsDocstring()}:
                 /// :param workers ${PythonType.Optional(PythonType.Int).renderAsDocstring()}:
                 /// :param tls ${PythonType.Optional(tlsConfig).renderAsDocstring()}:
+                /// :param watch ${PythonType.Optional(PythonType.Bool).renderAsDocstring()}:
                 /// :rtype ${PythonType.None.renderAsDocstring()}:
-                ##[pyo3(text_signature = "(${'$'}self, address=None, port=None, backlog=None, workers=None, tls=None)")]
+                ##[pyo3(text_signature = "(${'$'}self, address=None, port=None, backlog=None, workers=None, tls=None, watch=None)")]
+                ##[allow(clippy::too_many_arguments)]
                 pub fn run(
                     &mut self,
                     py: #{pyo3}::Python,
@@ -306,9 +314,10 @@ class PythonApplicationGenerator(
                     backlog: Option<i32>,
                     workers: Option<usize>,
                     tls: Option<#{SmithyPython}::tls::PyTlsConfig>,
+                    watch: Option<bool>,
                 ) -> #{pyo3}::PyResult<()> {
                     use #{SmithyPython}::PyApp;
-                    self.run_server(py, address, port, backlog, workers, tls)
+                    self.run_server(py, address, port, backlog, workers, tls, watch)
                 }
 
                 /// Lambda entrypoint: start the server on Lambda.
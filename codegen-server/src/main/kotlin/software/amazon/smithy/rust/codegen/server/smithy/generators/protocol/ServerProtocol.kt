@@ -17,14 +17,24 @@ import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.AwsJson
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.AwsJsonVersion
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.AwsQueryProtocol
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.Ec2QueryProtocol
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.Protocol
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestJson
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestXml
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.RpcV2Cbor
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.awsJsonFieldName
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.AwsQueryInputParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.CborParserSection
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.Ec2QueryInputParserGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.JsonParserCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.JsonParserGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.JsonParserSection
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.QueryParserCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.QueryParserSection
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.ReturnSymbolToParse
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.StructuredDataParserGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.restJsonFieldName
@@ -35,7 +45,10 @@ import software.amazon.smithy.rust.codegen.server.smithy.ServerRuntimeType
 import software.amazon.smithy.rust.codegen.server.smithy.canReachConstrainedShape
 import software.amazon.smithy.rust.codegen.server.smithy.generators.http.RestRequestSpecGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerAwsJsonSerializerGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerAwsQuerySerializerGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerEc2QuerySerializerGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerRestJsonSerializerGenerator
+import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerRpcV2CborSerializerGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.targetCanReachConstrainedShape
 
 interface ServerProtocol : Protocol {
@@ -253,6 +266,165 @@ class ServerRestXmlProtocol(
     override fun serverContentTypeCheckNoModeledInput() = true
 }
 
+class ServerRpcV2CborProtocol(
+    private val serverCodegenContext: ServerCodegenContext,
+) : RpcV2Cbor(serverCodegenContext), ServerProtocol {
+    val runtimeConfig = codegenContext.runtimeConfig
+
+    override val protocolModulePath = "rpc_v2_cbor"
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        CborParserGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            returnSymbolToParseFn(serverCodegenContext),
+            listOf(
+                ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedCborParserCustomization(
+                    serverCodegenContext,
+                ),
+            ),
+        )
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        ServerRpcV2CborSerializerGenerator(serverCodegenContext, httpBindingResolver)
+
+    override fun markerStruct() = ServerRuntimeType.protocol("RpcV2Cbor", protocolModulePath, runtimeConfig)
+
+    override fun routerType() = ServerCargoDependency.smithyHttpServer(runtimeConfig).toType()
+        .resolve("protocol::rpc_v2_cbor::router::RpcV2CborRouter")
+
+    /**
+     * Returns the service and operation names, which identify the operation in the `rpcv2Cbor` request URI.
+     */
+    override fun serverRouterRequestSpec(
+        operationShape: OperationShape,
+        operationName: String,
+        serviceName: String,
+        requestSpecModule: RuntimeType,
+    ) = writable {
+        rust("""String::from("$serviceName.$operationName")""")
+    }
+
+    override fun serverRouterRequestSpecType(
+        requestSpecModule: RuntimeType,
+    ): RuntimeType = RuntimeType.String
+
+    override fun serverRouterRuntimeConstructor() = "new_rpc_v2_cbor_router"
+}
+
+private fun queryProtocolType(runtimeConfig: RuntimeConfig, name: String) =
+    ServerCargoDependency.smithyHttpServer(runtimeConfig).toType().resolve("protocol::query::$name")
+
+class ServerAwsQueryProtocol(
+    private val serverCodegenContext: ServerCodegenContext,
+) : AwsQueryProtocol(serverCodegenContext), ServerProtocol {
+    val runtimeConfig = serverCodegenContext.runtimeConfig
+
+    override val protocolModulePath = "aws_query"
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        AwsQueryInputParserGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            returnSymbolToParseFn(serverCodegenContext),
+            listOf(
+                ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedQueryParserCustomization(
+                    serverCodegenContext,
+                ),
+            ),
+        )
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        ServerAwsQuerySerializerGenerator(httpBindingResolver, serverCodegenContext)
+
+    override fun markerStruct() = ServerRuntimeType.protocol("AwsQuery", protocolModulePath, runtimeConfig)
+
+    override fun routerType() = ServerCargoDependency.smithyHttpServer(runtimeConfig).toType()
+        .resolve("protocol::aws_query::router::AwsQueryRouter")
+
+    /**
+     * Returns the operation name, which is sent as the `Action` parameter of `awsQuery` requests.
+     */
+    override fun serverRouterRequestSpec(
+        operationShape: OperationShape,
+        operationName: String,
+        serviceName: String,
+        requestSpecModule: RuntimeType,
+    ) = writable {
+        rust("""String::from("$operationName")""")
+    }
+
+    override fun serverRouterRequestSpecType(
+        requestSpecModule: RuntimeType,
+    ): RuntimeType = RuntimeType.String
+
+    override fun serverRouterRuntimeConstructor() = "new_aws_query_router"
+
+    override fun requestRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryProtocolType(runtimeConfig, "rejection::RequestRejection")
+
+    override fun responseRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryProtocolType(runtimeConfig, "rejection::ResponseRejection")
+
+    override fun runtimeError(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryProtocolType(runtimeConfig, "runtime_error::RuntimeError")
+}
+
+class ServerEc2QueryProtocol(
+    private val serverCodegenContext: ServerCodegenContext,
+) : Ec2QueryProtocol(serverCodegenContext), ServerProtocol {
+    val runtimeConfig = serverCodegenContext.runtimeConfig
+
+    override val protocolModulePath = "ec2_query"
+
+    override fun structuredDataParser(): StructuredDataParserGenerator =
+        Ec2QueryInputParserGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            returnSymbolToParseFn(serverCodegenContext),
+            listOf(
+                ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedQueryParserCustomization(
+                    serverCodegenContext,
+                ),
+            ),
+        )
+
+    override fun structuredDataSerializer(): StructuredDataSerializerGenerator =
+        ServerEc2QuerySerializerGenerator(httpBindingResolver, serverCodegenContext)
+
+    override fun markerStruct() = ServerRuntimeType.protocol("Ec2Query", protocolModulePath, runtimeConfig)
+
+    override fun routerType() = ServerCargoDependency.smithyHttpServer(runtimeConfig).toType()
+        .resolve("protocol::ec2_query::router::Ec2QueryRouter")
+
+    /**
+     * Returns the operation name, which is sent as the `Action` parameter of `ec2Query` requests.
+     */
+    override fun serverRouterRequestSpec(
+        operationShape: OperationShape,
+        operationName: String,
+        serviceName: String,
+        requestSpecModule: RuntimeType,
+    ) = writable {
+        rust("""String::from("$operationName")""")
+    }
+
+    override fun serverRouterRequestSpecType(
+        requestSpecModule: RuntimeType,
+    ): RuntimeType = RuntimeType.String
+
+    override fun serverRouterRuntimeConstructor() = "new_ec2_query_router"
+
+    override fun requestRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryProtocolType(runtimeConfig, "rejection::RequestRejection")
+
+    override fun responseRejection(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryProtocolType(runtimeConfig, "rejection::ResponseRejection")
+
+    override fun runtimeError(runtimeConfig: RuntimeConfig): RuntimeType =
+        queryProtocolType(runtimeConfig, "runtime_error::RuntimeError")
+}
+
 /**
  * A customization to, just before we box a recursive member that we've deserialized into `Option<T>`, convert it into
  * `MaybeConstrained` if the target shape can reach a constrained shape.
@@ -273,3 +445,40 @@ class ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedJsonPa
         else -> emptySection
     }
 }
+
+/**
+ * The CBOR equivalent of [ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedJsonParserCustomization].
+ */
+class ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedCborParserCustomization(val codegenContext: ServerCodegenContext) :
+    CborParserCustomization() {
+    override fun section(section: CborParserSection): Writable = when (section) {
+        is CborParserSection.BeforeBoxingDeserializedMember -> writable {
+            // We're only interested in _structure_ member shapes that can reach constrained shapes.
+            if (
+                codegenContext.model.expectShape(section.shape.container) is StructureShape &&
+                section.shape.targetCanReachConstrainedShape(codegenContext.model, codegenContext.symbolProvider)
+            ) {
+                rust(".map(|x| x.into())")
+            }
+        }
+    }
+}
+
+/**
+ * The Query equivalent of [ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedJsonParserCustomization].
+ * Query parsers box the member's value itself rather than an `Option`, so the value is converted directly.
+ */
+class ServerRequestBeforeBoxingDeserializedMemberConvertToMaybeConstrainedQueryParserCustomization(val codegenContext: ServerCodegenContext) :
+    QueryParserCustomization() {
+    override fun section(section: QueryParserSection): Writable = when (section) {
+        is QueryParserSection.BeforeBoxingDeserializedMember -> writable {
+            // We're only interested in _structure_ member shapes that can reach constrained shapes.
+            if (
+                codegenContext.model.expectShape(section.shape.container) is StructureShape &&
+                section.shape.targetCanReachConstrainedShape(codegenContext.model, codegenContext.symbolProvider)
+            ) {
+                rust(".into()")
+            }
+        }
+    }
+}
@@ -98,6 +98,7 @@ class ServerProtocolTestGenerator(
 
     private val codegenScope = arrayOf(
         "Bytes" to RuntimeType.Bytes,
+        "Base64Decode" to RuntimeType.base64Decode(codegenContext.runtimeConfig),
         "SmithyHttp" to RuntimeType.smithyHttp(codegenContext.runtimeConfig),
         "Http" to RuntimeType.Http,
         "Hyper" to RuntimeType.Hyper,
@@ -287,7 +288,7 @@ class ServerProtocolTestGenerator(
         }
 
         with(httpRequestTestCase) {
-            renderHttpRequest(uri, method, headers, body.orNull(), queryParams, host.orNull())
+            renderHttpRequest(uri, method, headers, body.orNull(), bodyMediaType.orNull(), queryParams, host.orNull())
         }
         if (protocolSupport.requestBodyDeserialization) {
             makeRequest(operationShape, operationSymbol, this, checkRequestHandler(operationShape, httpRequestTestCase))
@@ -364,7 +365,7 @@ class ServerProtocolTestGenerator(
         rustBlock("") {
             with(testCase.request) {
                 // TODO(https://github.com/awslabs/smithy/issues/1102): `uri` should probably not be an `Optional`.
-                renderHttpRequest(uri.get(), method, headers, body.orNull(), queryParams, host.orNull())
+                renderHttpRequest(uri.get(), method, headers, body.orNull(), bodyMediaType.orNull(), queryParams, host.orNull())
             }
 
             makeRequest(
@@ -382,6 +383,7 @@ class ServerProtocolTestGenerator(
         method: String,
         headers: Map<String, String>,
         body: String?,
+        bodyMediaType: String?,
         queryParams: List<String>,
         host: String?,
     ) {
@@ -400,7 +402,10 @@ class ServerProtocolTestGenerator(
         rustTemplate(
             """
             .body(${
-                if (body != null) {
+                if (body != null && bodyMediaType == "application/cbor") {
+                    // Binary protocols such as `rpcv2Cbor` encode the request body in the test case as base64.
+                    "#{SmithyHttpServer}::body::Body::from(#{Base64Decode}(${body.dq()}).expect(\"request body must be valid base64\"))"
+                } else if (body != null) {
                     // The `replace` is necessary to fix the malformed request test `RestJsonInvalidJsonBody`.
                     // https://github.com/awslabs/smithy/blob/887ae4f6d118e55937105583a07deb90d8fabe1c/smithy-aws-protocol-tests/model/restJson1/malformedRequests/malformed-request-body.smithy#L47
                     //
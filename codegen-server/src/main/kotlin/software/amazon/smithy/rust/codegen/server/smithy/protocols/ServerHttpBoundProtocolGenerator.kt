@@ -7,6 +7,9 @@ package software.amazon.smithy.rust.codegen.server.smithy.protocols
 
 import software.amazon.smithy.aws.traits.protocols.AwsJson1_0Trait
 import software.amazon.smithy.aws.traits.protocols.AwsJson1_1Trait
+import software.amazon.smithy.aws.traits.protocols.AwsQueryErrorTrait
+import software.amazon.smithy.aws.traits.protocols.AwsQueryTrait
+import software.amazon.smithy.aws.traits.protocols.Ec2QueryTrait
 import software.amazon.smithy.aws.traits.protocols.RestJson1Trait
 import software.amazon.smithy.aws.traits.protocols.RestXmlTrait
 import software.amazon.smithy.codegen.core.Symbol
@@ -58,6 +61,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpLocation
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.Protocol
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolFunctions
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.parse.StructuredDataParserGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.traits.RpcV2CborTrait
 import software.amazon.smithy.rust.codegen.core.smithy.traits.SyntheticInputTrait
 import software.amazon.smithy.rust.codegen.core.smithy.transformers.operationErrors
 import software.amazon.smithy.rust.codegen.core.smithy.wrapOptional
@@ -515,6 +519,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                     }
                     val status =
                         variantShape.getTrait<HttpErrorTrait>()?.code
+                            ?: variantShape.getTrait<AwsQueryErrorTrait>()?.httpResponseCode
                             ?: errorTrait.defaultHttpStatusCode
 
                     serverRenderContentLengthHeader()
@@ -602,7 +607,8 @@ class ServerHttpBoundProtocolTraitImplGenerator(
      * It sets three groups of headers in order. Headers from one group take precedence over headers in a later group.
      *     1. Headers bound by the `httpHeader` and `httpPrefixHeader` traits. = null
      *     2. The protocol-specific `Content-Type` header for the operation.
-     *     3. Additional protocol-specific headers for errors, if [errorShape] is non-null.
+     *     3. Additional protocol-specific headers for errors, if [errorShape] is non-null, or for successful
+     *        responses otherwise.
      */
     private fun RustWriter.serverRenderResponseHeaders(operationShape: OperationShape, errorShape: StructureShape? = null) {
         val bindingGenerator = ServerResponseBindingGenerator(protocol, codegenContext, operationShape)
@@ -636,19 +642,22 @@ class ServerHttpBoundProtocolTraitImplGenerator(
             )
         }
 
-        if (errorShape != null) {
-            for ((headerName, headerValue) in protocol.additionalErrorResponseHeaders(errorShape)) {
-                rustTemplate(
-                    """
-                    builder = #{header_util}::set_response_header_if_absent(
-                        builder,
-                        http::header::HeaderName::from_static("$headerName"),
-                        "${escape(headerValue)}"
-                    );
-                    """,
-                    *codegenScope,
-                )
-            }
+        val additionalHeaders = if (errorShape != null) {
+            protocol.additionalErrorResponseHeaders(errorShape)
+        } else {
+            protocol.additionalResponseHeaders(operationShape)
+        }
+        for ((headerName, headerValue) in additionalHeaders) {
+            rustTemplate(
+                """
+                builder = #{header_util}::set_response_header_if_absent(
+                    builder,
+                    http::header::HeaderName::from_static("$headerName"),
+                    "${escape(headerValue)}"
+                );
+                """,
+                *codegenScope,
+            )
         }
     }
 
@@ -1261,6 +1270,12 @@ class ServerHttpBoundProtocolTraitImplGenerator(
             RestXmlTrait.ID -> {
                 RuntimeType.smithyXml(runtimeConfig).resolve("decode::XmlDecodeError").toSymbol()
             }
+            RpcV2CborTrait.ID -> {
+                RuntimeType.smithyCbor(runtimeConfig).resolve("DeserializeError").toSymbol()
+            }
+            AwsQueryTrait.ID, Ec2QueryTrait.ID -> {
+                RuntimeType.smithyQuery(runtimeConfig).resolve("deserialize::DeserializeError").toSymbol()
+            }
             else -> {
                 TODO("Protocol ${codegenContext.protocol} not supported yet")
             }
@@ -0,0 +1,210 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.protocols
+
+import software.amazon.smithy.model.traits.ErrorTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Attribute
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.smithy.generators.protocol.ProtocolSupport
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolGeneratorFactory
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.ResponseWrapperContext
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.StructuredDataSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.XmlBindingTraitSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.expectTrait
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerAwsQueryProtocol
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerEc2QueryProtocol
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerProtocol
+
+private fun serverQueryProtocolSupport() = ProtocolSupport(
+    /* Client support */
+    requestSerialization = false,
+    requestBodySerialization = false,
+    responseDeserialization = false,
+    errorDeserialization = false,
+    /* Server support */
+    requestDeserialization = true,
+    requestBodyDeserialization = true,
+    responseSerialization = true,
+    errorSerialization = true,
+)
+
+/**
+ * AWS Query server-side protocol factory. This factory creates the [ServerHttpBoundProtocolGenerator]
+ * with AWS Query specific configurations.
+ */
+class ServerAwsQueryFactory(
+    private val additionalServerHttpBoundProtocolCustomizations: List<ServerHttpBoundProtocolCustomization> = listOf(),
+) : ProtocolGeneratorFactory<ServerHttpBoundProtocolGenerator, ServerCodegenContext> {
+    override fun protocol(codegenContext: ServerCodegenContext): ServerProtocol = ServerAwsQueryProtocol(codegenContext)
+
+    override fun buildProtocolGenerator(codegenContext: ServerCodegenContext): ServerHttpBoundProtocolGenerator =
+        ServerHttpBoundProtocolGenerator(
+            codegenContext,
+            protocol(codegenContext),
+            additionalServerHttpBoundProtocolCustomizations,
+        )
+
+    override fun support(): ProtocolSupport = serverQueryProtocolSupport()
+}
+
+/**
+ * EC2 Query server-side protocol factory. This factory creates the [ServerHttpBoundProtocolGenerator]
+ * with EC2 Query specific configurations.
+ */
+class ServerEc2QueryFactory(
+    private val additionalServerHttpBoundProtocolCustomizations: List<ServerHttpBoundProtocolCustomization> = listOf(),
+) : ProtocolGeneratorFactory<ServerHttpBoundProtocolGenerator, ServerCodegenContext> {
+    override fun protocol(codegenContext: ServerCodegenContext): ServerProtocol = ServerEc2QueryProtocol(codegenContext)
+
+    override fun buildProtocolGenerator(codegenContext: ServerCodegenContext): ServerHttpBoundProtocolGenerator =
+        ServerHttpBoundProtocolGenerator(
+            codegenContext,
+            protocol(codegenContext),
+            additionalServerHttpBoundProtocolCustomizations,
+        )
+
+    override fun support(): ProtocolSupport = serverQueryProtocolSupport()
+}
+
+/** Writes the `Code` element of an error, as named by the protocol's [HttpBindingResolver.errorCode]. */
+private fun RustWriter.writeErrorCode(
+    httpBindingResolver: HttpBindingResolver,
+    context: ResponseWrapperContext,
+    scopeWriter: String,
+) {
+    val code = httpBindingResolver.errorCode(context.shape)
+    rust("$scopeWriter.start_el(\"Code\").finish().data(${code.dq()});")
+}
+
+/**
+ * AWS Query responses are identical to REST XML's, except that they are wrapped in protocol-specific elements:
+ *
+ * ```
+ * <SomeOperationResponse xmlns="...">
+ *     <SomeOperationResult>
+ *         <ActualData /> <!-- This part is the same as REST XML -->
+ *     </SomeOperationResult>
+ * </SomeOperationResponse>
+ *
+ * <ErrorResponse xmlns="...">
+ *     <Error>
+ *         <Type>Sender</Type>
+ *         <Code>SomeError</Code>
+ *         <ActualData /> <!-- This part is the same as REST XML -->
+ *     </Error>
+ * </ErrorResponse>
+ * ```
+ *
+ * This class wraps [XmlBindingTraitSerializerGenerator] and uses it to render the response bodies, providing it
+ * with the wrapping elements.
+ */
+class ServerAwsQuerySerializerGenerator(
+    httpBindingResolver: HttpBindingResolver,
+    serverCodegenContext: ServerCodegenContext,
+    private val xmlBindingTraitSerializerGenerator: XmlBindingTraitSerializerGenerator =
+        XmlBindingTraitSerializerGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            writeOperationOutputWrapper = { context, inner ->
+                val operationName = context.shape.id.name
+                rust(
+                    """
+                    let mut response = ${context.xmlWriter}.start_el("${operationName}Response")${context.xmlNamespace}.finish();
+                    """,
+                )
+                Attribute.AllowUnusedMut.render(this)
+                rust("""let mut result = response.start_el("${operationName}Result").finish();""")
+                inner("result")
+                rust(
+                    """
+                    result.finish();
+                    response.finish();
+                    """,
+                )
+            },
+            writeErrorWrapper = { context, inner ->
+                val errorType = when (context.shape.expectTrait<ErrorTrait>().isClientError) {
+                    true -> "Sender"
+                    false -> "Receiver"
+                }
+                rust(
+                    """
+                    let mut response = ${context.xmlWriter}.start_el("ErrorResponse")${context.xmlNamespace}.finish();
+                    let mut error = response.start_el("Error").finish();
+                    error.start_el("Type").finish().data("$errorType");
+                    """,
+                )
+                writeErrorCode(httpBindingResolver, context, "error")
+                inner("error")
+                rust(
+                    """
+                    error.finish();
+                    response.finish();
+                    """,
+                )
+            },
+        ),
+) : StructuredDataSerializerGenerator by xmlBindingTraitSerializerGenerator
+
+/**
+ * EC2 Query responses are identical to REST XML's, except that they are wrapped in protocol-specific elements:
+ *
+ * ```
+ * <SomeOperationResponse xmlns="...">
+ *     <ActualData /> <!-- This part is the same as REST XML -->
+ * </SomeOperationResponse>
+ *
+ * <Response>
+ *     <Errors>
+ *         <Error>
+ *             <Code>SomeError</Code>
+ *             <ActualData /> <!-- This part is the same as REST XML -->
+ *         </Error>
+ *     </Errors>
+ * </Response>
+ * ```
+ *
+ * This class wraps [XmlBindingTraitSerializerGenerator] and uses it to render the response bodies, providing it
+ * with the wrapping elements.
+ */
+class ServerEc2QuerySerializerGenerator(
+    httpBindingResolver: HttpBindingResolver,
+    serverCodegenContext: ServerCodegenContext,
+    private val xmlBindingTraitSerializerGenerator: XmlBindingTraitSerializerGenerator =
+        XmlBindingTraitSerializerGenerator(
+            serverCodegenContext,
+            httpBindingResolver,
+            writeOperationOutputWrapper = { context, inner ->
+                val operationName = context.shape.id.name
+                Attribute.AllowUnusedMut.render(this)
+                rust("""let mut response = ${context.xmlWriter}.start_el("${operationName}Response")${context.xmlNamespace}.finish();""")
+                inner("response")
+                rust("response.finish();")
+            },
+            writeErrorWrapper = { context, inner ->
+                rust(
+                    """
+                    let mut response = ${context.xmlWriter}.start_el("Response").finish();
+                    let mut errors = response.start_el("Errors").finish();
+                    let mut error = errors.start_el("Error").finish();
+                    """,
+                )
+                writeErrorCode(httpBindingResolver, context, "error")
+                inner("error")
+                rust(
+                    """
+                    error.finish();
+                    errors.finish();
+                    response.finish();
+                    """,
+                )
+            },
+        ),
+) : StructuredDataSerializerGenerator by xmlBindingTraitSerializerGenerator
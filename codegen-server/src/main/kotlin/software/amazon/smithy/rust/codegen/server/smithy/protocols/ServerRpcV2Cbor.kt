@@ -0,0 +1,94 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.protocols
+
+import software.amazon.smithy.model.traits.ErrorTrait
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.escape
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.generators.http.HttpBindingCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.generators.protocol.ProtocolSupport
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.ProtocolGeneratorFactory
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.CborSerializerCustomization
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.CborSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.CborSerializerSection
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.StructuredDataSerializerGenerator
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+import software.amazon.smithy.rust.codegen.server.smithy.customizations.BeforeIteratingOverMapOrCollectionCborCustomization
+import software.amazon.smithy.rust.codegen.server.smithy.customizations.BeforeSerializingMemberCborCustomization
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerProtocol
+import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.ServerRpcV2CborProtocol
+
+/**
+ * RPC v2 CBOR server-side protocol factory. This factory creates the [ServerHttpBoundProtocolGenerator]
+ * with RPC v2 CBOR specific configurations.
+ */
+class ServerRpcV2CborFactory(
+    private val additionalServerHttpBoundProtocolCustomizations: List<ServerHttpBoundProtocolCustomization> = listOf(),
+    private val additionalHttpBindingCustomizations: List<HttpBindingCustomization> = listOf(),
+) : ProtocolGeneratorFactory<ServerHttpBoundProtocolGenerator, ServerCodegenContext> {
+    override fun protocol(codegenContext: ServerCodegenContext): ServerProtocol = ServerRpcV2CborProtocol(codegenContext)
+
+    override fun buildProtocolGenerator(codegenContext: ServerCodegenContext): ServerHttpBoundProtocolGenerator =
+        ServerHttpBoundProtocolGenerator(
+            codegenContext,
+            protocol(codegenContext),
+            additionalServerHttpBoundProtocolCustomizations,
+            additionalHttpBindingCustomizations,
+        )
+
+    override fun support(): ProtocolSupport {
+        return ProtocolSupport(
+            /* Client support */
+            requestSerialization = false,
+            requestBodySerialization = false,
+            responseDeserialization = false,
+            errorDeserialization = false,
+            /* Server support */
+            requestDeserialization = true,
+            requestBodyDeserialization = true,
+            responseSerialization = true,
+            errorSerialization = true,
+        )
+    }
+}
+
+/**
+ * RPC v2 CBOR requires errors to be serialized in server responses with an additional `__type` field containing the
+ * full shape ID of the error.
+ *
+ * https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html#operation-error-serialization
+ */
+class ServerRpcV2CborError : CborSerializerCustomization() {
+    override fun section(section: CborSerializerSection): Writable = when (section) {
+        is CborSerializerSection.ServerError -> writable {
+            if (section.structureShape.hasTrait<ErrorTrait>()) {
+                val typeId = section.structureShape.id.toString()
+                rust("""${section.encoderBindingName}.str("__type").str("${escape(typeId)}");""")
+            }
+        }
+
+        else -> emptySection
+    }
+}
+
+class ServerRpcV2CborSerializerGenerator(
+    private val codegenContext: ServerCodegenContext,
+    private val httpBindingResolver: HttpBindingResolver,
+    private val cborSerializerGenerator: CborSerializerGenerator =
+        CborSerializerGenerator(
+            codegenContext,
+            httpBindingResolver,
+            customizations = listOf(
+                ServerRpcV2CborError(),
+                BeforeIteratingOverMapOrCollectionCborCustomization(codegenContext),
+                BeforeSerializingMemberCborCustomization(codegenContext),
+            ),
+        ),
+) : StructuredDataSerializerGenerator by cborSerializerGenerator
@@ -0,0 +1,201 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.protocols
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class ServerQueryTest {
+    private fun model(protocol: String, errorTraits: String = "") = """
+        namespace com.example
+
+        use aws.protocols#$protocol
+        use aws.protocols#awsQueryError
+
+        @$protocol
+        @xmlNamespace(uri: "https://example.com/")
+        service SampleService {
+            version: "2024-01-01",
+            operations: [Greet]
+        }
+
+        operation Greet {
+            input: GreetInput,
+            output: GreetOutput,
+            errors: [NotFound]
+        }
+
+        structure GreetInput {
+            name: String
+        }
+
+        structure GreetOutput {
+            greeting: String
+        }
+
+        @error("client")
+        $errorTraits
+        structure NotFound {
+            message: String
+        }
+    """.asSmithyModel(smithyVersion = "2")
+
+    private fun codegenScope(codegenContext: ServerCodegenContext) = arrayOf(
+        "Hyper" to ServerCargoDependency.HyperDev.toType(),
+        "Tower" to ServerCargoDependency.Tower.toType(),
+        "SmithyHttpServer" to ServerCargoDependency.smithyHttpServer(codegenContext.runtimeConfig).toType(),
+    )
+
+    /** Renders a `call` function that sends a form-encoded `body` to a service that greets whoever is named */
+    private fun RustWriter.renderCall(codegenContext: ServerCodegenContext) {
+        rustTemplate(
+            """
+            async fn call(method: &str, body: &'static str) -> #{Hyper}::Response<#{SmithyHttpServer}::body::BoxBody> {
+                let config = crate::SampleServiceConfig::builder().build();
+                let service = crate::SampleService::builder::<#{Hyper}::body::Body, _, _, _>(config)
+                    .greet(|input: crate::input::GreetInput| async move {
+                        match input.name() {
+                            Some("nobody") => Err(crate::error::GreetError::NotFound(
+                                crate::error::NotFound::builder()
+                                    .message(Some("nobody is here".to_owned()))
+                                    .build(),
+                            )),
+                            name => Ok(crate::output::GreetOutput::builder()
+                                .greeting(Some(format!("hello {}", name.unwrap_or_default())))
+                                .build()),
+                        }
+                    })
+                    .build_unchecked();
+                let request = #{Hyper}::Request::builder()
+                    .method(method)
+                    .uri("/")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(#{Hyper}::Body::from(body))
+                    .unwrap();
+                #{Tower}::ServiceExt::oneshot(service, request).await.unwrap()
+            }
+
+            async fn body_string(response: #{Hyper}::Response<#{SmithyHttpServer}::body::BoxBody>) -> String {
+                let bytes = #{Hyper}::body::to_bytes(response.into_body()).await.unwrap();
+                String::from_utf8(bytes.to_vec()).unwrap()
+            }
+            """,
+            *codegenScope(codegenContext),
+        )
+    }
+
+    @Test
+    fun `generated service routes and serializes AWS Query requests`() {
+        serverIntegrationTest(
+            model("awsQuery", """@awsQueryError(code: "NobodyHere", httpResponseCode: 404)"""),
+        ) { codegenContext, rustCrate ->
+            rustCrate.testModule {
+                addDependency(ServerCargoDependency.TokioDev)
+                renderCall(codegenContext)
+
+                tokioTest("successful_response_is_wrapped_in_a_result_element") {
+                    rustTemplate(
+                        """
+                        let response = call("POST", "Action=Greet&Version=2024-01-01&name=world").await;
+                        assert_eq!(response.status(), 200);
+                        assert_eq!(response.headers().get("content-type").unwrap(), "text/xml");
+                        assert_eq!(
+                            body_string(response).await,
+                            "<GreetResponse xmlns=\"https://example.com/\"><GreetResult><greeting>hello world</greeting></GreetResult></GreetResponse>"
+                        );
+                        """,
+                    )
+                }
+
+                tokioTest("modeled_errors_use_their_aws_query_error_code") {
+                    rustTemplate(
+                        """
+                        let response = call("POST", "Action=Greet&Version=2024-01-01&name=nobody").await;
+                        assert_eq!(response.status(), 404);
+                        let body = body_string(response).await;
+                        assert!(
+                            body.starts_with("<ErrorResponse xmlns=\"https://example.com/\"><Error><Type>Sender</Type><Code>NobodyHere</Code>"),
+                            "{}",
+                            body
+                        );
+                        assert!(body.contains("nobody is here"), "{}", body);
+                        """,
+                    )
+                }
+
+                tokioTest("unknown_actions_are_not_found") {
+                    rustTemplate(
+                        """
+                        let response = call("POST", "Action=Farewell&Version=2024-01-01").await;
+                        assert_eq!(response.status(), 404);
+                        let response = call("POST", "Version=2024-01-01&name=world").await;
+                        assert_eq!(response.status(), 404);
+                        let response = call("GET", "Action=Greet&Version=2024-01-01").await;
+                        assert_eq!(response.status(), 405);
+                        """,
+                    )
+                }
+            }
+        }
+    }
+
+    @Test
+    fun `generated service routes and serializes EC2 Query requests`() {
+        serverIntegrationTest(model("ec2Query")) { codegenContext, rustCrate ->
+            rustCrate.testModule {
+                addDependency(ServerCargoDependency.TokioDev)
+                renderCall(codegenContext)
+
+                tokioTest("successful_response_is_not_wrapped_in_a_result_element") {
+                    rustTemplate(
+                        """
+                        // EC2 Query capitalizes parameter names
+                        let response = call("POST", "Action=Greet&Version=2024-01-01&Name=world").await;
+                        assert_eq!(response.status(), 200);
+                        assert_eq!(response.headers().get("content-type").unwrap(), "text/xml");
+                        assert_eq!(
+                            body_string(response).await,
+                            "<GreetResponse xmlns=\"https://example.com/\"><greeting>hello world</greeting></GreetResponse>"
+                        );
+                        """,
+                    )
+                }
+
+                tokioTest("modeled_errors_are_wrapped_in_an_errors_element") {
+                    rustTemplate(
+                        """
+                        let response = call("POST", "Action=Greet&Version=2024-01-01&Name=nobody").await;
+                        assert_eq!(response.status(), 400);
+                        let body = body_string(response).await;
+                        assert!(
+                            body.starts_with("<Response><Errors><Error><Code>NotFound</Code>"),
+                            "{}",
+                            body
+                        );
+                        assert!(body.contains("nobody is here"), "{}", body);
+                        """,
+                    )
+                }
+
+                tokioTest("unknown_actions_are_not_found") {
+                    rustTemplate(
+                        """
+                        let response = call("POST", "Action=Farewell&Version=2024-01-01").await;
+                        assert_eq!(response.status(), 404);
+                        """,
+                    )
+                }
+            }
+        }
+    }
+}
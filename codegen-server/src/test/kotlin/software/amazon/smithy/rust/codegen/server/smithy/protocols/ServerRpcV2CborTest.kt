@@ -0,0 +1,142 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.protocols
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class ServerRpcV2CborTest {
+    private val model = """
+        namespace com.example
+
+        use smithy.protocols#rpcv2Cbor
+
+        @rpcv2Cbor
+        service SampleService {
+            operations: [Greet]
+        }
+
+        operation Greet {
+            input: GreetInput,
+            output: GreetOutput,
+            errors: [NotFound]
+        }
+
+        structure GreetInput {
+            name: String
+        }
+
+        structure GreetOutput {
+            greeting: String
+        }
+
+        @error("client")
+        structure NotFound {
+            message: String
+        }
+    """.asSmithyModel(smithyVersion = "2")
+
+    @Test
+    fun `generated service routes and serializes RPC v2 CBOR requests`() {
+        serverIntegrationTest(model) { codegenContext, rustCrate ->
+            val codegenScope = arrayOf(
+                "Hyper" to ServerCargoDependency.HyperDev.toType(),
+                "Tower" to ServerCargoDependency.Tower.toType(),
+                "SmithyHttpServer" to ServerCargoDependency.smithyHttpServer(codegenContext.runtimeConfig).toType(),
+            )
+            rustCrate.testModule {
+                addDependency(ServerCargoDependency.TokioDev)
+                rustTemplate(
+                    """
+                    async fn call(uri: &str, body: &'static [u8]) -> #{Hyper}::Response<#{SmithyHttpServer}::body::BoxBody> {
+                        let config = crate::SampleServiceConfig::builder().build();
+                        let service = crate::SampleService::builder::<#{Hyper}::body::Body, _, _, _>(config)
+                            .greet(|input: crate::input::GreetInput| async move {
+                                match input.name() {
+                                    Some("nobody") => Err(crate::error::GreetError::NotFound(
+                                        crate::error::NotFound::builder()
+                                            .message(Some("nobody is here".to_owned()))
+                                            .build(),
+                                    )),
+                                    name => Ok(crate::output::GreetOutput::builder()
+                                        .greeting(Some(format!("hello {}", name.unwrap_or_default())))
+                                        .build()),
+                                }
+                            })
+                            .build_unchecked();
+                        let request = #{Hyper}::Request::builder()
+                            .method("POST")
+                            .uri(uri)
+                            .header("smithy-protocol", "rpc-v2-cbor")
+                            .header("content-type", "application/cbor")
+                            .body(#{Hyper}::Body::from(body))
+                            .unwrap();
+                        #{Tower}::ServiceExt::oneshot(service, request).await.unwrap()
+                    }
+
+                    async fn body_bytes(response: #{Hyper}::Response<#{SmithyHttpServer}::body::BoxBody>) -> Vec<u8> {
+                        #{Hyper}::body::to_bytes(response.into_body()).await.unwrap().to_vec()
+                    }
+                    """,
+                    *codegenScope,
+                )
+
+                tokioTest("successful_response_is_cbor") {
+                    rustTemplate(
+                        """
+                        // {"name": "world"}
+                        let response = call("/service/SampleService/operation/Greet", b"\xa1\x64name\x65world").await;
+                        assert_eq!(response.status(), 200);
+                        assert_eq!(response.headers().get("smithy-protocol").unwrap(), "rpc-v2-cbor");
+                        assert_eq!(response.headers().get("content-type").unwrap(), "application/cbor");
+                        assert_eq!(
+                            body_bytes(response).await,
+                            b"\xbf\x68greeting\x6bhello world\xff"
+                        );
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("modeled_errors_include_their_shape_id") {
+                    rustTemplate(
+                        """
+                        // {"name": "nobody"}
+                        let response = call("/service/SampleService/operation/Greet", b"\xa1\x64name\x66nobody").await;
+                        assert_eq!(response.status(), 400);
+                        assert_eq!(response.headers().get("smithy-protocol").unwrap(), "rpc-v2-cbor");
+                        let body = body_bytes(response).await;
+                        let type_entry = b"\x66__type\x74com.example##NotFound";
+                        assert!(
+                            body.windows(type_entry.len()).any(|window| window == type_entry),
+                            "missing `__type` in {:?}",
+                            body
+                        );
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                tokioTest("unknown_operations_are_not_found") {
+                    rustTemplate(
+                        """
+                        let response = call("/service/SampleService/operation/Farewell", b"\xa0").await;
+                        assert_eq!(response.status(), 404);
+                        let response = call("/service/OtherService/operation/Greet", b"\xa0").await;
+                        assert_eq!(response.status(), 404);
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
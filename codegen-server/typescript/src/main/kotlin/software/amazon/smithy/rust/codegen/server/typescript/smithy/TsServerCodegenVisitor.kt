@@ -207,7 +207,7 @@ class TsServerCodegenVisitor(
         }
 
         rustCrate.withModule(ServerRustModule.Error) {
-            TsServerOperationErrorGenerator(codegenContext.model, codegenContext.symbolProvider, shape).render(this)
+            TsServerOperationErrorGenerator(codegenContext.model, codegenContext.symbolProvider, shape, codegenContext.runtimeConfig).render(this)
         }
     }
 }
@@ -43,28 +43,16 @@ class TsApplicationGenerator(
     private val codegenScope =
         arrayOf(
             "SmithyServer" to ServerCargoDependency.smithyHttpServer(runtimeConfig).toType(),
+            "SmithyTs" to TsServerCargoDependency.smithyHttpServerTs(runtimeConfig).toType(),
             "napi" to TsServerCargoDependency.Napi.toType(),
             "napi_derive" to TsServerCargoDependency.NapiDerive.toType(),
-            "tokio" to TsServerCargoDependency.Tokio.toType(),
-            "tracing" to TsServerCargoDependency.Tracing.toType(),
             "tower" to TsServerCargoDependency.Tower.toType(),
-            "tower_http" to TsServerCargoDependency.TowerHttp.toType(),
-            "num_cpus" to TsServerCargoDependency.NumCpus.toType(),
-            "hyper" to TsServerCargoDependency.Hyper.toType(),
-            "HashMap" to RuntimeType.HashMap,
-            "parking_lot" to TsServerCargoDependency.ParkingLot.toType(),
-            "http" to RuntimeType.Http,
-            "socket2" to TsServerCargoDependency.Socket2.toType(),
         )
 
     fun render(writer: RustWriter) {
         writer.write("use napi_derive::napi;")
         renderHandlers(writer)
         renderApp(writer)
-
-        // TODO(https://github.com/napi-rs/napi-rs/issues/1377) Move these to be part of the runtime crate.
-        renderSocket(writer)
-        renderServer(writer)
     }
 
     fun renderHandlers(writer: RustWriter) {
@@ -73,13 +61,12 @@ class TsApplicationGenerator(
             operations.map { operation ->
                 val operationName = symbolProvider.toSymbol(operation).name
                 val input = "crate::input::${operationName}Input"
+                val output = "crate::output::${operationName}Output"
+                val error = "crate::error::${operationName}Error"
                 val fnName = operationName.toSnakeCase()
                 rustTemplate(
                     """
-                    pub(crate) $fnName: #{napi}::threadsafe_function::ThreadsafeFunction<
-                        $input,
-                        #{napi}::threadsafe_function::ErrorStrategy::Fatal
-                    >,
+                    pub(crate) $fnName: #{SmithyTs}::TsHandler<$input, $output, $error>,
                     """,
                     *codegenScope,
                 )
@@ -94,7 +81,7 @@ class TsApplicationGenerator(
                 val fnName = operationName.toSnakeCase()
                 rustTemplate(
                     """
-                    ##[napi(ts_type = "(input: $input) => Promise<$output>")]
+                    ##[napi(ts_type = "(input: $input, context: any) => Promise<$output> | $output")]
                     pub $fnName: #{napi}::JsFunction,
                     """,
                     *codegenScope,
@@ -105,57 +92,85 @@ class TsApplicationGenerator(
 
     private fun renderApp(writer: RustWriter) {
         Attribute("napi").render(writer)
-        writer.rust(
+        writer.rustTemplate(
             """
             pub struct App {
                 handlers: Handlers,
+                server: Option<#{SmithyTs}::TsServer>,
             }
             """,
+            *codegenScope,
         )
         Attribute("napi").render(writer)
         writer.rustBlock("impl App") {
             renderAppCreate(writer)
             renderAppStart(writer)
+            renderAppShutdown(writer)
         }
     }
 
     private fun renderAppCreate(writer: RustWriter) {
+        writer.rust(
+            """
+            /// Create a new application from the handlers of the operations and a `context`
+            /// object, that is passed to every handler.
+            """.trimIndent(),
+        )
         Attribute("napi(constructor)").render(writer)
         writer.rustBlockTemplate(
-            """pub fn create(ts_handlers: TsHandlers) -> #{napi}::Result<Self>""",
+            """
+            pub fn create(
+                env: #{napi}::Env,
+                ts_handlers: TsHandlers,
+                ##[napi(ts_arg_type = "any")] context: Option<#{napi}::JsUnknown>,
+            ) -> #{napi}::Result<Self>
+            """,
             *codegenScope,
         ) {
+            rustTemplate(
+                """
+                let context = context
+                    .map(|context| #{SmithyTs}::TsContext::new(&env, context))
+                    .transpose()?;
+                """,
+                *codegenScope,
+            )
+            rust("let handlers = Handlers {")
             operations.map { operation ->
-                val operationName = symbolProvider.toSymbol(operation).name
-                val input = "crate::input::${operationName}Input"
-                val fnName = operationName.toSnakeCase()
+                val operationName = symbolProvider.toSymbol(operation).name.toSnakeCase()
                 rustTemplate(
-                    """
-                    let $fnName: #{napi}::threadsafe_function::ThreadsafeFunction<
-                        $input, #{napi}::threadsafe_function::ErrorStrategy::Fatal
-                    > = ts_handlers.$fnName.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
-                    """,
+                    "    $operationName: #{SmithyTs}::TsHandler::new(&env, ts_handlers.$operationName, context.clone())?,",
                     *codegenScope,
                 )
             }
-            rust("let handlers = Handlers {")
-            operations.map { operation ->
-                val operationName = symbolProvider.toSymbol(operation).name.toSnakeCase()
-                rust("    $operationName: $operationName.clone(),")
-            }
             rust("};")
-            writer.rust("Ok(Self{ handlers })")
+            writer.rust("Ok(Self { handlers, server: None })")
         }
     }
 
     private fun renderAppStart(writer: RustWriter) {
+        writer.rust(
+            """
+            /// Start serving requests from `socket`, using the number of workers and TLS
+            /// configuration in `options`.
+            """.trimIndent(),
+        )
         Attribute("napi").render(writer)
         writer.rustBlockTemplate(
-            """pub fn start(&self, socket: &TsSocket) -> #{napi}::Result<()>""",
+            """
+            pub fn start(
+                &mut self,
+                socket: &#{SmithyTs}::TsSocket,
+                options: Option<#{SmithyTs}::TsServerOptions>,
+            ) -> #{napi}::Result<()>
+            """,
             *codegenScope,
         ) {
             rustTemplate(
                 """
+                if self.server.is_some() {
+                    return Err(#{napi}::Error::from_reason("the application is already started"));
+                }
                 let plugins = #{SmithyServer}::plugin::PluginPipeline::new();
                 let builder = crate::service::$serviceName::builder_with_plugins(plugins);
                 """,
@@ -170,7 +185,8 @@ class TsApplicationGenerator(
                 let app = builder.build().expect("failed to build instance of $serviceName")
                     .layer(&#{SmithyServer}::AddExtensionLayer::new(self.handlers.clone()));
                 let service = #{tower}::util::BoxCloneService::new(app);
-                start_hyper_worker(socket, service).expect("failed to start the hyper server");
+                let server = #{SmithyTs}::TsServer::start(socket, service, options.unwrap_or_default())?;
+                self.server = Some(server);
                 Ok(())
                 """,
                 *codegenScope,
@@ -178,136 +194,24 @@ class TsApplicationGenerator(
         }
     }
 
-    private fun renderSocket(writer: RustWriter) {
-        Attribute("napi").render(writer)
-        Attribute(derive(RuntimeType.Debug)).render(writer)
-        writer.rustTemplate(
+    private fun renderAppShutdown(writer: RustWriter) {
+        writer.rust(
             """
-            pub struct TsSocket(#{socket2}::Socket);
-            """,
-            *codegenScope,
+            /// Stop accepting new connections and wait for the in-flight requests to complete.
+            """.trimIndent(),
         )
-
         Attribute("napi").render(writer)
-        writer.rustBlock("impl TsSocket") {
-            writer.rust(
-                """
-                /// Create a new UNIX `Socket` from an address, port and backlog.
-                /// If not specified, the backlog defaults to 1024 connections.
-                """.trimIndent(),
-            )
-            Attribute("napi(constructor)").render(writer)
-            writer.rustBlockTemplate(
-                """pub fn new(address: String, port: i32, backlog: Option<i32>) -> #{napi}::Result<Self>""".trimIndent(),
-                *codegenScope,
-            ) {
-                writer.rustTemplate(
-                    """
-                    let socket = Self::new_socket(address, port, backlog)
-                        .map_err(|e| #{napi}::Error::from_reason(e.to_string()))?;
-                    Ok(Self(socket))
-                    """,
-                    *codegenScope,
-                )
-            }
-            writer.rustBlockTemplate(
-                """pub fn new_socket(address: String, port: i32, backlog: Option<i32>) -> Result<#{socket2}::Socket, Box<dyn std::error::Error>> """.trimIndent(),
-                *codegenScope,
-            ) {
-                writer.rustTemplate(
-                    """
-                    let address: std::net::SocketAddr = format!("{}:{}", address, port).parse()?;
-                    let domain = if address.is_ipv6() {
-                        #{socket2}::Domain::IPV6
-                    } else {
-                        #{socket2}::Domain::IPV4
-                    };
-                    let socket = #{socket2}::Socket::new(domain, #{socket2}::Type::STREAM, Some(#{socket2}::Protocol::TCP))?;
-                    // Set value for the `SO_REUSEPORT` and `SO_REUSEADDR` options on this socket.
-                    // This indicates that further calls to `bind` may allow reuse of local
-                    // addresses. For IPv4 sockets this means that a socket may bind even when
-                    // there's a socket already listening on this port.
-                    socket.set_reuse_port(true)?;
-                    socket.set_reuse_address(true)?;
-                    socket.bind(&address.into())?;
-                    socket.listen(backlog.unwrap_or(1024))?;
-                    Ok(socket)
-                    """.trimIndent(),
-                    *codegenScope,
-                )
-            }
-
-            writer.rust(
-                """
-                /// Clone the inner socket allowing it to be shared between multiple
-                /// Nodejs processes.
-                """.trimIndent(),
-            )
-            Attribute("napi").render(writer)
-            writer.rustBlockTemplate(
-                """pub fn try_clone(&self) -> #{napi}::Result<Self>""".trimIndent(),
-                *codegenScope,
-            ) {
-                writer.rustTemplate(
-                    """
-                    Ok(TsSocket(
-                        self.0
-                            .try_clone()
-                            .map_err(|e| #{napi}::Error::from_reason(e.to_string()))?,
-                    ))
-                    """.trimIndent(),
-                    *codegenScope,
-                )
-            }
-        }
-
-        writer.rustBlock("impl TsSocket") {
-            writer.rustTemplate(
-                """pub fn to_raw_socket(&self) -> #{napi}::Result<#{socket2}::Socket> {
-                self.0
-                 .try_clone()
-                 .map_err(|e| #{napi}::Error::from_reason(e.to_string()))
-                }
-
-                """.trimIndent(),
-                *codegenScope,
-            )
-        }
-    }
-
-    private fun renderServer(writer: RustWriter) {
         writer.rustBlockTemplate(
-            """pub fn start_hyper_worker(
-            socket: &TsSocket,
-            app: #{tower}::util::BoxCloneService<
-                #{http}::Request<#{SmithyServer}::body::Body>,
-                #{http}::Response<#{SmithyServer}::body::BoxBody>,
-                std::convert::Infallible,
-            >,
-                ) -> #{napi}::Result<()>
-            """.trimIndent(),
+            """pub async fn shutdown(&self) -> #{napi}::Result<()>""",
             *codegenScope,
         ) {
-            writer.rustTemplate(
+            rust(
                 """
-                let server = #{hyper}::Server::from_tcp(
-                        socket
-                            .to_raw_socket()?
-                            .try_into()
-                            .expect("Unable to convert socket2::Socket into std::net::TcpListener"),
-                )
-                .expect("Unable to create hyper server from shared socket")
-                .serve(#{SmithyServer}::routing::IntoMakeService::new(app));
-
-                let handle = #{tokio}::runtime::Handle::current();
-                handle.spawn(async move {
-                    // Process each socket concurrently.
-                    server.await
-                });
-
+                if let Some(server) = &self.server {
+                    server.shutdown().await;
+                }
                 Ok(())
-                """.trimIndent(),
-                *codegenScope,
+                """,
             )
         }
     }
@@ -9,7 +9,10 @@ import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.knowledge.OperationIndex
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
 import software.amazon.smithy.rust.codegen.server.typescript.smithy.TsServerCargoDependency
@@ -21,6 +24,7 @@ class TsServerOperationErrorGenerator(
     private val model: Model,
     private val symbolProvider: RustSymbolProvider,
     private val operation: OperationShape,
+    private val runtimeConfig: RuntimeConfig,
 ) {
     private val operationIndex = OperationIndex.of(model)
     private val errors = operationIndex.getErrors(operation)
@@ -29,7 +33,6 @@ class TsServerOperationErrorGenerator(
         renderFromTsErr(writer)
     }
 
-    // TODO(https://github.com/awslabs/smithy-rs/issues/2317): match the Ts error type and return the right one.
     private fun renderFromTsErr(writer: RustWriter) {
         writer.rustTemplate(
             """
@@ -39,10 +42,36 @@ class TsServerOperationErrorGenerator(
                 }
             }
 
+            impl #{SmithyTs}::FromTsError for #{Error} {
+                fn from_ts_error(env: &#{napi}::Env, error: #{napi}::JsUnknown) -> #{Error} {
+                    #{CastTsErrToRustError:W}
+                    crate::error::InternalServerError { message: #{SmithyTs}::handler::error_message(&error) }.into()
+                }
+            }
+
             """,
             "napi" to TsServerCargoDependency.Napi.toType(),
+            "SmithyTs" to TsServerCargoDependency.smithyHttpServerTs(runtimeConfig).toType(),
             "Error" to symbolProvider.symbolForOperationError(operation),
             "From" to RuntimeType.From,
+            "CastTsErrToRustError" to castTsErrToRustError(),
         )
     }
+
+    private fun castTsErrToRustError(): Writable =
+        writable {
+            errors.forEach { error ->
+                val errorSymbol = symbolProvider.toSymbol(error)
+                if (errorSymbol.toString() != "crate::error::InternalServerError") {
+                    rustTemplate(
+                        """
+                        if let Some(error) = #{SmithyTs}::handler::downcast::<$errorSymbol>(env, &error) {
+                            return error.into()
+                        }
+                        """,
+                        "SmithyTs" to TsServerCargoDependency.smithyHttpServerTs(runtimeConfig).toType(),
+                    )
+                }
+            }
+        }
 }
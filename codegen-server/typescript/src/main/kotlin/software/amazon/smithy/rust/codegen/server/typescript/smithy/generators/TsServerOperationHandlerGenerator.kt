@@ -28,7 +28,6 @@ class TsServerOperationHandlerGenerator(
         arrayOf(
             "SmithyTs" to TsServerCargoDependency.smithyHttpServerTs(runtimeConfig).toType(),
             "SmithyServer" to TsServerCargoDependency.smithyHttpServer(runtimeConfig).toType(),
-            "napi" to TsServerCargoDependency.Napi.toType(),
         )
 
     fun render(writer: RustWriter) {
@@ -49,7 +48,7 @@ class TsServerOperationHandlerGenerator(
                 input: $input,
                 handlers: #{SmithyServer}::Extension<crate::ts_server_application::Handlers>,
             ) -> std::result::Result<$output, $error> {
-                handlers.$fnName.call_async::<#{napi}::bindgen_prelude::Promise<$output>>(input).await?.await.map_err(|e| e.into())
+                handlers.$fnName.call(input).await
             }
             """,
             *codegenScope,
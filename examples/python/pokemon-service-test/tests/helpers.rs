@@ -5,8 +5,8 @@
 
 use std::fs::File;
 use std::io::BufReader;
-use std::process::Command;
-use std::time::Duration;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
 use aws_smithy_runtime::client::http::hyper_014::HyperClientBuilder;
 use command_group::{CommandGroup, GroupChild};
@@ -76,13 +76,54 @@ impl PokemonService {
             child_process: PokemonServiceVariant::Http2.run_process().await,
         }
     }
+
+    /// Sends `signal` (e.g. `HUP`) to the main process of the service.
+    #[allow(dead_code)]
+    pub(crate) fn signal(&self, signal: &str) {
+        let status = Command::new("kill")
+            .args(["-s", signal, &self.child_process.id().to_string()])
+            .status()
+            .expect("failed to run `kill`");
+        assert!(
+            status.success(),
+            "failed to send SIG{signal} to the Pokémon Service program"
+        );
+    }
+
+    /// Waits until every process of the service, including its workers, has exited.
+    /// Returns `false` if some are still running after `timeout`.
+    #[allow(dead_code)]
+    pub(crate) async fn wait_for_exit(&mut self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            // Reap the main process once it has exited, so that it doesn't count as running.
+            let _ = self.child_process.inner().try_wait();
+            if !self.is_running() {
+                return true;
+            }
+            time::sleep(Duration::from_millis(100)).await;
+        }
+        false
+    }
+
+    /// Returns whether any process of the service is still running.
+    fn is_running(&self) -> bool {
+        Command::new("kill")
+            .args(["-0", "--", &format!("-{}", self.child_process.id())])
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
 }
 
 impl Drop for PokemonService {
     fn drop(&mut self) {
-        self.child_process
-            .kill()
-            .expect("failed to kill Pokémon Service program");
+        if self.is_running() {
+            self.child_process
+                .kill()
+                .expect("failed to kill Pokémon Service program");
+        }
         self.child_process.wait().ok();
     }
 }
@@ -0,0 +1,67 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::time::Duration;
+
+use async_stream::stream;
+use serial_test::serial;
+use tokio::sync::oneshot;
+use tokio::time;
+
+use crate::helpers::{client, PokemonService};
+use pokemon_service_client::types::{
+    AttemptCapturingPokemonEvent, CapturingEvent, CapturingPayload,
+};
+
+mod helpers;
+
+#[tokio::test]
+#[serial]
+async fn rolling_restart_keeps_serving_and_terminates_draining_workers() {
+    let mut program = PokemonService::run().await;
+    let client = client();
+    client.check_health().send().await.unwrap();
+
+    // Keep an event stream open, so that the worker it's served by keeps draining after it's replaced.
+    let (close_stream, closed) = oneshot::channel::<()>();
+    let input_stream = stream! {
+        let _ = closed.await;
+        yield Ok(AttemptCapturingPokemonEvent::Event(
+            CapturingEvent::builder()
+                .payload(CapturingPayload::builder().name("Pikachu").pokeball("Master Ball").build())
+                .build()
+        ));
+    };
+    let _in_flight = client
+        .capture_pokemon()
+        .region("Kanto")
+        .events(input_stream.into())
+        .send()
+        .await
+        .unwrap();
+
+    // Requests keep being served while the workers are replaced.
+    program.signal("HUP");
+    for _ in 0..30 {
+        client.check_health().send().await.unwrap();
+        time::sleep(Duration::from_millis(100)).await;
+    }
+    let pokemon_species_output = client
+        .get_pokemon_species()
+        .name("pikachu")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!("pikachu", pokemon_species_output.name());
+
+    // The worker serving the open event stream is still draining, and must be terminated along with
+    // the new workers.
+    program.signal("TERM");
+    assert!(
+        program.wait_for_exit(Duration::from_secs(10)).await,
+        "workers are still running after the Pokémon Service program was terminated"
+    );
+    drop(close_stream);
+}
@@ -453,9 +453,10 @@ def main() -> None:
     parser.add_argument("--enable-tls", action="store_true")
     parser.add_argument("--tls-key-path")
     parser.add_argument("--tls-cert-path")
+    parser.add_argument("--watch", action="store_true")
     args = parser.parse_args()
 
-    config: Dict[str, Any] = dict(workers=1)
+    config: Dict[str, Any] = dict(workers=1, watch=args.watch)
     if args.enable_tls:
         config["tls"] = TlsConfig(
             key_path=args.tls_key_path,
@@ -14,7 +14,8 @@
 //! Future utilities and runtime-agnostic abstractions for smithy-rs.
 //!
 //! Async runtime specific code is abstracted behind async traits, and implementations are
-//! provided via feature flag. For now, only Tokio runtime implementations are provided.
+//! provided via feature flag. Implementations are provided for Tokio (`rt-tokio`), and for
+//! runtimes built on `async-io` such as `smol` and `async-std` (`rt-async-io`).
 
 pub mod future;
 pub mod rt;
@@ -70,11 +70,21 @@ impl AsyncSleep for SharedAsyncSleep {
 
 #[cfg(feature = "rt-tokio")]
 /// Returns a default sleep implementation based on the features enabled
+///
+/// Tokio is preferred when both the `rt-tokio` and `rt-async-io` features are enabled.
 pub fn default_async_sleep() -> Option<SharedAsyncSleep> {
     Some(SharedAsyncSleep::from(sleep_tokio()))
 }
 
-#[cfg(not(feature = "rt-tokio"))]
+#[cfg(all(feature = "rt-async-io", not(feature = "rt-tokio")))]
+/// Returns a default sleep implementation based on the features enabled
+///
+/// Tokio is preferred when both the `rt-tokio` and `rt-async-io` features are enabled.
+pub fn default_async_sleep() -> Option<SharedAsyncSleep> {
+    Some(SharedAsyncSleep::new(AsyncIoSleep::new()))
+}
+
+#[cfg(not(any(feature = "rt-tokio", feature = "rt-async-io")))]
 /// Returns a default sleep implementation based on the features enabled
 pub fn default_async_sleep() -> Option<SharedAsyncSleep> {
     None
@@ -133,3 +143,43 @@ impl AsyncSleep for TokioSleep {
 fn sleep_tokio() -> Arc<dyn AsyncSleep> {
     Arc::new(TokioSleep::new())
 }
+
+/// Implementation of [`AsyncSleep`] for runtimes built on [`async-io`](https://docs.rs/async-io),
+/// such as `smol` and `async-std`.
+///
+/// The timers are driven by the `async-io` reactor, so this doesn't require a Tokio runtime.
+#[non_exhaustive]
+#[cfg(feature = "rt-async-io")]
+#[derive(Debug, Default)]
+pub struct AsyncIoSleep;
+
+#[cfg(feature = "rt-async-io")]
+impl AsyncIoSleep {
+    /// Create a new [`AsyncSleep`] implementation using `async-io` timers
+    pub fn new() -> AsyncIoSleep {
+        Default::default()
+    }
+}
+
+#[cfg(feature = "rt-async-io")]
+impl AsyncSleep for AsyncIoSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep::new(async move {
+            async_io::Timer::after(duration).await;
+        })
+    }
+}
+
+#[cfg(all(test, feature = "rt-async-io"))]
+mod tests {
+    use super::{AsyncIoSleep, AsyncSleep};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    #[allow(clippy::disallowed_methods)]
+    fn async_io_sleep_without_tokio() {
+        let start = Instant::now();
+        async_io::block_on(AsyncIoSleep::new().sleep(Duration::from_millis(50)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
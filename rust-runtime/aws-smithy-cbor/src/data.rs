@@ -0,0 +1,89 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR data model types.
+
+/// The type of the next data item in a CBOR input.
+///
+/// Returned by [`Decoder::datatype`](crate::decode::Decoder::datatype) so that callers can decide
+/// how to decode an item before consuming it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Type {
+    /// Unsigned integer (major type 0)
+    UnsignedInt,
+    /// Negative integer (major type 1)
+    NegativeInt,
+    /// Definite-length byte string (major type 2)
+    Bytes,
+    /// Indefinite-length byte string (major type 2)
+    BytesIndef,
+    /// Definite-length text string (major type 3)
+    String,
+    /// Indefinite-length text string (major type 3)
+    StringIndef,
+    /// Definite-length array (major type 4)
+    Array,
+    /// Indefinite-length array (major type 4)
+    ArrayIndef,
+    /// Definite-length map (major type 5)
+    Map,
+    /// Indefinite-length map (major type 5)
+    MapIndef,
+    /// Tagged data item (major type 6)
+    Tag,
+    /// `true` or `false`
+    Bool,
+    /// `null`
+    Null,
+    /// `undefined`
+    Undefined,
+    /// Simple value other than `true`, `false`, `null` or `undefined`
+    Simple,
+    /// IEEE 754 half-precision float
+    F16,
+    /// IEEE 754 single-precision float
+    F32,
+    /// IEEE 754 double-precision float
+    F64,
+    /// The "break" stop code that terminates an indefinite-length item
+    Break,
+}
+
+impl Type {
+    /// Returns true if this type is a floating point number of any width.
+    pub fn is_float(self) -> bool {
+        matches!(self, Type::F16 | Type::F32 | Type::F64)
+    }
+
+    /// Returns true if this type is an integer of either sign.
+    pub fn is_integer(self) -> bool {
+        matches!(self, Type::UnsignedInt | Type::NegativeInt)
+    }
+}
+
+/// Tag number for an epoch-based date/time (RFC 8949 §3.4.2)
+pub const TAG_EPOCH_DATE_TIME: u64 = 1;
+
+pub(crate) const MAJOR_UNSIGNED: u8 = 0;
+pub(crate) const MAJOR_NEGATIVE: u8 = 1;
+pub(crate) const MAJOR_BYTES: u8 = 2;
+pub(crate) const MAJOR_TEXT: u8 = 3;
+pub(crate) const MAJOR_ARRAY: u8 = 4;
+pub(crate) const MAJOR_MAP: u8 = 5;
+pub(crate) const MAJOR_TAG: u8 = 6;
+pub(crate) const MAJOR_SIMPLE: u8 = 7;
+
+/// Additional information value that marks an indefinite-length item
+pub(crate) const INDEFINITE: u8 = 31;
+
+pub(crate) const FALSE: u8 = 0xf4;
+pub(crate) const TRUE: u8 = 0xf5;
+pub(crate) const NULL: u8 = 0xf6;
+pub(crate) const UNDEFINED: u8 = 0xf7;
+pub(crate) const F16: u8 = 0xf9;
+pub(crate) const F32: u8 = 0xfa;
+pub(crate) const F64: u8 = 0xfb;
+pub(crate) const BREAK: u8 = 0xff;
@@ -0,0 +1,959 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR decoding.
+
+use crate::data::{
+    Type, BREAK, F16, F32, F64, FALSE, INDEFINITE, MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP,
+    MAJOR_NEGATIVE, MAJOR_SIMPLE, MAJOR_TAG, MAJOR_TEXT, MAJOR_UNSIGNED, NULL, TAG_EPOCH_DATE_TIME,
+    TRUE, UNDEFINED,
+};
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+
+#[derive(Debug)]
+enum DeserializeErrorKind {
+    Custom {
+        message: Cow<'static, str>,
+        source: Option<Box<dyn StdError + Send + Sync + 'static>>,
+    },
+    UnexpectedEof,
+    UnexpectedType {
+        expected: &'static str,
+        found: Type,
+    },
+    InvalidHead(u8),
+    InvalidUtf8,
+    InvalidIndefiniteChunk,
+    OutOfRange(&'static str),
+    UnexpectedTag(u64),
+}
+
+/// An error that occurred while decoding CBOR.
+#[derive(Debug)]
+pub struct DeserializeError {
+    kind: DeserializeErrorKind,
+    offset: Option<usize>,
+}
+
+impl DeserializeError {
+    fn new(kind: DeserializeErrorKind, offset: usize) -> Self {
+        Self {
+            kind,
+            offset: Some(offset),
+        }
+    }
+
+    /// Returns a custom error without an offset.
+    pub fn custom(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: DeserializeErrorKind::Custom {
+                message: message.into(),
+                source: None,
+            },
+            offset: None,
+        }
+    }
+
+    /// Returns a custom error with an error source without an offset.
+    pub fn custom_source(
+        message: impl Into<Cow<'static, str>>,
+        source: impl Into<Box<dyn StdError + Send + Sync + 'static>>,
+    ) -> Self {
+        Self {
+            kind: DeserializeErrorKind::Custom {
+                message: message.into(),
+                source: Some(source.into()),
+            },
+            offset: None,
+        }
+    }
+
+    /// Adds an offset to the error.
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl StdError for DeserializeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            DeserializeErrorKind::Custom {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DeserializeErrorKind::*;
+        if let Some(offset) = self.offset {
+            write!(f, "Error at offset {}: ", offset)?;
+        }
+        match &self.kind {
+            Custom { message, .. } => write!(f, "failed to parse CBOR: {message}"),
+            UnexpectedEof => write!(f, "unexpected end of input"),
+            UnexpectedType { expected, found } => {
+                write!(f, "expected {expected}, but found {found:?}")
+            }
+            InvalidHead(head) => write!(f, "invalid data item head: 0x{head:02x}"),
+            InvalidUtf8 => write!(f, "invalid UTF-8 in text string"),
+            InvalidIndefiniteChunk => write!(
+                f,
+                "indefinite-length strings may only contain definite-length strings of the same type"
+            ),
+            OutOfRange(ty) => write!(f, "value does not fit in {ty}"),
+            UnexpectedTag(tag) => write!(f, "unexpected tag {tag}"),
+        }
+    }
+}
+
+const MAX_DOCUMENT_RECURSION: usize = 256;
+
+/// Reads CBOR data items from a byte slice.
+///
+/// Text and byte strings are borrowed from the input when they are encoded with a definite
+/// length, and concatenated into an owned value when they are encoded in indefinite-length
+/// chunks. All numeric accessors accept any encoding width, but fail if the decoded value
+/// doesn't fit in the requested type.
+///
+/// ```rust
+/// use aws_smithy_cbor::Decoder;
+///
+/// let mut decoder = Decoder::new(b"\xbf\x64name\x67example\x65count\x02\xff");
+/// let (name, count) = decoder
+///     .map_entries((None, None), |(name, count), decoder| {
+///         Ok(match decoder.str()?.as_ref() {
+///             "name" => (Some(decoder.string()?), count),
+///             "count" => (name, Some(decoder.integer()?)),
+///             _ => {
+///                 decoder.skip()?;
+///                 (name, count)
+///             }
+///         })
+///     })
+///     .unwrap();
+/// assert_eq!(Some("example".to_string()), name);
+/// assert_eq!(Some(2), count);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Decoder<'b> {
+    input: &'b [u8],
+    position: usize,
+}
+
+impl<'b> Decoder<'b> {
+    /// Creates a decoder that reads from the start of `input`.
+    pub fn new(input: &'b [u8]) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Returns the offset of the next data item in the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns true if the entire input has been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    fn error(&self, kind: DeserializeErrorKind) -> DeserializeError {
+        DeserializeError::new(kind, self.position)
+    }
+
+    fn unexpected(&self, expected: &'static str) -> DeserializeError {
+        match self.datatype() {
+            Ok(found) => self.error(DeserializeErrorKind::UnexpectedType { expected, found }),
+            Err(err) => err,
+        }
+    }
+
+    fn peek(&self) -> Result<u8, DeserializeError> {
+        self.input
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| self.error(DeserializeErrorKind::UnexpectedEof))
+    }
+
+    fn read(&mut self, len: u64) -> Result<&'b [u8], DeserializeError> {
+        let input: &'b [u8] = self.input;
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| self.position.checked_add(len))
+            .filter(|end| *end <= input.len())
+            .ok_or_else(|| self.error(DeserializeErrorKind::UnexpectedEof))?;
+        let bytes = &input[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DeserializeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read(N as u64)?);
+        Ok(array)
+    }
+
+    /// Reads the head of a data item, returning its major type and argument. The argument is
+    /// `None` for indefinite-length items.
+    fn head(&mut self) -> Result<(u8, Option<u64>), DeserializeError> {
+        let start = self.position;
+        let initial = self.peek()?;
+        self.position += 1;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let argument = match info {
+            0..=23 => Some(info.into()),
+            24 => Some(u8::from_be_bytes(self.read_array()?).into()),
+            25 => Some(u16::from_be_bytes(self.read_array()?).into()),
+            26 => Some(u32::from_be_bytes(self.read_array()?).into()),
+            27 => Some(u64::from_be_bytes(self.read_array()?)),
+            INDEFINITE
+                if matches!(
+                    major,
+                    MAJOR_BYTES | MAJOR_TEXT | MAJOR_ARRAY | MAJOR_MAP | MAJOR_SIMPLE
+                ) =>
+            {
+                None
+            }
+            _ => {
+                return Err(DeserializeError::new(
+                    DeserializeErrorKind::InvalidHead(initial),
+                    start,
+                ))
+            }
+        };
+        Ok((major, argument))
+    }
+
+    /// Returns the type of the next data item without consuming it.
+    pub fn datatype(&self) -> Result<Type, DeserializeError> {
+        let initial = self.peek()?;
+        let indefinite = initial & 0x1f == INDEFINITE;
+        Ok(match (initial >> 5, indefinite) {
+            (MAJOR_UNSIGNED, _) => Type::UnsignedInt,
+            (MAJOR_NEGATIVE, _) => Type::NegativeInt,
+            (MAJOR_BYTES, false) => Type::Bytes,
+            (MAJOR_BYTES, true) => Type::BytesIndef,
+            (MAJOR_TEXT, false) => Type::String,
+            (MAJOR_TEXT, true) => Type::StringIndef,
+            (MAJOR_ARRAY, false) => Type::Array,
+            (MAJOR_ARRAY, true) => Type::ArrayIndef,
+            (MAJOR_MAP, false) => Type::Map,
+            (MAJOR_MAP, true) => Type::MapIndef,
+            (MAJOR_TAG, _) => Type::Tag,
+            _ => match initial {
+                FALSE | TRUE => Type::Bool,
+                NULL => Type::Null,
+                UNDEFINED => Type::Undefined,
+                F16 => Type::F16,
+                F32 => Type::F32,
+                F64 => Type::F64,
+                BREAK => Type::Break,
+                _ => Type::Simple,
+            },
+        })
+    }
+
+    /// Returns true if the next data item is `null`.
+    pub fn is_null(&self) -> Result<bool, DeserializeError> {
+        Ok(self.datatype()? == Type::Null)
+    }
+
+    /// Reads the head of a map, returning its number of entries, or `None` if it has an
+    /// indefinite length.
+    pub fn map(&mut self) -> Result<Option<u64>, DeserializeError> {
+        match self.datatype()? {
+            Type::Map | Type::MapIndef => Ok(self.head()?.1),
+            _ => Err(self.unexpected("map")),
+        }
+    }
+
+    /// Reads the head of an array, returning its number of items, or `None` if it has an
+    /// indefinite length.
+    pub fn array(&mut self) -> Result<Option<u64>, DeserializeError> {
+        match self.datatype()? {
+            Type::Array | Type::ArrayIndef => Ok(self.head()?.1),
+            _ => Err(self.unexpected("array")),
+        }
+    }
+
+    /// Calls `f` once per item of a container with `len` items, or until the break stop code if
+    /// `len` is `None`.
+    fn items<T>(
+        &mut self,
+        len: Option<u64>,
+        mut state: T,
+        mut f: impl FnMut(T, &mut Self) -> Result<T, DeserializeError>,
+    ) -> Result<T, DeserializeError> {
+        match len {
+            Some(len) => {
+                for _ in 0..len {
+                    state = f(state, self)?;
+                }
+            }
+            None => {
+                while self.datatype()? != Type::Break {
+                    state = f(state, self)?;
+                }
+                self.position += 1;
+            }
+        }
+        Ok(state)
+    }
+
+    /// Decodes a map of definite or indefinite length.
+    ///
+    /// `f` is called once per entry with the accumulated state, and must consume both the key and
+    /// the value of the entry.
+    pub fn map_entries<T>(
+        &mut self,
+        init: T,
+        f: impl FnMut(T, &mut Self) -> Result<T, DeserializeError>,
+    ) -> Result<T, DeserializeError> {
+        let len = self.map()?;
+        self.items(len, init, f)
+    }
+
+    /// Decodes an array of definite or indefinite length.
+    ///
+    /// `f` is called once per item with the accumulated state, and must consume the item.
+    pub fn array_items<T>(
+        &mut self,
+        init: T,
+        f: impl FnMut(T, &mut Self) -> Result<T, DeserializeError>,
+    ) -> Result<T, DeserializeError> {
+        let len = self.array()?;
+        self.items(len, init, f)
+    }
+
+    /// Reads a byte or text string, concatenating the chunks of indefinite-length strings.
+    fn string_bytes(&mut self, major: u8) -> Result<Cow<'b, [u8]>, DeserializeError> {
+        match self.head()? {
+            (_, Some(len)) => Ok(Cow::Borrowed(self.read(len)?)),
+            (_, None) => {
+                let mut value = Vec::new();
+                while self.peek()? != BREAK {
+                    let start = self.position;
+                    match self.head()? {
+                        (chunk_major, Some(len)) if chunk_major == major => {
+                            value.extend_from_slice(self.read(len)?)
+                        }
+                        _ => {
+                            return Err(DeserializeError::new(
+                                DeserializeErrorKind::InvalidIndefiniteChunk,
+                                start,
+                            ))
+                        }
+                    }
+                }
+                self.position += 1;
+                Ok(Cow::Owned(value))
+            }
+        }
+    }
+
+    /// Reads a text string.
+    pub fn str(&mut self) -> Result<Cow<'b, str>, DeserializeError> {
+        if !matches!(self.datatype()?, Type::String | Type::StringIndef) {
+            return Err(self.unexpected("text string"));
+        }
+        let start = self.position;
+        let invalid_utf8 = || DeserializeError::new(DeserializeErrorKind::InvalidUtf8, start);
+        match self.string_bytes(MAJOR_TEXT)? {
+            Cow::Borrowed(bytes) => std::str::from_utf8(bytes)
+                .map(Cow::Borrowed)
+                .map_err(|_| invalid_utf8()),
+            Cow::Owned(bytes) => String::from_utf8(bytes)
+                .map(Cow::Owned)
+                .map_err(|_| invalid_utf8()),
+        }
+    }
+
+    /// Reads a text string into an owned `String`.
+    pub fn string(&mut self) -> Result<String, DeserializeError> {
+        self.str().map(Cow::into_owned)
+    }
+
+    /// Reads a byte string.
+    pub fn bytes(&mut self) -> Result<Cow<'b, [u8]>, DeserializeError> {
+        if !matches!(self.datatype()?, Type::Bytes | Type::BytesIndef) {
+            return Err(self.unexpected("byte string"));
+        }
+        self.string_bytes(MAJOR_BYTES)
+    }
+
+    /// Reads a byte string into a `Blob`.
+    pub fn blob(&mut self) -> Result<Blob, DeserializeError> {
+        self.bytes().map(|bytes| Blob::new(bytes.into_owned()))
+    }
+
+    /// Reads a boolean.
+    pub fn boolean(&mut self) -> Result<bool, DeserializeError> {
+        match self.peek()? {
+            FALSE => {
+                self.position += 1;
+                Ok(false)
+            }
+            TRUE => {
+                self.position += 1;
+                Ok(true)
+            }
+            _ => Err(self.unexpected("boolean")),
+        }
+    }
+
+    /// Reads `null`.
+    pub fn null(&mut self) -> Result<(), DeserializeError> {
+        match self.peek()? {
+            NULL => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.unexpected("null")),
+        }
+    }
+
+    /// Reads `null` and returns `None`, or decodes the next data item with `f`.
+    pub fn nullable<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, DeserializeError>,
+    ) -> Result<Option<T>, DeserializeError> {
+        if self.is_null()? {
+            self.null()?;
+            Ok(None)
+        } else {
+            f(self).map(Some)
+        }
+    }
+
+    /// Reads an integer of either sign.
+    fn int<T: TryFrom<i128>>(&mut self, ty: &'static str) -> Result<T, DeserializeError> {
+        let start = self.position;
+        let value = match self.datatype()? {
+            Type::UnsignedInt => i128::from(self.head()?.1.unwrap_or_default()),
+            Type::NegativeInt => -1 - i128::from(self.head()?.1.unwrap_or_default()),
+            _ => return Err(self.unexpected(ty)),
+        };
+        T::try_from(value)
+            .map_err(|_| DeserializeError::new(DeserializeErrorKind::OutOfRange(ty), start))
+    }
+
+    /// Reads a Smithy `byte`.
+    pub fn byte(&mut self) -> Result<i8, DeserializeError> {
+        self.int("byte")
+    }
+
+    /// Reads a Smithy `short`.
+    pub fn short(&mut self) -> Result<i16, DeserializeError> {
+        self.int("short")
+    }
+
+    /// Reads a Smithy `integer`.
+    pub fn integer(&mut self) -> Result<i32, DeserializeError> {
+        self.int("integer")
+    }
+
+    /// Reads a Smithy `long`.
+    pub fn long(&mut self) -> Result<i64, DeserializeError> {
+        self.int("long")
+    }
+
+    /// Reads an unsigned integer.
+    pub fn unsigned(&mut self) -> Result<u64, DeserializeError> {
+        match self.datatype()? {
+            Type::UnsignedInt => Ok(self.head()?.1.unwrap_or_default()),
+            _ => Err(self.unexpected("unsigned integer")),
+        }
+    }
+
+    /// Reads a floating point number of any width.
+    fn any_float(&mut self, ty: &'static str) -> Result<f64, DeserializeError> {
+        let value = match self.datatype()? {
+            Type::F16 => {
+                self.position += 1;
+                f16_to_f64(u16::from_be_bytes(self.read_array()?))
+            }
+            Type::F32 => {
+                self.position += 1;
+                f32::from_be_bytes(self.read_array()?).into()
+            }
+            Type::F64 => {
+                self.position += 1;
+                f64::from_be_bytes(self.read_array()?)
+            }
+            _ => return Err(self.unexpected(ty)),
+        };
+        Ok(value)
+    }
+
+    /// Reads a Smithy `float`.
+    ///
+    /// Double-precision values are narrowed, which may lose precision.
+    pub fn float(&mut self) -> Result<f32, DeserializeError> {
+        self.any_float("float").map(|value| value as f32)
+    }
+
+    /// Reads a Smithy `double`.
+    pub fn double(&mut self) -> Result<f64, DeserializeError> {
+        self.any_float("double")
+    }
+
+    /// Reads a tag, returning its number.
+    pub fn tag(&mut self) -> Result<u64, DeserializeError> {
+        match self.datatype()? {
+            Type::Tag => Ok(self.head()?.1.unwrap_or_default()),
+            _ => Err(self.unexpected("tag")),
+        }
+    }
+
+    /// Reads a timestamp encoded as tag `1` wrapping an integer or floating point number of
+    /// seconds since the Unix epoch.
+    pub fn timestamp(&mut self) -> Result<DateTime, DeserializeError> {
+        let start = self.position;
+        let tag = self.tag()?;
+        if tag != TAG_EPOCH_DATE_TIME {
+            return Err(DeserializeError::new(
+                DeserializeErrorKind::UnexpectedTag(tag),
+                start,
+            ));
+        }
+        let value_start = self.position;
+        match self.datatype()? {
+            Type::UnsignedInt | Type::NegativeInt => Ok(DateTime::from_secs(self.long()?)),
+            ty if ty.is_float() => {
+                let seconds = self.double()?;
+                // `DateTime` can only represent finite values within the range of an `i64`
+                if seconds.is_finite() && seconds.abs() < i64::MAX as f64 {
+                    Ok(DateTime::from_secs_f64(seconds))
+                } else {
+                    Err(DeserializeError::new(
+                        DeserializeErrorKind::OutOfRange("timestamp"),
+                        value_start,
+                    ))
+                }
+            }
+            _ => Err(self.unexpected("integer or floating point seconds")),
+        }
+    }
+
+    /// Reads a document.
+    ///
+    /// Tags are ignored, and `undefined` is treated as `null`. Byte strings and simple values
+    /// other than booleans and `null` can't be represented in a document, and are rejected.
+    pub fn document(&mut self) -> Result<Document, DeserializeError> {
+        self.document_inner(0)
+    }
+
+    fn document_inner(&mut self, depth: usize) -> Result<Document, DeserializeError> {
+        let start = self.position;
+        if depth >= MAX_DOCUMENT_RECURSION {
+            return Err(DeserializeError::custom(
+                "exceeded max recursion depth while parsing document",
+            )
+            .with_offset(start));
+        }
+        Ok(match self.datatype()? {
+            Type::UnsignedInt => Document::Number(Number::PosInt(self.unsigned()?)),
+            Type::NegativeInt => Document::Number(Number::NegInt(self.long().map_err(|_| {
+                DeserializeError::new(DeserializeErrorKind::OutOfRange("document number"), start)
+            })?)),
+            Type::F16 | Type::F32 | Type::F64 => Document::Number(Number::Float(self.double()?)),
+            Type::String | Type::StringIndef => Document::String(self.string()?),
+            Type::Bool => Document::Bool(self.boolean()?),
+            Type::Null | Type::Undefined => {
+                self.position += 1;
+                Document::Null
+            }
+            Type::Array | Type::ArrayIndef => {
+                Document::Array(self.array_items(Vec::new(), |mut values, decoder| {
+                    values.push(decoder.document_inner(depth + 1)?);
+                    Ok(values)
+                })?)
+            }
+            Type::Map | Type::MapIndef => {
+                Document::Object(self.map_entries(HashMap::new(), |mut values, decoder| {
+                    let key = decoder.string()?;
+                    values.insert(key, decoder.document_inner(depth + 1)?);
+                    Ok(values)
+                })?)
+            }
+            Type::Tag => {
+                self.tag()?;
+                self.document_inner(depth + 1)?
+            }
+            _ => return Err(self.unexpected("document")),
+        })
+    }
+
+    /// Skips over the next data item, including all of its nested items.
+    pub fn skip(&mut self) -> Result<(), DeserializeError> {
+        // The number of items left in each open container, or `None` for indefinite-length ones
+        let mut remaining: Vec<Option<u64>> = Vec::new();
+        loop {
+            let start = self.position;
+            let mut complete = true;
+            match self.datatype()? {
+                Type::Break => match remaining.pop() {
+                    Some(None) => self.position += 1,
+                    _ => return Err(self.unexpected("data item")),
+                },
+                Type::Bytes | Type::BytesIndef => {
+                    self.string_bytes(MAJOR_BYTES)?;
+                }
+                Type::String | Type::StringIndef => {
+                    self.string_bytes(MAJOR_TEXT)?;
+                }
+                Type::Array | Type::ArrayIndef | Type::Map | Type::MapIndef => {
+                    let (major, len) = self.head()?;
+                    let len = match (major, len) {
+                        (MAJOR_MAP, Some(len)) => Some(len.checked_mul(2).ok_or_else(|| {
+                            DeserializeError::new(DeserializeErrorKind::UnexpectedEof, start)
+                        })?),
+                        (_, len) => len,
+                    };
+                    if len != Some(0) {
+                        remaining.push(len);
+                        complete = false;
+                    }
+                }
+                Type::Tag => {
+                    self.head()?;
+                    // The tagged item follows
+                    complete = false;
+                }
+                Type::F16 => self.position += 3,
+                Type::F32 => self.position += 5,
+                Type::F64 => self.position += 9,
+                Type::UnsignedInt | Type::NegativeInt | Type::Simple => {
+                    self.head()?;
+                }
+                _ => self.position += 1,
+            }
+            if self.position > self.input.len() {
+                return Err(DeserializeError::new(
+                    DeserializeErrorKind::UnexpectedEof,
+                    start,
+                ));
+            }
+            if complete {
+                // Count the item against its container, and close every container that is now full
+                while let Some(Some(left)) = remaining.last_mut() {
+                    *left -= 1;
+                    if *left > 0 {
+                        break;
+                    }
+                    remaining.pop();
+                }
+            }
+            if remaining.is_empty() && complete {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Converts the bits of an IEEE 754 half-precision float to an `f64`.
+fn f16_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f64::from(bits & 0x3ff);
+    match exponent {
+        0 => sign * mantissa * 2f64.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decoder;
+    use crate::data::Type;
+    use crate::Encoder;
+    use aws_smithy_types::{Blob, DateTime, Document, Number};
+    use proptest::prelude::*;
+
+    #[test]
+    fn integers() {
+        assert_eq!(0, Decoder::new(&[0x00]).byte().unwrap());
+        assert_eq!(100, Decoder::new(&[0x18, 0x64]).byte().unwrap());
+        assert_eq!(1000, Decoder::new(&[0x19, 0x03, 0xe8]).short().unwrap());
+        assert_eq!(-1000, Decoder::new(&[0x39, 0x03, 0xe7]).integer().unwrap());
+        assert_eq!(
+            i64::MIN,
+            Decoder::new(&[0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+                .long()
+                .unwrap()
+        );
+        // Values must fit in the requested type
+        let err = Decoder::new(&[0x19, 0x03, 0xe8]).byte().unwrap_err();
+        assert_eq!(
+            "Error at offset 0: value does not fit in byte",
+            format!("{err}")
+        );
+        assert!(
+            Decoder::new(&[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+                .long()
+                .is_err()
+        );
+        // Reserved additional information values
+        assert!(Decoder::new(&[0x1c]).long().is_err());
+        assert!(Decoder::new(&[0x1f]).long().is_err());
+    }
+
+    #[test]
+    fn floats_of_any_width() {
+        // Examples from RFC 8949 Appendix A
+        assert_eq!(1.5, Decoder::new(&[0xf9, 0x3e, 0x00]).double().unwrap());
+        assert_eq!(65504.0, Decoder::new(&[0xf9, 0x7b, 0xff]).double().unwrap());
+        assert_eq!(
+            5.960464477539063e-8,
+            Decoder::new(&[0xf9, 0x00, 0x01]).double().unwrap()
+        );
+        assert_eq!(-4.0, Decoder::new(&[0xf9, 0xc4, 0x00]).float().unwrap());
+        assert_eq!(
+            f64::NEG_INFINITY,
+            Decoder::new(&[0xf9, 0xfc, 0x00]).double().unwrap()
+        );
+        assert!(Decoder::new(&[0xf9, 0x7e, 0x00]).double().unwrap().is_nan());
+        assert_eq!(
+            100000.0,
+            Decoder::new(&[0xfa, 0x47, 0xc3, 0x50, 0x00])
+                .double()
+                .unwrap()
+        );
+        assert_eq!(
+            1.1,
+            Decoder::new(&[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a])
+                .double()
+                .unwrap()
+        );
+        assert!(Decoder::new(&[0x01]).double().is_err());
+    }
+
+    #[test]
+    fn strings() {
+        let mut decoder = Decoder::new(b"\x64IETF\x7f\x65strea\x64ming\xff");
+        assert_eq!("IETF", decoder.str().unwrap());
+        assert_eq!("streaming", decoder.str().unwrap());
+        assert!(decoder.is_empty());
+
+        let mut decoder = Decoder::new(b"\x5f\x42\x01\x02\x43\x03\x04\x05\xff\x40");
+        assert_eq!(Blob::new(vec![1, 2, 3, 4, 5]), decoder.blob().unwrap());
+        assert_eq!(Blob::new(vec![]), decoder.blob().unwrap());
+
+        // Chunks of an indefinite-length string must have the same major type
+        assert!(Decoder::new(b"\x7f\x41a\xff").str().is_err());
+        assert!(Decoder::new(b"\x62\xc3\x28").str().is_err());
+        assert!(Decoder::new(b"\x65abc").str().is_err());
+        assert!(Decoder::new(b"\x7f\x61a").str().is_err());
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(
+            DateTime::from_secs(1363896240),
+            Decoder::new(&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0])
+                .timestamp()
+                .unwrap()
+        );
+        assert_eq!(
+            DateTime::from_fractional_secs(1363896240, 0.5),
+            Decoder::new(&[0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20, 0x00, 0x00])
+                .timestamp()
+                .unwrap()
+        );
+        // Tag 0 is a date/time string, which isn't used by Smithy
+        assert!(Decoder::new(b"\xc0\x74\x32\x30\x31\x33\x2d\x30\x33\x2d\x32\x31\x54\x32\x30\x3a\x30\x34\x3a\x30\x30\x5a")
+            .timestamp()
+            .is_err());
+        assert!(Decoder::new(&[0xc1, 0xf9, 0x7c, 0x00]).timestamp().is_err());
+    }
+
+    #[test]
+    fn containers_of_either_length() {
+        for input in [
+            &b"\xa2\x61a\x01\x61b\x82\x02\x03"[..],
+            &b"\xbf\x61a\x01\x61b\x9f\x02\x03\xff\xff"[..],
+            &b"\xbf\x61a\x01\x7f\x61b\xff\x82\x02\x03\xff"[..],
+        ] {
+            let mut decoder = Decoder::new(input);
+            let entries = decoder
+                .map_entries(Vec::new(), |mut entries, decoder| {
+                    let key = decoder.string()?;
+                    let value = decoder.array_items(Vec::new(), |mut values, decoder| {
+                        values.push(decoder.integer()?);
+                        Ok(values)
+                    });
+                    let value = match value {
+                        Ok(value) => value,
+                        Err(_) => vec![decoder.integer()?],
+                    };
+                    entries.push((key, value));
+                    Ok(entries)
+                })
+                .unwrap();
+            assert_eq!(
+                vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2, 3])],
+                entries
+            );
+            assert!(decoder.is_empty());
+        }
+    }
+
+    #[test]
+    fn document() {
+        let mut decoder = Decoder::new(
+            b"\xbf\x61a\x9f\x01\x20\xf9\x3e\x00\xf6\xf7\xff\x61b\xa1\x61c\xf5\x61d\xc1\x01\xff",
+        );
+        assert_eq!(
+            Document::Object(
+                [
+                    (
+                        "a".to_string(),
+                        Document::Array(vec![
+                            Document::Number(Number::PosInt(1)),
+                            Document::Number(Number::NegInt(-1)),
+                            Document::Number(Number::Float(1.5)),
+                            Document::Null,
+                            Document::Null,
+                        ])
+                    ),
+                    (
+                        "b".to_string(),
+                        Document::Object([("c".to_string(), Document::Bool(true))].into())
+                    ),
+                    ("d".to_string(), Document::Number(Number::PosInt(1))),
+                ]
+                .into()
+            ),
+            decoder.document().unwrap()
+        );
+        assert!(Decoder::new(b"\x41a").document().is_err());
+    }
+
+    #[test]
+    fn deeply_nested_documents_are_rejected() {
+        let nested = |depth: usize| [vec![0x81; depth], vec![0xf6]].concat();
+        let mut expected = Document::Null;
+        for _ in 0..255 {
+            expected = Document::Array(vec![expected]);
+        }
+        assert_eq!(expected, Decoder::new(&nested(255)).document().unwrap());
+
+        let err = Decoder::new(&nested(256)).document().unwrap_err();
+        assert!(err.to_string().contains("exceeded max recursion depth"));
+        // Tags and indefinite-length maps count towards the depth too
+        let tagged = [vec![0xc1; 300], vec![0xf6]].concat();
+        let err = Decoder::new(&tagged).document().unwrap_err();
+        assert!(err.to_string().contains("exceeded max recursion depth"));
+        let maps = [[0xbf, 0x61, b'a'].repeat(300), vec![0xf6]].concat();
+        let err = Decoder::new(&maps).document().unwrap_err();
+        assert!(err.to_string().contains("exceeded max recursion depth"));
+    }
+
+    #[test]
+    fn nullable() {
+        let mut decoder = Decoder::new(b"\xf6\x61a");
+        assert_eq!(None, decoder.nullable(Decoder::string).unwrap());
+        assert_eq!(
+            Some("a".to_string()),
+            decoder.nullable(Decoder::string).unwrap()
+        );
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn skip() {
+        let input = b"\xbf\x61a\x9f\xc1\x01\x82\x5f\x41a\xff\xa0\xff\x61b\xf9\x3e\x00\xff\x17";
+        let mut decoder = Decoder::new(input);
+        decoder.skip().unwrap();
+        assert_eq!(Type::UnsignedInt, decoder.datatype().unwrap());
+        assert_eq!(23, decoder.integer().unwrap());
+        assert!(decoder.is_empty());
+
+        let mut decoder = Decoder::new(b"\x82\x01");
+        assert!(decoder.skip().is_err());
+        let mut decoder = Decoder::new(b"\xff");
+        assert!(decoder.skip().is_err());
+        let mut decoder = Decoder::new(b"\xbb\xff\xff\xff\xff\xff\xff\xff\xff");
+        assert!(decoder.skip().is_err());
+    }
+
+    #[test]
+    fn unexpected_type() {
+        let err = Decoder::new(b"\x01").str().unwrap_err();
+        assert_eq!(
+            "Error at offset 0: expected text string, but found UnsignedInt",
+            format!("{err}")
+        );
+        let err = Decoder::new(b"").boolean().unwrap_err();
+        assert_eq!(
+            "Error at offset 0: unexpected end of input",
+            format!("{err}")
+        );
+    }
+
+    fn document_strategy() -> impl Strategy<Value = Document> {
+        let leaf = prop_oneof![
+            Just(Document::Null),
+            any::<bool>().prop_map(Document::Bool),
+            any::<u64>().prop_map(|v| Document::Number(Number::PosInt(v))),
+            (i64::MIN..0).prop_map(|v| Document::Number(Number::NegInt(v))),
+            any::<f64>()
+                .prop_filter("NaN is not equal to itself", |v| !v.is_nan())
+                .prop_map(|v| Document::Number(Number::Float(v))),
+            ".*".prop_map(Document::String),
+        ];
+        leaf.prop_recursive(4, 64, 8, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..8).prop_map(Document::Array),
+                prop::collection::hash_map(".*", inner, 0..8).prop_map(Document::Object),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn document_round_trip(document in document_strategy()) {
+            let mut encoder = Encoder::new(Vec::new());
+            encoder.document(&document);
+            let encoded = encoder.into_writer();
+
+            let mut decoder = Decoder::new(&encoded);
+            prop_assert_eq!(&document, &decoder.document().unwrap());
+            prop_assert!(decoder.is_empty());
+
+            let mut decoder = Decoder::new(&encoded);
+            decoder.skip().unwrap();
+            prop_assert!(decoder.is_empty());
+        }
+
+        #[test]
+        fn decoding_arbitrary_input_does_not_panic(input in prop::collection::vec(any::<u8>(), 0..64)) {
+            let _ = Decoder::new(&input).document();
+            let _ = Decoder::new(&input).skip();
+            let _ = Decoder::new(&input).timestamp();
+        }
+
+        #[test]
+        fn timestamp_round_trip(seconds in -1_000_000_000_000i64..1_000_000_000_000, millis in 0u32..1000) {
+            let timestamp = DateTime::from_secs_and_nanos(seconds, millis * 1_000_000);
+            let mut encoder = Encoder::new(Vec::new());
+            encoder.timestamp(&timestamp);
+            let encoded = encoder.into_writer();
+            let decoded = Decoder::new(&encoded).timestamp().unwrap();
+            prop_assert_eq!(timestamp.secs(), decoded.secs());
+            prop_assert!((i64::from(timestamp.subsec_nanos()) - i64::from(decoded.subsec_nanos())).abs() < 1_000_000);
+        }
+    }
+}
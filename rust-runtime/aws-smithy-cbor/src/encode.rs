@@ -0,0 +1,357 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR encoding.
+
+use crate::data::{
+    BREAK, F32, F64, FALSE, INDEFINITE, MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP, MAJOR_NEGATIVE,
+    MAJOR_TAG, MAJOR_TEXT, MAJOR_UNSIGNED, NULL, TAG_EPOCH_DATE_TIME, TRUE,
+};
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+
+/// Writes CBOR data items into a byte buffer.
+///
+/// Every method appends exactly one data item (or, for `begin_*`/`end`, the start or end of a
+/// container) and returns `&mut Self` so that calls can be chained:
+///
+/// ```rust
+/// use aws_smithy_cbor::Encoder;
+///
+/// let mut encoder = Encoder::new(Vec::new());
+/// encoder.begin_map().str("name").str("example").str("count").integer(2).end();
+/// assert_eq!(
+///     b"\xbf\x64name\x67example\x65count\x02\xff"[..],
+///     encoder.into_writer()[..]
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct Encoder {
+    writer: Vec<u8>,
+}
+
+impl Encoder {
+    /// Creates an encoder that appends to `writer`.
+    pub fn new(writer: Vec<u8>) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the encoded bytes.
+    pub fn into_writer(self) -> Vec<u8> {
+        self.writer
+    }
+
+    /// Returns the encoded bytes written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.writer
+    }
+
+    /// Writes the head of a data item using the shortest possible encoding of `value`.
+    fn head(&mut self, major: u8, value: u64) -> &mut Self {
+        let major = major << 5;
+        if value < 24 {
+            self.writer.push(major | value as u8);
+        } else if let Ok(value) = u8::try_from(value) {
+            self.writer.push(major | 24);
+            self.writer.push(value);
+        } else if let Ok(value) = u16::try_from(value) {
+            self.writer.push(major | 25);
+            self.writer.extend_from_slice(&value.to_be_bytes());
+        } else if let Ok(value) = u32::try_from(value) {
+            self.writer.push(major | 26);
+            self.writer.extend_from_slice(&value.to_be_bytes());
+        } else {
+            self.writer.push(major | 27);
+            self.writer.extend_from_slice(&value.to_be_bytes());
+        }
+        self
+    }
+
+    /// Starts an indefinite-length map. Must be followed by key/value pairs and [`Encoder::end`].
+    pub fn begin_map(&mut self) -> &mut Self {
+        self.writer.push(MAJOR_MAP << 5 | INDEFINITE);
+        self
+    }
+
+    /// Starts a map of `len` key/value pairs.
+    pub fn map(&mut self, len: usize) -> &mut Self {
+        self.head(MAJOR_MAP, len as u64)
+    }
+
+    /// Starts an indefinite-length array. Must be followed by its items and [`Encoder::end`].
+    pub fn begin_array(&mut self) -> &mut Self {
+        self.writer.push(MAJOR_ARRAY << 5 | INDEFINITE);
+        self
+    }
+
+    /// Starts an array of `len` items.
+    pub fn array(&mut self, len: usize) -> &mut Self {
+        self.head(MAJOR_ARRAY, len as u64)
+    }
+
+    /// Ends the innermost indefinite-length map or array.
+    pub fn end(&mut self) -> &mut Self {
+        self.writer.push(BREAK);
+        self
+    }
+
+    /// Writes a text string.
+    pub fn str(&mut self, value: &str) -> &mut Self {
+        self.head(MAJOR_TEXT, value.len() as u64);
+        self.writer.extend_from_slice(value.as_bytes());
+        self
+    }
+
+    /// Writes a byte string.
+    pub fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.head(MAJOR_BYTES, value.len() as u64);
+        self.writer.extend_from_slice(value);
+        self
+    }
+
+    /// Writes a blob as a byte string.
+    pub fn blob(&mut self, value: &Blob) -> &mut Self {
+        self.bytes(value.as_ref())
+    }
+
+    /// Writes a boolean.
+    pub fn boolean(&mut self, value: bool) -> &mut Self {
+        self.writer.push(if value { TRUE } else { FALSE });
+        self
+    }
+
+    /// Writes `null`.
+    pub fn null(&mut self) -> &mut Self {
+        self.writer.push(NULL);
+        self
+    }
+
+    /// Writes a Smithy `byte`.
+    pub fn byte(&mut self, value: i8) -> &mut Self {
+        self.long(value.into())
+    }
+
+    /// Writes a Smithy `short`.
+    pub fn short(&mut self, value: i16) -> &mut Self {
+        self.long(value.into())
+    }
+
+    /// Writes a Smithy `integer`.
+    pub fn integer(&mut self, value: i32) -> &mut Self {
+        self.long(value.into())
+    }
+
+    /// Writes a Smithy `long`.
+    pub fn long(&mut self, value: i64) -> &mut Self {
+        if value >= 0 {
+            self.head(MAJOR_UNSIGNED, value as u64)
+        } else {
+            // A negative integer `n` is encoded as `-1 - n`, which can't overflow for any `i64`
+            self.head(MAJOR_NEGATIVE, (-1 - value) as u64)
+        }
+    }
+
+    /// Writes an unsigned integer.
+    pub fn unsigned(&mut self, value: u64) -> &mut Self {
+        self.head(MAJOR_UNSIGNED, value)
+    }
+
+    /// Writes a Smithy `float` as a single-precision float.
+    pub fn float(&mut self, value: f32) -> &mut Self {
+        self.writer.push(F32);
+        self.writer.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    /// Writes a Smithy `double` as a double-precision float.
+    pub fn double(&mut self, value: f64) -> &mut Self {
+        self.writer.push(F64);
+        self.writer.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    /// Writes a tag. Must be followed by the data item that it applies to.
+    pub fn tag(&mut self, tag: u64) -> &mut Self {
+        self.head(MAJOR_TAG, tag)
+    }
+
+    /// Writes a timestamp as tag `1` wrapping the number of seconds since the Unix epoch.
+    pub fn timestamp(&mut self, value: &DateTime) -> &mut Self {
+        self.tag(TAG_EPOCH_DATE_TIME).double(value.as_secs_f64())
+    }
+
+    /// Writes a number.
+    pub fn number(&mut self, value: Number) -> &mut Self {
+        match value {
+            Number::PosInt(value) => self.unsigned(value),
+            Number::NegInt(value) => self.long(value),
+            Number::Float(value) => self.double(value),
+        }
+    }
+
+    /// Writes a document.
+    pub fn document(&mut self, value: &Document) -> &mut Self {
+        match value {
+            Document::Object(values) => {
+                self.map(values.len());
+                for (key, value) in values {
+                    self.str(key).document(value);
+                }
+                self
+            }
+            Document::Array(values) => {
+                self.array(values.len());
+                for value in values {
+                    self.document(value);
+                }
+                self
+            }
+            Document::Number(value) => self.number(*value),
+            Document::String(value) => self.str(value),
+            Document::Bool(value) => self.boolean(*value),
+            Document::Null => self.null(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoder;
+    use aws_smithy_types::{Blob, DateTime, Document, Number};
+
+    fn encode(f: impl FnOnce(&mut Encoder) -> &mut Encoder) -> Vec<u8> {
+        let mut encoder = Encoder::new(Vec::new());
+        f(&mut encoder);
+        encoder.into_writer()
+    }
+
+    // Examples from RFC 8949 Appendix A
+    #[test]
+    fn integers() {
+        assert_eq!(vec![0x00], encode(|e| e.long(0)));
+        assert_eq!(vec![0x17], encode(|e| e.long(23)));
+        assert_eq!(vec![0x18, 0x18], encode(|e| e.long(24)));
+        assert_eq!(vec![0x18, 0x64], encode(|e| e.byte(100)));
+        assert_eq!(vec![0x19, 0x03, 0xe8], encode(|e| e.short(1000)));
+        assert_eq!(
+            vec![0x1a, 0x00, 0x0f, 0x42, 0x40],
+            encode(|e| e.integer(1000000))
+        );
+        assert_eq!(
+            vec![0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00],
+            encode(|e| e.long(1000000000000))
+        );
+        assert_eq!(
+            vec![0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            encode(|e| e.unsigned(u64::MAX))
+        );
+        assert_eq!(vec![0x20], encode(|e| e.long(-1)));
+        assert_eq!(vec![0x29], encode(|e| e.long(-10)));
+        assert_eq!(vec![0x38, 0x63], encode(|e| e.long(-100)));
+        assert_eq!(vec![0x39, 0x03, 0xe7], encode(|e| e.long(-1000)));
+        assert_eq!(
+            vec![0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            encode(|e| e.long(i64::MIN))
+        );
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(
+            vec![0xfa, 0x47, 0xc3, 0x50, 0x00],
+            encode(|e| e.float(100000.0))
+        );
+        assert_eq!(
+            vec![0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+            encode(|e| e.double(1.1))
+        );
+        assert_eq!(
+            vec![0xfb, 0x7f, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            encode(|e| e.double(f64::INFINITY))
+        );
+    }
+
+    #[test]
+    fn strings_and_blobs() {
+        assert_eq!(vec![0x60], encode(|e| e.str("")));
+        assert_eq!(
+            vec![0x64, 0x49, 0x45, 0x54, 0x46],
+            encode(|e| e.str("IETF"))
+        );
+        assert_eq!(vec![0x62, 0xc3, 0xbc], encode(|e| e.str("\u{00fc}")));
+        assert_eq!(
+            vec![0x44, 0x01, 0x02, 0x03, 0x04],
+            encode(|e| e.blob(&Blob::new(vec![1, 2, 3, 4])))
+        );
+        let long = "a".repeat(300);
+        let encoded = encode(|e| e.str(&long));
+        assert_eq!(&[0x79, 0x01, 0x2c], &encoded[..3]);
+        assert_eq!(303, encoded.len());
+    }
+
+    #[test]
+    fn simple_values() {
+        assert_eq!(
+            vec![0xf4, 0xf5, 0xf6],
+            encode(|e| e.boolean(false).boolean(true).null())
+        );
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(
+            vec![0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05],
+            encode(|e| e
+                .array(3)
+                .long(1)
+                .array(2)
+                .long(2)
+                .long(3)
+                .array(2)
+                .long(4)
+                .long(5))
+        );
+        assert_eq!(
+            vec![0xbf, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9f, 0x02, 0x03, 0xff, 0xff],
+            encode(|e| e
+                .begin_map()
+                .str("a")
+                .long(1)
+                .str("b")
+                .begin_array()
+                .long(2)
+                .long(3)
+                .end()
+                .end())
+        );
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(
+            vec![0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20, 0x00, 0x00],
+            encode(|e| e.timestamp(&DateTime::from_fractional_secs(1363896240, 0.5)))
+        );
+    }
+
+    #[test]
+    fn document() {
+        let document = Document::Array(vec![
+            Document::Number(Number::PosInt(1)),
+            Document::Number(Number::NegInt(-2)),
+            Document::Number(Number::Float(0.5)),
+            Document::String("s".into()),
+            Document::Bool(true),
+            Document::Null,
+            Document::Object([("k".to_string(), Document::Null)].into()),
+        ]);
+        assert_eq!(
+            vec![
+                0x87, 0x01, 0x21, 0xfb, 0x3f, 0xe0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x61, 0x73,
+                0xf5, 0xf6, 0xa1, 0x61, 0x6b, 0xf6
+            ],
+            encode(|e| e.document(&document))
+        );
+    }
+}
@@ -0,0 +1,34 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![allow(clippy::derive_partial_eq_without_eq)]
+#![warn(
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    unreachable_pub,
+    rust_2018_idioms
+)]
+
+//! CBOR Abstractions for Smithy
+//!
+//! This crate implements the subset of [RFC 8949](https://www.rfc-editor.org/rfc/rfc8949) that is
+//! needed to serialize and deserialize Smithy shapes:
+//!
+//! - Structures and maps are encoded as CBOR maps with text string keys.
+//! - Lists are encoded as CBOR arrays.
+//! - Blobs are encoded as byte strings.
+//! - Timestamps are encoded as tag `1` (epoch-based date/time) wrapping a floating point number
+//!   of seconds.
+//! - Documents are encoded as the untagged CBOR data item that corresponds to their JSON value.
+//!
+//! When decoding, definite and indefinite-length items are accepted interchangeably, and numbers
+//! may be encoded using any width that can hold their value.
+
+pub mod data;
+pub mod decode;
+pub mod encode;
+
+pub use decode::{Decoder, DeserializeError};
+pub use encode::Encoder;
@@ -19,6 +19,7 @@ mod error;
 pub mod lambda;
 pub mod logging;
 pub mod middleware;
+mod reload;
 mod server;
 mod socket;
 pub mod tls;
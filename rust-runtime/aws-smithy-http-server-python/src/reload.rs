@@ -0,0 +1,152 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Utilities to reload the Python handlers of a running application.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use signal_hook::consts::SIGHUP;
+
+/// Set while the modules defining the handlers are re-executed, to prevent them from starting a
+/// new server if their entrypoint isn't guarded by `if __name__ == "__main__"`.
+static RELOADING: AtomicBool = AtomicBool::new(false);
+
+/// How often the files are checked for changes in watch mode.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Python code re-executing the modules that define the handlers, returning the applications
+/// created by them.
+///
+/// The `__main__` module cannot be reloaded with `importlib`, so it's executed again from its
+/// file under a different name.
+pub(crate) const RELOAD_APPS: &str = r#"
+import importlib
+import runpy
+import sys
+
+def reload_apps(app_type, module_names):
+    apps = []
+    for name in module_names:
+        if name == "__main__":
+            namespace = runpy.run_path(sys.modules["__main__"].__file__, run_name="__smithy_rs_reload__")
+        else:
+            namespace = vars(importlib.reload(sys.modules[name]))
+        apps.extend(value for value in namespace.values() if isinstance(value, app_type))
+    return apps
+"#;
+
+/// Sets [RELOADING] for as long as it's alive.
+pub(crate) struct ReloadGuard;
+
+impl ReloadGuard {
+    pub(crate) fn new() -> Self {
+        RELOADING.store(true, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for ReloadGuard {
+    fn drop(&mut self) {
+        RELOADING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Returns `true` if the handlers are being reloaded.
+pub(crate) fn is_reloading() -> bool {
+    RELOADING.load(Ordering::SeqCst)
+}
+
+/// Detects changes to a set of files by polling their modification time.
+#[derive(Debug)]
+pub(crate) struct FileWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl FileWatcher {
+    pub(crate) fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let modified = modified(&path);
+                (path, modified)
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Returns `true` if any of the files was modified, created or removed since the last call.
+    pub(crate) fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, last_modified) in self.files.iter_mut() {
+            let modified = modified(path);
+            if modified != *last_modified {
+                tracing::debug!(path = %path.display(), "file changed");
+                *last_modified = modified;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Spawns a background thread raising `SIGHUP` in the current process whenever one of the files
+/// changes, which triggers a rolling restart of the workers.
+pub(crate) fn watch_for_changes(paths: Vec<PathBuf>) {
+    tracing::info!(files = ?paths, "watching files for changes");
+    let mut watcher = FileWatcher::new(paths);
+    thread::spawn(move || loop {
+        thread::sleep(WATCH_INTERVAL);
+        if watcher.changed() {
+            tracing::info!("files changed, reloading the application");
+            if let Err(err) = signal_hook::low_level::raise(SIGHUP) {
+                tracing::error!(error = ?err, "unable to raise SIGHUP to reload the application");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn file_watcher_detects_created_and_removed_files() {
+        let path =
+            std::env::temp_dir().join(format!("smithy-rs-file-watcher-{}.py", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut watcher = FileWatcher::new([path.clone()]);
+        assert!(!watcher.changed());
+
+        fs::write(&path, "print('hello')").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+
+    #[test]
+    fn reload_guard_resets_flag() {
+        assert!(!is_reloading());
+        {
+            let _guard = ReloadGuard::new();
+            assert!(is_reloading());
+        }
+        assert!(!is_reloading());
+    }
+}
@@ -3,10 +3,12 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::convert::Infallible;
+use std::future::Future;
 use std::net::TcpListener as StdTcpListener;
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::process;
 use std::sync::{mpsc, Arc};
 use std::thread;
@@ -18,15 +20,20 @@ use aws_smithy_http_server::{
 use http::{Request, Response};
 use hyper::server::conn::AddrIncoming;
 use parking_lot::Mutex;
-use pyo3::{prelude::*, types::IntoPyDict};
+use pyo3::{exceptions::PyRuntimeError, prelude::*, types::IntoPyDict};
 use signal_hook::{consts::*, iterator::Signals};
 use socket2::Socket;
-use tokio::{net::TcpListener, runtime};
+use tokio::{
+    net::TcpListener,
+    runtime,
+    signal::unix::{signal, SignalKind},
+};
 use tokio_rustls::TlsAcceptor;
 use tower::{util::BoxCloneService, ServiceBuilder};
 
 use crate::{
     context::{layer::AddPyContextLayer, PyContext},
+    reload::{self, ReloadGuard},
     tls::{listener::Listener as TlsListener, PyTlsConfig},
     util::{error::rich_py_err, func_metadata},
     PySocket,
@@ -63,6 +70,8 @@ type Service = BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>;
 /// function that will be executed as business logic by the code generated Rust handlers.
 /// To properly function, the application requires some state:
 /// * `workers`: the list of child Python worker processes, protected by a Mutex.
+/// * `draining_workers`: the list of child Python worker processes replaced during a
+///   [rolling restart](PyApp::rolling_restart) that haven't exited yet, protected by a Mutex.
 /// * `context`: the optional Python object that should be passed inside the Rust state struct.
 /// * `handlers`: the mapping between an operation name and its [PyHandler] representation.
 ///
@@ -71,11 +80,17 @@ type Service = BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>;
 /// it time to loop through all the active workers and terminate them. Workers registers their own signal handlers and attaches
 /// them to the Python event loop, ensuring all coroutines are cancelled before terminating a worker.
 ///
+/// The main process can also replace the workers with new ones running reloaded handlers, without closing the
+/// shared socket, see [PyApp::rolling_restart].
+///
 /// This trait will be implemented by the code generated by the `PythonApplicationGenerator` Kotlin class.
 pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
     /// List of active Python workers registered with this application.
     fn workers(&self) -> &Mutex<Vec<PyObject>>;
 
+    /// List of Python workers replaced during a rolling restart that are still draining their connections.
+    fn draining_workers(&self) -> &Mutex<Vec<PyObject>>;
+
     /// Optional Python context object that will be passed as part of the Rust state.
     fn context(&self) -> &Option<PyObject>;
 
@@ -86,11 +101,12 @@ pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
     fn build_service(&mut self, event_loop: &pyo3::PyAny) -> pyo3::PyResult<Service>;
 
     /// Handle the graceful termination of Python workers by looping through all the
-    /// active workers and calling `terminate()` on them. If termination fails, this
+    /// active and draining workers and calling `terminate()` on them. If termination fails, this
     /// method will try to `kill()` any failed worker.
     fn graceful_termination(&self, workers: &Mutex<Vec<PyObject>>) -> ! {
         let workers = workers.lock();
-        for (idx, worker) in workers.iter().enumerate() {
+        let draining_workers = self.draining_workers().lock();
+        for (idx, worker) in workers.iter().chain(draining_workers.iter()).enumerate() {
             let idx = idx + 1;
             Python::with_gil(|py| {
                 let pid: isize = worker
@@ -118,10 +134,11 @@ pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
     }
 
     /// Handler the immediate termination of Python workers by looping through all the
-    /// active workers and calling `kill()` on them.
+    /// active and draining workers and calling `kill()` on them.
     fn immediate_termination(&self, workers: &Mutex<Vec<PyObject>>) -> ! {
         let workers = workers.lock();
-        for (idx, worker) in workers.iter().enumerate() {
+        let draining_workers = self.draining_workers().lock();
+        for (idx, worker) in workers.iter().chain(draining_workers.iter()).enumerate() {
             let idx = idx + 1;
             Python::with_gil(|py| {
                 let pid: isize = worker
@@ -146,9 +163,13 @@ pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
     /// Signals supported:
     ///   * SIGTERM|SIGQUIT - graceful termination of all workers.
     ///   * SIGINT - immediate termination of all workers.
+    ///   * SIGHUP - rolling restart of all workers on reloaded handlers, see [PyApp::rolling_restart].
     ///
     /// Other signals are NOOP.
-    fn block_on_rust_signals(&self) {
+    fn block_on_rust_signals(&mut self, py: Python, socket: &PySocket, tls: Option<PyTlsConfig>)
+    where
+        Self: for<'source> FromPyObject<'source>,
+    {
         let mut signals =
             Signals::new([SIGINT, SIGHUP, SIGQUIT, SIGTERM, SIGUSR1, SIGUSR2, SIGWINCH])
                 .expect("Unable to register signals");
@@ -167,6 +188,16 @@ pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
                     );
                     self.graceful_termination(self.workers());
                 }
+                SIGHUP => {
+                    tracing::info!(
+                        sig = %sig, "reload signal received, all workers will be restarted on reloaded handlers"
+                    );
+                    if let Err(err) = self.rolling_restart(py, socket, tls.clone()) {
+                        tracing::error!(
+                            error = ?rich_py_err(err), "unable to restart workers, the running workers are kept"
+                        );
+                    }
+                }
                 _ => {
                     tracing::debug!(sig = %sig, "signal is ignored by this application");
                 }
@@ -174,6 +205,107 @@ pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
         }
     }
 
+    /// Reload the Python handlers by executing again the modules that register them.
+    ///
+    /// Every module that defines a registered handler is re-executed: `__main__` is run again from its
+    /// file under a different name, and other modules are reloaded with `importlib.reload`. The
+    /// handlers registered on the applications created by these modules replace the current ones.
+    /// Every operation must still have a handler after the reload.
+    ///
+    /// The context and middlewares of the application are not reloaded. Modules must not start the
+    /// application when they are reloaded, so the call to `run()` should be guarded by
+    /// `if __name__ == "__main__"`.
+    fn reload_handlers(&mut self, py: Python) -> PyResult<()>
+    where
+        Self: for<'source> FromPyObject<'source>,
+    {
+        let module_names = handler_modules(py, self.handlers())?;
+        let app_type = self.clone().into_py(py).into_ref(py).get_type();
+        let reloader = PyModule::from_code(py, reload::RELOAD_APPS, "reload.py", "reload")?;
+        let apps: Vec<Self> = {
+            let _guard = ReloadGuard::new();
+            reloader
+                .getattr("reload_apps")?
+                .call1((app_type, module_names.into_iter().collect::<Vec<_>>()))?
+                .extract()?
+        };
+
+        let mut handlers = HashMap::new();
+        for mut app in apps {
+            handlers.extend(app.handlers().drain());
+        }
+        if let Some(missing) = self
+            .handlers()
+            .keys()
+            .find(|name| !handlers.contains_key(*name))
+        {
+            return Err(PyRuntimeError::new_err(format!(
+                "no handler is registered for operation `{missing}` after reloading the application"
+            )));
+        }
+        for (name, handler) in handlers.iter() {
+            tracing::info!(
+                name,
+                is_coroutine = handler.is_coroutine,
+                args = handler.args,
+                "reloaded handler function",
+            );
+        }
+        *self.handlers() = handlers;
+        Ok(())
+    }
+
+    /// Handle the rolling restart of Python workers on reloaded handlers.
+    ///
+    /// The handlers are reloaded with [PyApp::reload_handlers] first, and the running workers are kept if
+    /// that fails. Then, one at a time, a new worker is started on the shared socket and the worker it
+    /// replaces is asked to drain: it stops accepting connections, and exits once its in-flight requests
+    /// are complete. Since the shared socket is never closed, no connection is refused during the restart.
+    ///
+    /// Replaced workers are tracked in [PyApp::draining_workers] until they exit, so that they are
+    /// terminated along with the active workers if the application is stopped while they are draining.
+    fn rolling_restart(
+        &mut self,
+        py: Python,
+        socket: &PySocket,
+        tls: Option<PyTlsConfig>,
+    ) -> PyResult<()>
+    where
+        Self: for<'source> FromPyObject<'source>,
+    {
+        self.reload_handlers(py)?;
+
+        let os = py.import("os")?;
+
+        let mut draining_workers = self.draining_workers().lock();
+        // Forget the workers that finished draining since the last restart. Checking whether a worker is
+        // alive also joins it once it has exited.
+        draining_workers.retain(|worker| {
+            worker
+                .call_method0(py, "is_alive")
+                .and_then(|is_alive| is_alive.extract(py))
+                .unwrap_or(true)
+        });
+
+        let mut workers = self.workers().lock();
+        for idx in 0..workers.len() {
+            let worker_number = idx + 1;
+            let new_worker = self.start_worker_process(py, socket, worker_number, tls.clone())?;
+            let old_worker = std::mem::replace(&mut workers[idx], new_worker);
+            let pid: isize = old_worker.getattr(py, "pid")?.extract(py)?;
+            tracing::debug!(idx = worker_number, pid, "draining worker");
+            if let Err(err) = os.call_method1("kill", (pid, SIGUSR1)) {
+                tracing::error!(
+                    error = ?rich_py_err(err), idx = worker_number, pid, "unable to drain worker, killing it"
+                );
+                old_worker.call_method0(py, "kill")?;
+            }
+            draining_workers.push(old_worker);
+        }
+        tracing::info!("all workers restarted");
+        Ok(())
+    }
+
     /// Register and handle termination of all the tasks on the Python asynchronous event loop.
     /// We only register SIGQUIT and SIGINT since the main signal handling is done by Rust.
     fn register_python_signals(&self, py: Python, event_loop: PyObject) -> PyResult<()> {
@@ -225,6 +357,10 @@ event_loop.add_signal_handler(signal.SIGINT,
     /// thread on Hyper serve() method.
     /// The main process continues and at the end it is blocked on Python `loop.run_forever()`.
     ///
+    /// When the worker receives SIGUSR1 during a [rolling restart](PyApp::rolling_restart), the server
+    /// stops accepting connections and completes the in-flight requests before stopping the Python event
+    /// loop, which lets the worker exit.
+    ///
     /// [uvloop]: https://github.com/MagicStack/uvloop
     fn start_hyper_worker(
         &mut self,
@@ -242,6 +378,7 @@ event_loop.add_signal_handler(signal.SIGINT,
 
         // Register signals on the Python event loop.
         self.register_python_signals(py, event_loop.to_object(py))?;
+        let event_loop_handle = event_loop.to_object(py);
 
         // Spawn a new background [std::thread] to run the application.
         // This is needed because `asyncio` doesn't work properly if it doesn't control the main thread.
@@ -258,12 +395,14 @@ event_loop.add_signal_handler(signal.SIGINT,
                 .expect("unable to start a new tokio runtime for this process");
             rt.block_on(async move {
                 let addr = addr_incoming_from_socket(raw_socket);
+                let drain = drain_signal(worker_number);
 
                 if let Some(config) = tls {
                     let (acceptor, acceptor_rx) = tls_config_reloader(config);
                     let listener = TlsListener::new(acceptor, addr, acceptor_rx);
-                    let server =
-                        hyper::Server::builder(listener).serve(IntoMakeService::new(service));
+                    let server = hyper::Server::builder(listener)
+                        .serve(IntoMakeService::new(service))
+                        .with_graceful_shutdown(drain);
 
                     tracing::trace!("started tls hyper server from shared socket");
                     // Run forever-ish...
@@ -271,7 +410,9 @@ event_loop.add_signal_handler(signal.SIGINT,
                         tracing::error!(error = ?err, "server error");
                     }
                 } else {
-                    let server = hyper::Server::builder(addr).serve(IntoMakeService::new(service));
+                    let server = hyper::Server::builder(addr)
+                        .serve(IntoMakeService::new(service))
+                        .with_graceful_shutdown(drain);
 
                     tracing::trace!("started hyper server from shared socket");
                     // Run forever-ish...
@@ -280,6 +421,18 @@ event_loop.add_signal_handler(signal.SIGINT,
                     }
                 }
             });
+            // The server is done, stop the Python event loop to let the worker exit.
+            tracing::debug!(
+                worker_number,
+                "server stopped, stopping the python event loop"
+            );
+            let stopped = Python::with_gil(|py| {
+                let stop = event_loop_handle.getattr(py, "stop")?;
+                event_loop_handle.call_method1(py, "call_soon_threadsafe", (stop,))
+            });
+            if let Err(err) = stopped {
+                tracing::error!(error = ?rich_py_err(err), "unable to stop the python event loop");
+            }
         });
         // Block on the event loop forever.
         tracing::trace!("run and block on the python event loop until a signal is received");
@@ -365,6 +518,7 @@ event_loop.add_signal_handler(signal.SIGINT,
     ///
     ///     impl PyApp for App {
     ///         fn workers(&self) -> &Mutex<Vec<PyObject>> { todo!() }
+    ///         fn draining_workers(&self) -> &Mutex<Vec<PyObject>> { todo!() }
     ///         fn context(&self) -> &Option<PyObject> { todo!() }
     ///         fn handlers(&mut self) -> &mut HashMap<String, PyHandler> { todo!() }
     ///         fn build_service(&mut self, event_loop: &PyAny) -> PyResult<BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>> { todo!() }
@@ -387,7 +541,12 @@ event_loop.add_signal_handler(signal.SIGINT,
     ///     }
     /// ```
     ///
+    /// Sending SIGHUP to the main process restarts the workers on reloaded handlers, see
+    /// [PyApp::rolling_restart]. With `watch` enabled, which is meant for development, this happens
+    /// whenever the source file of a module defining a handler changes.
+    ///
     /// [multiprocessing::Process]: https://docs.python.org/3/library/multiprocessing.html
+    #[allow(clippy::too_many_arguments)]
     fn run_server(
         &mut self,
         py: Python,
@@ -396,7 +555,18 @@ event_loop.add_signal_handler(signal.SIGINT,
         backlog: Option<i32>,
         workers: Option<usize>,
         tls: Option<PyTlsConfig>,
-    ) -> PyResult<()> {
+        watch: Option<bool>,
+    ) -> PyResult<()>
+    where
+        Self: for<'source> FromPyObject<'source>,
+    {
+        if reload::is_reloading() {
+            return Err(PyRuntimeError::new_err(
+                "the application cannot be started while its handlers are reloaded, \
+                guard the call to `run()` with `if __name__ == \"__main__\"`",
+            ));
+        }
+
         // Setup multiprocessing environment, allowing connections and socket
         // sharing between processes.
         let mp = py.import("multiprocessing")?;
@@ -427,25 +597,45 @@ event_loop.add_signal_handler(signal.SIGINT,
         // TODO(move from num_cpus to thread::available_parallelism after MSRV is 1.60)
         // Start all the workers as new Python processes and store the in the `workers` attribute.
         for idx in 1..workers.unwrap_or_else(num_cpus::get) + 1 {
-            let sock = socket.try_clone()?;
-            let tls = tls.clone();
-            let process = mp.getattr("Process")?;
-            let handle = process.call1((
-                py.None(),
-                self.clone().into_py(py).getattr(py, "start_worker")?,
-                format!("smithy-rs-worker[{idx}]"),
-                (sock.into_py(py), idx, tls.into_py(py)),
-            ))?;
-            handle.call_method0("start")?;
-            active_workers.push(handle.to_object(py));
+            let handle = self.start_worker_process(py, &socket, idx, tls.clone())?;
+            active_workers.push(handle);
         }
         // Unlock the workers mutex.
         drop(active_workers);
+        if watch.unwrap_or(false) {
+            let files = handler_modules(py, self.handlers())?
+                .iter()
+                .map(|name| module_file(py, name))
+                .collect::<PyResult<_>>()?;
+            reload::watch_for_changes(files);
+        }
         tracing::trace!("rust python server started successfully");
-        self.block_on_rust_signals();
+        self.block_on_rust_signals(py, &socket, tls);
         Ok(())
     }
 
+    /// Start a new worker as a Python process, running the `start_worker` method of the application on a
+    /// clone of the shared socket.
+    fn start_worker_process(
+        &self,
+        py: Python,
+        socket: &PySocket,
+        worker_number: usize,
+        tls: Option<PyTlsConfig>,
+    ) -> PyResult<PyObject> {
+        let mp = py.import("multiprocessing")?;
+        let sock = socket.try_clone()?;
+        let process = mp.getattr("Process")?;
+        let handle = process.call1((
+            py.None(),
+            self.clone().into_py(py).getattr(py, "start_worker")?,
+            format!("smithy-rs-worker[{worker_number}]"),
+            (sock.into_py(py), worker_number, tls.into_py(py)),
+        ))?;
+        handle.call_method0("start")?;
+        Ok(handle.to_object(py))
+    }
+
     /// Lambda main entrypoint: start the handler on Lambda.
     fn run_lambda_handler(&mut self, py: Python) -> PyResult<()> {
         use aws_smithy_http_server::routing::LambdaHandler;
@@ -498,6 +688,47 @@ event_loop.add_signal_handler(signal.SIGINT,
     }
 }
 
+// Returns the names of the Python modules defining the given handlers.
+fn handler_modules(
+    py: Python,
+    handlers: &HashMap<String, PyHandler>,
+) -> PyResult<BTreeSet<String>> {
+    handlers
+        .values()
+        .map(|handler| {
+            handler
+                .func
+                .getattr(py, "__module__")?
+                .extract::<String>(py)
+        })
+        .collect()
+}
+
+// Returns the path of the source file of the Python module named `name`.
+fn module_file(py: Python, name: &str) -> PyResult<PathBuf> {
+    let file: String = py
+        .import("sys")?
+        .getattr("modules")?
+        .get_item(name)?
+        .getattr("__file__")?
+        .extract()?;
+    Ok(PathBuf::from(file))
+}
+
+// Returns a future completing when the worker receives SIGUSR1, which asks it to stop accepting
+// connections and exit once its in-flight requests are complete.
+fn drain_signal(worker_number: isize) -> impl Future<Output = ()> {
+    let mut drain =
+        signal(SignalKind::user_defined1()).expect("unable to register the drain signal");
+    async move {
+        drain.recv().await;
+        tracing::info!(
+            worker_number,
+            "drain signal received, completing in-flight requests"
+        );
+    }
+}
+
 fn addr_incoming_from_socket(socket: Socket) -> AddrIncoming {
     let std_listener: StdTcpListener = socket
         .try_into()
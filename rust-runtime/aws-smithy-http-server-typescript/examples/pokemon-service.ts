@@ -3,7 +3,6 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-import cluster from "cluster";
 import { cpus } from "os";
 
 import {
@@ -15,12 +14,18 @@ import {
     DoNothingInput,
     DoNothingOutput,
     TsSocket,
+    ResourceNotFoundException,
     CheckHealthOutput,
     CheckHealthInput,
     GetServerStatisticsInput,
     GetServerStatisticsOutput,
 } from ".";
 
+// The context shared by all the handlers.
+interface Context {
+    callsCount: number;
+}
+
 class HandlerImpl implements TsHandlers {
     // TODO: implement
     async doNothing(input: DoNothingInput): Promise<DoNothingOutput> {
@@ -32,13 +37,19 @@ class HandlerImpl implements TsHandlers {
     }
     // TODO: implement
     async getServerStatistics(
-        input: GetServerStatisticsInput
+        input: GetServerStatisticsInput,
+        context: Context,
     ): Promise<GetServerStatisticsOutput> {
-        return { callsCount: 0 };
+        return { callsCount: context.callsCount };
     }
     async getPokemonSpecies(
         input: GetPokemonSpeciesInput,
+        context: Context,
     ): Promise<GetPokemonSpeciesOutput> {
+        context.callsCount += 1;
+        if (input.name !== "pikachu") {
+            throw new ResourceNotFoundException(`Requested Pokémon ${input.name} not available`);
+        }
         return {
             name: input.name,
             flavorTextEntries: [
@@ -67,24 +78,22 @@ class HandlerImpl implements TsHandlers {
     }
 }
 
-// Pass the handlers to the App.
-const app = new App(new HandlerImpl());
+// Pass the handlers and the context to the App.
+const context: Context = { callsCount: 0 };
+const app = new App(new HandlerImpl(), context);
 // Start the app 🤘
-const numCPUs = cpus().length / 2;
-const address ="127.0.0.1";
+const address = "127.0.0.1";
 const port = 9090;
 const socket = new TsSocket(address, port);
-app.start(socket);
+app.start(socket, { workers: cpus().length });
+console.log(`Listening on ${address}:${port}`);
 
-// TODO: This part should be abstracted out and done directly in Rust.
-// We could take an optional number of workers and the socket as input
-// of the App.start() method.
-if (cluster.isPrimary) {
-  console.log(`Listening on ${address}:${port}`);
-    // Fork workers.
-    for (let i = 0; i < numCPUs; i++) {
-        cluster.fork();
-    }
+// Complete the in-flight requests before exiting.
+for (const signal of ["SIGINT", "SIGTERM"]) {
+    process.on(signal, async () => {
+        await app.shutdown();
+        process.exit(0);
+    });
 }
 
 process.on("unhandledRejection", err => {
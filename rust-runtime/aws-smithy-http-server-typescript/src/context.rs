@@ -0,0 +1,51 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Context shared between the Typescript handlers.
+
+use std::sync::Arc;
+
+use napi::{Env, JsUnknown};
+
+use crate::util::JsRef;
+
+/// A JavaScript value shared by all the handlers of an application.
+///
+/// The context is passed to every handler as its second argument, after the operation input,
+/// and can be used to share state like database connections or counters between requests:
+///
+/// ```typescript
+/// const context = { callsCount: 0 };
+///
+/// class HandlerImpl implements TsHandlers {
+///     async getServerStatistics(input: GetServerStatisticsInput, context: any) {
+///         return { callsCount: context.callsCount };
+///     }
+/// }
+///
+/// const app = new App(new HandlerImpl(), context);
+/// ```
+///
+/// Handlers run on the JavaScript thread, so the context doesn't need any synchronization.
+#[derive(Clone)]
+pub struct TsContext(Arc<JsRef>);
+
+impl TsContext {
+    /// Creates a new context holding `value`.
+    pub fn new(env: &Env, value: JsUnknown) -> napi::Result<Self> {
+        Ok(Self(Arc::new(JsRef::new(env, value)?)))
+    }
+
+    /// Returns the value of the context.
+    pub(crate) fn get(&self, env: &Env) -> napi::Result<JsUnknown> {
+        self.0.get(env)
+    }
+}
+
+impl std::fmt::Debug for TsContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TsContext").finish_non_exhaustive()
+    }
+}
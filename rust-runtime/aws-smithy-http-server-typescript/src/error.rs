@@ -0,0 +1,40 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Typescript error definition.
+
+use std::io;
+use std::net::AddrParseError;
+
+use aws_smithy_types::{byte_stream::error::Error as ByteStreamError, date_time::ConversionError};
+use thiserror::Error;
+
+use crate::tls::TsTlsConfigError;
+
+/// Typescript error that implements foreign errors.
+#[derive(Error, Debug)]
+pub enum TsError {
+    /// Implements `From<aws_smithy_types::date_time::ConversionError>`.
+    #[error("DateTimeConversion: {0}")]
+    DateTimeConversion(#[from] ConversionError),
+    /// Implements `From<aws_smithy_types::byte_stream::error::Error>`.
+    #[error("ByteStream: {0}")]
+    ByteStream(#[from] ByteStreamError),
+    /// Implements `From<std::net::AddrParseError>`.
+    #[error("AddrParse: {0}")]
+    AddrParse(#[from] AddrParseError),
+    /// Implements `From<std::io::Error>`.
+    #[error("Io: {0}")]
+    Io(#[from] io::Error),
+    /// Implements `From<crate::tls::TsTlsConfigError>`.
+    #[error("TlsConfig: {0}")]
+    TlsConfig(#[from] TsTlsConfigError),
+}
+
+impl From<TsError> for napi::Error {
+    fn from(other: TsError) -> napi::Error {
+        napi::Error::from_reason(other.to_string())
+    }
+}
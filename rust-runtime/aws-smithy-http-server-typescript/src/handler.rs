@@ -0,0 +1,244 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Typescript operation handlers.
+//!
+//! Handlers are JavaScript functions and can only run on the JavaScript thread, while requests
+//! are served by the worker threads of the [TsServer](crate::TsServer). [TsHandler] schedules a
+//! call to the handler on the JavaScript thread through a [ThreadsafeFunction] and waits for the
+//! returned promise to settle.
+//!
+//! The handler is invoked from the callback of the threadsafe function rather than by the
+//! threadsafe function itself, so that the values it throws or rejects with are still alive
+//! when they are converted into operation errors by [FromTsError]. This is what allows
+//! handlers to return modeled errors:
+//!
+//! ```typescript
+//! async getPokemonSpecies(input: GetPokemonSpeciesInput): Promise<GetPokemonSpeciesOutput> {
+//!     throw new ResourceNotFoundException(`${input.name} not found`);
+//! }
+//! ```
+
+use std::ptr;
+use std::sync::Arc;
+
+use napi::{
+    bindgen_prelude::{FromNapiRef, FromNapiValue, ToNapiValue, ValidateNapiValue},
+    check_status, sys,
+    threadsafe_function::{
+        ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+    },
+    Env, JsFunction, JsObject, JsUnknown, NapiRaw, NapiValue, Status,
+};
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+use crate::{util::JsRef, TsContext};
+
+/// Conversion of the values thrown by a Typescript handler into an operation error.
+///
+/// The code generator implements this trait for every operation error: modeled errors are
+/// extracted from the thrown value with [downcast] and anything else becomes an internal
+/// server error. The conversion runs on the JavaScript thread, while the thrown value is alive.
+///
+/// Errors that happen while calling the handler, like an output that cannot be converted,
+/// are converted with the [From] implementation instead.
+pub trait FromTsError: From<napi::Error> {
+    /// Converts the value `error` thrown by a handler.
+    fn from_ts_error(env: &Env, error: JsUnknown) -> Self;
+}
+
+/// Extracts an instance of the `T` class from a JavaScript value.
+///
+/// Returns `None` if the value is not an instance of `T`.
+pub fn downcast<T>(env: &Env, value: &JsUnknown) -> Option<T>
+where
+    T: FromNapiRef + Clone + 'static,
+    for<'a> &'a T: ValidateNapiValue,
+{
+    // SAFETY: `value` belongs to `env`, and `from_napi_ref` is only called on instances of `T`.
+    unsafe {
+        <&T>::validate(env.raw(), value.raw()).ok()?;
+        T::from_napi_ref(env.raw(), value.raw()).ok().cloned()
+    }
+}
+
+/// Returns the message of a JavaScript error, or the string representation of any other value.
+pub fn error_message(value: &JsUnknown) -> String {
+    let message = if value.is_error().unwrap_or(false) {
+        // SAFETY: errors are objects.
+        let error: JsObject = unsafe { value.cast() };
+        error
+            .get_named_property::<JsUnknown>("message")
+            .and_then(|message| message.coerce_to_string())
+    } else {
+        // SAFETY: coercing doesn't require a specific type.
+        unsafe { value.cast::<JsUnknown>() }.coerce_to_string()
+    };
+    message
+        .and_then(|message| message.into_utf8())
+        .and_then(|message| message.into_owned())
+        .unwrap_or_default()
+}
+
+type Responder<O, E> = Arc<Mutex<Option<oneshot::Sender<Result<O, E>>>>>;
+
+/// A call to a handler scheduled on the JavaScript thread.
+struct Call<I, O, E> {
+    input: I,
+    responder: oneshot::Sender<Result<O, E>>,
+}
+
+/// A Typescript operation handler, that can be called from any thread.
+///
+/// `I`, `O` and `E` are the input, output and error of the operation. The handler is called
+/// with the input and the [TsContext] of the application, if any, and can either return the
+/// output or a promise of it.
+pub struct TsHandler<I: 'static, O: 'static, E: 'static> {
+    function: ThreadsafeFunction<Call<I, O, E>, ErrorStrategy::Fatal>,
+}
+
+impl<I, O, E> TsHandler<I, O, E>
+where
+    I: ToNapiValue + 'static,
+    O: FromNapiValue + Send + 'static,
+    E: FromTsError + Send + 'static,
+{
+    /// Creates a new handler calling `function`, passing it `context` if any.
+    ///
+    /// Must be called on the JavaScript thread.
+    pub fn new(env: &Env, function: JsFunction, context: Option<TsContext>) -> napi::Result<Self> {
+        let function = JsRef::new(env, function)?;
+        let dispatcher =
+            env.create_function_from_closure("smithyDispatch", |ctx| ctx.env.get_undefined())?;
+        let function = dispatcher.create_threadsafe_function(
+            0,
+            move |ctx: ThreadSafeCallContext<Call<I, O, E>>| {
+                let Call { input, responder } = ctx.value;
+                let responder = Arc::new(Mutex::new(Some(responder)));
+                if let Err(err) = invoke(&ctx.env, &function, context.as_ref(), input, &responder) {
+                    respond(&responder, Err(err.into()));
+                }
+                Ok(Vec::<()>::new())
+            },
+        )?;
+        Ok(Self { function })
+    }
+
+    /// Calls the handler with `input` and waits for its result.
+    pub async fn call(&self, input: I) -> Result<O, E> {
+        let (responder, response) = oneshot::channel();
+        let status = self.function.call(
+            Call { input, responder },
+            ThreadsafeFunctionCallMode::NonBlocking,
+        );
+        if status != Status::Ok {
+            return Err(napi::Error::new(status, "unable to schedule the handler call").into());
+        }
+        response.await.unwrap_or_else(|_| {
+            Err(napi::Error::from_reason("the handler completed without a result").into())
+        })
+    }
+}
+
+impl<I, O, E> Clone for TsHandler<I, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            function: self.function.clone(),
+        }
+    }
+}
+
+impl<I, O, E> std::fmt::Debug for TsHandler<I, O, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TsHandler").finish_non_exhaustive()
+    }
+}
+
+/// Sends the result of a call, unless it has already been sent.
+fn respond<O, E>(responder: &Mutex<Option<oneshot::Sender<Result<O, E>>>>, result: Result<O, E>) {
+    if let Some(responder) = responder.lock().take() {
+        // The receiver is gone if the request was cancelled, there's nobody to tell.
+        let _ = responder.send(result);
+    }
+}
+
+/// Calls the handler and settles the value it returns.
+fn invoke<I, O, E>(
+    env: &Env,
+    function: &JsRef,
+    context: Option<&TsContext>,
+    input: I,
+    responder: &Responder<O, E>,
+) -> napi::Result<()>
+where
+    I: ToNapiValue,
+    O: FromNapiValue + Send + 'static,
+    E: FromTsError + Send + 'static,
+{
+    let function: JsFunction = function.get(env)?;
+    // SAFETY: the value has just been created in `env`.
+    let input =
+        unsafe { JsUnknown::from_raw_unchecked(env.raw(), I::to_napi_value(env.raw(), input)?) };
+    let mut args = vec![input];
+    if let Some(context) = context {
+        args.push(context.get(env)?);
+    }
+    match function.call(None, &args) {
+        Ok(value) => settle(env, value, responder),
+        Err(err) if err.status == Status::PendingException => {
+            let error = take_exception(env)?;
+            respond(responder, Err(E::from_ts_error(env, error)));
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Waits for `value` to settle if it's a promise, and responds with its result.
+fn settle<O, E>(env: &Env, value: JsUnknown, responder: &Responder<O, E>) -> napi::Result<()>
+where
+    O: FromNapiValue + Send + 'static,
+    E: FromTsError + Send + 'static,
+{
+    if !value.is_promise()? {
+        // SAFETY: `value` belongs to `env`.
+        let output = unsafe { O::from_napi_value(env.raw(), value.raw()) };
+        respond(responder, output.map_err(E::from));
+        return Ok(());
+    }
+
+    let on_fulfilled = {
+        let responder = responder.clone();
+        env.create_function_from_closure("onFulfilled", move |ctx| {
+            let value = ctx.get::<JsUnknown>(0)?;
+            // SAFETY: `value` belongs to `ctx.env`.
+            let output = unsafe { O::from_napi_value(ctx.env.raw(), value.raw()) };
+            respond(&responder, output.map_err(E::from));
+            ctx.env.get_undefined()
+        })?
+    };
+    let on_rejected = {
+        let responder = responder.clone();
+        env.create_function_from_closure("onRejected", move |ctx| {
+            let error = ctx.get::<JsUnknown>(0)?;
+            respond(&responder, Err(E::from_ts_error(ctx.env, error)));
+            ctx.env.get_undefined()
+        })?
+    };
+    // SAFETY: promises are objects.
+    let promise: JsObject = unsafe { value.cast() };
+    let then: JsFunction = promise.get_named_property("then")?;
+    then.call(Some(&promise), &[on_fulfilled, on_rejected])?;
+    Ok(())
+}
+
+/// Returns and clears the pending JavaScript exception.
+fn take_exception(env: &Env) -> napi::Result<JsUnknown> {
+    let mut exception = ptr::null_mut();
+    check_status!(unsafe { sys::napi_get_and_clear_last_exception(env.raw(), &mut exception) })?;
+    // SAFETY: the exception has just been returned by `env`.
+    Ok(unsafe { JsUnknown::from_raw_unchecked(env.raw(), exception) })
+}
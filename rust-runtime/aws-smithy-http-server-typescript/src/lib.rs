@@ -2,3 +2,34 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0
  */
+
+#![allow(clippy::derive_partial_eq_without_eq)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+//! Rust/Typescript bindings, runtime and utilities.
+//!
+//! This crates implements all the generic code needed to start and manage
+//! a Smithy Rust HTTP server where the business logic is implemented in Typescript,
+//! leveraging [napi-rs].
+//!
+//! [napi-rs]: https://napi.rs/
+
+pub mod context;
+mod error;
+pub mod handler;
+mod server;
+mod socket;
+pub mod tls;
+pub mod types;
+mod util;
+
+#[doc(inline)]
+pub use context::TsContext;
+#[doc(inline)]
+pub use error::TsError;
+#[doc(inline)]
+pub use handler::{FromTsError, TsHandler};
+#[doc(inline)]
+pub use server::{Service, TsServer, TsServerOptions};
+#[doc(inline)]
+pub use socket::TsSocket;
@@ -0,0 +1,289 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+use std::net::TcpListener as StdTcpListener;
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use aws_smithy_http_server::{
+    body::{Body, BoxBody},
+    routing::IntoMakeService,
+};
+use http::{Request, Response};
+use hyper::server::conn::AddrIncoming;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use tokio::{
+    net::TcpListener,
+    runtime,
+    sync::{oneshot, watch},
+};
+use tokio_rustls::TlsAcceptor;
+use tower::util::BoxCloneService;
+
+use crate::{
+    tls::{listener::Listener as TlsListener, TsTlsConfig},
+    TsError, TsSocket,
+};
+
+/// The service served by every worker, built by the generated application.
+pub type Service = BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>;
+
+/// Options to start a [TsServer] with.
+///
+/// ```typescript
+/// app.start(socket, { workers: 4 });
+/// ```
+#[napi(object, js_name = "ServerOptions")]
+#[derive(Debug, Clone, Default)]
+pub struct TsServerOptions {
+    /// Number of workers serving requests, defaults to the number of CPUs.
+    pub workers: Option<u32>,
+
+    /// Serves HTTPS instead of HTTP when set.
+    pub tls: Option<TsTlsConfig>,
+}
+
+/// A multi-worker hyper server serving a [Service] from a [TsSocket].
+///
+/// Every worker runs its own hyper server on a dedicated thread with a single-threaded [tokio]
+/// runtime, accepting connections from a clone of the same socket. The Typescript handlers
+/// always run on the JavaScript thread: workers only handle the HTTP layer and schedule the
+/// handler calls.
+///
+/// To use more than one JavaScript thread, start an application in every process of a Node.js
+/// `cluster`, all listening on the same address.
+#[derive(Debug)]
+pub struct TsServer {
+    shutdown: watch::Sender<bool>,
+    stopped: Mutex<Vec<oneshot::Receiver<()>>>,
+}
+
+impl TsServer {
+    /// Starts the workers serving `service` from `socket`.
+    pub fn start(
+        socket: &TsSocket,
+        service: Service,
+        options: TsServerOptions,
+    ) -> Result<Self, TsError> {
+        let workers = options
+            .workers
+            .map(|workers| workers as usize)
+            .unwrap_or_else(num_cpus::get)
+            .max(1);
+        if let Some(tls) = &options.tls {
+            // Fail early instead of in every worker.
+            tls.build()?;
+        }
+
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let mut stopped = Vec::with_capacity(workers);
+        for worker_number in 0..workers {
+            let listener: StdTcpListener = socket.get_socket()?.into();
+            // `TcpListener::from_std` doesn't set `O_NONBLOCK`.
+            listener.set_nonblocking(true)?;
+            let (stopped_tx, stopped_rx) = oneshot::channel();
+            start_hyper_worker(
+                worker_number,
+                listener,
+                service.clone(),
+                options.tls.clone(),
+                shutdown_rx.clone(),
+                stopped_tx,
+            )?;
+            stopped.push(stopped_rx);
+        }
+        tracing::info!(workers, "started the server");
+
+        Ok(Self {
+            shutdown,
+            stopped: Mutex::new(stopped),
+        })
+    }
+
+    /// Stops accepting new connections and waits for the in-flight requests to complete.
+    pub async fn shutdown(&self) {
+        tracing::info!("shutting down the server, completing in-flight requests");
+        // Sending fails only if all the workers are gone already.
+        let _ = self.shutdown.send(true);
+        let stopped = std::mem::take(&mut *self.stopped.lock());
+        for worker in stopped {
+            let _ = worker.await;
+        }
+        tracing::info!("server stopped");
+    }
+}
+
+/// Spawns a new [std::thread] running a hyper server until `shutdown` is signaled.
+fn start_hyper_worker(
+    worker_number: usize,
+    listener: StdTcpListener,
+    service: Service,
+    tls: Option<TsTlsConfig>,
+    shutdown: watch::Receiver<bool>,
+    stopped: oneshot::Sender<()>,
+) -> Result<(), TsError> {
+    thread::Builder::new()
+        .name(format!("smithy-rs-worker[{worker_number}]"))
+        .spawn(move || {
+            let rt = runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("unable to start a new tokio runtime for this worker");
+            rt.block_on(async move {
+                let addr = addr_incoming_from_listener(listener);
+                let drain = drain_signal(worker_number, shutdown);
+
+                if let Some(config) = tls {
+                    let (acceptor, acceptor_rx) = tls_config_reloader(config);
+                    let listener = TlsListener::new(acceptor, addr, acceptor_rx);
+                    let server = hyper::Server::builder(listener)
+                        .serve(IntoMakeService::new(service))
+                        .with_graceful_shutdown(drain);
+
+                    tracing::trace!(worker_number, "started tls hyper server from shared socket");
+                    if let Err(err) = server.await {
+                        tracing::error!(error = ?err, "server error");
+                    }
+                } else {
+                    let server = hyper::Server::builder(addr)
+                        .serve(IntoMakeService::new(service))
+                        .with_graceful_shutdown(drain);
+
+                    tracing::trace!(worker_number, "started hyper server from shared socket");
+                    if let Err(err) = server.await {
+                        tracing::error!(error = ?err, "server error");
+                    }
+                }
+            });
+            tracing::debug!(worker_number, "worker stopped");
+            let _ = stopped.send(());
+        })?;
+    Ok(())
+}
+
+/// Completes when the shutdown of the server is requested, or when the server is dropped.
+async fn drain_signal(worker_number: usize, mut shutdown: watch::Receiver<bool>) {
+    while !*shutdown.borrow() {
+        if shutdown.changed().await.is_err() {
+            break;
+        }
+    }
+    tracing::debug!(
+        worker_number,
+        "shutdown requested, completing in-flight requests"
+    );
+}
+
+fn addr_incoming_from_listener(listener: StdTcpListener) -> AddrIncoming {
+    let listener = TcpListener::from_std(listener)
+        .expect("unable to create `tokio::net::TcpListener` from `std::net::TcpListener`");
+    AddrIncoming::from_listener(listener)
+        .expect("unable to create `AddrIncoming` from `TcpListener`")
+}
+
+// Builds `TlsAcceptor` from given `config` and also creates a background task
+// to reload certificates and returns a channel to receive new `TlsAcceptor`s.
+fn tls_config_reloader(config: TsTlsConfig) -> (TlsAcceptor, mpsc::Receiver<TlsAcceptor>) {
+    let reload_dur = config.reload_duration();
+    let (tx, rx) = mpsc::channel();
+    let acceptor = TlsAcceptor::from(Arc::new(config.build().expect("invalid tls config")));
+
+    tokio::spawn(async move {
+        tracing::trace!(dur = ?reload_dur, "starting timer to reload tls config");
+        loop {
+            tokio::time::sleep(reload_dur).await;
+            tracing::trace!("reloading tls config");
+            match config.build() {
+                Ok(config) => {
+                    let new_config = TlsAcceptor::from(Arc::new(config));
+                    // `tx.send` can only fail if the receiver is dropped, which happens when
+                    // the server shuts down.
+                    if tx.send(new_config).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(error = ?err, "could not reload tls config because it is invalid");
+                }
+            }
+        }
+    });
+
+    (acceptor, rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use aws_smithy_http_server::body::boxed;
+    use tower::service_fn;
+
+    use super::*;
+
+    fn hello_service() -> Service {
+        BoxCloneService::new(service_fn(|_request: Request<Body>| async {
+            Ok::<_, Infallible>(Response::new(boxed(Body::from("hello"))))
+        }))
+    }
+
+    fn get(address: std::net::SocketAddr) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn workers_serve_requests_until_shutdown() {
+        let socket = TsSocket::new("127.0.0.1".to_owned(), 0, None).unwrap();
+        let address = socket.inner.local_addr().unwrap().as_socket().unwrap();
+        let server = TsServer::start(
+            &socket,
+            hello_service(),
+            TsServerOptions {
+                workers: Some(2),
+                tls: None,
+            },
+        )
+        .unwrap();
+
+        for _ in 0..4 {
+            let response = tokio::task::spawn_blocking(move || get(address))
+                .await
+                .unwrap();
+            assert!(response.starts_with("HTTP/1.1 200 OK"));
+            assert!(response.ends_with("hello"));
+        }
+
+        server.shutdown().await;
+        assert!(server.stopped.lock().is_empty());
+    }
+
+    #[test]
+    fn invalid_tls_config_fails_to_start() {
+        let socket = TsSocket::new("127.0.0.1".to_owned(), 0, None).unwrap();
+        let err = TsServer::start(
+            &socket,
+            hello_service(),
+            TsServerOptions {
+                workers: Some(1),
+                tls: Some(TsTlsConfig {
+                    key_path: "/does/not/exist".to_owned(),
+                    cert_path: "/does/not/exist".to_owned(),
+                    reload_secs: None,
+                }),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, TsError::TlsConfig(_)));
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Socket implementation that can be shared between multiple Node.js processes.
+
+use std::net::SocketAddr;
+
+use napi_derive::napi;
+use socket2::{Domain, Protocol, Socket, Type};
+
+use crate::TsError;
+
+/// Socket implementation that can be shared between multiple workers and Node.js processes.
+///
+/// The socket is created with `SO_REUSEADDR` and `SO_REUSEPORT` enabled, so the same address
+/// can be served by all the workers of a [TsServer](crate::TsServer) and, using the Node.js
+/// `cluster` module, by several processes.
+#[napi]
+#[derive(Debug)]
+pub struct TsSocket {
+    pub(crate) inner: Socket,
+}
+
+#[napi]
+impl TsSocket {
+    /// Create a new UNIX `Socket` from an address, port and backlog.
+    /// If not specified, the backlog defaults to 1024 connections.
+    #[napi(constructor)]
+    pub fn tsnew(address: String, port: i32, backlog: Option<i32>) -> napi::Result<Self> {
+        Ok(Self::new(address, port, backlog)?)
+    }
+
+    /// Clone the inner socket allowing it to be shared between multiple
+    /// Node.js processes.
+    #[napi]
+    pub fn try_clone(&self) -> napi::Result<TsSocket> {
+        let copied = self.inner.try_clone().map_err(TsError::from)?;
+        Ok(TsSocket { inner: copied })
+    }
+}
+
+impl TsSocket {
+    /// Create a new UNIX `Socket` from an address, port and backlog.
+    /// If not specified, the backlog defaults to 1024 connections.
+    pub fn new(address: String, port: i32, backlog: Option<i32>) -> Result<Self, TsError> {
+        let address: SocketAddr = format!("{}:{}", address, port).parse()?;
+        let (domain, ip_version) = TsSocket::socket_domain(address);
+        tracing::trace!(address = %address, ip_version, "shared socket listening");
+        let socket = Socket::new(domain, Type::STREAM, Some(Protocol::TCP))?;
+        // Set value for the `SO_REUSEPORT` and `SO_REUSEADDR` options on this socket.
+        // This indicates that further calls to `bind` may allow reuse of local
+        // addresses. For IPv4 sockets this means that a socket may bind even when
+        // there's a socket already listening on this port.
+        socket.set_reuse_port(true)?;
+        socket.set_reuse_address(true)?;
+        socket.bind(&address.into())?;
+        socket.listen(backlog.unwrap_or(1024))?;
+        Ok(TsSocket { inner: socket })
+    }
+
+    /// Get a cloned inner socket.
+    pub fn get_socket(&self) -> Result<Socket, std::io::Error> {
+        self.inner.try_clone()
+    }
+
+    /// Find the socket domain
+    fn socket_domain(address: SocketAddr) -> (Domain, &'static str) {
+        if address.is_ipv6() {
+            (Domain::IPV6, "6")
+        } else {
+            (Domain::IPV4, "4")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_can_bind_on_random_port() {
+        let socket = TsSocket::new("127.0.0.1".to_owned(), 0, None).unwrap();
+        assert!(socket.inner.local_addr().unwrap().is_ipv4());
+    }
+
+    #[test]
+    fn socket_can_be_cloned() {
+        let socket = TsSocket::new("127.0.0.1".to_owned(), 0, None).unwrap();
+        let cloned_socket = socket.get_socket().unwrap();
+        assert_eq!(
+            socket.inner.local_addr().unwrap(),
+            cloned_socket.local_addr().unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_address_is_rejected() {
+        let err = TsSocket::new("not an address".to_owned(), 0, None).unwrap_err();
+        assert!(matches!(err, TsError::AddrParse(_)));
+    }
+}
@@ -0,0 +1,159 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! TLS related types for Typescript.
+//!
+//! [TsTlsConfig] implementation is mostly borrowed from:
+//! <https://github.com/seanmonstar/warp/blob/4e9c4fd6ce238197fd1088061bbc07fa2852cb0f/src/tls.rs>
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::time::Duration;
+
+use napi_derive::napi;
+use thiserror::Error;
+use tokio_rustls::rustls::{Certificate, Error as RustTlsError, PrivateKey, ServerConfig};
+
+pub mod listener;
+
+/// Certificates are reloaded once a day by default.
+const DEFAULT_RELOAD_SECS: u32 = 86400;
+
+/// TsTlsConfig represents TLS configuration created from Typescript.
+///
+/// ```typescript
+/// app.start(socket, {
+///     tls: { keyPath: "/path/to/key.pem", certPath: "/path/to/cert.pem", reloadSecs: 3600 },
+/// });
+/// ```
+#[napi(object, js_name = "TlsConfig")]
+#[derive(Debug, Clone)]
+pub struct TsTlsConfig {
+    /// Absolute path of the RSA or PKCS private key.
+    pub key_path: String,
+
+    /// Absolute path of the x509 certificate.
+    pub cert_path: String,
+
+    /// Interval in seconds between certificate reloads, defaults to a day.
+    pub reload_secs: Option<u32>,
+}
+
+impl TsTlsConfig {
+    /// Build [ServerConfig] from [TsTlsConfig].
+    pub fn build(&self) -> Result<ServerConfig, TsTlsConfigError> {
+        let cert_chain = self.cert_chain()?;
+        let key_der = self.key_der()?;
+        let mut config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, key_der)?;
+        config.alpn_protocols = vec!["h2".into(), "http/1.1".into()];
+        Ok(config)
+    }
+
+    /// Returns reload duration.
+    pub fn reload_duration(&self) -> Duration {
+        Duration::from_secs(self.reload_secs.unwrap_or(DEFAULT_RELOAD_SECS).into())
+    }
+
+    /// Reads certificates from `cert_path`.
+    fn cert_chain(&self) -> Result<Vec<Certificate>, TsTlsConfigError> {
+        let file = File::open(&self.cert_path).map_err(TsTlsConfigError::CertParse)?;
+        let mut cert_rdr = BufReader::new(file);
+        Ok(rustls_pemfile::certs(&mut cert_rdr)
+            .map_err(TsTlsConfigError::CertParse)?
+            .into_iter()
+            .map(Certificate)
+            .collect())
+    }
+
+    /// Parses RSA or PKCS private key from `key_path`.
+    fn key_der(&self) -> Result<PrivateKey, TsTlsConfigError> {
+        let mut key_vec = Vec::new();
+        File::open(&self.key_path)
+            .and_then(|mut f| f.read_to_end(&mut key_vec))
+            .map_err(TsTlsConfigError::KeyParse)?;
+        if key_vec.is_empty() {
+            return Err(TsTlsConfigError::EmptyKey);
+        }
+
+        let mut pkcs8 = rustls_pemfile::pkcs8_private_keys(&mut key_vec.as_slice())
+            .map_err(TsTlsConfigError::Pkcs8Parse)?;
+        if !pkcs8.is_empty() {
+            return Ok(PrivateKey(pkcs8.remove(0)));
+        }
+
+        let mut rsa = rustls_pemfile::rsa_private_keys(&mut key_vec.as_slice())
+            .map_err(TsTlsConfigError::RsaParse)?;
+        if !rsa.is_empty() {
+            return Ok(PrivateKey(rsa.remove(0)));
+        }
+
+        Err(TsTlsConfigError::EmptyKey)
+    }
+}
+
+/// Possible TLS configuration errors.
+#[derive(Error, Debug)]
+pub enum TsTlsConfigError {
+    #[error("could not parse certificate")]
+    CertParse(io::Error),
+    #[error("could not parse key")]
+    KeyParse(io::Error),
+    #[error("empty key")]
+    EmptyKey,
+    #[error("could not parse pkcs8 keys")]
+    Pkcs8Parse(io::Error),
+    #[error("could not parse rsa keys")]
+    RsaParse(io::Error),
+    #[error("rusttls protocol error")]
+    RustTlsError(#[from] RustTlsError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KEY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../examples/python/pokemon-service-test/tests/testdata/localhost.key"
+    );
+    const TEST_CERT: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../examples/python/pokemon-service-test/tests/testdata/localhost.crt"
+    );
+
+    #[test]
+    fn building_tls_config() {
+        let config = TsTlsConfig {
+            key_path: TEST_KEY.to_owned(),
+            cert_path: TEST_CERT.to_owned(),
+            reload_secs: Some(1000),
+        };
+        assert_eq!(Duration::from_secs(1000), config.reload_duration());
+        config.build().unwrap();
+    }
+
+    #[test]
+    fn reload_duration_defaults_to_a_day() {
+        let config = TsTlsConfig {
+            key_path: TEST_KEY.to_owned(),
+            cert_path: TEST_CERT.to_owned(),
+            reload_secs: None,
+        };
+        assert_eq!(Duration::from_secs(86400), config.reload_duration());
+    }
+
+    #[test]
+    fn empty_key_is_rejected() {
+        let config = TsTlsConfig {
+            key_path: "/dev/null".to_owned(),
+            cert_path: TEST_CERT.to_owned(),
+            reload_secs: None,
+        };
+        assert!(matches!(config.build(), Err(TsTlsConfigError::EmptyKey)));
+    }
+}
@@ -0,0 +1,458 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Typescript wrapped types from aws-smithy-types.
+//!
+//! The generated structures use these types in place of the ones from [aws_smithy_types], to
+//! convert them from and to JavaScript values:
+//!
+//! | Smithy           | Rust         | JavaScript                                    |
+//! |------------------|--------------|-----------------------------------------------|
+//! | `blob`           | [Blob]       | `Blob` class, or a `Buffer` when received     |
+//! | streaming `blob` | [ByteStream] | `ByteStream` class, or a `Buffer` when received |
+//! | `timestamp`      | [DateTime]   | `Date`                                        |
+//! | `document`       | [Document]   | JSON-like values                              |
+//!
+//! ## `Deref` hacks for Json serializer
+//! [aws_smithy_json::serialize::JsonValueWriter] expects references to the types
+//! from [aws_smithy_types] (for example [aws_smithy_json::serialize::JsonValueWriter::document()]
+//! expects `&aws_smithy_types::Document`). In order to make
+//! [aws_smithy_json::serialize::JsonValueWriter] happy, we implement `Deref` traits for
+//! Typescript types to their Rust counterparts (for example
+//! `impl Deref<Target=aws_smithy_types::Document> for Document` and that allows `&Document` to
+//! get coerced to `&aws_smithy_types::Document`). This is a hack, we should ideally handle this
+//! in `JsonSerializerGenerator.kt` but it's not easy to do it with our current Kotlin structure.
+//!
+//! [aws_smithy_json::serialize::JsonValueWriter]: https://docs.rs/aws-smithy-json/latest/aws_smithy_json/serialize/struct.JsonValueWriter.html
+//! [aws_smithy_json::serialize::JsonValueWriter::document()]: https://docs.rs/aws-smithy-json/latest/aws_smithy_json/serialize/struct.JsonValueWriter.html#method.document
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use napi::{
+    bindgen_prelude::{
+        Buffer, FromNapiRef, FromNapiValue, ToNapiValue, TypeName, ValidateNapiValue,
+    },
+    sys, Env, JsDate, JsUnknown, NapiRaw, NapiValue, ValueType,
+};
+use napi_derive::napi;
+use tokio::sync::Mutex;
+
+use crate::TsError;
+
+/// Typescript Wrapper for [aws_smithy_types::Blob].
+///
+/// Handlers receive instances of `Blob`, and can return either a `Blob` or a `Buffer`.
+#[napi]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Blob {
+    inner: aws_smithy_types::Blob,
+}
+
+impl Blob {
+    /// Creates a new blob from the given `input`.
+    pub fn new<T: Into<Vec<u8>>>(input: T) -> Self {
+        Self {
+            inner: aws_smithy_types::Blob::new(input),
+        }
+    }
+
+    /// Consumes the `Blob` and returns a `Vec<u8>` with its contents.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.inner.into_inner()
+    }
+}
+
+impl AsRef<[u8]> for Blob {
+    fn as_ref(&self) -> &[u8] {
+        self.inner.as_ref()
+    }
+}
+
+#[napi]
+impl Blob {
+    /// Create a new Typescript instance of `Blob`.
+    #[napi(constructor)]
+    pub fn tsnew(data: Buffer) -> Self {
+        Self::new(data)
+    }
+
+    /// Typescript getter for the `Blob` byte array.
+    #[napi(getter)]
+    pub fn data(&self) -> Buffer {
+        self.as_ref().into()
+    }
+
+    /// Typescript setter for the `Blob` byte array.
+    #[napi(setter, js_name = "data")]
+    pub fn set_data(&mut self, data: Buffer) {
+        *self = Self::new(data);
+    }
+}
+
+impl FromNapiValue for Blob {
+    unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+        if <&Blob>::validate(env, napi_val).is_ok() {
+            Blob::from_napi_ref(env, napi_val).cloned()
+        } else {
+            Buffer::from_napi_value(env, napi_val).map(Self::new)
+        }
+    }
+}
+
+impl From<aws_smithy_types::Blob> for Blob {
+    fn from(other: aws_smithy_types::Blob) -> Blob {
+        Blob { inner: other }
+    }
+}
+
+impl From<Blob> for aws_smithy_types::Blob {
+    fn from(other: Blob) -> aws_smithy_types::Blob {
+        other.inner
+    }
+}
+
+impl<'blob> From<&'blob Blob> for &'blob aws_smithy_types::Blob {
+    fn from(other: &'blob Blob) -> &'blob aws_smithy_types::Blob {
+        &other.inner
+    }
+}
+
+/// Typescript Wrapper for [aws_smithy_types::date_time::DateTime].
+///
+/// Timestamps are exchanged with handlers as JavaScript `Date`s, which have a millisecond
+/// precision: sub-millisecond precision is lost when a `DateTime` is passed to a handler.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DateTime(aws_smithy_types::date_time::DateTime);
+
+impl DateTime {
+    /// Formats the `DateTime` to a string using the given `format`.
+    ///
+    /// Returns an error if the given `DateTime` cannot be represented by the desired format.
+    pub fn fmt(
+        &self,
+        format: aws_smithy_types::date_time::Format,
+    ) -> Result<String, aws_smithy_types::date_time::DateTimeFormatError> {
+        self.0.fmt(format)
+    }
+}
+
+impl TypeName for DateTime {
+    fn type_name() -> &'static str {
+        "Date"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Object
+    }
+}
+
+impl ValidateNapiValue for DateTime {
+    unsafe fn validate(
+        env: sys::napi_env,
+        napi_val: sys::napi_value,
+    ) -> napi::Result<sys::napi_value> {
+        JsDate::validate(env, napi_val)
+    }
+}
+
+impl ToNapiValue for DateTime {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+        let millis = val.0.to_millis().map_err(TsError::from)?;
+        let date = Env::from_raw(env).create_date(millis as f64)?;
+        Ok(date.raw())
+    }
+}
+
+impl FromNapiValue for DateTime {
+    unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+        let millis = JsDate::from_napi_value(env, napi_val)?.value_of()?;
+        Ok(Self(aws_smithy_types::DateTime::from_secs_f64(
+            millis / 1000.0,
+        )))
+    }
+}
+
+impl From<aws_smithy_types::DateTime> for DateTime {
+    fn from(other: aws_smithy_types::DateTime) -> DateTime {
+        DateTime(other)
+    }
+}
+
+impl Deref for DateTime {
+    type Target = aws_smithy_types::DateTime;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Typescript Wrapper for [aws_smithy_types::byte_stream::ByteStream].
+///
+/// ByteStream provides misuse-resistant primitives to make it easier to handle common patterns with streaming data.
+///
+/// On the Rust side, The Typescript implementation wraps the original [ByteStream](aws_smithy_types::byte_stream::ByteStream)
+/// in a clonable structure and implements the [Stream](futures::stream::Stream) trait for it to
+/// allow Rust to handle the type transparently.
+///
+/// On the Typescript side the stream is consumed one chunk at a time with `next()`,
+/// or all at once with `collect()`:
+///
+/// ```typescript
+/// const stream = await ByteStream.fromPath("/tmp/music.mp3");
+/// for (let chunk = await stream.next(); chunk !== null; chunk = await stream.next()) {
+///     console.log(chunk);
+/// }
+/// ```
+///
+/// The original Rust [ByteStream](aws_smithy_types::byte_stream::ByteStream) is wrapped inside a `Arc<Mutex>` to allow the type to be
+/// [Clone] and to allow internal mutability, required to fetch the next chunk of data.
+#[napi]
+#[derive(Debug, Clone)]
+pub struct ByteStream {
+    inner: Arc<Mutex<aws_smithy_types::byte_stream::ByteStream>>,
+}
+
+impl futures::stream::Stream for ByteStream {
+    type Item = Result<Bytes, aws_smithy_types::byte_stream::error::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = self.inner.lock();
+        tokio::pin!(stream);
+        match stream.poll(cx) {
+            Poll::Ready(mut stream) => Pin::new(&mut *stream).poll_next(cx),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl ByteStream {
+    /// Construct a new [`ByteStream`](aws_smithy_types::byte_stream::ByteStream) from a
+    /// [`SdkBody`](aws_smithy_types::body::SdkBody).
+    ///
+    /// This method is available only to Rust and it is required to comply with the
+    /// interface required by the code generator.
+    pub fn new(body: aws_smithy_types::body::SdkBody) -> Self {
+        aws_smithy_types::byte_stream::ByteStream::new(body).into()
+    }
+}
+
+impl Default for ByteStream {
+    fn default() -> Self {
+        Self::new(aws_smithy_types::body::SdkBody::from(""))
+    }
+}
+
+impl From<aws_smithy_types::byte_stream::ByteStream> for ByteStream {
+    fn from(other: aws_smithy_types::byte_stream::ByteStream) -> ByteStream {
+        ByteStream {
+            inner: Arc::new(Mutex::new(other)),
+        }
+    }
+}
+
+#[napi]
+impl ByteStream {
+    /// Create a new [ByteStream](aws_smithy_types::byte_stream::ByteStream) from a `Buffer`.
+    #[napi(constructor)]
+    pub fn tsnew(input: Buffer) -> Self {
+        Self::new(aws_smithy_types::body::SdkBody::from(input.to_vec()))
+    }
+
+    /// Create a new [ByteStream](aws_smithy_types::byte_stream::ByteStream) from a path.
+    #[napi]
+    pub async fn from_path(path: String) -> napi::Result<ByteStream> {
+        let byte_stream = aws_smithy_types::byte_stream::ByteStream::from_path(path)
+            .await
+            .map_err(TsError::from)?;
+        Ok(byte_stream.into())
+    }
+
+    /// Return the next chunk of data from the stream, or `null` if the stream is exhausted.
+    #[napi]
+    pub async fn next(&self) -> napi::Result<Option<Buffer>> {
+        let mut stream = self.inner.lock().await;
+        let chunk = stream.next().await.transpose().map_err(TsError::from)?;
+        Ok(chunk.map(|chunk| chunk.to_vec().into()))
+    }
+
+    /// Read all the remaining data from the stream.
+    #[napi]
+    pub async fn collect(&self) -> napi::Result<Buffer> {
+        let mut stream = self.inner.lock().await;
+        let stream = std::mem::take(&mut *stream);
+        let data = stream.collect().await.map_err(TsError::from)?;
+        Ok(data.to_vec().into())
+    }
+}
+
+impl FromNapiValue for ByteStream {
+    unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+        if <&ByteStream>::validate(env, napi_val).is_ok() {
+            ByteStream::from_napi_ref(env, napi_val).cloned()
+        } else {
+            Buffer::from_napi_value(env, napi_val).map(Self::tsnew)
+        }
+    }
+}
+
+/// Typescript Wrapper for [aws_smithy_types::Document].
+///
+/// Documents are exchanged with handlers as plain JavaScript values: objects, arrays, numbers,
+/// strings, booleans and `null`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document(aws_smithy_types::Document);
+
+impl TypeName for Document {
+    fn type_name() -> &'static str {
+        "Document"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Unknown
+    }
+}
+
+impl ValidateNapiValue for Document {}
+
+impl ToNapiValue for Document {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> napi::Result<sys::napi_value> {
+        use aws_smithy_types::{Document as D, Number};
+
+        match val.0 {
+            D::Object(obj) => HashMap::to_napi_value(
+                env,
+                obj.into_iter()
+                    .map(|(k, v)| (k, Document(v)))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            D::Array(vec) => {
+                Vec::to_napi_value(env, vec.into_iter().map(Document).collect::<Vec<_>>())
+            }
+            D::Number(Number::Float(f)) => f64::to_napi_value(env, f),
+            D::Number(Number::PosInt(pi)) => f64::to_napi_value(env, pi as f64),
+            D::Number(Number::NegInt(ni)) => i64::to_napi_value(env, ni),
+            D::String(str) => String::to_napi_value(env, str),
+            D::Bool(bool) => bool::to_napi_value(env, bool),
+            D::Null => Env::from_raw(env).get_null().map(|null| null.raw()),
+        }
+    }
+}
+
+impl FromNapiValue for Document {
+    unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+        use aws_smithy_types::Document as D;
+
+        let value = JsUnknown::from_raw_unchecked(env, napi_val);
+        match value.get_type()? {
+            ValueType::Object if value.is_array()? => {
+                let vec = Vec::<Document>::from_napi_value(env, napi_val)?;
+                Ok(Self(D::Array(vec.into_iter().map(|d| d.0).collect())))
+            }
+            ValueType::Object => {
+                let obj = HashMap::<String, Document>::from_napi_value(env, napi_val)?;
+                Ok(Self(D::Object(
+                    obj.into_iter().map(|(k, v)| (k, v.0)).collect(),
+                )))
+            }
+            ValueType::Boolean => Ok(Self(D::Bool(bool::from_napi_value(env, napi_val)?))),
+            ValueType::Number => {
+                let f = f64::from_napi_value(env, napi_val)?;
+                Ok(Self(D::Number(number_from_f64(f))))
+            }
+            ValueType::String => Ok(Self(D::String(String::from_napi_value(env, napi_val)?))),
+            ValueType::Null | ValueType::Undefined => Ok(Self(D::Null)),
+            other => Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("'{other}' cannot be converted to 'Document'"),
+            )),
+        }
+    }
+}
+
+/// JavaScript only has floating point numbers, integers are converted back to integers.
+fn number_from_f64(f: f64) -> aws_smithy_types::Number {
+    use aws_smithy_types::Number;
+
+    if f.fract() != 0.0 || !f.is_finite() {
+        Number::Float(f)
+    } else if f >= 0.0 && f <= u64::MAX as f64 {
+        Number::PosInt(f as u64)
+    } else if f < 0.0 && f >= i64::MIN as f64 {
+        Number::NegInt(f as i64)
+    } else {
+        Number::Float(f)
+    }
+}
+
+impl Deref for Document {
+    type Target = aws_smithy_types::Document;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<aws_smithy_types::Document> for Document {
+    fn from(other: aws_smithy_types::Document) -> Document {
+        Document(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_smithy_types::Number;
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[test]
+    fn blob_can_be_converted_to_and_from_aws_smithy_types() {
+        let blob = Blob::new("some data");
+        assert_eq!(b"some data", blob.as_ref());
+        let inner: aws_smithy_types::Blob = blob.clone().into();
+        assert_eq!(blob, Blob::from(inner));
+        assert_eq!(b"some data".to_vec(), blob.into_inner());
+    }
+
+    #[test]
+    fn datetime_derefs_to_aws_smithy_types() {
+        let datetime = DateTime::from(aws_smithy_types::DateTime::from_secs(1_576_540_098));
+        assert_eq!(1_576_540_098, datetime.secs());
+        assert_eq!(
+            "2019-12-16T23:48:18Z",
+            datetime
+                .fmt(aws_smithy_types::date_time::Format::DateTime)
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn bytestream_can_be_streamed_in_rust() {
+        let mut stream = ByteStream::new(aws_smithy_types::body::SdkBody::from("some data"));
+        let mut data = Vec::new();
+        while let Some(chunk) = StreamExt::next(&mut stream).await {
+            data.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(b"some data".to_vec(), data);
+        assert!(StreamExt::next(&mut ByteStream::default()).await.is_none());
+    }
+
+    #[test]
+    fn numbers_are_converted_back_to_integers() {
+        assert_eq!(Number::PosInt(42), number_from_f64(42.0));
+        assert_eq!(Number::NegInt(-42), number_from_f64(-42.0));
+        assert_eq!(Number::Float(4.2), number_from_f64(4.2));
+        assert!(matches!(number_from_f64(f64::NAN), Number::Float(f) if f.is_nan()));
+        assert_eq!(Number::Float(f64::INFINITY), number_from_f64(f64::INFINITY));
+    }
+}
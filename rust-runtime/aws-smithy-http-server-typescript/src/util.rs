@@ -0,0 +1,29 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::mem::ManuallyDrop;
+
+use napi::{Env, NapiRaw, NapiValue, Ref};
+
+/// A reference to a JavaScript value that can be moved to the server threads.
+///
+/// The value can only be accessed with an [Env], which is only available on the JavaScript
+/// thread, so moving the reference around is safe. The referenced values (handlers and
+/// context) live as long as the application, so the reference is never released.
+pub(crate) struct JsRef(ManuallyDrop<Ref<()>>);
+
+// SAFETY: the reference is only dereferenced on the JavaScript thread, see above.
+unsafe impl Send for JsRef {}
+unsafe impl Sync for JsRef {}
+
+impl JsRef {
+    pub(crate) fn new<T: NapiRaw>(env: &Env, value: T) -> napi::Result<Self> {
+        Ok(Self(ManuallyDrop::new(env.create_reference(value)?)))
+    }
+
+    pub(crate) fn get<T: NapiValue>(&self, env: &Env) -> napi::Result<T> {
+        env.get_reference_value(&self.0)
+    }
+}
@@ -0,0 +1,37 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_query::error::ec2_query_error;
+
+use crate::body::BoxBody;
+use crate::extension::RuntimeErrorExtension;
+use crate::response::IntoResponse;
+use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+
+use super::Ec2Query;
+
+pub use crate::protocol::query::router::*;
+
+/// A [`QueryRouter`] for the [`Ec2Query`] protocol.
+pub type Ec2QueryRouter<S> = QueryRouter<S, Ec2Query>;
+
+impl IntoResponse<Ec2Query> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        match self {
+            Error::MethodNotAllowed => method_disallowed(),
+            _ => http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .header(http::header::CONTENT_TYPE, "text/xml")
+                .extension(RuntimeErrorExtension::new(
+                    UNKNOWN_OPERATION_EXCEPTION.to_string(),
+                ))
+                .body(crate::body::to_boxed(ec2_query_error(
+                    UNKNOWN_OPERATION_EXCEPTION,
+                    Some(&self.to_string()),
+                )))
+                .expect("invalid HTTP response for EC2 Query routing error; please file a bug report under https://github.com/awslabs/smithy-rs/issues"),
+        }
+    }
+}
@@ -0,0 +1,322 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use aws_smithy_query::deserialize::{DeserializeError, QueryDocument};
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use http_body::Body as HttpBody;
+use tower::Layer;
+use tower::Service;
+use tower::ServiceExt;
+
+use crate::body::BoxBody;
+use crate::error::BoxError;
+use crate::response::IntoResponse;
+use crate::routing::tiny_map::TinyMap;
+use crate::routing::Route;
+use crate::routing::Router;
+
+use thiserror::Error;
+
+/// An AWS Query or EC2 Query routing error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Relative URI was not "/".
+    #[error("relative URI is not \"/\"")]
+    NotRootUrl,
+    /// Method was not `POST`.
+    #[error("method not POST")]
+    MethodNotAllowed,
+    /// The request body could not be read.
+    #[error("failed to read request body: {0}")]
+    BufferBody(crate::Error),
+    /// The request body is not a valid query string.
+    #[error("failed to parse request body: {0}")]
+    InvalidBody(DeserializeError),
+    /// Missing the `Action` parameter.
+    #[error("missing the \"Action\" parameter")]
+    MissingAction,
+    /// Operation not found.
+    #[error("operation not found")]
+    NotFound,
+}
+
+// A route is never left in an inconsistent state while locked, so a poisoned lock can be ignored.
+fn lock<S>(route: &Mutex<S>) -> std::sync::MutexGuard<'_, S> {
+    route.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn into_inner<S>(route: Mutex<S>) -> S {
+    route.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// This constant determines when the `TinyMap` implementation switches from being a `Vec` to a
+// `HashMap`. This is chosen to be 15 as a result of the discussion around
+// https://github.com/awslabs/smithy-rs/pull/1429#issuecomment-1147516546
+const ROUTE_CUTOFF: usize = 15;
+
+/// A [`Router`] supporting the [`AWS Query`] and [`EC2 Query`] protocols.
+///
+/// Both protocols identify the operation with the `Action` parameter of the form-urlencoded request body, so
+/// the route can only be chosen once the body has been read. [`QueryRouter::match_route`] therefore only checks
+/// the method and URI, and returns a [`QueryRoute`] which buffers the body before dispatching the request.
+///
+/// [AWS Query]: https://smithy.io/2.0/aws/protocols/aws-query-protocol.html
+/// [EC2 Query]: https://smithy.io/2.0/aws/protocols/aws-ec2-query-protocol.html
+pub struct QueryRouter<S, P> {
+    routes: Arc<TinyMap<String, Mutex<S>, ROUTE_CUTOFF>>,
+    _protocol: PhantomData<fn() -> P>,
+}
+
+impl<S, P> fmt::Debug for QueryRouter<S, P>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryRouter").field("routes", &self.routes).finish()
+    }
+}
+
+impl<S, P> Clone for QueryRouter<S, P> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S, P> QueryRouter<S, P>
+where
+    S: Clone,
+{
+    fn into_routes(self) -> Vec<(String, S)> {
+        match Arc::try_unwrap(self.routes) {
+            Ok(routes) => routes
+                .into_iter()
+                .map(|(key, route)| (key, into_inner(route)))
+                .collect(),
+            Err(routes) => routes
+                .iter()
+                .map(|(key, route)| (key.clone(), lock(route).clone()))
+                .collect(),
+        }
+    }
+
+    /// Applies a [`Layer`] uniformly to all routes.
+    pub fn layer<L>(self, layer: L) -> QueryRouter<L::Service, P>
+    where
+        L: Layer<S>,
+    {
+        QueryRouter {
+            routes: Arc::new(
+                self.into_routes()
+                    .into_iter()
+                    .map(|(key, route)| (key, Mutex::new(layer.layer(route))))
+                    .collect(),
+            ),
+            _protocol: PhantomData,
+        }
+    }
+
+    /// Applies type erasure to the inner route using [`Route::new`].
+    pub fn boxed<B>(self) -> QueryRouter<Route<B>, P>
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        QueryRouter {
+            routes: Arc::new(
+                self.into_routes()
+                    .into_iter()
+                    .map(|(key, s)| (key, Mutex::new(Route::new(s))))
+                    .collect(),
+            ),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<B, S, P> Router<B> for QueryRouter<S, P> {
+    type Service = QueryRoute<S, P>;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<Self::Service, Self::Error> {
+        // The URI must be root,
+        if request.uri().path() != "/" {
+            return Err(Error::NotRootUrl);
+        }
+
+        // Only `Method::POST` is allowed.
+        if request.method() != http::Method::POST {
+            return Err(Error::MethodNotAllowed);
+        }
+
+        // The `Action` parameter is in the body, which is only read when the request is dispatched.
+        Ok(QueryRoute {
+            routes: self.routes.clone(),
+            _protocol: PhantomData,
+        })
+    }
+}
+
+impl<S, P> FromIterator<(String, S)> for QueryRouter<S, P> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (String, S)>>(iter: T) -> Self {
+        Self {
+            routes: Arc::new(iter.into_iter().map(|(key, route)| (key, Mutex::new(route))).collect()),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+/// A [`Service`] which reads the body of an AWS Query or EC2 Query request, and dispatches it to the route
+/// named by its `Action` parameter.
+///
+/// Routing errors found after reading the body are converted into responses using the protocol `P`.
+pub struct QueryRoute<S, P> {
+    // Routes are not required to be `Sync`, but are shared with the futures of in-flight requests. Each one is
+    // therefore guarded by a `Mutex`, which is only held while the route is cloned.
+    routes: Arc<TinyMap<String, Mutex<S>, ROUTE_CUTOFF>>,
+    _protocol: PhantomData<fn() -> P>,
+}
+
+impl<S, P> fmt::Debug for QueryRoute<S, P>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryRoute").field("routes", &self.routes).finish()
+    }
+}
+
+impl<S, P> Clone for QueryRoute<S, P> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: self.routes.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S, P> QueryRoute<S, P> {
+    fn route(&self, body: &[u8]) -> Result<S, Error>
+    where
+        S: Clone,
+    {
+        let document = QueryDocument::parse(body).map_err(Error::InvalidBody)?;
+        let action = document.action().ok_or(Error::MissingAction)?;
+        self.routes
+            .get(action)
+            .map(|route| lock(route).clone())
+            .ok_or(Error::NotFound)
+    }
+}
+
+impl<B, S, P> Service<http::Request<B>> for QueryRoute<S, P>
+where
+    B: HttpBody + From<Bytes> + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    P: 'static,
+    Error: IntoResponse<P>,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(err) => return Ok(Error::BufferBody(crate::Error::new(err)).into_response()),
+            };
+            match this.route(&body) {
+                Ok(route) => route.oneshot(http::Request::from_parts(parts, B::from(body))).await,
+                Err(error) => {
+                    tracing::debug!(%error, "failed to route");
+                    Ok(error.into_response())
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{protocol::test_helpers::req, routing::Router};
+
+    use http::Method;
+    use pretty_assertions::assert_eq;
+    use tower::service_fn;
+
+    struct TestProtocol;
+
+    impl IntoResponse<TestProtocol> for Error {
+        fn into_response(self) -> http::Response<BoxBody> {
+            http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .body(crate::body::to_boxed(self.to_string()))
+                .unwrap()
+        }
+    }
+
+    fn router() -> QueryRouter<Route<hyper::Body>, TestProtocol> {
+        ["Operation", "Other"]
+            .into_iter()
+            .map(|operation| {
+                let svc = service_fn(move |_: http::Request<hyper::Body>| async move {
+                    Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed(operation)))
+                });
+                (operation.to_string(), Route::new(svc))
+            })
+            .collect()
+    }
+
+    async fn call(body: &'static str) -> String {
+        let request = http::Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .body(hyper::Body::from(body))
+            .unwrap();
+        let route = router().match_route(&request).unwrap();
+        let response = route.oneshot(request).await.unwrap();
+        crate::protocol::test_helpers::get_body_as_string(response.into_body()).await
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        assert_eq!("Operation", call("Action=Operation&Version=2020-01-01").await);
+        assert_eq!("Other", call("Version=2020-01-01&Action=Other&Foo.member.1=bar").await);
+        assert_eq!(Error::NotFound.to_string(), call("Action=Missing").await);
+        assert_eq!(Error::MissingAction.to_string(), call("Version=2020-01-01").await);
+
+        let router = router();
+
+        // Wrong HTTP method, should return `MethodNotAllowed`.
+        let res = router.match_route(&req(&Method::GET, "/", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::MethodNotAllowed.to_string());
+
+        // Wrong URI, should return `NotRootUrl`.
+        let res = router.match_route(&req(&Method::POST, "/something", None));
+        assert_eq!(res.unwrap_err().to_string(), Error::NotRootUrl.to_string());
+    }
+}
@@ -0,0 +1,230 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+
+use tower::Layer;
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+use crate::extension::RuntimeErrorExtension;
+use crate::response::IntoResponse;
+use crate::routing::tiny_map::TinyMap;
+use crate::routing::{method_disallowed, Route, Router, UNKNOWN_OPERATION_EXCEPTION};
+
+use http::header::ToStrError;
+use thiserror::Error;
+
+use super::RpcV2Cbor;
+
+/// The header every request and response of the protocol carries.
+pub const SMITHY_PROTOCOL_HEADER: &str = "smithy-protocol";
+
+/// The value of the `smithy-protocol` header for this protocol.
+pub const SMITHY_PROTOCOL_VALUE: &str = "rpc-v2-cbor";
+
+/// An RPC v2 CBOR routing error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Method was not `POST`.
+    #[error("method not POST")]
+    MethodNotAllowed,
+    /// Missing the `smithy-protocol` header.
+    #[error("missing the \"smithy-protocol\" header")]
+    MissingHeader,
+    /// Unable to parse header into UTF-8.
+    #[error("failed to parse header: {0}")]
+    InvalidHeader(ToStrError),
+    /// The `smithy-protocol` header does not name this protocol.
+    #[error("\"smithy-protocol\" header is not \"rpc-v2-cbor\"")]
+    WrongProtocol,
+    /// The URI path does not end in `/service/{ServiceName}/operation/{OperationName}`.
+    #[error("URI path does not match \"/service/{{ServiceName}}/operation/{{OperationName}}\"")]
+    InvalidUri,
+    /// Operation not found.
+    #[error("operation not found")]
+    NotFound,
+}
+
+// This constant determines when the `TinyMap` implementation switches from being a `Vec` to a
+// `HashMap`. This is chosen to be 15 as a result of the discussion around
+// https://github.com/awslabs/smithy-rs/pull/1429#issuecomment-1147516546
+const ROUTE_CUTOFF: usize = 15;
+
+/// A [`Router`] supporting the [`Smithy RPC v2 CBOR`] protocol.
+///
+/// Routes are keyed by `ServiceName.OperationName`, where both names are taken from the last four
+/// segments of the request's URI path: `/service/{ServiceName}/operation/{OperationName}`.
+///
+/// [Smithy RPC v2 CBOR]: https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html
+#[derive(Debug, Clone)]
+pub struct RpcV2CborRouter<S> {
+    routes: TinyMap<String, S, ROUTE_CUTOFF>,
+}
+
+impl<S> RpcV2CborRouter<S> {
+    /// Applies a [`Layer`] uniformly to all routes.
+    pub fn layer<L>(self, layer: L) -> RpcV2CborRouter<L::Service>
+    where
+        L: Layer<S>,
+    {
+        RpcV2CborRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(key, route)| (key, layer.layer(route)))
+                .collect(),
+        }
+    }
+
+    /// Applies type erasure to the inner route using [`Route::new`].
+    pub fn boxed<B>(self) -> RpcV2CborRouter<Route<B>>
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        RpcV2CborRouter {
+            routes: self.routes.into_iter().map(|(key, s)| (key, Route::new(s))).collect(),
+        }
+    }
+}
+
+/// Extracts `ServiceName.OperationName` from a path ending in
+/// `/service/{ServiceName}/operation/{OperationName}`.
+fn route_key(path: &str) -> Option<String> {
+    let mut segments = path.rsplit('/');
+    let operation = segments.next()?;
+    if segments.next()? != "operation" {
+        return None;
+    }
+    let service = segments.next()?;
+    if segments.next()? != "service" || operation.is_empty() || service.is_empty() {
+        return None;
+    }
+    Some(format!("{service}.{operation}"))
+}
+
+impl<B, S> Router<B> for RpcV2CborRouter<S>
+where
+    S: Clone,
+{
+    type Service = S;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
+        // Only `Method::POST` is allowed.
+        if request.method() != http::Method::POST {
+            return Err(Error::MethodNotAllowed);
+        }
+
+        // The `smithy-protocol` header must name this protocol.
+        let protocol = request
+            .headers()
+            .get(SMITHY_PROTOCOL_HEADER)
+            .ok_or(Error::MissingHeader)?;
+        if protocol.to_str().map_err(Error::InvalidHeader)? != SMITHY_PROTOCOL_VALUE {
+            return Err(Error::WrongProtocol);
+        }
+
+        let key = route_key(request.uri().path()).ok_or(Error::InvalidUri)?;
+
+        // Lookup in the `TinyMap` for a route for the operation.
+        let route = self.routes.get(&key).ok_or(Error::NotFound)?;
+        Ok(route.clone())
+    }
+}
+
+impl<S> FromIterator<(String, S)> for RpcV2CborRouter<S> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (String, S)>>(iter: T) -> Self {
+        Self {
+            routes: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        match self {
+            Error::MethodNotAllowed => method_disallowed(),
+            _ => http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .header(http::header::CONTENT_TYPE, "application/cbor")
+                .header(SMITHY_PROTOCOL_HEADER, SMITHY_PROTOCOL_VALUE)
+                .extension(RuntimeErrorExtension::new(
+                    UNKNOWN_OPERATION_EXCEPTION.to_string(),
+                ))
+                .body(empty())
+                .expect("invalid HTTP response for RPC v2 CBOR routing error; please file a bug report under https://github.com/awslabs/smithy-rs/issues"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{protocol::test_helpers::req, routing::Router};
+
+    use http::{HeaderMap, HeaderValue, Method};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn route_keys() {
+        assert_eq!(
+            Some("Service.Operation".to_string()),
+            route_key("/service/Service/operation/Operation")
+        );
+        assert_eq!(
+            Some("Service.Operation".to_string()),
+            route_key("/prefix/service/Service/operation/Operation")
+        );
+        assert_eq!(None, route_key("/service/Service/operation/"));
+        assert_eq!(None, route_key("/service/Service/operation/Operation/"));
+        assert_eq!(None, route_key("/service/Service/Operation"));
+        assert_eq!(None, route_key("/"));
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        let routes = vec![("Service.Operation")];
+        let router: RpcV2CborRouter<_> = routes
+            .clone()
+            .into_iter()
+            .map(|operation| (operation.to_string(), ()))
+            .collect();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(SMITHY_PROTOCOL_HEADER, HeaderValue::from_static(SMITHY_PROTOCOL_VALUE));
+        let uri = "/service/Service/operation/Operation";
+
+        // Valid request, should match.
+        router
+            .match_route(&req(&Method::POST, uri, Some(headers.clone())))
+            .unwrap();
+
+        // No headers, should return `MissingHeader`.
+        let res = router.match_route(&req(&Method::POST, uri, None));
+        assert_eq!(res.unwrap_err().to_string(), Error::MissingHeader.to_string());
+
+        // Another protocol, should return `WrongProtocol`.
+        let mut wrong_headers = HeaderMap::new();
+        wrong_headers.insert(SMITHY_PROTOCOL_HEADER, HeaderValue::from_static("rpc-v2-json"));
+        let res = router.match_route(&req(&Method::POST, uri, Some(wrong_headers)));
+        assert_eq!(res.unwrap_err().to_string(), Error::WrongProtocol.to_string());
+
+        // Wrong HTTP method, should return `MethodNotAllowed`.
+        let res = router.match_route(&req(&Method::GET, uri, Some(headers.clone())));
+        assert_eq!(res.unwrap_err().to_string(), Error::MethodNotAllowed.to_string());
+
+        // Malformed URI, should return `InvalidUri`.
+        let res = router.match_route(&req(&Method::POST, "/", Some(headers.clone())));
+        assert_eq!(res.unwrap_err().to_string(), Error::InvalidUri.to_string());
+
+        // Unknown operation, should return `NotFound`.
+        let res = router.match_route(&req(&Method::POST, "/service/Service/operation/Missing", Some(headers)));
+        assert_eq!(res.unwrap_err().to_string(), Error::NotFound.to_string());
+    }
+}
@@ -114,6 +114,14 @@ where
             TinyMapInner::HashMap(hash_map) => hash_map.get(key),
         }
     }
+
+    /// Returns an iterator over the entries of the map, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        match &self.inner {
+            TinyMapInner::Vec(vec) => OrIterator::Left(vec.iter().map(|(key, value)| (key, value))),
+            TinyMapInner::HashMap(hash_map) => OrIterator::Right(hash_map.iter()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +187,16 @@ mod tests {
         });
     }
 
+    #[test]
+    fn iter() {
+        for values in [&SMALL_VALUES[..], &MEDIUM_VALUES[..], &LARGE_VALUES[..]] {
+            let tiny_map: TinyMap<_, _, CUTOFF> = values.iter().copied().collect();
+            let mut entries: Vec<_> = tiny_map.iter().map(|(key, value)| (*key, *value)).collect();
+            entries.sort();
+            assert_eq!(entries, values);
+        }
+    }
+
     #[test]
     fn get_small_fail() {
         let tiny_map: TinyMap<_, _, CUTOFF> = SMALL_VALUES.into_iter().collect();
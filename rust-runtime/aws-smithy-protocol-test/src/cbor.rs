@@ -0,0 +1,158 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::{pretty_comparison, ProtocolTestFailure};
+use aws_smithy_cbor::data::Type;
+use aws_smithy_cbor::{Decoder, DeserializeError};
+
+/// A CBOR data item, normalized so that encodings with the same meaning compare equal
+#[derive(Debug)]
+enum Value {
+    Integer(i128),
+    Float(f64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    /// Entries are sorted by the debug representation of their keys
+    Map(Vec<(Value, Value)>),
+    Tag(u64, Box<Value>),
+    Bool(bool),
+    Null,
+    Undefined,
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        use Value::*;
+        match (self, other) {
+            (Integer(a), Integer(b)) => a == b,
+            // All NaN values are considered equal, regardless of width or payload
+            (Float(a), Float(b)) => (a.is_nan() && b.is_nan()) || a == b,
+            (Bytes(a), Bytes(b)) => a == b,
+            (Text(a), Text(b)) => a == b,
+            (Array(a), Array(b)) => a == b,
+            (Map(a), Map(b)) => a == b,
+            (Tag(a, a_value), Tag(b, b_value)) => a == b && a_value == b_value,
+            (Bool(a), Bool(b)) => a == b,
+            (Null, Null) | (Undefined, Undefined) => true,
+            _ => false,
+        }
+    }
+}
+
+fn decode_value(decoder: &mut Decoder<'_>) -> Result<Value, DeserializeError> {
+    Ok(match decoder.datatype()? {
+        Type::UnsignedInt => Value::Integer(decoder.unsigned()?.into()),
+        Type::NegativeInt => Value::Integer(decoder.long()?.into()),
+        Type::F16 | Type::F32 | Type::F64 => Value::Float(decoder.double()?),
+        Type::Bytes | Type::BytesIndef => Value::Bytes(decoder.bytes()?.into_owned()),
+        Type::String | Type::StringIndef => Value::Text(decoder.string()?),
+        Type::Array | Type::ArrayIndef => {
+            Value::Array(decoder.array_items(Vec::new(), |mut values, decoder| {
+                values.push(decode_value(decoder)?);
+                Ok(values)
+            })?)
+        }
+        Type::Map | Type::MapIndef => {
+            let mut entries = decoder.map_entries(Vec::new(), |mut entries, decoder| {
+                let key = decode_value(decoder)?;
+                entries.push((key, decode_value(decoder)?));
+                Ok(entries)
+            })?;
+            entries.sort_by_cached_key(|(key, _)| format!("{key:?}"));
+            Value::Map(entries)
+        }
+        Type::Tag => {
+            let tag = decoder.tag()?;
+            Value::Tag(tag, Box::new(decode_value(decoder)?))
+        }
+        Type::Bool => Value::Bool(decoder.boolean()?),
+        Type::Null => {
+            decoder.null()?;
+            Value::Null
+        }
+        Type::Undefined => {
+            decoder.skip()?;
+            Value::Undefined
+        }
+        other => {
+            return Err(DeserializeError::custom(format!(
+                "unsupported data item: {other:?}"
+            )))
+        }
+    })
+}
+
+fn decode(input: &[u8]) -> Result<Option<Value>, DeserializeError> {
+    if input.is_empty() {
+        return Ok(None);
+    }
+    let mut decoder = Decoder::new(input);
+    let value = decode_value(&mut decoder)?;
+    if !decoder.is_empty() {
+        return Err(DeserializeError::custom("trailing data after data item")
+            .with_offset(decoder.position()));
+    }
+    Ok(Some(value))
+}
+
+/// Compares two CBOR bodies semantically.
+///
+/// The expected body is base64 encoded, as it appears in Smithy protocol tests. Map entry order,
+/// definite vs. indefinite lengths, and the width used to encode numbers are not significant.
+pub(crate) fn try_cbor_eq(expected: &str, actual: &[u8]) -> Result<(), ProtocolTestFailure> {
+    let expected_bytes =
+        aws_smithy_types::base64::decode(expected).expect("expected body must be valid base64");
+    let expected_value = decode(&expected_bytes).expect("expected body must be valid CBOR");
+    let actual_value = decode(actual).map_err(|e| ProtocolTestFailure::InvalidBodyFormat {
+        expected: "cbor".to_owned(),
+        found: format!("{e}: {}", aws_smithy_types::base64::encode(actual)),
+    })?;
+    if expected_value == actual_value {
+        Ok(())
+    } else {
+        Err(ProtocolTestFailure::BodyDidNotMatch {
+            comparison: pretty_comparison(
+                &format!("{expected_value:#?}"),
+                &format!("{actual_value:#?}"),
+            ),
+            hint: "CBOR bodies are shown decoded, with map entries sorted by key".into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::try_cbor_eq;
+    use aws_smithy_types::base64;
+
+    #[test]
+    fn encodings_with_the_same_meaning_are_equivalent() {
+        // {"a": 1, "b": [1.5, "xy"]}, definite lengths, preferred float width
+        let expected = base64::encode(b"\xa2\x61a\x01\x61b\x82\xf9\x3e\x00\x62xy");
+        // The same value with indefinite lengths, reordered keys, and a chunked string
+        let actual = b"\xbf\x61b\x9f\xfb\x3f\xf8\x00\x00\x00\x00\x00\x00\x7f\x61x\x61y\xff\xff\x61a\x18\x01\xff";
+        try_cbor_eq(&expected, actual).expect("bodies are equivalent");
+    }
+
+    #[test]
+    fn different_values_are_not_equivalent() {
+        let expected = base64::encode(b"\xa1\x61a\x01");
+        try_cbor_eq(&expected, b"\xa1\x61a\x02").expect_err("values differ");
+        try_cbor_eq(&expected, b"\xa1\x61a\xf9\x3c\x00").expect_err("integers are not floats");
+        try_cbor_eq(&expected, b"").expect_err("body is missing");
+        try_cbor_eq(&expected, b"\xa1\x61a").expect_err("body is truncated");
+    }
+
+    #[test]
+    fn nan_and_empty_bodies() {
+        try_cbor_eq(
+            &base64::encode(b"\xf9\x7e\x00"),
+            b"\xfb\x7f\xf8\x00\x00\x00\x00\x00\x01",
+        )
+        .expect("all NaN values are equal");
+        try_cbor_eq("", b"").expect("empty bodies are equal");
+    }
+}
@@ -10,9 +10,11 @@
     rust_2018_idioms
 )]
 
+mod cbor;
 mod urlencoded;
 mod xml;
 
+use crate::cbor::try_cbor_eq;
 use crate::sealed::GetNormalizedHeader;
 use crate::xml::try_xml_equivalent;
 use assert_json_diff::assert_json_eq_no_panic;
@@ -309,6 +311,8 @@ pub enum MediaType {
     Xml,
     /// For x-www-form-urlencoded, do some map order comparison shenanigans
     UrlEncodedForm,
+    /// CBOR media types are decoded from base64, deserialized and compared
+    Cbor,
     /// Other media types are compared literally
     Other(String),
 }
@@ -320,6 +324,7 @@ impl<T: AsRef<str>> From<T> for MediaType {
             "application/x-amz-json-1.1" => MediaType::Json,
             "application/xml" => MediaType::Xml,
             "application/x-www-form-urlencoded" => MediaType::UrlEncodedForm,
+            "application/cbor" => MediaType::Cbor,
             other => MediaType::Other(other.to_string()),
         }
     }
@@ -330,6 +335,9 @@ pub fn validate_body<T: AsRef<[u8]>>(
     expected_body: &str,
     media_type: MediaType,
 ) -> Result<(), ProtocolTestFailure> {
+    if let MediaType::Cbor = media_type {
+        return try_cbor_eq(expected_body, actual_body.as_ref());
+    }
     let body_str = std::str::from_utf8(actual_body.as_ref());
     match (media_type, body_str) {
         (MediaType::Json, Ok(actual_body)) => try_json_eq(expected_body, actual_body),
@@ -364,6 +372,7 @@ pub fn validate_body<T: AsRef<[u8]>>(
         (MediaType::Other(_), Err(_)) => {
             unimplemented!("binary/non-utf8 formats not yet supported")
         }
+        (MediaType::Cbor, _) => unreachable!("CBOR bodies are validated above"),
     }
 }
 
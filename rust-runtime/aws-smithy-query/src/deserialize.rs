@@ -0,0 +1,479 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Deserialization of `application/x-www-form-urlencoded` AWS Query and EC2 Query request bodies.
+//!
+//! A request body such as `Action=Op&Version=2020-01-01&List.member.1=a&List.member.2=b` is parsed into
+//! a tree keyed by the `.`-separated segments of each parameter name. Values are then read out of the
+//! tree with [`QueryValue`], which understands the list and map conventions of both protocols.
+
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::primitive::Parse;
+use aws_smithy_types::{base64, Blob, DateTime};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+enum DeserializeErrorKind {
+    InvalidUtf8,
+    InvalidEscape {
+        input: String,
+    },
+    DuplicateParameter {
+        name: String,
+    },
+    Custom {
+        message: Cow<'static, str>,
+        path: Option<String>,
+    },
+}
+
+/// An error that occurred while parsing or reading a Query request body.
+#[derive(Debug)]
+pub struct DeserializeError {
+    kind: DeserializeErrorKind,
+}
+
+impl DeserializeError {
+    /// Creates a custom error with the given message.
+    pub fn custom(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: DeserializeErrorKind::Custom {
+                message: message.into(),
+                path: None,
+            },
+        }
+    }
+
+    fn at(path: &str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: DeserializeErrorKind::Custom {
+                message: message.into(),
+                path: Some(path.into()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DeserializeErrorKind::*;
+        match &self.kind {
+            InvalidUtf8 => write!(f, "query body is not valid UTF-8"),
+            InvalidEscape { input } => write!(f, "invalid percent-encoding: {input}"),
+            DuplicateParameter { name } => write!(f, "duplicate query parameter `{name}`"),
+            Custom {
+                message,
+                path: Some(path),
+            } => write!(f, "{message} (at `{path}`)"),
+            Custom {
+                message,
+                path: None,
+            } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for DeserializeError {}
+
+#[derive(Debug, Default)]
+struct Node {
+    /// The full, `.`-separated name of the parameter this node represents
+    path: String,
+    value: Option<String>,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn child(&mut self, segment: &str) -> &mut Node {
+        let parent_path = &self.path;
+        self.children
+            .entry(segment.to_string())
+            .or_insert_with(|| Node {
+                path: if parent_path.is_empty() {
+                    segment.to_string()
+                } else {
+                    format!("{parent_path}.{segment}")
+                },
+                ..Default::default()
+            })
+    }
+}
+
+fn decode_component(input: &str) -> Result<String, DeserializeError> {
+    // `application/x-www-form-urlencoded` encodes spaces as `+`
+    let input = input.replace('+', " ");
+    urlencoding::decode(&input)
+        .map(Cow::into_owned)
+        .map_err(|_| DeserializeError {
+            kind: DeserializeErrorKind::InvalidEscape { input },
+        })
+}
+
+/// A parsed AWS Query or EC2 Query request body.
+#[derive(Debug)]
+pub struct QueryDocument {
+    root: Node,
+}
+
+impl QueryDocument {
+    /// Parses an `application/x-www-form-urlencoded` request body.
+    pub fn parse(input: &[u8]) -> Result<Self, DeserializeError> {
+        let input = std::str::from_utf8(input).map_err(|_| DeserializeError {
+            kind: DeserializeErrorKind::InvalidUtf8,
+        })?;
+        let mut root = Node::default();
+        for pair in input.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let name = decode_component(name)?;
+            let value = decode_component(value)?;
+            let node = name
+                .split('.')
+                .fold(&mut root, |node, segment| node.child(segment));
+            if node.value.replace(value).is_some() {
+                return Err(DeserializeError {
+                    kind: DeserializeErrorKind::DuplicateParameter { name },
+                });
+            }
+        }
+        Ok(Self { root })
+    }
+
+    /// Returns the value of the `Action` parameter, which names the operation being invoked.
+    pub fn action(&self) -> Option<&str> {
+        self.root().member("Action")?.node.value.as_deref()
+    }
+
+    /// Returns the value of the `Version` parameter, which names the API version of the service.
+    pub fn version(&self) -> Option<&str> {
+        self.root().member("Version")?.node.value.as_deref()
+    }
+
+    /// Returns the top-level parameters of the document.
+    pub fn root(&self) -> QueryValue<'_> {
+        QueryValue { node: &self.root }
+    }
+}
+
+/// A parameter of a [`QueryDocument`], along with all parameters nested below it.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryValue<'a> {
+    node: &'a Node,
+}
+
+impl<'a> QueryValue<'a> {
+    /// Returns the nested parameter with the given name, if it is present.
+    pub fn member(&self, name: &str) -> Option<QueryValue<'a>> {
+        self.node.children.get(name).map(|node| QueryValue { node })
+    }
+
+    /// Returns all nested parameters, ordered by name.
+    pub fn members(&self) -> impl Iterator<Item = (&'a str, QueryValue<'a>)> {
+        self.node
+            .children
+            .iter()
+            .map(|(name, node)| (name.as_str(), QueryValue { node }))
+    }
+
+    /// Returns the full, `.`-separated name of this parameter.
+    pub fn path(&self) -> &'a str {
+        &self.node.path
+    }
+
+    /// Reads the value of this parameter as a string.
+    pub fn string(&self) -> Result<&'a str, DeserializeError> {
+        self.node
+            .value
+            .as_deref()
+            .ok_or_else(|| DeserializeError::at(self.path(), "expected a value"))
+    }
+
+    /// Reads the value of this parameter as a boolean.
+    pub fn boolean(&self) -> Result<bool, DeserializeError> {
+        self.primitive()
+    }
+
+    /// Reads the value of this parameter as a number or boolean.
+    pub fn primitive<T: Parse>(&self) -> Result<T, DeserializeError> {
+        T::parse_smithy_primitive(self.string()?)
+            .map_err(|err| DeserializeError::at(self.path(), err.to_string()))
+    }
+
+    /// Reads the value of this parameter as a timestamp in the given `format`.
+    pub fn date_time(&self, format: Format) -> Result<DateTime, DeserializeError> {
+        DateTime::from_str(self.string()?, format)
+            .map_err(|err| DeserializeError::at(self.path(), err.to_string()))
+    }
+
+    /// Reads the value of this parameter as a base64-encoded blob.
+    pub fn blob(&self) -> Result<Blob, DeserializeError> {
+        base64::decode(self.string()?)
+            .map(Blob::new)
+            .map_err(|err| DeserializeError::at(self.path(), err.to_string()))
+    }
+
+    /// Returns the items of a list.
+    ///
+    /// Flattened lists number their items directly below the parameter (`List.1`), while other lists number
+    /// them below a member name that defaults to `member` (`List.member.1`). An empty list may be sent as
+    /// the parameter name without any items (`List=`).
+    pub fn list(
+        &self,
+        flat: bool,
+        member_name: Option<&str>,
+    ) -> Result<Vec<QueryValue<'a>>, DeserializeError> {
+        let container = if flat {
+            Some(*self)
+        } else {
+            self.member(member_name.unwrap_or("member"))
+        };
+        match container {
+            Some(container) => container.indexed_items(),
+            None => self.empty_container(),
+        }
+    }
+
+    /// Returns the entries of a map.
+    ///
+    /// Flattened maps number their entries directly below the parameter (`Map.1.key`), while other maps
+    /// number them below `entry` (`Map.entry.1.key`). Each entry holds its key and value below `key_name`
+    /// and `value_name`.
+    pub fn map(
+        &self,
+        flat: bool,
+        key_name: &str,
+        value_name: &str,
+    ) -> Result<Vec<(&'a str, QueryValue<'a>)>, DeserializeError> {
+        let container = if flat {
+            Some(*self)
+        } else {
+            self.member("entry")
+        };
+        let entries = match container {
+            Some(container) => container.indexed_items()?,
+            None => self.empty_container()?,
+        };
+        entries
+            .into_iter()
+            .map(|entry| {
+                let key = entry
+                    .member(key_name)
+                    .ok_or_else(|| {
+                        DeserializeError::at(entry.path(), "map entry is missing its key")
+                    })?
+                    .string()?;
+                let value = entry.member(value_name).ok_or_else(|| {
+                    DeserializeError::at(entry.path(), "map entry is missing its value")
+                })?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    /// Returns the children of this parameter, which must be numbered contiguously from 1.
+    fn indexed_items(&self) -> Result<Vec<QueryValue<'a>>, DeserializeError> {
+        let mut items = self
+            .node
+            .children
+            .iter()
+            .map(|(index, node)| match index.parse::<usize>() {
+                Ok(index) if index > 0 => Ok((index, QueryValue { node })),
+                _ => Err(DeserializeError::at(
+                    &node.path,
+                    "expected a 1-based list or map index",
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        items.sort_by_key(|(index, _)| *index);
+        for (position, (index, item)) in items.iter().enumerate() {
+            if *index != position + 1 {
+                return Err(DeserializeError::at(
+                    item.path(),
+                    format!("list and map indices must be contiguous, but found index {index}"),
+                ));
+            }
+        }
+        Ok(items.into_iter().map(|(_, item)| item).collect())
+    }
+
+    fn empty_container<T>(&self) -> Result<Vec<T>, DeserializeError> {
+        if self.node.children.is_empty() && self.node.value.as_deref().unwrap_or("").is_empty() {
+            Ok(Vec::new())
+        } else {
+            Err(DeserializeError::at(self.path(), "expected a list or map"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryDocument;
+    use aws_smithy_types::date_time::Format;
+    use aws_smithy_types::{Blob, DateTime};
+
+    fn strings<'a>(values: impl IntoIterator<Item = super::QueryValue<'a>>) -> Vec<&'a str> {
+        values.into_iter().map(|v| v.string().unwrap()).collect()
+    }
+
+    #[test]
+    fn action_and_version() {
+        let doc = QueryDocument::parse(b"Action=SomeAction&Version=2020-01-01").unwrap();
+        assert_eq!(Some("SomeAction"), doc.action());
+        assert_eq!(Some("2020-01-01"), doc.version());
+
+        let doc = QueryDocument::parse(b"").unwrap();
+        assert_eq!(None, doc.action());
+    }
+
+    #[test]
+    fn scalars() {
+        let doc = QueryDocument::parse(
+            b"Action=A&Version=1&Str=hello+world%21&Bool=true&Int=-5&Float=NaN\
+              &Time=2021-05-24T15%3A34%3A50.123Z&Blob=YmxvYg%3D%3D",
+        )
+        .unwrap();
+        let root = doc.root();
+        assert_eq!(
+            "hello world!",
+            root.member("Str").unwrap().string().unwrap()
+        );
+        assert!(root.member("Bool").unwrap().boolean().unwrap());
+        assert_eq!(-5, root.member("Int").unwrap().primitive::<i32>().unwrap());
+        assert!(root
+            .member("Float")
+            .unwrap()
+            .primitive::<f64>()
+            .unwrap()
+            .is_nan());
+        assert_eq!(
+            DateTime::from_str("2021-05-24T15:34:50.123Z", Format::DateTime).unwrap(),
+            root.member("Time")
+                .unwrap()
+                .date_time(Format::DateTime)
+                .unwrap()
+        );
+        assert_eq!(
+            Blob::new("blob"),
+            root.member("Blob").unwrap().blob().unwrap()
+        );
+        assert!(root.member("Int").unwrap().boolean().is_err());
+        assert!(root.member("Missing").is_none());
+    }
+
+    #[test]
+    fn nested_structures() {
+        let doc = QueryDocument::parse(b"Outer.Inner.Value=1&Outer.Other=2").unwrap();
+        let outer = doc.root().member("Outer").unwrap();
+        assert_eq!(
+            "1",
+            outer
+                .member("Inner")
+                .unwrap()
+                .member("Value")
+                .unwrap()
+                .string()
+                .unwrap()
+        );
+        assert_eq!("Outer.Other", outer.member("Other").unwrap().path());
+        assert!(outer.string().is_err());
+    }
+
+    #[test]
+    fn lists() {
+        let doc = QueryDocument::parse(
+            b"ListArg.member.2=bar&ListArg.member.1=foo\
+              &Flat.1=A&Flat.2=B\
+              &Renamed.item.1=x\
+              &Empty=\
+              &Nested.member.1.member.1=a&Nested.member.1.member.2=b&Nested.member.2.member.1=c",
+        )
+        .unwrap();
+        let root = doc.root();
+        let list =
+            |name: &str, flat, member| root.member(name).unwrap().list(flat, member).unwrap();
+        assert_eq!(vec!["foo", "bar"], strings(list("ListArg", false, None)));
+        assert_eq!(vec!["A", "B"], strings(list("Flat", true, None)));
+        assert_eq!(vec!["x"], strings(list("Renamed", false, Some("item"))));
+        assert!(list("Empty", false, None).is_empty());
+        assert!(list("Empty", true, None).is_empty());
+
+        let nested = list("Nested", false, None);
+        assert_eq!(
+            vec!["a", "b"],
+            strings(nested[0].list(false, None).unwrap())
+        );
+        assert_eq!(vec!["c"], strings(nested[1].list(false, None).unwrap()));
+    }
+
+    #[test]
+    fn invalid_lists() {
+        let doc = QueryDocument::parse(b"Gap.member.1=a&Gap.member.3=b&Zero.0=a&Value=a").unwrap();
+        let root = doc.root();
+        let err = root.member("Gap").unwrap().list(false, None).unwrap_err();
+        assert!(err.to_string().contains("Gap.member.3"), "{err}");
+        assert!(root.member("Zero").unwrap().list(true, None).is_err());
+        assert!(root.member("Value").unwrap().list(false, None).is_err());
+    }
+
+    #[test]
+    fn maps() {
+        let doc = QueryDocument::parse(
+            b"MapArg.entry.1.key=foo&MapArg.entry.1.value=Foo\
+              &MapArg.entry.2.key=bar&MapArg.entry.2.value=Bar\
+              &Flat.1.K=a&Flat.1.V=A\
+              &Nested.entry.1.key=outer&Nested.entry.1.value.entry.1.key=inner\
+              &Nested.entry.1.value.entry.1.value=value\
+              &Broken.entry.1.key=no-value",
+        )
+        .unwrap();
+        let root = doc.root();
+        let entries = |map: Vec<(&str, super::QueryValue<'_>)>| {
+            map.into_iter()
+                .map(|(k, v)| (k.to_string(), v.string().unwrap().to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                ("foo".to_string(), "Foo".to_string()),
+                ("bar".to_string(), "Bar".to_string())
+            ],
+            entries(
+                root.member("MapArg")
+                    .unwrap()
+                    .map(false, "key", "value")
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            vec![("a".to_string(), "A".to_string())],
+            entries(root.member("Flat").unwrap().map(true, "K", "V").unwrap())
+        );
+
+        let nested = root
+            .member("Nested")
+            .unwrap()
+            .map(false, "key", "value")
+            .unwrap();
+        assert_eq!("outer", nested[0].0);
+        assert_eq!(
+            vec![("inner".to_string(), "value".to_string())],
+            entries(nested[0].1.map(false, "key", "value").unwrap())
+        );
+        assert!(root
+            .member("Broken")
+            .unwrap()
+            .map(false, "key", "value")
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_bodies() {
+        assert!(QueryDocument::parse(b"A=1&A=2").is_err());
+        assert!(QueryDocument::parse(b"A=%FF").is_err());
+        assert!(QueryDocument::parse(b"A=\xff").is_err());
+    }
+}
@@ -0,0 +1,111 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! XML error response bodies for the AWS Query and EC2 Query protocols.
+
+use aws_smithy_xml::encode::{ScopeWriter, XmlWriter};
+
+/// Whether an AWS Query error was caused by the client or by the service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorType {
+    /// The request was invalid.
+    Sender,
+    /// The service failed to process a valid request.
+    Receiver,
+}
+
+impl ErrorType {
+    /// Returns the value written to the `Type` element of an AWS Query error.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorType::Sender => "Sender",
+            ErrorType::Receiver => "Receiver",
+        }
+    }
+}
+
+fn write_error(scope: &mut ScopeWriter<'_, '_>, code: &str, message: Option<&str>) {
+    scope.start_el("Code").finish().data(code);
+    if let Some(message) = message {
+        scope.start_el("Message").finish().data(message);
+    }
+}
+
+/// Writes an AWS Query error response body.
+///
+/// ```xml
+/// <ErrorResponse>
+///     <Error>
+///         <Type>Sender</Type>
+///         <Code>InvalidGreeting</Code>
+///         <Message>Hi</Message>
+///     </Error>
+/// </ErrorResponse>
+/// ```
+pub fn aws_query_error(code: &str, error_type: ErrorType, message: Option<&str>) -> String {
+    let mut out = String::new();
+    {
+        let mut writer = XmlWriter::new(&mut out);
+        let mut response = writer.start_el("ErrorResponse").finish();
+        let mut error = response.start_el("Error").finish();
+        error.start_el("Type").finish().data(error_type.as_str());
+        write_error(&mut error, code, message);
+        error.finish();
+        response.finish();
+    }
+    out
+}
+
+/// Writes an EC2 Query error response body.
+///
+/// ```xml
+/// <Response>
+///     <Errors>
+///         <Error>
+///             <Code>InvalidGreeting</Code>
+///             <Message>Hi</Message>
+///         </Error>
+///     </Errors>
+/// </Response>
+/// ```
+pub fn ec2_query_error(code: &str, message: Option<&str>) -> String {
+    let mut out = String::new();
+    {
+        let mut writer = XmlWriter::new(&mut out);
+        let mut response = writer.start_el("Response").finish();
+        let mut errors = response.start_el("Errors").finish();
+        let mut error = errors.start_el("Error").finish();
+        write_error(&mut error, code, message);
+        error.finish();
+        errors.finish();
+        response.finish();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{aws_query_error, ec2_query_error, ErrorType};
+
+    #[test]
+    fn aws_query_errors() {
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Sender</Type><Code>InvalidGreeting</Code><Message>a &lt; b</Message></Error></ErrorResponse>",
+            aws_query_error("InvalidGreeting", ErrorType::Sender, Some("a < b"))
+        );
+        assert_eq!(
+            "<ErrorResponse><Error><Type>Receiver</Type><Code>InternalFailure</Code></Error></ErrorResponse>",
+            aws_query_error("InternalFailure", ErrorType::Receiver, None)
+        );
+    }
+
+    #[test]
+    fn ec2_query_errors() {
+        assert_eq!(
+            "<Response><Errors><Error><Code>InvalidGreeting</Code><Message>Hi</Message></Error></Errors></Response>",
+            ec2_query_error("InvalidGreeting", Some("Hi"))
+        );
+    }
+}
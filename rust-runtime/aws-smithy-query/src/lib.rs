@@ -13,6 +13,9 @@
 
 //! Abstractions for the Smithy AWS Query protocol
 
+pub mod deserialize;
+pub mod error;
+
 use aws_smithy_types::date_time::{DateTimeFormatError, Format};
 use aws_smithy_types::primitive::Encoder;
 use aws_smithy_types::{DateTime, Number};
@@ -108,6 +108,8 @@ pub mod interceptors;
 
 pub mod orchestrator;
 
+pub mod request_limiter;
+
 pub mod result;
 
 pub mod retries;
@@ -117,3 +119,7 @@ pub mod runtime_components;
 pub mod runtime_plugin;
 
 pub mod ser_de;
+
+pub mod telemetry;
+
+pub mod waiters;
@@ -11,6 +11,8 @@ use crate::impl_shared_conversions;
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
 use aws_smithy_types::endpoint::Endpoint;
 use aws_smithy_types::type_erasure::TypeErasedBox;
+use std::any::TypeId;
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
@@ -24,18 +26,67 @@ new_type_future! {
 /// The actual endpoint parameters are code generated from the Smithy model, and thus,
 /// are not known to the runtime crates. Hence, this struct is really a new-type around
 /// a [`TypeErasedBox`] that holds the actual concrete parameters in it.
+///
+/// It also holds a set of type-keyed properties that the orchestrator makes available to
+/// [`ResolveEndpoint::finalize_params`], such as the resolved identity for the request.
 #[derive(Debug)]
-pub struct EndpointResolverParams(TypeErasedBox);
+pub struct EndpointResolverParams {
+    inner: TypeErasedBox,
+    properties: HashMap<TypeId, TypeErasedBox>,
+    requires_identity: bool,
+}
 
 impl EndpointResolverParams {
     /// Creates a new [`EndpointResolverParams`] from a concrete parameters instance.
     pub fn new<T: fmt::Debug + Send + Sync + 'static>(params: T) -> Self {
-        Self(TypeErasedBox::new(params))
+        Self {
+            inner: TypeErasedBox::new(params),
+            properties: HashMap::new(),
+            requires_identity: false,
+        }
     }
 
     /// Attempts to downcast the underlying concrete parameters to `T` and return it as a reference.
     pub fn get<T: fmt::Debug + Send + Sync + 'static>(&self) -> Option<&T> {
-        self.0.downcast_ref()
+        self.inner.downcast_ref()
+    }
+
+    /// Attempts to downcast the underlying concrete parameters to `T` and return it as a mutable reference.
+    pub fn get_mut<T: fmt::Debug + Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.inner.downcast_mut()
+    }
+
+    /// Sets a property of type `T`, replacing any previously set property of the same type.
+    pub fn set_property<T: fmt::Debug + Send + Sync + 'static>(&mut self, property: T) {
+        self.properties
+            .insert(TypeId::of::<T>(), TypeErasedBox::new(property));
+    }
+
+    /// Returns a reference to the property of type `T`, if it has been set.
+    pub fn get_property<T: fmt::Debug + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.properties
+            .get(&TypeId::of::<T>())
+            .and_then(|property| property.downcast_ref())
+    }
+
+    /// Returns a mutable reference to the property of type `T`, if it has been set.
+    pub fn get_property_mut<T: fmt::Debug + Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.properties
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|property| property.downcast_mut())
+    }
+
+    /// Marks whether these parameters can only be finalized once the identity for the request is known.
+    ///
+    /// When set by [`ResolveEndpoint::finalize_params`], the orchestrator resolves the identity
+    /// ahead of endpoint resolution and finalizes the parameters a second time with it.
+    pub fn set_requires_identity(&mut self, requires_identity: bool) {
+        self.requires_identity = requires_identity;
+    }
+
+    /// Returns true if these parameters can only be finalized once the identity for the request is known.
+    pub fn requires_identity(&self) -> bool {
+        self.requires_identity
     }
 }
 
@@ -50,6 +101,21 @@ pub use ResolveEndpoint as EndpointResolver;
 pub trait ResolveEndpoint: Send + Sync + fmt::Debug {
     /// Asynchronously resolves an endpoint to use from the given endpoint parameters.
     fn resolve_endpoint<'a>(&'a self, params: &'a EndpointResolverParams) -> EndpointFuture<'a>;
+
+    /// Finalizes the endpoint parameters before they're used to resolve an endpoint.
+    ///
+    /// This is called by the orchestrator before the identity for the request is resolved.
+    /// Implementations that need that identity, for example to fill in an account ID, should call
+    /// [`EndpointResolverParams::set_requires_identity`]. The orchestrator will then resolve the
+    /// identity, set it as an [`Identity`](crate::client::identity::Identity) property of `params`,
+    /// and call this method again before resolving the endpoint.
+    fn finalize_params<'a>(
+        &'a self,
+        params: &'a mut EndpointResolverParams,
+    ) -> Result<(), BoxError> {
+        let _ = params;
+        Ok(())
+    }
 }
 
 /// Shared endpoint resolver.
@@ -69,8 +135,42 @@ impl ResolveEndpoint for SharedEndpointResolver {
     fn resolve_endpoint<'a>(&'a self, params: &'a EndpointResolverParams) -> EndpointFuture<'a> {
         self.0.resolve_endpoint(params)
     }
+
+    fn finalize_params<'a>(
+        &'a self,
+        params: &'a mut EndpointResolverParams,
+    ) -> Result<(), BoxError> {
+        self.0.finalize_params(params)
+    }
 }
 
 impl ValidateConfig for SharedEndpointResolver {}
 
 impl_shared_conversions!(convert SharedEndpointResolver from ResolveEndpoint using SharedEndpointResolver::new);
+
+#[cfg(test)]
+mod tests {
+    use super::EndpointResolverParams;
+
+    #[test]
+    fn params_and_properties() {
+        #[derive(Debug, PartialEq)]
+        struct Params(&'static str);
+        #[derive(Debug, PartialEq)]
+        struct Property(u32);
+
+        let mut params = EndpointResolverParams::new(Params("a"));
+        assert_eq!(None, params.get_property::<Property>());
+        params.set_property(Property(1));
+        params.get_property_mut::<Property>().unwrap().0 += 1;
+        assert_eq!(Some(&Property(2)), params.get_property::<Property>());
+
+        params.get_mut::<Params>().unwrap().0 = "b";
+        assert_eq!(Some(&Params("b")), params.get::<Params>());
+        assert_eq!(None, params.get_mut::<Property>());
+
+        assert!(!params.requires_identity());
+        params.set_requires_identity(true);
+        assert!(params.requires_identity());
+    }
+}
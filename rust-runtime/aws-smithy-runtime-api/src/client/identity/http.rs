@@ -47,6 +47,11 @@ impl Token {
     pub fn token(&self) -> &str {
         &self.0.token
     }
+
+    /// Returns the expiration time of this token (if any)
+    pub fn expiration(&self) -> Option<SystemTime> {
+        self.0.expiration
+    }
 }
 
 impl From<&str> for Token {
@@ -0,0 +1,101 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Interfaces for limiting the rate and concurrency of requests.
+//!
+//! Unlike the rate limiting done by adaptive retries, which reacts to throttling errors, a
+//! request limiter proactively caps how many requests a client sends so that a client can
+//! stay under a service quota. A [`LimitRequests`] implementation is registered with a client
+//! through its [`RuntimeComponents`]. The orchestrator acquires a [`RequestPermit`] from it
+//! before each attempt of an operation's request, including retries, and holds on to that
+//! permit until the attempt has completed.
+
+use crate::box_error::BoxError;
+use crate::client::runtime_components::RuntimeComponents;
+use crate::impl_shared_conversions;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::fmt;
+use std::sync::Arc;
+
+new_type_future! {
+    #[doc = "Future for [`LimitRequests::acquire_permit`]."]
+    pub struct RequestPermitFuture<'a, RequestPermit, BoxError>;
+}
+
+/// Permission to send an attempt of an operation's request.
+///
+/// Whatever a request limiter stored in the permit is dropped when the attempt completes,
+/// which is how concurrency limits release their slot.
+pub struct RequestPermit {
+    _inner: Option<Box<dyn fmt::Debug + Send + Sync>>,
+}
+
+impl RequestPermit {
+    /// Creates a permit that holds on to `inner` until the attempt completes.
+    pub fn new(inner: impl fmt::Debug + Send + Sync + 'static) -> Self {
+        Self {
+            _inner: Some(Box::new(inner)),
+        }
+    }
+
+    /// Creates a permit that holds on to nothing.
+    pub fn empty() -> Self {
+        Self { _inner: None }
+    }
+}
+
+impl fmt::Debug for RequestPermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestPermit").finish_non_exhaustive()
+    }
+}
+
+/// Limits the rate and concurrency of the requests that a client sends.
+pub trait LimitRequests: fmt::Debug + Send + Sync {
+    /// Waits until an attempt of an operation's request is allowed to be sent.
+    ///
+    /// This should return an error if the request should be rejected rather than sent, in which
+    /// case the operation fails without the attempt being sent.
+    fn acquire_permit<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        cfg: &'a ConfigBag,
+    ) -> RequestPermitFuture<'a>;
+}
+
+/// Shared request limiter.
+#[derive(Clone, Debug)]
+pub struct SharedRequestLimiter(Arc<dyn LimitRequests>);
+
+impl SharedRequestLimiter {
+    /// Creates a new [`SharedRequestLimiter`].
+    pub fn new(request_limiter: impl LimitRequests + 'static) -> Self {
+        Self(Arc::new(request_limiter))
+    }
+}
+
+impl LimitRequests for SharedRequestLimiter {
+    fn acquire_permit<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        cfg: &'a ConfigBag,
+    ) -> RequestPermitFuture<'a> {
+        self.0.acquire_permit(runtime_components, cfg)
+    }
+}
+
+impl_shared_conversions!(convert SharedRequestLimiter from LimitRequests using SharedRequestLimiter::new);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_send() {
+        fn is_send<T: Send>() {}
+        is_send::<RequestPermitFuture<'_>>();
+        is_send::<RequestPermit>();
+    }
+}
@@ -23,8 +23,10 @@ use crate::client::identity::{
     SharedIdentityResolver,
 };
 use crate::client::interceptors::{Intercept, SharedInterceptor};
+use crate::client::request_limiter::{LimitRequests, SharedRequestLimiter};
 use crate::client::retries::classifiers::{ClassifyRetry, SharedRetryClassifier};
 use crate::client::retries::{RetryStrategy, SharedRetryStrategy};
+use crate::client::telemetry::{ProvideMeter, SharedMeterProvider};
 use crate::impl_shared_conversions;
 use crate::shared::IntoShared;
 use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
@@ -357,6 +359,10 @@ declare_runtime_components! {
 
         sleep_impl: Option<SharedAsyncSleep>,
 
+        meter_provider: Option<SharedMeterProvider>,
+
+        request_limiter: Option<SharedRequestLimiter>,
+
         config_validators: Vec<SharedConfigValidator>,
     }
 }
@@ -420,6 +426,16 @@ impl RuntimeComponents {
         self.time_source.as_ref().map(|s| s.value.clone())
     }
 
+    /// Returns the meter provider.
+    pub fn meter_provider(&self) -> Option<SharedMeterProvider> {
+        self.meter_provider.as_ref().map(|s| s.value.clone())
+    }
+
+    /// Returns the request limiter.
+    pub fn request_limiter(&self) -> Option<SharedRequestLimiter> {
+        self.request_limiter.as_ref().map(|s| s.value.clone())
+    }
+
     /// Returns the config validators.
     pub fn config_validators(&self) -> impl Iterator<Item = SharedConfigValidator> + '_ {
         self.config_validators.iter().map(|s| s.value.clone())
@@ -583,6 +599,17 @@ impl RuntimeComponentsBuilder {
         self
     }
 
+    /// Sets the identity resolver for the given scheme, replacing any identity resolvers that were previously added for it.
+    pub fn set_identity_resolver(
+        &mut self,
+        scheme_id: AuthSchemeId,
+        identity_resolver: impl ResolveIdentity + 'static,
+    ) -> &mut Self {
+        self.identity_resolvers
+            .retain(|resolver| resolver.value.scheme_id() != scheme_id);
+        self.push_identity_resolver(scheme_id, identity_resolver)
+    }
+
     /// Adds an identity resolver.
     pub fn with_identity_resolver(
         mut self,
@@ -744,6 +771,54 @@ impl RuntimeComponentsBuilder {
         self
     }
 
+    /// Returns the meter provider.
+    pub fn meter_provider(&self) -> Option<SharedMeterProvider> {
+        self.meter_provider.as_ref().map(|s| s.value.clone())
+    }
+
+    /// Sets the meter provider.
+    pub fn set_meter_provider(
+        &mut self,
+        meter_provider: Option<impl ProvideMeter + 'static>,
+    ) -> &mut Self {
+        self.meter_provider =
+            meter_provider.map(|p| Tracked::new(self.builder_name, p.into_shared()));
+        self
+    }
+
+    /// Sets the meter provider.
+    pub fn with_meter_provider(
+        mut self,
+        meter_provider: Option<impl ProvideMeter + 'static>,
+    ) -> Self {
+        self.set_meter_provider(meter_provider);
+        self
+    }
+
+    /// Returns the request limiter.
+    pub fn request_limiter(&self) -> Option<SharedRequestLimiter> {
+        self.request_limiter.as_ref().map(|s| s.value.clone())
+    }
+
+    /// Sets the request limiter.
+    pub fn set_request_limiter(
+        &mut self,
+        request_limiter: Option<impl LimitRequests + 'static>,
+    ) -> &mut Self {
+        self.request_limiter =
+            request_limiter.map(|l| Tracked::new(self.builder_name, l.into_shared()));
+        self
+    }
+
+    /// Sets the request limiter.
+    pub fn with_request_limiter(
+        mut self,
+        request_limiter: Option<impl LimitRequests + 'static>,
+    ) -> Self {
+        self.set_request_limiter(request_limiter);
+        self
+    }
+
     /// Returns the config validators.
     pub fn config_validators(&self) -> impl Iterator<Item = SharedConfigValidator> + '_ {
         self.config_validators.iter().map(|s| s.value.clone())
@@ -0,0 +1,295 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Interfaces for recording client telemetry.
+//!
+//! These interfaces are modeled after the [OpenTelemetry metrics API](https://opentelemetry.io/docs/specs/otel/metrics/api/)
+//! so that they can be easily bridged to an OpenTelemetry SDK or any other metrics library.
+//!
+//! A [`ProvideMeter`] implementation is registered with a client through its
+//! [`RuntimeComponents`](crate::client::runtime_components::RuntimeComponents). The orchestrator
+//! creates a [`Meter`] from it, and uses that to create the [`Counter`]s and [`Histogram`]s
+//! that it records operation and attempt metrics to.
+
+use crate::impl_shared_conversions;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+/// The value of a metric attribute.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValue {
+    /// A string value.
+    String(Cow<'static, str>),
+    /// A signed integer value.
+    I64(i64),
+    /// A boolean value.
+    Bool(bool),
+}
+
+impl From<&'static str> for AttributeValue {
+    fn from(value: &'static str) -> Self {
+        Self::String(Cow::Borrowed(value))
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::String(Cow::Owned(value))
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        Self::I64(value)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(value) => f.write_str(value),
+            Self::I64(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Key-value pairs that are recorded alongside a metric value.
+///
+/// Attributes distinguish between recordings of the same metric, such as the service and
+/// operation that a request was made to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attributes {
+    attributes: Vec<(&'static str, AttributeValue)>,
+}
+
+impl Attributes {
+    /// Creates an empty set of attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets an attribute, replacing any previous value for `key`.
+    pub fn set(&mut self, key: &'static str, value: impl Into<AttributeValue>) -> &mut Self {
+        let value = value.into();
+        match self.attributes.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.attributes.push((key, value)),
+        }
+        self
+    }
+
+    /// Sets an attribute, replacing any previous value for `key`.
+    pub fn with(mut self, key: &'static str, value: impl Into<AttributeValue>) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Returns the value of the attribute for `key`, if it is set.
+    pub fn get(&self, key: &str) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the attributes.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &AttributeValue)> {
+        self.attributes.iter().map(|(key, value)| (*key, value))
+    }
+
+    /// Returns true if no attributes are set.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+/// Describes a metric instrument that is being created.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InstrumentDescriptor {
+    name: &'static str,
+    units: Option<&'static str>,
+    description: Option<&'static str>,
+}
+
+impl InstrumentDescriptor {
+    /// Creates a new descriptor for an instrument with the given `name`.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            units: None,
+            description: None,
+        }
+    }
+
+    /// Sets the units that values are recorded in, e.g. `s` or `By`.
+    pub const fn with_units(mut self, units: &'static str) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    /// Sets a human readable description of the instrument.
+    pub const fn with_description(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// The name of the instrument.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The units that values are recorded in, if any.
+    pub fn units(&self) -> Option<&'static str> {
+        self.units
+    }
+
+    /// The description of the instrument, if any.
+    pub fn description(&self) -> Option<&'static str> {
+        self.description
+    }
+}
+
+/// A metric instrument that records monotonically increasing values, such as a number of attempts.
+pub trait Counter: fmt::Debug + Send + Sync {
+    /// Adds `value` to the counter.
+    fn add(&self, value: u64, attributes: &Attributes);
+}
+
+/// Shared instance of [`Counter`].
+#[derive(Clone, Debug)]
+pub struct SharedCounter(Arc<dyn Counter>);
+
+impl SharedCounter {
+    /// Creates a new [`SharedCounter`].
+    pub fn new(counter: impl Counter + 'static) -> Self {
+        Self(Arc::new(counter))
+    }
+}
+
+impl Counter for SharedCounter {
+    fn add(&self, value: u64, attributes: &Attributes) {
+        self.0.add(value, attributes)
+    }
+}
+
+impl_shared_conversions!(convert SharedCounter from Counter using SharedCounter::new);
+
+/// A metric instrument that records a distribution of values, such as request latencies.
+pub trait Histogram: fmt::Debug + Send + Sync {
+    /// Records `value` in the histogram.
+    fn record(&self, value: f64, attributes: &Attributes);
+}
+
+/// Shared instance of [`Histogram`].
+#[derive(Clone, Debug)]
+pub struct SharedHistogram(Arc<dyn Histogram>);
+
+impl SharedHistogram {
+    /// Creates a new [`SharedHistogram`].
+    pub fn new(histogram: impl Histogram + 'static) -> Self {
+        Self(Arc::new(histogram))
+    }
+}
+
+impl Histogram for SharedHistogram {
+    fn record(&self, value: f64, attributes: &Attributes) {
+        self.0.record(value, attributes)
+    }
+}
+
+impl_shared_conversions!(convert SharedHistogram from Histogram using SharedHistogram::new);
+
+/// Creates metric instruments.
+pub trait Meter: fmt::Debug + Send + Sync {
+    /// Creates a [`Counter`] described by `descriptor`.
+    fn create_counter(&self, descriptor: InstrumentDescriptor) -> SharedCounter;
+
+    /// Creates a [`Histogram`] described by `descriptor`.
+    fn create_histogram(&self, descriptor: InstrumentDescriptor) -> SharedHistogram;
+}
+
+/// Shared instance of [`Meter`].
+#[derive(Clone, Debug)]
+pub struct SharedMeter(Arc<dyn Meter>);
+
+impl SharedMeter {
+    /// Creates a new [`SharedMeter`].
+    pub fn new(meter: impl Meter + 'static) -> Self {
+        Self(Arc::new(meter))
+    }
+}
+
+impl Meter for SharedMeter {
+    fn create_counter(&self, descriptor: InstrumentDescriptor) -> SharedCounter {
+        self.0.create_counter(descriptor)
+    }
+
+    fn create_histogram(&self, descriptor: InstrumentDescriptor) -> SharedHistogram {
+        self.0.create_histogram(descriptor)
+    }
+}
+
+impl_shared_conversions!(convert SharedMeter from Meter using SharedMeter::new);
+
+/// Provides [`Meter`]s.
+///
+/// This is the entry point for metrics, and is registered as a runtime component.
+pub trait ProvideMeter: fmt::Debug + Send + Sync {
+    /// Returns a meter for the given instrumentation `scope`, e.g. the name of the crate recording metrics.
+    fn meter(&self, scope: &'static str) -> SharedMeter;
+}
+
+/// Shared instance of [`ProvideMeter`].
+#[derive(Clone, Debug)]
+pub struct SharedMeterProvider(Arc<dyn ProvideMeter>);
+
+impl SharedMeterProvider {
+    /// Creates a new [`SharedMeterProvider`].
+    pub fn new(provider: impl ProvideMeter + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+}
+
+impl ProvideMeter for SharedMeterProvider {
+    fn meter(&self, scope: &'static str) -> SharedMeter {
+        self.0.meter(scope)
+    }
+}
+
+impl_shared_conversions!(convert SharedMeterProvider from ProvideMeter using SharedMeterProvider::new);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_replace_existing_values() {
+        let mut attributes = Attributes::new().with("rpc.service", "foo");
+        attributes
+            .set("rpc.method", "Bar")
+            .set("rpc.service", "baz");
+        assert_eq!(
+            Some(&AttributeValue::from("baz")),
+            attributes.get("rpc.service")
+        );
+        assert_eq!(
+            vec![("rpc.service", "baz".into()), ("rpc.method", "Bar".into())],
+            attributes
+                .iter()
+                .map(|(k, v)| (k, v.clone()))
+                .collect::<Vec<(&str, AttributeValue)>>()
+        );
+        assert_eq!(None, attributes.get("missing"));
+    }
+}
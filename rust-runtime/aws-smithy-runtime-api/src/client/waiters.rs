@@ -0,0 +1,47 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Types returned by waiters.
+//!
+//! Waiters repeatedly poll an operation until the resource it describes reaches a desired state.
+//! The polling loop itself lives in `aws-smithy-runtime`.
+
+pub mod error;
+
+/// The last response received by a waiter before it stopped polling.
+///
+/// This is the operation's result from the final poll, which could be either a successful output
+/// or an error, depending on which acceptor matched.
+#[derive(Debug)]
+pub struct FinalPoll<O, E> {
+    result: Result<O, E>,
+}
+
+impl<O, E> FinalPoll<O, E> {
+    /// Creates a new `FinalPoll` from the result of the last poll.
+    pub fn new(result: Result<O, E>) -> Self {
+        Self { result }
+    }
+
+    /// Returns the result of the last poll.
+    pub fn as_result(&self) -> Result<&O, &E> {
+        self.result.as_ref()
+    }
+
+    /// Converts this into the result of the last poll.
+    pub fn into_result(self) -> Result<O, E> {
+        self.result
+    }
+
+    /// Maps the operation output with the given function.
+    pub fn map<O2, F: FnOnce(O) -> O2>(self, mapper: F) -> FinalPoll<O2, E> {
+        FinalPoll::new(self.result.map(mapper))
+    }
+
+    /// Maps the operation error with the given function.
+    pub fn map_err<E2, F: FnOnce(E) -> E2>(self, mapper: F) -> FinalPoll<O, E2> {
+        FinalPoll::new(self.result.map_err(mapper))
+    }
+}
@@ -0,0 +1,165 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Errors returned by waiters.
+
+use crate::box_error::BoxError;
+use crate::client::waiters::FinalPoll;
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+
+/// An error that occurred while waiting for a resource to reach a desired state.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum WaiterError<O, E> {
+    /// The waiter failed to construct the request or the matchers for its acceptors.
+    /// No requests were sent.
+    ConstructionFailure(ConstructionFailure),
+
+    /// The maximum wait time was exceeded before the resource reached a success or failure state.
+    ExceededMaxWait(ExceededMaxWait),
+
+    /// An acceptor transitioned the waiter to a failure state.
+    ///
+    /// This means the resource reached a state that it will never transition out of to reach
+    /// the success state.
+    FailureState(FailureState<O, E>),
+
+    /// The operation returned an error that none of the waiter's acceptors matched.
+    OperationFailed(OperationFailed<E>),
+}
+
+impl<O, E> WaiterError<O, E> {
+    /// Constructs a `WaiterError` for a construction failure.
+    pub fn construction_failure(source: impl Into<BoxError>) -> Self {
+        Self::ConstructionFailure(ConstructionFailure {
+            source: source.into(),
+        })
+    }
+}
+
+impl<O, E> fmt::Display for WaiterError<O, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConstructionFailure(_) => f.write_str("failed to construct waiter"),
+            Self::ExceededMaxWait(ctx) => write!(
+                f,
+                "exceeded max wait time ({:?}) after {} polls",
+                ctx.max_wait, ctx.poll_count
+            ),
+            Self::FailureState(_) => {
+                f.write_str("waiter entered a failure state while waiting for a success state")
+            }
+            Self::OperationFailed(_) => {
+                f.write_str("operation failed while waiting for a success state")
+            }
+        }
+    }
+}
+
+impl<O, E> StdError for WaiterError<O, E>
+where
+    O: fmt::Debug,
+    E: StdError + 'static,
+{
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::ConstructionFailure(ctx) => Some(ctx.source.as_ref()),
+            Self::ExceededMaxWait(_) => None,
+            Self::FailureState(ctx) => match ctx.final_poll.as_result() {
+                Ok(_) => None,
+                Err(err) => Some(err),
+            },
+            Self::OperationFailed(ctx) => Some(&ctx.source),
+        }
+    }
+}
+
+/// Error context for [`WaiterError::ConstructionFailure`].
+#[derive(Debug)]
+pub struct ConstructionFailure {
+    source: BoxError,
+}
+
+/// Error context for [`WaiterError::ExceededMaxWait`].
+#[derive(Debug)]
+pub struct ExceededMaxWait {
+    max_wait: Duration,
+    elapsed: Duration,
+    poll_count: u32,
+}
+
+impl ExceededMaxWait {
+    /// Creates the error context.
+    pub fn new(max_wait: Duration, elapsed: Duration, poll_count: u32) -> Self {
+        Self {
+            max_wait,
+            elapsed,
+            poll_count,
+        }
+    }
+
+    /// Returns the configured max wait time.
+    pub fn max_wait(&self) -> Duration {
+        self.max_wait
+    }
+
+    /// Returns the time spent waiting before giving up.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the number of times the operation was polled.
+    pub fn poll_count(&self) -> u32 {
+        self.poll_count
+    }
+}
+
+/// Error context for [`WaiterError::FailureState`].
+#[derive(Debug)]
+pub struct FailureState<O, E> {
+    final_poll: FinalPoll<O, E>,
+}
+
+impl<O, E> FailureState<O, E> {
+    /// Creates the error context.
+    pub fn new(final_poll: FinalPoll<O, E>) -> Self {
+        Self { final_poll }
+    }
+
+    /// Returns the result of the poll that transitioned the waiter into the failure state.
+    pub fn final_poll(&self) -> &FinalPoll<O, E> {
+        &self.final_poll
+    }
+
+    /// Converts this context into the result of the poll that transitioned the waiter into the failure state.
+    pub fn into_final_poll(self) -> FinalPoll<O, E> {
+        self.final_poll
+    }
+}
+
+/// Error context for [`WaiterError::OperationFailed`].
+#[derive(Debug)]
+pub struct OperationFailed<E> {
+    source: E,
+}
+
+impl<E> OperationFailed<E> {
+    /// Creates the error context.
+    pub fn new(source: E) -> Self {
+        Self { source }
+    }
+
+    /// Returns the error returned by the operation.
+    pub fn error(&self) -> &E {
+        &self.source
+    }
+
+    /// Converts this context into the error returned by the operation.
+    pub fn into_error(self) -> E {
+        self.source
+    }
+}
@@ -22,6 +22,10 @@ pub mod config_override;
 /// The client orchestrator implementation
 pub mod orchestrator;
 
+pub mod request_limiter;
+
+pub mod response_cache;
+
 /// Smithy code related to retry handling and token buckets.
 ///
 /// This code defines when and how failed requests should be retried. It also defines the behavior
@@ -34,6 +38,8 @@ pub mod retries;
 #[cfg(feature = "test-util")]
 pub mod test_util;
 
+pub mod telemetry;
+
 mod timeout;
 
 /// Smithy identity used by auth and signing.
@@ -41,3 +47,6 @@ pub mod identity;
 
 /// Interceptors for Smithy clients.
 pub mod interceptors;
+
+#[cfg(feature = "waiters")]
+pub mod waiters;
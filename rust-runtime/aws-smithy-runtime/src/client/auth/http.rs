@@ -9,7 +9,6 @@ use aws_smithy_http::query_writer::QueryWriter;
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::auth::http::{
     HTTP_API_KEY_AUTH_SCHEME_ID, HTTP_BASIC_AUTH_SCHEME_ID, HTTP_BEARER_AUTH_SCHEME_ID,
-    HTTP_DIGEST_AUTH_SCHEME_ID,
 };
 use aws_smithy_runtime_api::client::auth::{
     AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, Sign,
@@ -22,6 +21,12 @@ use aws_smithy_types::base64::encode;
 use aws_smithy_types::config_bag::ConfigBag;
 use http::HeaderValue;
 
+mod digest;
+
+pub use digest::{
+    DigestAuthChallengeInterceptor, DigestAuthChallengeRetryClassifier, DigestAuthScheme,
+};
+
 /// Destination for the API key
 #[derive(Copy, Clone, Debug)]
 pub enum ApiKeyLocation {
@@ -231,56 +236,6 @@ impl Sign for BearerAuthSigner {
     }
 }
 
-/// Auth implementation for Smithy's `@httpDigestAuth` auth scheme
-#[derive(Debug, Default)]
-pub struct DigestAuthScheme {
-    signer: DigestAuthSigner,
-}
-
-impl DigestAuthScheme {
-    /// Creates a new `DigestAuthScheme`.
-    pub fn new() -> Self {
-        Self {
-            signer: DigestAuthSigner,
-        }
-    }
-}
-
-impl AuthScheme for DigestAuthScheme {
-    fn scheme_id(&self) -> AuthSchemeId {
-        HTTP_DIGEST_AUTH_SCHEME_ID
-    }
-
-    fn identity_resolver(
-        &self,
-        identity_resolvers: &dyn GetIdentityResolver,
-    ) -> Option<SharedIdentityResolver> {
-        identity_resolvers.identity_resolver(self.scheme_id())
-    }
-
-    fn signer(&self) -> &dyn Sign {
-        &self.signer
-    }
-}
-
-#[derive(Debug, Default)]
-struct DigestAuthSigner;
-
-impl Sign for DigestAuthSigner {
-    fn sign_http_request(
-        &self,
-        _request: &mut HttpRequest,
-        _identity: &Identity,
-        _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
-        _runtime_components: &RuntimeComponents,
-        _config_bag: &ConfigBag,
-    ) -> Result<(), BoxError> {
-        unimplemented!(
-            "support for signing with Smithy's `@httpDigestAuth` auth scheme is not implemented yet"
-        )
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,1004 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Implementation of Smithy's `@httpDigestAuth` auth scheme ([RFC 7616]).
+//!
+//! Digest auth requires a round-trip with the server before a request can be signed: the server
+//! responds to an unauthenticated request with `401 Unauthorized` and a `WWW-Authenticate: Digest ...`
+//! challenge, and the client then answers that challenge on a follow-up request.
+//!
+//! This is split across three components that share state:
+//! - [`DigestAuthScheme`] signs requests using the most recent challenge received for the
+//!   request's authority, incrementing the nonce count each time the challenge is used.
+//! - [`DigestAuthChallengeInterceptor`] records challenges from `401` responses (and `nextnonce`
+//!   values from `Authentication-Info` headers) and decides whether the challenge should be answered.
+//! - [`DigestAuthChallengeRetryClassifier`] tells the retry strategy to immediately retry a request
+//!   whose challenge should be answered.
+//!
+//! Answering a challenge is implemented as a retry, so it requires a retry strategy that consults
+//! retry classifiers, such as the `StandardRetryStrategy`. Answered challenges don't count against
+//! the max attempts of the `RetryConfig`, so this works even when retries are disabled. Once a
+//! challenge has been received, subsequent requests to the same authority are signed preemptively
+//! without an additional round-trip.
+//!
+//! [RFC 7616]: https://datatracker.ietf.org/doc/html/rfc7616
+
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::auth::http::HTTP_DIGEST_AUTH_SCHEME_ID;
+use aws_smithy_runtime_api::client::auth::{
+    AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, Sign,
+};
+use aws_smithy_runtime_api::client::identity::http::Login;
+use aws_smithy_runtime_api::client::identity::{Identity, SharedIdentityResolver};
+use aws_smithy_runtime_api::client::interceptors::context::{
+    BeforeDeserializationInterceptorContextMut, BeforeTransmitInterceptorContextRef,
+    InterceptorContext,
+};
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+use aws_smithy_runtime_api::client::retries::classifiers::{ClassifyRetry, RetryAction};
+use aws_smithy_runtime_api::client::runtime_components::{GetIdentityResolver, RuntimeComponents};
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use aws_smithy_types::retry::{ErrorKind, RetryConfig};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::debug;
+
+const AUTHENTICATION_INFO: &str = "authentication-info";
+
+/// Upper bound on challenges answered per operation: the initial challenge plus one stale nonce.
+const MAX_CHALLENGES_ANSWERED: u32 = 2;
+
+/// Auth implementation for Smithy's `@httpDigestAuth` auth scheme
+///
+/// Requests are only signed once a challenge has been received from the server, so this
+/// auth scheme must be paired with the interceptor returned by
+/// [`challenge_interceptor`](DigestAuthScheme::challenge_interceptor) and a
+/// [`DigestAuthChallengeRetryClassifier`]. See the [module docs](self) for more details.
+#[derive(Debug, Default)]
+pub struct DigestAuthScheme {
+    signer: DigestAuthSigner,
+}
+
+impl DigestAuthScheme {
+    /// Creates a new `DigestAuthScheme`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an interceptor that records the challenges this auth scheme will answer.
+    pub fn challenge_interceptor(&self) -> DigestAuthChallengeInterceptor {
+        DigestAuthChallengeInterceptor {
+            sessions: self.signer.sessions.clone(),
+        }
+    }
+}
+
+impl AuthScheme for DigestAuthScheme {
+    fn scheme_id(&self) -> AuthSchemeId {
+        HTTP_DIGEST_AUTH_SCHEME_ID
+    }
+
+    fn identity_resolver(
+        &self,
+        identity_resolvers: &dyn GetIdentityResolver,
+    ) -> Option<SharedIdentityResolver> {
+        identity_resolvers.identity_resolver(self.scheme_id())
+    }
+
+    fn signer(&self) -> &dyn Sign {
+        &self.signer
+    }
+}
+
+#[derive(Debug, Default)]
+struct DigestAuthSigner {
+    sessions: DigestSessions,
+}
+
+impl Sign for DigestAuthSigner {
+    fn sign_http_request(
+        &self,
+        request: &mut HttpRequest,
+        identity: &Identity,
+        _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
+        _runtime_components: &RuntimeComponents,
+        _config_bag: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        let login = identity
+            .data::<Login>()
+            .ok_or("HTTP digest auth requires a `Login` identity")?;
+        let uri: http::Uri = request.uri().parse()?;
+        let authority = authority_of(&uri);
+        let (challenge, nonce_count) = match self.sessions.next_nonce_count(&authority) {
+            Some(session) => session,
+            None => {
+                debug!(
+                    authority = %authority,
+                    "no digest challenge has been received yet; sending the request without credentials"
+                );
+                return Ok(());
+            }
+        };
+        let digest_uri = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+        let authorization = challenge.authorization(
+            login,
+            request.method(),
+            digest_uri,
+            request.body().bytes(),
+            nonce_count,
+            &generate_cnonce(),
+        )?;
+        request
+            .headers_mut()
+            .try_insert(http::header::AUTHORIZATION, authorization)
+            .map_err(|_| {
+                "Digest credentials contain characters that can't be included in a HTTP header"
+            })?;
+        Ok(())
+    }
+}
+
+/// Interceptor that records digest challenges for [`DigestAuthScheme`].
+///
+/// When a `401 Unauthorized` response includes a supported digest challenge, this interceptor
+/// stores the challenge and marks the response as retryable if:
+/// - the request wasn't signed with digest credentials yet, or
+/// - the server indicated that the nonce used to sign the request was stale.
+///
+/// A request that was signed with a fresh nonce and still rejected will not be retried since
+/// that indicates the credentials are incorrect. At most two challenges are answered per operation.
+#[derive(Debug)]
+pub struct DigestAuthChallengeInterceptor {
+    sessions: DigestSessions,
+}
+
+impl Intercept for DigestAuthChallengeInterceptor {
+    fn name(&self) -> &'static str {
+        "DigestAuthChallengeInterceptor"
+    }
+
+    fn read_before_transmit(
+        &self,
+        context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let request = context.request();
+        let uri: http::Uri = request.uri().parse()?;
+        let signed = request
+            .headers()
+            .get(http::header::AUTHORIZATION)
+            .map(|value| value.starts_with("Digest "))
+            .unwrap_or_default();
+        cfg.interceptor_state().store_put(DigestAuthAttempt {
+            authority: authority_of(&uri),
+            signed,
+        });
+        Ok(())
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let attempt = match cfg.load::<DigestAuthAttempt>() {
+            Some(attempt) => attempt.clone(),
+            None => return Ok(()),
+        };
+        let response = context.response_mut();
+
+        if response.status() != http::StatusCode::UNAUTHORIZED {
+            if attempt.signed {
+                let next_nonce = response
+                    .headers()
+                    .get(AUTHENTICATION_INFO)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| {
+                        parse_auth_params(value)
+                            .into_iter()
+                            .find(|(name, _)| name.eq_ignore_ascii_case("nextnonce"))
+                    })
+                    .map(|(_, nonce)| nonce);
+                if let Some(next_nonce) = next_nonce {
+                    debug!("server provided a `nextnonce`; future requests will use it");
+                    self.sessions.rotate_nonce(&attempt.authority, next_nonce);
+                }
+            }
+            return Ok(());
+        }
+
+        let challenge = match DigestChallenge::select(
+            response
+                .headers()
+                .get_all(http::header::WWW_AUTHENTICATE)
+                .iter()
+                .filter_map(|value| value.to_str().ok()),
+        ) {
+            Some(challenge) => challenge,
+            None => return Ok(()),
+        };
+
+        let answered = cfg
+            .load::<DigestChallengesAnswered>()
+            .map(|answered| answered.0)
+            .unwrap_or_default();
+        let should_answer = answered < MAX_CHALLENGES_ANSWERED
+            && if attempt.signed {
+                challenge.stale
+            } else {
+                answered == 0
+            };
+        debug!(
+            realm = %challenge.realm,
+            algorithm = challenge.algorithm.as_str(),
+            stale = challenge.stale,
+            should_answer,
+            "received a digest challenge"
+        );
+        self.sessions.update(attempt.authority, challenge);
+
+        if should_answer {
+            // Answering a challenge shouldn't consume one of the attempts allowed by the retry config
+            if let Some(retry_config) = cfg.load::<RetryConfig>() {
+                let retry_config = retry_config
+                    .clone()
+                    .with_max_attempts(retry_config.max_attempts() + 1);
+                cfg.interceptor_state().store_put(retry_config);
+            }
+            cfg.interceptor_state()
+                .store_put(DigestChallengesAnswered(answered + 1));
+            response.extensions_mut().insert(AnswerDigestChallenge);
+        }
+        Ok(())
+    }
+}
+
+/// Retry classifier that immediately retries requests with a digest challenge that should be answered.
+///
+/// This classifier only works in conjunction with [`DigestAuthChallengeInterceptor`], which
+/// decides which challenges are answered.
+#[derive(Debug, Default)]
+pub struct DigestAuthChallengeRetryClassifier;
+
+impl DigestAuthChallengeRetryClassifier {
+    /// Creates a new `DigestAuthChallengeRetryClassifier`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ClassifyRetry for DigestAuthChallengeRetryClassifier {
+    fn classify_retry(&self, ctx: &InterceptorContext) -> RetryAction {
+        let should_answer = ctx
+            .response()
+            .map(|response| {
+                response
+                    .extensions()
+                    .get::<AnswerDigestChallenge>()
+                    .is_some()
+            })
+            .unwrap_or_default();
+        if should_answer {
+            // Answering a challenge isn't an error condition, so don't back off
+            RetryAction::retryable_error_with_explicit_delay(ErrorKind::ClientError, Duration::ZERO)
+        } else {
+            RetryAction::NoActionIndicated
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Digest Auth Challenge"
+    }
+}
+
+/// Details of the current attempt, used to decide whether a challenge should be answered.
+#[derive(Clone, Debug)]
+struct DigestAuthAttempt {
+    authority: String,
+    signed: bool,
+}
+
+impl Storable for DigestAuthAttempt {
+    type Storer = StoreReplace<Self>;
+}
+
+/// The number of digest challenges answered so far for the current operation.
+#[derive(Clone, Copy, Debug)]
+struct DigestChallengesAnswered(u32);
+
+impl Storable for DigestChallengesAnswered {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Response extension that tells the retry classifier to answer a digest challenge.
+#[derive(Clone, Copy, Debug)]
+struct AnswerDigestChallenge;
+
+/// Challenges received from servers, keyed by the authority (`host:port`) they were received from.
+#[derive(Clone, Debug, Default)]
+struct DigestSessions {
+    inner: Arc<Mutex<HashMap<String, DigestSession>>>,
+}
+
+#[derive(Debug)]
+struct DigestSession {
+    challenge: DigestChallenge,
+    nonce_count: u32,
+}
+
+impl DigestSessions {
+    fn update(&self, authority: String, challenge: DigestChallenge) {
+        self.inner.lock().unwrap().insert(
+            authority,
+            DigestSession {
+                challenge,
+                nonce_count: 0,
+            },
+        );
+    }
+
+    fn rotate_nonce(&self, authority: &str, nonce: String) {
+        if let Some(session) = self.inner.lock().unwrap().get_mut(authority) {
+            session.challenge.nonce = nonce;
+            session.nonce_count = 0;
+        }
+    }
+
+    /// Returns the challenge for the given authority along with the next nonce count to use.
+    fn next_nonce_count(&self, authority: &str) -> Option<(DigestChallenge, u32)> {
+        let mut sessions = self.inner.lock().unwrap();
+        let session = sessions.get_mut(authority)?;
+        session.nonce_count = session.nonce_count.wrapping_add(1);
+        Some((session.challenge.clone(), session.nonce_count))
+    }
+}
+
+fn authority_of(uri: &http::Uri) -> String {
+    uri.authority()
+        .map(|authority| authority.as_str().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+fn generate_cnonce() -> String {
+    let bytes: [u8; 16] = std::array::from_fn(|_| fastrand::u8(..));
+    hex::encode(bytes)
+}
+
+/// Hash algorithms supported for digest auth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DigestAlgorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl DigestAlgorithm {
+    fn parse(value: &str) -> Option<Self> {
+        [Self::Md5, Self::Md5Sess, Self::Sha256, Self::Sha256Sess]
+            .into_iter()
+            .find(|algorithm| algorithm.as_str().eq_ignore_ascii_case(value))
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Md5Sess => "MD5-sess",
+            Self::Sha256 => "SHA-256",
+            Self::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_session_variant(&self) -> bool {
+        matches!(self, Self::Md5Sess | Self::Sha256Sess)
+    }
+
+    /// Relative strength, used to pick a challenge when the server offers several.
+    fn strength(&self) -> u8 {
+        match self {
+            Self::Md5 | Self::Md5Sess => 0,
+            Self::Sha256 | Self::Sha256Sess => 1,
+        }
+    }
+
+    fn hash(&self, data: impl AsRef<[u8]>) -> String {
+        match self {
+            Self::Md5 | Self::Md5Sess => hex::encode(Md5::digest(data)),
+            Self::Sha256 | Self::Sha256Sess => hex::encode(Sha256::digest(data)),
+        }
+    }
+}
+
+/// Quality of protection applied to a digest response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Qop {
+    Auth,
+    AuthInt,
+}
+
+impl Qop {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auth => "auth",
+            Self::AuthInt => "auth-int",
+        }
+    }
+}
+
+/// A parsed `WWW-Authenticate: Digest ...` challenge.
+#[derive(Clone, PartialEq, Eq)]
+struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: DigestAlgorithm,
+    /// `None` when the server didn't send a `qop` directive (RFC 2069 compatibility).
+    qop: Option<Vec<Qop>>,
+    stale: bool,
+    userhash: bool,
+}
+
+impl fmt::Debug for DigestChallenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestChallenge")
+            .field("realm", &self.realm)
+            .field("algorithm", &self.algorithm)
+            .field("qop", &self.qop)
+            .field("stale", &self.stale)
+            .field("userhash", &self.userhash)
+            .finish()
+    }
+}
+
+impl DigestChallenge {
+    /// Selects the strongest supported digest challenge out of the given `WWW-Authenticate` header values.
+    fn select<'a>(header_values: impl Iterator<Item = &'a str>) -> Option<Self> {
+        header_values
+            .flat_map(parse_challenges)
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("digest"))
+            .filter_map(|(_, params)| Self::from_params(params))
+            .reduce(|best, next| {
+                if next.algorithm.strength() > best.algorithm.strength() {
+                    next
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Returns `None` if required parameters are missing or the challenge can't be answered.
+    fn from_params(params: Vec<(String, String)>) -> Option<Self> {
+        let mut realm = None;
+        let mut nonce = None;
+        let mut opaque = None;
+        let mut algorithm = DigestAlgorithm::Md5;
+        let mut qop = None;
+        let mut stale = false;
+        let mut userhash = false;
+        for (name, value) in params {
+            match name.to_ascii_lowercase().as_str() {
+                "realm" => realm = Some(value),
+                "nonce" => nonce = Some(value),
+                "opaque" => opaque = Some(value),
+                "algorithm" => match DigestAlgorithm::parse(&value) {
+                    Some(parsed) => algorithm = parsed,
+                    None => {
+                        debug!(algorithm = %value, "ignoring digest challenge with an unsupported algorithm");
+                        return None;
+                    }
+                },
+                "qop" => {
+                    let options: Vec<_> = value
+                        .split(',')
+                        .filter_map(|option| match option.trim() {
+                            "auth" => Some(Qop::Auth),
+                            "auth-int" => Some(Qop::AuthInt),
+                            _ => None,
+                        })
+                        .collect();
+                    if options.is_empty() {
+                        debug!(qop = %value, "ignoring digest challenge with no supported `qop` options");
+                        return None;
+                    }
+                    qop = Some(options);
+                }
+                "stale" => stale = value.eq_ignore_ascii_case("true"),
+                "userhash" => userhash = value.eq_ignore_ascii_case("true"),
+                _ => {}
+            }
+        }
+        Some(Self {
+            realm: realm?,
+            nonce: nonce?,
+            opaque,
+            algorithm,
+            qop,
+            stale,
+            userhash,
+        })
+    }
+
+    /// Computes the `Authorization` header value that answers this challenge.
+    fn authorization(
+        &self,
+        login: &Login,
+        method: &str,
+        digest_uri: &str,
+        body: Option<&[u8]>,
+        nonce_count: u32,
+        cnonce: &str,
+    ) -> Result<String, BoxError> {
+        let algorithm = self.algorithm;
+        // Prefer `auth` since it works for streaming bodies; only use `auth-int` when it's the sole option.
+        let qop = match &self.qop {
+            None => None,
+            Some(options) if options.contains(&Qop::Auth) => Some(Qop::Auth),
+            Some(_) => Some(Qop::AuthInt),
+        };
+
+        let mut ha1 = algorithm.hash(format!(
+            "{}:{}:{}",
+            login.user(),
+            self.realm,
+            login.password()
+        ));
+        if algorithm.is_session_variant() {
+            ha1 = algorithm.hash(format!("{ha1}:{}:{cnonce}", self.nonce));
+        }
+        let ha2 = match qop {
+            Some(Qop::AuthInt) => {
+                let body = body.ok_or(
+                    "HTTP digest auth with `qop=auth-int` requires a request body that is loaded into memory",
+                )?;
+                algorithm.hash(format!("{method}:{digest_uri}:{}", algorithm.hash(body)))
+            }
+            _ => algorithm.hash(format!("{method}:{digest_uri}")),
+        };
+        let nc = format!("{nonce_count:08x}");
+        let response = match qop {
+            Some(qop) => algorithm.hash(format!(
+                "{ha1}:{}:{nc}:{cnonce}:{}:{ha2}",
+                self.nonce,
+                qop.as_str()
+            )),
+            None => algorithm.hash(format!("{ha1}:{}:{ha2}", self.nonce)),
+        };
+
+        let username = if self.userhash {
+            format!(
+                "username={}",
+                quote(&algorithm.hash(format!("{}:{}", login.user(), self.realm)))
+            )
+        } else if login.user().is_ascii() {
+            format!("username={}", quote(login.user()))
+        } else {
+            format!(
+                "username*=UTF-8''{}",
+                percent_encode_ext_value(login.user())
+            )
+        };
+        let mut header = format!(
+            "Digest {username}, realm={}, uri={}, algorithm={}, nonce={}",
+            quote(&self.realm),
+            quote(digest_uri),
+            algorithm.as_str(),
+            quote(&self.nonce),
+        );
+        if let Some(qop) = qop {
+            header.push_str(&format!(
+                ", nc={nc}, cnonce={}, qop={}",
+                quote(cnonce),
+                qop.as_str()
+            ));
+        }
+        header.push_str(&format!(", response={}", quote(&response)));
+        if let Some(opaque) = &self.opaque {
+            header.push_str(&format!(", opaque={}", quote(opaque)));
+        }
+        if self.userhash {
+            header.push_str(", userhash=true");
+        }
+        Ok(header)
+    }
+}
+
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Percent-encodes a value for use in an RFC 5987 `ext-value`.
+fn percent_encode_ext_value(value: &str) -> String {
+    const ATTR_CHARS: &[u8] = b"!#$&+-.^_`|~";
+    let mut encoded = String::with_capacity(value.len());
+    for &byte in value.as_bytes() {
+        if byte.is_ascii_alphanumeric() || ATTR_CHARS.contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Parses a `WWW-Authenticate` header value into a list of challenges ([RFC 7235 section 4.1]).
+///
+/// Each challenge is an auth scheme name and a list of auth parameters. Challenges that use the
+/// `token68` syntax are returned without any parameters.
+///
+/// [RFC 7235 section 4.1]: https://datatracker.ietf.org/doc/html/rfc7235#section-4.1
+fn parse_challenges(header: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut parser = Parser::new(header);
+    let mut challenges = Vec::new();
+    loop {
+        parser.skip_separators();
+        let scheme = match parser.token() {
+            Some(scheme) => scheme,
+            None => break,
+        };
+        let params = parser.auth_params();
+        challenges.push((scheme, params));
+    }
+    challenges
+}
+
+/// Parses a comma-separated list of auth parameters, as found in an `Authentication-Info` header.
+fn parse_auth_params(value: &str) -> Vec<(String, String)> {
+    Parser::new(value).auth_params()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn remaining(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let remaining = self.remaining();
+        self.position += remaining.len() - remaining.trim_start().len();
+    }
+
+    fn skip_separators(&mut self) {
+        let remaining = self.remaining();
+        self.position += remaining.len()
+            - remaining
+                .trim_start_matches(|c: char| c == ',' || c.is_whitespace())
+                .len();
+    }
+
+    fn token(&mut self) -> Option<String> {
+        let remaining = self.remaining();
+        let length = remaining
+            .find(|c: char| !is_token_char(c))
+            .unwrap_or(remaining.len());
+        if length == 0 {
+            return None;
+        }
+        self.position += length;
+        Some(remaining[..length].to_string())
+    }
+
+    fn quoted_string(&mut self) -> Option<String> {
+        let mut chars = self.remaining().char_indices();
+        if chars.next() != Some((0, '"')) {
+            return None;
+        }
+        let mut value = String::new();
+        let mut escaped = false;
+        for (index, c) in chars {
+            if escaped {
+                value.push(c);
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                self.position += index + 1;
+                return Some(value);
+            } else {
+                value.push(c);
+            }
+        }
+        // Unterminated quoted string: consume the rest of the input
+        self.position = self.input.len();
+        Some(value)
+    }
+
+    /// Parses auth parameters until the input is exhausted or the start of the next challenge is found.
+    fn auth_params(&mut self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        self.skip_whitespace();
+        if self.skip_token68() {
+            return params;
+        }
+        loop {
+            self.skip_separators();
+            let start = self.position;
+            let name = match self.token() {
+                Some(name) => name,
+                None => break,
+            };
+            self.skip_whitespace();
+            if !self.remaining().starts_with('=') {
+                // This is the scheme of the next challenge
+                self.position = start;
+                break;
+            }
+            self.position += 1;
+            self.skip_whitespace();
+            let value = self
+                .quoted_string()
+                .or_else(|| self.token())
+                .unwrap_or_default();
+            params.push((name, value));
+        }
+        params
+    }
+
+    /// Skips a `token68` value if one is present at the current position.
+    fn skip_token68(&mut self) -> bool {
+        let remaining = self.remaining();
+        let length = remaining
+            .find(|c: char| !(c.is_ascii_alphanumeric() || "-._~+/".contains(c)))
+            .unwrap_or(remaining.len());
+        let rest = &remaining[length..];
+        let padding = rest.len() - rest.trim_start_matches('=').len();
+        let after = &rest[padding..];
+        if length > 0 && (after.is_empty() || after.trim_start().starts_with(',')) {
+            self.position += length + padding;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::body::SdkBody;
+
+    // Test vectors from https://datatracker.ietf.org/doc/html/rfc7616#section-3.9.1
+    const RFC_CHALLENGE_MD5: &str = r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#;
+    const RFC_CHALLENGE_SHA256: &str = r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#;
+    const RFC_CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn rfc_login() -> Login {
+        Login::new("Mufasa", "Circle of Life", None)
+    }
+
+    fn response_param(header: &str) -> String {
+        parse_auth_params(header.strip_prefix("Digest ").unwrap())
+            .into_iter()
+            .find(|(name, _)| name == "response")
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn parse_multiple_challenges() {
+        let challenges = parse_challenges(
+            r#"Newauth realm="apps", type=1, title="Login to \"apps\"", Basic realm="simple", Bearer abc123=="#,
+        );
+        assert_eq!(
+            vec![
+                (
+                    "Newauth".to_string(),
+                    vec![
+                        ("realm".to_string(), "apps".to_string()),
+                        ("type".to_string(), "1".to_string()),
+                        ("title".to_string(), r#"Login to "apps""#.to_string()),
+                    ]
+                ),
+                (
+                    "Basic".to_string(),
+                    vec![("realm".to_string(), "simple".to_string())]
+                ),
+                ("Bearer".to_string(), vec![]),
+            ],
+            challenges
+        );
+    }
+
+    #[test]
+    fn select_prefers_sha256() {
+        let challenge =
+            DigestChallenge::select([RFC_CHALLENGE_MD5, RFC_CHALLENGE_SHA256].into_iter()).unwrap();
+        assert_eq!(DigestAlgorithm::Sha256, challenge.algorithm);
+        assert_eq!(Some(vec![Qop::Auth, Qop::AuthInt]), challenge.qop);
+        assert_eq!("http-auth@example.org", challenge.realm);
+
+        let combined = format!("Basic realm=\"x\", {RFC_CHALLENGE_MD5}, {RFC_CHALLENGE_SHA256}");
+        let challenge = DigestChallenge::select([combined.as_str()].into_iter()).unwrap();
+        assert_eq!(DigestAlgorithm::Sha256, challenge.algorithm);
+    }
+
+    #[test]
+    fn select_ignores_unsupported_challenges() {
+        assert!(DigestChallenge::select(
+            [
+                r#"Digest realm="r", nonce="n", algorithm=SHA-512-256"#,
+                r#"Digest realm="r", nonce="n", qop="auth-conf""#,
+                r#"Digest realm="r""#,
+                r#"Basic realm="r""#,
+            ]
+            .into_iter()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn rfc7616_md5() {
+        let challenge = DigestChallenge::select([RFC_CHALLENGE_MD5].into_iter()).unwrap();
+        let header = challenge
+            .authorization(&rfc_login(), "GET", "/dir/index.html", None, 1, RFC_CNONCE)
+            .unwrap();
+        assert_eq!("8ca523f5e9506fed4657c9700eebdbec", response_param(&header));
+        assert_eq!(
+            r#"Digest username="Mufasa", realm="http-auth@example.org", uri="/dir/index.html", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", nc=00000001, cnonce="f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ", qop=auth, response="8ca523f5e9506fed4657c9700eebdbec", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+            header
+        );
+    }
+
+    #[test]
+    fn rfc7616_sha256() {
+        let challenge = DigestChallenge::select([RFC_CHALLENGE_SHA256].into_iter()).unwrap();
+        let header = challenge
+            .authorization(&rfc_login(), "GET", "/dir/index.html", None, 1, RFC_CNONCE)
+            .unwrap();
+        assert_eq!(
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1",
+            response_param(&header)
+        );
+    }
+
+    #[test]
+    fn session_variant_and_auth_int() {
+        let challenge = DigestChallenge::select(
+            [r#"Digest realm="r", nonce="n", algorithm=MD5-sess, qop="auth-int""#].into_iter(),
+        )
+        .unwrap();
+        let header = challenge
+            .authorization(&rfc_login(), "POST", "/", Some(b"hello"), 2, "c")
+            .unwrap();
+        let alg = DigestAlgorithm::Md5;
+        let ha1 = alg.hash(format!("{}:n:c", alg.hash("Mufasa:r:Circle of Life")));
+        let ha2 = alg.hash(format!("POST:/:{}", alg.hash("hello")));
+        let expected = alg.hash(format!("{ha1}:n:00000002:c:auth-int:{ha2}"));
+        assert_eq!(expected, response_param(&header));
+        assert!(header.contains("qop=auth-int"));
+        assert!(header.contains("nc=00000002"));
+
+        let err = challenge
+            .authorization(&rfc_login(), "POST", "/", None, 1, "c")
+            .unwrap_err();
+        assert!(format!("{err}").contains("auth-int"));
+    }
+
+    #[test]
+    fn legacy_challenge_without_qop() {
+        let challenge =
+            DigestChallenge::select([r#"Digest realm="r", nonce="n""#].into_iter()).unwrap();
+        let header = challenge
+            .authorization(&rfc_login(), "GET", "/a", None, 1, "c")
+            .unwrap();
+        let alg = DigestAlgorithm::Md5;
+        let expected = alg.hash(format!(
+            "{}:n:{}",
+            alg.hash("Mufasa:r:Circle of Life"),
+            alg.hash("GET:/a")
+        ));
+        assert_eq!(expected, response_param(&header));
+        assert!(!header.contains("qop="));
+        assert!(!header.contains("cnonce="));
+    }
+
+    #[test]
+    fn userhash_and_non_ascii_usernames() {
+        let challenge = DigestChallenge::select(
+            [r#"Digest realm="r", nonce="n", algorithm=SHA-256, qop=auth, userhash=true"#]
+                .into_iter(),
+        )
+        .unwrap();
+        let header = challenge
+            .authorization(&rfc_login(), "GET", "/", None, 1, "c")
+            .unwrap();
+        let hashed = DigestAlgorithm::Sha256.hash("Mufasa:r");
+        assert!(header.starts_with(&format!("Digest username=\"{hashed}\"")));
+        assert!(header.ends_with("userhash=true"));
+
+        let challenge =
+            DigestChallenge::select([r#"Digest realm="r", nonce="n", qop=auth"#].into_iter())
+                .unwrap();
+        let header = challenge
+            .authorization(
+                &Login::new("Jäsøn Doe", "pw", None),
+                "GET",
+                "/",
+                None,
+                1,
+                "c",
+            )
+            .unwrap();
+        assert!(header.starts_with("Digest username*=UTF-8''J%C3%A4s%C3%B8n%20Doe, "));
+    }
+
+    #[test]
+    fn signer_tracks_nonce_count() {
+        let scheme = DigestAuthScheme::new();
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let config_bag = ConfigBag::base();
+        let identity = Identity::new(rfc_login(), None);
+        let sign = || {
+            let mut request: HttpRequest = http::Request::builder()
+                .uri("https://example.com/dir/index.html?a=b")
+                .body(SdkBody::empty())
+                .unwrap()
+                .try_into()
+                .unwrap();
+            scheme
+                .signer()
+                .sign_http_request(
+                    &mut request,
+                    &identity,
+                    AuthSchemeEndpointConfig::empty(),
+                    &runtime_components,
+                    &config_bag,
+                )
+                .expect("success");
+            request
+                .headers()
+                .get(http::header::AUTHORIZATION)
+                .map(str::to_string)
+        };
+
+        // Without a challenge, requests aren't signed
+        assert_eq!(None, sign());
+
+        scheme.signer.sessions.update(
+            "example.com".into(),
+            DigestChallenge::select([RFC_CHALLENGE_SHA256].into_iter()).unwrap(),
+        );
+        let first = sign().unwrap();
+        assert!(first.contains("nc=00000001"));
+        assert!(first.contains(r#"uri="/dir/index.html?a=b""#));
+        assert!(sign().unwrap().contains("nc=00000002"));
+
+        scheme
+            .signer
+            .sessions
+            .rotate_nonce("example.com", "new-nonce".into());
+        let rotated = sign().unwrap();
+        assert!(rotated.contains("nc=00000001"));
+        assert!(rotated.contains(r#"nonce="new-nonce""#));
+    }
+}
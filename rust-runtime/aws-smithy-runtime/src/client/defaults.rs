@@ -12,6 +12,7 @@
 use crate::client::identity::IdentityCache;
 use crate::client::retries::strategy::StandardRetryStrategy;
 use crate::client::retries::RetryPartition;
+use crate::client::telemetry::NoopMeterProvider;
 use aws_smithy_async::rt::sleep::default_async_sleep;
 use aws_smithy_async::time::SystemTimeSource;
 use aws_smithy_runtime_api::box_error::BoxError;
@@ -47,10 +48,24 @@ where
 }
 
 /// Runtime plugin that provides a default connector.
+///
+/// When the `connector-async-io` feature is enabled without `rt-tokio`, the default connector doesn't
+/// require a Tokio runtime. Like [`default_async_sleep`], Tokio is preferred when both are enabled.
+/// When only the `connector-hyper-1-x` feature is enabled, the default connector is built on hyper 1.x.
 pub fn default_http_client_plugin() -> Option<SharedRuntimePlugin> {
     let _default: Option<SharedHttpClient> = None;
-    #[cfg(feature = "connector-hyper-0-14-x")]
+    #[cfg(all(
+        feature = "connector-hyper-1-x",
+        not(feature = "connector-hyper-0-14-x")
+    ))]
+    let _default = crate::client::http::hyper_1::default_client();
+    #[cfg(all(
+        feature = "connector-hyper-0-14-x",
+        any(feature = "rt-tokio", not(feature = "connector-async-io"))
+    ))]
     let _default = crate::client::http::hyper_014::default_client();
+    #[cfg(all(feature = "connector-async-io", not(feature = "rt-tokio")))]
+    let _default = crate::client::http::async_io::default_client();
 
     _default.map(|default| {
         default_plugin("default_http_client_plugin", |components| {
@@ -80,6 +95,16 @@ pub fn default_time_source_plugin() -> Option<SharedRuntimePlugin> {
     )
 }
 
+/// Runtime plugin that provides a default meter provider that discards all metrics.
+pub fn default_meter_provider_plugin() -> Option<SharedRuntimePlugin> {
+    Some(
+        default_plugin("default_meter_provider_plugin", |components| {
+            components.with_meter_provider(Some(NoopMeterProvider::new()))
+        })
+        .into_shared(),
+    )
+}
+
 /// Runtime plugin that sets the default retry strategy, config (disabled), and partition.
 pub fn default_retry_config_plugin(
     default_partition_name: impl Into<Cow<'static, str>>,
@@ -192,6 +217,7 @@ pub fn default_plugins(
     [
         default_http_client_plugin(),
         default_identity_cache_plugin(),
+        default_meter_provider_plugin(),
         default_retry_config_plugin(
             params
                 .retry_partition_name
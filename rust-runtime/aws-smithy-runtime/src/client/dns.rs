@@ -5,6 +5,9 @@
 
 //! Built-in DNS resolver implementations.
 
+mod caching;
+pub use self::caching::{CachingDnsResolver, CachingDnsResolverBuilder};
+
 #[cfg(all(feature = "rt-tokio", not(target_family = "wasm")))]
 mod tokio {
     use aws_smithy_runtime_api::client::dns::{DnsFuture, ResolveDns, ResolveDnsError};
@@ -0,0 +1,343 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_runtime_api::client::dns::{
+    DnsFuture, ResolveDns, ResolveDnsError, SharedDnsResolver,
+};
+use aws_smithy_runtime_api::shared::IntoShared;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(30);
+const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(10);
+
+/// Builder for [`CachingDnsResolver`].
+#[derive(Debug)]
+pub struct CachingDnsResolverBuilder {
+    resolver: SharedDnsResolver,
+    ttl: Option<Duration>,
+    negative_ttl: Option<Duration>,
+    time_source: Option<SharedTimeSource>,
+}
+
+impl CachingDnsResolverBuilder {
+    /// Set how long successful lookups are cached for.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.set_ttl(Some(ttl));
+        self
+    }
+
+    /// Set how long successful lookups are cached for.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn set_ttl(&mut self, ttl: Option<Duration>) -> &mut Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set how long failed lookups are cached for.
+    ///
+    /// Lookups of a name that recently failed to resolve will fail immediately with the cached
+    /// error until this duration has passed. Setting this to zero disables negative caching.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.set_negative_ttl(Some(negative_ttl));
+        self
+    }
+
+    /// Set how long failed lookups are cached for.
+    ///
+    /// Lookups of a name that recently failed to resolve will fail immediately with the cached
+    /// error until this duration has passed. Setting this to zero disables negative caching.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn set_negative_ttl(&mut self, negative_ttl: Option<Duration>) -> &mut Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    /// Set the time source used to expire cache entries.
+    ///
+    /// This is only necessary for testing.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.set_time_source(Some(time_source.into_shared()));
+        self
+    }
+
+    /// Set the time source used to expire cache entries.
+    ///
+    /// This is only necessary for testing.
+    pub fn set_time_source(&mut self, time_source: Option<SharedTimeSource>) -> &mut Self {
+        self.time_source = time_source;
+        self
+    }
+
+    /// Create the [`CachingDnsResolver`].
+    pub fn build(self) -> CachingDnsResolver {
+        CachingDnsResolver {
+            resolver: self.resolver,
+            ttl: self.ttl.unwrap_or(DEFAULT_TTL),
+            negative_ttl: self.negative_ttl.unwrap_or(DEFAULT_NEGATIVE_TTL),
+            time_source: self.time_source.unwrap_or_default(),
+            cache: Default::default(),
+        }
+    }
+}
+
+/// DNS resolver that caches the lookups of another [`ResolveDns`] implementation.
+///
+/// Successful lookups are cached for a fixed TTL, and failed lookups are cached for a separate,
+/// typically shorter, negative TTL. Each lookup served from the cache rotates the order of the
+/// resolved addresses so that new connections are spread round-robin across every address
+/// that a name resolved to.
+///
+/// # Examples
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::dns::{CachingDnsResolver, TokioDnsResolver};
+/// use aws_smithy_runtime::client::http::hyper_014::HyperClientBuilder;
+/// use std::time::Duration;
+///
+/// let resolver = CachingDnsResolver::builder(TokioDnsResolver::new())
+///     .ttl(Duration::from_secs(60))
+///     .build();
+/// let http_client = HyperClientBuilder::new()
+///     .dns_resolver(resolver)
+///     .build_https();
+/// ```
+#[derive(Debug)]
+pub struct CachingDnsResolver {
+    resolver: SharedDnsResolver,
+    ttl: Duration,
+    negative_ttl: Duration,
+    time_source: SharedTimeSource,
+    cache: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl CachingDnsResolver {
+    /// Returns a builder for a `CachingDnsResolver` that caches the lookups of `resolver`.
+    pub fn builder(resolver: impl ResolveDns + 'static) -> CachingDnsResolverBuilder {
+        CachingDnsResolverBuilder {
+            resolver: resolver.into_shared(),
+            ttl: None,
+            negative_ttl: None,
+            time_source: None,
+        }
+    }
+
+    /// Returns a cached lookup for `name` if there is one that hasn't expired.
+    fn cached(&self, name: &str) -> Option<Result<Vec<IpAddr>, ResolveDnsError>> {
+        let now = self.time_source.now();
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get_mut(name) {
+            Some(entry) if now < entry.expires_at => Some(entry.next_result()),
+            Some(_) => {
+                cache.remove(name);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn store(
+        &self,
+        name: &str,
+        result: Result<Vec<IpAddr>, ResolveDnsError>,
+    ) -> Result<Vec<IpAddr>, ResolveDnsError> {
+        let (result, ttl) = match result {
+            Ok(addresses) => (Ok(addresses), self.ttl),
+            Err(err) => (Err(Arc::new(err)), self.negative_ttl),
+        };
+        let mut entry = CacheEntry {
+            result,
+            expires_at: self.time_source.now() + ttl,
+            next: 0,
+        };
+        let next = entry.next_result();
+        if !ttl.is_zero() {
+            self.cache.lock().unwrap().insert(name.to_string(), entry);
+        }
+        next
+    }
+}
+
+impl ResolveDns for CachingDnsResolver {
+    fn resolve_dns<'a>(&'a self, name: &'a str) -> DnsFuture<'a> {
+        if let Some(result) = self.cached(name) {
+            tracing::trace!(name = %name, "resolved DNS lookup from the cache");
+            return DnsFuture::ready(result);
+        }
+        DnsFuture::new(async move {
+            let result = self.resolver.resolve_dns(name).await;
+            self.store(name, result)
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    result: Result<Vec<IpAddr>, Arc<ResolveDnsError>>,
+    expires_at: SystemTime,
+    /// Index of the address that the next lookup should start with
+    next: usize,
+}
+
+impl CacheEntry {
+    fn next_result(&mut self) -> Result<Vec<IpAddr>, ResolveDnsError> {
+        match &self.result {
+            Ok(addresses) => {
+                let mut addresses = addresses.clone();
+                if !addresses.is_empty() {
+                    let start = self.next % addresses.len();
+                    addresses.rotate_left(start);
+                    self.next = self.next.wrapping_add(1);
+                }
+                Ok(addresses)
+            }
+            Err(err) => Err(ResolveDnsError::new(CachedLookupFailure {
+                source: err.clone(),
+            })),
+        }
+    }
+}
+
+/// A failed lookup that was stored in the cache.
+#[derive(Debug)]
+struct CachedLookupFailure {
+    source: Arc<ResolveDnsError>,
+}
+
+impl fmt::Display for CachedLookupFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cached DNS lookup failure")
+    }
+}
+
+impl StdError for CachedLookupFailure {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(self.source.as_ref() as _)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use std::net::Ipv4Addr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Clone, Debug, Default)]
+    struct CountingResolver {
+        lookups: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    impl CountingResolver {
+        fn lookups(&self) -> usize {
+            self.lookups.load(Ordering::SeqCst)
+        }
+    }
+
+    impl ResolveDns for CountingResolver {
+        fn resolve_dns<'a>(&'a self, _name: &'a str) -> DnsFuture<'a> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                DnsFuture::ready(Err(ResolveDnsError::new("no such host")))
+            } else {
+                DnsFuture::ready(Ok(vec![ip(1), ip(2), ip(3)]))
+            }
+        }
+    }
+
+    fn ip(last_octet: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet))
+    }
+
+    fn resolver(
+        fail: bool,
+    ) -> (
+        CachingDnsResolverBuilder,
+        CountingResolver,
+        ManualTimeSource,
+    ) {
+        let inner = CountingResolver {
+            fail,
+            ..Default::default()
+        };
+        let time = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let builder = CachingDnsResolver::builder(inner.clone()).time_source(time.clone());
+        (builder, inner, time)
+    }
+
+    #[tokio::test]
+    async fn caches_lookups_until_the_ttl_expires() {
+        let (builder, inner, time) = resolver(false);
+        let resolver = builder.ttl(Duration::from_secs(60)).build();
+
+        resolver.resolve_dns("example.com").await.unwrap();
+        time.advance(Duration::from_secs(59));
+        resolver.resolve_dns("example.com").await.unwrap();
+        assert_eq!(1, inner.lookups());
+
+        time.advance(Duration::from_secs(1));
+        resolver.resolve_dns("example.com").await.unwrap();
+        assert_eq!(2, inner.lookups());
+
+        // Names are cached independently
+        resolver.resolve_dns("example.org").await.unwrap();
+        assert_eq!(3, inner.lookups());
+    }
+
+    #[tokio::test]
+    async fn rotates_addresses_round_robin() {
+        let (builder, _inner, _time) = resolver(false);
+        let resolver = builder.build();
+
+        let mut firsts = Vec::new();
+        for _ in 0..4 {
+            let addresses = resolver.resolve_dns("example.com").await.unwrap();
+            assert_eq!(3, addresses.len());
+            firsts.push(addresses[0]);
+        }
+        assert_eq!(vec![ip(1), ip(2), ip(3), ip(1)], firsts);
+    }
+
+    #[tokio::test]
+    async fn caches_failed_lookups_for_the_negative_ttl() {
+        let (builder, inner, time) = resolver(true);
+        let resolver = builder.negative_ttl(Duration::from_secs(5)).build();
+
+        resolver.resolve_dns("example.com").await.unwrap_err();
+        let err = resolver.resolve_dns("example.com").await.unwrap_err();
+        assert_eq!(1, inner.lookups());
+        let message = format!(
+            "{}",
+            aws_smithy_types::error::display::DisplayErrorContext(&err)
+        );
+        assert!(message.contains("no such host"), "{message}");
+
+        time.advance(Duration::from_secs(5));
+        resolver.resolve_dns("example.com").await.unwrap_err();
+        assert_eq!(2, inner.lookups());
+    }
+
+    #[tokio::test]
+    async fn zero_negative_ttl_disables_negative_caching() {
+        let (builder, inner, _time) = resolver(true);
+        let resolver = builder.negative_ttl(Duration::ZERO).build();
+
+        resolver.resolve_dns("example.com").await.unwrap_err();
+        resolver.resolve_dns("example.com").await.unwrap_err();
+        assert_eq!(2, inner.lookups());
+    }
+}
@@ -11,10 +11,22 @@ pub mod test_util;
 
 /// Default HTTP and TLS connectors that use hyper 0.14.x and rustls.
 ///
-/// This module is named after the hyper version number since we anticipate
-/// needing to provide equivalent functionality for hyper 1.x in the future.
+/// This module is named after the hyper version number since [`hyper_1`](crate::client::http::hyper_1)
+/// provides equivalent functionality for hyper 1.x.
 #[cfg(feature = "connector-hyper-0-14-x")]
 pub mod hyper_014;
 
+/// HTTP and TLS connectors that use hyper 1.x, hyper-util, and rustls.
+#[cfg(feature = "connector-hyper-1-x")]
+pub mod hyper_1;
+
+/// An HTTP client built on hyper 0.14.x and `async-io` that doesn't require a Tokio runtime.
+#[cfg(feature = "connector-async-io")]
+pub mod async_io;
+
+/// Interceptor for compressing request bodies.
+#[cfg(feature = "request-compression")]
+pub mod request_compression;
+
 /// HTTP body and body-wrapper types
 pub mod body;
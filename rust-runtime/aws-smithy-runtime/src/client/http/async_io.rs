@@ -0,0 +1,272 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! An HTTP client that doesn't require a Tokio runtime.
+//!
+//! The client is built on hyper 0.14.x, but opens its TCP connections with [`async-io`](https://docs.rs/async-io)
+//! and runs hyper's background connection tasks on [`async-global-executor`](https://docs.rs/async-global-executor).
+//! This makes it usable from `smol` and `async-std` applications, as well as from Tokio.
+//!
+//! A client built on this module needs an [`AsyncSleep`](aws_smithy_async::rt::sleep::AsyncSleep)
+//! implementation that doesn't require Tokio either, such as
+//! [`AsyncIoSleep`](aws_smithy_async::rt::sleep::AsyncIoSleep).
+//!
+//! # Examples
+//!
+//! ```no_run,ignore
+//! use aws_smithy_async::rt::sleep::AsyncIoSleep;
+//! use aws_smithy_runtime::client::http::async_io;
+//!
+//! let config = my_service_client::Config::builder()
+//!     .http_client(async_io::default_client().expect("tls-rustls is enabled"))
+//!     .sleep_impl(AsyncIoSleep::new())
+//!     .build();
+//! let client = my_service_client::Client::from_conf(config);
+//! ```
+
+use aws_smithy_runtime_api::client::http::SharedHttpClient;
+use futures_lite::io::{AsyncRead as _, AsyncWrite as _};
+use http::uri::Scheme;
+use http::Uri;
+use hyper_0_14::client::connect::{Connected, Connection};
+use hyper_0_14::service::Service;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Creates an HTTPS client that doesn't require a Tokio runtime, if the `tls-rustls` feature is enabled.
+pub fn default_client() -> Option<SharedHttpClient> {
+    #[cfg(feature = "tls-rustls")]
+    {
+        tracing::trace!("creating a new default async-io client");
+        Some(
+            crate::client::http::hyper_014::HyperClientBuilder::new()
+                .hyper_builder(hyper_builder())
+                .build(https()),
+        )
+    }
+    #[cfg(not(feature = "tls-rustls"))]
+    {
+        tracing::trace!("no default async-io client available");
+        None
+    }
+}
+
+/// Returns an HTTPS connector that opens its TCP connections with [`AsyncIoTcpConnector`].
+///
+/// It uses the same TLS configuration as the default hyper 0.14.x client, and allows you to
+/// connect to both `http` and `https` URLs.
+#[cfg(feature = "tls-rustls")]
+pub fn https() -> hyper_rustls::HttpsConnector<AsyncIoTcpConnector> {
+    hyper_rustls::HttpsConnectorBuilder::new()
+        .with_tls_config(crate::client::http::hyper_014::default_connector::tls_config())
+        .https_or_http()
+        .enable_http1()
+        .enable_http2()
+        .wrap_connector(AsyncIoTcpConnector::new())
+}
+
+/// Returns a hyper client [`Builder`](hyper_0_14::client::Builder) that can be used without a Tokio runtime.
+///
+/// The builder spawns hyper's background tasks with [`AsyncIoExecutor`]. It also disables the
+/// connection pool's idle timeout, since evicting idle connections relies on a Tokio timer.
+/// Connections that were closed by the server are still discarded when they're next checked out.
+///
+/// Pass this to [`HyperClientBuilder::hyper_builder`](crate::client::http::hyper_014::HyperClientBuilder::hyper_builder) when building a client with a custom connector.
+pub fn hyper_builder() -> hyper_0_14::client::Builder {
+    let mut builder = hyper_0_14::Client::builder();
+    builder
+        .executor(AsyncIoExecutor::new())
+        .pool_idle_timeout(None);
+    builder
+}
+
+/// A hyper [`Executor`](hyper_0_14::rt::Executor) that spawns tasks on the global `async-global-executor`.
+///
+/// This is the same executor that `async-std` spawns its tasks onto.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct AsyncIoExecutor;
+
+impl AsyncIoExecutor {
+    /// Creates a new `AsyncIoExecutor`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<F> hyper_0_14::rt::Executor<F> for AsyncIoExecutor
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fn execute(&self, future: F) {
+        async_global_executor::spawn(future).detach();
+    }
+}
+
+/// A TCP connector for hyper that opens connections with `async-io`.
+///
+/// Host names are resolved on `async-io`'s blocking thread pool using the standard library's resolver.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct AsyncIoTcpConnector;
+
+impl AsyncIoTcpConnector {
+    /// Creates a new `AsyncIoTcpConnector`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Service<Uri> for AsyncIoTcpConnector {
+    type Response = AsyncIoTcpStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        Box::pin(async move {
+            let host = uri.host().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "URI is missing a host")
+            })?;
+            // IPv6 addresses are enclosed in brackets in URIs
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            let port = uri.port_u16().unwrap_or_else(|| {
+                if uri.scheme() == Some(&Scheme::HTTPS) {
+                    443
+                } else {
+                    80
+                }
+            });
+            let stream = async_net::TcpStream::connect((host, port)).await?;
+            stream.set_nodelay(true)?;
+            Ok(AsyncIoTcpStream { inner: stream })
+        })
+    }
+}
+
+/// A TCP connection opened by [`AsyncIoTcpConnector`].
+pub struct AsyncIoTcpStream {
+    inner: async_net::TcpStream,
+}
+
+impl fmt::Debug for AsyncIoTcpStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncIoTcpStream")
+            .field("peer_addr", &self.inner.peer_addr().ok())
+            .finish()
+    }
+}
+
+impl Connection for AsyncIoTcpStream {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for AsyncIoTcpStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let read = ready!(Pin::new(&mut self.inner).poll_read(cx, buf.initialize_unfilled()))?;
+        buf.advance(read);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for AsyncIoTcpStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::{hyper_builder, AsyncIoTcpConnector};
+    use crate::client::http::hyper_014::HyperClientBuilder;
+    use aws_smithy_async::rt::sleep::AsyncIoSleep;
+    use aws_smithy_runtime_api::client::http::{HttpClient, HttpConnector, HttpConnectorSettings};
+    use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::byte_stream::ByteStream;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    #[test]
+    fn sends_requests_without_a_tokio_runtime() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // Consume the remaining headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            reader
+                .into_inner()
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nhello")
+                .unwrap();
+            request_line
+        });
+
+        let client = HyperClientBuilder::new()
+            .hyper_builder(hyper_builder())
+            .build(AsyncIoTcpConnector::new());
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_sleep_impl(Some(AsyncIoSleep::new()))
+            .build()
+            .unwrap();
+        let settings = HttpConnectorSettings::builder()
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(5))
+            .build();
+        let connector = client.http_connector(&settings, &components);
+
+        let (status, body) = async_io::block_on(async {
+            let request = HttpRequest::try_from(
+                http::Request::builder()
+                    .uri(format!("http://{addr}/greeting"))
+                    .body(SdkBody::empty())
+                    .unwrap(),
+            )
+            .unwrap();
+            let response = connector.call(request).await.expect("request succeeds");
+            let status = response.status().as_u16();
+            let body = ByteStream::new(response.into_body()).collect().await;
+            (status, body.unwrap().into_bytes())
+        });
+
+        assert_eq!(200, status);
+        assert_eq!(&b"hello"[..], &body[..]);
+        assert_eq!("GET /greeting HTTP/1.1\r\n", server.join().unwrap());
+    }
+}
@@ -4,10 +4,12 @@
  */
 
 use crate::client::http::connection_poisoning::CaptureSmithyConnection;
+use crate::client::telemetry::{metric_names, METER_SCOPE};
 use aws_smithy_async::future::timeout::TimedOutError;
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep, SharedAsyncSleep};
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::connection::ConnectionMetadata;
+use aws_smithy_runtime_api::client::dns::{ResolveDns, SharedDnsResolver};
 use aws_smithy_runtime_api::client::http::{
     HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpClient,
     SharedHttpConnector,
@@ -15,6 +17,9 @@ use aws_smithy_runtime_api::client::http::{
 use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
 use aws_smithy_runtime_api::client::result::ConnectorError;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::client::telemetry::{
+    InstrumentDescriptor, Meter, ProvideMeter, SharedMeterProvider,
+};
 use aws_smithy_runtime_api::shared::IntoShared;
 use aws_smithy_types::body::SdkBody;
 use aws_smithy_types::error::display::DisplayErrorContext;
@@ -31,18 +36,21 @@ use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 #[cfg(feature = "tls-rustls")]
-mod default_connector {
+pub(crate) mod default_connector {
     use aws_smithy_async::rt::sleep::SharedAsyncSleep;
+    use aws_smithy_runtime_api::client::dns::{ResolveDns, ResolveDnsError, SharedDnsResolver};
     use aws_smithy_runtime_api::client::http::HttpConnectorSettings;
+    use hyper_0_14::client::connect::dns::Name;
+    use std::future::Future;
+    use std::net::SocketAddr;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
 
-    // Creating a `with_native_roots` HTTP client takes 300ms on OS X. Cache this so that we
+    // Loading the native root certificates takes 300ms on OS X. Cache this so that we
     // don't need to repeatedly incur that cost.
-    static HTTPS_NATIVE_ROOTS: once_cell::sync::Lazy<
-        hyper_rustls::HttpsConnector<hyper_0_14::client::HttpConnector>,
-    > = once_cell::sync::Lazy::new(|| {
-        use hyper_rustls::ConfigBuilderExt;
-        hyper_rustls::HttpsConnectorBuilder::new()
-        .with_tls_config(
+    static TLS_CONFIG: once_cell::sync::Lazy<rustls::ClientConfig> = once_cell::sync::Lazy::new(
+        || {
+            use hyper_rustls::ConfigBuilderExt;
             rustls::ClientConfig::builder()
                 .with_cipher_suites(&[
                     // TLS1.3 suites
@@ -60,12 +68,15 @@ mod default_connector {
                 .expect("Error with the TLS configuration. Please file a bug report under https://github.com/awslabs/smithy-rs/issues.")
                 .with_native_roots()
                 .with_no_client_auth()
-        )
-        .https_or_http()
-        .enable_http1()
-        .enable_http2()
-        .build()
-    });
+        },
+    );
+
+    /// Returns the default `rustls` configuration, which trusts the platform's native root certificates.
+    ///
+    /// It requires a minimum TLS version of 1.2.
+    pub(crate) fn tls_config() -> rustls::ClientConfig {
+        TLS_CONFIG.clone()
+    }
 
     pub(super) fn base(
         settings: &HttpConnectorSettings,
@@ -78,12 +89,71 @@ mod default_connector {
         hyper
     }
 
-    /// Return a default HTTPS connector backed by the `rustls` crate.
+    /// Return an HTTPS connector backed by the `rustls` crate that wraps the given HTTP connector.
     ///
     /// It requires a minimum TLS version of 1.2.
     /// It allows you to connect to both `http` and `https` URLs.
-    pub(super) fn https() -> hyper_rustls::HttpsConnector<hyper_0_14::client::HttpConnector> {
-        HTTPS_NATIVE_ROOTS.clone()
+    pub(super) fn https<R>(
+        http_connector: hyper_0_14::client::HttpConnector<R>,
+    ) -> hyper_rustls::HttpsConnector<hyper_0_14::client::HttpConnector<R>> {
+        hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(tls_config())
+            .https_or_http()
+            .enable_http1()
+            .enable_http2()
+            .wrap_connector(http_connector)
+    }
+
+    /// Returns a TCP connector that resolves host names with `resolver`.
+    ///
+    /// When a host name resolves to several addresses, the connector tries each of them in turn,
+    /// racing IPv6 and IPv4 addresses happy-eyeballs style, and only fails once every address
+    /// has failed. The connect timeout is split evenly across the addresses so that one
+    /// unreachable address doesn't use up the entire timeout.
+    pub(super) fn http_connector<R>(
+        resolver: R,
+        connect_timeout: Option<std::time::Duration>,
+    ) -> hyper_0_14::client::HttpConnector<R> {
+        let mut connector = hyper_0_14::client::HttpConnector::new_with_resolver(resolver);
+        // The connector is wrapped by an HTTPS connector, so it must allow `https` URLs
+        connector.enforce_http(false);
+        connector.set_connect_timeout(connect_timeout);
+        connector
+    }
+
+    /// Adapts a [`ResolveDns`] implementation to the resolver interface of hyper's HTTP connector.
+    #[derive(Clone, Debug)]
+    pub(super) struct DnsResolverAdapter {
+        resolver: SharedDnsResolver,
+    }
+
+    impl DnsResolverAdapter {
+        pub(super) fn new(resolver: SharedDnsResolver) -> Self {
+            Self { resolver }
+        }
+    }
+
+    impl hyper_0_14::service::Service<Name> for DnsResolverAdapter {
+        type Response = std::vec::IntoIter<SocketAddr>;
+        type Error = ResolveDnsError;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, name: Name) -> Self::Future {
+            let resolver = self.resolver.clone();
+            Box::pin(async move {
+                let addresses = resolver.resolve_dns(name.as_str()).await?;
+                // The HTTP connector replaces the port with the one from the request URI
+                Ok(addresses
+                    .into_iter()
+                    .map(|ip| SocketAddr::new(ip, 0))
+                    .collect::<Vec<_>>()
+                    .into_iter())
+            })
+        }
     }
 }
 
@@ -194,6 +264,7 @@ pub struct HyperConnectorBuilder {
     connector_settings: Option<HttpConnectorSettings>,
     sleep_impl: Option<SharedAsyncSleep>,
     client_builder: Option<hyper_0_14::client::Builder>,
+    meter_provider: Option<SharedMeterProvider>,
 }
 
 impl HyperConnectorBuilder {
@@ -213,6 +284,14 @@ impl HyperConnectorBuilder {
             .map(|c| (c.connect_timeout(), c.read_timeout()))
             .unwrap_or((None, None));
 
+        let connections_opened = self.meter_provider.map(|provider| {
+            provider.meter(METER_SCOPE).create_counter(
+                InstrumentDescriptor::new(metric_names::HTTP_CONNECTIONS_OPENED)
+                    .with_description("The number of new connections opened by the HTTP client"),
+            )
+        });
+        let tcp_connector =
+            connection_metrics::CountConnections::new(tcp_connector, connections_opened);
         let connector = match connect_timeout {
             Some(duration) => timeout_middleware::ConnectTimeout::new(
                 tcp_connector,
@@ -242,7 +321,14 @@ impl HyperConnectorBuilder {
     /// Create a [`HyperConnector`] with the default rustls HTTPS implementation.
     #[cfg(feature = "tls-rustls")]
     pub fn build_https(self) -> HyperConnector {
-        self.build(default_connector::https())
+        let connect_timeout = self
+            .connector_settings
+            .as_ref()
+            .and_then(|settings| settings.connect_timeout());
+        self.build(default_connector::https(default_connector::http_connector(
+            hyper_0_14::client::connect::dns::GaiResolver::new(),
+            connect_timeout,
+        )))
     }
 
     /// Set the async sleep implementation used for timeouts
@@ -263,6 +349,24 @@ impl HyperConnectorBuilder {
         self
     }
 
+    /// Set the meter provider used to record connection metrics
+    ///
+    /// When set, the number of new connections opened is recorded to the
+    /// [`HTTP_CONNECTIONS_OPENED`](metric_names::HTTP_CONNECTIONS_OPENED) counter. Comparing
+    /// this with the number of request attempts shows how often pooled connections are reused.
+    pub fn meter_provider(mut self, meter_provider: impl ProvideMeter + 'static) -> Self {
+        self.meter_provider = Some(meter_provider.into_shared());
+        self
+    }
+
+    /// Set the meter provider used to record connection metrics
+    ///
+    /// See [`HyperConnectorBuilder::meter_provider`] for more information.
+    pub fn set_meter_provider(&mut self, meter_provider: Option<SharedMeterProvider>) -> &mut Self {
+        self.meter_provider = meter_provider;
+        self
+    }
+
     /// Configure the HTTP settings for the `HyperAdapter`
     pub fn connector_settings(mut self, connector_settings: HttpConnectorSettings) -> Self {
         self.connector_settings = Some(connector_settings);
@@ -303,7 +407,10 @@ impl HyperConnectorBuilder {
 /// This adapter also enables TCP `CONNECT` and HTTP `READ` timeouts via [`HyperConnector::builder`].
 struct Adapter<C> {
     client: timeout_middleware::HttpReadTimeout<
-        hyper_0_14::Client<timeout_middleware::ConnectTimeout<C>, SdkBody>,
+        hyper_0_14::Client<
+            timeout_middleware::ConnectTimeout<connection_metrics::CountConnections<C>>,
+            SdkBody,
+        >,
     >,
 }
 
@@ -390,7 +497,10 @@ fn downcast_error(err: BoxError) -> ConnectorError {
 
 /// Convert a [`hyper_0_14::Error`] into a [`ConnectorError`]
 fn to_connector_error(err: hyper_0_14::Error) -> ConnectorError {
-    if err.is_timeout() || find_source::<timeout_middleware::HttpTimeoutError>(&err).is_some() {
+    if err.is_timeout()
+        || find_source::<timeout_middleware::HttpTimeoutError>(&err).is_some()
+        || is_connect_timeout(&err)
+    {
         ConnectorError::timeout(err.into())
     } else if err.is_user() {
         ConnectorError::user(err.into())
@@ -407,6 +517,14 @@ fn to_connector_error(err: hyper_0_14::Error) -> ConnectorError {
     }
 }
 
+/// Returns true if the TCP connector gave up on connecting because its own connect timeout elapsed
+fn is_connect_timeout(err: &hyper_0_14::Error) -> bool {
+    err.is_connect()
+        && find_source::<std::io::Error>(err)
+            .map(|io_err| io_err.kind() == std::io::ErrorKind::TimedOut)
+            .unwrap_or_default()
+}
+
 fn find_source<'a, E: Error + 'static>(err: &'a (dyn Error + 'static)) -> Option<&'a E> {
     let mut next = Some(err);
     while let Some(err) = next {
@@ -450,7 +568,7 @@ impl<F> fmt::Debug for HyperClient<F> {
 
 impl<C, F> HttpClient for HyperClient<F>
 where
-    F: Fn() -> C + Send + Sync,
+    F: Fn(&HttpConnectorSettings) -> C + Send + Sync,
     C: Clone + Send + Sync + 'static,
     C: Service<Uri>,
     C::Response: Connection + AsyncRead + AsyncWrite + Send + Unpin + 'static,
@@ -472,8 +590,9 @@ where
                     .hyper_builder(self.client_builder.clone())
                     .connector_settings(settings.clone());
                 builder.set_sleep_impl(components.sleep_impl());
+                builder.set_meter_provider(components.meter_provider());
 
-                let tcp_connector = (self.tcp_connector_fn)();
+                let tcp_connector = (self.tcp_connector_fn)(settings);
                 let connector = SharedHttpConnector::new(builder.build(tcp_connector));
                 cache.insert(key.clone(), connector);
             }
@@ -486,11 +605,32 @@ where
 
 /// Builder for a hyper-backed [`HttpClient`] implementation.
 ///
-/// This builder can be used to customize the underlying TCP connector used, as well as
-/// hyper client configuration.
+/// This builder can be used to customize the underlying TCP connector and DNS resolver used,
+/// as well as hyper client configuration.
+///
+/// # Examples
+///
+/// Construct a client that resolves host names with a custom [`ResolveDns`] implementation,
+/// and caches the resolved addresses:
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::dns::CachingDnsResolver;
+/// use aws_smithy_runtime::client::http::hyper_014::HyperClientBuilder;
+///
+/// let http_client = HyperClientBuilder::new()
+///     .dns_resolver(CachingDnsResolver::builder(MySplitHorizonResolver::new()).build())
+///     .build_https();
+///
+/// // This client can then be given to a generated service Config
+/// let config = my_service_client::Config::builder()
+///     .http_client(http_client)
+///     .build();
+/// let client = my_service_client::Client::from_conf(config);
+/// ```
 #[derive(Clone, Default, Debug)]
 pub struct HyperClientBuilder {
     client_builder: Option<hyper_0_14::client::Builder>,
+    dns_resolver: Option<SharedDnsResolver>,
 }
 
 impl HyperClientBuilder {
@@ -518,10 +658,45 @@ impl HyperClientBuilder {
         self
     }
 
+    /// Override the DNS resolver used by [`build_https`](HyperClientBuilder::build_https).
+    ///
+    /// By default, host names are resolved with the standard library's resolver on Tokio's blocking thread pool.
+    /// The resolver isn't used by clients built with a custom TCP connector.
+    pub fn dns_resolver(mut self, dns_resolver: impl ResolveDns + 'static) -> Self {
+        self.dns_resolver = Some(dns_resolver.into_shared());
+        self
+    }
+
+    /// Override the DNS resolver used by [`build_https`](HyperClientBuilder::build_https).
+    ///
+    /// By default, host names are resolved with the standard library's resolver on Tokio's blocking thread pool.
+    /// The resolver isn't used by clients built with a custom TCP connector.
+    pub fn set_dns_resolver(&mut self, dns_resolver: Option<SharedDnsResolver>) -> &mut Self {
+        self.dns_resolver = dns_resolver;
+        self
+    }
+
     /// Create a [`HyperConnector`] with the default rustls HTTPS implementation.
+    ///
+    /// Connections are attempted against every address that a host name resolves to before
+    /// failing with a connect error.
     #[cfg(feature = "tls-rustls")]
-    pub fn build_https(self) -> SharedHttpClient {
-        self.build(default_connector::https())
+    pub fn build_https(mut self) -> SharedHttpClient {
+        use default_connector::{http_connector, https, DnsResolverAdapter};
+        match self.dns_resolver.take() {
+            Some(resolver) => self.build_with_fn(move |settings| {
+                https(http_connector(
+                    DnsResolverAdapter::new(resolver.clone()),
+                    settings.connect_timeout(),
+                ))
+            }),
+            None => self.build_with_fn(|settings| {
+                https(http_connector(
+                    hyper_0_14::client::connect::dns::GaiResolver::new(),
+                    settings.connect_timeout(),
+                ))
+            }),
+        }
     }
 
     /// Create a [`SharedHttpClient`] from this builder and a given connector.
@@ -538,17 +713,12 @@ impl HyperClientBuilder {
         C::Future: Unpin + Send + 'static,
         C::Error: Into<BoxError>,
     {
-        SharedHttpClient::new(HyperClient {
-            connector_cache: RwLock::new(HashMap::new()),
-            client_builder: self.client_builder.unwrap_or_default(),
-            tcp_connector_fn: move || tcp_connector.clone(),
-        })
+        self.build_with_fn(move |_: &HttpConnectorSettings| tcp_connector.clone())
     }
 
-    #[cfg(all(test, feature = "test-util"))]
     fn build_with_fn<C, F>(self, tcp_connector_fn: F) -> SharedHttpClient
     where
-        F: Fn() -> C + Send + Sync + 'static,
+        F: Fn(&HttpConnectorSettings) -> C + Send + Sync + 'static,
         C: Clone + Send + Sync + 'static,
         C: Service<Uri>,
         C::Response: Connection + AsyncRead + AsyncWrite + Send + Unpin + 'static,
@@ -563,6 +733,68 @@ impl HyperClientBuilder {
     }
 }
 
+mod connection_metrics {
+    use aws_smithy_runtime_api::client::telemetry::{Attributes, Counter, SharedCounter};
+    use http::Uri;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// TCP connector wrapper that counts the connections that were successfully opened
+    #[derive(Clone, Debug)]
+    pub(super) struct CountConnections<I> {
+        inner: I,
+        counter: Option<SharedCounter>,
+    }
+
+    impl<I> CountConnections<I> {
+        pub(super) fn new(inner: I, counter: Option<SharedCounter>) -> Self {
+            Self { inner, counter }
+        }
+    }
+
+    impl<I> hyper_0_14::service::Service<Uri> for CountConnections<I>
+    where
+        I: hyper_0_14::service::Service<Uri>,
+        I::Future: Unpin,
+    {
+        type Response = I::Response;
+        type Error = I::Error;
+        type Future = CountConnectionFuture<I::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, req: Uri) -> Self::Future {
+            CountConnectionFuture {
+                inner: self.inner.call(req),
+                counter: self.counter.clone(),
+            }
+        }
+    }
+
+    pub(super) struct CountConnectionFuture<F> {
+        inner: F,
+        counter: Option<SharedCounter>,
+    }
+
+    impl<F, T, E> Future for CountConnectionFuture<F>
+    where
+        F: Future<Output = Result<T, E>> + Unpin,
+    {
+        type Output = Result<T, E>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let result = Pin::new(&mut self.inner).poll(cx);
+            if let (Poll::Ready(Ok(_)), Some(counter)) = (&result, &self.counter) {
+                counter.add(1, &Attributes::new());
+            }
+            result
+        }
+    }
+}
+
 mod timeout_middleware {
     use aws_smithy_async::future::timeout::{TimedOutError, Timeout};
     use aws_smithy_async::rt::sleep::Sleep;
@@ -951,7 +1183,7 @@ mod test {
         let creation_count = Arc::new(AtomicU32::new(0));
         let http_client = HyperClientBuilder::new().build_with_fn({
             let count = creation_count.clone();
-            move || {
+            move |_: &HttpConnectorSettings| {
                 count.fetch_add(1, Ordering::Relaxed);
                 NeverTcpConnector::new()
             }
@@ -998,6 +1230,27 @@ mod test {
         assert_eq!(4, creation_count.load(Ordering::Relaxed));
     }
 
+    #[tokio::test]
+    async fn opened_connections_are_counted() {
+        use crate::client::telemetry::test_util::InMemoryMeterProvider;
+
+        let meter_provider = InMemoryMeterProvider::new();
+        let connector = TestConnection {
+            inner: HangupStream,
+        };
+        let adapter = HyperConnector::builder()
+            .meter_provider(meter_provider.clone())
+            .build(connector)
+            .adapter;
+        let _ = adapter
+            .call(HttpRequest::get("https://socket-hangup.com").unwrap())
+            .await;
+        assert_eq!(
+            1,
+            meter_provider.counter_total(metric_names::HTTP_CONNECTIONS_OPENED)
+        );
+    }
+
     #[tokio::test]
     async fn hyper_io_error() {
         let connector = TestConnection {
@@ -1011,6 +1264,60 @@ mod test {
         assert!(err.is_io(), "{:?}", err);
     }
 
+    #[cfg(feature = "tls-rustls")]
+    #[tokio::test]
+    async fn connections_fail_over_to_the_next_resolved_address() {
+        use aws_smithy_async::rt::sleep::TokioSleep;
+        use aws_smithy_runtime_api::client::dns::{DnsFuture, ResolveDns};
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::{IpAddr, Ipv4Addr, TcpListener};
+
+        /// Resolves every name to an address with nothing listening on it, followed by the loopback address
+        #[derive(Debug)]
+        struct FailoverResolver;
+
+        impl ResolveDns for FailoverResolver {
+            fn resolve_dns<'a>(&'a self, _name: &'a str) -> DnsFuture<'a> {
+                DnsFuture::ready(Ok(vec![
+                    IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)),
+                    IpAddr::V4(Ipv4Addr::LOCALHOST),
+                ]))
+            }
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            reader
+                .into_inner()
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+        });
+
+        let http_client = HyperClientBuilder::new()
+            .dns_resolver(FailoverResolver)
+            .build_https();
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap();
+        let settings = HttpConnectorSettings::builder()
+            .connect_timeout(Duration::from_secs(5))
+            .build();
+        let connector = http_client.http_connector(&settings, &components);
+        let response = connector
+            .call(HttpRequest::get(format!("http://split-horizon.example:{port}/")).unwrap())
+            .await
+            .expect("the second address accepts connections");
+        assert_eq!(200, response.status().as_u16());
+    }
+
     // ---- machinery to make a Hyper connector that responds with an IO Error
     #[derive(Clone)]
     struct HangupStream;
@@ -0,0 +1,1087 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::http::connection_poisoning::CaptureSmithyConnection;
+use aws_smithy_async::future::timeout::TimedOutError;
+use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep, SharedAsyncSleep};
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::connection::ConnectionMetadata;
+use aws_smithy_runtime_api::client::dns::{ResolveDns, ResolveDnsError, SharedDnsResolver};
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpClient,
+    SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::retry::ErrorKind;
+use http_1x::Uri;
+use hyper_util::client::legacy::connect::dns::Name;
+use hyper_util::client::legacy::connect::{
+    capture_connection, CaptureConnection, Connection, HttpConnector as HyperHttpConnector,
+    HttpInfo,
+};
+use hyper_util::rt::{TokioExecutor, TokioTimer};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::RwLock;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower_service::Service;
+
+#[cfg(feature = "tls-rustls-hyper-1-x")]
+mod default_connector {
+    use hyper_rustls_0_27::ConfigBuilderExt;
+    use rustls_0_23::crypto::ring;
+    use std::sync::Arc;
+
+    // Loading the native root certificates takes 300ms on OS X. Cache this so that we
+    // don't need to repeatedly incur that cost.
+    static TLS_CONFIG: once_cell::sync::Lazy<rustls_0_23::ClientConfig> =
+        once_cell::sync::Lazy::new(|| {
+            let provider = rustls_0_23::crypto::CryptoProvider {
+                cipher_suites: vec![
+                    // TLS1.3 suites
+                    ring::cipher_suite::TLS13_AES_256_GCM_SHA384,
+                    ring::cipher_suite::TLS13_AES_128_GCM_SHA256,
+                    // TLS1.2 suites
+                    ring::cipher_suite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+                    ring::cipher_suite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+                    ring::cipher_suite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+                    ring::cipher_suite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+                    ring::cipher_suite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+                ],
+                ..ring::default_provider()
+            };
+            rustls_0_23::ClientConfig::builder_with_provider(Arc::new(provider))
+                .with_safe_default_protocol_versions()
+                .expect("Error with the TLS configuration. Please file a bug report under https://github.com/awslabs/smithy-rs/issues.")
+                .with_native_roots()
+                .expect("Failed to load the native root certificates. Please file a bug report under https://github.com/awslabs/smithy-rs/issues.")
+                .with_no_client_auth()
+        });
+
+    /// Return an HTTPS connector backed by the `rustls` crate that wraps the given HTTP connector.
+    ///
+    /// It requires a minimum TLS version of 1.2.
+    /// It allows you to connect to both `http` and `https` URLs.
+    pub(super) fn https<R>(
+        http_connector: super::HyperHttpConnector<R>,
+    ) -> hyper_rustls_0_27::HttpsConnector<super::HyperHttpConnector<R>> {
+        hyper_rustls_0_27::HttpsConnectorBuilder::new()
+            .with_tls_config(TLS_CONFIG.clone())
+            .https_or_http()
+            .enable_http1()
+            .enable_http2()
+            .wrap_connector(http_connector)
+    }
+}
+
+/// Creates a hyper 1.x backed HTTPS client from defaults depending on what cargo features are activated.
+pub fn default_client() -> Option<SharedHttpClient> {
+    #[cfg(feature = "tls-rustls-hyper-1-x")]
+    {
+        tracing::trace!("creating a new default hyper 1.x client");
+        Some(HyperClientBuilder::new().build_https())
+    }
+    #[cfg(not(feature = "tls-rustls-hyper-1-x"))]
+    {
+        tracing::trace!("no default hyper 1.x client available");
+        None
+    }
+}
+
+/// Returns a hyper-util HTTP connector that resolves host names with `resolver`.
+///
+/// When a host name resolves to several addresses, the connector tries each of them in turn,
+/// racing IPv6 and IPv4 addresses happy-eyeballs style, and only fails once every address
+/// has failed. The connect timeout is split evenly across the addresses so that one
+/// unreachable address doesn't use up the entire timeout.
+///
+/// The connector allows `https` URLs so that it can be wrapped by a TLS connector.
+fn http_connector(
+    resolver: SharedDnsResolver,
+    connect_timeout: Option<Duration>,
+) -> HyperHttpConnector<DnsResolverAdapter> {
+    let mut connector = HyperHttpConnector::new_with_resolver(DnsResolverAdapter { resolver });
+    connector.enforce_http(false);
+    connector.set_nodelay(true);
+    connector.set_connect_timeout(connect_timeout);
+    connector
+}
+
+/// Returns the resolver used when no DNS resolver was configured.
+fn default_dns_resolver() -> SharedDnsResolver {
+    crate::client::dns::TokioDnsResolver::new().into_shared()
+}
+
+/// Adapts a [`ResolveDns`] implementation to the resolver interface of hyper-util's HTTP connector.
+#[derive(Clone, Debug)]
+struct DnsResolverAdapter {
+    resolver: SharedDnsResolver,
+}
+
+impl Service<Name> for DnsResolverAdapter {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = ResolveDnsError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let resolver = self.resolver.clone();
+        Box::pin(async move {
+            let addresses = resolver.resolve_dns(name.as_str()).await?;
+            // The HTTP connector replaces the port with the one from the request URI
+            Ok(addresses
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, 0))
+                .collect::<Vec<_>>()
+                .into_iter())
+        })
+    }
+}
+
+/// [`HttpConnector`] that uses hyper 1.x and hyper-util to make HTTP requests.
+///
+/// This connector also implements socket connect and read timeouts.
+///
+/// # Examples
+///
+/// Construct a `HyperConnector` with the default TLS implementation (rustls).
+/// This can be useful when you want to share a Hyper connector between multiple
+/// generated Smithy clients.
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::http::hyper_1::HyperConnector;
+///
+/// let hyper_connector = HyperConnector::builder().build_https();
+///
+/// // This connector can then be given to a generated service Config
+/// let config = my_service_client::Config::builder()
+///     .endpoint_url("http://localhost:1234")
+///     .http_connector(hyper_connector)
+///     .build();
+/// let client = my_service_client::Client::from_conf(config);
+/// ```
+#[derive(Debug)]
+pub struct HyperConnector {
+    adapter: Box<dyn HttpConnector>,
+}
+
+impl HyperConnector {
+    /// Builder for a Hyper connector.
+    pub fn builder() -> HyperConnectorBuilder {
+        Default::default()
+    }
+}
+
+impl HttpConnector for HyperConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        self.adapter.call(request)
+    }
+}
+
+/// Builder for [`HyperConnector`].
+#[derive(Default, Debug)]
+pub struct HyperConnectorBuilder {
+    connector_settings: Option<HttpConnectorSettings>,
+    sleep_impl: Option<SharedAsyncSleep>,
+    client_builder: Option<hyper_util::client::legacy::Builder>,
+}
+
+impl HyperConnectorBuilder {
+    /// Create a [`HyperConnector`] from this builder and a given connector.
+    pub fn build<C>(self, tcp_connector: C) -> HyperConnector
+    where
+        C: Clone + Send + Sync + 'static,
+        C: Service<Uri>,
+        C::Response: Connection + hyper_1::rt::Read + hyper_1::rt::Write + Send + Unpin + 'static,
+        C::Future: Unpin + Send + 'static,
+        C::Error: Into<BoxError>,
+    {
+        let client_builder = self.client_builder.unwrap_or_else(default_hyper_builder);
+        let sleep_impl = self.sleep_impl.or_else(default_async_sleep);
+        let (connect_timeout, read_timeout) = self
+            .connector_settings
+            .map(|c| (c.connect_timeout(), c.read_timeout()))
+            .unwrap_or((None, None));
+
+        let connector = match connect_timeout {
+            Some(duration) => timeout_middleware::ConnectTimeout::new(
+                tcp_connector,
+                sleep_impl
+                    .clone()
+                    .expect("a sleep impl must be provided in order to have a connect timeout"),
+                duration,
+            ),
+            None => timeout_middleware::ConnectTimeout::no_timeout(tcp_connector),
+        };
+        let base = client_builder.build(connector);
+        let read_timeout = match read_timeout {
+            Some(duration) => timeout_middleware::HttpReadTimeout::new(
+                base,
+                sleep_impl.expect("a sleep impl must be provided in order to have a read timeout"),
+                duration,
+            ),
+            None => timeout_middleware::HttpReadTimeout::no_timeout(base),
+        };
+        HyperConnector {
+            adapter: Box::new(Adapter {
+                client: read_timeout,
+            }),
+        }
+    }
+
+    /// Create a [`HyperConnector`] with the default rustls HTTPS implementation.
+    #[cfg(feature = "tls-rustls-hyper-1-x")]
+    pub fn build_https(self) -> HyperConnector {
+        let connect_timeout = self
+            .connector_settings
+            .as_ref()
+            .and_then(|settings| settings.connect_timeout());
+        self.build(default_connector::https(http_connector(
+            default_dns_resolver(),
+            connect_timeout,
+        )))
+    }
+
+    /// Set the async sleep implementation used for timeouts
+    ///
+    /// Calling this is only necessary for testing or to use something other than
+    /// [`default_async_sleep`].
+    pub fn sleep_impl(mut self, sleep_impl: impl AsyncSleep + 'static) -> Self {
+        self.sleep_impl = Some(sleep_impl.into_shared());
+        self
+    }
+
+    /// Set the async sleep implementation used for timeouts
+    ///
+    /// Calling this is only necessary for testing or to use something other than
+    /// [`default_async_sleep`].
+    pub fn set_sleep_impl(&mut self, sleep_impl: Option<SharedAsyncSleep>) -> &mut Self {
+        self.sleep_impl = sleep_impl;
+        self
+    }
+
+    /// Configure the HTTP settings for the `HyperAdapter`
+    pub fn connector_settings(mut self, connector_settings: HttpConnectorSettings) -> Self {
+        self.connector_settings = Some(connector_settings);
+        self
+    }
+
+    /// Configure the HTTP settings for the `HyperAdapter`
+    pub fn set_connector_settings(
+        &mut self,
+        connector_settings: Option<HttpConnectorSettings>,
+    ) -> &mut Self {
+        self.connector_settings = connector_settings;
+        self
+    }
+
+    /// Override the hyper-util client [`Builder`](hyper_util::client::legacy::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn hyper_builder(mut self, hyper_builder: hyper_util::client::legacy::Builder) -> Self {
+        self.client_builder = Some(hyper_builder);
+        self
+    }
+
+    /// Override the hyper-util client [`Builder`](hyper_util::client::legacy::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn set_hyper_builder(
+        &mut self,
+        hyper_builder: Option<hyper_util::client::legacy::Builder>,
+    ) -> &mut Self {
+        self.client_builder = hyper_builder;
+        self
+    }
+}
+
+/// Returns a hyper-util client builder that runs its background tasks and timers on Tokio.
+fn default_hyper_builder() -> hyper_util::client::legacy::Builder {
+    let mut builder = hyper_util::client::legacy::Client::builder(TokioExecutor::new());
+    builder.pool_timer(TokioTimer::new());
+    builder
+}
+
+/// Adapter from a hyper-util [`Client`](hyper_util::client::legacy::Client) to [`HttpConnector`].
+///
+/// This adapter also enables TCP `CONNECT` and HTTP `READ` timeouts via [`HyperConnector::builder`].
+struct Adapter<C> {
+    client: timeout_middleware::HttpReadTimeout<timeout_middleware::ConnectTimeout<C>>,
+}
+
+impl<C> fmt::Debug for Adapter<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Adapter")
+            .field("client", &"** hyper client **")
+            .finish()
+    }
+}
+
+/// Extract a smithy connection from a hyper-util CaptureConnection
+fn extract_smithy_connection(capture_conn: &CaptureConnection) -> Option<ConnectionMetadata> {
+    let capture_conn = capture_conn.clone();
+    if let Some(conn) = capture_conn.clone().connection_metadata().as_ref() {
+        let mut extensions = http_1x::Extensions::new();
+        conn.get_extras(&mut extensions);
+        let http_info = extensions.get::<HttpInfo>();
+        let smithy_connection = ConnectionMetadata::new(
+            conn.is_proxied(),
+            http_info.map(|info| info.remote_addr()),
+            move || match capture_conn.connection_metadata().as_ref() {
+                Some(conn) => conn.poison(),
+                None => tracing::trace!("no connection existed to poison"),
+            },
+        );
+        Some(smithy_connection)
+    } else {
+        None
+    }
+}
+
+impl<C> HttpConnector for Adapter<C>
+where
+    C: Clone + Send + Sync + 'static,
+    C: Service<Uri>,
+    C::Response: Connection + hyper_1::rt::Read + hyper_1::rt::Write + Send + Unpin + 'static,
+    C::Future: Unpin + Send + 'static,
+    C::Error: Into<BoxError>,
+{
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        let request = match request.into_http02x() {
+            Ok(request) => request,
+            Err(err) => return HttpConnectorFuture::ready(Err(ConnectorError::user(err.into()))),
+        };
+        let capture_smithy_connection = request
+            .extensions()
+            .get::<CaptureSmithyConnection>()
+            .cloned();
+        let mut request = match convert::request_to_http_1x(request) {
+            Ok(request) => request,
+            Err(err) => return HttpConnectorFuture::ready(Err(ConnectorError::user(err))),
+        };
+        let capture_connection = capture_connection(&mut request);
+        if let Some(capture_smithy_connection) = capture_smithy_connection {
+            capture_smithy_connection
+                .set_connection_retriever(move || extract_smithy_connection(&capture_connection));
+        }
+        let fut = self.client.call(request);
+        HttpConnectorFuture::new(async move {
+            let response = fut.await.map_err(downcast_error)?;
+            Ok(convert::response_to_http_0x(
+                response.map(SdkBody::from_body_1_x),
+            ))
+        })
+    }
+}
+
+/// Downcast errors coming out of hyper into an appropriate `ConnectorError`
+fn downcast_error(err: BoxError) -> ConnectorError {
+    // is a `TimedOutError` (from aws_smithy_async::timeout) in the chain? if it is, this is a timeout
+    if find_source::<TimedOutError>(err.as_ref()).is_some() {
+        return ConnectorError::timeout(err);
+    }
+    // is the top of chain error actually already a `ConnectorError`? return that directly
+    let err = match err.downcast::<ConnectorError>() {
+        Ok(connector_error) => return *connector_error,
+        Err(box_error) => box_error,
+    };
+    // generally, the top of chain will probably be a hyper-util error. Go through a set of hyper specific
+    // error classifications
+    let err = match err.downcast::<hyper_util::client::legacy::Error>() {
+        Ok(client_error) => return to_connector_error(*client_error),
+        Err(box_error) => box_error,
+    };
+
+    // otherwise, we have no idea!
+    ConnectorError::other(err, None)
+}
+
+/// Convert a [`hyper_util::client::legacy::Error`] into a [`ConnectorError`]
+fn to_connector_error(err: hyper_util::client::legacy::Error) -> ConnectorError {
+    if find_source::<timeout_middleware::HttpTimeoutError>(&err).is_some() {
+        return ConnectorError::timeout(err.into());
+    }
+    if err.is_connect() {
+        // The TCP connector gives up on connecting once its own connect timeout elapses
+        let timed_out = find_source::<std::io::Error>(&err)
+            .map(|io_err| io_err.kind() == std::io::ErrorKind::TimedOut)
+            .unwrap_or_default();
+        if timed_out {
+            return ConnectorError::timeout(err.into());
+        }
+        return ConnectorError::io(err.into());
+    }
+    if let Some(hyper_error) = find_source::<hyper_1::Error>(&err) {
+        if hyper_error.is_timeout() {
+            return ConnectorError::timeout(err.into());
+        } else if hyper_error.is_user() {
+            return ConnectorError::user(err.into());
+        } else if hyper_error.is_closed() || hyper_error.is_canceled() {
+            return ConnectorError::io(err.into());
+        }
+        // We sometimes receive this from S3: hyper::Error(IncompleteMessage)
+        else if hyper_error.is_incomplete_message() {
+            return ConnectorError::other(err.into(), Some(ErrorKind::TransientError));
+        }
+    }
+    if find_source::<std::io::Error>(&err).is_some() {
+        ConnectorError::io(err.into())
+    } else {
+        tracing::warn!(err = %DisplayErrorContext(&err), "unrecognized error from Hyper. If this error should be retried, please file an issue.");
+        ConnectorError::other(err.into(), None)
+    }
+}
+
+fn find_source<'a, E: Error + 'static>(err: &'a (dyn Error + 'static)) -> Option<&'a E> {
+    let mut next = Some(err);
+    while let Some(err) = next {
+        if let Some(matching_err) = err.downcast_ref::<E>() {
+            return Some(matching_err);
+        }
+        next = err.source();
+    }
+    None
+}
+
+/// Conversions between the http 0.2 types used by the orchestrator and the http 1.x types used by hyper 1.x.
+mod convert {
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use aws_smithy_types::body::SdkBody;
+
+    pub(super) fn request_to_http_1x(
+        request: http::Request<SdkBody>,
+    ) -> Result<http_1x::Request<SdkBody>, BoxError> {
+        let (parts, body) = request.into_parts();
+        let mut builder = http_1x::Request::builder()
+            .method(parts.method.as_str())
+            .uri(parts.uri.to_string())
+            .version(version_to_http_1x(parts.version));
+        for (name, value) in parts.headers.iter() {
+            builder = builder.header(name.as_str(), value.as_bytes());
+        }
+        Ok(builder.body(body)?)
+    }
+
+    pub(super) fn response_to_http_0x(
+        response: http_1x::Response<SdkBody>,
+    ) -> http::Response<SdkBody> {
+        let (parts, body) = response.into_parts();
+        let mut builder = http::Response::builder()
+            .status(parts.status.as_u16())
+            .version(version_to_http_0x(parts.version));
+        for (name, value) in parts.headers.iter() {
+            builder = builder.header(name.as_str(), value.as_bytes());
+        }
+        builder
+            .body(body)
+            .expect("status and headers were valid in the http 1.x response")
+    }
+
+    fn version_to_http_1x(version: http::Version) -> http_1x::Version {
+        match version {
+            http::Version::HTTP_09 => http_1x::Version::HTTP_09,
+            http::Version::HTTP_10 => http_1x::Version::HTTP_10,
+            http::Version::HTTP_2 => http_1x::Version::HTTP_2,
+            http::Version::HTTP_3 => http_1x::Version::HTTP_3,
+            _ => http_1x::Version::HTTP_11,
+        }
+    }
+
+    fn version_to_http_0x(version: http_1x::Version) -> http::Version {
+        match version {
+            http_1x::Version::HTTP_09 => http::Version::HTTP_09,
+            http_1x::Version::HTTP_10 => http::Version::HTTP_10,
+            http_1x::Version::HTTP_2 => http::Version::HTTP_2,
+            http_1x::Version::HTTP_3 => http::Version::HTTP_3,
+            _ => http::Version::HTTP_11,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct CacheKey {
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+}
+
+impl From<&HttpConnectorSettings> for CacheKey {
+    fn from(value: &HttpConnectorSettings) -> Self {
+        Self {
+            connect_timeout: value.connect_timeout(),
+            read_timeout: value.read_timeout(),
+        }
+    }
+}
+
+struct HyperClient<F> {
+    connector_cache: RwLock<HashMap<CacheKey, SharedHttpConnector>>,
+    client_builder: Option<hyper_util::client::legacy::Builder>,
+    tcp_connector_fn: F,
+}
+
+impl<F> fmt::Debug for HyperClient<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HyperClient")
+            .field("connector_cache", &self.connector_cache)
+            .field("client_builder", &self.client_builder)
+            .finish()
+    }
+}
+
+impl<C, F> HttpClient for HyperClient<F>
+where
+    F: Fn(&HttpConnectorSettings) -> C + Send + Sync,
+    C: Clone + Send + Sync + 'static,
+    C: Service<Uri>,
+    C::Response: Connection + hyper_1::rt::Read + hyper_1::rt::Write + Send + Unpin + 'static,
+    C::Future: Unpin + Send + 'static,
+    C::Error: Into<BoxError>,
+{
+    fn http_connector(
+        &self,
+        settings: &HttpConnectorSettings,
+        components: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        let key = CacheKey::from(settings);
+        let mut connector = self.connector_cache.read().unwrap().get(&key).cloned();
+        if connector.is_none() {
+            let mut cache = self.connector_cache.write().unwrap();
+            // Short-circuit if another thread already wrote a connector to the cache for this key
+            if !cache.contains_key(&key) {
+                let mut builder = HyperConnector::builder().connector_settings(settings.clone());
+                builder.set_hyper_builder(self.client_builder.clone());
+                builder.set_sleep_impl(components.sleep_impl());
+
+                let tcp_connector = (self.tcp_connector_fn)(settings);
+                let connector = SharedHttpConnector::new(builder.build(tcp_connector));
+                cache.insert(key.clone(), connector);
+            }
+            connector = cache.get(&key).cloned();
+        }
+
+        connector.expect("cache populated above")
+    }
+}
+
+/// Builder for a hyper 1.x backed [`HttpClient`] implementation.
+///
+/// This builder can be used to customize the underlying TCP connector and DNS resolver used,
+/// as well as hyper client configuration.
+///
+/// # Examples
+///
+/// Construct a client that resolves host names with a custom [`ResolveDns`] implementation,
+/// and caches the resolved addresses:
+///
+/// ```no_run,ignore
+/// use aws_smithy_runtime::client::dns::CachingDnsResolver;
+/// use aws_smithy_runtime::client::http::hyper_1::HyperClientBuilder;
+///
+/// let http_client = HyperClientBuilder::new()
+///     .dns_resolver(CachingDnsResolver::builder(MySplitHorizonResolver::new()).build())
+///     .build_https();
+///
+/// // This client can then be given to a generated service Config
+/// let config = my_service_client::Config::builder()
+///     .http_client(http_client)
+///     .build();
+/// let client = my_service_client::Client::from_conf(config);
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct HyperClientBuilder {
+    client_builder: Option<hyper_util::client::legacy::Builder>,
+    dns_resolver: Option<SharedDnsResolver>,
+}
+
+impl HyperClientBuilder {
+    /// Creates a new builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the hyper-util client [`Builder`](hyper_util::client::legacy::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn hyper_builder(mut self, hyper_builder: hyper_util::client::legacy::Builder) -> Self {
+        self.client_builder = Some(hyper_builder);
+        self
+    }
+
+    /// Override the hyper-util client [`Builder`](hyper_util::client::legacy::Builder) used to construct this client.
+    ///
+    /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
+    pub fn set_hyper_builder(
+        &mut self,
+        hyper_builder: Option<hyper_util::client::legacy::Builder>,
+    ) -> &mut Self {
+        self.client_builder = hyper_builder;
+        self
+    }
+
+    /// Override the DNS resolver used by [`build_http`](HyperClientBuilder::build_http) and `build_https`.
+    ///
+    /// By default, host names are resolved with [`TokioDnsResolver`](crate::client::dns::TokioDnsResolver).
+    /// The resolver isn't used by clients built with a custom TCP connector.
+    pub fn dns_resolver(mut self, dns_resolver: impl ResolveDns + 'static) -> Self {
+        self.dns_resolver = Some(dns_resolver.into_shared());
+        self
+    }
+
+    /// Override the DNS resolver used by [`build_http`](HyperClientBuilder::build_http) and `build_https`.
+    ///
+    /// By default, host names are resolved with [`TokioDnsResolver`](crate::client::dns::TokioDnsResolver).
+    /// The resolver isn't used by clients built with a custom TCP connector.
+    pub fn set_dns_resolver(&mut self, dns_resolver: Option<SharedDnsResolver>) -> &mut Self {
+        self.dns_resolver = dns_resolver;
+        self
+    }
+
+    /// Create a hyper client with the default rustls HTTPS implementation.
+    ///
+    /// The client can connect to both `http` and `https` URLs. Connections are attempted against
+    /// every address that a host name resolves to before failing with a connect error.
+    #[cfg(feature = "tls-rustls-hyper-1-x")]
+    pub fn build_https(mut self) -> SharedHttpClient {
+        let resolver = self
+            .dns_resolver
+            .take()
+            .unwrap_or_else(default_dns_resolver);
+        self.build_with_fn(move |settings| {
+            default_connector::https(http_connector(resolver.clone(), settings.connect_timeout()))
+        })
+    }
+
+    /// Create a hyper client that can only connect to `http` URLs.
+    ///
+    /// Connections are attempted against every address that a host name resolves to before
+    /// failing with a connect error.
+    pub fn build_http(mut self) -> SharedHttpClient {
+        let resolver = self
+            .dns_resolver
+            .take()
+            .unwrap_or_else(default_dns_resolver);
+        self.build_with_fn(move |settings| {
+            let mut connector = http_connector(resolver.clone(), settings.connect_timeout());
+            connector.enforce_http(true);
+            connector
+        })
+    }
+
+    /// Create a [`SharedHttpClient`] from this builder and a given connector.
+    ///
+    #[cfg_attr(
+        feature = "tls-rustls-hyper-1-x",
+        doc = "Use [`build_https`](HyperClientBuilder::build_https) if you don't want to provide a custom TCP connector."
+    )]
+    pub fn build<C>(self, tcp_connector: C) -> SharedHttpClient
+    where
+        C: Clone + Send + Sync + 'static,
+        C: Service<Uri>,
+        C::Response: Connection + hyper_1::rt::Read + hyper_1::rt::Write + Send + Unpin + 'static,
+        C::Future: Unpin + Send + 'static,
+        C::Error: Into<BoxError>,
+    {
+        self.build_with_fn(move |_: &HttpConnectorSettings| tcp_connector.clone())
+    }
+
+    fn build_with_fn<C, F>(self, tcp_connector_fn: F) -> SharedHttpClient
+    where
+        F: Fn(&HttpConnectorSettings) -> C + Send + Sync + 'static,
+        C: Clone + Send + Sync + 'static,
+        C: Service<Uri>,
+        C::Response: Connection + hyper_1::rt::Read + hyper_1::rt::Write + Send + Unpin + 'static,
+        C::Future: Unpin + Send + 'static,
+        C::Error: Into<BoxError>,
+    {
+        SharedHttpClient::new(HyperClient {
+            connector_cache: RwLock::new(HashMap::new()),
+            client_builder: self.client_builder,
+            tcp_connector_fn,
+        })
+    }
+}
+
+mod timeout_middleware {
+    use aws_smithy_async::future::timeout::{TimedOutError, Timeout};
+    use aws_smithy_async::rt::sleep::Sleep;
+    use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use aws_smithy_types::body::SdkBody;
+    use http_1x::Uri;
+    use hyper_util::client::legacy::connect::Connect;
+    use hyper_util::client::legacy::{Client, ResponseFuture};
+    use pin_project_lite::pin_project;
+    use std::error::Error;
+    use std::fmt::Formatter;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+    use tower_service::Service;
+
+    #[derive(Debug)]
+    pub(crate) struct HttpTimeoutError {
+        kind: &'static str,
+        duration: Duration,
+    }
+
+    impl std::fmt::Display for HttpTimeoutError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "{} timeout occurred after {:?}",
+                self.kind, self.duration
+            )
+        }
+    }
+
+    impl Error for HttpTimeoutError {
+        // We implement the `source` function as returning a `TimedOutError` because when `downcast_error`
+        // or `find_source` is called with an `HttpTimeoutError` (or another error wrapping an `HttpTimeoutError`)
+        // this method will be checked to determine if it's a timeout-related error.
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&TimedOutError)
+        }
+    }
+
+    /// Timeout wrapper that will timeout on the initial TCP connection
+    #[derive(Clone, Debug)]
+    pub(super) struct ConnectTimeout<I> {
+        inner: I,
+        timeout: Option<(SharedAsyncSleep, Duration)>,
+    }
+
+    impl<I> ConnectTimeout<I> {
+        /// Create a new `ConnectTimeout` around `inner`.
+        pub(super) fn new(inner: I, sleep: SharedAsyncSleep, timeout: Duration) -> Self {
+            Self {
+                inner,
+                timeout: Some((sleep, timeout)),
+            }
+        }
+
+        pub(super) fn no_timeout(inner: I) -> Self {
+            Self {
+                inner,
+                timeout: None,
+            }
+        }
+    }
+
+    /// Timeout wrapper that will timeout if the response headers aren't received in time
+    pub(super) struct HttpReadTimeout<C> {
+        client: Client<C, SdkBody>,
+        timeout: Option<(SharedAsyncSleep, Duration)>,
+    }
+
+    impl<C> HttpReadTimeout<C>
+    where
+        C: Connect + Clone + Send + Sync + 'static,
+    {
+        /// Create a new `HttpReadTimeout` around `client`.
+        pub(super) fn new(
+            client: Client<C, SdkBody>,
+            sleep: SharedAsyncSleep,
+            timeout: Duration,
+        ) -> Self {
+            Self {
+                client,
+                timeout: Some((sleep, timeout)),
+            }
+        }
+
+        pub(super) fn no_timeout(client: Client<C, SdkBody>) -> Self {
+            Self {
+                client,
+                timeout: None,
+            }
+        }
+
+        pub(super) fn call(
+            &self,
+            request: http_1x::Request<SdkBody>,
+        ) -> MaybeTimeoutFuture<ResponseFuture> {
+            match &self.timeout {
+                Some((sleep, duration)) => {
+                    let sleep = sleep.sleep(*duration);
+                    MaybeTimeoutFuture::Timeout {
+                        timeout: Timeout::new(self.client.request(request), sleep),
+                        error_type: "HTTP read",
+                        duration: *duration,
+                    }
+                }
+                None => MaybeTimeoutFuture::NoTimeout {
+                    future: self.client.request(request),
+                },
+            }
+        }
+    }
+
+    pin_project! {
+        /// Timeout future for Tower services
+        ///
+        /// Timeout future to handle timing out, mapping errors, and the possibility of not timing out
+        /// without incurring an additional allocation for each timeout layer.
+        #[project = MaybeTimeoutFutureProj]
+        pub(super) enum MaybeTimeoutFuture<F> {
+            Timeout {
+                #[pin]
+                timeout: Timeout<F, Sleep>,
+                error_type: &'static str,
+                duration: Duration,
+            },
+            NoTimeout {
+                #[pin]
+                future: F
+            }
+        }
+    }
+
+    impl<F, T, E> Future for MaybeTimeoutFuture<F>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Into<BoxError>,
+    {
+        type Output = Result<T, BoxError>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let (timeout_future, kind, &mut duration) = match self.project() {
+                MaybeTimeoutFutureProj::NoTimeout { future } => {
+                    return future.poll(cx).map_err(|err| err.into());
+                }
+                MaybeTimeoutFutureProj::Timeout {
+                    timeout,
+                    error_type,
+                    duration,
+                } => (timeout, error_type, duration),
+            };
+            match timeout_future.poll(cx) {
+                Poll::Ready(Ok(response)) => Poll::Ready(response.map_err(|err| err.into())),
+                Poll::Ready(Err(_timeout)) => {
+                    Poll::Ready(Err(HttpTimeoutError { kind, duration }.into()))
+                }
+                Poll::Pending => Poll::Pending,
+            }
+        }
+    }
+
+    impl<I> Service<Uri> for ConnectTimeout<I>
+    where
+        I: Service<Uri>,
+        I::Error: Into<BoxError>,
+    {
+        type Response = I::Response;
+        type Error = BoxError;
+        type Future = MaybeTimeoutFuture<I::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx).map_err(|err| err.into())
+        }
+
+        fn call(&mut self, req: Uri) -> Self::Future {
+            match &self.timeout {
+                Some((sleep, duration)) => {
+                    let sleep = sleep.sleep(*duration);
+                    MaybeTimeoutFuture::Timeout {
+                        timeout: Timeout::new(self.inner.call(req), sleep),
+                        error_type: "HTTP connect",
+                        duration: *duration,
+                    }
+                }
+                None => MaybeTimeoutFuture::NoTimeout {
+                    future: self.inner.call(req),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_async::assert_elapsed;
+    use aws_smithy_async::future::never::Never;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_runtime_api::client::dns::DnsFuture;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::byte_stream::ByteStream;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{IpAddr, Ipv4Addr, TcpListener};
+    use std::sync::{Arc, Mutex};
+
+    /// Resolves every host name to the loopback address, and records the names it was asked to resolve
+    #[derive(Clone, Debug, Default)]
+    struct LoopbackResolver {
+        names: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ResolveDns for LoopbackResolver {
+        fn resolve_dns<'a>(&'a self, name: &'a str) -> DnsFuture<'a> {
+            self.names.lock().unwrap().push(name.to_string());
+            DnsFuture::ready(Ok(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]))
+        }
+    }
+
+    /// A resolver that never resolves any host names
+    #[derive(Debug)]
+    struct NeverResolves;
+
+    impl ResolveDns for NeverResolves {
+        fn resolve_dns<'a>(&'a self, _name: &'a str) -> DnsFuture<'a> {
+            DnsFuture::new(async {
+                Never::new().await;
+                unreachable!()
+            })
+        }
+    }
+
+    /// Serves a single request with a fixed response, or never responds if `respond` is false.
+    ///
+    /// Returns the port that the server is listening on.
+    fn serve_once(respond: bool) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            if respond {
+                reader
+                    .into_inner()
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nhello")
+                    .unwrap();
+            } else {
+                std::thread::sleep(Duration::from_secs(5));
+            }
+        });
+        port
+    }
+
+    fn connector(
+        client: &SharedHttpClient,
+        settings: HttpConnectorSettings,
+    ) -> SharedHttpConnector {
+        let components = RuntimeComponentsBuilder::for_tests()
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap();
+        client.http_connector(&settings, &components)
+    }
+
+    #[tokio::test]
+    async fn sends_requests_with_the_dns_resolver_hook() {
+        let port = serve_once(true);
+        let resolver = LoopbackResolver::default();
+        let client = HyperClientBuilder::new()
+            .dns_resolver(resolver.clone())
+            .build_http();
+        let connector = connector(&client, HttpConnectorSettings::default());
+
+        let mut request = HttpRequest::get(format!("http://custom.example:{port}/")).unwrap();
+        let capture = CaptureSmithyConnection::new();
+        request.add_extension(capture.clone());
+        let response = connector.call(request).await.expect("request succeeds");
+
+        assert_eq!(200, response.status().as_u16());
+        let body = ByteStream::new(response.into_body()).collect().await;
+        assert_eq!(&b"hello"[..], &body.unwrap().into_bytes()[..]);
+        assert_eq!(vec!["custom.example"], *resolver.names.lock().unwrap());
+
+        let connection = capture.get().expect("connection was captured");
+        assert_eq!(
+            Some(SocketAddr::from((Ipv4Addr::LOCALHOST, port))),
+            connection.remote_addr()
+        );
+        // Poisoning the connection must not panic
+        connection.poison();
+    }
+
+    #[tokio::test]
+    async fn http_connect_timeout_works() {
+        let client = HyperClientBuilder::new()
+            .dns_resolver(NeverResolves)
+            .build_http();
+        let connector = connector(
+            &client,
+            HttpConnectorSettings::builder()
+                .connect_timeout(Duration::from_secs(1))
+                .build(),
+        );
+        let now = tokio::time::Instant::now();
+        tokio::time::pause();
+        let err = connector
+            .call(HttpRequest::get("http://static-uri.com").unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout(), "expected a timeout error, got {err:?}");
+        let message = DisplayErrorContext(&err).to_string();
+        let expected = "HTTP connect timeout occurred after 1s";
+        assert!(
+            message.contains(expected),
+            "expected '{message}' to contain '{expected}'"
+        );
+        assert_elapsed!(now, Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn http_read_timeout_works() {
+        let port = serve_once(false);
+        let client = HyperClientBuilder::new()
+            .dns_resolver(LoopbackResolver::default())
+            .build_http();
+        let connector = connector(
+            &client,
+            HttpConnectorSettings::builder()
+                .read_timeout(Duration::from_millis(100))
+                .build(),
+        );
+        let err = connector
+            .call(HttpRequest::get(format!("http://localhost:{port}/")).unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout(), "expected a timeout error, got {err:?}");
+        let message = DisplayErrorContext(&err).to_string();
+        let expected = "HTTP read timeout occurred after 100ms";
+        assert!(
+            message.contains(expected),
+            "expected '{message}' to contain '{expected}'"
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_failures_are_io_errors() {
+        // Bind and immediately drop a listener to find a port that refuses connections
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = HyperClientBuilder::new()
+            .dns_resolver(LoopbackResolver::default())
+            .build_http();
+        let connector = connector(&client, HttpConnectorSettings::default());
+        let err = connector
+            .call(HttpRequest::get(format!("http://localhost:{port}/")).unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.is_io(), "expected an IO error, got {err:?}");
+    }
+}
@@ -0,0 +1,500 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Interceptor for compressing request bodies of operations modeled with the `@requestCompression` trait.
+//!
+//! In-memory bodies are only compressed when they are at least as large as the configured
+//! [`RequestMinCompressionSizeBytes`]. Streaming bodies are always compressed, and since their
+//! compressed length can't be known ahead of time, their `Content-Length` header is removed.
+//!
+//! Compression runs in `modify_before_retry_loop`, so interceptors that are registered after this one
+//! (such as the one that adds `aws-chunked` checksum trailers) operate on the compressed body.
+
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::BeforeTransmitInterceptorContextMut;
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use http::header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH};
+use std::fmt;
+use std::io::Write;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+
+/// The default minimum size, in bytes, that an in-memory request body must be to get compressed.
+pub const DEFAULT_MIN_COMPRESSION_SIZE_BYTES: u32 = 10_240;
+
+/// The largest value that [`RequestMinCompressionSizeBytes`] may be set to.
+pub const MAX_MIN_COMPRESSION_SIZE_BYTES: u32 = 10_485_760;
+
+/// Whether request compression is disabled.
+///
+/// When stored in the config bag with a value of `true`, request bodies won't be compressed
+/// even if the operation supports it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DisableRequestCompression(bool);
+
+impl DisableRequestCompression {
+    /// Creates a new `DisableRequestCompression`.
+    pub fn new(disabled: bool) -> Self {
+        Self(disabled)
+    }
+
+    /// Returns true if request compression is disabled.
+    pub fn disabled(&self) -> bool {
+        self.0
+    }
+}
+
+impl From<bool> for DisableRequestCompression {
+    fn from(disabled: bool) -> Self {
+        Self(disabled)
+    }
+}
+
+impl Storable for DisableRequestCompression {
+    type Storer = StoreReplace<Self>;
+}
+
+/// The minimum size, in bytes, that an in-memory request body must be to get compressed.
+///
+/// Valid values are between 0 and 10485760 inclusive. Defaults to 10240 when not set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RequestMinCompressionSizeBytes(u32);
+
+impl RequestMinCompressionSizeBytes {
+    /// Creates a new `RequestMinCompressionSizeBytes`.
+    pub fn new(bytes: u32) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the minimum size in bytes.
+    pub fn bytes(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for RequestMinCompressionSizeBytes {
+    fn default() -> Self {
+        Self(DEFAULT_MIN_COMPRESSION_SIZE_BYTES)
+    }
+}
+
+impl From<u32> for RequestMinCompressionSizeBytes {
+    fn from(bytes: u32) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Storable for RequestMinCompressionSizeBytes {
+    type Storer = StoreReplace<Self>;
+}
+
+/// A compression algorithm that request bodies can be compressed with.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompressionAlgorithm {
+    /// The `gzip` algorithm.
+    Gzip,
+}
+
+impl CompressionAlgorithm {
+    /// The value of this algorithm in a `Content-Encoding` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+        }
+    }
+
+    fn compress_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+impl FromStr for CompressionAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("gzip") {
+            Ok(Self::Gzip)
+        } else {
+            Err(Error::UnknownAlgorithm(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Errors that can occur while compressing a request.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// The compression algorithm isn't supported.
+    UnknownAlgorithm(String),
+    /// The configured minimum compression size is out of range.
+    InvalidMinCompressionSize(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownAlgorithm(algorithm) => {
+                write!(f, "unknown compression algorithm `{algorithm}`")
+            }
+            Self::InvalidMinCompressionSize(bytes) => write!(
+                f,
+                "the minimum compression size must be between 0 and \
+                 {MAX_MIN_COMPRESSION_SIZE_BYTES} bytes, but was set to {bytes}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Interceptor that compresses request bodies.
+///
+/// This should only be registered for operations modeled with the `@requestCompression` trait,
+/// with the first of the trait's encodings that is supported.
+#[derive(Debug)]
+pub struct RequestCompressionInterceptor {
+    algorithm: CompressionAlgorithm,
+}
+
+impl RequestCompressionInterceptor {
+    /// Creates a new `RequestCompressionInterceptor` that compresses with the given `algorithm`.
+    pub fn new(algorithm: CompressionAlgorithm) -> Self {
+        Self { algorithm }
+    }
+}
+
+impl Intercept for RequestCompressionInterceptor {
+    fn name(&self) -> &'static str {
+        "RequestCompressionInterceptor"
+    }
+
+    fn modify_before_retry_loop(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if cfg
+            .load::<DisableRequestCompression>()
+            .map(DisableRequestCompression::disabled)
+            .unwrap_or_default()
+        {
+            tracing::trace!("request compression is disabled");
+            return Ok(());
+        }
+        let min_compression_size = cfg
+            .load::<RequestMinCompressionSizeBytes>()
+            .copied()
+            .unwrap_or_default()
+            .bytes();
+        if min_compression_size > MAX_MIN_COMPRESSION_SIZE_BYTES {
+            return Err(Error::InvalidMinCompressionSize(min_compression_size).into());
+        }
+        compress_request(context.request_mut(), self.algorithm, min_compression_size)
+    }
+}
+
+fn compress_request(
+    request: &mut HttpRequest,
+    algorithm: CompressionAlgorithm,
+    min_compression_size: u32,
+) -> Result<(), BoxError> {
+    match request.body().bytes() {
+        Some(bytes) => {
+            if (bytes.len() as u64) < u64::from(min_compression_size) {
+                tracing::trace!(
+                    "request body is smaller than {min_compression_size} bytes; not compressing it"
+                );
+                return Ok(());
+            }
+            tracing::debug!("compressing the request body with {algorithm}");
+            let compressed = algorithm.compress_bytes(bytes)?;
+            request
+                .headers_mut()
+                .insert(CONTENT_LENGTH, compressed.len().to_string());
+            *request.body_mut() = SdkBody::from(compressed);
+        }
+        None => {
+            tracing::debug!("compressing the streaming request body with {algorithm}");
+            let body = std::mem::replace(request.body_mut(), SdkBody::taken());
+            *request.body_mut() =
+                body.map(move |body| SdkBody::from_body_0_4(CompressedBody::new(body, algorithm)));
+            request.headers_mut().remove(CONTENT_LENGTH);
+        }
+    }
+
+    let content_encoding = match request.headers().get(CONTENT_ENCODING) {
+        Some(existing) if !existing.is_empty() => format!("{existing}, {algorithm}"),
+        _ => algorithm.as_str().to_string(),
+    };
+    request
+        .headers_mut()
+        .insert(CONTENT_ENCODING, HeaderValue::try_from(content_encoding)?);
+    Ok(())
+}
+
+pin_project_lite::pin_project! {
+    /// A body that compresses the data of the body it wraps as it's streamed.
+    struct CompressedBody {
+        #[pin]
+        inner: SdkBody,
+        // `None` once the encoder has been finished
+        encoder: Option<GzEncoder<Vec<u8>>>,
+    }
+}
+
+impl CompressedBody {
+    fn new(inner: SdkBody, algorithm: CompressionAlgorithm) -> Self {
+        let encoder = match algorithm {
+            CompressionAlgorithm::Gzip => GzEncoder::new(Vec::new(), Compression::default()),
+        };
+        Self {
+            inner,
+            encoder: Some(encoder),
+        }
+    }
+}
+
+impl http_body_0_4::Body for CompressedBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let mut this = self.project();
+        loop {
+            let encoder = match this.encoder.as_mut() {
+                Some(encoder) => encoder,
+                None => return Poll::Ready(None),
+            };
+            match this.inner.as_mut().poll_data(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(Some(Ok(data))) => {
+                    encoder.write_all(&data)?;
+                    let compressed = std::mem::take(encoder.get_mut());
+                    // The encoder buffers its input, so it may not have produced any output yet
+                    if !compressed.is_empty() {
+                        return Poll::Ready(Some(Ok(compressed.into())));
+                    }
+                }
+                Poll::Ready(None) => {
+                    let encoder = this.encoder.take().expect("checked above");
+                    return Poll::Ready(Some(Ok(encoder.finish()?.into())));
+                }
+            }
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        self.project().inner.poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.encoder.is_none() && self.inner.is_end_stream()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_runtime_api::client::interceptors::context::{Input, InterceptorContext};
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::config_bag::Layer;
+    use flate2::read::GzDecoder;
+    use http_body_0_4::Body;
+    use std::io::Read;
+
+    fn decompress(bytes: &[u8]) -> String {
+        let mut decompressed = String::new();
+        GzDecoder::new(bytes)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        decompressed
+    }
+
+    async fn collect(mut body: SdkBody) -> Vec<u8> {
+        let mut collected = Vec::new();
+        while let Some(data) = body.data().await {
+            collected.extend_from_slice(&data.unwrap());
+        }
+        collected
+    }
+
+    fn intercept(request: http::Request<SdkBody>, layer: Layer) -> HttpRequest {
+        let mut context = InterceptorContext::new(Input::doesnt_matter());
+        context.enter_serialization_phase();
+        context.set_request(request.try_into().unwrap());
+        let _ = context.take_input();
+        context.enter_before_transmit_phase();
+
+        let mut cfg = ConfigBag::of_layers(vec![layer]);
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        RequestCompressionInterceptor::new(CompressionAlgorithm::Gzip)
+            .modify_before_retry_loop(&mut (&mut context).into(), &runtime_components, &mut cfg)
+            .expect("success");
+        context.take_request().unwrap()
+    }
+
+    fn min_size(bytes: u32) -> Layer {
+        let mut layer = Layer::new("test");
+        layer.store_put(RequestMinCompressionSizeBytes::new(bytes));
+        layer
+    }
+
+    #[tokio::test]
+    async fn compresses_in_memory_bodies() {
+        let request = http::Request::builder()
+            .header(CONTENT_LENGTH, "11")
+            .body(SdkBody::from("hello world"))
+            .unwrap();
+        let request = intercept(request, min_size(0));
+
+        assert_eq!("gzip", request.headers().get(CONTENT_ENCODING).unwrap());
+        let body = request.body().bytes().unwrap();
+        assert_eq!(
+            body.len().to_string(),
+            request.headers().get(CONTENT_LENGTH).unwrap()
+        );
+        assert_eq!("hello world", decompress(body));
+    }
+
+    #[tokio::test]
+    async fn small_bodies_are_not_compressed() {
+        let request = http::Request::builder()
+            .body(SdkBody::from("hello world"))
+            .unwrap();
+        let request = intercept(request, Layer::new("test"));
+
+        assert_eq!(None, request.headers().get(CONTENT_ENCODING));
+        assert_eq!(b"hello world", request.body().bytes().unwrap());
+    }
+
+    #[tokio::test]
+    async fn compression_can_be_disabled() {
+        let mut layer = min_size(0);
+        layer.store_put(DisableRequestCompression::new(true));
+        let request = http::Request::builder()
+            .body(SdkBody::from("hello world"))
+            .unwrap();
+        let request = intercept(request, layer);
+
+        assert_eq!(None, request.headers().get(CONTENT_ENCODING));
+        assert_eq!(b"hello world", request.body().bytes().unwrap());
+    }
+
+    #[tokio::test]
+    async fn gzip_is_appended_to_existing_content_encoding() {
+        let request = http::Request::builder()
+            .header(CONTENT_ENCODING, "custom")
+            .body(SdkBody::from("hello world"))
+            .unwrap();
+        let request = intercept(request, min_size(0));
+
+        assert_eq!(
+            "custom, gzip",
+            request.headers().get(CONTENT_ENCODING).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn compresses_streaming_bodies() {
+        let request = http::Request::builder()
+            .header(CONTENT_LENGTH, "11")
+            .body(SdkBody::retryable(|| {
+                SdkBody::from_body_0_4(ChunkedBody(vec!["hello", " ", "world"]))
+            }))
+            .unwrap();
+        // Streaming bodies are compressed regardless of the minimum size
+        let request = intercept(request, Layer::new("test"));
+
+        assert_eq!("gzip", request.headers().get(CONTENT_ENCODING).unwrap());
+        assert_eq!(None, request.headers().get(CONTENT_LENGTH));
+        assert!(request.body().bytes().is_none());
+
+        // The compressed body remains retryable
+        let body = request.body().try_clone().expect("retryable");
+        assert_eq!("hello world", decompress(&collect(body).await));
+        let body = request.body().try_clone().expect("retryable");
+        assert_eq!("hello world", decompress(&collect(body).await));
+    }
+
+    #[test]
+    fn invalid_min_compression_size() {
+        let mut context = InterceptorContext::new(Input::doesnt_matter());
+        context.enter_serialization_phase();
+        context.set_request(HttpRequest::empty());
+        let _ = context.take_input();
+        context.enter_before_transmit_phase();
+
+        let mut cfg = ConfigBag::of_layers(vec![min_size(MAX_MIN_COMPRESSION_SIZE_BYTES + 1)]);
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let err = RequestCompressionInterceptor::new(CompressionAlgorithm::Gzip)
+            .modify_before_retry_loop(&mut (&mut context).into(), &runtime_components, &mut cfg)
+            .expect_err("out of range");
+        assert!(format!("{err}").contains("must be between 0 and 10485760 bytes"));
+    }
+
+    #[test]
+    fn parse_algorithm() {
+        assert_eq!(
+            CompressionAlgorithm::Gzip,
+            "gzip".parse::<CompressionAlgorithm>().unwrap()
+        );
+        assert!("zstd".parse::<CompressionAlgorithm>().is_err());
+    }
+
+    /// A streaming body that emits each of the given chunks separately.
+    struct ChunkedBody(Vec<&'static str>);
+
+    impl Body for ChunkedBody {
+        type Data = Bytes;
+        type Error = BoxError;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            if self.0.is_empty() {
+                Poll::Ready(None)
+            } else {
+                Poll::Ready(Some(Ok(Bytes::from_static(self.0.remove(0).as_bytes()))))
+            }
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+            Poll::Ready(Ok(None))
+        }
+    }
+}
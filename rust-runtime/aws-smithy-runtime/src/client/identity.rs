@@ -4,7 +4,10 @@
  */
 
 mod cache;
-pub use cache::{IdentityCache, LazyCacheBuilder};
+pub use cache::{IdentityCache, LazyCacheBuilder, PersistentCacheBuilder};
+
+/// Stores and codecs for persistent identity caching.
+pub use cache::persistent;
 
 /// Identity resolver implementation for "no auth".
 pub mod no_auth;
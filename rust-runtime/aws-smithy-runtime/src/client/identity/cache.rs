@@ -11,8 +11,10 @@ use aws_smithy_runtime_api::shared::IntoShared;
 use aws_smithy_types::config_bag::ConfigBag;
 
 mod lazy;
+pub mod persistent;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 pub use lazy::LazyCacheBuilder;
+pub use persistent::PersistentCacheBuilder;
 
 /// Identity cache configuration.
 ///
@@ -59,6 +61,30 @@ pub use lazy::LazyCacheBuilder;
 /// let client = some_service::Client::new(config);
 /// # */
 /// ```
+///
+/// Persisting identities in memory, so that they're shared by all the clients of the process:
+/// ```no_run
+/// use aws_smithy_runtime::client::identity::IdentityCache;
+/// use aws_smithy_runtime::client::identity::persistent::{InMemoryIdentityStore, TokenCodec};
+///
+/// # /*
+/// let config = some_service::Config::builder()
+///     .identity_cache(
+/// # */
+/// # drop(
+///         IdentityCache::persistent()
+///             .key("my-tool/default")
+///             .store(InMemoryIdentityStore::new())
+///             .codec(TokenCodec::new())
+///             .build()
+/// # );
+/// # /*
+///     )
+///     // ...
+///     .build();
+/// let client = some_service::Client::new(config);
+/// # */
+/// ```
 #[non_exhaustive]
 pub struct IdentityCache;
 
@@ -74,6 +100,15 @@ impl IdentityCache {
     pub fn lazy() -> LazyCacheBuilder {
         LazyCacheBuilder::new()
     }
+
+    /// Configure a persistent identity cache.
+    ///
+    /// Identities are lazy loaded and cached in memory like with the lazy cache, and are also
+    /// saved in an [`IdentityStore`](persistent::IdentityStore) from which other processes
+    /// can load them until they expire.
+    pub fn persistent() -> PersistentCacheBuilder {
+        PersistentCacheBuilder::new()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,844 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Identity caching that outlives the process.
+//!
+//! The lazy identity cache only keeps identities in memory, so short-lived processes, like
+//! command line tools, resolve their identity again on every invocation. The persistent cache
+//! additionally saves resolved identities in an [`IdentityStore`], from which the next
+//! process can load them until they expire.
+//!
+//! Identities are type erased, so an [`IdentityCodec`] converts them to and from bytes
+//! before they are handed to the store.
+
+use super::lazy::{CachePartitions, TimedOutError};
+use aws_smithy_async::future::now_or_later::{BoxFuture, NowOrLater};
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::identity::{
+    Identity, IdentityFuture, ResolveCachedIdentity, ResolveIdentity, SharedIdentityCache,
+    SharedIdentityResolver,
+};
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tracing::Instrument;
+
+#[cfg(feature = "encrypted-file-identity-store")]
+mod encrypted_file;
+#[cfg(feature = "encrypted-file-identity-store")]
+pub use encrypted_file::EncryptedFileIdentityStore;
+
+const DEFAULT_LOAD_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_EXPIRATION: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_BUFFER_TIME: Duration = Duration::from_secs(10);
+const DEFAULT_BUFFER_TIME_JITTER_FRACTION: fn() -> f64 = fastrand::f64;
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An identity serialized by an [`IdentityCodec`], along with its expiration time.
+#[derive(Clone, Eq, PartialEq)]
+pub struct StoredIdentity {
+    data: Vec<u8>,
+    expiration: SystemTime,
+}
+
+impl StoredIdentity {
+    /// Creates a new stored identity.
+    pub fn new(data: Vec<u8>, expiration: SystemTime) -> Self {
+        Self { data, expiration }
+    }
+
+    /// Returns the serialized identity.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the time at which the identity expires.
+    pub fn expiration(&self) -> SystemTime {
+        self.expiration
+    }
+}
+
+impl fmt::Debug for StoredIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredIdentity")
+            .field("data", &"** redacted **")
+            .field("expiration", &self.expiration)
+            .finish()
+    }
+}
+
+/// A lock on an entry of an [`IdentityStore`], released when dropped.
+///
+/// While an entry is locked, other processes wait for the identity to be resolved and stored
+/// instead of resolving it themselves.
+pub struct StoreLock {
+    _guard: Box<dyn fmt::Debug + Send + Sync>,
+}
+
+impl StoreLock {
+    /// Creates a new lock that releases the entry when `guard` is dropped.
+    pub fn new(guard: impl fmt::Debug + Send + Sync + 'static) -> Self {
+        Self {
+            _guard: Box::new(guard),
+        }
+    }
+}
+
+impl fmt::Debug for StoreLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StoreLock").field(&self._guard).finish()
+    }
+}
+
+/// Future returned by the methods of [`IdentityStore`].
+pub type StoreFuture<'a, T> = NowOrLater<Result<T, BoxError>, BoxFuture<'a, Result<T, BoxError>>>;
+
+/// Storage for the identities of a persistent identity cache.
+///
+/// Entries are identified by the key given to [`PersistentCacheBuilder::key`]. Failures of the
+/// store never fail identity resolution: they're logged, and the identity is resolved again.
+///
+/// Stores are used while resolving identities, so they must not block: stores doing blocking
+/// I/O should move it off of the async runtime.
+pub trait IdentityStore: fmt::Debug + Send + Sync {
+    /// Loads the identity stored for `key`, if any.
+    fn load<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<StoredIdentity>>;
+
+    /// Stores `identity` for `key`, replacing any previously stored identity.
+    fn store<'a>(&'a self, key: &'a str, identity: StoredIdentity) -> StoreFuture<'a, ()>;
+
+    /// Attempts to lock the entry for `key`.
+    ///
+    /// Returns `None` if the entry is already locked by someone else.
+    fn try_lock<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<StoreLock>>;
+}
+
+/// Conversion of identities to and from the bytes saved by an [`IdentityStore`].
+pub trait IdentityCodec: fmt::Debug + Send + Sync {
+    /// Serializes `identity`.
+    ///
+    /// Returns `None` if the identity isn't of the type handled by this codec, in which case it
+    /// is only cached in memory.
+    fn encode(&self, identity: &Identity) -> Option<Vec<u8>>;
+
+    /// Deserializes an identity expiring at `expiration`.
+    fn decode(&self, data: &[u8], expiration: SystemTime) -> Result<Identity, BoxError>;
+}
+
+/// [`IdentityCodec`] for HTTP bearer [`Token`](aws_smithy_runtime_api::client::identity::http::Token)s.
+#[cfg(feature = "http-auth")]
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct TokenCodec;
+
+#[cfg(feature = "http-auth")]
+impl TokenCodec {
+    /// Creates a new token codec.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "http-auth")]
+impl IdentityCodec for TokenCodec {
+    fn encode(&self, identity: &Identity) -> Option<Vec<u8>> {
+        use aws_smithy_runtime_api::client::identity::http::Token;
+        identity
+            .data::<Token>()
+            .map(|token| token.token().as_bytes().to_vec())
+    }
+
+    fn decode(&self, data: &[u8], expiration: SystemTime) -> Result<Identity, BoxError> {
+        use aws_smithy_runtime_api::client::identity::http::Token;
+        let token = std::str::from_utf8(data)?;
+        Ok(Identity::new(
+            Token::new(token, Some(expiration)),
+            Some(expiration),
+        ))
+    }
+}
+
+/// [`IdentityStore`] keeping identities in memory.
+///
+/// Clones share the same identities, so a single store can back the caches of several clients
+/// of the same process.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryIdentityStore {
+    inner: Arc<InMemoryInner>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryInner {
+    identities: Mutex<HashMap<String, StoredIdentity>>,
+    locked: Mutex<HashSet<String>>,
+}
+
+impl InMemoryIdentityStore {
+    /// Creates a new, empty, in-memory store.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl IdentityStore for InMemoryIdentityStore {
+    fn load<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<StoredIdentity>> {
+        StoreFuture::ready(Ok(self.inner.identities.lock().unwrap().get(key).cloned()))
+    }
+
+    fn store<'a>(&'a self, key: &'a str, identity: StoredIdentity) -> StoreFuture<'a, ()> {
+        self.inner
+            .identities
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), identity);
+        StoreFuture::ready(Ok(()))
+    }
+
+    fn try_lock<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<StoreLock>> {
+        let lock = if self.inner.locked.lock().unwrap().insert(key.to_string()) {
+            Some(StoreLock::new(InMemoryLock {
+                inner: self.inner.clone(),
+                key: key.to_string(),
+            }))
+        } else {
+            None
+        };
+        StoreFuture::ready(Ok(lock))
+    }
+}
+
+#[derive(Debug)]
+struct InMemoryLock {
+    inner: Arc<InMemoryInner>,
+    key: String,
+}
+
+impl Drop for InMemoryLock {
+    fn drop(&mut self) {
+        self.inner.locked.lock().unwrap().remove(&self.key);
+    }
+}
+
+/// Builder for persistent identity caching.
+#[derive(Debug, Default)]
+pub struct PersistentCacheBuilder {
+    key: Option<String>,
+    store: Option<Arc<dyn IdentityStore>>,
+    codec: Option<Arc<dyn IdentityCodec>>,
+    load_timeout: Option<Duration>,
+    buffer_time: Option<Duration>,
+    buffer_time_jitter_fraction: Option<fn() -> f64>,
+    default_expiration: Option<Duration>,
+}
+
+impl PersistentCacheBuilder {
+    /// Create a new builder.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Key identifying the cached identity in the store.
+    ///
+    /// The key must be unique to the identity resolver, for example by including the name of
+    /// the profile the identity is resolved from. All the identities resolved through the cache
+    /// share this key, so a client shouldn't use a persistent cache with more than one identity
+    /// resolver.
+    ///
+    /// This is required.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.set_key(Some(key.into()));
+        self
+    }
+
+    /// Key identifying the cached identity in the store.
+    ///
+    /// The key must be unique to the identity resolver, for example by including the name of
+    /// the profile the identity is resolved from. All the identities resolved through the cache
+    /// share this key, so a client shouldn't use a persistent cache with more than one identity
+    /// resolver.
+    ///
+    /// This is required.
+    pub fn set_key(&mut self, key: Option<String>) -> &mut Self {
+        self.key = key;
+        self
+    }
+
+    /// Store saving the resolved identities.
+    ///
+    /// This is required.
+    pub fn store(mut self, store: impl IdentityStore + 'static) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
+    /// Codec serializing the resolved identities for the store.
+    ///
+    /// This is required.
+    pub fn codec(mut self, codec: impl IdentityCodec + 'static) -> Self {
+        self.codec = Some(Arc::new(codec));
+        self
+    }
+
+    /// Timeout for identity resolution.
+    ///
+    /// This is also the maximum amount of time spent waiting for another process to resolve
+    /// the identity. Defaults to 5 seconds.
+    pub fn load_timeout(mut self, timeout: Duration) -> Self {
+        self.set_load_timeout(Some(timeout));
+        self
+    }
+
+    /// Timeout for identity resolution.
+    ///
+    /// This is also the maximum amount of time spent waiting for another process to resolve
+    /// the identity. Defaults to 5 seconds.
+    pub fn set_load_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.load_timeout = timeout;
+        self
+    }
+
+    /// Amount of time before the actual identity expiration time where the identity is considered expired.
+    ///
+    /// This applies to identities loaded from the store as well as to those cached in memory.
+    ///
+    /// Note: random jitter value between [0.0, 1.0] is multiplied to this buffer time.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn buffer_time(mut self, buffer_time: Duration) -> Self {
+        self.set_buffer_time(Some(buffer_time));
+        self
+    }
+
+    /// Amount of time before the actual identity expiration time where the identity is considered expired.
+    ///
+    /// This applies to identities loaded from the store as well as to those cached in memory.
+    ///
+    /// Note: random jitter value between [0.0, 1.0] is multiplied to this buffer time.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn set_buffer_time(&mut self, buffer_time: Option<Duration>) -> &mut Self {
+        self.buffer_time = buffer_time;
+        self
+    }
+
+    #[allow(unused)]
+    #[cfg(test)]
+    fn buffer_time_jitter_fraction(mut self, buffer_time_jitter_fraction: fn() -> f64) -> Self {
+        self.buffer_time_jitter_fraction = Some(buffer_time_jitter_fraction);
+        self
+    }
+
+    /// Default expiration time to set on an identity if it doesn't have an expiration time.
+    ///
+    /// Identities without an expiration time are stored with this expiration time too.
+    /// This must be at least 15 minutes.
+    ///
+    /// Defaults to 15 minutes.
+    pub fn default_expiration(mut self, duration: Duration) -> Self {
+        self.set_default_expiration(Some(duration));
+        self
+    }
+
+    /// Default expiration time to set on an identity if it doesn't have an expiration time.
+    ///
+    /// Identities without an expiration time are stored with this expiration time too.
+    /// This must be at least 15 minutes.
+    ///
+    /// Defaults to 15 minutes.
+    pub fn set_default_expiration(&mut self, duration: Option<Duration>) -> &mut Self {
+        self.default_expiration = duration;
+        self
+    }
+
+    /// Builds a [`SharedIdentityCache`] from this builder.
+    ///
+    /// # Panics
+    ///
+    /// This builder will panic if required fields are not given, or if given values are not valid.
+    pub fn build(self) -> SharedIdentityCache {
+        self.build_cache().into_shared()
+    }
+
+    fn build_cache(self) -> PersistentCache {
+        let default_expiration = self.default_expiration.unwrap_or(DEFAULT_EXPIRATION);
+        assert!(
+            default_expiration >= DEFAULT_EXPIRATION,
+            "default_expiration must be at least 15 minutes"
+        );
+        let buffer_time = self.buffer_time.unwrap_or(DEFAULT_BUFFER_TIME);
+        PersistentCache {
+            partitions: CachePartitions::new(buffer_time),
+            key: self
+                .key
+                .expect("a key is required for persistent identity caching"),
+            store: self
+                .store
+                .expect("a store is required for persistent identity caching"),
+            codec: self
+                .codec
+                .expect("a codec is required for persistent identity caching"),
+            load_timeout: self.load_timeout.unwrap_or(DEFAULT_LOAD_TIMEOUT),
+            buffer_time,
+            buffer_time_jitter_fraction: self
+                .buffer_time_jitter_fraction
+                .unwrap_or(DEFAULT_BUFFER_TIME_JITTER_FRACTION),
+            default_expiration,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PersistentCache {
+    partitions: CachePartitions,
+    key: String,
+    store: Arc<dyn IdentityStore>,
+    codec: Arc<dyn IdentityCodec>,
+    load_timeout: Duration,
+    buffer_time: Duration,
+    buffer_time_jitter_fraction: fn() -> f64,
+    default_expiration: Duration,
+}
+
+impl PersistentCache {
+    /// Loads the identity from the store, unless it's missing, invalid or expired.
+    async fn load_stored(&self, now: SystemTime) -> Option<(Identity, SystemTime)> {
+        let stored = match self.store.load(&self.key).await {
+            Ok(Some(stored)) => stored,
+            Ok(None) => return None,
+            Err(err) => {
+                tracing::warn!(key = %self.key, err = %err, "failed to load the stored identity");
+                return None;
+            }
+        };
+        if now + self.buffer_time >= stored.expiration() {
+            tracing::debug!(key = %self.key, expiration = ?stored.expiration(), "stored identity is expired");
+            return None;
+        }
+        match self.codec.decode(stored.data(), stored.expiration()) {
+            // Codecs aren't required to set the expiration on the decoded identity.
+            Ok(identity) => {
+                let expiration = identity.expiration().unwrap_or(stored.expiration());
+                Some((identity, expiration))
+            }
+            Err(err) => {
+                tracing::warn!(key = %self.key, err = %err, "failed to decode the stored identity");
+                None
+            }
+        }
+    }
+
+    /// Stores `identity`, if the codec supports it.
+    async fn store(&self, identity: &Identity, expiration: SystemTime) {
+        let Some(data) = self.codec.encode(identity) else {
+            tracing::debug!(key = %self.key, "identity is not supported by the codec, only caching it in memory");
+            return;
+        };
+        if let Err(err) = self
+            .store
+            .store(&self.key, StoredIdentity::new(data, expiration))
+            .await
+        {
+            tracing::warn!(key = %self.key, err = %err, "failed to store the identity");
+        }
+    }
+
+    /// Locks the store entry, waiting up to the load timeout for another process to release it.
+    ///
+    /// Returns `None` if the entry couldn't be locked, in which case the identity is resolved
+    /// without holding the lock.
+    async fn lock(&self, sleep_impl: &SharedAsyncSleep) -> Option<StoreLock> {
+        let attempts = (self.load_timeout.as_millis() / LOCK_RETRY_INTERVAL.as_millis()).max(1);
+        for _ in 0..attempts {
+            match self.store.try_lock(&self.key).await {
+                Ok(Some(lock)) => return Some(lock),
+                Ok(None) => sleep_impl.sleep(LOCK_RETRY_INTERVAL).await,
+                Err(err) => {
+                    tracing::warn!(key = %self.key, err = %err, "failed to lock the stored identity");
+                    return None;
+                }
+            }
+        }
+        tracing::warn!(key = %self.key, timeout = ?self.load_timeout, "timed out waiting for the lock on the stored identity");
+        None
+    }
+
+    fn jitter(&self) -> Duration {
+        self.buffer_time
+            .mul_f64((self.buffer_time_jitter_fraction)())
+    }
+}
+
+fn validate_components(
+    time_source: Option<SharedTimeSource>,
+    sleep_impl: Option<SharedAsyncSleep>,
+) -> Result<(), BoxError> {
+    const DISABLE: &str = " If this isn't possible, then use the lazy identity cache, or disable identity caching by calling the `identity_cache` method on config with `IdentityCache::no_cache()`";
+    if time_source.is_none() {
+        return Err(format!("Persistent identity caching requires a time source to be configured. Set a time source using the `time_source` method on config.{DISABLE}").into());
+    }
+    if sleep_impl.is_none() {
+        return Err(format!("Persistent identity caching requires an async sleep implementation to be configured. Set a sleep impl using the `sleep_impl` method on config.{DISABLE}").into());
+    }
+    Ok(())
+}
+
+impl ResolveCachedIdentity for PersistentCache {
+    fn validate_base_client_config(
+        &self,
+        runtime_components: &RuntimeComponentsBuilder,
+        _cfg: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        validate_components(
+            runtime_components.time_source(),
+            runtime_components.sleep_impl(),
+        )
+    }
+
+    fn validate_final_config(
+        &self,
+        runtime_components: &RuntimeComponents,
+        _cfg: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        validate_components(
+            runtime_components.time_source(),
+            runtime_components.sleep_impl(),
+        )
+    }
+
+    fn resolve_cached_identity<'a>(
+        &'a self,
+        resolver: SharedIdentityResolver,
+        runtime_components: &'a RuntimeComponents,
+        config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        let (time_source, sleep_impl) = (
+            runtime_components.time_source().expect("validated"),
+            runtime_components.sleep_impl().expect("validated"),
+        );
+        let now = time_source.now();
+        let cache = self.partitions.partition(resolver.cache_partition());
+
+        IdentityFuture::new(async move {
+            if let Some(identity) = cache.yield_or_clear_if_expired(now).await {
+                tracing::debug!(
+                    cached_expiration = ?identity.expiration(),
+                    now = ?now,
+                    "loaded identity from memory"
+                );
+                return Ok(identity);
+            }
+
+            let result = cache
+                .get_or_load(|| {
+                    let span = tracing::info_span!("persistent_load_identity", key = %self.key);
+                    async move {
+                        // Another process may have stored a fresh identity since this one last
+                        // loaded it.
+                        if let Some((identity, expiration)) = self.load_stored(now).await {
+                            tracing::debug!("loaded identity from the store");
+                            return Ok((identity, expiration + self.jitter()));
+                        }
+
+                        let _lock = self.lock(&sleep_impl).await;
+                        // Check again now that the entry is locked, in case another process
+                        // resolved the identity while this one was waiting for the lock.
+                        let now = time_source.now();
+                        if let Some((identity, expiration)) = self.load_stored(now).await {
+                            tracing::debug!(
+                                "loaded identity from the store after waiting for the lock"
+                            );
+                            return Ok((identity, expiration + self.jitter()));
+                        }
+
+                        let fut = Timeout::new(
+                            resolver.resolve_identity(runtime_components, config_bag),
+                            sleep_impl.sleep(self.load_timeout),
+                        );
+                        let identity = match fut.await {
+                            Ok(result) => result?,
+                            Err(_err) => match resolver.fallback_on_interrupt() {
+                                Some(identity) => identity,
+                                None => {
+                                    return Err(BoxError::from(TimedOutError(self.load_timeout)))
+                                }
+                            },
+                        };
+                        let expiration = identity
+                            .expiration()
+                            .unwrap_or(now + self.default_expiration);
+                        self.store(&identity, expiration).await;
+                        tracing::info!(
+                            "identity cache miss occurred; resolved and stored a new identity"
+                        );
+                        Ok((identity, expiration + self.jitter()))
+                    }
+                    .instrument(span)
+                })
+                .await;
+            tracing::debug!("loaded identity");
+            result
+        })
+    }
+}
+
+#[cfg(all(test, feature = "client", feature = "http-auth"))]
+mod tests {
+    use super::*;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_runtime_api::client::identity::http::Token;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::UNIX_EPOCH;
+
+    const BUFFER_TIME_NO_JITTER: fn() -> f64 = || 0_f64;
+
+    #[derive(Debug)]
+    struct CountingResolver {
+        calls: Arc<AtomicUsize>,
+        expiration: Option<SystemTime>,
+    }
+
+    impl ResolveIdentity for CountingResolver {
+        fn resolve_identity<'a>(
+            &'a self,
+            _: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            IdentityFuture::ready(Ok(Identity::new(
+                Token::new(format!("token-{call}"), self.expiration),
+                self.expiration,
+            )))
+        }
+    }
+
+    fn epoch_secs(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn components(time: &ManualTimeSource) -> RuntimeComponents {
+        RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time.clone()))
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap()
+    }
+
+    fn cache(store: &InMemoryIdentityStore) -> PersistentCache {
+        PersistentCacheBuilder::new()
+            .key("test")
+            .store(store.clone())
+            .codec(TokenCodec::new())
+            .buffer_time_jitter_fraction(BUFFER_TIME_NO_JITTER)
+            .build_cache()
+    }
+
+    fn resolver(calls: &Arc<AtomicUsize>, expiration: Option<u64>) -> SharedIdentityResolver {
+        SharedIdentityResolver::new(CountingResolver {
+            calls: calls.clone(),
+            expiration: expiration.map(epoch_secs),
+        })
+    }
+
+    async fn resolve_token(
+        cache: &PersistentCache,
+        resolver: &SharedIdentityResolver,
+        components: &RuntimeComponents,
+    ) -> String {
+        let identity = cache
+            .resolve_cached_identity(resolver.clone(), components, &ConfigBag::base())
+            .await
+            .expect("identity");
+        identity.data::<Token>().unwrap().token().to_string()
+    }
+
+    #[tokio::test]
+    async fn stored_identity_is_shared_between_caches() {
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = components(&time);
+        let store = InMemoryIdentityStore::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        // A first "process" resolves and stores the identity.
+        let first = resolver(&calls, Some(1000));
+        assert_eq!(
+            "token-0",
+            resolve_token(&cache(&store), &first, &components).await
+        );
+
+        // A second one loads it from the store, without calling its resolver.
+        let second = resolver(&calls, Some(1000));
+        assert_eq!(
+            "token-0",
+            resolve_token(&cache(&store), &second, &components).await
+        );
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+
+        let stored = store.load("test").await.unwrap().unwrap();
+        assert_eq!(epoch_secs(1000), stored.expiration());
+        assert_eq!(b"token-0", stored.data());
+    }
+
+    #[tokio::test]
+    async fn expired_stored_identity_is_replaced() {
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = components(&time);
+        let store = InMemoryIdentityStore::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let resolver = resolver(&calls, Some(1000));
+        assert_eq!(
+            "token-0",
+            resolve_token(&cache(&store), &resolver, &components).await
+        );
+
+        // Within the buffer time of the expiration.
+        time.set_time(epoch_secs(995));
+        assert_eq!(
+            "token-1",
+            resolve_token(&cache(&store), &resolver, &components).await
+        );
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+        assert_eq!(
+            b"token-1",
+            store.load("test").await.unwrap().unwrap().data()
+        );
+    }
+
+    #[tokio::test]
+    async fn identity_without_expiration_is_stored_with_the_default_expiration() {
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = components(&time);
+        let store = InMemoryIdentityStore::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let resolver = resolver(&calls, None);
+        resolve_token(&cache(&store), &resolver, &components).await;
+        assert_eq!(
+            epoch_secs(100) + DEFAULT_EXPIRATION,
+            store.load("test").await.unwrap().unwrap().expiration()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_stored_identity_is_ignored() {
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = components(&time);
+        let store = InMemoryIdentityStore::new();
+        store
+            .store(
+                "test",
+                StoredIdentity::new(vec![0xff, 0xfe], epoch_secs(1000)),
+            )
+            .await
+            .unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let resolver = resolver(&calls, Some(1000));
+        assert_eq!(
+            "token-0",
+            resolve_token(&cache(&store), &resolver, &components).await
+        );
+    }
+
+    #[tokio::test]
+    async fn stored_expiration_is_used_when_the_codec_does_not_set_one() {
+        #[derive(Debug)]
+        struct NoExpirationCodec;
+
+        impl IdentityCodec for NoExpirationCodec {
+            fn encode(&self, identity: &Identity) -> Option<Vec<u8>> {
+                TokenCodec::new().encode(identity)
+            }
+
+            fn decode(&self, data: &[u8], _: SystemTime) -> Result<Identity, BoxError> {
+                let token = std::str::from_utf8(data)?;
+                Ok(Identity::new(Token::new(token, None), None))
+            }
+        }
+
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = components(&time);
+        let store = InMemoryIdentityStore::new();
+        store
+            .store(
+                "test",
+                StoredIdentity::new(b"stored".to_vec(), epoch_secs(1000)),
+            )
+            .await
+            .unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let cache = PersistentCacheBuilder::new()
+            .key("test")
+            .store(store.clone())
+            .codec(NoExpirationCodec)
+            .buffer_time_jitter_fraction(BUFFER_TIME_NO_JITTER)
+            .build_cache();
+        let resolver = resolver(&calls, Some(2000));
+        assert_eq!(
+            "stored",
+            resolve_token(&cache, &resolver, &components).await
+        );
+
+        // The identity expires from memory at the stored expiration.
+        time.set_time(epoch_secs(1000));
+        assert_eq!(
+            "token-0",
+            resolve_token(&cache, &resolver, &components).await
+        );
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn resolves_without_the_lock_when_it_is_never_released() {
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let components = components(&time);
+        let store = InMemoryIdentityStore::new();
+        let _held = store.try_lock("test").await.unwrap().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let cache = PersistentCacheBuilder::new()
+            .key("test")
+            .store(store.clone())
+            .codec(TokenCodec::new())
+            .load_timeout(Duration::from_millis(100))
+            .build_cache();
+        let resolver = resolver(&calls, Some(1000));
+        assert_eq!(
+            "token-0",
+            resolve_token(&cache, &resolver, &components).await
+        );
+    }
+
+    #[tokio::test]
+    async fn in_memory_lock_is_released_on_drop() {
+        let store = InMemoryIdentityStore::new();
+        let lock = store.try_lock("a").await.unwrap();
+        assert!(lock.is_some());
+        assert!(store.try_lock("a").await.unwrap().is_none());
+        assert!(store.try_lock("b").await.unwrap().is_some());
+        drop(lock);
+        assert!(store.try_lock("a").await.unwrap().is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "a key is required")]
+    fn key_is_required() {
+        PersistentCacheBuilder::new()
+            .store(InMemoryIdentityStore::new())
+            .codec(TokenCodec::new())
+            .build();
+    }
+}
@@ -0,0 +1,431 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::{IdentityStore, StoreFuture, StoreLock, StoredIdentity};
+use aws_smithy_async::time::{SystemTimeSource, TimeSource};
+use aws_smithy_runtime_api::box_error::BoxError;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::digest;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::error::Error as StdError;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Version of the file format, stored as the first byte of every file.
+const FORMAT_VERSION: u8 = 1;
+/// Length of the expiration time (seconds and nanoseconds) at the start of the plaintext.
+const EXPIRATION_LEN: usize = 12;
+const DEFAULT_STALE_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// [`IdentityStore`] saving identities in encrypted files.
+///
+/// Every key is saved in its own file of `directory`, named after the SHA-256 digest of the
+/// key. Files are encrypted with AES-256-GCM, and authenticated with their key so that they
+/// can't be swapped. They are written atomically, by renaming a temporary file, and are only
+/// readable by their owner on Unix.
+///
+/// Entries are locked across processes with lock files created next to them. A lock file
+/// older than the stale lock timeout is assumed to belong to a process that died while
+/// holding the lock, and is removed.
+///
+/// File system operations run on Tokio's blocking thread pool, so this store requires a Tokio
+/// runtime.
+///
+/// ```no_run
+/// use aws_smithy_runtime::client::identity::persistent::EncryptedFileIdentityStore;
+///
+/// # fn load_key() -> [u8; 32] { [0; 32] }
+/// // The key should come from a secure location, like the OS keyring.
+/// let store = EncryptedFileIdentityStore::new("/home/user/.my-tool/cache", load_key());
+/// ```
+#[derive(Clone)]
+pub struct EncryptedFileIdentityStore {
+    directory: PathBuf,
+    key: Arc<LessSafeKey>,
+    rng: SystemRandom,
+    stale_lock_timeout: Duration,
+}
+
+impl EncryptedFileIdentityStore {
+    /// Creates a new store saving identities in `directory`, encrypted with the AES-256 `key`.
+    ///
+    /// The directory is created when the first identity is stored.
+    pub fn new(directory: impl Into<PathBuf>, key: [u8; 32]) -> Self {
+        Self {
+            directory: directory.into(),
+            key: Arc::new(LessSafeKey::new(
+                UnboundKey::new(&AES_256_GCM, &key).expect("the key length is correct"),
+            )),
+            rng: SystemRandom::new(),
+            stale_lock_timeout: DEFAULT_STALE_LOCK_TIMEOUT,
+        }
+    }
+
+    /// Age after which a lock file is considered abandoned and removed.
+    ///
+    /// This should be longer than the time it takes to resolve an identity. Defaults to 30 seconds.
+    pub fn stale_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.stale_lock_timeout = timeout;
+        self
+    }
+
+    fn path(&self, key: &str, extension: &str) -> PathBuf {
+        let digest = digest::digest(&digest::SHA256, key.as_bytes());
+        let mut path = self.directory.join(hex::encode(digest));
+        path.set_extension(extension);
+        path
+    }
+
+    fn encrypt(&self, key: &str, identity: &StoredIdentity) -> Result<Vec<u8>, FileStoreError> {
+        let since_epoch = identity
+            .expiration()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| FileStoreError::Malformed("expiration is before the Unix epoch"))?;
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| FileStoreError::Crypto("failed to generate a nonce"))?;
+
+        let mut in_out = Vec::with_capacity(EXPIRATION_LEN + identity.data().len());
+        in_out.extend_from_slice(&since_epoch.as_secs().to_be_bytes());
+        in_out.extend_from_slice(&since_epoch.subsec_nanos().to_be_bytes());
+        in_out.extend_from_slice(identity.data());
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(key.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| FileStoreError::Crypto("failed to encrypt the identity"))?;
+
+        let mut contents = Vec::with_capacity(1 + NONCE_LEN + in_out.len());
+        contents.push(FORMAT_VERSION);
+        contents.extend_from_slice(&nonce);
+        contents.extend_from_slice(&in_out);
+        Ok(contents)
+    }
+
+    fn decrypt(&self, key: &str, mut contents: Vec<u8>) -> Result<StoredIdentity, FileStoreError> {
+        if contents.first() != Some(&FORMAT_VERSION) {
+            return Err(FileStoreError::Malformed("unsupported file format"));
+        }
+        if contents.len() < 1 + NONCE_LEN {
+            return Err(FileStoreError::Malformed("file is truncated"));
+        }
+        let nonce = Nonce::try_assume_unique_for_key(&contents[1..1 + NONCE_LEN])
+            .expect("the nonce length is correct");
+        let plaintext = self
+            .key
+            .open_in_place(
+                nonce,
+                Aad::from(key.as_bytes()),
+                &mut contents[1 + NONCE_LEN..],
+            )
+            .map_err(|_| FileStoreError::Crypto("failed to decrypt the identity"))?;
+        if plaintext.len() < EXPIRATION_LEN {
+            return Err(FileStoreError::Malformed("expiration is missing"));
+        }
+        let (expiration, data) = plaintext.split_at(EXPIRATION_LEN);
+        let secs = u64::from_be_bytes(expiration[..8].try_into().expect("correct length"));
+        let nanos = u32::from_be_bytes(expiration[8..].try_into().expect("correct length"));
+        let expiration = UNIX_EPOCH
+            .checked_add(Duration::new(secs, nanos))
+            .ok_or(FileStoreError::Malformed("expiration is out of range"))?;
+        Ok(StoredIdentity::new(data.to_vec(), expiration))
+    }
+
+    /// Writes `contents` to `path` by renaming a temporary file, so that concurrent readers
+    /// never see a partially written file.
+    fn write_atomically(&self, path: &Path, contents: &[u8]) -> Result<(), FileStoreError> {
+        create_private_dir(&self.directory).map_err(|source| FileStoreError::Io {
+            what: "create",
+            path: self.directory.clone(),
+            source,
+        })?;
+        let mut temp_path = path.to_path_buf();
+        temp_path.set_extension(format!("{}.{}.tmp", std::process::id(), fastrand::u32(..)));
+        let result = create_private_file(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(contents)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, path));
+        if let Err(source) = result {
+            let _ = fs::remove_file(&temp_path);
+            return Err(FileStoreError::Io {
+                what: "write",
+                path: path.to_path_buf(),
+                source,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for EncryptedFileIdentityStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedFileIdentityStore")
+            .field("directory", &self.directory)
+            .field("key", &"** redacted **")
+            .field("stale_lock_timeout", &self.stale_lock_timeout)
+            .finish()
+    }
+}
+
+impl EncryptedFileIdentityStore {
+    fn load_blocking(&self, key: &str) -> Result<Option<StoredIdentity>, BoxError> {
+        let path = self.path(key, "identity");
+        let contents = match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => {
+                return Err(FileStoreError::Io {
+                    what: "read",
+                    path,
+                    source,
+                }
+                .into())
+            }
+        };
+        Ok(Some(self.decrypt(key, contents)?))
+    }
+
+    fn store_blocking(&self, key: &str, identity: StoredIdentity) -> Result<(), BoxError> {
+        let contents = self.encrypt(key, &identity)?;
+        self.write_atomically(&self.path(key, "identity"), &contents)?;
+        Ok(())
+    }
+
+    fn try_lock_blocking(&self, key: &str) -> Result<Option<StoreLock>, BoxError> {
+        let path = self.path(key, "lock");
+        create_private_dir(&self.directory).map_err(|source| FileStoreError::Io {
+            what: "create",
+            path: self.directory.clone(),
+            source,
+        })?;
+        for _ in 0..2 {
+            match create_private_file(&path) {
+                Ok(_) => return Ok(Some(StoreLock::new(LockFile { path }))),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| {
+                            // Lock files are dated by the file system, not by the time source of the client.
+                            SystemTimeSource::new().now().duration_since(modified).ok()
+                        });
+                    match age {
+                        Some(age) if age > self.stale_lock_timeout => {
+                            tracing::warn!(path = %path.display(), age = ?age, "removing stale lock file");
+                            let _ = fs::remove_file(&path);
+                        }
+                        _ => return Ok(None),
+                    }
+                }
+                Err(source) => {
+                    return Err(FileStoreError::Io {
+                        what: "create",
+                        path,
+                        source,
+                    }
+                    .into())
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl IdentityStore for EncryptedFileIdentityStore {
+    fn load<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<StoredIdentity>> {
+        let (store, key) = (self.clone(), key.to_string());
+        spawn_blocking(move || store.load_blocking(&key))
+    }
+
+    fn store<'a>(&'a self, key: &'a str, identity: StoredIdentity) -> StoreFuture<'a, ()> {
+        let (store, key) = (self.clone(), key.to_string());
+        spawn_blocking(move || store.store_blocking(&key, identity))
+    }
+
+    fn try_lock<'a>(&'a self, key: &'a str) -> StoreFuture<'a, Option<StoreLock>> {
+        let (store, key) = (self.clone(), key.to_string());
+        spawn_blocking(move || store.try_lock_blocking(&key))
+    }
+}
+
+/// Runs the file system operation `f` on Tokio's blocking thread pool.
+fn spawn_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, BoxError> + Send + 'static,
+) -> StoreFuture<'static, T> {
+    StoreFuture::new(Box::pin(async move {
+        tokio::task::spawn_blocking(f)
+            .await
+            .map_err(BoxError::from)?
+    }))
+}
+
+/// Lock file removed when the lock is released.
+#[derive(Debug)]
+struct LockFile {
+    path: PathBuf,
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            tracing::warn!(path = %self.path.display(), err = %err, "failed to remove lock file");
+        }
+    }
+}
+
+/// Creates a new file, failing if it already exists, that only its owner can read and write.
+fn create_private_file(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Creates a directory and its parents, if missing, that only its owner can access.
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(path)
+}
+
+#[derive(Debug)]
+enum FileStoreError {
+    Crypto(&'static str),
+    Io {
+        what: &'static str,
+        path: PathBuf,
+        source: io::Error,
+    },
+    Malformed(&'static str),
+}
+
+impl fmt::Display for FileStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Crypto(message) => f.write_str(message),
+            Self::Io { what, path, .. } => write!(f, "failed to {what} `{}`", path.display()),
+            Self::Malformed(message) => write!(f, "malformed identity file: {message}"),
+        }
+    }
+}
+
+impl StdError for FileStoreError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Crypto(_) | Self::Malformed(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    fn identity() -> StoredIdentity {
+        StoredIdentity::new(
+            b"secret".to_vec(),
+            UNIX_EPOCH + Duration::new(1_700_000_000, 123),
+        )
+    }
+
+    #[tokio::test]
+    async fn stored_identity_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileIdentityStore::new(dir.path().join("cache"), KEY);
+        assert_eq!(None, store.load("key").await.unwrap());
+
+        store.store("key", identity()).await.unwrap();
+        assert_eq!(Some(identity()), store.load("key").await.unwrap());
+        assert_eq!(None, store.load("other-key").await.unwrap());
+
+        // Only the identity file is left behind.
+        let files: Vec<_> = fs::read_dir(dir.path().join("cache")).unwrap().collect();
+        assert_eq!(1, files.len());
+    }
+
+    #[tokio::test]
+    async fn identities_are_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileIdentityStore::new(dir.path(), KEY);
+        store.store("key", identity()).await.unwrap();
+
+        let contents = fs::read(store.path("key", "identity")).unwrap();
+        assert!(!contents.windows(6).any(|window| window == b"secret"));
+
+        let other_store = EncryptedFileIdentityStore::new(dir.path(), [8; 32]);
+        let err = other_store.load("key").await.unwrap_err();
+        assert_eq!("failed to decrypt the identity", err.to_string());
+    }
+
+    #[tokio::test]
+    async fn identities_are_bound_to_their_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileIdentityStore::new(dir.path(), KEY);
+        store.store("key", identity()).await.unwrap();
+        fs::copy(
+            store.path("key", "identity"),
+            store.path("other-key", "identity"),
+        )
+        .unwrap();
+        assert!(store.load("other-key").await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileIdentityStore::new(dir.path().join("cache"), KEY);
+        store.store("key", identity()).await.unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(0o700, mode(&dir.path().join("cache")));
+        assert_eq!(0o600, mode(&store.path("key", "identity")));
+    }
+
+    #[tokio::test]
+    async fn lock_is_exclusive_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EncryptedFileIdentityStore::new(dir.path(), KEY);
+        let lock = store.try_lock("key").await.unwrap();
+        assert!(lock.is_some());
+        assert!(store.try_lock("key").await.unwrap().is_none());
+        assert!(store.try_lock("other-key").await.unwrap().is_some());
+        drop(lock);
+        assert!(store.try_lock("key").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn stale_lock_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            EncryptedFileIdentityStore::new(dir.path(), KEY).stale_lock_timeout(Duration::ZERO);
+        let abandoned = store.try_lock("key").await.unwrap().unwrap();
+        std::mem::forget(abandoned);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(store.try_lock("key").await.unwrap().is_some());
+    }
+}
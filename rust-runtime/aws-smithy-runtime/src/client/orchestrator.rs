@@ -6,20 +6,24 @@
 // TODO(msrvUpgrade): This can be removed once we upgrade the MSRV to Rust 1.69
 #![allow(unknown_lints)]
 
-use self::auth::orchestrate_auth;
+use self::auth::{orchestrate_auth, resolve_identity};
+use self::metrics::{OperationMetrics, Phase};
 use crate::client::interceptors::Interceptors;
-use crate::client::orchestrator::endpoints::orchestrate_endpoint;
+use crate::client::orchestrator::endpoints::{finalize_endpoint_params, orchestrate_endpoint};
 use crate::client::orchestrator::http::{log_response_body, read_body};
+use crate::client::response_cache;
+use crate::client::retries::hedging;
 use crate::client::timeout::{MaybeTimeout, MaybeTimeoutConfig, TimeoutKind};
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_runtime_api::box_error::BoxError;
-use aws_smithy_runtime_api::client::http::{HttpClient, HttpConnector, HttpConnectorSettings};
+use aws_smithy_runtime_api::client::http::{HttpClient, HttpConnectorSettings};
 use aws_smithy_runtime_api::client::interceptors::context::{
     Error, Input, InterceptorContext, Output, RewindResult,
 };
 use aws_smithy_runtime_api::client::orchestrator::{
     HttpResponse, LoadedRequestBody, OrchestratorError,
 };
+use aws_smithy_runtime_api::client::request_limiter::LimitRequests;
 use aws_smithy_runtime_api::client::result::SdkError;
 use aws_smithy_runtime_api::client::retries::{RequestAttempts, RetryStrategy, ShouldAttempt};
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
@@ -42,6 +46,8 @@ pub mod endpoints;
 /// Defines types that work with HTTP types
 mod http;
 
+mod metrics;
+
 /// Utility for making one-off unmodeled requests with the orchestrator.
 #[doc(hidden)]
 pub mod operation;
@@ -150,20 +156,30 @@ pub async fn invoke_with_stop_point(
             .map_err(SdkError::construction_failure)?;
         trace!(runtime_components = ?runtime_components);
 
+        let metrics = OperationMetrics::new(&runtime_components, service_name, operation_name);
+        let call_start = metrics.start();
+
         let operation_timeout_config =
             MaybeTimeoutConfig::new(&runtime_components, cfg, TimeoutKind::Operation);
         trace!(operation_timeout_config = ?operation_timeout_config);
-        async {
+        let result = async {
             // If running the pre-execution interceptors failed, then we skip running the op and run the
             // final interceptors instead.
             if !ctx.is_failed() {
-                try_op(&mut ctx, cfg, &runtime_components, stop_point).await;
+                try_op(&mut ctx, cfg, &runtime_components, &metrics, stop_point).await;
             }
             finally_op(&mut ctx, cfg, &runtime_components).await;
             Ok(ctx)
         }
         .maybe_timeout(operation_timeout_config)
-        .await
+        .await;
+
+        metrics.record_duration(Phase::Call, call_start);
+        metrics.record_retry_backoff();
+        if !matches!(&result, Ok(ctx) if !ctx.is_failed()) {
+            metrics.record_error();
+        }
+        result
     }
     .instrument(debug_span!("invoke", service = %service_name, operation = %operation_name))
     .await
@@ -199,6 +215,7 @@ async fn try_op(
     ctx: &mut InterceptorContext,
     cfg: &mut ConfigBag,
     runtime_components: &RuntimeComponents,
+    metrics: &OperationMetrics,
     stop_point: StopPoint,
 ) {
     // Before serialization
@@ -216,10 +233,33 @@ async fn try_op(
             .expect("request serializer must be in the config bag")
             .clone();
         let input = ctx.take_input().expect("input set at this point");
-        let request = halt_on_err!([ctx] => request_serializer.serialize_input(input, cfg).map_err(OrchestratorError::other));
+        let serialization_start = metrics.start();
+        let request = request_serializer.serialize_input(input, cfg);
+        metrics.record_duration(Phase::Serialization, serialization_start);
+        let request = halt_on_err!([ctx] => request.map_err(OrchestratorError::other));
         ctx.set_request(request);
     }
 
+    // Serve read-only operations from the response cache if a fresh response is cached for their
+    // input. No attempts are made in that case.
+    if stop_point == StopPoint::None {
+        let request = ctx.request().expect("set above");
+        if let Some(response) = response_cache::lookup(request, runtime_components, cfg) {
+            ctx.enter_before_transmit_phase();
+            ctx.enter_transmit_phase();
+            let _ = ctx.take_request();
+            ctx.set_response(response);
+            ctx.enter_before_deserialization_phase();
+            ctx.enter_deserialization_phase();
+            let output_or_error = deserialize(ctx, cfg)
+                .instrument(debug_span!("deserialization"))
+                .await;
+            ctx.set_output_or_error(output_or_error);
+            ctx.enter_after_deserialization_phase();
+            return;
+        }
+    }
+
     // Load the request body into memory if configured to do so
     if let Some(&LoadedRequestBody::Requested) = cfg.load::<LoadedRequestBody>() {
         debug!("loading request body into memory");
@@ -262,6 +302,7 @@ async fn try_op(
                 "the retry strategy requested a delay before sending the initial request, but no 'async sleep' implementation was set"
             )));
             debug!("retry strategy has OKed initial request after a {delay:?} delay");
+            metrics.add_retry_backoff(delay);
             sleep_impl.sleep(delay).await;
         }
     }
@@ -286,18 +327,31 @@ async fn try_op(
             debug!("delaying for {delay:?}");
             sleep.await;
         }
+        // Wait for the request limiter to allow the attempt, so that retries count against its
+        // limits too. The permit is held until the attempt completes. Like backoff time, time
+        // spent queued isn't included in the attempt timeout.
+        let _request_permit = match runtime_components.request_limiter() {
+            Some(request_limiter) => Some(halt_on_err!([ctx] => request_limiter
+                .acquire_permit(runtime_components, cfg)
+                .await
+                .map_err(OrchestratorError::other))),
+            None => None,
+        };
         let attempt_timeout_config =
             MaybeTimeoutConfig::new(runtime_components, cfg, TimeoutKind::OperationAttempt);
         trace!(attempt_timeout_config = ?attempt_timeout_config);
+        metrics.record_attempt();
+        let attempt_start = metrics.start();
         let maybe_timeout = async {
             debug!("beginning attempt #{i}");
-            try_attempt(ctx, cfg, runtime_components, stop_point).await;
+            try_attempt(ctx, cfg, runtime_components, metrics, stop_point).await;
             finally_attempt(ctx, cfg, runtime_components).await;
             Result::<_, SdkError<Error, HttpResponse>>::Ok(())
         }
         .maybe_timeout(attempt_timeout_config)
         .await
         .map_err(|err| OrchestratorError::timeout(err.into_source().unwrap()));
+        metrics.record_duration(Phase::Attempt, attempt_start);
 
         // We continue when encountering a timeout error. The retry classifier will decide what to do with it.
         continue_on_err!([ctx] => maybe_timeout);
@@ -320,6 +374,7 @@ async fn try_op(
                 let sleep_impl = halt_on_err!([ctx] => runtime_components.sleep_impl().ok_or_else(|| OrchestratorError::other(
                     "the retry strategy requested a delay before sending the retry request, but no 'async sleep' implementation was set"
                 )));
+                metrics.add_retry_backoff(delay);
                 retry_delay = Some((delay, sleep_impl.sleep(delay)));
                 continue;
             }
@@ -332,18 +387,34 @@ async fn try_attempt(
     ctx: &mut InterceptorContext,
     cfg: &mut ConfigBag,
     runtime_components: &RuntimeComponents,
+    metrics: &OperationMetrics,
     stop_point: StopPoint,
 ) {
     run_interceptors!(halt_on_err: read_before_attempt(ctx, runtime_components, cfg));
 
-    halt_on_err!([ctx] => orchestrate_endpoint(ctx, runtime_components, cfg).await.map_err(OrchestratorError::other));
+    // Endpoint params that are derived from the identity require it to be resolved ahead of the endpoint
+    let requires_identity = halt_on_err!([ctx] => finalize_endpoint_params(None, runtime_components, cfg).map_err(OrchestratorError::other));
+    let resolved_identity = if requires_identity {
+        let resolved_identity = halt_on_err!([ctx] => resolve_identity(runtime_components, cfg, metrics).await.map_err(OrchestratorError::other));
+        if let Some(identity) = resolved_identity.identity() {
+            halt_on_err!([ctx] => finalize_endpoint_params(Some(identity), runtime_components, cfg).map_err(OrchestratorError::other));
+        }
+        Some(resolved_identity)
+    } else {
+        None
+    };
+
+    let resolve_endpoint_start = metrics.start();
+    let endpoint_result = orchestrate_endpoint(ctx, runtime_components, cfg).await;
+    metrics.record_duration(Phase::ResolveEndpoint, resolve_endpoint_start);
+    halt_on_err!([ctx] => endpoint_result.map_err(OrchestratorError::other));
 
     run_interceptors!(halt_on_err: {
         modify_before_signing(ctx, runtime_components, cfg);
         read_before_signing(ctx, runtime_components, cfg);
     });
 
-    halt_on_err!([ctx] => orchestrate_auth(ctx, runtime_components, cfg).await.map_err(OrchestratorError::other));
+    halt_on_err!([ctx] => orchestrate_auth(ctx, runtime_components, cfg, metrics, resolved_identity).await.map_err(OrchestratorError::other));
 
     run_interceptors!(halt_on_err: {
         read_after_signing(ctx, runtime_components, cfg);
@@ -375,7 +446,16 @@ async fn try_attempt(
             builder.build()
         };
         let connector = http_client.http_connector(&settings, runtime_components);
-        connector.call(request).await.map_err(OrchestratorError::connector)
+        if let Some(content_length) = request.body().content_length() {
+            metrics.record_bytes_sent(content_length);
+        }
+        let transmit_start = metrics.start();
+        let response = response_cache::send(request, runtime_components, cfg, |request| {
+            hedging::send(&connector, request, runtime_components, cfg)
+        })
+        .await;
+        metrics.record_duration(Phase::Transmit, transmit_start);
+        response.map_err(OrchestratorError::connector)
     });
     trace!(response = ?response, "received response from service");
     ctx.set_response(response);
@@ -388,30 +468,17 @@ async fn try_attempt(
     });
 
     ctx.enter_deserialization_phase();
-    let output_or_error = async {
-        let response = ctx.response_mut().expect("set during transmit");
-        let response_deserializer = cfg
-            .load::<SharedResponseDeserializer>()
-            .expect("a request deserializer must be in the config bag");
-        let maybe_deserialized = {
-            let _span = debug_span!("deserialize_streaming").entered();
-            response_deserializer.deserialize_streaming(response)
-        };
-        match maybe_deserialized {
-            Some(output_or_error) => output_or_error,
-            None => read_body(response)
-                .instrument(debug_span!("read_body"))
-                .await
-                .map_err(OrchestratorError::response)
-                .and_then(|_| {
-                    let _span = debug_span!("deserialize_nonstreaming").entered();
-                    log_response_body(response, cfg);
-                    response_deserializer.deserialize_nonstreaming(response)
-                }),
-        }
+    let deserialization_start = metrics.start();
+    let output_or_error = deserialize(ctx, cfg)
+        .instrument(debug_span!("deserialization"))
+        .await;
+    metrics.record_duration(Phase::Deserialization, deserialization_start);
+    if let Some(content_length) = ctx
+        .response()
+        .and_then(|response| response.body().content_length())
+    {
+        metrics.record_bytes_received(content_length);
     }
-    .instrument(debug_span!("deserialization"))
-    .await;
     trace!(output_or_error = ?output_or_error);
     ctx.set_output_or_error(output_or_error);
 
@@ -419,6 +486,32 @@ async fn try_attempt(
     run_interceptors!(halt_on_err: read_after_deserialization(ctx, runtime_components, cfg));
 }
 
+async fn deserialize(
+    ctx: &mut InterceptorContext,
+    cfg: &ConfigBag,
+) -> Result<Output, OrchestratorError<Error>> {
+    let response = ctx.response_mut().expect("set during transmit");
+    let response_deserializer = cfg
+        .load::<SharedResponseDeserializer>()
+        .expect("a request deserializer must be in the config bag");
+    let maybe_deserialized = {
+        let _span = debug_span!("deserialize_streaming").entered();
+        response_deserializer.deserialize_streaming(response)
+    };
+    match maybe_deserialized {
+        Some(output_or_error) => output_or_error,
+        None => read_body(response)
+            .instrument(debug_span!("read_body"))
+            .await
+            .map_err(OrchestratorError::response)
+            .and_then(|_| {
+                let _span = debug_span!("deserialize_nonstreaming").entered();
+                log_response_body(response, cfg);
+                response_deserializer.deserialize_nonstreaming(response)
+            }),
+    }
+}
+
 #[instrument(skip_all, level = "debug")]
 async fn finally_attempt(
     ctx: &mut InterceptorContext,
@@ -1199,6 +1292,139 @@ mod tests {
         assert!(context.response().is_none());
     }
 
+    #[tokio::test]
+    async fn test_metrics_are_recorded() {
+        use crate::client::telemetry::metric_names::*;
+        use crate::client::telemetry::test_util::InMemoryMeterProvider;
+        use aws_smithy_async::time::StaticTimeSource;
+        use aws_smithy_runtime_api::client::telemetry::AttributeValue;
+        use std::time::UNIX_EPOCH;
+
+        #[derive(Debug)]
+        struct MetricsRuntimePlugin {
+            builder: RuntimeComponentsBuilder,
+        }
+        impl RuntimePlugin for MetricsRuntimePlugin {
+            fn runtime_components(
+                &self,
+                _: &RuntimeComponentsBuilder,
+            ) -> Cow<'_, RuntimeComponentsBuilder> {
+                Cow::Borrowed(&self.builder)
+            }
+        }
+
+        let meter_provider = InMemoryMeterProvider::new();
+        let runtime_plugins = RuntimePlugins::new()
+            .with_operation_plugin(TestOperationRuntimePlugin::new())
+            .with_operation_plugin(NoAuthRuntimePlugin::new())
+            .with_operation_plugin(MetricsRuntimePlugin {
+                builder: RuntimeComponentsBuilder::new("metrics")
+                    .with_meter_provider(Some(meter_provider.clone()))
+                    .with_time_source(Some(StaticTimeSource::new(UNIX_EPOCH))),
+            });
+
+        invoke(
+            "TestService",
+            "TestOperation",
+            Input::doesnt_matter(),
+            &runtime_plugins,
+        )
+        .await
+        .expect("success");
+
+        assert_eq!(1, meter_provider.counter_total(CALL_ATTEMPTS));
+        assert_eq!(0, meter_provider.counter_total(CALL_ERRORS));
+        for histogram in [
+            CALL_DURATION,
+            ATTEMPT_DURATION,
+            SERIALIZATION_DURATION,
+            RESOLVE_ENDPOINT_DURATION,
+            RESOLVE_IDENTITY_DURATION,
+            SIGNING_DURATION,
+            TRANSMIT_DURATION,
+            DESERIALIZATION_DURATION,
+            RETRY_BACKOFF_DURATION,
+        ] {
+            assert_eq!(
+                vec![0.0],
+                meter_provider.histogram_values(histogram),
+                "{histogram}"
+            );
+        }
+        assert_eq!(1, meter_provider.recordings_for(BYTES_SENT).len());
+        assert_eq!(1, meter_provider.recordings_for(BYTES_RECEIVED).len());
+
+        let recording = &meter_provider.recordings_for(CALL_DURATION)[0];
+        assert_eq!("aws-smithy-runtime", recording.scope());
+        assert_eq!(Some("s"), recording.descriptor().units());
+        assert_eq!(
+            Some(&AttributeValue::from("TestService")),
+            recording.attributes().get("rpc.service")
+        );
+        assert_eq!(
+            Some(&AttributeValue::from("TestOperation")),
+            recording.attributes().get("rpc.method")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_operations_are_counted() {
+        use crate::client::telemetry::metric_names::*;
+        use crate::client::telemetry::test_util::InMemoryMeterProvider;
+        use aws_smithy_async::time::StaticTimeSource;
+        use aws_smithy_runtime_api::client::result::ConnectorError;
+        use std::time::UNIX_EPOCH;
+
+        #[derive(Debug)]
+        struct FailingConnector;
+        impl HttpConnector for FailingConnector {
+            fn call(&self, _request: HttpRequest) -> HttpConnectorFuture {
+                HttpConnectorFuture::ready(Err(ConnectorError::io("connection refused".into())))
+            }
+        }
+
+        #[derive(Debug)]
+        struct MetricsRuntimePlugin {
+            builder: RuntimeComponentsBuilder,
+        }
+        impl RuntimePlugin for MetricsRuntimePlugin {
+            fn runtime_components(
+                &self,
+                _: &RuntimeComponentsBuilder,
+            ) -> Cow<'_, RuntimeComponentsBuilder> {
+                Cow::Borrowed(&self.builder)
+            }
+        }
+
+        let meter_provider = InMemoryMeterProvider::new();
+        let runtime_plugins = RuntimePlugins::new()
+            .with_operation_plugin(TestOperationRuntimePlugin::new())
+            .with_operation_plugin(NoAuthRuntimePlugin::new())
+            .with_operation_plugin(MetricsRuntimePlugin {
+                builder: RuntimeComponentsBuilder::new("metrics")
+                    .with_http_client(Some(http_client_fn(|_, _| FailingConnector.into_shared())))
+                    .with_meter_provider(Some(meter_provider.clone()))
+                    .with_time_source(Some(StaticTimeSource::new(UNIX_EPOCH))),
+            });
+
+        invoke(
+            "TestService",
+            "TestOperation",
+            Input::doesnt_matter(),
+            &runtime_plugins,
+        )
+        .await
+        .expect_err("the connector fails");
+
+        assert_eq!(1, meter_provider.counter_total(CALL_ATTEMPTS));
+        assert_eq!(1, meter_provider.counter_total(CALL_ERRORS));
+        assert_eq!(1, meter_provider.histogram_values(TRANSMIT_DURATION).len());
+        assert!(meter_provider
+            .histogram_values(DESERIALIZATION_DURATION)
+            .is_empty());
+        assert!(meter_provider.recordings_for(BYTES_RECEIVED).is_empty());
+    }
+
     /// The "finally" interceptors should run upon error when the StopPoint is set to BeforeTransmit
     #[tokio::test]
     async fn test_stop_points_error_handling() {
@@ -4,12 +4,15 @@
  */
 
 use crate::client::auth::no_auth::NO_AUTH_SCHEME_ID;
+use crate::client::orchestrator::metrics::{OperationMetrics, Phase};
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::auth::{
     AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, AuthSchemeOptionResolverParams,
     ResolveAuthSchemeOptions,
 };
-use aws_smithy_runtime_api::client::identity::ResolveCachedIdentity;
+use aws_smithy_runtime_api::client::identity::{
+    Identity, ResolveCachedIdentity, SharedIdentityResolver,
+};
 use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 use aws_smithy_types::config_bag::ConfigBag;
@@ -39,16 +42,88 @@ impl fmt::Display for AuthOrchestrationError {
 
 impl StdError for AuthOrchestrationError {}
 
+/// Auth scheme options, and the identity for the first of them, resolved ahead of endpoint resolution
+#[derive(Debug)]
+pub(super) struct ResolvedIdentity {
+    options: Vec<AuthSchemeId>,
+    identity: Option<(AuthSchemeId, Identity)>,
+}
+
+impl ResolvedIdentity {
+    pub(super) fn identity(&self) -> Option<&Identity> {
+        self.identity.as_ref().map(|(_, identity)| identity)
+    }
+}
+
+/// Resolves the identity for the first auth scheme option that has an identity resolver.
+///
+/// The identity is `None` if none of the auth scheme options has an identity resolver, in which
+/// case [`orchestrate_auth`] will report the failure after endpoint resolution.
+pub(super) async fn resolve_identity(
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+    metrics: &OperationMetrics,
+) -> Result<ResolvedIdentity, BoxError> {
+    let params = cfg
+        .load::<AuthSchemeOptionResolverParams>()
+        .expect("auth scheme option resolver params must be set");
+    let option_resolver = runtime_components.auth_scheme_option_resolver();
+    let options = option_resolver
+        .resolve_auth_scheme_options(params)?
+        .into_owned();
+
+    for &scheme_id in &options {
+        if let Some(auth_scheme) = runtime_components.auth_scheme(scheme_id) {
+            if let Some(identity_resolver) = auth_scheme.identity_resolver(runtime_components) {
+                let identity =
+                    resolve_cached_identity(identity_resolver, runtime_components, cfg, metrics)
+                        .await?;
+                return Ok(ResolvedIdentity {
+                    options,
+                    identity: Some((scheme_id, identity)),
+                });
+            }
+        }
+    }
+    Ok(ResolvedIdentity {
+        options,
+        identity: None,
+    })
+}
+
+async fn resolve_cached_identity(
+    identity_resolver: SharedIdentityResolver,
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+    metrics: &OperationMetrics,
+) -> Result<Identity, BoxError> {
+    let resolve_identity_start = metrics.start();
+    let identity = runtime_components
+        .identity_cache()
+        .resolve_cached_identity(identity_resolver, runtime_components, cfg)
+        .await;
+    metrics.record_duration(Phase::ResolveIdentity, resolve_identity_start);
+    let identity = identity?;
+    trace!(identity = ?identity, "resolved identity");
+    Ok(identity)
+}
+
 pub(super) async fn orchestrate_auth(
     ctx: &mut InterceptorContext,
     runtime_components: &RuntimeComponents,
     cfg: &ConfigBag,
+    metrics: &OperationMetrics,
+    resolved_identity: Option<ResolvedIdentity>,
 ) -> Result<(), BoxError> {
     let params = cfg
         .load::<AuthSchemeOptionResolverParams>()
         .expect("auth scheme option resolver params must be set");
     let option_resolver = runtime_components.auth_scheme_option_resolver();
-    let options = option_resolver.resolve_auth_scheme_options(params)?;
+    // Reuse the options resolved before endpoint resolution, if any
+    let (options, mut resolved_identity) = match resolved_identity {
+        Some(resolved) => (Cow::Owned(resolved.options), resolved.identity),
+        None => (option_resolver.resolve_auth_scheme_options(params)?, None),
+    };
     let endpoint = cfg
         .load::<Endpoint>()
         .expect("endpoint added to config bag by endpoint orchestrator");
@@ -65,11 +140,10 @@ pub(super) async fn orchestrate_auth(
         if let Some(auth_scheme) = runtime_components.auth_scheme(scheme_id) {
             // Use the resolved auth scheme to resolve an identity
             if let Some(identity_resolver) = auth_scheme.identity_resolver(runtime_components) {
-                let identity_cache = runtime_components.identity_cache();
                 let signer = auth_scheme.signer();
                 trace!(
                     auth_scheme = ?auth_scheme,
-                    identity_cache = ?identity_cache,
+                    identity_cache = ?runtime_components.identity_cache(),
                     identity_resolver = ?identity_resolver,
                     signer = ?signer,
                     "resolved auth scheme, identity cache, identity resolver, and signing implementation"
@@ -79,21 +153,37 @@ pub(super) async fn orchestrate_auth(
                     Ok(auth_scheme_endpoint_config) => {
                         trace!(auth_scheme_endpoint_config = ?auth_scheme_endpoint_config, "extracted auth scheme endpoint config");
 
-                        let identity = identity_cache
-                            .resolve_cached_identity(identity_resolver, runtime_components, cfg)
-                            .await?;
-                        trace!(identity = ?identity, "resolved identity");
+                        // Reuse the identity resolved before endpoint resolution if it was resolved for
+                        // this scheme. Otherwise, the endpoint config ruled out its scheme.
+                        let identity = match resolved_identity.take() {
+                            Some((resolved_scheme_id, identity))
+                                if resolved_scheme_id == scheme_id =>
+                            {
+                                identity
+                            }
+                            _ => {
+                                resolve_cached_identity(
+                                    identity_resolver,
+                                    runtime_components,
+                                    cfg,
+                                    metrics,
+                                )
+                                .await?
+                            }
+                        };
 
                         trace!("signing request");
                         let request = ctx.request_mut().expect("set during serialization");
-                        signer.sign_http_request(
+                        let signing_start = metrics.start();
+                        let signing_result = signer.sign_http_request(
                             request,
                             &identity,
                             auth_scheme_endpoint_config,
                             runtime_components,
                             cfg,
-                        )?;
-                        return Ok(());
+                        );
+                        metrics.record_duration(Phase::Signing, signing_start);
+                        return signing_result;
                     }
                     Err(AuthOrchestrationError::NoMatchingAuthScheme) => {
                         continue;
@@ -237,9 +327,15 @@ mod tests {
         layer.store_put(Endpoint::builder().url("dontcare").build());
         let cfg = ConfigBag::of_layers(vec![layer]);
 
-        orchestrate_auth(&mut ctx, &runtime_components, &cfg)
-            .await
-            .expect("success");
+        orchestrate_auth(
+            &mut ctx,
+            &runtime_components,
+            &cfg,
+            &OperationMetrics::new(&runtime_components, "test", "test"),
+            None,
+        )
+        .await
+        .expect("success");
 
         assert_eq!(
             "success!",
@@ -293,9 +389,15 @@ mod tests {
         // First, test the presence of a basic auth login and absence of a bearer token
         let (runtime_components, cfg) =
             config_with_identity(HTTP_BASIC_AUTH_SCHEME_ID, Login::new("a", "b", None));
-        orchestrate_auth(&mut ctx, &runtime_components, &cfg)
-            .await
-            .expect("success");
+        orchestrate_auth(
+            &mut ctx,
+            &runtime_components,
+            &cfg,
+            &OperationMetrics::new(&runtime_components, "test", "test"),
+            None,
+        )
+        .await
+        .expect("success");
         assert_eq!(
             // "YTpi" == "a:b" in base64
             "Basic YTpi",
@@ -314,9 +416,100 @@ mod tests {
         ctx.set_request(HttpRequest::empty());
         let _ = ctx.take_input();
         ctx.enter_before_transmit_phase();
-        orchestrate_auth(&mut ctx, &runtime_components, &cfg)
+        orchestrate_auth(
+            &mut ctx,
+            &runtime_components,
+            &cfg,
+            &OperationMetrics::new(&runtime_components, "test", "test"),
+            None,
+        )
+        .await
+        .expect("success");
+        assert_eq!(
+            "Bearer t",
+            ctx.request()
+                .expect("request is set")
+                .headers()
+                .get("Authorization")
+                .unwrap()
+        );
+    }
+
+    #[cfg(feature = "http-auth")]
+    #[tokio::test]
+    async fn identity_resolved_ahead_of_the_endpoint_is_only_used_for_its_scheme() {
+        use crate::client::auth::http::{BasicAuthScheme, BearerAuthScheme};
+        use aws_smithy_runtime_api::client::auth::http::{
+            HTTP_BASIC_AUTH_SCHEME_ID, HTTP_BEARER_AUTH_SCHEME_ID,
+        };
+        use aws_smithy_runtime_api::client::identity::http::{Login, Token};
+
+        let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+        ctx.enter_serialization_phase();
+        ctx.set_request(HttpRequest::empty());
+        let _ = ctx.take_input();
+        ctx.enter_before_transmit_phase();
+
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .with_auth_scheme(SharedAuthScheme::new(BasicAuthScheme::new()))
+            .with_auth_scheme(SharedAuthScheme::new(BearerAuthScheme::new()))
+            .with_auth_scheme_option_resolver(Some(SharedAuthSchemeOptionResolver::new(
+                StaticAuthSchemeOptionResolver::new(vec![
+                    HTTP_BASIC_AUTH_SCHEME_ID,
+                    HTTP_BEARER_AUTH_SCHEME_ID,
+                ]),
+            )))
+            .with_identity_resolver(
+                HTTP_BASIC_AUTH_SCHEME_ID,
+                SharedIdentityResolver::new(Login::new("a", "b", None)),
+            )
+            .with_identity_resolver(
+                HTTP_BEARER_AUTH_SCHEME_ID,
+                SharedIdentityResolver::new(Token::new("t", None)),
+            )
+            .build()
+            .unwrap();
+
+        let mut layer = Layer::new("test");
+        layer.store_put(AuthSchemeOptionResolverParams::new("doesntmatter"));
+        // The endpoint only supports bearer auth
+        layer.store_put(
+            Endpoint::builder()
+                .url("dontcare")
+                .property(
+                    "authSchemes",
+                    vec![Document::Object({
+                        let mut out = HashMap::new();
+                        out.insert(
+                            "name".to_string(),
+                            HTTP_BEARER_AUTH_SCHEME_ID.as_str().to_string().into(),
+                        );
+                        out
+                    })],
+                )
+                .build(),
+        );
+        let cfg = ConfigBag::of_layers(vec![layer]);
+        let metrics = OperationMetrics::new(&runtime_components, "test", "test");
+
+        let resolved_identity = resolve_identity(&runtime_components, &cfg, &metrics)
             .await
             .expect("success");
+        assert!(resolved_identity
+            .identity()
+            .expect("identity is resolved")
+            .data::<Login>()
+            .is_some());
+
+        orchestrate_auth(
+            &mut ctx,
+            &runtime_components,
+            &cfg,
+            &metrics,
+            Some(resolved_identity),
+        )
+        .await
+        .expect("success");
         assert_eq!(
             "Bearer t",
             ctx.request()
@@ -469,9 +662,15 @@ mod tests {
         layer.store_put(AuthSchemeOptionResolverParams::new("doesntmatter"));
         let config_bag = ConfigBag::of_layers(vec![layer]);
 
-        orchestrate_auth(&mut ctx, &runtime_components, &config_bag)
-            .await
-            .expect("success");
+        orchestrate_auth(
+            &mut ctx,
+            &runtime_components,
+            &config_bag,
+            &OperationMetrics::new(&runtime_components, "test", "test"),
+            None,
+        )
+        .await
+        .expect("success");
         assert_eq!(
             "result: cached (pass)",
             ctx.request()
@@ -9,6 +9,7 @@ use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::endpoint::{
     EndpointFuture, EndpointResolverParams, ResolveEndpoint,
 };
+use aws_smithy_runtime_api::client::identity::Identity;
 use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
 use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
@@ -69,6 +70,35 @@ impl From<StaticUriEndpointResolverParams> for EndpointResolverParams {
     }
 }
 
+/// Finalizes the endpoint resolver params with the endpoint resolver, making `identity` available to it if given.
+///
+/// Returns true if the params can't be finalized until the identity for the request is known.
+pub(super) fn finalize_endpoint_params(
+    identity: Option<&Identity>,
+    runtime_components: &RuntimeComponents,
+    cfg: &mut ConfigBag,
+) -> Result<bool, BoxError> {
+    let endpoint_resolver = runtime_components.endpoint_resolver();
+    match cfg.get_mut_from_interceptor_state::<EndpointResolverParams>() {
+        Some(params) => {
+            if let Some(identity) = identity {
+                params.set_property(identity.clone());
+            }
+            endpoint_resolver.finalize_params(params)?;
+            Ok(identity.is_none() && params.requires_identity())
+        }
+        None => {
+            // Params that were stored in a shared layer can't be mutated, and are used as-is
+            if cfg.load::<EndpointResolverParams>().is_some() {
+                tracing::debug!(
+                    "endpoint resolver params weren't set in the interceptor state, so they won't be finalized"
+                );
+            }
+            Ok(false)
+        }
+    }
+}
+
 pub(super) async fn orchestrate_endpoint(
     ctx: &mut InterceptorContext,
     runtime_components: &RuntimeComponents,
@@ -76,6 +106,7 @@ pub(super) async fn orchestrate_endpoint(
 ) -> Result<(), BoxError> {
     trace!("orchestrating endpoint resolution");
 
+    let endpoint_resolver = runtime_components.endpoint_resolver();
     let params = cfg
         .load::<EndpointResolverParams>()
         .expect("endpoint resolver params must be set");
@@ -83,10 +114,7 @@ pub(super) async fn orchestrate_endpoint(
     tracing::debug!(endpoint_params = ?params, endpoint_prefix = ?endpoint_prefix, "resolving endpoint");
     let request = ctx.request_mut().expect("set during serialization");
 
-    let endpoint = runtime_components
-        .endpoint_resolver()
-        .resolve_endpoint(params)
-        .await?;
+    let endpoint = endpoint_resolver.resolve_endpoint(params).await?;
     tracing::debug!("will use endpoint {:?}", endpoint);
     apply_endpoint(request, &endpoint, endpoint_prefix)?;
 
@@ -0,0 +1,157 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::telemetry::metric_names as names;
+use crate::client::telemetry::{NoopMeterProvider, METER_SCOPE};
+use aws_smithy_async::time::{SharedTimeSource, SystemTimeSource};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::client::telemetry::{
+    Attributes, Counter, Histogram, InstrumentDescriptor, Meter, ProvideMeter, SharedCounter,
+    SharedHistogram,
+};
+use aws_smithy_runtime_api::shared::IntoShared;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A timed phase of an operation.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Phase {
+    Call,
+    Attempt,
+    Serialization,
+    ResolveEndpoint,
+    ResolveIdentity,
+    Signing,
+    Transmit,
+    Deserialization,
+}
+
+/// Records the metrics for a single operation invocation.
+///
+/// Durations are only measured when a meter provider is configured. Otherwise, all metrics
+/// are discarded without consulting the time source.
+#[derive(Debug)]
+pub(super) struct OperationMetrics {
+    time_source: Option<SharedTimeSource>,
+    attributes: Attributes,
+    retry_backoff_nanos: AtomicU64,
+
+    call_duration: SharedHistogram,
+    call_attempts: SharedCounter,
+    call_errors: SharedCounter,
+    attempt_duration: SharedHistogram,
+    serialization_duration: SharedHistogram,
+    resolve_endpoint_duration: SharedHistogram,
+    resolve_identity_duration: SharedHistogram,
+    signing_duration: SharedHistogram,
+    transmit_duration: SharedHistogram,
+    deserialization_duration: SharedHistogram,
+    retry_backoff_duration: SharedHistogram,
+    bytes_sent: SharedCounter,
+    bytes_received: SharedCounter,
+}
+
+impl OperationMetrics {
+    pub(super) fn new(
+        runtime_components: &RuntimeComponents,
+        service_name: &str,
+        operation_name: &str,
+    ) -> Self {
+        let meter_provider = runtime_components.meter_provider();
+        let time_source = meter_provider.as_ref().map(|_| {
+            runtime_components
+                .time_source()
+                .unwrap_or_else(|| SystemTimeSource::new().into_shared())
+        });
+        let meter = meter_provider
+            .unwrap_or_else(|| NoopMeterProvider::new().into_shared())
+            .meter(METER_SCOPE);
+        let seconds =
+            |name| meter.create_histogram(InstrumentDescriptor::new(name).with_units("s"));
+        let bytes = |name| meter.create_counter(InstrumentDescriptor::new(name).with_units("By"));
+        Self {
+            time_source,
+            attributes: Attributes::new()
+                .with("rpc.service", service_name.to_string())
+                .with("rpc.method", operation_name.to_string()),
+            retry_backoff_nanos: AtomicU64::new(0),
+
+            call_duration: seconds(names::CALL_DURATION),
+            call_attempts: meter.create_counter(
+                InstrumentDescriptor::new(names::CALL_ATTEMPTS)
+                    .with_description("The number of attempts made for an operation"),
+            ),
+            call_errors: meter.create_counter(
+                InstrumentDescriptor::new(names::CALL_ERRORS)
+                    .with_description("The number of operations that failed"),
+            ),
+            attempt_duration: seconds(names::ATTEMPT_DURATION),
+            serialization_duration: seconds(names::SERIALIZATION_DURATION),
+            resolve_endpoint_duration: seconds(names::RESOLVE_ENDPOINT_DURATION),
+            resolve_identity_duration: seconds(names::RESOLVE_IDENTITY_DURATION),
+            signing_duration: seconds(names::SIGNING_DURATION),
+            transmit_duration: seconds(names::TRANSMIT_DURATION),
+            deserialization_duration: seconds(names::DESERIALIZATION_DURATION),
+            retry_backoff_duration: seconds(names::RETRY_BACKOFF_DURATION),
+            bytes_sent: bytes(names::BYTES_SENT),
+            bytes_received: bytes(names::BYTES_RECEIVED),
+        }
+    }
+
+    /// Returns the current time, to later be given to [`OperationMetrics::record_duration`].
+    pub(super) fn start(&self) -> SystemTime {
+        self.time_source
+            .as_ref()
+            .map(|time_source| time_source.now())
+            .unwrap_or(UNIX_EPOCH)
+    }
+
+    /// Records the time elapsed since `start` for the given `phase`.
+    pub(super) fn record_duration(&self, phase: Phase, start: SystemTime) {
+        let elapsed = match &self.time_source {
+            Some(time_source) => time_source.now().duration_since(start).unwrap_or_default(),
+            None => return,
+        };
+        let histogram = match phase {
+            Phase::Call => &self.call_duration,
+            Phase::Attempt => &self.attempt_duration,
+            Phase::Serialization => &self.serialization_duration,
+            Phase::ResolveEndpoint => &self.resolve_endpoint_duration,
+            Phase::ResolveIdentity => &self.resolve_identity_duration,
+            Phase::Signing => &self.signing_duration,
+            Phase::Transmit => &self.transmit_duration,
+            Phase::Deserialization => &self.deserialization_duration,
+        };
+        histogram.record(elapsed.as_secs_f64(), &self.attributes);
+    }
+
+    pub(super) fn record_attempt(&self) {
+        self.call_attempts.add(1, &self.attributes);
+    }
+
+    pub(super) fn record_error(&self) {
+        self.call_errors.add(1, &self.attributes);
+    }
+
+    pub(super) fn add_retry_backoff(&self, delay: Duration) {
+        let nanos = u64::try_from(delay.as_nanos()).unwrap_or(u64::MAX);
+        self.retry_backoff_nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    /// Records the total time spent backing off between attempts.
+    pub(super) fn record_retry_backoff(&self) {
+        let total = Duration::from_nanos(self.retry_backoff_nanos.load(Ordering::Relaxed));
+        self.retry_backoff_duration
+            .record(total.as_secs_f64(), &self.attributes);
+    }
+
+    pub(super) fn record_bytes_sent(&self, bytes: u64) {
+        self.bytes_sent.add(bytes, &self.attributes);
+    }
+
+    pub(super) fn record_bytes_received(&self, bytes: u64) {
+        self.bytes_received.add(bytes, &self.attributes);
+    }
+}
@@ -0,0 +1,683 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Proactive limits on the rate and concurrency of requests.
+//!
+//! See [`RequestLimiter`] for more information.
+
+use crate::client::retries::RetryPartition;
+use crate::static_partition_map::StaticPartitionMap;
+use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::request_limiter::{
+    LimitRequests, RequestPermit, RequestPermitFuture,
+};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+static PARTITIONED_LIMITS: StaticPartitionMap<LimitsPartition, Limits> = StaticPartitionMap::new();
+
+/// Request limiters only share limits with request limiters that were configured with the same limits.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct LimitsPartition {
+    retry_partition: RetryPartition,
+    // The bits of the `f64` value, since floats aren't `Eq` or `Hash`
+    requests_per_second: Option<u64>,
+    max_concurrent_requests: Option<usize>,
+}
+
+/// What a [`RequestLimiter`]'s limits are shared between.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RequestLimitScope {
+    /// Limits are shared by every request that the client sends.
+    #[default]
+    Client,
+    /// Limits are shared by every request sent to the same [`RetryPartition`], even across
+    /// clients.
+    ///
+    /// Only request limiters configured with the same limits share them. Request limiters with
+    /// different limits keep separate limits even when they send to the same retry partition.
+    RetryPartition,
+}
+
+/// Builder for [`RequestLimiter`].
+#[derive(Clone, Debug, Default)]
+pub struct RequestLimiterBuilder {
+    requests_per_second: Option<f64>,
+    max_concurrent_requests: Option<usize>,
+    max_queue_time: Option<Duration>,
+    scope: Option<RequestLimitScope>,
+}
+
+impl RequestLimiterBuilder {
+    /// Sets the maximum number of requests that can be sent per second.
+    ///
+    /// Up to a second's worth of requests can be sent in a burst.
+    pub fn requests_per_second(mut self, requests_per_second: f64) -> Self {
+        self.set_requests_per_second(Some(requests_per_second));
+        self
+    }
+
+    /// Sets the maximum number of requests that can be sent per second.
+    ///
+    /// Up to a second's worth of requests can be sent in a burst.
+    pub fn set_requests_per_second(&mut self, requests_per_second: Option<f64>) -> &mut Self {
+        self.requests_per_second = requests_per_second;
+        self
+    }
+
+    /// Sets the maximum number of requests that can be in flight at the same time.
+    ///
+    /// This must be at least one.
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.set_max_concurrent_requests(Some(max_concurrent_requests));
+        self
+    }
+
+    /// Sets the maximum number of requests that can be in flight at the same time.
+    pub fn set_max_concurrent_requests(
+        &mut self,
+        max_concurrent_requests: Option<usize>,
+    ) -> &mut Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Sets the longest time that a request can be queued for before it is rejected.
+    ///
+    /// By default, requests are queued for as long as it takes. A zero duration rejects requests
+    /// as soon as a limit is reached rather than queueing them.
+    pub fn max_queue_time(mut self, max_queue_time: Duration) -> Self {
+        self.set_max_queue_time(Some(max_queue_time));
+        self
+    }
+
+    /// Sets the longest time that a request can be queued for before it is rejected.
+    ///
+    /// By default, requests are queued for as long as it takes. A zero duration rejects requests
+    /// as soon as a limit is reached rather than queueing them.
+    pub fn set_max_queue_time(&mut self, max_queue_time: Option<Duration>) -> &mut Self {
+        self.max_queue_time = max_queue_time;
+        self
+    }
+
+    /// Sets what the limits are shared between.
+    ///
+    /// Defaults to [`RequestLimitScope::Client`].
+    pub fn scope(mut self, scope: RequestLimitScope) -> Self {
+        self.set_scope(Some(scope));
+        self
+    }
+
+    /// Sets what the limits are shared between.
+    ///
+    /// Defaults to [`RequestLimitScope::Client`].
+    pub fn set_scope(&mut self, scope: Option<RequestLimitScope>) -> &mut Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Builds the [`RequestLimiter`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if requests per second isn't a positive number, or if the maximum number of
+    /// concurrent requests is zero.
+    pub fn build(self) -> RequestLimiter {
+        if let Some(requests_per_second) = self.requests_per_second {
+            assert!(
+                requests_per_second > 0.0 && requests_per_second.is_finite(),
+                "requests per second must be a positive number"
+            );
+        }
+        assert_ne!(
+            Some(0),
+            self.max_concurrent_requests,
+            "the maximum number of concurrent requests must be at least one"
+        );
+        RequestLimiter {
+            requests_per_second: self.requests_per_second,
+            max_concurrent_requests: self.max_concurrent_requests,
+            max_queue_time: self.max_queue_time,
+            scope: self.scope.unwrap_or_default(),
+            client_limits: Limits::new(self.requests_per_second, self.max_concurrent_requests),
+        }
+    }
+}
+
+/// Request limiter that caps the rate and concurrency of requests.
+///
+/// Unlike the client rate limiter used by adaptive retries, this limits requests up front,
+/// whether or not the service has started throttling them, so that a client can be kept under
+/// a service quota. Requests over a limit are queued until they can be sent, and are rejected
+/// with a [`RequestLimitExceededError`] if they would be queued for longer than the configured
+/// maximum queue time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_smithy_runtime::client::request_limiter::RequestLimiter;
+///
+/// // Send at most 50 requests per second, with no more than 10 in flight at a time
+/// let request_limiter = RequestLimiter::builder()
+///     .requests_per_second(50.0)
+///     .max_concurrent_requests(10)
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct RequestLimiter {
+    requests_per_second: Option<f64>,
+    max_concurrent_requests: Option<usize>,
+    max_queue_time: Option<Duration>,
+    scope: RequestLimitScope,
+    client_limits: Limits,
+}
+
+impl RequestLimiter {
+    /// Returns a builder for a `RequestLimiter`.
+    pub fn builder() -> RequestLimiterBuilder {
+        RequestLimiterBuilder::default()
+    }
+
+    fn limits(&self, cfg: &ConfigBag) -> Limits {
+        match (self.scope, cfg.load::<RetryPartition>()) {
+            (RequestLimitScope::RetryPartition, Some(retry_partition)) => {
+                let partition = LimitsPartition {
+                    retry_partition: retry_partition.clone(),
+                    requests_per_second: self.requests_per_second.map(f64::to_bits),
+                    max_concurrent_requests: self.max_concurrent_requests,
+                };
+                PARTITIONED_LIMITS.get_or_init(partition, || {
+                    Limits::new(self.requests_per_second, self.max_concurrent_requests)
+                })
+            }
+            _ => self.client_limits.clone(),
+        }
+    }
+
+    async fn acquire(
+        &self,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Result<RequestPermit, BoxError> {
+        let limits = self.limits(cfg);
+        let time_source = runtime_components.time_source().unwrap_or_default();
+        let start = time_source.now();
+
+        let concurrency_permit = match &limits.concurrency {
+            Some(semaphore) => Some(
+                self.acquire_concurrency_permit(semaphore, runtime_components)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        if let Some(rate) = &limits.rate {
+            let now = time_source.now();
+            let remaining_queue_time = self
+                .max_queue_time
+                .map(|max| max.saturating_sub(now.duration_since(start).unwrap_or_default()));
+            let delay = rate
+                .lock()
+                .unwrap()
+                .reserve(now, remaining_queue_time)
+                .map_err(|retry_after| RequestLimitExceededError {
+                    kind: LimitKind::Rate { retry_after },
+                })?;
+            if !delay.is_zero() {
+                // If the request is cancelled or fails while it's queued, its token is given back
+                let mut reservation = Reservation { rate, refund: true };
+                let sleep_impl = runtime_components.sleep_impl().ok_or(
+                    "the request limiter needs to delay a request, but no 'async sleep' implementation was set",
+                )?;
+                sleep_impl.sleep(delay).await;
+                reservation.refund = false;
+            }
+        }
+
+        let queue_time = time_source.now().duration_since(start).unwrap_or_default();
+        if !queue_time.is_zero() {
+            debug!(queue_time = ?queue_time, "request was queued by the request limiter");
+        }
+        Ok(match concurrency_permit {
+            Some(permit) => RequestPermit::new(permit),
+            None => RequestPermit::empty(),
+        })
+    }
+
+    async fn acquire_concurrency_permit(
+        &self,
+        semaphore: &Arc<Semaphore>,
+        runtime_components: &RuntimeComponents,
+    ) -> Result<OwnedSemaphorePermit, BoxError> {
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Ok(permit);
+        }
+        let exceeded = || RequestLimitExceededError {
+            kind: LimitKind::Concurrency,
+        };
+        let mut acquire = Box::pin(semaphore.clone().acquire_owned());
+        let mut timeout = match self.max_queue_time {
+            Some(max_queue_time) if max_queue_time.is_zero() => return Err(exceeded().into()),
+            Some(max_queue_time) => Some(
+                runtime_components
+                    .sleep_impl()
+                    .ok_or("the request limiter needs to time out queued requests, but no 'async sleep' implementation was set")?
+                    .sleep(max_queue_time),
+            ),
+            None => None,
+        };
+        poll_fn(|cx| {
+            if let Poll::Ready(permit) = acquire.as_mut().poll(cx) {
+                return Poll::Ready(Ok(permit.expect("the semaphore is never closed")));
+            }
+            if let Some(timeout) = timeout.as_mut() {
+                if Pin::new(timeout).poll(cx).is_ready() {
+                    return Poll::Ready(Err(exceeded().into()));
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl LimitRequests for RequestLimiter {
+    fn acquire_permit<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        cfg: &'a ConfigBag,
+    ) -> RequestPermitFuture<'a> {
+        RequestPermitFuture::new(self.acquire(runtime_components, cfg))
+    }
+}
+
+/// The state of the limits that a request limiter enforces.
+#[derive(Clone, Debug)]
+struct Limits {
+    rate: Option<Arc<Mutex<RateLimit>>>,
+    concurrency: Option<Arc<Semaphore>>,
+}
+
+impl Limits {
+    fn new(requests_per_second: Option<f64>, max_concurrent_requests: Option<usize>) -> Self {
+        Self {
+            rate: requests_per_second.map(|rate| Arc::new(Mutex::new(RateLimit::new(rate)))),
+            concurrency: max_concurrent_requests.map(|max| Arc::new(Semaphore::new(max))),
+        }
+    }
+}
+
+/// Token bucket that refills at a fixed number of tokens per second.
+#[derive(Debug)]
+struct RateLimit {
+    rate: f64,
+    capacity: f64,
+    /// Tokens available to requests. This goes negative when requests are queued.
+    tokens: f64,
+    last_refill: Option<SystemTime>,
+}
+
+impl RateLimit {
+    fn new(rate: f64) -> Self {
+        let capacity = rate.max(1.0);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: None,
+        }
+    }
+
+    /// Reserves a token, returning how long to wait before the request can be sent.
+    ///
+    /// If the wait would be longer than `max_wait`, nothing is reserved and the wait is
+    /// returned as an error.
+    fn reserve(
+        &mut self,
+        now: SystemTime,
+        max_wait: Option<Duration>,
+    ) -> Result<Duration, Duration> {
+        if let Some(last_refill) = self.last_refill {
+            let elapsed = now.duration_since(last_refill).unwrap_or_default();
+            self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        }
+        self.last_refill = Some(now);
+
+        let wait = if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.rate)
+        };
+        if max_wait.is_some_and(|max_wait| wait > max_wait) {
+            return Err(wait);
+        }
+        self.tokens -= 1.0;
+        Ok(wait)
+    }
+
+    /// Gives back a token reserved by a request that was never sent.
+    fn refund(&mut self) {
+        self.tokens = (self.tokens + 1.0).min(self.capacity);
+    }
+}
+
+/// A token reserved by a queued request, which is refunded if the request is dropped while queued.
+struct Reservation<'a> {
+    rate: &'a Mutex<RateLimit>,
+    refund: bool,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if self.refund {
+            self.rate.lock().unwrap().refund();
+        }
+    }
+}
+
+/// Error returned when a request is rejected by a [`RequestLimiter`].
+#[derive(Debug)]
+pub struct RequestLimitExceededError {
+    kind: LimitKind,
+}
+
+#[derive(Debug)]
+enum LimitKind {
+    Rate { retry_after: Duration },
+    Concurrency,
+}
+
+impl RequestLimitExceededError {
+    /// Returns how long to wait before the request would be allowed by the rate limit, if the
+    /// request was rejected because of the rate limit.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self.kind {
+            LimitKind::Rate { retry_after } => Some(retry_after),
+            LimitKind::Concurrency => None,
+        }
+    }
+}
+
+impl fmt::Display for RequestLimitExceededError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LimitKind::Rate { retry_after } => write!(
+                f,
+                "the request was rejected since it would have exceeded the client's request rate limit (retry after {retry_after:?})"
+            ),
+            LimitKind::Concurrency => write!(
+                f,
+                "the request was rejected since the client's maximum number of concurrent requests are in flight"
+            ),
+        }
+    }
+}
+
+impl StdError for RequestLimitExceededError {}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_async::test_util::{instant_time_and_sleep, InstantSleep, ManualTimeSource};
+    use aws_smithy_async::time::SystemTimeSource;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::config_bag::Layer;
+
+    fn runtime_components() -> (RuntimeComponents, InstantSleep) {
+        let (time_source, sleep_impl) = instant_time_and_sleep(SystemTime::UNIX_EPOCH);
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time_source))
+            .with_sleep_impl(Some(sleep_impl.clone()))
+            .build()
+            .unwrap();
+        (runtime_components, sleep_impl)
+    }
+
+    fn cfg(retry_partition: &'static str) -> ConfigBag {
+        let mut layer = Layer::new("test");
+        layer.store_put(RetryPartition::new(retry_partition));
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_rate_are_queued() {
+        let (runtime_components, sleep_impl) = runtime_components();
+        let cfg = cfg("rate");
+        let limiter = RequestLimiter::builder().requests_per_second(2.0).build();
+
+        for _ in 0..4 {
+            limiter
+                .acquire_permit(&runtime_components, &cfg)
+                .await
+                .unwrap();
+        }
+        // The first two requests use up the burst, and the rest are spaced out by half a second
+        assert_eq!(
+            vec![Duration::from_millis(500), Duration::from_millis(500)],
+            sleep_impl.logs()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_requests_give_back_their_rate_token() {
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(ManualTimeSource::new(SystemTime::UNIX_EPOCH)))
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap();
+        let cfg = cfg("cancelled");
+        let limiter = RequestLimiter::builder()
+            .requests_per_second(1.0)
+            .max_queue_time(Duration::from_millis(1500))
+            .build();
+
+        limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap();
+        let mut queued = Box::pin(limiter.acquire_permit(&runtime_components, &cfg));
+        assert!(futures_util::poll!(queued.as_mut()).is_pending());
+        drop(queued);
+
+        // Without the cancelled request's token, this would have to wait for two seconds
+        limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "the maximum number of concurrent requests must be at least one")]
+    fn zero_concurrent_requests_are_rejected() {
+        RequestLimiter::builder().max_concurrent_requests(0).build();
+    }
+
+    #[tokio::test]
+    async fn requests_queued_for_too_long_are_rejected() {
+        let (runtime_components, sleep_impl) = runtime_components();
+        let cfg = cfg("reject");
+        let limiter = RequestLimiter::builder()
+            .requests_per_second(1.0)
+            .max_queue_time(Duration::from_millis(100))
+            .build();
+
+        limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap();
+        let err = limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap_err();
+        let err = err.downcast_ref::<RequestLimitExceededError>().unwrap();
+        assert_eq!(Some(Duration::from_secs(1)), err.retry_after());
+        assert!(sleep_impl.logs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_are_capped() {
+        let (runtime_components, _sleep_impl) = runtime_components();
+        let cfg = cfg("concurrency");
+        let limiter = RequestLimiter::builder()
+            .max_concurrent_requests(1)
+            .max_queue_time(Duration::ZERO)
+            .build();
+
+        let permit = limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap();
+        let err = limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap_err();
+        let err = err.downcast_ref::<RequestLimitExceededError>().unwrap();
+        assert_eq!(None, err.retry_after());
+
+        // Completing the first request frees up its slot
+        drop(permit);
+        limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn queued_requests_wait_for_a_concurrency_slot() {
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .with_time_source(Some(SystemTimeSource::new()))
+            .build()
+            .unwrap();
+        let cfg = cfg("concurrency-queue");
+        let limiter = RequestLimiter::builder().max_concurrent_requests(1).build();
+
+        let permit = limiter
+            .acquire_permit(&runtime_components, &cfg)
+            .await
+            .unwrap();
+        let mut queued = Box::pin(limiter.acquire_permit(&runtime_components, &cfg));
+        assert!(futures_util::poll!(queued.as_mut()).is_pending());
+        drop(permit);
+        queued.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn limits_can_be_shared_by_retry_partition() {
+        let (runtime_components, _sleep_impl) = runtime_components();
+        let limiter = || {
+            RequestLimiter::builder()
+                .max_concurrent_requests(1)
+                .max_queue_time(Duration::ZERO)
+                .scope(RequestLimitScope::RetryPartition)
+                .build()
+        };
+        let (first, second) = (limiter(), limiter());
+
+        let _permit = first
+            .acquire_permit(&runtime_components, &cfg("shared"))
+            .await
+            .unwrap();
+        // Another client sending to the same partition is limited too
+        second
+            .acquire_permit(&runtime_components, &cfg("shared"))
+            .await
+            .unwrap_err();
+        // But other partitions aren't
+        second
+            .acquire_permit(&runtime_components, &cfg("not-shared"))
+            .await
+            .unwrap();
+        // And neither are clients with different limits
+        RequestLimiter::builder()
+            .max_concurrent_requests(2)
+            .max_queue_time(Duration::ZERO)
+            .scope(RequestLimitScope::RetryPartition)
+            .build()
+            .acquire_permit(&runtime_components, &cfg("shared"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn retries_are_rate_limited() {
+        use crate::client::http::test_util::{ReplayEvent, StaticReplayClient};
+        use crate::client::orchestrator::operation::Operation;
+        use crate::client::retries::classifiers::HttpStatusCodeClassifier;
+        use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, OrchestratorError};
+        use aws_smithy_runtime_api::client::result::ConnectorError;
+        use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+        use aws_smithy_types::body::SdkBody;
+        use aws_smithy_types::retry::RetryConfig;
+        use aws_smithy_types::timeout::TimeoutConfig;
+        use std::convert::Infallible;
+
+        let event = |status| {
+            ReplayEvent::new(
+                http::Request::builder()
+                    .uri("http://localhost:1234/")
+                    .body(SdkBody::empty())
+                    .unwrap(),
+                http::Response::builder()
+                    .status(status)
+                    .body(SdkBody::empty())
+                    .unwrap(),
+            )
+        };
+        let http_client = StaticReplayClient::new(vec![event(503), event(503), event(200)]);
+        let (time_source, sleep_impl) = instant_time_and_sleep(SystemTime::UNIX_EPOCH);
+        let limiter = RequestLimiter::builder().requests_per_second(1.0).build();
+        let operation = Operation::builder()
+            .service_name("test")
+            .operation_name("test")
+            .http_client(http_client.clone())
+            .endpoint_url("http://localhost:1234")
+            .no_auth()
+            .standard_retry(&RetryConfig::standard().with_initial_backoff(Duration::ZERO))
+            .retry_classifier(HttpStatusCodeClassifier::default())
+            .timeout_config(TimeoutConfig::disabled())
+            .sleep_impl(sleep_impl.clone())
+            .time_source(time_source)
+            .runtime_plugin(StaticRuntimePlugin::new().with_runtime_components(
+                RuntimeComponentsBuilder::new("test").with_request_limiter(Some(limiter)),
+            ))
+            .serializer(|_: ()| Ok(HttpRequest::new(SdkBody::empty())))
+            .deserializer::<_, Infallible>(|response| {
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    Err(OrchestratorError::connector(ConnectorError::io(
+                        "test".into(),
+                    )))
+                }
+            })
+            .build();
+
+        operation.invoke(()).await.expect("success");
+        http_client.assert_requests_match(&[]);
+        // Each retry waits for a rate token, on top of the retry backoff
+        let limiter_delays: Vec<_> = sleep_impl
+            .logs()
+            .into_iter()
+            .filter(|delay| !delay.is_zero())
+            .collect();
+        assert_eq!(
+            vec![Duration::from_secs(1), Duration::from_secs(1)],
+            limiter_delays
+        );
+    }
+}
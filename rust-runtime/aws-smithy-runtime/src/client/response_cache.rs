@@ -0,0 +1,757 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Opt-in caching of the responses to read-only operations.
+//!
+//! When a [`ResponseCache`] is configured, the orchestrator checks it as soon as the input of any
+//! operation marked with [`CacheableOperation`] is serialized. A fresh cached response is
+//! deserialized into the operation's output without making any attempts to send the request.
+//! When a cached response has expired but came with an `ETag`, the request is sent with an
+//! `If-None-Match` header instead, and the cached response is reused if the service replies with
+//! `304 Not Modified`.
+//!
+//! Responses are cached for the `max-age` of their `Cache-Control` header, or for the cache's
+//! default TTL if they don't have one. Responses with `Cache-Control: no-store` are never cached,
+//! and responses with `Cache-Control: no-cache` are revalidated every time they are used.
+
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::impl_shared_conversions;
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::byte_stream::ByteStream;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use bytes::Bytes;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_SIZE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Marks an operation's responses as safe to cache.
+///
+/// This is stored in the config bag by operations modeled with the `@readonly` trait, and holds
+/// the shape ID of the operation, which is part of the key that responses are cached by.
+#[derive(Clone, Debug)]
+pub struct CacheableOperation {
+    shape_id: Cow<'static, str>,
+}
+
+impl CacheableOperation {
+    /// Creates a new `CacheableOperation` for the operation with the given shape ID.
+    pub fn new(shape_id: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            shape_id: shape_id.into(),
+        }
+    }
+
+    /// Returns the shape ID of the operation.
+    pub fn shape_id(&self) -> &str {
+        &self.shape_id
+    }
+}
+
+impl Storable for CacheableOperation {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Key that a response is cached by.
+///
+/// Responses are cached by the shape ID of the operation along with its serialized input, which
+/// is the method, URI, headers, and body of the request as the request serializer produced it,
+/// before it is modified by interceptors or signed.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CacheKey {
+    operation: Cow<'static, str>,
+    method: String,
+    uri: String,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+}
+
+impl CacheKey {
+    /// Returns the shape ID of the operation that the response is for.
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    /// Returns the size in bytes of the key.
+    pub fn size(&self) -> usize {
+        let headers: usize = self
+            .headers
+            .iter()
+            .map(|(name, value)| name.len() + value.len())
+            .sum();
+        self.operation.len() + self.method.len() + self.uri.len() + headers + self.body.len()
+    }
+}
+
+/// A response stored in a [`ResponseCache`].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    status: u16,
+    headers: http::HeaderMap,
+    body: Bytes,
+    etag: Option<String>,
+    expires_at: SystemTime,
+}
+
+impl CachedResponse {
+    /// Returns the `ETag` of the response, if it had one.
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    /// Returns the time after which the response must be revalidated before it is used.
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+
+    /// Returns the approximate size in bytes of the response.
+    pub fn size(&self) -> usize {
+        let headers: usize = self
+            .headers
+            .iter()
+            .map(|(name, value)| name.as_str().len() + value.len())
+            .sum();
+        headers + self.body.len()
+    }
+
+    fn to_response(&self) -> HttpResponse {
+        let mut response = http::Response::new(SdkBody::from(self.body.clone()));
+        *response.status_mut() = http::StatusCode::from_u16(self.status).expect("valid status");
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+/// Storage for cached responses.
+///
+/// Implementations are free to evict entries at any time, such as to bound their size.
+pub trait StoreResponses: fmt::Debug + Send + Sync {
+    /// Returns the response cached for `key`, if any.
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse>;
+
+    /// Caches `response` for `key`, replacing any response previously cached for it.
+    fn put(&self, key: CacheKey, response: CachedResponse);
+}
+
+/// Shared response cache store.
+#[derive(Clone, Debug)]
+pub struct SharedResponseStore(Arc<dyn StoreResponses>);
+
+impl SharedResponseStore {
+    /// Creates a new [`SharedResponseStore`].
+    pub fn new(store: impl StoreResponses + 'static) -> Self {
+        Self(Arc::new(store))
+    }
+}
+
+impl StoreResponses for SharedResponseStore {
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        self.0.get(key)
+    }
+
+    fn put(&self, key: CacheKey, response: CachedResponse) {
+        self.0.put(key, response)
+    }
+}
+
+impl_shared_conversions!(convert SharedResponseStore from StoreResponses using SharedResponseStore::new);
+
+/// In-memory response store that evicts the least recently used responses to stay under a
+/// maximum size.
+#[derive(Debug)]
+pub struct InMemoryResponseStore {
+    max_size_bytes: usize,
+    inner: Mutex<InMemoryInner>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryInner {
+    entries: HashMap<CacheKey, InMemoryEntry>,
+    size_bytes: usize,
+    /// Incremented on every access to track how recently each entry was used
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct InMemoryEntry {
+    response: CachedResponse,
+    last_used: u64,
+}
+
+impl Default for InMemoryResponseStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SIZE_BYTES)
+    }
+}
+
+impl InMemoryResponseStore {
+    /// Creates a new `InMemoryResponseStore` that holds at most `max_size_bytes` of responses.
+    ///
+    /// Responses that are larger than this by themselves are never cached.
+    pub fn new(max_size_bytes: usize) -> Self {
+        Self {
+            max_size_bytes,
+            inner: Default::default(),
+        }
+    }
+}
+
+impl StoreResponses for InMemoryResponseStore {
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+        inner.entries.get_mut(key).map(|entry| {
+            entry.last_used = clock;
+            entry.response.clone()
+        })
+    }
+
+    fn put(&self, key: CacheKey, response: CachedResponse) {
+        let size = key.size() + response.size();
+        if size > self.max_size_bytes {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(previous) = inner.entries.remove(&key) {
+            inner.size_bytes -= key.size() + previous.response.size();
+        }
+        while inner.size_bytes + size > self.max_size_bytes {
+            let lru = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+                .expect("the cache can't be over its size when empty");
+            let evicted = inner.entries.remove(&lru).expect("present");
+            inner.size_bytes -= lru.size() + evicted.response.size();
+        }
+        inner.clock += 1;
+        let last_used = inner.clock;
+        inner.size_bytes += size;
+        inner.entries.insert(
+            key,
+            InMemoryEntry {
+                response,
+                last_used,
+            },
+        );
+    }
+}
+
+/// Builder for [`ResponseCache`].
+#[derive(Debug, Default)]
+pub struct ResponseCacheBuilder {
+    store: Option<SharedResponseStore>,
+    default_ttl: Option<Duration>,
+}
+
+impl ResponseCacheBuilder {
+    /// Sets the store that responses are cached in.
+    ///
+    /// Defaults to an [`InMemoryResponseStore`] that holds up to 10 MiB of responses.
+    pub fn store(mut self, store: impl StoreResponses + 'static) -> Self {
+        self.set_store(Some(store.into_shared()));
+        self
+    }
+
+    /// Sets the store that responses are cached in.
+    ///
+    /// Defaults to an [`InMemoryResponseStore`] that holds up to 10 MiB of responses.
+    pub fn set_store(&mut self, store: Option<SharedResponseStore>) -> &mut Self {
+        self.store = store;
+        self
+    }
+
+    /// Sets how long responses without a `Cache-Control` max age are cached for.
+    ///
+    /// Defaults to 60 seconds.
+    pub fn default_ttl(mut self, default_ttl: Duration) -> Self {
+        self.set_default_ttl(Some(default_ttl));
+        self
+    }
+
+    /// Sets how long responses without a `Cache-Control` max age are cached for.
+    ///
+    /// Defaults to 60 seconds.
+    pub fn set_default_ttl(&mut self, default_ttl: Option<Duration>) -> &mut Self {
+        self.default_ttl = default_ttl;
+        self
+    }
+
+    /// Builds the [`ResponseCache`].
+    pub fn build(self) -> ResponseCache {
+        ResponseCache {
+            store: self
+                .store
+                .unwrap_or_else(|| InMemoryResponseStore::default().into_shared()),
+            default_ttl: self.default_ttl.unwrap_or(DEFAULT_TTL),
+        }
+    }
+}
+
+/// Cache for the responses to read-only operations.
+///
+/// See the [module docs](crate::client::response_cache) for how responses are cached.
+///
+/// Since responses are cached by operation input alone, a response cache shouldn't be shared
+/// between clients that send requests on behalf of different identities.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    store: SharedResponseStore,
+    default_ttl: Duration,
+}
+
+impl Storable for ResponseCache {
+    type Storer = StoreReplace<Self>;
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl ResponseCache {
+    /// Returns a builder for a `ResponseCache`.
+    pub fn builder() -> ResponseCacheBuilder {
+        ResponseCacheBuilder::default()
+    }
+
+    /// Returns how long a response with the given headers should be cached for, or `None` if it
+    /// shouldn't be cached at all.
+    fn ttl(&self, headers: &http::HeaderMap) -> Option<Duration> {
+        let mut ttl = self.default_ttl;
+        for directive in headers
+            .get_all(http::header::CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|directive| directive.trim().to_ascii_lowercase())
+        {
+            if directive == "no-store" {
+                return None;
+            } else if directive == "no-cache" {
+                return Some(Duration::ZERO);
+            } else if let Some(max_age) = directive.strip_prefix("max-age=") {
+                if let Ok(max_age) = max_age.trim_matches('"').parse() {
+                    ttl = Duration::from_secs(max_age);
+                }
+            }
+        }
+        Some(ttl)
+    }
+}
+
+fn etag(headers: &http::HeaderMap) -> Option<String> {
+    headers
+        .get(http::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// The entry of the response cache for an operation's input, which the operation's requests
+/// revalidate or replace.
+#[derive(Clone, Debug)]
+pub(crate) struct CacheLookup {
+    key: CacheKey,
+    cached: Option<CachedResponse>,
+}
+
+impl Storable for CacheLookup {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Looks up the response cached for an operation's serialized `request`, if the response cache is
+/// configured and the operation is cacheable.
+///
+/// A fresh cached response is returned. Otherwise, the lookup is stored in the config bag so that
+/// the response to the request can be cached by [`send`].
+pub(crate) fn lookup(
+    request: &HttpRequest,
+    runtime_components: &RuntimeComponents,
+    cfg: &mut ConfigBag,
+) -> Option<HttpResponse> {
+    let (Some(cache), Some(operation), Some(body)) = (
+        cfg.load::<ResponseCache>(),
+        cfg.load::<CacheableOperation>(),
+        request.body().bytes(),
+    ) else {
+        return None;
+    };
+    let mut headers: Vec<_> = request
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.to_string()))
+        .collect();
+    headers.sort();
+    let key = CacheKey {
+        operation: operation.shape_id.clone(),
+        method: request.method().to_string(),
+        uri: request.uri().to_string(),
+        headers,
+        body: Bytes::copy_from_slice(body),
+    };
+
+    let cached = cache.store.get(&key);
+    if let Some(cached) = &cached {
+        let time_source = runtime_components.time_source().unwrap_or_default();
+        if time_source.now() < cached.expires_at {
+            debug!(operation = %key.operation, "using cached response");
+            return Some(cached.to_response());
+        }
+    }
+    cfg.interceptor_state()
+        .store_put(CacheLookup { key, cached });
+    None
+}
+
+/// Sends a request with `transmit`, revalidating or caching its response if the response cache
+/// was looked up for the operation.
+pub(crate) async fn send<F>(
+    mut request: HttpRequest,
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+    transmit: impl FnOnce(HttpRequest) -> F,
+) -> Result<HttpResponse, ConnectorError>
+where
+    F: Future<Output = Result<HttpResponse, ConnectorError>>,
+{
+    let (Some(cache), Some(CacheLookup { key, cached })) = (
+        cfg.load::<ResponseCache>(),
+        cfg.load::<CacheLookup>().cloned(),
+    ) else {
+        return transmit(request).await;
+    };
+    let time_source = runtime_components.time_source().unwrap_or_default();
+    if let Some(etag) = cached.as_ref().and_then(|cached| cached.etag.as_ref()) {
+        request.headers_mut().insert("if-none-match", etag.clone());
+    }
+
+    let response = transmit(request).await?;
+    if response.status() == http::StatusCode::NOT_MODIFIED {
+        if let Some(mut cached) = cached {
+            debug!(operation = %key.operation, "cached response was revalidated");
+            // The cached response's caching directives apply unless the service sent new ones
+            let headers = if response.headers().contains_key(http::header::CACHE_CONTROL) {
+                response.headers()
+            } else {
+                &cached.headers
+            };
+            if let Some(ttl) = cache.ttl(headers) {
+                cached.expires_at = time_source.now() + ttl;
+                cache.store.put(key, cached.clone());
+            }
+            return Ok(cached.to_response());
+        }
+    }
+    if !response.status().is_success() {
+        return Ok(response);
+    }
+    let ttl = match cache.ttl(response.headers()) {
+        // A response that expires immediately is only worth caching if it can be revalidated
+        Some(ttl) if !ttl.is_zero() || etag(response.headers()).is_some() => ttl,
+        _ => return Ok(response),
+    };
+
+    let (parts, body) = response.into_parts();
+    let body = ByteStream::new(body)
+        .collect()
+        .await
+        .map_err(|err| ConnectorError::io(BoxError::from(err)))?
+        .into_bytes();
+    cache.store.put(
+        key,
+        CachedResponse {
+            status: parts.status.as_u16(),
+            etag: etag(&parts.headers),
+            headers: parts.headers.clone(),
+            body: body.clone(),
+            expires_at: time_source.now() + ttl,
+        },
+    );
+    Ok(HttpResponse::from_parts(parts, SdkBody::from(body)))
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::config_bag::{FrozenLayer, Layer};
+
+    fn runtime_components(time_source: ManualTimeSource) -> RuntimeComponents {
+        RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time_source))
+            .build()
+            .unwrap()
+    }
+
+    fn cfg(cache: ResponseCache, cacheable: bool) -> FrozenLayer {
+        let mut layer = Layer::new("test");
+        layer.store_put(cache);
+        if cacheable {
+            layer.store_put(CacheableOperation::new("com.example#DescribeThing"));
+        }
+        layer.freeze()
+    }
+
+    fn request(body: &'static str) -> HttpRequest {
+        let mut request = HttpRequest::new(SdkBody::from(body));
+        request.set_uri("https://example.com/").unwrap();
+        request
+    }
+
+    fn response(body: &'static str, headers: &[(&'static str, &'static str)]) -> HttpResponse {
+        let mut response = http::Response::builder().status(200);
+        for (name, value) in headers {
+            response = response.header(*name, *value);
+        }
+        response.body(SdkBody::from(body)).unwrap()
+    }
+
+    /// Sends a request through the response cache, returning the response body and the request
+    /// that was sent to the service, if any
+    async fn send_request(
+        body: &'static str,
+        runtime_components: &RuntimeComponents,
+        cfg: &FrozenLayer,
+        response: HttpResponse,
+    ) -> (String, Option<HttpRequest>) {
+        send_http_request(request(body), runtime_components, cfg, response).await
+    }
+
+    async fn send_http_request(
+        request: HttpRequest,
+        runtime_components: &RuntimeComponents,
+        layer: &FrozenLayer,
+        response: HttpResponse,
+    ) -> (String, Option<HttpRequest>) {
+        // Like the orchestrator, look up the cache for each operation before making any attempts
+        let mut cfg = ConfigBag::of_layers(vec![]);
+        cfg.push_shared_layer(layer.clone());
+        let mut sent = None;
+        let response = match lookup(&request, runtime_components, &mut cfg) {
+            Some(cached) => cached,
+            None => send(request, runtime_components, &cfg, |request| {
+                sent = Some(request);
+                async move { Ok(response) }
+            })
+            .await
+            .unwrap(),
+        };
+        let body = std::str::from_utf8(response.body().bytes().unwrap())
+            .unwrap()
+            .to_string();
+        (body, sent)
+    }
+
+    #[tokio::test]
+    async fn fresh_responses_are_served_from_the_cache() {
+        let time = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let rc = runtime_components(time.clone());
+        let cfg = cfg(
+            ResponseCache::builder()
+                .default_ttl(Duration::from_secs(10))
+                .build(),
+            true,
+        );
+
+        let (body, sent) = send_request("input", &rc, &cfg, response("first", &[])).await;
+        assert_eq!(("first", true), (body.as_str(), sent.is_some()));
+        let (body, sent) = send_request("input", &rc, &cfg, response("second", &[])).await;
+        assert_eq!(("first", false), (body.as_str(), sent.is_some()));
+
+        // A different input isn't served from the cache
+        let (body, _) = send_request("other input", &rc, &cfg, response("third", &[])).await;
+        assert_eq!("third", body);
+
+        time.advance(Duration::from_secs(10));
+        let (body, sent) = send_request("input", &rc, &cfg, response("fourth", &[])).await;
+        assert_eq!(("fourth", true), (body.as_str(), sent.is_some()));
+    }
+
+    #[tokio::test]
+    async fn requests_with_different_headers_are_cached_separately() {
+        let rc = runtime_components(ManualTimeSource::new(SystemTime::UNIX_EPOCH));
+        let cfg = cfg(ResponseCache::default(), true);
+        let with_range = |range: &'static str| {
+            let mut request = request("input");
+            request.headers_mut().insert("range", range);
+            request
+        };
+
+        send_http_request(with_range("bytes=0-9"), &rc, &cfg, response("first", &[])).await;
+        let (body, sent) = send_http_request(
+            with_range("bytes=10-19"),
+            &rc,
+            &cfg,
+            response("second", &[]),
+        )
+        .await;
+        assert_eq!(("second", true), (body.as_str(), sent.is_some()));
+        let (body, sent) =
+            send_http_request(with_range("bytes=0-9"), &rc, &cfg, response("third", &[])).await;
+        assert_eq!(("first", false), (body.as_str(), sent.is_some()));
+    }
+
+    #[tokio::test]
+    async fn operations_that_are_not_cacheable_are_not_cached() {
+        let rc = runtime_components(ManualTimeSource::new(SystemTime::UNIX_EPOCH));
+        let cfg = cfg(ResponseCache::default(), false);
+
+        send_request("input", &rc, &cfg, response("first", &[])).await;
+        let (body, sent) = send_request("input", &rc, &cfg, response("second", &[])).await;
+        assert_eq!(("second", true), (body.as_str(), sent.is_some()));
+    }
+
+    #[tokio::test]
+    async fn cache_control_is_respected() {
+        let time = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let rc = runtime_components(time.clone());
+        let cfg = cfg(ResponseCache::default(), true);
+
+        let no_store = response("first", &[("cache-control", "no-store")]);
+        send_request("input", &rc, &cfg, no_store).await;
+        let max_age = response("second", &[("cache-control", "public, max-age=5")]);
+        let (body, sent) = send_request("input", &rc, &cfg, max_age).await;
+        assert_eq!(("second", true), (body.as_str(), sent.is_some()));
+
+        time.advance(Duration::from_secs(4));
+        let (body, _) = send_request("input", &rc, &cfg, response("third", &[])).await;
+        assert_eq!("second", body);
+        time.advance(Duration::from_secs(1));
+        let (body, _) = send_request("input", &rc, &cfg, response("fourth", &[])).await;
+        assert_eq!("fourth", body);
+    }
+
+    #[tokio::test]
+    async fn expired_responses_are_revalidated_with_their_etag() {
+        let time = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+        let rc = runtime_components(time.clone());
+        let cfg = cfg(ResponseCache::default(), true);
+
+        let must_revalidate = response(
+            "first",
+            &[("cache-control", "no-cache"), ("etag", "\"v1\"")],
+        );
+        send_request("input", &rc, &cfg, must_revalidate).await;
+
+        let not_modified = http::Response::builder()
+            .status(304)
+            .body(SdkBody::empty())
+            .unwrap();
+        let (body, sent) = send_request("input", &rc, &cfg, not_modified).await;
+        assert_eq!("first", body);
+        assert_eq!(
+            Some("\"v1\""),
+            sent.as_ref().unwrap().headers().get("if-none-match")
+        );
+
+        // A modified response replaces the cached one
+        let modified = response("second", &[("etag", "\"v2\"")]);
+        let (body, _) = send_request("input", &rc, &cfg, modified).await;
+        assert_eq!("second", body);
+        let (body, sent) = send_request("input", &rc, &cfg, response("third", &[])).await;
+        assert_eq!(("second", false), (body.as_str(), sent.is_some()));
+    }
+
+    fn key(body: &'static str) -> CacheKey {
+        CacheKey {
+            operation: "op".into(),
+            method: "GET".into(),
+            uri: "/".into(),
+            headers: Vec::new(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    fn cached(body: &'static str) -> CachedResponse {
+        CachedResponse {
+            status: 200,
+            headers: Default::default(),
+            body: Bytes::from_static(body.as_bytes()),
+            etag: None,
+            expires_at: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn in_memory_store_evicts_the_least_recently_used_responses() {
+        // Each entry is 10 bytes: 2 for the operation, 3 for the method, 1 for the URI,
+        // 1 for the key body, and 3 for the response body
+        let store = InMemoryResponseStore::new(30);
+        store.put(key("a"), cached("aaa"));
+        store.put(key("b"), cached("bbb"));
+        store.put(key("c"), cached("ccc"));
+        store.get(&key("a"));
+
+        store.put(key("d"), cached("ddd"));
+        assert!(store.get(&key("b")).is_none());
+        for present in ["a", "c", "d"] {
+            assert!(store.get(&key(present)).is_some(), "{present}");
+        }
+
+        // Responses bigger than the whole store aren't cached
+        let big = "too big to fit in the store";
+        store.put(key("f"), cached(big));
+        assert!(store.get(&key("f")).is_none());
+    }
+
+    #[tokio::test]
+    async fn cached_responses_are_deserialized_without_making_attempts() {
+        use crate::client::http::test_util::{ReplayEvent, StaticReplayClient};
+        use crate::client::orchestrator::operation::Operation;
+        use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+        use aws_smithy_types::timeout::TimeoutConfig;
+        use std::convert::Infallible;
+
+        let http_client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .uri("http://localhost:1234/thing")
+                .body(SdkBody::empty())
+                .unwrap(),
+            response("thing", &[]),
+        )]);
+        let operation = Operation::builder()
+            .service_name("test")
+            .operation_name("DescribeThing")
+            .http_client(http_client.clone())
+            .endpoint_url("http://localhost:1234")
+            .no_auth()
+            .no_retry()
+            .timeout_config(TimeoutConfig::disabled())
+            .time_source(ManualTimeSource::new(SystemTime::UNIX_EPOCH))
+            .runtime_plugin(
+                StaticRuntimePlugin::new().with_config(cfg(ResponseCache::default(), true)),
+            )
+            .serializer(|path: &'static str| {
+                let mut request = HttpRequest::empty();
+                request.set_uri(path).unwrap();
+                Ok(request)
+            })
+            .deserializer::<_, Infallible>(|response| {
+                Ok(std::str::from_utf8(response.body().bytes().unwrap())
+                    .unwrap()
+                    .to_string())
+            })
+            .build();
+
+        assert_eq!("thing", operation.invoke("/thing").await.unwrap());
+        assert_eq!("thing", operation.invoke("/thing").await.unwrap());
+        assert_eq!(1, http_client.actual_requests().count());
+        http_client.assert_requests_match(&[]);
+    }
+}
@@ -9,6 +9,9 @@ pub mod classifiers;
 /// Smithy retry strategies.
 pub mod strategy;
 
+/// Request hedging for idempotent operations.
+pub mod hedging;
+
 mod client_rate_limiter;
 mod token_bucket;
 
@@ -0,0 +1,624 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::retries::{RetryPartition, TokenBucket};
+use crate::static_partition_map::StaticPartitionMap;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::TimeSource;
+use aws_smithy_runtime_api::client::http::{HttpConnector, SharedHttpConnector};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use aws_smithy_types::retry::ErrorKind;
+use std::collections::VecDeque;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+const DEFAULT_PERCENTILE: f64 = 0.95;
+const DEFAULT_MIN_SAMPLES: usize = 20;
+const MAX_SAMPLES: usize = 128;
+
+static LATENCY_TRACKERS: StaticPartitionMap<RetryPartition, LatencyTracker> =
+    StaticPartitionMap::new();
+
+/// Configuration for request hedging.
+///
+/// When request hedging is enabled, an attempt of an idempotent operation that hasn't completed
+/// after a delay sends a second, _hedged_, request in parallel. Whichever request succeeds first
+/// is used, and the other one is cancelled. Responses that would be retried, like server errors
+/// and throttling errors, don't count as succeeding.
+///
+/// The delay is the configured percentile of the latencies recently observed for the client's
+/// [`RetryPartition`]. Until enough latencies have been observed, requests are only hedged if an
+/// initial delay was configured. Hedged requests are paid for out of the same token bucket as
+/// retries, so hedging stops while the retry quota is exhausted.
+///
+/// Only operations marked with [`IdempotentOperation`] are hedged, and only when their request
+/// body can be cloned.
+#[derive(Clone, Debug)]
+pub struct HedgingConfig {
+    percentile: f64,
+    min_samples: usize,
+    initial_delay: Option<Duration>,
+}
+
+impl Storable for HedgingConfig {
+    type Storer = StoreReplace<Self>;
+}
+
+impl Default for HedgingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HedgingConfig {
+    /// Creates a new `HedgingConfig` that hedges requests taking longer than the p95 latency.
+    pub fn new() -> Self {
+        Self {
+            percentile: DEFAULT_PERCENTILE,
+            min_samples: DEFAULT_MIN_SAMPLES,
+            initial_delay: None,
+        }
+    }
+
+    /// Sets the latency percentile after which a hedged request is sent.
+    ///
+    /// The percentile is given as a fraction between 0.0 and 1.0 and defaults to 0.95.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the percentile is outside of that range.
+    pub fn with_percentile(mut self, percentile: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&percentile),
+            "percentile must be between 0.0 and 1.0"
+        );
+        self.percentile = percentile;
+        self
+    }
+
+    /// Sets the number of latencies that must be observed before the percentile is used.
+    ///
+    /// Defaults to 20.
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples.clamp(1, MAX_SAMPLES);
+        self
+    }
+
+    /// Sets the delay to hedge requests after until enough latencies have been observed.
+    ///
+    /// By default, requests aren't hedged until enough latencies have been observed.
+    pub fn with_initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = Some(initial_delay);
+        self
+    }
+
+    /// Returns the latency percentile after which a hedged request is sent.
+    pub fn percentile(&self) -> f64 {
+        self.percentile
+    }
+
+    /// Returns the number of latencies that must be observed before the percentile is used.
+    pub fn min_samples(&self) -> usize {
+        self.min_samples
+    }
+
+    /// Returns the delay to hedge requests after until enough latencies have been observed.
+    pub fn initial_delay(&self) -> Option<Duration> {
+        self.initial_delay
+    }
+}
+
+/// Marks an operation as safe to send more than once, making it eligible for request hedging.
+///
+/// This is stored in the config bag by operations modeled with the `@readonly` or
+/// `@idempotent` traits.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct IdempotentOperation;
+
+impl Storable for IdempotentOperation {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Recently observed request latencies for a retry partition.
+#[derive(Clone, Debug, Default)]
+struct LatencyTracker {
+    samples: Arc<Mutex<VecDeque<Duration>>>,
+}
+
+impl LatencyTracker {
+    /// Records the latency of a request that was sent at `sent_at` and just completed.
+    fn record_since(&self, sent_at: SystemTime, time_source: &impl TimeSource) {
+        if let Ok(latency) = time_source.now().duration_since(sent_at) {
+            self.record(latency);
+        }
+    }
+
+    fn record(&self, latency: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+    }
+
+    /// Returns the latency at `percentile`, if at least `min_samples` latencies were recorded.
+    fn percentile(&self, percentile: f64, min_samples: usize) -> Option<Duration> {
+        let mut samples: Vec<_> = self.samples.lock().unwrap().iter().copied().collect();
+        if samples.is_empty() || samples.len() < min_samples {
+            return None;
+        }
+        samples.sort_unstable();
+        // Nearest-rank percentile
+        let rank = (samples.len() as f64 * percentile).ceil() as usize;
+        samples.get(rank.saturating_sub(1)).copied()
+    }
+}
+
+/// Everything needed to hedge a request.
+struct Hedge {
+    request: HttpRequest,
+    delay: Duration,
+    sleep_impl: SharedAsyncSleep,
+    token_bucket: Option<TokenBucket>,
+}
+
+/// Sends a request with `connector`, hedging it if hedging is enabled for the operation.
+pub(crate) async fn send(
+    connector: &SharedHttpConnector,
+    request: HttpRequest,
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+) -> Result<HttpResponse, ConnectorError> {
+    let (Some(config), Some(_), Some(retry_partition), Some(time_source)) = (
+        cfg.load::<HedgingConfig>(),
+        cfg.load::<IdempotentOperation>(),
+        cfg.load::<RetryPartition>(),
+        runtime_components.time_source(),
+    ) else {
+        return connector.call(request).await;
+    };
+    let tracker = LATENCY_TRACKERS.get_or_init_default(retry_partition.clone());
+    let hedge = match (
+        tracker
+            .percentile(config.percentile, config.min_samples)
+            .or(config.initial_delay),
+        runtime_components.sleep_impl(),
+        request.try_clone(),
+    ) {
+        (Some(delay), Some(sleep_impl), Some(hedged_request)) => Some(Hedge {
+            request: hedged_request,
+            delay,
+            sleep_impl,
+            token_bucket: cfg.load::<TokenBucket>().cloned(),
+        }),
+        _ => None,
+    };
+
+    match hedge {
+        Some(hedge) => send_hedged(connector, request, hedge, &tracker, &time_source).await,
+        None => {
+            let sent_at = time_source.now();
+            let result = connector.call(request).await;
+            tracker.record_since(sent_at, &time_source);
+            result
+        }
+    }
+}
+
+/// Returns true if `response` is one that the retry strategy would retry, such as a server error
+/// or a throttling error.
+fn is_retryable(response: &HttpResponse) -> bool {
+    let status = response.status();
+    status.is_server_error() || status.as_u16() == 429
+}
+
+/// Sends `request`, and sends the request of `hedge` too if `request` takes longer than the
+/// hedge's delay to complete.
+///
+/// Returns the first successful response that isn't retryable. If neither request gets one, the
+/// result of the request that completed first is returned. The latency of every request that
+/// completes is recorded with `tracker`.
+async fn send_hedged(
+    connector: &SharedHttpConnector,
+    request: HttpRequest,
+    hedge: Hedge,
+    tracker: &LatencyTracker,
+    time_source: &impl TimeSource,
+) -> Result<HttpResponse, ConnectorError> {
+    let Hedge {
+        request: hedged_request,
+        delay,
+        sleep_impl,
+        token_bucket,
+    } = hedge;
+    let primary_sent_at = time_source.now();
+    let mut primary = Some(connector.call(request));
+    let mut sleep = Some(sleep_impl.sleep(delay));
+    let mut hedged_request = Some(hedged_request);
+    let mut hedged: Option<(_, SystemTime)> = None;
+    let mut permit = None;
+    let mut fallback = None;
+
+    let result = poll_fn(|cx| {
+        if let Some(future) = primary.as_mut() {
+            if let Poll::Ready(result) = Pin::new(future).poll(cx) {
+                primary = None;
+                tracker.record_since(primary_sent_at, time_source);
+                match result {
+                    Ok(response) if !is_retryable(&response) => return Poll::Ready(Ok(response)),
+                    // Failures before the hedge delay are left for the retry strategy to handle
+                    result if hedged.is_none() => {
+                        return Poll::Ready(fallback.take().unwrap_or(result))
+                    }
+                    result => fallback = Some(result),
+                }
+            }
+        }
+        if let Some(future) = sleep.as_mut() {
+            if Pin::new(future).poll(cx).is_ready() {
+                sleep = None;
+                let acquired = token_bucket
+                    .as_ref()
+                    .map(|token_bucket| token_bucket.acquire(&ErrorKind::ServerError));
+                match acquired {
+                    Some(None) => {
+                        debug!("not hedging the request since the retry quota is exhausted")
+                    }
+                    acquired => {
+                        debug!(delay = ?delay, "request is taking a while; sending a hedged request");
+                        permit = acquired.flatten();
+                        let request = hedged_request.take().expect("only hedged once");
+                        hedged = Some((connector.call(request), time_source.now()));
+                    }
+                }
+            }
+        }
+        if let Some((future, sent_at)) = hedged.as_mut() {
+            if let Poll::Ready(result) = Pin::new(future).poll(cx) {
+                tracker.record_since(*sent_at, time_source);
+                hedged = None;
+                match result {
+                    Ok(response) if !is_retryable(&response) => {
+                        debug!("hedged request completed first");
+                        return Poll::Ready(Ok(response));
+                    }
+                    result => {
+                        // The quota spent on a failed hedged request isn't given back
+                        if let Some(permit) = permit.take() {
+                            permit.forget();
+                        }
+                        if primary.is_none() {
+                            return Poll::Ready(fallback.take().unwrap_or(result));
+                        }
+                        fallback = Some(result);
+                    }
+                }
+            }
+        }
+        Poll::Pending
+    })
+    .await;
+    // Dropping the request that didn't complete first cancels it
+    drop(primary);
+    drop(hedged);
+    result
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_runtime_api::client::http::HttpConnectorFuture;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_runtime_api::shared::IntoShared;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::config_bag::Layer;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Connector whose responses take as long as the next configured delay
+    #[derive(Clone, Debug)]
+    struct DelayedConnector {
+        delays: Arc<Mutex<VecDeque<(Duration, u16)>>>,
+        calls: Arc<AtomicUsize>,
+        completed: Arc<AtomicUsize>,
+    }
+
+    impl DelayedConnector {
+        fn new(delays: impl IntoIterator<Item = (Duration, u16)>) -> Self {
+            Self {
+                delays: Arc::new(Mutex::new(delays.into_iter().collect())),
+                calls: Default::default(),
+                completed: Default::default(),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+
+        fn completed(&self) -> usize {
+            self.completed.load(Ordering::SeqCst)
+        }
+    }
+
+    impl HttpConnector for DelayedConnector {
+        fn call(&self, _request: HttpRequest) -> HttpConnectorFuture {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let (delay, status) = self.delays.lock().unwrap().pop_front().expect("delay");
+            let completed = self.completed.clone();
+            HttpConnectorFuture::new(async move {
+                tokio::time::sleep(delay).await;
+                completed.fetch_add(1, Ordering::SeqCst);
+                if status == 0 {
+                    return Err(ConnectorError::io("connection reset".into()));
+                }
+                Ok(::http::Response::builder()
+                    .status(status)
+                    .body(SdkBody::empty())
+                    .unwrap())
+            })
+        }
+    }
+
+    /// Time source that follows Tokio's clock, so that it advances while time is paused
+    #[derive(Debug)]
+    struct TokioTimeSource(tokio::time::Instant);
+
+    impl TimeSource for TokioTimeSource {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + self.0.elapsed()
+        }
+    }
+
+    fn runtime_components() -> RuntimeComponents {
+        RuntimeComponentsBuilder::for_tests()
+            .with_sleep_impl(Some(SharedAsyncSleep::new(TokioSleep::new())))
+            .with_time_source(Some(TokioTimeSource(tokio::time::Instant::now())))
+            .build()
+            .unwrap()
+    }
+
+    fn cfg(partition: &'static str, config: HedgingConfig, idempotent: bool) -> ConfigBag {
+        let mut layer = Layer::new("test");
+        layer.store_put(config);
+        layer.store_put(RetryPartition::new(partition));
+        if idempotent {
+            layer.store_put(IdempotentOperation);
+        }
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    fn request() -> HttpRequest {
+        HttpRequest::new(SdkBody::from("hello"))
+    }
+
+    fn hedge_after(delay: Duration) -> HedgingConfig {
+        HedgingConfig::new().with_initial_delay(delay)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedged_request_wins_when_the_first_request_is_slow() {
+        let connector = DelayedConnector::new([
+            (Duration::from_secs(10), 500),
+            (Duration::from_secs(1), 200),
+        ]);
+        let cfg = cfg("hedge-wins", hedge_after(Duration::from_secs(1)), true);
+        let response = send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &cfg,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!(2, connector.calls());
+        // The slow request was cancelled
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        assert_eq!(1, connector.completed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fast_requests_are_not_hedged() {
+        let connector = DelayedConnector::new([(Duration::from_millis(100), 200)]);
+        let cfg = cfg("not-hedged", hedge_after(Duration::from_secs(1)), true);
+        send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &cfg,
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, connector.calls());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_idempotent_operations_are_not_hedged() {
+        let connector = DelayedConnector::new([(Duration::from_secs(10), 200)]);
+        let cfg = cfg("not-idempotent", hedge_after(Duration::from_secs(1)), false);
+        send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &cfg,
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, connector.calls());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_request_is_used_if_the_hedged_request_fails() {
+        let connector =
+            DelayedConnector::new([(Duration::from_secs(3), 200), (Duration::from_secs(1), 0)]);
+        let cfg = cfg("hedge-fails", hedge_after(Duration::from_secs(1)), true);
+        let response = send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &cfg,
+        )
+        .await
+        .unwrap();
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!(2, connector.calls());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retryable_responses_do_not_win() {
+        // The first request fails with a server error while the hedged request is in flight
+        let connector =
+            DelayedConnector::new([(Duration::from_secs(2), 503), (Duration::from_secs(5), 200)]);
+        let server_error = cfg("retryable-first", hedge_after(Duration::from_secs(1)), true);
+        let response = send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &server_error,
+        )
+        .await
+        .unwrap();
+        assert_eq!(200, response.status().as_u16());
+
+        // The hedged request is throttled before the first request completes
+        let connector =
+            DelayedConnector::new([(Duration::from_secs(3), 200), (Duration::from_secs(1), 429)]);
+        let throttled = cfg("retryable-hedge", hedge_after(Duration::from_secs(1)), true);
+        let response = send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &throttled,
+        )
+        .await
+        .unwrap();
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_completed_response_is_used_if_neither_request_succeeds() {
+        let connector =
+            DelayedConnector::new([(Duration::from_secs(3), 500), (Duration::from_secs(1), 503)]);
+        let cfg = cfg(
+            "neither-succeeds",
+            hedge_after(Duration::from_secs(1)),
+            true,
+        );
+        let response = send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &cfg,
+        )
+        .await
+        .unwrap();
+        assert_eq!(503, response.status().as_u16());
+        assert_eq!(2, connector.completed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn latency_is_recorded_for_every_completed_request() {
+        let connector =
+            DelayedConnector::new([(Duration::from_secs(3), 200), (Duration::from_secs(1), 0)]);
+        let cfg = cfg("record-all", hedge_after(Duration::from_secs(1)), true);
+        send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &cfg,
+        )
+        .await
+        .unwrap();
+
+        let tracker = LATENCY_TRACKERS.get_or_init_default(RetryPartition::new("record-all"));
+        let samples: Vec<_> = tracker.samples.lock().unwrap().iter().copied().collect();
+        assert_eq!(
+            vec![Duration::from_secs(1), Duration::from_secs(3)],
+            samples
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedging_respects_the_token_bucket() {
+        let connector = DelayedConnector::new([(Duration::from_secs(10), 200)]);
+        let mut cfg = cfg("empty-bucket", hedge_after(Duration::from_secs(1)), true);
+        let mut layer = Layer::new("token bucket");
+        layer.store_put(TokenBucket::new(1));
+        cfg.push_layer(layer);
+        send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components(),
+            &cfg,
+        )
+        .await
+        .unwrap();
+        assert_eq!(1, connector.calls());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedge_delay_follows_the_observed_latency_percentile() {
+        let config = HedgingConfig::new()
+            .with_min_samples(4)
+            .with_percentile(0.5);
+        let cfg = cfg("percentile", config, true);
+        let runtime_components = runtime_components();
+
+        // Before enough latencies have been observed, requests aren't hedged
+        let latencies = [1, 2, 3, 4].map(|secs| (Duration::from_secs(secs), 200));
+        let connector = DelayedConnector::new(latencies);
+        for _ in 0..4 {
+            send(
+                &connector.clone().into_shared(),
+                request(),
+                &runtime_components,
+                &cfg,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(4, connector.calls());
+
+        // The median latency is 2 seconds, so a 10 second request is hedged after 2 seconds
+        let connector = DelayedConnector::new([
+            (Duration::from_secs(10), 200),
+            (Duration::from_secs(1), 200),
+        ]);
+        let start = tokio::time::Instant::now();
+        send(
+            &connector.clone().into_shared(),
+            request(),
+            &runtime_components,
+            &cfg,
+        )
+        .await
+        .unwrap();
+        assert_eq!(2, connector.calls());
+        assert_eq!(Duration::from_secs(3), start.elapsed());
+    }
+
+    #[test]
+    fn latency_percentiles() {
+        let tracker = LatencyTracker::default();
+        assert_eq!(None, tracker.percentile(0.5, 1));
+        for millis in 1..=100 {
+            tracker.record(Duration::from_millis(millis));
+        }
+        assert_eq!(None, tracker.percentile(0.5, 101));
+        assert_eq!(Some(Duration::from_millis(50)), tracker.percentile(0.5, 1));
+        assert_eq!(Some(Duration::from_millis(95)), tracker.percentile(0.95, 1));
+        assert_eq!(Some(Duration::from_millis(100)), tracker.percentile(1.0, 1));
+    }
+}
@@ -0,0 +1,468 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::retries::classifiers::run_classifiers_on_ctx;
+use crate::client::retries::strategy::StandardRetryStrategy;
+use crate::client::retries::RetryPartition;
+use crate::static_partition_map::StaticPartitionMap;
+use aws_smithy_http::operation::Metadata;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
+use aws_smithy_runtime_api::client::retries::classifiers::{RetryAction, RetryReason};
+use aws_smithy_runtime_api::client::retries::{RetryStrategy, SharedRetryStrategy, ShouldAttempt};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::retry::ErrorKind;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_OPEN_DURATION: Duration = Duration::from_secs(30);
+
+static CIRCUIT_BREAKERS: StaticPartitionMap<CircuitBreakerPartition, CircuitBreaker> =
+    StaticPartitionMap::new();
+
+/// Retry strategy that stops sending requests to an operation that keeps failing.
+///
+/// Every operation has a circuit breaker per [`RetryPartition`], which starts out _closed_.
+/// Each attempt is classified with the client's retry classifiers, and attempts that fail with a
+/// transient, server, or throttling error count as failures. Once enough consecutive attempts
+/// have failed, the circuit breaker _opens_, and requests fail fast with a
+/// [`CircuitBreakerOpenError`] instead of being sent. After the circuit breaker has been open
+/// for a while, it becomes _half-open_ and lets a single probe request through. The circuit
+/// breaker closes again if the probe succeeds, and reopens if it fails.
+///
+/// While the circuit breaker is closed, the decision of whether and when to retry is delegated
+/// to another retry strategy, which is a [`StandardRetryStrategy`] by default.
+#[derive(Debug)]
+pub struct CircuitBreakerRetryStrategy {
+    inner: SharedRetryStrategy,
+    failure_threshold: u32,
+    open_duration: Duration,
+}
+
+impl Default for CircuitBreakerRetryStrategy {
+    fn default() -> Self {
+        Self {
+            inner: StandardRetryStrategy::new().into_shared(),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            open_duration: DEFAULT_OPEN_DURATION,
+        }
+    }
+}
+
+impl CircuitBreakerRetryStrategy {
+    /// Creates a new circuit breaker retry strategy that wraps a [`StandardRetryStrategy`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the retry strategy that decides whether and when to retry while the circuit breaker is closed.
+    pub fn with_retry_strategy(mut self, retry_strategy: impl RetryStrategy + 'static) -> Self {
+        self.inner = retry_strategy.into_shared();
+        self
+    }
+
+    /// Sets the number of consecutive failed attempts that opens the circuit breaker.
+    ///
+    /// Defaults to 5. A threshold of zero is treated as one.
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Sets how long the circuit breaker stays open before letting a probe request through.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn with_open_duration(mut self, open_duration: Duration) -> Self {
+        self.open_duration = open_duration;
+        self
+    }
+
+    fn circuit_breaker(cfg: &ConfigBag) -> (CircuitBreakerPartition, CircuitBreaker) {
+        let partition = CircuitBreakerPartition {
+            retry_partition: cfg
+                .load::<RetryPartition>()
+                .cloned()
+                .unwrap_or_else(|| RetryPartition::new("default")),
+            operation: cfg
+                .load::<Metadata>()
+                .map(|metadata| Cow::Owned(metadata.name().to_string()))
+                .unwrap_or(Cow::Borrowed("")),
+        };
+        let circuit_breaker = CIRCUIT_BREAKERS.get_or_init_default(partition.clone());
+        (partition, circuit_breaker)
+    }
+}
+
+fn now(runtime_components: &RuntimeComponents) -> SystemTime {
+    runtime_components.time_source().unwrap_or_default().now()
+}
+
+impl RetryStrategy for CircuitBreakerRetryStrategy {
+    fn should_attempt_initial_request(
+        &self,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Result<ShouldAttempt, BoxError> {
+        let (partition, circuit_breaker) = Self::circuit_breaker(cfg);
+        let now = now(runtime_components);
+        if let Err(retry_after) = circuit_breaker.acquire(now, self.open_duration) {
+            debug!(
+                retry_partition = %partition.retry_partition,
+                operation = %partition.operation,
+                "circuit breaker is open, so the request will not be sent"
+            );
+            return Err(CircuitBreakerOpenError {
+                retry_partition: partition.retry_partition,
+                operation: partition.operation,
+                retry_after,
+            }
+            .into());
+        }
+        self.inner
+            .should_attempt_initial_request(runtime_components, cfg)
+    }
+
+    fn should_attempt_retry(
+        &self,
+        ctx: &InterceptorContext,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Result<ShouldAttempt, BoxError> {
+        let output_or_error = ctx.output_or_error().expect(
+            "This must never be called without reaching the point where the result exists.",
+        );
+        let failed = output_or_error.is_err()
+            && match run_classifiers_on_ctx(runtime_components.retry_classifiers(), ctx) {
+                RetryAction::RetryIndicated(RetryReason::RetryableError { kind, .. }) => {
+                    kind != ErrorKind::ClientError
+                }
+                _ => false,
+            };
+
+        let (partition, circuit_breaker) = Self::circuit_breaker(cfg);
+        let now = now(runtime_components);
+        if failed {
+            if circuit_breaker.record_failure(now, self.failure_threshold) {
+                debug!(
+                    retry_partition = %partition.retry_partition,
+                    operation = %partition.operation,
+                    "circuit breaker is open, so the request will not be retried"
+                );
+                return Ok(ShouldAttempt::No);
+            }
+        } else if output_or_error.is_ok() {
+            circuit_breaker.record_success();
+        }
+        // Other errors, like client errors, neither open nor close the circuit breaker
+        self.inner
+            .should_attempt_retry(ctx, runtime_components, cfg)
+    }
+}
+
+/// Error returned instead of sending a request while its circuit breaker is open.
+#[derive(Debug)]
+pub struct CircuitBreakerOpenError {
+    retry_partition: RetryPartition,
+    operation: Cow<'static, str>,
+    retry_after: Duration,
+}
+
+impl CircuitBreakerOpenError {
+    /// Returns the name of the operation whose circuit breaker is open.
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    /// Returns how long it will be until the circuit breaker lets a probe request through.
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+}
+
+impl fmt::Display for CircuitBreakerOpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the circuit breaker for `{}` in retry partition `{}` is open, so the request was not sent",
+            self.operation, self.retry_partition
+        )
+    }
+}
+
+impl StdError for CircuitBreakerOpenError {}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct CircuitBreakerPartition {
+    retry_partition: RetryPartition,
+    operation: Cow<'static, str>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct CircuitBreaker {
+    state: Arc<Mutex<CircuitState>>,
+}
+
+#[derive(Debug)]
+enum CircuitState {
+    Closed { consecutive_failures: u32 },
+    Open { opened_at: SystemTime },
+    HalfOpen { probe_sent_at: SystemTime },
+}
+
+impl Default for CircuitState {
+    fn default() -> Self {
+        CircuitState::Closed {
+            consecutive_failures: 0,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Checks whether a request may be sent, returning how long until one may be sent if not.
+    fn acquire(&self, now: SystemTime, open_duration: Duration) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let waiting_since = match *state {
+            CircuitState::Closed { .. } => return Ok(()),
+            CircuitState::Open { opened_at } => opened_at,
+            // If a probe never completed, e.g. because it was cancelled, send another one
+            CircuitState::HalfOpen { probe_sent_at } => probe_sent_at,
+        };
+        let elapsed = now.duration_since(waiting_since).unwrap_or_default();
+        if elapsed >= open_duration {
+            *state = CircuitState::HalfOpen { probe_sent_at: now };
+            Ok(())
+        } else {
+            Err(open_duration - elapsed)
+        }
+    }
+
+    /// Records a failed attempt, returning true if the circuit breaker is now open.
+    fn record_failure(&self, now: SystemTime, failure_threshold: u32) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            CircuitState::Closed {
+                consecutive_failures,
+            } => {
+                let consecutive_failures = consecutive_failures + 1;
+                *state = if consecutive_failures >= failure_threshold {
+                    CircuitState::Open { opened_at: now }
+                } else {
+                    CircuitState::Closed {
+                        consecutive_failures,
+                    }
+                };
+            }
+            CircuitState::Open { .. } => {}
+            CircuitState::HalfOpen { .. } => *state = CircuitState::Open { opened_at: now },
+        }
+        matches!(*state, CircuitState::Open { .. })
+    }
+
+    fn record_success(&self) {
+        *self.state.lock().unwrap() = CircuitState::default();
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_runtime_api::client::interceptors::context::{Input, Output};
+    use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
+    use aws_smithy_runtime_api::client::retries::classifiers::SharedRetryClassifier;
+    use aws_smithy_runtime_api::client::retries::{AlwaysRetry, RequestAttempts};
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::config_bag::Layer;
+    use aws_smithy_types::retry::RetryConfig;
+
+    struct TestCase {
+        time_source: ManualTimeSource,
+        runtime_components: RuntimeComponents,
+        cfg: ConfigBag,
+        strategy: CircuitBreakerRetryStrategy,
+    }
+
+    impl TestCase {
+        // Each test uses its own retry partition since circuit breakers are shared statically
+        fn new(retry_partition: &'static str, error_kind: ErrorKind) -> Self {
+            let time_source = ManualTimeSource::new(SystemTime::UNIX_EPOCH);
+            let runtime_components = RuntimeComponentsBuilder::for_tests()
+                .with_retry_classifier(SharedRetryClassifier::new(AlwaysRetry(error_kind)))
+                .with_time_source(Some(time_source.clone()))
+                .build()
+                .unwrap();
+            let mut layer = Layer::new("test");
+            layer.store_put(RetryConfig::standard().with_max_attempts(3));
+            layer.store_put(RetryPartition::new(retry_partition));
+            layer.store_put(Metadata::new("GetThing", "ThingService"));
+            layer.store_put(RequestAttempts::new(1));
+            Self {
+                time_source,
+                runtime_components,
+                cfg: ConfigBag::of_layers(vec![layer]),
+                strategy: CircuitBreakerRetryStrategy::new()
+                    .with_failure_threshold(2)
+                    .with_open_duration(Duration::from_secs(10)),
+            }
+        }
+
+        fn initial_request(&self) -> Result<ShouldAttempt, BoxError> {
+            self.strategy
+                .should_attempt_initial_request(&self.runtime_components, &self.cfg)
+        }
+
+        fn complete_attempt(&self, succeeded: bool) -> ShouldAttempt {
+            let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+            if succeeded {
+                ctx.set_output_or_error(Ok(Output::doesnt_matter()));
+            } else {
+                ctx.set_output_or_error(Err(OrchestratorError::other("doesn't matter")));
+            }
+            self.strategy
+                .should_attempt_retry(&ctx, &self.runtime_components, &self.cfg)
+                .unwrap()
+        }
+    }
+
+    fn assert_fails_fast(result: Result<ShouldAttempt, BoxError>) -> Duration {
+        let err = result.expect_err("circuit breaker should be open");
+        let err = err
+            .downcast_ref::<CircuitBreakerOpenError>()
+            .expect("fails fast with a CircuitBreakerOpenError");
+        assert_eq!("GetThing", err.operation());
+        err.retry_after()
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let test = TestCase::new("opens_after_consecutive_failures", ErrorKind::ServerError);
+
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+        assert!(matches!(
+            test.complete_attempt(false),
+            ShouldAttempt::YesAfterDelay(_)
+        ));
+        // The second failure opens the circuit, so the request isn't retried
+        assert_eq!(ShouldAttempt::No, test.complete_attempt(false));
+
+        test.time_source.advance(Duration::from_secs(4));
+        assert_eq!(
+            Duration::from_secs(6),
+            assert_fails_fast(test.initial_request())
+        );
+    }
+
+    #[test]
+    fn successful_attempts_reset_the_failure_count() {
+        let test = TestCase::new(
+            "successful_attempts_reset_the_failure_count",
+            ErrorKind::TransientError,
+        );
+
+        test.complete_attempt(false);
+        test.complete_attempt(true);
+        test.complete_attempt(false);
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+    }
+
+    #[test]
+    fn client_errors_do_not_open_the_circuit() {
+        let test = TestCase::new(
+            "client_errors_do_not_open_the_circuit",
+            ErrorKind::ClientError,
+        );
+
+        for _ in 0..5 {
+            test.complete_attempt(false);
+        }
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+    }
+
+    #[test]
+    fn client_errors_do_not_reset_the_failure_count() {
+        let server_errors = TestCase::new(
+            "client_errors_do_not_reset_the_failure_count",
+            ErrorKind::ServerError,
+        );
+        let client_errors = TestCase::new(
+            "client_errors_do_not_reset_the_failure_count",
+            ErrorKind::ClientError,
+        );
+
+        server_errors.complete_attempt(false);
+        client_errors.complete_attempt(false);
+        assert_eq!(ShouldAttempt::No, server_errors.complete_attempt(false));
+        assert_fails_fast(server_errors.initial_request());
+    }
+
+    #[test]
+    fn half_open_circuit_sends_a_single_probe() {
+        let test = TestCase::new(
+            "half_open_circuit_sends_a_single_probe",
+            ErrorKind::ThrottlingError,
+        );
+        test.complete_attempt(false);
+        test.complete_attempt(false);
+
+        test.time_source.advance(Duration::from_secs(10));
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+        // Other requests fail fast while the probe is in flight
+        assert_fails_fast(test.initial_request());
+
+        // A successful probe closes the circuit
+        test.complete_attempt(true);
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+    }
+
+    #[test]
+    fn failed_probe_reopens_the_circuit() {
+        let test = TestCase::new("failed_probe_reopens_the_circuit", ErrorKind::ServerError);
+        test.complete_attempt(false);
+        test.complete_attempt(false);
+
+        test.time_source.advance(Duration::from_secs(10));
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+        assert_eq!(ShouldAttempt::No, test.complete_attempt(false));
+
+        test.time_source.advance(Duration::from_secs(1));
+        assert_eq!(
+            Duration::from_secs(9),
+            assert_fails_fast(test.initial_request())
+        );
+        test.time_source.advance(Duration::from_secs(9));
+        assert_eq!(ShouldAttempt::Yes, test.initial_request().unwrap());
+    }
+
+    #[test]
+    fn circuits_are_partitioned_by_operation() {
+        let test = TestCase::new(
+            "circuits_are_partitioned_by_operation",
+            ErrorKind::ServerError,
+        );
+        test.complete_attempt(false);
+        test.complete_attempt(false);
+        assert_fails_fast(test.initial_request());
+
+        let mut layer = Layer::new("other operation");
+        layer.store_put(RetryConfig::standard());
+        layer.store_put(RetryPartition::new("circuits_are_partitioned_by_operation"));
+        layer.store_put(Metadata::new("PutThing", "ThingService"));
+        let cfg = ConfigBag::of_layers(vec![layer]);
+        assert_eq!(
+            ShouldAttempt::Yes,
+            test.strategy
+                .should_attempt_initial_request(&test.runtime_components, &cfg)
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Telemetry implementations.
+//!
+//! See the [module docs in `aws-smithy-runtime-api`](aws_smithy_runtime_api::client::telemetry)
+//! for more information about the telemetry interfaces.
+
+use aws_smithy_runtime_api::client::telemetry::{
+    Attributes, Counter, Histogram, InstrumentDescriptor, Meter, ProvideMeter, SharedCounter,
+    SharedHistogram, SharedMeter,
+};
+use aws_smithy_runtime_api::shared::IntoShared;
+
+/// In-memory [`ProvideMeter`] implementation for asserting on recorded metrics in tests.
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+/// The instrumentation scope of the meters used by this crate.
+pub(crate) const METER_SCOPE: &str = "aws-smithy-runtime";
+
+/// Names of the metrics recorded by the orchestrator and the built-in HTTP clients.
+///
+/// The orchestrator records its metrics with `rpc.service` and `rpc.method` attributes set
+/// to the service and operation names.
+pub mod metric_names {
+    /// Histogram of the overall time taken by an operation, in seconds.
+    pub const CALL_DURATION: &str = "smithy.client.call.duration";
+    /// Counter of the attempts made for operations.
+    pub const CALL_ATTEMPTS: &str = "smithy.client.call.attempts";
+    /// Counter of the operations that failed.
+    pub const CALL_ERRORS: &str = "smithy.client.call.errors";
+    /// Histogram of the time taken by each attempt, in seconds.
+    pub const ATTEMPT_DURATION: &str = "smithy.client.call.attempt_duration";
+    /// Histogram of the time taken to serialize a request, in seconds.
+    pub const SERIALIZATION_DURATION: &str = "smithy.client.call.serialization_duration";
+    /// Histogram of the time taken to resolve an endpoint, in seconds.
+    pub const RESOLVE_ENDPOINT_DURATION: &str = "smithy.client.call.resolve_endpoint_duration";
+    /// Histogram of the time taken to resolve an identity (including the identity cache), in seconds.
+    pub const RESOLVE_IDENTITY_DURATION: &str = "smithy.client.call.auth.resolve_identity_duration";
+    /// Histogram of the time taken to sign a request, in seconds.
+    pub const SIGNING_DURATION: &str = "smithy.client.call.auth.signing_duration";
+    /// Histogram of the time taken to send a request and receive the response headers, in seconds.
+    pub const TRANSMIT_DURATION: &str = "smithy.client.call.transmit_duration";
+    /// Histogram of the time taken to read and deserialize a response, in seconds.
+    pub const DESERIALIZATION_DURATION: &str = "smithy.client.call.deserialization_duration";
+    /// Histogram of the total time an operation spent backing off between retries, in seconds.
+    pub const RETRY_BACKOFF_DURATION: &str = "smithy.client.call.retry_backoff_duration";
+    /// Counter of request body bytes sent, when the body's length is known.
+    pub const BYTES_SENT: &str = "smithy.client.http.bytes_sent";
+    /// Counter of response body bytes received, when the body's length is known.
+    pub const BYTES_RECEIVED: &str = "smithy.client.http.bytes_received";
+    /// Counter of new connections opened by the hyper-backed HTTP client.
+    ///
+    /// This is recorded without attributes since connections are shared between operations.
+    pub const HTTP_CONNECTIONS_OPENED: &str = "smithy.client.http.connections.opened";
+}
+
+/// A [`ProvideMeter`] implementation that discards all metrics.
+///
+/// This is the default meter provider.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct NoopMeterProvider;
+
+impl NoopMeterProvider {
+    /// Creates a new `NoopMeterProvider`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ProvideMeter for NoopMeterProvider {
+    fn meter(&self, _scope: &'static str) -> SharedMeter {
+        NoopMeter.into_shared()
+    }
+}
+
+#[derive(Debug)]
+struct NoopMeter;
+
+impl Meter for NoopMeter {
+    fn create_counter(&self, _descriptor: InstrumentDescriptor) -> SharedCounter {
+        NoopInstrument.into_shared()
+    }
+
+    fn create_histogram(&self, _descriptor: InstrumentDescriptor) -> SharedHistogram {
+        NoopInstrument.into_shared()
+    }
+}
+
+#[derive(Debug)]
+struct NoopInstrument;
+
+impl Counter for NoopInstrument {
+    fn add(&self, _value: u64, _attributes: &Attributes) {}
+}
+
+impl Histogram for NoopInstrument {
+    fn record(&self, _value: f64, _attributes: &Attributes) {}
+}
@@ -0,0 +1,201 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_runtime_api::client::telemetry::{
+    Attributes, Counter, Histogram, InstrumentDescriptor, Meter, ProvideMeter, SharedCounter,
+    SharedHistogram, SharedMeter,
+};
+use aws_smithy_runtime_api::shared::IntoShared;
+use std::sync::{Arc, Mutex};
+
+/// A value recorded to a metric instrument.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetricValue {
+    /// A value added to a [`Counter`].
+    Count(u64),
+    /// A value recorded to a [`Histogram`].
+    Measurement(f64),
+}
+
+/// A single recording made to an [`InMemoryMeterProvider`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedMetric {
+    scope: &'static str,
+    descriptor: InstrumentDescriptor,
+    value: MetricValue,
+    attributes: Attributes,
+}
+
+impl RecordedMetric {
+    /// The scope of the meter that created the instrument.
+    pub fn scope(&self) -> &'static str {
+        self.scope
+    }
+
+    /// The name of the instrument.
+    pub fn name(&self) -> &'static str {
+        self.descriptor.name()
+    }
+
+    /// The descriptor of the instrument.
+    pub fn descriptor(&self) -> &InstrumentDescriptor {
+        &self.descriptor
+    }
+
+    /// The recorded value.
+    pub fn value(&self) -> &MetricValue {
+        &self.value
+    }
+
+    /// The attributes the value was recorded with.
+    pub fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+}
+
+type Recordings = Arc<Mutex<Vec<RecordedMetric>>>;
+
+/// A [`ProvideMeter`] implementation that keeps every recording in memory.
+///
+/// Clones share the same recordings, so a clone can be given to a client while
+/// the original is used to make assertions.
+///
+/// # Examples
+/// ```
+/// use aws_smithy_runtime::client::telemetry::test_util::InMemoryMeterProvider;
+/// use aws_smithy_runtime_api::client::telemetry::{
+///     Attributes, Counter, InstrumentDescriptor, Meter, ProvideMeter,
+/// };
+///
+/// let provider = InMemoryMeterProvider::new();
+/// let counter = provider
+///     .meter("example")
+///     .create_counter(InstrumentDescriptor::new("example.count"));
+/// counter.add(2, &Attributes::new());
+/// counter.add(3, &Attributes::new());
+///
+/// assert_eq!(5, provider.counter_total("example.count"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryMeterProvider {
+    recordings: Recordings,
+}
+
+impl InMemoryMeterProvider {
+    /// Creates a new `InMemoryMeterProvider` with no recordings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all recordings, in the order they were made.
+    pub fn recordings(&self) -> Vec<RecordedMetric> {
+        self.recordings.lock().unwrap().clone()
+    }
+
+    /// Returns all recordings made to instruments with the given `name`.
+    pub fn recordings_for(&self, name: &str) -> Vec<RecordedMetric> {
+        self.recordings
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|recording| recording.name() == name)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the sum of all values added to counters with the given `name`.
+    pub fn counter_total(&self, name: &str) -> u64 {
+        self.recordings_for(name)
+            .iter()
+            .filter_map(|recording| match recording.value {
+                MetricValue::Count(value) => Some(value),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Returns all values recorded to histograms with the given `name`.
+    pub fn histogram_values(&self, name: &str) -> Vec<f64> {
+        self.recordings_for(name)
+            .iter()
+            .filter_map(|recording| match recording.value {
+                MetricValue::Measurement(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Discards all recordings.
+    pub fn clear(&self) {
+        self.recordings.lock().unwrap().clear();
+    }
+}
+
+impl ProvideMeter for InMemoryMeterProvider {
+    fn meter(&self, scope: &'static str) -> SharedMeter {
+        InMemoryMeter {
+            scope,
+            recordings: self.recordings.clone(),
+        }
+        .into_shared()
+    }
+}
+
+#[derive(Debug)]
+struct InMemoryMeter {
+    scope: &'static str,
+    recordings: Recordings,
+}
+
+impl InMemoryMeter {
+    fn instrument(&self, descriptor: InstrumentDescriptor) -> InMemoryInstrument {
+        InMemoryInstrument {
+            scope: self.scope,
+            descriptor,
+            recordings: self.recordings.clone(),
+        }
+    }
+}
+
+impl Meter for InMemoryMeter {
+    fn create_counter(&self, descriptor: InstrumentDescriptor) -> SharedCounter {
+        self.instrument(descriptor).into_shared()
+    }
+
+    fn create_histogram(&self, descriptor: InstrumentDescriptor) -> SharedHistogram {
+        self.instrument(descriptor).into_shared()
+    }
+}
+
+#[derive(Debug)]
+struct InMemoryInstrument {
+    scope: &'static str,
+    descriptor: InstrumentDescriptor,
+    recordings: Recordings,
+}
+
+impl InMemoryInstrument {
+    fn push(&self, value: MetricValue, attributes: &Attributes) {
+        self.recordings.lock().unwrap().push(RecordedMetric {
+            scope: self.scope,
+            descriptor: self.descriptor,
+            value,
+            attributes: attributes.clone(),
+        });
+    }
+}
+
+impl Counter for InMemoryInstrument {
+    fn add(&self, value: u64, attributes: &Attributes) {
+        self.push(MetricValue::Count(value), attributes);
+    }
+}
+
+impl Histogram for InMemoryInstrument {
+    fn record(&self, value: f64, attributes: &Attributes) {
+        self.push(MetricValue::Measurement(value), attributes);
+    }
+}
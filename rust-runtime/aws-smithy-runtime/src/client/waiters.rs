@@ -0,0 +1,379 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Waiters repeatedly poll an operation until the resource it describes reaches a desired state.
+//!
+//! Generated clients expose waiters as `wait_until_*` methods for operations modeled with the
+//! [`@waitable` trait](https://smithy.io/2.0/additional-specs/waiters.html). Each waiter is run by
+//! a [`WaiterOrchestrator`], which polls the operation and passes each result to an acceptor
+//! function that decides whether to keep waiting. See the [`acceptors`] module for the matchers
+//! that acceptor functions are built from.
+
+use crate::client::waiters::acceptors::AcceptorState;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::{SharedTimeSource, SystemTimeSource, TimeSource};
+use aws_smithy_runtime_api::client::waiters::error::{
+    ExceededMaxWait, FailureState, OperationFailed, WaiterError,
+};
+use aws_smithy_runtime_api::client::waiters::FinalPoll;
+use aws_smithy_runtime_api::shared::IntoShared;
+use std::future::Future;
+use std::time::Duration;
+
+pub mod acceptors;
+mod backoff;
+pub mod jmespath;
+
+/// The default minimum delay between polls, used when the waiter doesn't define one.
+pub const DEFAULT_MIN_DELAY: Duration = Duration::from_secs(2);
+
+/// The default maximum delay between polls, used when the waiter doesn't define one.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(120);
+
+/// Polls an operation until an acceptor transitions it into a success or failure state,
+/// or until the max wait time is exceeded.
+///
+/// Between polls, the orchestrator sleeps for a jittered, exponentially increasing delay between
+/// the min and max delay.
+#[derive(Debug)]
+pub struct WaiterOrchestrator<AcceptorFn, OperationFn> {
+    min_delay: Duration,
+    max_delay: Duration,
+    max_wait: Duration,
+    time_source: SharedTimeSource,
+    sleep_impl: Option<SharedAsyncSleep>,
+    acceptor_fn: AcceptorFn,
+    operation_fn: OperationFn,
+}
+
+impl WaiterOrchestrator<(), ()> {
+    /// Creates a builder for a `WaiterOrchestrator`.
+    pub fn builder() -> WaiterOrchestratorBuilder<(), ()> {
+        WaiterOrchestratorBuilder::default()
+    }
+}
+
+impl<AcceptorFn, OperationFn> WaiterOrchestrator<AcceptorFn, OperationFn> {
+    /// Polls the operation until the waiter reaches a terminal state.
+    ///
+    /// Returns the final poll if the waiter reached the success state. Otherwise, returns an error
+    /// describing why waiting stopped.
+    pub async fn orchestrate<O, E, Fut>(self) -> Result<FinalPoll<O, E>, WaiterError<O, E>>
+    where
+        AcceptorFn: Fn(Result<&O, &E>) -> AcceptorState,
+        OperationFn: Fn() -> Fut,
+        Fut: Future<Output = Result<O, E>>,
+    {
+        let sleep_impl = self.sleep_impl.ok_or_else(|| {
+            WaiterError::construction_failure(
+                "waiters require a sleep implementation. Enable the `rt-tokio` feature or configure one on the client",
+            )
+        })?;
+        let start = self.time_source.now();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = (self.operation_fn)().await;
+            match (self.acceptor_fn)(result.as_ref()) {
+                AcceptorState::Success => {
+                    tracing::debug!(attempt, "waiter succeeded");
+                    return Ok(FinalPoll::new(result));
+                }
+                AcceptorState::Failure => {
+                    tracing::debug!(attempt, "waiter entered a failure state");
+                    return Err(WaiterError::FailureState(FailureState::new(
+                        FinalPoll::new(result),
+                    )));
+                }
+                AcceptorState::NoAcceptorsMatched if result.is_err() => {
+                    let err = result.err().expect("checked above");
+                    return Err(WaiterError::OperationFailed(OperationFailed::new(err)));
+                }
+                AcceptorState::NoAcceptorsMatched | AcceptorState::Retry => {}
+            }
+
+            let elapsed = self
+                .time_source
+                .now()
+                .duration_since(start)
+                .unwrap_or_default();
+            let remaining = self.max_wait.saturating_sub(elapsed);
+            match backoff::compute_delay(
+                self.min_delay,
+                self.max_delay,
+                attempt,
+                remaining,
+                backoff::jitter,
+            ) {
+                Some(delay) => {
+                    tracing::debug!(attempt, ?delay, "waiting before polling again");
+                    sleep_impl.sleep(delay).await;
+                }
+                None => {
+                    return Err(WaiterError::ExceededMaxWait(ExceededMaxWait::new(
+                        self.max_wait,
+                        elapsed,
+                        attempt,
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Builder for [`WaiterOrchestrator`].
+#[derive(Debug)]
+pub struct WaiterOrchestratorBuilder<AcceptorFn, OperationFn> {
+    min_delay: Duration,
+    max_delay: Duration,
+    max_wait: Option<Duration>,
+    time_source: Option<SharedTimeSource>,
+    sleep_impl: Option<SharedAsyncSleep>,
+    acceptor_fn: Option<AcceptorFn>,
+    operation_fn: Option<OperationFn>,
+}
+
+impl Default for WaiterOrchestratorBuilder<(), ()> {
+    fn default() -> Self {
+        Self {
+            min_delay: DEFAULT_MIN_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            max_wait: None,
+            time_source: None,
+            sleep_impl: None,
+            acceptor_fn: None,
+            operation_fn: None,
+        }
+    }
+}
+
+impl<AcceptorFn, OperationFn> WaiterOrchestratorBuilder<AcceptorFn, OperationFn> {
+    /// Sets the minimum delay between polls. Defaults to [`DEFAULT_MIN_DELAY`].
+    pub fn min_delay(mut self, min_delay: Duration) -> Self {
+        self.min_delay = min_delay;
+        self
+    }
+
+    /// Sets the maximum delay between polls. Defaults to [`DEFAULT_MAX_DELAY`].
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets the maximum amount of time to wait for the waiter to reach a terminal state.
+    pub fn max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = Some(max_wait);
+        self
+    }
+
+    /// Sets the time source used to track how long the waiter has been waiting.
+    ///
+    /// Defaults to the system time.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.set_time_source(Some(time_source.into_shared()));
+        self
+    }
+
+    /// Sets the time source used to track how long the waiter has been waiting.
+    ///
+    /// Defaults to the system time.
+    pub fn set_time_source(&mut self, time_source: Option<SharedTimeSource>) -> &mut Self {
+        self.time_source = time_source;
+        self
+    }
+
+    /// Sets the sleep implementation used to wait between polls.
+    pub fn sleep_impl(mut self, sleep_impl: impl AsyncSleep + 'static) -> Self {
+        self.set_sleep_impl(Some(sleep_impl.into_shared()));
+        self
+    }
+
+    /// Sets the sleep implementation used to wait between polls.
+    pub fn set_sleep_impl(&mut self, sleep_impl: Option<SharedAsyncSleep>) -> &mut Self {
+        self.sleep_impl = sleep_impl;
+        self
+    }
+
+    /// Sets the function that decides which state the waiter transitions to after each poll.
+    pub fn acceptor<NewAcceptorFn>(
+        self,
+        acceptor_fn: NewAcceptorFn,
+    ) -> WaiterOrchestratorBuilder<NewAcceptorFn, OperationFn> {
+        WaiterOrchestratorBuilder {
+            min_delay: self.min_delay,
+            max_delay: self.max_delay,
+            max_wait: self.max_wait,
+            time_source: self.time_source,
+            sleep_impl: self.sleep_impl,
+            acceptor_fn: Some(acceptor_fn),
+            operation_fn: self.operation_fn,
+        }
+    }
+
+    /// Sets the function that polls the operation.
+    pub fn operation<NewOperationFn>(
+        self,
+        operation_fn: NewOperationFn,
+    ) -> WaiterOrchestratorBuilder<AcceptorFn, NewOperationFn> {
+        WaiterOrchestratorBuilder {
+            min_delay: self.min_delay,
+            max_delay: self.max_delay,
+            max_wait: self.max_wait,
+            time_source: self.time_source,
+            sleep_impl: self.sleep_impl,
+            acceptor_fn: self.acceptor_fn,
+            operation_fn: Some(operation_fn),
+        }
+    }
+
+    /// Builds the [`WaiterOrchestrator`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max wait time, acceptor function or operation function weren't set.
+    pub fn build(self) -> WaiterOrchestrator<AcceptorFn, OperationFn> {
+        WaiterOrchestrator {
+            min_delay: self.min_delay,
+            max_delay: self.max_delay,
+            max_wait: self.max_wait.expect("max_wait is required"),
+            time_source: self
+                .time_source
+                .unwrap_or_else(|| SystemTimeSource::new().into_shared()),
+            sleep_impl: self.sleep_impl,
+            acceptor_fn: self.acceptor_fn.expect("an acceptor function is required"),
+            operation_fn: self
+                .operation_fn
+                .expect("an operation function is required"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_async::test_util::instant_time_and_sleep;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::UNIX_EPOCH;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Status {
+        Creating,
+        Active,
+        Deleted,
+    }
+
+    /// Returns the statuses in order, repeating the last one forever.
+    fn poll_statuses(
+        statuses: Vec<Result<Status, &'static str>>,
+    ) -> impl Fn() -> std::future::Ready<Result<Status, &'static str>> {
+        let count = AtomicUsize::new(0);
+        move || {
+            let index = count.fetch_add(1, Ordering::SeqCst).min(statuses.len() - 1);
+            std::future::ready(statuses[index].clone())
+        }
+    }
+
+    fn acceptor(result: Result<&Status, &&'static str>) -> AcceptorState {
+        match result {
+            Ok(Status::Active) => AcceptorState::Success,
+            Ok(Status::Deleted) => AcceptorState::Failure,
+            Err(&"NotFound") => AcceptorState::Retry,
+            _ => AcceptorState::NoAcceptorsMatched,
+        }
+    }
+
+    async fn wait(
+        statuses: Vec<Result<Status, &'static str>>,
+        max_wait: Duration,
+    ) -> (
+        Result<FinalPoll<Status, &'static str>, WaiterError<Status, &'static str>>,
+        Vec<Duration>,
+    ) {
+        let (time_source, sleep_impl) = instant_time_and_sleep(UNIX_EPOCH);
+        let result = WaiterOrchestrator::builder()
+            .min_delay(Duration::from_secs(2))
+            .max_delay(Duration::from_secs(10))
+            .max_wait(max_wait)
+            .time_source(time_source)
+            .sleep_impl(sleep_impl.clone())
+            .acceptor(acceptor)
+            .operation(poll_statuses(statuses))
+            .build()
+            .orchestrate()
+            .await;
+        (result, sleep_impl.logs())
+    }
+
+    #[tokio::test]
+    async fn success_after_retries() {
+        let (result, sleeps) = wait(
+            vec![Err("NotFound"), Ok(Status::Creating), Ok(Status::Active)],
+            Duration::from_secs(300),
+        )
+        .await;
+        assert_eq!(Ok(&Status::Active), result.unwrap().as_result());
+        assert_eq!(2, sleeps.len());
+        // The first delay is always the min delay, and the second is jittered between 2s and 4s
+        assert_eq!(Duration::from_secs(2), sleeps[0]);
+        assert!(sleeps[1] >= Duration::from_secs(2) && sleeps[1] <= Duration::from_secs(4));
+    }
+
+    #[tokio::test]
+    async fn failure_state() {
+        let (result, _) = wait(
+            vec![Ok(Status::Creating), Ok(Status::Deleted)],
+            Duration::from_secs(300),
+        )
+        .await;
+        match result.unwrap_err() {
+            WaiterError::FailureState(ctx) => {
+                assert_eq!(Ok(&Status::Deleted), ctx.final_poll().as_result())
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn unmatched_error_fails_the_waiter() {
+        let (result, sleeps) = wait(
+            vec![Ok(Status::Creating), Err("AccessDenied")],
+            Duration::from_secs(300),
+        )
+        .await;
+        match result.unwrap_err() {
+            WaiterError::OperationFailed(ctx) => assert_eq!("AccessDenied", *ctx.error()),
+            err => panic!("unexpected error: {err:?}"),
+        }
+        assert_eq!(1, sleeps.len());
+    }
+
+    #[tokio::test]
+    async fn exceeded_max_wait() {
+        let (result, sleeps) = wait(vec![Ok(Status::Creating)], Duration::from_secs(30)).await;
+        match result.unwrap_err() {
+            WaiterError::ExceededMaxWait(ctx) => {
+                assert_eq!(Duration::from_secs(30), ctx.max_wait());
+                assert_eq!(sleeps.len() as u32 + 1, ctx.poll_count());
+                // The waiter stops once less than the min delay is left
+                assert!(ctx.elapsed() >= Duration::from_secs(28));
+                assert!(ctx.elapsed() <= Duration::from_secs(30));
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+        assert!(sleeps.iter().all(|delay| *delay <= Duration::from_secs(10)));
+    }
+
+    #[tokio::test]
+    async fn missing_sleep_impl() {
+        let result = WaiterOrchestrator::builder()
+            .max_wait(Duration::from_secs(30))
+            .acceptor(acceptor)
+            .operation(poll_statuses(vec![Ok(Status::Active)]))
+            .build()
+            .orchestrate()
+            .await;
+        assert!(matches!(result, Err(WaiterError::ConstructionFailure(_))));
+    }
+}
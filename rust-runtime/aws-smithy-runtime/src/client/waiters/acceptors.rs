@@ -0,0 +1,223 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Matchers that decide which state a waiter transitions to after each poll.
+//!
+//! Each waiter has an ordered list of acceptors, which pair a matcher with the
+//! [`AcceptorState`] to transition to when it matches. The first acceptor that matches
+//! decides the state.
+
+use crate::client::waiters::jmespath::{Expression, ParseError, ToJmespathValue, Value};
+use aws_smithy_types::error::metadata::ProvideErrorMetadata;
+
+/// The state a waiter transitions to after polling an operation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AcceptorState {
+    /// None of the acceptors matched the poll result.
+    ///
+    /// The waiter keeps polling if the operation succeeded, and fails if the operation returned an error.
+    NoAcceptorsMatched,
+    /// The resource reached the desired state, so the waiter stops successfully.
+    Success,
+    /// The resource reached a state that it won't transition out of, so the waiter fails.
+    Failure,
+    /// The resource hasn't reached the desired state yet, so the waiter keeps polling.
+    Retry,
+}
+
+/// Returns true if the result is `Ok` when `expected` is true, or `Err` when `expected` is false.
+///
+/// This implements the `success` matcher.
+pub fn match_success<O, E>(result: Result<&O, &E>, expected: bool) -> bool {
+    result.is_ok() == expected
+}
+
+/// Returns true if the result is an error with the given error code.
+///
+/// This implements the `errorType` matcher.
+pub fn match_error_type<O, E: ProvideErrorMetadata>(result: Result<&O, &E>, code: &str) -> bool {
+    match result {
+        Ok(_) => false,
+        Err(err) => err.code() == Some(code),
+    }
+}
+
+/// How the value selected by a [`PathMatcher`] is compared to its expected value.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PathComparator {
+    /// Matches if the value is a string equal to the expected value.
+    StringEquals,
+    /// Matches if the value is a boolean equal to the expected value.
+    BooleanEquals,
+    /// Matches if the value is a non-empty array and every element is a string equal to the expected value.
+    AllStringEquals,
+    /// Matches if the value is an array and any element is a string equal to the expected value.
+    AnyStringEquals,
+}
+
+/// Matches the value selected by a JMESPath expression against an expected value.
+///
+/// This implements the `output` and `inputOutput` matchers.
+#[derive(Clone, Debug)]
+pub struct PathMatcher {
+    path: Expression,
+    expected: String,
+    comparator: PathComparator,
+}
+
+impl PathMatcher {
+    /// Creates a new `PathMatcher`, failing if `path` isn't a valid JMESPath expression.
+    pub fn new(
+        path: &str,
+        expected: impl Into<String>,
+        comparator: PathComparator,
+    ) -> Result<Self, ParseError> {
+        Ok(Self {
+            path: Expression::parse(path)?,
+            expected: expected.into(),
+            comparator,
+        })
+    }
+
+    /// Returns true if the value selected from `data` matches the expected value.
+    pub fn matches(&self, data: &(impl ToJmespathValue + ?Sized)) -> bool {
+        let value = self.path.search(data);
+        let expected = self.expected.as_str();
+        let is_expected = |value: &Value<'_>| value.as_str() == Some(expected);
+        match self.comparator {
+            PathComparator::StringEquals => is_expected(&value),
+            PathComparator::BooleanEquals => {
+                matches!((value.as_bool(), expected.parse::<bool>()), (Some(value), Ok(expected)) if value == expected)
+            }
+            PathComparator::AllStringEquals => match value.as_array() {
+                Some(values) => !values.is_empty() && values.iter().all(is_expected),
+                None => false,
+            },
+            PathComparator::AnyStringEquals => match value.as_array() {
+                Some(values) => values.iter().any(is_expected),
+                None => false,
+            },
+        }
+    }
+
+    /// Returns true if the operation succeeded and the value selected from its output matches.
+    pub fn matches_output<O: ToJmespathValue, E>(&self, result: Result<&O, &E>) -> bool {
+        match result {
+            Ok(output) => self.matches(output),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns true if the operation succeeded and the value selected from its input and output matches.
+    ///
+    /// The expression is evaluated against an object with `input` and `output` fields.
+    pub fn matches_input_output<I: ToJmespathValue, O: ToJmespathValue, E>(
+        &self,
+        input: &I,
+        result: Result<&O, &E>,
+    ) -> bool {
+        match result {
+            Ok(output) => self.matches(&Value::object([
+                ("input", input.to_jmespath_value()),
+                ("output", output.to_jmespath_value()),
+            ])),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_types::error::ErrorMetadata;
+
+    #[derive(Debug)]
+    struct TestError(ErrorMetadata);
+
+    impl ProvideErrorMetadata for TestError {
+        fn meta(&self) -> &ErrorMetadata {
+            &self.0
+        }
+    }
+
+    fn output(status: &str, replicas: &[&'static str]) -> Value<'static> {
+        Value::Object(vec![
+            ("Status".into(), Value::String(status.to_string().into())),
+            (
+                "Replicas".into(),
+                Value::Array(replicas.iter().map(|r| Value::string(r)).collect()),
+            ),
+            ("Enabled".into(), Value::Bool(true)),
+        ])
+    }
+
+    #[test]
+    fn success_and_error_type_matchers() {
+        let ok: Result<&(), &TestError> = Ok(&());
+        let err_meta = TestError(ErrorMetadata::builder().code("ResourceNotFound").build());
+        let err: Result<&(), &TestError> = Err(&err_meta);
+
+        assert!(match_success(ok, true));
+        assert!(!match_success(err, true));
+        assert!(match_success(err, false));
+        assert!(match_error_type(err, "ResourceNotFound"));
+        assert!(!match_error_type(err, "Throttling"));
+        assert!(!match_error_type(ok, "ResourceNotFound"));
+    }
+
+    #[test]
+    fn path_comparators() {
+        let matcher =
+            |path, expected, comparator| PathMatcher::new(path, expected, comparator).unwrap();
+        let active = output("ACTIVE", &["ACTIVE", "ACTIVE"]);
+        let mixed = output("ACTIVE", &["ACTIVE", "FAILED"]);
+        let empty = output("CREATING", &[]);
+
+        let string_equals = matcher("Status", "ACTIVE", PathComparator::StringEquals);
+        assert!(string_equals.matches(&active));
+        assert!(!string_equals.matches(&empty));
+
+        let boolean_equals = matcher("Enabled", "true", PathComparator::BooleanEquals);
+        assert!(boolean_equals.matches(&active));
+        assert!(!matcher("Status", "true", PathComparator::BooleanEquals).matches(&active));
+
+        let all = matcher("Replicas", "ACTIVE", PathComparator::AllStringEquals);
+        assert!(all.matches(&active));
+        assert!(!all.matches(&mixed));
+        assert!(!all.matches(&empty));
+
+        let any = matcher("Replicas", "FAILED", PathComparator::AnyStringEquals);
+        assert!(any.matches(&mixed));
+        assert!(!any.matches(&active));
+        assert!(!any.matches(&empty));
+    }
+
+    #[test]
+    fn output_and_input_output_matchers() {
+        let input = Value::object([("Name", Value::string("test"))]);
+        let output = Value::object([("Name", Value::string("test"))]);
+        let ok: Result<&Value<'_>, &()> = Ok(&output);
+        let err: Result<&Value<'_>, &()> = Err(&());
+
+        let matcher = PathMatcher::new(
+            "input.Name == output.Name",
+            "true",
+            PathComparator::BooleanEquals,
+        )
+        .unwrap();
+        assert!(matcher.matches_input_output(&input, ok));
+        assert!(!matcher.matches_input_output(&input, err));
+
+        let matcher = PathMatcher::new("Name", "test", PathComparator::StringEquals).unwrap();
+        assert!(matcher.matches_output(ok));
+        assert!(!matcher.matches_output(err));
+    }
+
+    #[test]
+    fn invalid_path() {
+        assert!(PathMatcher::new("Status ==", "ACTIVE", PathComparator::StringEquals).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::time::Duration;
+
+/// Computes how long to wait before the next poll, following the
+/// [waiter retry behavior](https://smithy.io/2.0/additional-specs/waiters.html#waiter-retries)
+/// in the Smithy spec.
+///
+/// `attempt` is the number of polls made so far, and `remaining` is how much of the max wait
+/// time is left. The delay grows exponentially from `min_delay` up to `max_delay`, and is
+/// jittered with `random`, which must return a duration between its two arguments. Returns
+/// `None` if there isn't more than `min_delay` left for another poll.
+pub(super) fn compute_delay(
+    min_delay: Duration,
+    max_delay: Duration,
+    attempt: u32,
+    remaining: Duration,
+    random: impl FnOnce(Duration, Duration) -> Duration,
+) -> Option<Duration> {
+    if remaining <= min_delay {
+        return None;
+    }
+    let attempt_ceiling = (max_delay.as_secs_f64() / min_delay.as_secs_f64()).log2() + 1.0;
+    let upper_bound = if f64::from(attempt) > attempt_ceiling {
+        max_delay
+    } else {
+        2u32.checked_pow(attempt.saturating_sub(1))
+            .and_then(|multiplier| min_delay.checked_mul(multiplier))
+            .map(|delay| delay.min(max_delay))
+            .unwrap_or(max_delay)
+    };
+    let delay = random(min_delay, upper_bound);
+    if remaining.saturating_sub(delay) <= min_delay {
+        Some(remaining.saturating_sub(min_delay))
+    } else {
+        Some(delay)
+    }
+}
+
+/// Returns a uniformly random duration between `min` and `max`.
+pub(super) fn jitter(min: Duration, max: Duration) -> Duration {
+    min + max.saturating_sub(min).mul_f64(fastrand::f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: Duration = Duration::from_secs(2);
+    const MAX: Duration = Duration::from_secs(120);
+    const PLENTY: Duration = Duration::from_secs(3600);
+
+    fn upper(_min: Duration, max: Duration) -> Duration {
+        max
+    }
+
+    #[test]
+    fn delay_grows_exponentially_up_to_max_delay() {
+        let delays: Vec<_> = (1..=9)
+            .map(|attempt| {
+                compute_delay(MIN, MAX, attempt, PLENTY, upper)
+                    .unwrap()
+                    .as_secs()
+            })
+            .collect();
+        assert_eq!(vec![2, 4, 8, 16, 32, 64, 120, 120, 120], delays);
+        // Large attempt counts don't overflow
+        assert_eq!(Some(MAX), compute_delay(MIN, MAX, u32::MAX, PLENTY, upper));
+    }
+
+    #[test]
+    fn delay_is_jittered_from_min_delay() {
+        let mut bounds = None;
+        let delay = compute_delay(MIN, MAX, 4, PLENTY, |min, max| {
+            bounds = Some((min, max));
+            Duration::from_secs(5)
+        });
+        assert_eq!(Some((MIN, Duration::from_secs(16))), bounds);
+        assert_eq!(Some(Duration::from_secs(5)), delay);
+
+        for _ in 0..100 {
+            let delay = jitter(MIN, MAX);
+            assert!(delay >= MIN && delay <= MAX, "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn delay_is_limited_by_remaining_time() {
+        // The last poll is scheduled so that it leaves min_delay of the remaining time
+        assert_eq!(
+            Some(Duration::from_secs(8)),
+            compute_delay(MIN, MAX, 5, Duration::from_secs(10), upper)
+        );
+        assert_eq!(None, compute_delay(MIN, MAX, 1, MIN, upper));
+        assert_eq!(
+            None,
+            compute_delay(MIN, MAX, 1, Duration::from_secs(1), upper)
+        );
+    }
+}
@@ -0,0 +1,257 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [JMESPath](https://jmespath.org/specification.html) evaluator used by waiter acceptors to
+//! select values out of operation inputs and outputs.
+//!
+//! Expressions are evaluated against types implementing [`ToJmespathValue`], which is implemented
+//! by generated shapes. Expression references (`&expr`) and the functions that take them
+//! (`sort_by`, `max_by`, etc.) aren't supported. The supported functions are `length`, `contains`,
+//! `starts_with`, `ends_with`, `keys`, `values`, `type` and `not_null`.
+
+use std::borrow::Cow;
+use std::fmt;
+
+mod interpreter;
+mod lexer;
+mod parser;
+mod value;
+
+pub use value::{ToJmespathValue, Value};
+
+/// An error that occurred while parsing a JMESPath expression.
+#[derive(Debug)]
+pub struct ParseError {
+    offset: usize,
+    message: Cow<'static, str>,
+}
+
+impl ParseError {
+    fn new(offset: usize, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    /// The offset in the expression at which the error occurred.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to parse JMESPath expression at offset {}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A compiled JMESPath expression.
+#[derive(Clone, Debug)]
+pub struct Expression {
+    expression: String,
+    ast: parser::Ast,
+}
+
+impl Expression {
+    /// Parses a JMESPath expression.
+    pub fn parse(expression: impl Into<String>) -> Result<Self, ParseError> {
+        let expression = expression.into();
+        let ast = parser::parse(&expression)?;
+        Ok(Self { expression, ast })
+    }
+
+    /// Returns the text of the expression.
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    /// Evaluates the expression against `data`.
+    pub fn search<'a>(&self, data: &'a (impl ToJmespathValue + ?Sized)) -> Value<'a> {
+        interpreter::interpret(&self.ast, &data.to_jmespath_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct Table {
+        name: String,
+        status: Option<&'static str>,
+        replicas: Vec<Replica>,
+        tags: HashMap<String, String>,
+    }
+
+    struct Replica {
+        region: &'static str,
+        status: &'static str,
+        size: Option<i64>,
+    }
+
+    impl ToJmespathValue for Table {
+        fn to_jmespath_value(&self) -> Value<'_> {
+            Value::object([
+                ("Name", self.name.to_jmespath_value()),
+                ("Status", self.status.to_jmespath_value()),
+                ("Replicas", self.replicas.to_jmespath_value()),
+                ("Tags", self.tags.to_jmespath_value()),
+            ])
+        }
+    }
+
+    impl ToJmespathValue for Replica {
+        fn to_jmespath_value(&self) -> Value<'_> {
+            Value::object([
+                ("Region", self.region.to_jmespath_value()),
+                ("Status", self.status.to_jmespath_value()),
+                ("Size", self.size.to_jmespath_value()),
+            ])
+        }
+    }
+
+    fn table() -> Table {
+        Table {
+            name: "test".into(),
+            status: Some("ACTIVE"),
+            replicas: vec![
+                Replica {
+                    region: "us-east-1",
+                    status: "ACTIVE",
+                    size: Some(10),
+                },
+                Replica {
+                    region: "us-west-2",
+                    status: "CREATING",
+                    size: None,
+                },
+                Replica {
+                    region: "eu-west-1",
+                    status: "ACTIVE",
+                    size: Some(30),
+                },
+            ],
+            tags: HashMap::from([("team".to_string(), "storage".to_string())]),
+        }
+    }
+
+    #[track_caller]
+    fn search(expression: &str) -> Value<'static> {
+        Expression::parse(expression)
+            .unwrap()
+            .search(&table())
+            .into_owned()
+    }
+
+    #[track_caller]
+    fn json(literal: &str) -> Value<'static> {
+        Expression::parse(format!("`{literal}`"))
+            .unwrap()
+            .search(&Value::Null)
+            .into_owned()
+    }
+
+    #[test]
+    fn fields_and_indexes() {
+        assert_eq!(json(r#""ACTIVE""#), search("Status"));
+        assert_eq!(json(r#""storage""#), search("Tags.team"));
+        assert_eq!(json(r#""eu-west-1""#), search("Replicas[-1].Region"));
+        assert_eq!(json("null"), search("Replicas[3].Region"));
+        assert_eq!(json("null"), search("Missing.Field"));
+        assert_eq!(json("null"), search("Name.Field"));
+    }
+
+    #[test]
+    fn projections() {
+        assert_eq!(
+            json(r#"["ACTIVE", "CREATING", "ACTIVE"]"#),
+            search("Replicas[].Status")
+        );
+        // Null values are removed from projections
+        assert_eq!(json("[10, 30]"), search("Replicas[*].Size"));
+        assert_eq!(json(r#"["storage"]"#), search("Tags.*"));
+        assert_eq!(
+            json(r#"["us-east-1", "eu-west-1"]"#),
+            search("Replicas[?Status == 'ACTIVE'].Region")
+        );
+        assert_eq!(
+            json(r#"["eu-west-1"]"#),
+            search("Replicas[?Size > `20`].Region")
+        );
+        assert_eq!(
+            json(r#""us-west-2""#),
+            search("Replicas[?!Size].Region | [0]")
+        );
+        assert_eq!(
+            json(r#"["us-east-1", "eu-west-1"]"#),
+            search("Replicas[::2].Region")
+        );
+        assert_eq!(
+            json(r#"["eu-west-1", "us-west-2"]"#),
+            search("Replicas[:0:-1].Region")
+        );
+        // Stepping past the end of the array stops the slice instead of overflowing
+        assert_eq!(
+            json(r#"["us-west-2"]"#),
+            search("Replicas[1::9223372036854775807].Region")
+        );
+        assert_eq!(json("[[1, 2], 3]"), search("`[[[1, 2]], [3]]`[]"));
+    }
+
+    #[test]
+    fn logic_and_comparisons() {
+        assert_eq!(json("true"), search("Status == 'ACTIVE' && Name == 'test'"));
+        assert_eq!(json(r#""test""#), search("Missing || Name"));
+        assert_eq!(json("null"), search("Missing && Name"));
+        assert_eq!(json("false"), search("!Name"));
+        assert_eq!(json("null"), search("Name < `1`"));
+        assert_eq!(json("true"), search("Tags == `{\"team\": \"storage\"}`"));
+    }
+
+    #[test]
+    fn multi_selects() {
+        assert_eq!(json(r#"["test", "ACTIVE"]"#), search("[Name, Status]"));
+        assert_eq!(
+            json(r#"{"n": "test", "count": 3}"#),
+            search("{n: Name, count: length(Replicas)}")
+        );
+        assert_eq!(json("null"), search("Missing.[Name]"));
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(json("4"), search("length(Name)"));
+        assert_eq!(
+            json("true"),
+            search("contains(Replicas[].Status, 'CREATING')")
+        );
+        assert_eq!(json("true"), search("contains(Name, 'es')"));
+        assert_eq!(json("true"), search("starts_with(Name, 'te')"));
+        assert_eq!(json("false"), search("ends_with(Name, 'te')"));
+        assert_eq!(json(r#"["team"]"#), search("keys(Tags)"));
+        assert_eq!(json(r#"["storage"]"#), search("values(Tags)"));
+        assert_eq!(json(r#""array""#), search("type(Replicas)"));
+        assert_eq!(json(r#""test""#), search("not_null(Missing, Name)"));
+        // Type errors evaluate to null
+        assert_eq!(json("null"), search("length(`1`)"));
+        assert_eq!(json("null"), search("unknown(Name)"));
+    }
+
+    #[test]
+    fn parse_error_display() {
+        let err = Expression::parse("Replicas[?Status ==").unwrap_err();
+        assert_eq!(
+            "failed to parse JMESPath expression at offset 19: unexpected token Eof",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,220 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::parser::{Ast, Comparator};
+use super::value::Value;
+use std::borrow::Cow;
+
+/// Evaluates `ast` against `data`.
+///
+/// Type errors, such as passing a number to `length`, evaluate to null rather than failing.
+pub(super) fn interpret<'a>(ast: &Ast, data: &Value<'a>) -> Value<'a> {
+    match ast {
+        Ast::Identity => data.clone(),
+        Ast::Field(name) => match data {
+            Value::Object(fields) => fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        },
+        Ast::Subexpr(left, right) => interpret(right, &interpret(left, data)),
+        Ast::Index(index) => match data {
+            Value::Array(values) => {
+                let index = if *index < 0 {
+                    values.len() as i64 + index
+                } else {
+                    *index
+                };
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|index| values.get(index))
+                    .cloned()
+                    .unwrap_or(Value::Null)
+            }
+            _ => Value::Null,
+        },
+        Ast::Slice { start, stop, step } => match data {
+            Value::Array(values) => Value::Array(slice(values, *start, *stop, *step)),
+            _ => Value::Null,
+        },
+        Ast::Projection(left, right) => match interpret(left, data) {
+            Value::Array(values) => Value::Array(
+                values
+                    .iter()
+                    .map(|value| interpret(right, value))
+                    .filter(|value| !value.is_null())
+                    .collect(),
+            ),
+            _ => Value::Null,
+        },
+        Ast::ObjectValues(inner) => match interpret(inner, data) {
+            Value::Object(fields) => {
+                Value::Array(fields.into_iter().map(|(_, value)| value).collect())
+            }
+            _ => Value::Null,
+        },
+        Ast::Flatten(inner) => match interpret(inner, data) {
+            Value::Array(values) => {
+                let mut flattened = Vec::with_capacity(values.len());
+                for value in values {
+                    match value {
+                        Value::Array(nested) => flattened.extend(nested),
+                        value => flattened.push(value),
+                    }
+                }
+                Value::Array(flattened)
+            }
+            _ => Value::Null,
+        },
+        Ast::Condition(predicate, then) => {
+            if interpret(predicate, data).is_truthy() {
+                interpret(then, data)
+            } else {
+                Value::Null
+            }
+        }
+        Ast::Comparison(comparator, left, right) => {
+            compare(*comparator, &interpret(left, data), &interpret(right, data))
+        }
+        Ast::And(left, right) => {
+            let left = interpret(left, data);
+            if left.is_truthy() {
+                interpret(right, data)
+            } else {
+                left
+            }
+        }
+        Ast::Or(left, right) => {
+            let left = interpret(left, data);
+            if left.is_truthy() {
+                left
+            } else {
+                interpret(right, data)
+            }
+        }
+        Ast::Not(inner) => Value::Bool(!interpret(inner, data).is_truthy()),
+        Ast::Literal(value) => value.clone(),
+        Ast::MultiList(elements) => match data {
+            Value::Null => Value::Null,
+            _ => Value::Array(elements.iter().map(|ast| interpret(ast, data)).collect()),
+        },
+        Ast::MultiHash(fields) => match data {
+            Value::Null => Value::Null,
+            _ => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, ast)| (Cow::Owned(key.clone()), interpret(ast, data)))
+                    .collect(),
+            ),
+        },
+        Ast::Function(name, args) => {
+            let args: Vec<_> = args.iter().map(|arg| interpret(arg, data)).collect();
+            call_function(name, args)
+        }
+    }
+}
+
+fn compare<'a>(comparator: Comparator, left: &Value<'a>, right: &Value<'a>) -> Value<'a> {
+    let ordering = match comparator {
+        Comparator::Eq => return Value::Bool(left == right),
+        Comparator::Ne => return Value::Bool(left != right),
+        _ => match (left, right) {
+            (Value::Number(left), Value::Number(right)) => left.partial_cmp(right),
+            _ => None,
+        },
+    };
+    match ordering {
+        Some(ordering) => Value::Bool(match comparator {
+            Comparator::Lt => ordering.is_lt(),
+            Comparator::Lte => ordering.is_le(),
+            Comparator::Gt => ordering.is_gt(),
+            Comparator::Gte => ordering.is_ge(),
+            Comparator::Eq | Comparator::Ne => unreachable!("handled above"),
+        }),
+        None => Value::Null,
+    }
+}
+
+fn slice<'a>(
+    values: &[Value<'a>],
+    start: Option<i64>,
+    stop: Option<i64>,
+    step: i64,
+) -> Vec<Value<'a>> {
+    let len = values.len() as i64;
+    let adjust = |index: i64| {
+        if index < 0 {
+            (index + len).max(if step < 0 { -1 } else { 0 })
+        } else if index >= len {
+            if step < 0 {
+                len - 1
+            } else {
+                len
+            }
+        } else {
+            index
+        }
+    };
+    let (mut index, stop) = match step > 0 {
+        true => (
+            start.map(adjust).unwrap_or(0),
+            stop.map(adjust).unwrap_or(len),
+        ),
+        false => (
+            start.map(adjust).unwrap_or(len - 1),
+            stop.map(adjust).unwrap_or(-1),
+        ),
+    };
+    let mut sliced = Vec::new();
+    while (step > 0 && index < stop) || (step < 0 && index > stop) {
+        sliced.push(values[index as usize].clone());
+        index = match index.checked_add(step) {
+            Some(index) => index,
+            None => break,
+        };
+    }
+    sliced
+}
+
+fn call_function<'a>(name: &str, mut args: Vec<Value<'a>>) -> Value<'a> {
+    match (name, args.as_slice()) {
+        ("length", [Value::String(value)]) => Value::Number(value.chars().count() as f64),
+        ("length", [Value::Array(values)]) => Value::Number(values.len() as f64),
+        ("length", [Value::Object(fields)]) => Value::Number(fields.len() as f64),
+        ("contains", [Value::Array(values), search]) => Value::Bool(values.contains(search)),
+        ("contains", [Value::String(value), Value::String(search)]) => {
+            Value::Bool(value.contains(search.as_ref()))
+        }
+        ("starts_with", [Value::String(value), Value::String(prefix)]) => {
+            Value::Bool(value.starts_with(prefix.as_ref()))
+        }
+        ("ends_with", [Value::String(value), Value::String(suffix)]) => {
+            Value::Bool(value.ends_with(suffix.as_ref()))
+        }
+        ("type", [value]) => Value::string(value.type_name()),
+        ("not_null", _) => args
+            .into_iter()
+            .find(|value| !value.is_null())
+            .unwrap_or(Value::Null),
+        ("keys", [Value::Object(_)]) | ("values", [Value::Object(_)]) => {
+            let fields = match args.pop() {
+                Some(Value::Object(fields)) => fields,
+                _ => unreachable!("matched above"),
+            };
+            Value::Array(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| match name {
+                        "keys" => Value::String(key),
+                        _ => value,
+                    })
+                    .collect(),
+            )
+        }
+        _ => Value::Null,
+    }
+}
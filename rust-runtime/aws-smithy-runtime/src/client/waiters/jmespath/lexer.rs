@@ -0,0 +1,237 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::value::{ToJmespathValue, Value};
+use super::ParseError;
+use aws_smithy_json::deserialize::token::expect_document;
+use aws_smithy_json::deserialize::{json_token_iter, EscapedStr};
+use std::borrow::Cow;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    Number(i64),
+    Literal(Value<'static>),
+    Dot,
+    Star,
+    Flatten,
+    Filter,
+    Lbracket,
+    Rbracket,
+    Lbrace,
+    Rbrace,
+    Lparen,
+    Rparen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    At,
+    Ampersand,
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Eof,
+}
+
+impl Token {
+    /// The left binding power of the token, used by the Pratt parser.
+    pub(super) fn lbp(&self) -> u8 {
+        match self {
+            Token::Pipe => 1,
+            Token::Or => 2,
+            Token::And => 3,
+            Token::Eq | Token::Ne | Token::Lt | Token::Lte | Token::Gt | Token::Gte => 5,
+            Token::Flatten => 9,
+            Token::Star => 20,
+            Token::Filter => 21,
+            Token::Dot => 40,
+            Token::Not => 45,
+            Token::Lbrace => 50,
+            Token::Lbracket => 55,
+            Token::Lparen => 60,
+            _ => 0,
+        }
+    }
+}
+
+/// Splits an expression into tokens, each paired with its offset in the expression.
+pub(super) fn tokenize(expression: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let mut next_is = |expected: char| chars.next_if(|(_, c)| *c == expected).is_some();
+        let token = match c {
+            c if c.is_ascii_whitespace() => continue,
+            '.' => Token::Dot,
+            '*' => Token::Star,
+            ']' => Token::Rbracket,
+            '{' => Token::Lbrace,
+            '}' => Token::Rbrace,
+            '(' => Token::Lparen,
+            ')' => Token::Rparen,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '@' => Token::At,
+            '[' if next_is(']') => Token::Flatten,
+            '[' if next_is('?') => Token::Filter,
+            '[' => Token::Lbracket,
+            '|' if next_is('|') => Token::Or,
+            '|' => Token::Pipe,
+            '&' if next_is('&') => Token::And,
+            '&' => Token::Ampersand,
+            '!' if next_is('=') => Token::Ne,
+            '!' => Token::Not,
+            '<' if next_is('=') => Token::Lte,
+            '<' => Token::Lt,
+            '>' if next_is('=') => Token::Gte,
+            '>' => Token::Gt,
+            '=' if next_is('=') => Token::Eq,
+            '=' => return Err(ParseError::new(offset, "expected `==`")),
+            '"' => {
+                let raw = delimited(expression, offset, '"', &mut chars)?;
+                let unescaped = EscapedStr::new(raw)
+                    .to_unescaped()
+                    .map_err(|_| ParseError::new(offset, "invalid escape in quoted identifier"))?;
+                Token::QuotedIdentifier(unescaped.into_owned())
+            }
+            '\'' => {
+                let raw = delimited(expression, offset, '\'', &mut chars)?;
+                Token::Literal(Value::String(Cow::Owned(raw.replace("\\'", "'"))))
+            }
+            '`' => {
+                let raw = delimited(expression, offset, '`', &mut chars)?;
+                Token::Literal(parse_json_literal(offset, &raw.replace("\\`", "`"))?)
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let mut end = offset + c.len_utf8();
+                while let Some((index, _)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    end = index + 1;
+                }
+                let number = expression[offset..end]
+                    .parse()
+                    .map_err(|_| ParseError::new(offset, "invalid number"))?;
+                Token::Number(number)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = offset + 1;
+                while let Some((index, _)) =
+                    chars.next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    end = index + 1;
+                }
+                Token::Identifier(expression[offset..end].to_string())
+            }
+            _ => {
+                return Err(ParseError::new(
+                    offset,
+                    format!("unexpected character `{c}`"),
+                ))
+            }
+        };
+        tokens.push((offset, token));
+    }
+    tokens.push((expression.len(), Token::Eof));
+    Ok(tokens)
+}
+
+/// Returns the text between the delimiter at `start` and the next unescaped `delimiter`.
+fn delimited<'a>(
+    expression: &'a str,
+    start: usize,
+    delimiter: char,
+    chars: &mut impl Iterator<Item = (usize, char)>,
+) -> Result<&'a str, ParseError> {
+    let mut escaped = false;
+    for (index, c) in chars {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == delimiter => return Ok(&expression[start + 1..index]),
+            _ => {}
+        }
+    }
+    Err(ParseError::new(
+        start,
+        format!("unterminated `{delimiter}` delimited value"),
+    ))
+}
+
+fn parse_json_literal(offset: usize, json: &str) -> Result<Value<'static>, ParseError> {
+    let invalid = |_| ParseError::new(offset, "invalid JSON literal");
+    let mut tokens = json_token_iter(json.trim().as_bytes()).peekable();
+    let document = expect_document(&mut tokens).map_err(invalid)?;
+    if let Some(token) = tokens.next() {
+        token.map_err(invalid)?;
+        return Err(ParseError::new(offset, "trailing data after JSON literal"));
+    }
+    Ok(document.to_jmespath_value().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(expression: &str) -> Vec<Token> {
+        tokenize(expression)
+            .unwrap()
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_operators() {
+        assert_eq!(
+            vec![
+                Token::Identifier("a".into()),
+                Token::Flatten,
+                Token::Filter,
+                Token::At,
+                Token::Ne,
+                Token::Literal(Value::String("b".into())),
+                Token::Rbracket,
+                Token::Or,
+                Token::Not,
+                Token::Identifier("c_1".into()),
+                Token::Lbracket,
+                Token::Number(-1),
+                Token::Rbracket,
+                Token::Eof,
+            ],
+            tokens("a[] [?@ != 'b'] || !c_1[-1]")
+        );
+    }
+
+    #[test]
+    fn tokenize_quoted_identifiers_and_literals() {
+        assert_eq!(
+            vec![
+                Token::QuotedIdentifier("with \"quotes\"".into()),
+                Token::Dot,
+                Token::Literal(Value::Array(vec![
+                    Value::Number(1.0),
+                    Value::String("`".into())
+                ])),
+                Token::Eof,
+            ],
+            tokens(r#""with \"quotes\"".`[1, "\`"]`"#)
+        );
+    }
+
+    #[test]
+    fn tokenize_errors() {
+        assert_eq!(0, tokenize("'unterminated").unwrap_err().offset());
+        assert_eq!(2, tokenize("a =b").unwrap_err().offset());
+        assert_eq!(4, tokenize("foo.`{bad`").unwrap_err().offset());
+        assert_eq!(1, tokenize("a#").unwrap_err().offset());
+    }
+}
@@ -0,0 +1,434 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::lexer::{tokenize, Token};
+use super::value::Value;
+use super::ParseError;
+use std::vec::IntoIter;
+
+/// Projections stop when they encounter a token with a lower binding power than this.
+const PROJECTION_STOP: u8 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+/// A parsed JMESPath expression.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Ast {
+    /// The current node, `@`.
+    Identity,
+    Field(String),
+    /// Evaluates the right side against the result of the left side. Pipes parse to this too.
+    Subexpr(Box<Ast>, Box<Ast>),
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        stop: Option<i64>,
+        step: i64,
+    },
+    /// Evaluates the right side against each element of the array produced by the left side,
+    /// dropping null results.
+    Projection(Box<Ast>, Box<Ast>),
+    ObjectValues(Box<Ast>),
+    Flatten(Box<Ast>),
+    /// Evaluates the right side if the predicate on the left side is truthy, or returns null otherwise.
+    Condition(Box<Ast>, Box<Ast>),
+    Comparison(Comparator, Box<Ast>, Box<Ast>),
+    And(Box<Ast>, Box<Ast>),
+    Or(Box<Ast>, Box<Ast>),
+    Not(Box<Ast>),
+    Literal(Value<'static>),
+    MultiList(Vec<Ast>),
+    MultiHash(Vec<(String, Ast)>),
+    Function(String, Vec<Ast>),
+}
+
+pub(super) fn parse(expression: &str) -> Result<Ast, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(expression)?.into_iter(),
+        current: (0, Token::Eof),
+        offset: 0,
+    };
+    parser.advance();
+    let ast = parser.expression(0)?;
+    match parser.peek() {
+        Token::Eof => Ok(ast),
+        token => Err(parser.error_at_current(format!("unexpected token {token:?}"))),
+    }
+}
+
+struct Parser {
+    tokens: IntoIter<(usize, Token)>,
+    current: (usize, Token),
+    /// The offset of the most recently consumed token.
+    offset: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.current.1
+    }
+
+    fn advance(&mut self) -> Token {
+        let next = self.tokens.next().unwrap_or((self.current.0, Token::Eof));
+        let (offset, token) = std::mem::replace(&mut self.current, next);
+        self.offset = offset;
+        token
+    }
+
+    /// Creates an error at the most recently consumed token.
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.offset, message.into())
+    }
+
+    /// Creates an error at the token that hasn't been consumed yet.
+    fn error_at_current(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.current.0, message.into())
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        if *self.peek() == expected {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.error_at_current(format!("expected {expected:?} but found {:?}", self.peek())))
+        }
+    }
+
+    fn expression(&mut self, rbp: u8) -> Result<Ast, ParseError> {
+        let token = self.advance();
+        let mut left = self.nud(token)?;
+        while rbp < self.peek().lbp() {
+            let token = self.advance();
+            left = self.led(token, left)?;
+        }
+        Ok(left)
+    }
+
+    /// Parses a token that starts an expression.
+    fn nud(&mut self, token: Token) -> Result<Ast, ParseError> {
+        match token {
+            Token::At => Ok(Ast::Identity),
+            Token::Identifier(name) => Ok(Ast::Field(name)),
+            Token::QuotedIdentifier(name) => match self.peek() {
+                Token::Lparen => {
+                    Err(self.error_at_current("quoted identifiers can't be function names"))
+                }
+                _ => Ok(Ast::Field(name)),
+            },
+            Token::Literal(value) => Ok(Ast::Literal(value)),
+            Token::Star => self.wildcard_values(Ast::Identity),
+            Token::Lbracket => match self.peek() {
+                Token::Number(_) | Token::Colon => self.index(Ast::Identity),
+                Token::Star => {
+                    self.advance();
+                    self.expect(Token::Rbracket)?;
+                    self.wildcard_index(Ast::Identity)
+                }
+                _ => self.multi_list(),
+            },
+            Token::Flatten => self.flatten(Ast::Identity),
+            Token::Filter => self.filter(Ast::Identity),
+            Token::Lbrace => self.multi_hash(),
+            Token::Not => Ok(Ast::Not(Box::new(self.expression(Token::Not.lbp())?))),
+            Token::Lparen => {
+                let inner = self.expression(0)?;
+                self.expect(Token::Rparen)?;
+                Ok(inner)
+            }
+            Token::Ampersand => Err(self.error("expression references aren't supported")),
+            token => Err(self.error(format!("unexpected token {token:?}"))),
+        }
+    }
+
+    /// Parses a token that continues the expression on its left.
+    fn led(&mut self, token: Token, left: Ast) -> Result<Ast, ParseError> {
+        match token {
+            Token::Dot => {
+                if *self.peek() == Token::Star {
+                    self.advance();
+                    self.wildcard_values(left)
+                } else {
+                    let right = self.dot(Token::Dot.lbp())?;
+                    Ok(Ast::Subexpr(Box::new(left), Box::new(right)))
+                }
+            }
+            Token::Lbracket => match self.peek() {
+                Token::Number(_) | Token::Colon => self.index(left),
+                Token::Star => {
+                    self.advance();
+                    self.expect(Token::Rbracket)?;
+                    self.wildcard_index(left)
+                }
+                token => {
+                    Err(self.error_at_current(format!("unexpected token {token:?} after `[`")))
+                }
+            },
+            Token::Flatten => self.flatten(left),
+            Token::Filter => self.filter(left),
+            Token::Pipe => self.binary(token, Ast::Subexpr, left),
+            Token::Or => self.binary(token, Ast::Or, left),
+            Token::And => self.binary(token, Ast::And, left),
+            Token::Eq => self.comparison(Comparator::Eq, left),
+            Token::Ne => self.comparison(Comparator::Ne, left),
+            Token::Lt => self.comparison(Comparator::Lt, left),
+            Token::Lte => self.comparison(Comparator::Lte, left),
+            Token::Gt => self.comparison(Comparator::Gt, left),
+            Token::Gte => self.comparison(Comparator::Gte, left),
+            Token::Lparen => match left {
+                Ast::Field(name) => self.function(name),
+                _ => Err(self.error("invalid function call")),
+            },
+            token => Err(self.error(format!("unexpected token {token:?}"))),
+        }
+    }
+
+    fn binary(
+        &mut self,
+        token: Token,
+        ast: fn(Box<Ast>, Box<Ast>) -> Ast,
+        left: Ast,
+    ) -> Result<Ast, ParseError> {
+        let right = self.expression(token.lbp())?;
+        Ok(ast(Box::new(left), Box::new(right)))
+    }
+
+    /// Parses the arguments of a function call after its opening `(` has been consumed.
+    fn function(&mut self, name: String) -> Result<Ast, ParseError> {
+        let mut args = Vec::new();
+        while *self.peek() != Token::Rparen {
+            args.push(self.expression(0)?);
+            match self.peek() {
+                Token::Comma => {
+                    self.advance();
+                }
+                Token::Rparen => {}
+                token => {
+                    return Err(self.error_at_current(format!(
+                        "unexpected token {token:?} in function arguments"
+                    )))
+                }
+            }
+        }
+        self.advance();
+        Ok(Ast::Function(name, args))
+    }
+
+    fn comparison(&mut self, comparator: Comparator, left: Ast) -> Result<Ast, ParseError> {
+        let right = self.expression(Token::Eq.lbp())?;
+        Ok(Ast::Comparison(comparator, Box::new(left), Box::new(right)))
+    }
+
+    /// Parses the right side of a projection.
+    fn projection_rhs(&mut self, lbp: u8) -> Result<Ast, ParseError> {
+        match self.peek() {
+            token if token.lbp() < PROJECTION_STOP => Ok(Ast::Identity),
+            Token::Lbracket | Token::Filter => self.expression(lbp),
+            Token::Dot => {
+                self.advance();
+                self.dot(lbp)
+            }
+            token => {
+                Err(self.error_at_current(format!("unexpected token {token:?} after projection")))
+            }
+        }
+    }
+
+    /// Parses the right side of a `.`.
+    fn dot(&mut self, lbp: u8) -> Result<Ast, ParseError> {
+        if *self.peek() == Token::Lbracket {
+            self.advance();
+            self.multi_list()
+        } else {
+            self.expression(lbp)
+        }
+    }
+
+    fn wildcard_values(&mut self, left: Ast) -> Result<Ast, ParseError> {
+        let rhs = self.projection_rhs(Token::Star.lbp())?;
+        Ok(Ast::Projection(
+            Box::new(Ast::ObjectValues(Box::new(left))),
+            Box::new(rhs),
+        ))
+    }
+
+    fn wildcard_index(&mut self, left: Ast) -> Result<Ast, ParseError> {
+        let rhs = self.projection_rhs(Token::Star.lbp())?;
+        Ok(Ast::Projection(Box::new(left), Box::new(rhs)))
+    }
+
+    fn flatten(&mut self, left: Ast) -> Result<Ast, ParseError> {
+        let rhs = self.projection_rhs(Token::Flatten.lbp())?;
+        Ok(Ast::Projection(
+            Box::new(Ast::Flatten(Box::new(left))),
+            Box::new(rhs),
+        ))
+    }
+
+    fn filter(&mut self, left: Ast) -> Result<Ast, ParseError> {
+        let predicate = self.expression(0)?;
+        self.expect(Token::Rbracket)?;
+        let rhs = self.projection_rhs(Token::Filter.lbp())?;
+        Ok(Ast::Projection(
+            Box::new(left),
+            Box::new(Ast::Condition(Box::new(predicate), Box::new(rhs))),
+        ))
+    }
+
+    /// Parses an index or slice after its opening `[` has been consumed.
+    fn index(&mut self, left: Ast) -> Result<Ast, ParseError> {
+        let mut parts = [None, None, None];
+        let mut position = 0;
+        loop {
+            match self.advance() {
+                Token::Number(value) => parts[position] = Some(value),
+                Token::Colon if position < 2 => position += 1,
+                Token::Rbracket => break,
+                token => return Err(self.error(format!("unexpected token {token:?} in index"))),
+            }
+        }
+        if position == 0 {
+            let index = match parts[0] {
+                Some(index) => Ast::Index(index),
+                None => return Err(self.error("expected an index")),
+            };
+            return Ok(match left {
+                Ast::Identity => index,
+                left => Ast::Subexpr(Box::new(left), Box::new(index)),
+            });
+        }
+        let step = parts[2].unwrap_or(1);
+        if step == 0 {
+            return Err(self.error("slice step can't be 0"));
+        }
+        let slice = Ast::Slice {
+            start: parts[0],
+            stop: parts[1],
+            step,
+        };
+        let slice = match left {
+            Ast::Identity => slice,
+            left => Ast::Subexpr(Box::new(left), Box::new(slice)),
+        };
+        let rhs = self.projection_rhs(Token::Star.lbp())?;
+        Ok(Ast::Projection(Box::new(slice), Box::new(rhs)))
+    }
+
+    /// Parses a multi-select list after its opening `[` has been consumed.
+    fn multi_list(&mut self) -> Result<Ast, ParseError> {
+        let mut elements = Vec::new();
+        loop {
+            elements.push(self.expression(0)?);
+            match self.advance() {
+                Token::Comma => continue,
+                Token::Rbracket => return Ok(Ast::MultiList(elements)),
+                token => {
+                    return Err(
+                        self.error(format!("unexpected token {token:?} in multi-select list"))
+                    )
+                }
+            }
+        }
+    }
+
+    /// Parses a multi-select hash after its opening `{` has been consumed.
+    fn multi_hash(&mut self) -> Result<Ast, ParseError> {
+        let mut fields = Vec::new();
+        loop {
+            let key = match self.advance() {
+                Token::Identifier(key) | Token::QuotedIdentifier(key) => key,
+                token => {
+                    return Err(self.error(format!("expected a key but found {token:?}")));
+                }
+            };
+            self.expect(Token::Colon)?;
+            fields.push((key, self.expression(0)?));
+            match self.advance() {
+                Token::Comma => continue,
+                Token::Rbrace => return Ok(Ast::MultiHash(fields)),
+                token => {
+                    return Err(
+                        self.error(format!("unexpected token {token:?} in multi-select hash"))
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str) -> Box<Ast> {
+        Box::new(Ast::Field(name.into()))
+    }
+
+    #[test]
+    fn parse_subexpressions_and_projections() {
+        assert_eq!(
+            Ast::Subexpr(Box::new(Ast::Subexpr(field("a"), field("b"))), field("c")),
+            parse("a.b.c").unwrap()
+        );
+        // The projection continues until the pipe, which then applies to the projected array
+        assert_eq!(
+            Ast::Subexpr(
+                Box::new(Ast::Projection(field("a"), field("b"))),
+                Box::new(Ast::Index(0)),
+            ),
+            parse("a[*].b | [0]").unwrap()
+        );
+        assert_eq!(
+            Ast::Projection(
+                field("a"),
+                Box::new(Ast::Condition(
+                    Box::new(Ast::Comparison(
+                        Comparator::Eq,
+                        field("b"),
+                        Box::new(Ast::Literal(Value::String("x".into()))),
+                    )),
+                    field("c"),
+                )),
+            ),
+            parse("a[?b == 'x'].c").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_slices() {
+        assert_eq!(
+            Ast::Projection(
+                Box::new(Ast::Subexpr(
+                    field("a"),
+                    Box::new(Ast::Slice {
+                        start: None,
+                        stop: Some(2),
+                        step: 1
+                    })
+                )),
+                Box::new(Ast::Identity),
+            ),
+            parse("a[:2]").unwrap()
+        );
+        assert!(parse("a[::0]").is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(2, parse("a..b").unwrap_err().offset());
+        assert_eq!(2, parse("a b").unwrap_err().offset());
+        assert!(parse("foo[").is_err());
+        assert!(parse("{a: b").is_err());
+        assert!(parse("\"f\"(a)").is_err());
+        assert!(parse("sort_by(a, &b)").is_err());
+    }
+}
@@ -0,0 +1,269 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// A JSON-like value that JMESPath expressions are evaluated against.
+///
+/// Values borrow from the data they were created from wherever possible, so converting
+/// an operation output into a `Value` is cheap relative to the request that produced it.
+#[derive(Clone, Debug)]
+pub enum Value<'a> {
+    /// The absence of a value.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A number. All JMESPath numbers are compared as floating point values.
+    Number(f64),
+    /// A string.
+    String(Cow<'a, str>),
+    /// An ordered list of values.
+    Array(Vec<Value<'a>>),
+    /// A set of key-value pairs.
+    Object(Vec<(Cow<'a, str>, Value<'a>)>),
+}
+
+impl<'a> Value<'a> {
+    /// Creates a string value that borrows `value`.
+    pub fn string(value: &'a str) -> Self {
+        Self::String(Cow::Borrowed(value))
+    }
+
+    /// Creates an object value from its fields.
+    pub fn object(fields: impl IntoIterator<Item = (&'a str, Value<'a>)>) -> Self {
+        Self::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (Cow::Borrowed(key), value))
+                .collect(),
+        )
+    }
+
+    /// Returns true if the value is considered true by JMESPath.
+    ///
+    /// Null, false, empty strings, empty arrays and empty objects are false. Everything else is true.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::Null => false,
+            Self::Bool(value) => *value,
+            Self::Number(_) => true,
+            Self::String(value) => !value.is_empty(),
+            Self::Array(values) => !values.is_empty(),
+            Self::Object(fields) => !fields.is_empty(),
+        }
+    }
+
+    /// Returns true if the value is [`Value::Null`].
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns the string if the value is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean if the value is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements if the value is an array.
+    pub fn as_array(&self) -> Option<&[Value<'a>]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Converts this value into one that doesn't borrow anything.
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Self::Null => Value::Null,
+            Self::Bool(value) => Value::Bool(value),
+            Self::Number(value) => Value::Number(value),
+            Self::String(value) => Value::String(Cow::Owned(value.into_owned())),
+            Self::Array(values) => {
+                Value::Array(values.into_iter().map(Value::into_owned).collect())
+            }
+            Self::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (Cow::Owned(key.into_owned()), value.into_owned()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The name of the value's type, as returned by the JMESPath `type` function.
+    pub(super) fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "boolean",
+            Self::Number(_) => "number",
+            Self::String(_) => "string",
+            Self::Array(_) => "array",
+            Self::Object(_) => "object",
+        }
+    }
+}
+
+/// Objects are equal if they have the same set of keys and values, regardless of field order.
+impl PartialEq for Value<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Null, Self::Null) => true,
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::Number(a), Self::Number(b)) => a == b,
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Array(a), Self::Array(b)) => a == b,
+            (Self::Object(a), Self::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(key, value)| {
+                        b.iter()
+                            .find(|(other_key, _)| other_key == key)
+                            .map(|(_, other_value)| value == other_value)
+                            .unwrap_or(false)
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Converts a value into a [`Value`] so that JMESPath expressions can be evaluated against it.
+///
+/// This is implemented for the types that make up generated shapes. Code generated
+/// structures, unions and enums implement it for the operations that have waiters.
+pub trait ToJmespathValue {
+    /// Converts `self` into a JMESPath [`Value`].
+    fn to_jmespath_value(&self) -> Value<'_>;
+}
+
+impl<T: ToJmespathValue + ?Sized> ToJmespathValue for &T {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        (**self).to_jmespath_value()
+    }
+}
+
+impl<T: ToJmespathValue + ?Sized> ToJmespathValue for Box<T> {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        (**self).to_jmespath_value()
+    }
+}
+
+impl<T: ToJmespathValue> ToJmespathValue for Option<T> {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        self.as_ref()
+            .map(ToJmespathValue::to_jmespath_value)
+            .unwrap_or(Value::Null)
+    }
+}
+
+impl<T: ToJmespathValue> ToJmespathValue for [T] {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::Array(
+            self.iter()
+                .map(ToJmespathValue::to_jmespath_value)
+                .collect(),
+        )
+    }
+}
+
+impl<T: ToJmespathValue> ToJmespathValue for Vec<T> {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        self.as_slice().to_jmespath_value()
+    }
+}
+
+/// Map keys are strings or enums, which are converted to strings.
+impl<K: AsRef<str>, V: ToJmespathValue, S> ToJmespathValue for HashMap<K, V, S> {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::Object(
+            self.iter()
+                .map(|(key, value)| (Cow::Borrowed(key.as_ref()), value.to_jmespath_value()))
+                .collect(),
+        )
+    }
+}
+
+impl ToJmespathValue for str {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::string(self)
+    }
+}
+
+impl ToJmespathValue for String {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::string(self)
+    }
+}
+
+impl ToJmespathValue for bool {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::Bool(*self)
+    }
+}
+
+macro_rules! number_to_jmespath_value {
+    ($($ty:ty),+) => {
+        $(
+            impl ToJmespathValue for $ty {
+                fn to_jmespath_value(&self) -> Value<'_> {
+                    Value::Number(*self as f64)
+                }
+            }
+        )+
+    };
+}
+
+number_to_jmespath_value!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+impl ToJmespathValue for Number {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::Number(self.to_f64_lossy())
+    }
+}
+
+/// Timestamps are represented as the number of seconds since the Unix epoch.
+impl ToJmespathValue for DateTime {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::Number(self.as_secs_f64())
+    }
+}
+
+/// Blobs have no JMESPath representation, so they are always null.
+impl ToJmespathValue for Blob {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        Value::Null
+    }
+}
+
+impl ToJmespathValue for Document {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        match self {
+            Document::Object(fields) => fields.to_jmespath_value(),
+            Document::Array(values) => values.to_jmespath_value(),
+            Document::Number(value) => value.to_jmespath_value(),
+            Document::String(value) => value.to_jmespath_value(),
+            Document::Bool(value) => Value::Bool(*value),
+            Document::Null => Value::Null,
+        }
+    }
+}
+
+impl ToJmespathValue for Value<'_> {
+    fn to_jmespath_value(&self) -> Value<'_> {
+        self.clone()
+    }
+}
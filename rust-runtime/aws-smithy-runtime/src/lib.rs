@@ -7,9 +7,16 @@
 //!
 //! # Crate Features
 //!
+//! - `connector-async-io`: Enables an HTTP client that doesn't require a Tokio runtime, for use
+//!   with `smol`, `async-std`, or other runtimes built on `async-io`. It is only used as the default
+//!   HTTP client when `rt-tokio` isn't enabled.
+//! - `connector-hyper-1-x`: Enables an HTTP client built on hyper 1.x and hyper-util. It is used as
+//!   the default HTTP client when `connector-hyper-0-14-x` isn't enabled.
+//! - `tls-rustls-hyper-1-x`: Enables HTTPS support for the hyper 1.x client using rustls.
 //! - `http-auth`: Enables auth scheme and identity resolver implementations for HTTP API Key,
 //!   Basic Auth, Bearer Token, and Digest Auth.
 //! - `test-util`: Enables utilities for unit tests. DO NOT ENABLE IN PRODUCTION.
+//! - `waiters`: Enables the runtime for waiters generated from the Smithy `@waitable` trait.
 
 #![warn(
     missing_docs,
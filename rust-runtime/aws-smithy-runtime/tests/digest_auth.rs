@@ -0,0 +1,338 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![cfg(all(
+    feature = "client",
+    feature = "http-auth",
+    feature = "wire-mock",
+    feature = "connector-hyper-0-14-x",
+))]
+
+use aws_smithy_async::rt::sleep::TokioSleep;
+use aws_smithy_runtime::client::auth::http::{
+    DigestAuthChallengeRetryClassifier, DigestAuthScheme,
+};
+use aws_smithy_runtime::client::http::hyper_014::HyperClientBuilder;
+use aws_smithy_runtime::client::identity::IdentityCache;
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime::test_util::capture_test_logs::capture_test_logs;
+use aws_smithy_runtime_api::client::auth::http::HTTP_DIGEST_AUTH_SCHEME_ID;
+use aws_smithy_runtime_api::client::auth::static_resolver::StaticAuthSchemeOptionResolver;
+use aws_smithy_runtime_api::client::auth::AuthSchemeOptionResolverParams;
+use aws_smithy_runtime_api::client::identity::http::Login;
+use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::Layer;
+use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::timeout::TimeoutConfig;
+use hyper_0_14::service::{make_service_fn, service_fn};
+use hyper_0_14::{Body, Server};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+
+const USER: &str = "Mufasa";
+const PASSWORD: &str = "Circle of Life";
+const REALM: &str = "stand-in@example.com";
+
+/// Outcome of a request received by the stand-in server
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Exchange {
+    /// The request had no credentials and was challenged
+    Challenged,
+    /// The request used a nonce that has expired
+    Stale,
+    /// The request had invalid credentials
+    Rejected,
+    /// The request was authenticated with the given nonce count
+    Authenticated { nc: u32 },
+}
+
+#[derive(Debug, Default)]
+struct ServerState {
+    next_nonce: u32,
+    /// Nonce -> highest nonce count seen so far
+    nonces: HashMap<String, u32>,
+    exchanges: Vec<Exchange>,
+}
+
+/// A minimal RFC 7616 server that only accepts `SHA-256` with `qop=auth`.
+struct DigestServer {
+    state: Arc<Mutex<ServerState>>,
+    /// The number of times a nonce may be used before it becomes stale
+    nonce_uses: u32,
+    addr: SocketAddr,
+    shutdown: tokio::sync::oneshot::Sender<()>,
+}
+
+impl DigestServer {
+    async fn start(nonce_uses: u32) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(ServerState::default()));
+        let (shutdown, rx) = tokio::sync::oneshot::channel::<()>();
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: http::Request<Body>| {
+                    let response = handle(&mut state.lock().unwrap(), nonce_uses, &request);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        let server = Server::from_tcp(listener)
+            .unwrap()
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                rx.await.ok();
+            });
+        tokio::spawn(server);
+        Self {
+            state,
+            nonce_uses,
+            addr,
+            shutdown,
+        }
+    }
+
+    fn endpoint_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    fn exchanges(&self) -> Vec<Exchange> {
+        self.state.lock().unwrap().exchanges.clone()
+    }
+
+    fn shutdown(self) {
+        let _ = self.shutdown.send(());
+    }
+}
+
+impl fmt::Debug for DigestServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestServer")
+            .field("nonce_uses", &self.nonce_uses)
+            .field("addr", &self.addr)
+            .finish()
+    }
+}
+
+fn sha256(data: impl AsRef<[u8]>) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn handle(
+    state: &mut ServerState,
+    nonce_uses: u32,
+    request: &http::Request<Body>,
+) -> http::Response<Body> {
+    let challenge = |state: &mut ServerState, stale: bool| {
+        state.next_nonce += 1;
+        let nonce = format!("nonce-{}", state.next_nonce);
+        state.nonces.insert(nonce.clone(), 0);
+        http::Response::builder()
+            .status(401)
+            // Offer a challenge that can't be answered, and a weaker one, to exercise selection
+            .header(
+                "WWW-Authenticate",
+                format!(r#"Digest realm="{REALM}", nonce="{nonce}", algorithm=SHA-512-256, qop="auth""#),
+            )
+            .header(
+                "WWW-Authenticate",
+                format!(r#"Digest realm="{REALM}", nonce="{nonce}", algorithm=MD5, qop="auth""#),
+            )
+            .header(
+                "WWW-Authenticate",
+                format!(
+                    r#"Digest realm="{REALM}", nonce="{nonce}", algorithm=SHA-256, qop="auth", opaque="opaque-value", stale={stale}"#
+                ),
+            )
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let authorization = match request.headers().get("authorization") {
+        Some(value) => value.to_str().unwrap().to_string(),
+        None => {
+            state.exchanges.push(Exchange::Challenged);
+            return challenge(state, false);
+        }
+    };
+    let params = parse_params(authorization.strip_prefix("Digest ").unwrap());
+    assert_eq!("SHA-256", params["algorithm"]);
+    assert_eq!("auth", params["qop"]);
+    assert_eq!("opaque-value", params["opaque"]);
+    assert_eq!(
+        request.uri().path_and_query().unwrap().as_str(),
+        params["uri"]
+    );
+    let nonce = &params["nonce"];
+    let nc = u32::from_str_radix(&params["nc"], 16).unwrap();
+    let last_nc = *state.nonces.get(nonce).expect("nonce was issued");
+    assert!(nc > last_nc, "nonce count must increase");
+
+    if nc > nonce_uses {
+        state.exchanges.push(Exchange::Stale);
+        return challenge(state, true);
+    }
+    state.nonces.insert(nonce.clone(), nc);
+
+    let ha1 = sha256(format!("{}:{REALM}:{PASSWORD}", params["username"]));
+    let ha2 = sha256(format!("{}:{}", request.method(), params["uri"]));
+    let expected = sha256(format!(
+        "{ha1}:{nonce}:{}:{}:auth:{ha2}",
+        params["nc"], params["cnonce"]
+    ));
+    if params["username"] != USER || params["response"] != expected {
+        state.exchanges.push(Exchange::Rejected);
+        return challenge(state, false);
+    }
+
+    state.exchanges.push(Exchange::Authenticated { nc });
+    http::Response::builder()
+        .status(200)
+        .body(Body::from("authenticated"))
+        .unwrap()
+}
+
+fn parse_params(value: &str) -> HashMap<String, String> {
+    value
+        .split(", ")
+        .map(|param| {
+            let (name, value) = param.split_once('=').unwrap();
+            (name.to_string(), value.trim_matches('"').to_string())
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct Unauthorized;
+
+impl fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unauthorized")
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+fn digest_operation(server: &DigestServer, login: Login) -> Operation<(), String, Unauthorized> {
+    let auth_scheme = DigestAuthScheme::new();
+    let mut config = Layer::new("digest");
+    config.store_put(AuthSchemeOptionResolverParams::new(()));
+    let runtime_components = RuntimeComponentsBuilder::new("digest")
+        .with_auth_scheme_option_resolver(Some(StaticAuthSchemeOptionResolver::new(vec![
+            HTTP_DIGEST_AUTH_SCHEME_ID,
+        ])))
+        .with_identity_cache(Some(IdentityCache::no_cache()))
+        .with_identity_resolver(HTTP_DIGEST_AUTH_SCHEME_ID, login)
+        .with_interceptor(auth_scheme.challenge_interceptor())
+        .with_retry_classifier(DigestAuthChallengeRetryClassifier::new())
+        .with_auth_scheme(auth_scheme);
+
+    let endpoint_url = server.endpoint_url();
+    Operation::builder()
+        .service_name("test")
+        .operation_name("test")
+        .endpoint_url(&endpoint_url)
+        .http_client(HyperClientBuilder::new().build(hyper_0_14::client::HttpConnector::new()))
+        .timeout_config(TimeoutConfig::disabled())
+        // Answering challenges must work even with retries disabled
+        .standard_retry(&RetryConfig::disabled())
+        .sleep_impl(TokioSleep::new())
+        .runtime_plugin(
+            StaticRuntimePlugin::new()
+                .with_config(config.freeze())
+                .with_runtime_components(runtime_components),
+        )
+        .serializer(move |_: ()| {
+            Ok(http::Request::builder()
+                .uri(format!("{endpoint_url}/resource?id=1"))
+                .body(SdkBody::from("request body"))
+                .unwrap()
+                .try_into()
+                .unwrap())
+        })
+        .deserializer(|response| {
+            if response.status().is_success() {
+                Ok(String::from_utf8(response.body().bytes().unwrap().into()).unwrap())
+            } else {
+                Err(OrchestratorError::operation(Unauthorized))
+            }
+        })
+        .build()
+}
+
+#[tokio::test]
+async fn challenge_is_answered_and_reused() {
+    let _logs = capture_test_logs();
+    let server = DigestServer::start(10).await;
+    let operation = digest_operation(&server, Login::new(USER, PASSWORD, None));
+
+    assert_eq!("authenticated", operation.invoke(()).await.unwrap());
+    assert_eq!(
+        vec![Exchange::Challenged, Exchange::Authenticated { nc: 1 }],
+        server.exchanges()
+    );
+
+    // The challenge is reused for subsequent requests without another round-trip
+    assert_eq!("authenticated", operation.invoke(()).await.unwrap());
+    assert_eq!("authenticated", operation.invoke(()).await.unwrap());
+    assert_eq!(
+        vec![
+            Exchange::Challenged,
+            Exchange::Authenticated { nc: 1 },
+            Exchange::Authenticated { nc: 2 },
+            Exchange::Authenticated { nc: 3 },
+        ],
+        server.exchanges()
+    );
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn stale_nonce_is_refreshed() {
+    let _logs = capture_test_logs();
+    let server = DigestServer::start(1).await;
+    let operation = digest_operation(&server, Login::new(USER, PASSWORD, None));
+
+    operation.invoke(()).await.unwrap();
+    operation.invoke(()).await.unwrap();
+    assert_eq!(
+        vec![
+            Exchange::Challenged,
+            Exchange::Authenticated { nc: 1 },
+            Exchange::Stale,
+            Exchange::Authenticated { nc: 1 },
+        ],
+        server.exchanges()
+    );
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn invalid_credentials_are_not_retried() {
+    let _logs = capture_test_logs();
+    let server = DigestServer::start(10).await;
+    let operation = digest_operation(&server, Login::new(USER, "wrong password", None));
+
+    let err = operation.invoke(()).await.expect_err("invalid credentials");
+    assert!(
+        matches!(err, SdkError::ServiceError(_)),
+        "expected a service error, got {err:?}"
+    );
+    assert_eq!(
+        vec![Exchange::Challenged, Exchange::Rejected],
+        server.exchanges()
+    );
+    server.shutdown();
+}
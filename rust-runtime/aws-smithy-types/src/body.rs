@@ -14,12 +14,17 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-/// This module is named after the `http-body` version number since we anticipate
-/// needing to provide equivalent functionality for 1.x of that crate in the future.
+/// This module is named after the `http-body` version number since equivalent
+/// functionality for 1.x of that crate lives in [`http_body_1_x`].
 /// The name has a suffix `_x` to avoid name collision with a third-party `http-body-0-4`.
 #[cfg(feature = "http-body-0-4-x")]
 pub mod http_body_0_4_x;
 
+/// This module is named after the `http-body` version number to distinguish it from [`http_body_0_4_x`].
+/// The name has a suffix `_x` to avoid name collision with a third-party `http-body-1-0`.
+#[cfg(feature = "http-body-1-x")]
+pub mod http_body_1_x;
+
 /// A generic, boxed error that's `Send` and `Sync`
 pub type Error = Box<dyn StdError + Send + Sync>;
 
@@ -57,6 +62,12 @@ impl Debug for SdkBody {
 enum BoxBody {
     #[cfg(feature = "http-body-0-4-x")]
     HttpBody04(http_body_0_4::combinators::BoxBody<Bytes, Error>),
+    #[cfg(feature = "http-body-1-x")]
+    HttpBody1 {
+        body: Pin<Box<dyn http_body_1_0::Body<Data = Bytes, Error = Error> + Send + Sync>>,
+        // http-body 1.x delivers trailers as a frame, which may be received while polling for data
+        trailers: Option<http_1x::HeaderMap>,
+    },
 }
 
 pin_project! {
@@ -151,9 +162,13 @@ impl SdkBody {
                     use http_body_0_4::Body;
                     Pin::new(box_body).poll_data(cx)
                 }
+                #[cfg(feature = "http-body-1-x")]
+                BoxBody::HttpBody1 { body, trailers } => {
+                    http_body_1_x::poll_data(body.as_mut(), trailers, cx)
+                }
                 #[allow(unreachable_patterns)]
                 _ => unreachable!(
-                    "enabling `http-body-0-4-x` is the only way to create the `Dyn` variant"
+                    "enabling `http-body-0-4-x` or `http-body-1-x` is the only way to create the `Dyn` variant"
                 ),
             },
             InnerProj::Taken => {
@@ -162,7 +177,7 @@ impl SdkBody {
         }
     }
 
-    #[cfg(feature = "http-body-0-4-x")]
+    #[cfg(any(feature = "http-body-0-4-x", feature = "http-body-1-x"))]
     pub(crate) fn poll_next_trailers(
         self: Pin<&mut Self>,
         #[allow(unused)] cx: &mut Context<'_>,
@@ -171,10 +186,15 @@ impl SdkBody {
         match this.inner.project() {
             InnerProj::Once { .. } => Poll::Ready(Ok(None)),
             InnerProj::Dyn { inner } => match inner.get_mut() {
+                #[cfg(feature = "http-body-0-4-x")]
                 BoxBody::HttpBody04(box_body) => {
                     use http_body_0_4::Body;
                     Pin::new(box_body).poll_trailers(cx)
                 }
+                #[cfg(feature = "http-body-1-x")]
+                BoxBody::HttpBody1 { body, trailers } => {
+                    http_body_1_x::poll_trailers(body.as_mut(), trailers, cx)
+                }
             },
             InnerProj::Taken => Poll::Ready(Err(
                 "A `Taken` body should never be polled for trailers".into(),
@@ -226,9 +246,11 @@ impl SdkBody {
                     use http_body_0_4::Body;
                     box_body.is_end_stream()
                 }
+                #[cfg(feature = "http-body-1-x")]
+                BoxBody::HttpBody1 { body, trailers } => trailers.is_none() && body.is_end_stream(),
                 #[allow(unreachable_patterns)]
                 _ => unreachable!(
-                    "enabling `http-body-0-4-x` is the only way to create the `Dyn` variant"
+                    "enabling `http-body-0-4-x` or `http-body-1-x` is the only way to create the `Dyn` variant"
                 ),
             },
             Inner::Taken => true,
@@ -249,9 +271,14 @@ impl SdkBody {
                     let hint = box_body.size_hint();
                     (hint.lower(), hint.upper())
                 }
+                #[cfg(feature = "http-body-1-x")]
+                BoxBody::HttpBody1 { body, .. } => {
+                    let hint = body.size_hint();
+                    (hint.lower(), hint.upper())
+                }
                 #[allow(unreachable_patterns)]
                 _ => unreachable!(
-                    "enabling `http-body-0-4-x` is the only way to create the `Dyn` variant"
+                    "enabling `http-body-0-4-x` or `http-body-1-x` is the only way to create the `Dyn` variant"
                 ),
             },
             Inner::Taken => (0, Some(0)),
@@ -0,0 +1,179 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::body::{BoxBody, Error, Inner, SdkBody};
+use bytes::Bytes;
+use http_body_1_0::Frame;
+use http_body_util::BodyExt;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+type DynBody = dyn http_body_1_0::Body<Data = Bytes, Error = Error> + Send + Sync;
+
+impl SdkBody {
+    /// Construct an `SdkBody` from a type that implements [`http_body_1_0::Body<Data = Bytes>`](http_body_1_0::Body).
+    ///
+    /// _Note: This is only available with `http-body-1-x` enabled._
+    pub fn from_body_1_x<T, E>(body: T) -> Self
+    where
+        T: http_body_1_0::Body<Data = Bytes, Error = E> + Send + Sync + 'static,
+        E: Into<Error> + 'static,
+    {
+        Self {
+            inner: Inner::Dyn {
+                inner: BoxBody::HttpBody1 {
+                    body: Box::pin(body.map_err(Into::into)),
+                    trailers: None,
+                },
+            },
+            rebuild: None,
+            bytes_contents: None,
+        }
+    }
+}
+
+/// Polls an http-body 1.x body for its next data frame.
+///
+/// Trailers received while polling for data are stashed in `trailers` for [`poll_trailers`].
+pub(super) fn poll_data(
+    mut body: Pin<&mut DynBody>,
+    trailers: &mut Option<http_1x::HeaderMap>,
+    cx: &mut Context<'_>,
+) -> Poll<Option<Result<Bytes, Error>>> {
+    match ready!(body.as_mut().poll_frame(cx)) {
+        Some(Ok(frame)) => match frame.into_data() {
+            Ok(data) => Poll::Ready(Some(Ok(data))),
+            Err(frame) => {
+                *trailers = frame.into_trailers().ok();
+                Poll::Ready(None)
+            }
+        },
+        Some(Err(err)) => Poll::Ready(Some(Err(err))),
+        None => Poll::Ready(None),
+    }
+}
+
+/// Polls an http-body 1.x body for its trailers, skipping over any remaining data frames.
+pub(super) fn poll_trailers(
+    mut body: Pin<&mut DynBody>,
+    trailers: &mut Option<http_1x::HeaderMap>,
+    cx: &mut Context<'_>,
+) -> Poll<Result<Option<http::HeaderMap<http::HeaderValue>>, Error>> {
+    if let Some(trailers) = trailers.take() {
+        return Poll::Ready(Ok(Some(headers_1x_to_0x(trailers))));
+    }
+    loop {
+        match ready!(body.as_mut().poll_frame(cx)) {
+            Some(Ok(frame)) => {
+                if let Ok(trailers) = frame.into_trailers() {
+                    return Poll::Ready(Ok(Some(headers_1x_to_0x(trailers))));
+                }
+            }
+            Some(Err(err)) => return Poll::Ready(Err(err)),
+            None => return Poll::Ready(Ok(None)),
+        }
+    }
+}
+
+fn headers_1x_to_0x(headers: http_1x::HeaderMap) -> http::HeaderMap<http::HeaderValue> {
+    let mut converted = http::HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers.iter() {
+        converted.append(
+            http::HeaderName::from_bytes(name.as_str().as_bytes()).expect("valid header name"),
+            http::HeaderValue::from_bytes(value.as_bytes()).expect("valid header value"),
+        );
+    }
+    converted
+}
+
+fn headers_0x_to_1x(headers: http::HeaderMap<http::HeaderValue>) -> http_1x::HeaderMap {
+    let mut converted = http_1x::HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers.iter() {
+        converted.append(
+            http_1x::HeaderName::from_bytes(name.as_str().as_bytes()).expect("valid header name"),
+            http_1x::HeaderValue::from_bytes(value.as_bytes()).expect("valid header value"),
+        );
+    }
+    converted
+}
+
+impl http_body_1_0::Body for SdkBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        match ready!(self.as_mut().poll_next(cx)) {
+            Some(Ok(data)) => Poll::Ready(Some(Ok(Frame::data(data)))),
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => match ready!(self.poll_next_trailers(cx)) {
+                Ok(Some(trailers)) => {
+                    Poll::Ready(Some(Ok(Frame::trailers(headers_0x_to_1x(trailers)))))
+                }
+                Ok(None) => Poll::Ready(None),
+                Err(err) => Poll::Ready(Some(Err(err))),
+            },
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body_1_0::SizeHint {
+        let mut result = http_body_1_0::SizeHint::default();
+        let (lower, upper) = self.bounds_on_remaining_length();
+        result.set_lower(lower);
+        if let Some(u) = upper {
+            result.set_upper(u)
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::body::SdkBody;
+    use bytes::Bytes;
+    use http_body_1_0::{Body, Frame};
+    use http_body_util::{BodyExt, StreamBody};
+
+    #[test]
+    fn map_preserve_preserves_bytes_hint() {
+        let initial = SdkBody::from("hello!");
+        assert_eq!(initial.bytes(), Some(b"hello!".as_slice()));
+
+        let new_body = initial.map_preserve_contents(SdkBody::from_body_1_x);
+        assert_eq!(new_body.bytes(), Some(b"hello!".as_slice()));
+    }
+
+    #[tokio::test]
+    async fn sdk_body_round_trips_data_and_trailers() {
+        let mut trailers = http_1x::HeaderMap::new();
+        trailers.insert("checksum", http_1x::HeaderValue::from_static("abc"));
+        let frames: Vec<Result<Frame<Bytes>, crate::body::Error>> = vec![
+            Ok(Frame::data(Bytes::from("hello "))),
+            Ok(Frame::data(Bytes::from("world"))),
+            Ok(Frame::trailers(trailers.clone())),
+        ];
+        let body = SdkBody::from_body_1_x(StreamBody::new(tokio_stream::iter(frames)));
+        assert!(format!("{:?}", body).contains("BoxBody"));
+
+        let collected = body.collect().await.unwrap();
+        assert_eq!(Some(&trailers), collected.trailers());
+        assert_eq!(Bytes::from("hello world"), collected.to_bytes());
+    }
+
+    #[tokio::test]
+    async fn in_memory_sdk_body_is_an_http_body_1_x() {
+        let body = SdkBody::from("hello!");
+        assert_eq!(Some(6), body.size_hint().exact());
+        assert!(!Body::is_end_stream(&body));
+        let collected = body.collect().await.unwrap();
+        assert_eq!(Bytes::from("hello!"), collected.to_bytes());
+    }
+}
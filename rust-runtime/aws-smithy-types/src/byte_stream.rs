@@ -143,12 +143,17 @@ pub mod error;
 #[cfg(feature = "rt-tokio")]
 pub use self::bytestream_util::FsBuilder;
 
-/// This module is named after the `http-body` version number since we anticipate
-/// needing to provide equivalent functionality for 1.x of that crate in the future.
+/// This module is named after the `http-body` version number since equivalent
+/// functionality for 1.x of that crate lives in [`http_body_1_x`].
 /// The name has a suffix `_x` to avoid name collision with a third-party `http-body-0-4`.
 #[cfg(feature = "http-body-0-4-x")]
 pub mod http_body_0_4_x;
 
+/// This module is named after the `http-body` version number to distinguish it from [`http_body_0_4_x`].
+/// The name has a suffix `_x` to avoid name collision with a third-party `http-body-1-0`.
+#[cfg(feature = "http-body-1-x")]
+pub mod http_body_1_x;
+
 pin_project! {
     /// Stream of binary data
     ///
@@ -0,0 +1,37 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::body::SdkBody;
+use crate::byte_stream::ByteStream;
+use bytes::Bytes;
+
+impl ByteStream {
+    /// Construct a `ByteStream` from a type that implements [`http_body_1_0::Body<Data = Bytes>`](http_body_1_0::Body).
+    ///
+    /// _Note: This is only available with `http-body-1-x` enabled._
+    pub fn from_body_1_x<T, E>(body: T) -> Self
+    where
+        T: http_body_1_0::Body<Data = Bytes, Error = E> + Send + Sync + 'static,
+        E: Into<crate::body::Error> + 'static,
+    {
+        ByteStream::new(SdkBody::from_body_1_x(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::byte_stream::ByteStream;
+    use bytes::Bytes;
+    use http_body_util::Full;
+
+    #[tokio::test]
+    async fn read_from_http_body_1_x() {
+        let byte_stream = ByteStream::from_body_1_x(Full::new(Bytes::from("data")));
+        assert_eq!(
+            byte_stream.collect().await.expect("no errors").into_bytes(),
+            Bytes::from("data")
+        );
+    }
+}
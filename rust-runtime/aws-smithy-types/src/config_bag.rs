@@ -608,6 +608,22 @@ impl ConfigBag {
         }
     }
 
+    /// Returns a mutable reference to `T` if it is stored in the interceptor state
+    ///
+    /// Unlike [`ConfigBag::get_mut`], this doesn't require `T` to implement [`Clone`], and
+    /// returns `None` when `T` is only stored in a deeper layer of the bag.
+    pub fn get_mut_from_interceptor_state<T: Send + Sync + Debug + 'static>(
+        &mut self,
+    ) -> Option<&mut T>
+    where
+        T: Storable<Storer = StoreReplace<T>>,
+    {
+        match self.interceptor_state.get_mut::<StoreReplace<T>>() {
+            Some(Value::Set(t)) => Some(t),
+            _ => None,
+        }
+    }
+
     /// Returns a mutable reference to `T` if it is stored in the top layer of the bag
     ///
     /// - If `T` is in a deeper layer of the bag, that value will be cloned and inserted into the top layer
@@ -981,6 +997,28 @@ mod test {
         assert_eq!(bag.get_mut_or_default::<Foo>(), &Foo(0));
     }
 
+    #[test]
+    fn get_mut_from_interceptor_state() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Foo(usize);
+        impl Storable for Foo {
+            type Storer = StoreReplace<Foo>;
+        }
+
+        let mut layer = Layer::new("layer");
+        layer.store_put(Foo(0));
+        let mut bag = ConfigBag::of_layers(vec![layer]);
+        // only the interceptor state is considered
+        assert_eq!(bag.get_mut_from_interceptor_state::<Foo>(), None);
+
+        bag.interceptor_state().store_put(Foo(1));
+        bag.get_mut_from_interceptor_state::<Foo>().unwrap().0 += 1;
+        assert_eq!(bag.load::<Foo>(), Some(&Foo(2)));
+
+        bag.interceptor_state().unset::<Foo>();
+        assert_eq!(bag.get_mut_from_interceptor_state::<Foo>(), None);
+    }
+
     #[test]
     fn cloning_layers() {
         #[derive(Clone, Debug)]
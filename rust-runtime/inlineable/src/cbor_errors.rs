@@ -0,0 +1,115 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_cbor::data::Type;
+use aws_smithy_cbor::{Decoder, DeserializeError};
+use aws_smithy_types::error::metadata::{Builder as ErrorMetadataBuilder, ErrorMetadata};
+use std::borrow::Cow;
+
+fn sanitize_error_code(error_code: &str) -> &str {
+    // Trim a trailing URL from the error code, beginning with a `:`
+    let error_code = match error_code.find(':') {
+        Some(idx) => &error_code[..idx],
+        None => error_code,
+    };
+
+    // Trim a prefixing namespace from the error code, beginning with a `#`
+    match error_code.find('#') {
+        Some(idx) => &error_code[idx + 1..],
+        None => error_code,
+    }
+}
+
+#[derive(Default)]
+struct ErrorBody<'a> {
+    code: Option<Cow<'a, str>>,
+    message: Option<Cow<'a, str>>,
+}
+
+fn parse_error_body(bytes: &[u8]) -> Result<ErrorBody<'_>, DeserializeError> {
+    if bytes.is_empty() {
+        return Ok(ErrorBody::default());
+    }
+    let mut decoder = Decoder::new(bytes);
+    let body = decoder.map_entries(ErrorBody::default(), |mut body, decoder| {
+        let key = decoder.str()?;
+        let is_string = matches!(decoder.datatype()?, Type::String | Type::StringIndef);
+        match key.as_ref() {
+            "__type" if is_string => body.code = Some(decoder.str()?),
+            "message" | "Message" | "errorMessage" if is_string => {
+                body.message = Some(decoder.str()?)
+            }
+            _ => decoder.skip()?,
+        }
+        Ok(body)
+    })?;
+    if !decoder.is_empty() {
+        return Err(DeserializeError::custom(
+            "found more CBOR data after completing parsing",
+        ));
+    }
+    Ok(body)
+}
+
+pub fn parse_error_metadata(payload: &[u8]) -> Result<ErrorMetadataBuilder, DeserializeError> {
+    let ErrorBody { code, message } = parse_error_body(payload)?;
+
+    let mut err_builder = ErrorMetadata::builder();
+    if let Some(code) = code.as_deref().map(sanitize_error_code) {
+        err_builder = err_builder.code(code);
+    }
+    if let Some(message) = message {
+        err_builder = err_builder.message(message);
+    }
+    Ok(err_builder)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cbor_errors::{parse_error_metadata, sanitize_error_code};
+    use aws_smithy_cbor::Encoder;
+    use aws_smithy_types::Error;
+
+    #[test]
+    fn error_metadata() {
+        let mut encoder = Encoder::new(Vec::new());
+        encoder
+            .begin_map()
+            .str("__type")
+            .str("aws.protocoltests.rpcv2Cbor#FooError")
+            .str("other")
+            .array(1)
+            .null()
+            .str("message")
+            .str("Go to foo")
+            .end();
+        assert_eq!(
+            parse_error_metadata(&encoder.into_writer())
+                .unwrap()
+                .build(),
+            Error::builder()
+                .code("FooError")
+                .message("Go to foo")
+                .build()
+        );
+    }
+
+    #[test]
+    fn empty_body() {
+        assert_eq!(
+            parse_error_metadata(b"").unwrap().build(),
+            Error::builder().build()
+        );
+        assert!(parse_error_metadata(b"\xa0\x01").is_err());
+    }
+
+    #[test]
+    fn sanitize_namespace_and_url() {
+        assert_eq!(
+            sanitize_error_code("aws.protocoltests.rpcv2Cbor#FooError:http://internal.amazon.com/"),
+            "FooError"
+        );
+    }
+}
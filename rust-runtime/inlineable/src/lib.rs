@@ -5,6 +5,8 @@
 
 #[allow(dead_code)]
 mod aws_query_compatible_errors;
+#[allow(dead_code)]
+mod cbor_errors;
 #[allow(unused)]
 mod client_http_checksum_required;
 #[allow(dead_code)]